@@ -0,0 +1,561 @@
+use entries_common::db::auth::AuthDao;
+use entries_common::db::budget::BudgetDao;
+use entries_common::db::user::{UserDao, UserStatus};
+use entries_common::db::{self, DaoError, DbThreadPool};
+use entries_common::email::templates::UserVerificationMessage;
+use entries_common::email::{EmailError, EmailMessage, SendEmail};
+use entries_common::models::admin_audit_log_entry::AdminAuditLogEntry;
+use entries_common::token::auth_token::{AuthToken, AuthTokenType, NewAuthTokenClaims};
+
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+use uuid::Uuid;
+
+use crate::env::EmailConfig;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    LookUpUser,
+    ResendVerification,
+    ForceVerify,
+    CancelDeletion,
+    ExpediteDeletion,
+    ClearOtps,
+    ListInvitations,
+    ExpireInvitations,
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Action::LookUpUser => "look_up_user",
+            Action::ResendVerification => "resend_verification",
+            Action::ForceVerify => "force_verify",
+            Action::CancelDeletion => "cancel_deletion",
+            Action::ExpediteDeletion => "expedite_deletion",
+            Action::ClearOtps => "clear_otps",
+            Action::ListInvitations => "list_invitations",
+            Action::ExpireInvitations => "expire_invitations",
+        };
+
+        write!(f, "{name}")
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct UserReport {
+    pub status: UserStatus,
+    pub pending_invitation_count: usize,
+}
+
+/// Carries out support actions on behalf of an operator. Every action, including lookups and
+/// failed attempts, is recorded in the audit log.
+pub struct Admin {
+    db_thread_pool: DbThreadPool,
+    operator: String,
+}
+
+impl Admin {
+    pub fn new(db_thread_pool: &DbThreadPool, operator: impl Into<String>) -> Self {
+        Self {
+            db_thread_pool: db_thread_pool.clone(),
+            operator: operator.into(),
+        }
+    }
+
+    pub fn look_up_user(&self, user_email: &str) -> Result<UserReport, AdminError> {
+        let result = self.get_user_status(user_email).and_then(|status| {
+            let pending_invitation_count = db::budget::Dao::new(&self.db_thread_pool)
+                .get_pending_invitation_ids(user_email)?
+                .len();
+
+            Ok(UserReport {
+                status,
+                pending_invitation_count,
+            })
+        });
+
+        self.audit(Action::LookUpUser, user_email, result, |_| String::new())
+    }
+
+    pub async fn resend_verification(
+        &self,
+        user_email: &str,
+        email_config: Option<&EmailConfig>,
+        sender: &dyn SendEmail,
+    ) -> Result<(), AdminError> {
+        let result = self
+            .send_verification_email(user_email, email_config, sender)
+            .await;
+
+        self.audit(Action::ResendVerification, user_email, result, |_| {
+            String::from("Sent a new verification email")
+        })
+    }
+
+    /// Returns false if the user was already verified
+    pub fn force_verify(&self, user_email: &str) -> Result<bool, AdminError> {
+        let result = self.get_user_status(user_email).and_then(|status| {
+            if status.is_verified {
+                return Ok(false);
+            }
+
+            db::user::Dao::new(&self.db_thread_pool).verify_user_creation(status.id)?;
+            Ok(true)
+        });
+
+        self.audit(Action::ForceVerify, user_email, result, |verified| {
+            if *verified {
+                String::from("Marked user as verified")
+            } else {
+                String::from("User was already verified")
+            }
+        })
+    }
+
+    pub fn cancel_deletion(&self, user_email: &str) -> Result<(), AdminError> {
+        let result = self.get_user_status(user_email).and_then(|status| {
+            if status.ready_for_deletion_time.is_none() {
+                return Err(AdminError::NoPendingDeletion);
+            }
+
+            db::user::Dao::new(&self.db_thread_pool).cancel_user_deletion(status.id)?;
+            Ok(())
+        });
+
+        self.audit(Action::CancelDeletion, user_email, result, |_| {
+            String::from("Cancelled pending deletion")
+        })
+    }
+
+    /// Makes a pending deletion due now. The user is deleted the next time the job scheduler
+    /// runs its user deletion job.
+    pub fn expedite_deletion(&self, user_email: &str) -> Result<(), AdminError> {
+        let result = self.get_user_status(user_email).and_then(|status| {
+            if db::user::Dao::new(&self.db_thread_pool).expedite_user_deletion(status.id)? {
+                Ok(())
+            } else {
+                Err(AdminError::NoPendingDeletion)
+            }
+        });
+
+        self.audit(Action::ExpediteDeletion, user_email, result, |_| {
+            String::from("Made pending deletion due immediately")
+        })
+    }
+
+    /// Returns the number of OTPs cleared
+    pub fn clear_otps(&self, user_email: &str) -> Result<usize, AdminError> {
+        let result = self.get_user_status(user_email).and_then(|_| {
+            Ok(db::auth::Dao::new(&self.db_thread_pool).delete_all_otps_for_user(user_email)?)
+        });
+
+        self.audit(Action::ClearOtps, user_email, result, |count| {
+            format!("Cleared {count} OTP(s)")
+        })
+    }
+
+    pub fn list_invitations(&self, user_email: &str) -> Result<Vec<Uuid>, AdminError> {
+        let result = db::budget::Dao::new(&self.db_thread_pool)
+            .get_pending_invitation_ids(user_email)
+            .map_err(AdminError::from);
+
+        self.audit(Action::ListInvitations, user_email, result, |ids| {
+            format!("Found {} pending invitation(s)", ids.len())
+        })
+    }
+
+    /// Expires a single invitation sent to the user, or all of them if `invitation_id` is
+    /// `None`. Returns the number of invitations expired.
+    pub fn expire_invitations(
+        &self,
+        user_email: &str,
+        invitation_id: Option<Uuid>,
+    ) -> Result<usize, AdminError> {
+        let budget_dao = db::budget::Dao::new(&self.db_thread_pool);
+
+        let result = match invitation_id {
+            Some(id) => budget_dao
+                .get_pending_invitation_ids(user_email)
+                .map_err(AdminError::from)
+                .and_then(|ids| {
+                    if !ids.contains(&id) {
+                        return Err(AdminError::InvitationNotFound(id));
+                    }
+
+                    budget_dao.delete_invitation(id)?;
+                    Ok(1)
+                }),
+            None => budget_dao
+                .delete_all_invitations_for_user(user_email)
+                .map_err(AdminError::from),
+        };
+
+        self.audit(
+            Action::ExpireInvitations,
+            user_email,
+            result,
+            |count| match invitation_id {
+                Some(id) => format!("Expired invitation {id}"),
+                None => format!("Expired {count} invitation(s)"),
+            },
+        )
+    }
+
+    pub fn audit_log(
+        &self,
+        user_email: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<AdminAuditLogEntry>, AdminError> {
+        Ok(db::admin_audit_log::Dao::new(&self.db_thread_pool)
+            .get_recent_entries(user_email, limit)?)
+    }
+
+    fn get_user_status(&self, user_email: &str) -> Result<UserStatus, AdminError> {
+        match db::user::Dao::new(&self.db_thread_pool).get_user_status(user_email) {
+            Ok(s) => Ok(s),
+            Err(DaoError::QueryFailure(diesel::result::Error::NotFound)) => {
+                Err(AdminError::UserNotFound)
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn send_verification_email(
+        &self,
+        user_email: &str,
+        email_config: Option<&EmailConfig>,
+        sender: &dyn SendEmail,
+    ) -> Result<(), AdminError> {
+        let email_config = email_config.ok_or(AdminError::EmailDisabled)?;
+        let status = self.get_user_status(user_email)?;
+
+        if status.is_verified {
+            return Err(AdminError::AlreadyVerified);
+        }
+
+        let user_creation_token_claims = NewAuthTokenClaims {
+            user_id: status.id,
+            user_email: &status.email,
+            expiration: (SystemTime::now() + email_config.user_creation_token_lifetime)
+                .duration_since(UNIX_EPOCH)
+                .expect("System time should be after Unix Epoch")
+                .as_secs(),
+            token_type: AuthTokenType::UserCreation,
+        };
+
+        let user_creation_token =
+            AuthToken::sign_new(user_creation_token_claims, &email_config.token_signing_key);
+
+        let message = EmailMessage {
+            body: UserVerificationMessage::generate(
+                &email_config.user_verification_url,
+                &user_creation_token,
+                email_config.user_creation_token_lifetime,
+            ),
+            subject: "Verify your account",
+            from: email_config.email_from_address.clone(),
+            reply_to: email_config.email_reply_to_address.clone(),
+            destination: &status.email,
+            is_html: true,
+        };
+
+        sender.send(message).await?;
+
+        Ok(())
+    }
+
+    fn audit<T>(
+        &self,
+        action: Action,
+        user_email: &str,
+        result: Result<T, AdminError>,
+        describe: impl FnOnce(&T) -> String,
+    ) -> Result<T, AdminError> {
+        let (details, succeeded) = match &result {
+            Ok(value) => (describe(value), true),
+            Err(e) => (e.to_string(), false),
+        };
+
+        db::admin_audit_log::Dao::new(&self.db_thread_pool)
+            .record(
+                &self.operator,
+                &action.to_string(),
+                user_email,
+                &details,
+                succeeded,
+            )
+            .map_err(AdminError::AuditLogFailure)?;
+
+        result
+    }
+}
+
+#[derive(Debug)]
+pub enum AdminError {
+    UserNotFound,
+    AlreadyVerified,
+    NoPendingDeletion,
+    InvitationNotFound(Uuid),
+    EmailDisabled,
+    EmailFailure(EmailError),
+    DatabaseFailure(DaoError),
+    AuditLogFailure(DaoError),
+}
+
+impl std::error::Error for AdminError {}
+
+impl fmt::Display for AdminError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AdminError::UserNotFound => write!(f, "No user exists with that email address"),
+            AdminError::AlreadyVerified => write!(f, "User is already verified"),
+            AdminError::NoPendingDeletion => write!(f, "User has no pending deletion"),
+            AdminError::InvitationNotFound(id) => {
+                write!(f, "No pending invitation {id} exists for that user")
+            }
+            AdminError::EmailDisabled => write!(
+                f,
+                "Email is disabled. Set ENTRIES_EMAIL_ENABLED=true and the email settings to \
+                 send email"
+            ),
+            AdminError::EmailFailure(e) => write!(f, "Failed to send email: {e}"),
+            AdminError::DatabaseFailure(e) => write!(f, "Database operation failed: {e}"),
+            AdminError::AuditLogFailure(e) => write!(
+                f,
+                "Failed to record the action in the audit log (the action itself may have \
+                 completed): {e}"
+            ),
+        }
+    }
+}
+
+impl From<DaoError> for AdminError {
+    fn from(e: DaoError) -> Self {
+        AdminError::DatabaseFailure(e)
+    }
+}
+
+impl From<EmailError> for AdminError {
+    fn from(e: EmailError) -> Self {
+        AdminError::EmailFailure(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use entries_common::db::user;
+    use entries_common::email::senders::MockSender;
+    use entries_common::models::budget_share_invite::NewBudgetShareInvite;
+    use entries_common::schema::budget_share_invites;
+
+    use diesel::RunQueryDsl;
+    use rand::Rng;
+    use std::time::Duration;
+
+    use crate::env;
+
+    fn create_user() -> (Uuid, String) {
+        let user_number = rand::thread_rng().gen_range::<u128, _>(u128::MIN..u128::MAX);
+        let email = format!("test_user{}@test.com", &user_number);
+
+        let user_id = user::Dao::new(&env::testing::DB_THREAD_POOL)
+            .create_user(
+                &email,
+                "",
+                &[],
+                1024,
+                1,
+                2,
+                &[],
+                1024,
+                1,
+                2,
+                &[],
+                1024,
+                1,
+                2,
+                &[],
+                &[],
+                Uuid::now_v7(),
+                &[],
+                &[],
+                rand::thread_rng().gen(),
+                &[],
+                rand::thread_rng().gen(),
+                &[],
+            )
+            .unwrap();
+
+        (user_id, email)
+    }
+
+    fn invite_user(email: &str) -> Uuid {
+        let invite = NewBudgetShareInvite {
+            id: Uuid::now_v7(),
+            recipient_user_email: email,
+            sender_public_key: &[],
+            encryption_key_encrypted: &[],
+            budget_accept_private_key_encrypted: &[],
+            budget_info_encrypted: &[],
+            sender_info_encrypted: &[],
+            budget_accept_key_info_encrypted: &[],
+            budget_accept_key_id_encrypted: &[],
+            share_info_symmetric_key_encrypted: &[],
+            recipient_public_key_id_used_by_sender: Uuid::now_v7(),
+            recipient_public_key_id_used_by_server: Uuid::now_v7(),
+            created_unix_timestamp_intdiv_five_million: (SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_secs()
+                / 5_000_000) as i16,
+        };
+
+        diesel::insert_into(budget_share_invites::table)
+            .values(&invite)
+            .execute(&mut env::testing::DB_THREAD_POOL.get().unwrap())
+            .unwrap();
+
+        invite.id
+    }
+
+    #[tokio::test]
+    async fn test_user_actions() {
+        let (user_id, email) = create_user();
+        let admin = Admin::new(&env::testing::DB_THREAD_POOL, "test_operator");
+
+        let report = admin.look_up_user(&email).unwrap();
+        assert_eq!(report.status.id, user_id);
+        assert!(!report.status.is_verified);
+        assert!(report.status.ready_for_deletion_time.is_none());
+        assert_eq!(report.pending_invitation_count, 0);
+
+        assert!(matches!(
+            admin.look_up_user("nobody@test.com"),
+            Err(AdminError::UserNotFound)
+        ));
+
+        // Email is always disabled in tests
+        assert!(matches!(
+            admin
+                .resend_verification(&email, None, &MockSender::new())
+                .await,
+            Err(AdminError::EmailDisabled)
+        ));
+
+        assert!(admin.force_verify(&email).unwrap());
+        assert!(!admin.force_verify(&email).unwrap());
+        assert!(admin.look_up_user(&email).unwrap().status.is_verified);
+
+        db::auth::Dao::new(&env::testing::DB_THREAD_POOL)
+            .save_otp(
+                "ABC123",
+                &email,
+                SystemTime::now() + Duration::from_secs(600),
+            )
+            .unwrap();
+        assert_eq!(admin.clear_otps(&email).unwrap(), 1);
+        assert_eq!(admin.clear_otps(&email).unwrap(), 0);
+
+        assert!(matches!(
+            admin.cancel_deletion(&email),
+            Err(AdminError::NoPendingDeletion)
+        ));
+        assert!(matches!(
+            admin.expedite_deletion(&email),
+            Err(AdminError::NoPendingDeletion)
+        ));
+
+        let user_dao = user::Dao::new(&env::testing::DB_THREAD_POOL);
+        user_dao
+            .initiate_user_deletion(user_id, Duration::from_secs(86400 * 7))
+            .unwrap();
+        assert!(admin
+            .look_up_user(&email)
+            .unwrap()
+            .status
+            .ready_for_deletion_time
+            .is_some());
+
+        admin.cancel_deletion(&email).unwrap();
+        assert!(admin
+            .look_up_user(&email)
+            .unwrap()
+            .status
+            .ready_for_deletion_time
+            .is_none());
+
+        user_dao
+            .initiate_user_deletion(user_id, Duration::from_secs(86400 * 7))
+            .unwrap();
+        admin.expedite_deletion(&email).unwrap();
+        assert!(user_dao
+            .get_all_users_ready_for_deletion()
+            .unwrap()
+            .iter()
+            .any(|r| r.user_id == user_id));
+
+        let audit_log = admin.audit_log(Some(&email), 100).unwrap();
+        assert!(audit_log.iter().all(|e| e.operator == "test_operator"));
+        assert!(audit_log.iter().any(|e| e.action == "force_verify"
+            && e.succeeded
+            && e.details == "Marked user as verified"));
+        assert!(audit_log
+            .iter()
+            .any(|e| e.action == "resend_verification" && !e.succeeded));
+        assert!(audit_log
+            .iter()
+            .any(|e| e.action == "expedite_deletion" && e.succeeded));
+
+        // Newest first
+        assert_eq!(audit_log[0].action, "expedite_deletion");
+        assert!(audit_log[0].succeeded);
+    }
+
+    #[test]
+    fn test_expire_invitations() {
+        let (_, email) = create_user();
+        let admin = Admin::new(&env::testing::DB_THREAD_POOL, "test_operator");
+
+        let invite1 = invite_user(&email);
+        let invite2 = invite_user(&email);
+        let invite3 = invite_user(&email);
+
+        assert_eq!(
+            admin.list_invitations(&email).unwrap(),
+            vec![invite1, invite2, invite3]
+        );
+        assert_eq!(
+            admin.look_up_user(&email).unwrap().pending_invitation_count,
+            3
+        );
+
+        assert!(matches!(
+            admin.expire_invitations(&email, Some(Uuid::now_v7())),
+            Err(AdminError::InvitationNotFound(_))
+        ));
+
+        assert_eq!(admin.expire_invitations(&email, Some(invite2)).unwrap(), 1);
+        assert_eq!(
+            admin.list_invitations(&email).unwrap(),
+            vec![invite1, invite3]
+        );
+
+        assert_eq!(admin.expire_invitations(&email, None).unwrap(), 2);
+        assert!(admin.list_invitations(&email).unwrap().is_empty());
+
+        let audit_log = admin.audit_log(Some(&email), 100).unwrap();
+        assert_eq!(
+            audit_log
+                .iter()
+                .filter(|e| e.action == "expire_invitations")
+                .count(),
+            3
+        );
+        assert!(audit_log
+            .iter()
+            .any(|e| e.details == format!("Expired invitation {invite2}")));
+    }
+}
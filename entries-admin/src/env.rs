@@ -0,0 +1,234 @@
+use entries_common::config::{ConfigError, ConfigSource};
+use lettre::message::Mailbox;
+use once_cell::sync::Lazy;
+use std::cell::UnsafeCell;
+use std::ops::Deref;
+use std::time::Duration;
+use zeroize::Zeroize;
+
+const DB_USERNAME_VAR: &str = "ENTRIES_DB_USERNAME";
+const DB_PASSWORD_VAR: &str = "ENTRIES_DB_PASSWORD";
+const DB_HOSTNAME_VAR: &str = "ENTRIES_DB_HOSTNAME";
+const DB_PORT_VAR: &str = "ENTRIES_DB_PORT";
+const DB_NAME_VAR: &str = "ENTRIES_DB_NAME";
+const DB_IDLE_TIMEOUT_SECS_VAR: &str = "ENTRIES_DB_IDLE_TIMEOUT_SECS";
+
+const ADMIN_OPERATOR_VAR: &str = "ENTRIES_ADMIN_OPERATOR";
+
+const EMAIL_ENABLED_VAR: &str = "ENTRIES_EMAIL_ENABLED";
+const TOKEN_SIGNING_KEY_VAR: &str = "ENTRIES_TOKEN_SIGNING_KEY_B64";
+const AMAZON_SES_USERNAME_VAR: &str = "ENTRIES_AMAZON_SES_USERNAME";
+const AMAZON_SES_KEY_VAR: &str = "ENTRIES_AMAZON_SES_KEY";
+const EMAIL_FROM_ADDR: &str = "ENTRIES_EMAIL_FROM_ADDR";
+const EMAIL_REPLY_TO_ADDR: &str = "ENTRIES_EMAIL_REPLY_TO_ADDR";
+const SMTP_ADDRESS_VAR: &str = "ENTRIES_SMTP_ADDRESS";
+const USER_VERIFICATION_URL_VAR: &str = "ENTRIES_USER_VERIFICATION_URL";
+const USER_CREATION_TOKEN_LIFETIME_DAYS_VAR: &str = "ENTRIES_USER_CREATION_TOKEN_LIFETIME_DAYS";
+
+const TOKEN_SIGNING_KEY_SIZE: usize = 64;
+
+pub static CONF: Lazy<Config> = Lazy::new(|| match Config::load() {
+    Ok(c) => c,
+    Err(e) => {
+        eprintln!("ERROR: Failed to load configuration: {e}");
+
+        if cfg!(test) {
+            panic!();
+        } else {
+            std::process::exit(1);
+        }
+    }
+});
+
+#[derive(Zeroize)]
+pub struct ConfigInner {
+    pub db_username: String,
+    pub db_password: String,
+    pub db_hostname: String,
+    pub db_port: u16,
+    pub db_name: String,
+    #[zeroize(skip)]
+    pub db_idle_timeout: Duration,
+
+    #[zeroize(skip)]
+    pub operator: Option<String>,
+
+    /// Only loaded when email is enabled, so operators who only need to look things up don't
+    /// need the email credentials
+    pub email: Option<EmailConfig>,
+}
+
+#[derive(Zeroize)]
+pub struct EmailConfig {
+    pub token_signing_key: [u8; TOKEN_SIGNING_KEY_SIZE],
+    pub amazon_ses_username: String,
+    pub amazon_ses_key: String,
+    #[zeroize(skip)]
+    pub email_from_address: Mailbox,
+    #[zeroize(skip)]
+    pub email_reply_to_address: Mailbox,
+    #[zeroize(skip)]
+    pub smtp_address: String,
+    #[zeroize(skip)]
+    pub user_verification_url: String,
+    #[zeroize(skip)]
+    pub user_creation_token_lifetime: Duration,
+}
+
+pub struct Config {
+    inner: UnsafeCell<ConfigInner>,
+}
+
+impl Deref for Config {
+    type Target = ConfigInner;
+
+    fn deref(&self) -> &Self::Target {
+        // Safe as long as `unsafe Config::zeroize()` hasn't been called
+        unsafe { &*self.inner.get() }
+    }
+}
+
+// Safe to be shared across threads as long as `unsafe Config::zeroize()` hasn't been called
+unsafe impl Sync for Config {}
+
+impl Config {
+    pub fn load() -> Result<Config, ConfigError> {
+        Self::from_source(&ConfigSource::load()?)
+    }
+
+    pub fn from_source(source: &ConfigSource) -> Result<Config, ConfigError> {
+        let email_enabled = if cfg!(test) {
+            false
+        } else {
+            source.get_or(EMAIL_ENABLED_VAR, false)?
+        };
+
+        let email = if email_enabled {
+            Some(EmailConfig::from_source(source)?)
+        } else {
+            None
+        };
+
+        let inner = ConfigInner {
+            db_username: source.get(DB_USERNAME_VAR)?,
+            db_password: source.get_secret(DB_PASSWORD_VAR)?,
+            db_hostname: source.get(DB_HOSTNAME_VAR)?,
+            db_port: source.get(DB_PORT_VAR)?,
+            db_name: source.get(DB_NAME_VAR)?,
+            db_idle_timeout: Duration::from_secs(source.get_or(DB_IDLE_TIMEOUT_SECS_VAR, 30)?),
+
+            operator: source.get_opt(ADMIN_OPERATOR_VAR)?,
+
+            email,
+        };
+
+        Ok(Config {
+            inner: UnsafeCell::new(inner),
+        })
+    }
+
+    /// # Safety
+    ///
+    /// Safe only if the Config isn't being used by other threads or across an async
+    /// boundary. Generally, this should only be used at the end of the main function once
+    /// all threads have been joined.
+    pub unsafe fn zeroize(&self) {
+        unsafe {
+            (*self.inner.get()).zeroize();
+        }
+    }
+}
+
+impl EmailConfig {
+    fn from_source(source: &ConfigSource) -> Result<EmailConfig, ConfigError> {
+        let token_signing_key = source.get_secret_key(TOKEN_SIGNING_KEY_VAR)?;
+
+        let email_from_address = source.get_mailbox(EMAIL_FROM_ADDR)?;
+        let email_reply_to_address = source.get_mailbox(EMAIL_REPLY_TO_ADDR)?;
+
+        Ok(EmailConfig {
+            token_signing_key,
+            amazon_ses_username: source.get_secret(AMAZON_SES_USERNAME_VAR)?,
+            amazon_ses_key: source.get_secret(AMAZON_SES_KEY_VAR)?,
+            email_from_address,
+            email_reply_to_address,
+            smtp_address: source.get(SMTP_ADDRESS_VAR)?,
+            user_verification_url: source.get(USER_VERIFICATION_URL_VAR)?,
+            user_creation_token_lifetime: Duration::from_secs(
+                source.get_or(USER_CREATION_TOKEN_LIFETIME_DAYS_VAR, 7)? * 86400,
+            ),
+        })
+    }
+}
+
+/// Loads and validates the configuration, then prints the effective configuration with
+/// secrets redacted. Returns whether the configuration is valid.
+pub fn check_config() -> bool {
+    let source = match ConfigSource::load() {
+        Ok(s) => s.collect_errors(),
+        Err(e) => {
+            eprintln!("ERROR: {e}");
+            return false;
+        }
+    };
+
+    // Missing and invalid values are collected by the source instead of failing the load, so
+    // every problem can be reported at once
+    let config = Config::from_source(&source);
+    let errors: Vec<String> = source
+        .take_errors()
+        .iter()
+        .chain(config.as_ref().err())
+        .map(ToString::to_string)
+        .collect();
+
+    match source.file_path() {
+        Some(path) => println!("Config file: {}", path.display()),
+        None => println!("Config file: none"),
+    }
+
+    println!();
+    print!("{}", source.effective_config());
+
+    let unused_keys = source.unused_file_keys();
+    if !unused_keys.is_empty() {
+        println!();
+        println!("Keys in the config file not used by this binary:");
+
+        for key in unused_keys {
+            println!("  {key}");
+        }
+    }
+
+    for e in &errors {
+        eprintln!("ERROR: {e}");
+    }
+
+    if let Ok(config) = config {
+        // Safe because this config is never shared
+        unsafe {
+            config.zeroize();
+        }
+    }
+
+    errors.is_empty()
+}
+
+#[cfg(test)]
+pub mod testing {
+    use entries_common::db::{create_db_thread_pool, DbThreadPool};
+
+    use super::*;
+
+    pub static DB_THREAD_POOL: Lazy<DbThreadPool> = Lazy::new(|| {
+        create_db_thread_pool(
+            &format!(
+                "postgres://{}:{}@{}:{}/{}",
+                CONF.db_username, CONF.db_password, CONF.db_hostname, CONF.db_port, CONF.db_name,
+            ),
+            8,
+            CONF.db_idle_timeout,
+            None,
+        )
+    });
+}
@@ -0,0 +1,320 @@
+use clap::{Parser, Subcommand};
+use entries_common::config;
+use entries_common::db::migrations::MigrationsDao;
+use entries_common::db::{self, create_db_thread_pool};
+use entries_common::email::senders::{AmazonSes, MockSender};
+use entries_common::email::SendEmail;
+
+use chrono::{DateTime, Utc};
+use std::io::{self, BufRead, Write};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use uuid::Uuid;
+use zeroize::Zeroizing;
+
+mod actions;
+mod env;
+
+use actions::{Admin, AdminError};
+
+#[derive(Debug, Parser)]
+#[command(
+    version,
+    about = "Support tooling for operators of the Entries App",
+    long_about = "Support tooling for operators of the Entries App. Every action is recorded \
+                  in the admin audit log along with the operator who took it."
+)]
+struct Args {
+    /// Read configuration from this TOML file. Overrides ENTRIES_CONFIG_FILE
+    #[arg(long, global = true, value_name = "PATH")]
+    config: Option<String>,
+
+    /// Validate the configuration, print it with secrets redacted, and exit
+    #[arg(long)]
+    check_config: bool,
+
+    /// Name recorded in the audit log. Overrides ENTRIES_ADMIN_OPERATOR and defaults to $USER
+    #[arg(long, global = true, value_name = "NAME")]
+    operator: Option<String>,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Show a user's verification and deletion status
+    User { email: String },
+
+    /// Send a new verification email to an unverified user
+    ResendVerification { email: String },
+
+    /// Mark a user as verified without them following the emailed link
+    Verify { email: String },
+
+    /// Cancel a user's pending account deletion
+    CancelDeletion { email: String },
+
+    /// Make a user's pending deletion due now. The job scheduler deletes the user on the next
+    /// run of its user deletion job
+    ExpediteDeletion {
+        email: String,
+
+        /// Don't ask for confirmation
+        #[arg(long)]
+        yes: bool,
+    },
+
+    /// Clear a user's outstanding sign-in OTPs. Rate limits are kept in the memory of each
+    /// server process and clear on their own at the end of the limiter period
+    ClearOtps { email: String },
+
+    /// List the budget invitations waiting for a user
+    Invitations { email: String },
+
+    /// Expire budget invitations sent to a user
+    ExpireInvitations {
+        email: String,
+
+        /// Expire only this invitation instead of all of the user's invitations
+        #[arg(long, value_name = "INVITATION_ID")]
+        id: Option<Uuid>,
+    },
+
+    /// Show recent entries in the audit log
+    AuditLog {
+        /// Show only entries for this user
+        #[arg(long)]
+        email: Option<String>,
+
+        /// Maximum number of entries to show
+        #[arg(long, default_value_t = 50)]
+        limit: i64,
+    },
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() {
+    let args = Args::parse();
+
+    if let Some(config_path) = &args.config {
+        std::env::set_var(config::CONFIG_FILE_VAR, config_path);
+    }
+
+    if args.check_config {
+        std::process::exit(if env::check_config() { 0 } else { 1 });
+    }
+
+    let Some(command) = args.command else {
+        eprintln!("ERROR: No command given. Run with --help to see the available commands");
+        std::process::exit(2);
+    };
+
+    let operator = match args
+        .operator
+        .or_else(|| env::CONF.operator.clone())
+        .or_else(|| std::env::var("USER").ok())
+    {
+        Some(o) if !o.trim().is_empty() => o,
+        _ => {
+            eprintln!("ERROR: Set --operator or ENTRIES_ADMIN_OPERATOR so actions can be audited");
+            std::process::exit(2);
+        }
+    };
+
+    let db_uri = Zeroizing::new(format!(
+        "postgres://{}:{}@{}:{}/{}",
+        env::CONF.db_username,
+        env::CONF.db_password,
+        env::CONF.db_hostname,
+        env::CONF.db_port,
+        env::CONF.db_name,
+    ));
+
+    let db_thread_pool = create_db_thread_pool(&db_uri, 2, env::CONF.db_idle_timeout, None);
+
+    let pending_migrations =
+        match db::migrations::Dao::new(&db_thread_pool).get_pending_migrations() {
+            Ok(m) => m,
+            Err(e) => {
+                eprintln!("ERROR: Failed to check for pending database migrations: {e}");
+                std::process::exit(1);
+            }
+        };
+
+    if !pending_migrations.is_empty() {
+        for name in &pending_migrations {
+            eprintln!("Pending migration: {name}");
+        }
+
+        eprintln!(
+            "ERROR: The database schema is behind this tool. Run `entries_server migrate` first."
+        );
+        std::process::exit(1);
+    }
+
+    let admin = Admin::new(&db_thread_pool, operator);
+
+    let result = match command {
+        Command::User { email } => admin.look_up_user(&email).map(|report| {
+            let status = &report.status;
+
+            println!("User ID:             {}", status.id);
+            println!("Email:               {}", status.email);
+            println!("Verified:            {}", yes_no(status.is_verified));
+            println!(
+                "Created:             {}",
+                format_time(status.created_timestamp)
+            );
+
+            match status.ready_for_deletion_time {
+                Some(time) => {
+                    println!("Pending deletion:    yes, due {}", format_time(time));
+                    println!("Budgets to delete:   {}", status.budgets_pending_deletion);
+                }
+                None => {
+                    println!("Pending deletion:    no");
+                    // Budget membership is end-to-end encrypted, so the server can only count a
+                    // user's budgets once the user has handed over their keys for deletion
+                    println!("Budgets:             unknown until deletion is requested");
+                }
+            }
+
+            println!("Pending invitations: {}", report.pending_invitation_count);
+        }),
+        Command::ResendVerification { email } => {
+            let sender: Box<dyn SendEmail> = match &env::CONF.email {
+                Some(email_config) => match AmazonSes::with_credentials(
+                    &email_config.amazon_ses_username,
+                    &email_config.amazon_ses_key,
+                    &email_config.smtp_address,
+                    1,
+                    Duration::from_secs(30),
+                ) {
+                    Ok(s) => Box::new(s),
+                    Err(e) => {
+                        eprintln!("ERROR: Failed to connect to SMTP relay: {e}");
+                        std::process::exit(1);
+                    }
+                },
+                None => Box::new(MockSender::new()),
+            };
+
+            admin
+                .resend_verification(&email, env::CONF.email.as_ref(), sender.as_ref())
+                .await
+                .map(|_| println!("Sent a new verification email to {email}"))
+        }
+        Command::Verify { email } => admin.force_verify(&email).map(|verified| {
+            if verified {
+                println!("Marked {email} as verified");
+            } else {
+                println!("{email} was already verified");
+            }
+        }),
+        Command::CancelDeletion { email } => admin
+            .cancel_deletion(&email)
+            .map(|_| println!("Cancelled the pending deletion of {email}")),
+        Command::ExpediteDeletion { email, yes } => {
+            let confirmed = yes
+                || confirm(&format!(
+                    "{email} and any budgets only they belong to will be permanently deleted \
+                     the next time the user deletion job runs. Type the email address to \
+                     confirm: "
+                ))
+                .is_some_and(|answer| answer == email);
+
+            if !confirmed {
+                eprintln!("Aborted");
+                std::process::exit(1);
+            }
+
+            admin.expedite_deletion(&email).map(|_| {
+                println!(
+                    "The deletion of {email} is now due and will happen on the next run of the \
+                     user deletion job"
+                )
+            })
+        }
+        Command::ClearOtps { email } => admin
+            .clear_otps(&email)
+            .map(|count| println!("Cleared {count} OTP(s) for {email}")),
+        Command::Invitations { email } => admin.list_invitations(&email).map(|ids| {
+            if ids.is_empty() {
+                println!("No pending invitations for {email}");
+            }
+
+            for id in ids {
+                match invitation_sent_time(id) {
+                    Some(time) => println!("{id}  sent {}", format_time(time)),
+                    None => println!("{id}"),
+                }
+            }
+        }),
+        Command::ExpireInvitations { email, id } => admin
+            .expire_invitations(&email, id)
+            .map(|count| println!("Expired {count} invitation(s) for {email}")),
+        Command::AuditLog { email, limit } => {
+            admin.audit_log(email.as_deref(), limit).map(|entries| {
+                for entry in entries {
+                    println!(
+                        "{}  {}  {}  {}  {}  {}",
+                        format_time(entry.timestamp),
+                        entry.operator,
+                        entry.action,
+                        entry.target_user_email,
+                        if entry.succeeded { "ok" } else { "failed" },
+                        entry.details,
+                    );
+                }
+            })
+        }
+    };
+
+    if let Err(e) = result {
+        eprintln!("ERROR: {e}");
+
+        let exit_code = match e {
+            AdminError::DatabaseFailure(_)
+            | AdminError::AuditLogFailure(_)
+            | AdminError::EmailFailure(_) => 1,
+            _ => 3,
+        };
+
+        std::process::exit(exit_code);
+    }
+
+    // Safe because all work on other threads has finished
+    unsafe {
+        env::CONF.zeroize();
+    }
+}
+
+fn confirm(prompt: &str) -> Option<String> {
+    eprint!("{prompt}");
+    io::stderr().flush().ok()?;
+
+    let mut answer = String::new();
+    io::stdin().lock().read_line(&mut answer).ok()?;
+
+    Some(answer.trim().to_owned())
+}
+
+fn yes_no(value: bool) -> &'static str {
+    if value {
+        "yes"
+    } else {
+        "no"
+    }
+}
+
+fn format_time(time: SystemTime) -> String {
+    DateTime::<Utc>::from(time)
+        .format("%Y-%m-%dT%H:%M:%SZ")
+        .to_string()
+}
+
+/// Invitation IDs are v7 UUIDs, which begin with the time they were created
+fn invitation_sent_time(invitation_id: Uuid) -> Option<SystemTime> {
+    let (secs, nanos) = invitation_id.get_timestamp()?.to_unix();
+    Some(UNIX_EPOCH + Duration::new(secs, nanos))
+}
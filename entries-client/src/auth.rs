@@ -0,0 +1,125 @@
+use entries_common::messages::{
+    BackupCode, BackupCodeList, CredentialPair, Otp, SigninNonceAndHashParams, SigninToken,
+    TokenPair,
+};
+
+use reqwest::Method;
+
+use crate::client::{Call, Client};
+use crate::error::ClientError;
+use crate::tokens::{self, AuthStringParams};
+
+impl Client {
+    pub async fn obtain_nonce_and_auth_string_params(
+        &self,
+        email: &str,
+    ) -> Result<SigninNonceAndHashParams, ClientError> {
+        let call =
+            Call::new(Method::GET, "/api/auth/nonce_and_auth_string_params").query("email", email);
+
+        self.send(call).await
+    }
+
+    /// Derives the user's auth string from their password and starts signing in. The server
+    /// emails the user an OTP to pass to `verify_otp` along with the returned token.
+    pub async fn sign_in(&self, email: &str, password: &str) -> Result<SigninToken, ClientError> {
+        let params = self.obtain_nonce_and_auth_string_params(email).await?;
+        let password = String::from(password);
+
+        let auth_string = tokio::task::spawn_blocking(move || {
+            tokens::derive_auth_string(&password, AuthStringParams::from(&params))
+                .map(|auth_string| (auth_string, params.nonce))
+        })
+        .await
+        .expect("Auth string derivation panicked");
+
+        let (auth_string, nonce) = auth_string?;
+
+        self.sign_in_with_credentials(&CredentialPair {
+            email: String::from(email),
+            auth_string,
+            nonce,
+        })
+        .await
+    }
+
+    pub async fn sign_in_with_credentials(
+        &self,
+        credentials: &CredentialPair,
+    ) -> Result<SigninToken, ClientError> {
+        let call = Call::new(Method::POST, "/api/auth/sign_in").body(credentials);
+        self.send(call).await
+    }
+
+    /// Finishes signing in. The client keeps the returned tokens for later calls.
+    pub async fn verify_otp(
+        &self,
+        signin_token: &SigninToken,
+        otp: &str,
+    ) -> Result<TokenPair, ClientError> {
+        let call = Call::new(Method::POST, "/api/auth/otp/verify")
+            .header("SignInToken", &signin_token.value)
+            .body(&Otp {
+                value: String::from(otp),
+            });
+
+        let tokens: TokenPair = self.send(call).await?;
+        self.set_tokens(tokens.clone());
+
+        Ok(tokens)
+    }
+
+    /// Finishes signing in with a backup code instead of an OTP. The client keeps the returned
+    /// tokens for later calls.
+    pub async fn use_backup_code(
+        &self,
+        signin_token: &SigninToken,
+        code: &str,
+    ) -> Result<TokenPair, ClientError> {
+        let call = Call::new(Method::POST, "/api/auth/backup_code/use")
+            .header("SignInToken", &signin_token.value)
+            .body(&BackupCode {
+                value: String::from(code),
+            });
+
+        let tokens: TokenPair = self.send(call).await?;
+        self.set_tokens(tokens.clone());
+
+        Ok(tokens)
+    }
+
+    pub async fn regenerate_backup_codes(&self, otp: &str) -> Result<BackupCodeList, ClientError> {
+        let call = Call::new(Method::PUT, "/api/auth/backup_code/regenerate")
+            .with_access_token()
+            .body(&Otp {
+                value: String::from(otp),
+            });
+
+        self.send(call).await
+    }
+
+    /// Has the server email the user an OTP
+    pub async fn obtain_otp(&self) -> Result<(), ClientError> {
+        let call = Call::new(Method::GET, "/api/auth/otp").with_access_token();
+        self.send(call).await
+    }
+
+    /// Replaces the client's tokens. Calls refresh the tokens on their own when the access
+    /// token expires, so this is only needed to extend a session ahead of time.
+    pub async fn refresh_tokens(&self) -> Result<TokenPair, ClientError> {
+        let _guard = self.lock_refresh().await;
+        self.refresh_now().await
+    }
+
+    /// Invalidates the client's refresh token on the server and forgets both tokens
+    pub async fn logout(&self) -> Result<(), ClientError> {
+        let call = Call::new(Method::POST, "/api/auth/logout")
+            .with_access_token()
+            .with_refresh_token();
+
+        self.send::<()>(call).await?;
+        self.clear_tokens();
+
+        Ok(())
+    }
+}
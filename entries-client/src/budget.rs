@@ -0,0 +1,199 @@
+use entries_common::messages::{
+    BudgetAccessTokenList, BudgetFrame, BudgetIdAndEncryptionKey, BudgetList,
+    BudgetShareInviteList, CategoryId, CategoryUpdate, EncryptedBlobAndCategoryId,
+    EncryptedBlobUpdate, EntryAndCategory, EntryId, EntryIdAndCategoryId, EntryUpdate,
+    InvitationId, NewBudget, NewEncryptedBlob, PublicKey, UserInvitationToBudget,
+};
+
+use reqwest::Method;
+
+use crate::client::{Call, Client};
+use crate::error::ClientError;
+
+// Budget routes take a budget token signed by the caller in addition to the user's access
+// token. See the `tokens` module for signing them.
+
+impl Client {
+    pub async fn get_budgets(
+        &self,
+        budget_access_tokens: &BudgetAccessTokenList,
+    ) -> Result<BudgetList, ClientError> {
+        let call = Call::new(Method::GET, "/api/budget")
+            .with_access_token()
+            .body(budget_access_tokens);
+
+        self.send(call).await
+    }
+
+    pub async fn create_budget(&self, new_budget: &NewBudget) -> Result<BudgetFrame, ClientError> {
+        let call = Call::new(Method::POST, "/api/budget")
+            .with_access_token()
+            .body(new_budget);
+
+        self.send(call).await
+    }
+
+    pub async fn edit_budget(
+        &self,
+        budget_access_token: &str,
+        update: &EncryptedBlobUpdate,
+    ) -> Result<(), ClientError> {
+        let call = Call::new(Method::PUT, "/api/budget")
+            .with_access_token()
+            .header("BudgetAccessToken", budget_access_token)
+            .body(update);
+
+        self.send(call).await
+    }
+
+    pub async fn invite_user_to_budget(
+        &self,
+        budget_access_token: &str,
+        invitation: &UserInvitationToBudget,
+    ) -> Result<InvitationId, ClientError> {
+        let call = Call::new(Method::POST, "/api/budget/invitation")
+            .with_access_token()
+            .header("BudgetAccessToken", budget_access_token)
+            .body(invitation);
+
+        self.send(call).await
+    }
+
+    pub async fn retract_budget_invitation(
+        &self,
+        invite_sender_token: &str,
+    ) -> Result<(), ClientError> {
+        let call = Call::new(Method::DELETE, "/api/budget/invitation")
+            .with_access_token()
+            .header("BudgetInviteSenderToken", invite_sender_token);
+
+        self.send(call).await
+    }
+
+    pub async fn accept_budget_invitation(
+        &self,
+        accept_token: &str,
+        budget_user_public_key: &PublicKey,
+    ) -> Result<BudgetIdAndEncryptionKey, ClientError> {
+        let call = Call::new(Method::PUT, "/api/budget/invitation/accept")
+            .with_access_token()
+            .header("BudgetAcceptToken", accept_token)
+            .body(budget_user_public_key);
+
+        self.send(call).await
+    }
+
+    pub async fn decline_budget_invitation(&self, accept_token: &str) -> Result<(), ClientError> {
+        let call = Call::new(Method::PUT, "/api/budget/invitation/decline")
+            .with_access_token()
+            .header("BudgetAcceptToken", accept_token);
+
+        self.send(call).await
+    }
+
+    pub async fn get_all_pending_budget_invitations(
+        &self,
+    ) -> Result<BudgetShareInviteList, ClientError> {
+        let call = Call::new(Method::GET, "/api/budget/invitation/all_pending").with_access_token();
+        self.send(call).await
+    }
+
+    pub async fn leave_budget(&self, budget_access_token: &str) -> Result<(), ClientError> {
+        let call = Call::new(Method::DELETE, "/api/budget/leave")
+            .with_access_token()
+            .header("BudgetAccessToken", budget_access_token);
+
+        self.send(call).await
+    }
+
+    pub async fn create_entry(
+        &self,
+        budget_access_token: &str,
+        entry: &EncryptedBlobAndCategoryId,
+    ) -> Result<EntryId, ClientError> {
+        let call = Call::new(Method::POST, "/api/budget/entry")
+            .with_access_token()
+            .header("BudgetAccessToken", budget_access_token)
+            .body(entry);
+
+        self.send(call).await
+    }
+
+    pub async fn create_entry_and_category(
+        &self,
+        budget_access_token: &str,
+        entry_and_category: &EntryAndCategory,
+    ) -> Result<EntryIdAndCategoryId, ClientError> {
+        let call = Call::new(Method::POST, "/api/budget/entry_and_category")
+            .with_access_token()
+            .header("BudgetAccessToken", budget_access_token)
+            .body(entry_and_category);
+
+        self.send(call).await
+    }
+
+    pub async fn edit_entry(
+        &self,
+        budget_access_token: &str,
+        update: &EntryUpdate,
+    ) -> Result<(), ClientError> {
+        let call = Call::new(Method::PUT, "/api/budget/entry")
+            .with_access_token()
+            .header("BudgetAccessToken", budget_access_token)
+            .body(update);
+
+        self.send(call).await
+    }
+
+    pub async fn delete_entry(
+        &self,
+        budget_access_token: &str,
+        entry_id: &EntryId,
+    ) -> Result<(), ClientError> {
+        let call = Call::new(Method::DELETE, "/api/budget/entry")
+            .with_access_token()
+            .header("BudgetAccessToken", budget_access_token)
+            .body(entry_id);
+
+        self.send(call).await
+    }
+
+    pub async fn create_category(
+        &self,
+        budget_access_token: &str,
+        category: &NewEncryptedBlob,
+    ) -> Result<CategoryId, ClientError> {
+        let call = Call::new(Method::POST, "/api/budget/category")
+            .with_access_token()
+            .header("BudgetAccessToken", budget_access_token)
+            .body(category);
+
+        self.send(call).await
+    }
+
+    pub async fn edit_category(
+        &self,
+        budget_access_token: &str,
+        update: &CategoryUpdate,
+    ) -> Result<(), ClientError> {
+        let call = Call::new(Method::PUT, "/api/budget/category")
+            .with_access_token()
+            .header("BudgetAccessToken", budget_access_token)
+            .body(update);
+
+        self.send(call).await
+    }
+
+    pub async fn delete_category(
+        &self,
+        budget_access_token: &str,
+        category_id: &CategoryId,
+    ) -> Result<(), ClientError> {
+        let call = Call::new(Method::DELETE, "/api/budget/category")
+            .with_access_token()
+            .header("BudgetAccessToken", budget_access_token)
+            .body(category_id);
+
+        self.send(call).await
+    }
+}
@@ -0,0 +1,412 @@
+use entries_common::messages::{ErrorType, ServerErrorResponse, TokenPair};
+use entries_common::token::auth_token::AuthToken;
+use entries_common::token::Token;
+
+use prost::Message;
+use reqwest::header::{ACCEPT, CONTENT_TYPE};
+use reqwest::{Method, StatusCode, Url};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::error::{ClientError, ServerError};
+
+const PROTOBUF_CONTENT_TYPE: &str = "application/protobuf";
+
+/// Access tokens this close to expiring are refreshed before they are used. This also decides
+/// whether a `TokenExpired` error was about the access token rather than a budget token.
+const ACCESS_TOKEN_EXPIRY_MARGIN: Duration = Duration::from_secs(30);
+
+struct Session {
+    tokens: TokenPair,
+    access_token_expiration: u64,
+    // Seconds to add to the local clock to get the server's clock, as of the last token refresh
+    clock_offset: i64,
+}
+
+/// A client for the Entries App API. Once signed in, the client keeps the user's access and
+/// refresh tokens and refreshes the access token as it expires.
+pub struct Client {
+    http: reqwest::Client,
+    base_url: String,
+    app_version: Option<String>,
+    session: Mutex<Option<Session>>,
+    refresh_lock: tokio::sync::Mutex<()>,
+}
+
+impl Client {
+    /// `base_url` is the scheme and host of the server, e.g. `https://api.entries.example`.
+    pub fn new(base_url: &str) -> Result<Self, ClientError> {
+        if Url::parse(base_url).is_err() {
+            return Err(ClientError::InvalidUrl(String::from(base_url)));
+        }
+
+        Ok(Self {
+            http: reqwest::Client::new(),
+            base_url: String::from(base_url.trim_end_matches('/')),
+            app_version: None,
+            session: Mutex::new(None),
+            refresh_lock: tokio::sync::Mutex::new(()),
+        })
+    }
+
+    /// Uses an existing `reqwest::Client`, e.g. one with custom timeouts or TLS settings
+    pub fn with_http_client(mut self, http: reqwest::Client) -> Self {
+        self.http = http;
+        self
+    }
+
+    /// Sends an `AppVersion` header with every request, in the `<platform>/<version>` format
+    /// the server checks against its minimum client versions
+    pub fn with_app_version(mut self, app_version: &str) -> Self {
+        self.app_version = Some(String::from(app_version));
+        self
+    }
+
+    /// Succeeds if the server is up
+    pub async fn heartbeat(&self) -> Result<(), ClientError> {
+        self.send_ignoring_body(Call::new(Method::GET, "/api/heartbeat"))
+            .await
+    }
+
+    /// The current access and refresh tokens, for persisting a session between runs
+    pub fn tokens(&self) -> Option<TokenPair> {
+        self.session
+            .lock()
+            .expect("Session lock was poisoned")
+            .as_ref()
+            .map(|session| session.tokens.clone())
+    }
+
+    /// Resumes a session using tokens obtained earlier
+    pub fn set_tokens(&self, tokens: TokenPair) {
+        let access_token_expiration = AuthToken::decode(&tokens.access_token)
+            .map(|token| token.claims.expiration)
+            .unwrap_or(0);
+
+        let now = unix_secs(SystemTime::now());
+        let server_now = unix_secs((&tokens.server_time).into());
+
+        *self.session.lock().expect("Session lock was poisoned") = Some(Session {
+            tokens,
+            access_token_expiration,
+            clock_offset: server_now as i64 - now as i64,
+        });
+    }
+
+    pub fn clear_tokens(&self) {
+        *self.session.lock().expect("Session lock was poisoned") = None;
+    }
+
+    fn refresh_token(&self) -> Result<String, ClientError> {
+        self.session
+            .lock()
+            .expect("Session lock was poisoned")
+            .as_ref()
+            .map(|session| session.tokens.refresh_token.clone())
+            .ok_or(ClientError::NotSignedIn)
+    }
+
+    /// Returns the access token and whether it has expired or is about to
+    fn access_token(&self) -> Result<(String, bool), ClientError> {
+        let session = self.session.lock().expect("Session lock was poisoned");
+        let session = session.as_ref().ok_or(ClientError::NotSignedIn)?;
+
+        let server_now = unix_secs(SystemTime::now()) as i64 + session.clock_offset;
+        let expiring = session.access_token_expiration as i64
+            <= server_now + ACCESS_TOKEN_EXPIRY_MARGIN.as_secs() as i64;
+
+        Ok((session.tokens.access_token.clone(), expiring))
+    }
+
+    /// Sends a call to the server and decodes the response. Calls that require an access
+    /// token refresh it when it has expired and are retried once if the server says it has.
+    pub(crate) async fn send<M>(&self, call: Call) -> Result<M, ClientError>
+    where
+        M: Message + Default,
+    {
+        let resp = self.send_with_refresh(call).await?;
+        Ok(M::decode(resp)?)
+    }
+
+    /// Like `send`, but ignores the body of a successful response. This is for the routes
+    /// that don't respond with messages, like the ones linked to from emails.
+    pub(crate) async fn send_ignoring_body(&self, call: Call) -> Result<(), ClientError> {
+        self.send_with_refresh(call).await?;
+        Ok(())
+    }
+
+    async fn send_with_refresh(&self, call: Call) -> Result<bytes::Bytes, ClientError> {
+        if !call.access_token {
+            return self.send_once(&call, None).await;
+        }
+
+        let (mut access_token, expiring) = self.access_token()?;
+
+        if expiring {
+            access_token = self.refresh_expired(&access_token).await?;
+        }
+
+        match self.send_once(&call, Some(&access_token)).await {
+            Err(ClientError::Server(e)) if e.err_type == ErrorType::TokenExpired => {
+                // Budget tokens expire too, and refreshing won't help with those
+                let (current_token, expiring) = self.access_token()?;
+                if current_token == access_token && !expiring {
+                    return Err(ClientError::Server(e));
+                }
+
+                let access_token = self.refresh_expired(&access_token).await?;
+                self.send_once(&call, Some(&access_token)).await
+            }
+            resp => resp,
+        }
+    }
+
+    /// Refreshes the tokens unless another call already replaced `expired_access_token`, and
+    /// returns the new access token
+    async fn refresh_expired(&self, expired_access_token: &str) -> Result<String, ClientError> {
+        let _guard = self.refresh_lock.lock().await;
+
+        let (current_token, _) = self.access_token()?;
+        if current_token != expired_access_token {
+            return Ok(current_token);
+        }
+
+        let tokens = self.refresh_now().await?;
+        Ok(tokens.access_token)
+    }
+
+    /// Exchanges the refresh token for new tokens. Callers must hold `refresh_lock`.
+    pub(crate) async fn refresh_now(&self) -> Result<TokenPair, ClientError> {
+        let call = Call::new(Method::POST, "/api/auth/token/refresh").with_refresh_token();
+
+        let tokens = TokenPair::decode(self.send_once(&call, None).await?)?;
+        self.set_tokens(tokens.clone());
+
+        Ok(tokens)
+    }
+
+    pub(crate) async fn lock_refresh(&self) -> tokio::sync::MutexGuard<'_, ()> {
+        self.refresh_lock.lock().await
+    }
+
+    async fn send_once(
+        &self,
+        call: &Call,
+        access_token: Option<&str>,
+    ) -> Result<bytes::Bytes, ClientError> {
+        let mut req = self
+            .http
+            .request(
+                call.method.clone(),
+                format!("{}{}", self.base_url, call.path),
+            )
+            .header(ACCEPT, PROTOBUF_CONTENT_TYPE);
+
+        if !call.query.is_empty() {
+            req = req.query(&call.query);
+        }
+
+        if let Some(app_version) = &self.app_version {
+            req = req.header("AppVersion", app_version);
+        }
+
+        if let Some(access_token) = access_token {
+            req = req.header("AccessToken", access_token);
+        }
+
+        if call.refresh_token {
+            req = req.header("RefreshToken", self.refresh_token()?);
+        }
+
+        for (name, value) in &call.headers {
+            req = req.header(*name, value);
+        }
+
+        if let Some(body) = &call.body {
+            req = req
+                .header(CONTENT_TYPE, PROTOBUF_CONTENT_TYPE)
+                .body(body.clone());
+        }
+
+        let resp = req.send().await?;
+        let status = resp.status();
+        let body = resp.bytes().await?;
+
+        if status.is_success() {
+            return Ok(body);
+        }
+
+        Err(error_from_response(status, &body))
+    }
+}
+
+fn error_from_response(status: StatusCode, body: &[u8]) -> ClientError {
+    match ServerErrorResponse::decode(body) {
+        Ok(resp) => ClientError::Server(Box::new(ServerError::from_response(status, resp))),
+        Err(_) => ClientError::UnexpectedResponse(status),
+    }
+}
+
+fn unix_secs(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// A request to one of the server's routes
+pub(crate) struct Call {
+    method: Method,
+    path: String,
+    query: Vec<(&'static str, String)>,
+    headers: Vec<(&'static str, String)>,
+    body: Option<Vec<u8>>,
+    access_token: bool,
+    refresh_token: bool,
+}
+
+impl Call {
+    pub(crate) fn new(method: Method, path: &str) -> Self {
+        Self {
+            method,
+            path: String::from(path),
+            query: Vec::new(),
+            headers: Vec::new(),
+            body: None,
+            access_token: false,
+            refresh_token: false,
+        }
+    }
+
+    pub(crate) fn body<M: Message>(mut self, message: &M) -> Self {
+        self.body = Some(message.encode_to_vec());
+        self
+    }
+
+    pub(crate) fn header(mut self, name: &'static str, value: &str) -> Self {
+        self.headers.push((name, String::from(value)));
+        self
+    }
+
+    pub(crate) fn query(mut self, key: &'static str, value: &str) -> Self {
+        self.query.push((key, String::from(value)));
+        self
+    }
+
+    /// Sends the user's access token with the call
+    pub(crate) fn with_access_token(mut self) -> Self {
+        self.access_token = true;
+        self
+    }
+
+    /// Sends the user's refresh token with the call
+    pub(crate) fn with_refresh_token(mut self) -> Self {
+        self.refresh_token = true;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use entries_common::messages::Timestamp;
+    use uuid::Uuid;
+
+    use crate::error::ErrorDetails;
+
+    #[test]
+    fn test_error_from_response() {
+        let resp = ServerErrorResponse {
+            err_type: ErrorType::BudgetDoesNotExist as i32,
+            err_message: String::from("Budget not found"),
+            request_id: Some(String::from("abc")),
+            ..Default::default()
+        };
+
+        let err = error_from_response(StatusCode::NOT_FOUND, &resp.encode_to_vec());
+        let ClientError::Server(err) = err else {
+            panic!("Expected a server error, got {err:?}");
+        };
+
+        assert_eq!(err.status, StatusCode::NOT_FOUND);
+        assert_eq!(err.err_type, ErrorType::BudgetDoesNotExist);
+        assert_eq!(err.message, "Budget not found");
+        assert_eq!(err.request_id.as_deref(), Some("abc"));
+        assert_eq!(err.details, ErrorDetails::default());
+
+        let conflicting_id = Uuid::now_v7();
+        let resp = ServerErrorResponse {
+            err_type: ErrorType::ConflictWithExisting as i32,
+            err_message: String::from("Entry already exists"),
+            conflicting_id: Some(conflicting_id.into()),
+            ..Default::default()
+        };
+
+        let err = error_from_response(StatusCode::BAD_REQUEST, &resp.encode_to_vec());
+        assert_eq!(err.details().unwrap().conflicting_id, Some(conflicting_id));
+
+        let resp = ServerErrorResponse {
+            err_type: ErrorType::TooManyAttempts as i32,
+            err_message: String::from("Too many requests"),
+            retry_after_secs: Some(30),
+            ..Default::default()
+        };
+
+        let err = error_from_response(StatusCode::TOO_MANY_REQUESTS, &resp.encode_to_vec());
+        assert_eq!(
+            err.details().unwrap().retry_after,
+            Some(Duration::from_secs(30))
+        );
+
+        let resp = ServerErrorResponse {
+            err_type: 9999,
+            err_message: String::from("From the future"),
+            request_id: None,
+            ..Default::default()
+        };
+
+        let err = error_from_response(StatusCode::BAD_REQUEST, &resp.encode_to_vec());
+        assert_eq!(err.err_type(), Some(ErrorType::ActixWebPrehandler));
+
+        let err = error_from_response(StatusCode::BAD_GATEWAY, b"<html>Bad Gateway</html>");
+        assert!(matches!(
+            err,
+            ClientError::UnexpectedResponse(StatusCode::BAD_GATEWAY)
+        ));
+    }
+
+    #[test]
+    fn test_access_token_expiry_uses_server_clock() {
+        let client = Client::new("http://localhost:9000").unwrap();
+        assert!(matches!(
+            client.access_token(),
+            Err(ClientError::NotSignedIn)
+        ));
+
+        let now = SystemTime::now();
+
+        // The server's clock is an hour behind the local clock, so this token expires in ten
+        // seconds
+        let access_token = crate::tests::fake_auth_token(now - Duration::from_secs(3590));
+        client.set_tokens(TokenPair {
+            access_token: access_token.clone(),
+            refresh_token: String::from("refresh"),
+            server_time: Timestamp::try_from(now - Duration::from_secs(3600)).unwrap(),
+        });
+
+        assert_eq!(client.access_token().unwrap(), (access_token, true));
+
+        // And this one is good for another ten minutes
+        let access_token = crate::tests::fake_auth_token(now - Duration::from_secs(3000));
+        client.set_tokens(TokenPair {
+            access_token: access_token.clone(),
+            refresh_token: String::from("refresh"),
+            server_time: Timestamp::try_from(now - Duration::from_secs(3600)).unwrap(),
+        });
+
+        assert_eq!(client.access_token().unwrap(), (access_token, false));
+
+        client.clear_tokens();
+        assert!(client.tokens().is_none());
+    }
+}
@@ -0,0 +1,139 @@
+use entries_common::messages::{ErrorType, ServerErrorResponse};
+
+use reqwest::StatusCode;
+use std::time::Duration;
+use uuid::Uuid;
+
+/// An error the server responded with
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ServerError {
+    pub status: StatusCode,
+    pub err_type: ErrorType,
+    pub message: String,
+    pub request_id: Option<String>,
+    pub details: ErrorDetails,
+}
+
+/// Details the server includes with some error types so they don't have to be parsed from the
+/// message. Servers that predate a field leave it unset.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ErrorDetails {
+    /// The field that was too large, or `None` if the whole request body was
+    pub field: Option<String>,
+    pub max_size: Option<u64>,
+    pub retry_after: Option<Duration>,
+    pub conflicting_id: Option<Uuid>,
+    pub current_version_nonce: Option<i64>,
+}
+
+impl ServerError {
+    pub(crate) fn from_response(status: StatusCode, resp: ServerErrorResponse) -> Self {
+        // An error type from a newer server than this client knows about is reported the same
+        // way as errors actix-web returns before reaching a handler
+        let err_type = ErrorType::try_from(resp.err_type).unwrap_or(ErrorType::ActixWebPrehandler);
+
+        let details = ErrorDetails {
+            field: resp.field,
+            max_size: resp.max_size,
+            retry_after: resp.retry_after_secs.map(Duration::from_secs),
+            conflicting_id: resp.conflicting_id.and_then(|id| Uuid::try_from(id).ok()),
+            current_version_nonce: resp.current_version_nonce,
+        };
+
+        Self {
+            status,
+            err_type,
+            message: resp.err_message,
+            request_id: resp.request_id,
+            details,
+        }
+    }
+}
+
+impl std::fmt::Display for ServerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} ({}): {}",
+            self.err_type.as_str_name(),
+            self.status,
+            self.message
+        )?;
+
+        if let Some(request_id) = &self.request_id {
+            write!(f, " [request {request_id}]")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub enum ClientError {
+    /// The server responded with a `ServerErrorResponse`
+    Server(Box<ServerError>),
+    /// The server responded with an error status but no `ServerErrorResponse`, as proxies do
+    UnexpectedResponse(StatusCode),
+    /// The request failed to reach the server or the response failed to arrive
+    Http(reqwest::Error),
+    /// The server's response body is not the expected message
+    Decode(prost::DecodeError),
+    /// The route requires the client to be signed in
+    NotSignedIn,
+    InvalidUrl(String),
+    Argon2(argon2_kdf::Argon2Error),
+}
+
+impl ClientError {
+    /// The `ErrorType` the server responded with, if any
+    pub fn err_type(&self) -> Option<ErrorType> {
+        match self {
+            ClientError::Server(e) => Some(e.err_type),
+            _ => None,
+        }
+    }
+
+    /// The details the server responded with, if any
+    pub fn details(&self) -> Option<&ErrorDetails> {
+        match self {
+            ClientError::Server(e) => Some(&e.details),
+            _ => None,
+        }
+    }
+}
+
+impl std::error::Error for ClientError {}
+
+impl std::fmt::Display for ClientError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ClientError::Server(e) => write!(f, "Server error: {e}"),
+            ClientError::UnexpectedResponse(status) => {
+                write!(f, "Unexpected response from server: {status}")
+            }
+            ClientError::Http(e) => write!(f, "HTTP error: {e}"),
+            ClientError::Decode(e) => write!(f, "Failed to decode response: {e}"),
+            ClientError::NotSignedIn => write!(f, "Not signed in"),
+            ClientError::InvalidUrl(url) => write!(f, "Invalid URL: {url}"),
+            ClientError::Argon2(e) => write!(f, "Failed to derive auth string: {e}"),
+        }
+    }
+}
+
+impl From<reqwest::Error> for ClientError {
+    fn from(e: reqwest::Error) -> Self {
+        ClientError::Http(e)
+    }
+}
+
+impl From<prost::DecodeError> for ClientError {
+    fn from(e: prost::DecodeError) -> Self {
+        ClientError::Decode(e)
+    }
+}
+
+impl From<argon2_kdf::Argon2Error> for ClientError {
+    fn from(e: argon2_kdf::Argon2Error) -> Self {
+        ClientError::Argon2(e)
+    }
+}
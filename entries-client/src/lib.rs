@@ -0,0 +1,192 @@
+//! A typed async client for the Entries App API.
+//!
+//! ```ignore
+//! let client = Client::new("https://api.entries.example")?.with_app_version("ios/1.4.0");
+//!
+//! let signin_token = client.sign_in(email, password).await?;
+//! client.verify_otp(&signin_token, &otp_from_email).await?;
+//!
+//! let token = tokens::sign_budget_access_token(key_id, budget_id, &signing_key, lifetime);
+//! let entry_id = client.create_entry(&token, &entry).await?;
+//! ```
+
+mod auth;
+mod budget;
+mod client;
+mod error;
+pub mod tokens;
+mod user;
+
+pub use client::Client;
+pub use entries_common::messages;
+pub use error::{ClientError, ErrorDetails, ServerError};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use entries_common::messages::{EntryId, ErrorType, ServerErrorResponse, TokenPair};
+    use entries_common::token::auth_token::{AuthToken, AuthTokenType, NewAuthTokenClaims};
+
+    use actix_web::http::StatusCode as ActixStatusCode;
+    use actix_web::web::{self, Data};
+    use actix_web::{App, HttpRequest, HttpResponse, HttpServer};
+    use prost::Message;
+    use reqwest::StatusCode;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+    use uuid::Uuid;
+
+    pub fn fake_auth_token(expiration: SystemTime) -> String {
+        AuthToken::sign_new(
+            NewAuthTokenClaims {
+                user_id: Uuid::now_v7(),
+                user_email: "test@test.com",
+                expiration: expiration.duration_since(UNIX_EPOCH).unwrap().as_secs(),
+                token_type: AuthTokenType::Access,
+            },
+            b"not the server's key",
+        )
+    }
+
+    fn token_pair(access_token: &str, refresh_token: &str) -> TokenPair {
+        TokenPair {
+            access_token: String::from(access_token),
+            refresh_token: String::from(refresh_token),
+            server_time: SystemTime::now().try_into().unwrap(),
+        }
+    }
+
+    fn error_response(status: ActixStatusCode, err_type: ErrorType) -> HttpResponse {
+        let resp = ServerErrorResponse {
+            err_type: err_type as i32,
+            err_message: String::from(err_type.as_str_name()),
+            ..Default::default()
+        };
+
+        HttpResponse::build(status)
+            .content_type("application/protobuf")
+            .body(resp.encode_to_vec())
+    }
+
+    struct FakeServer {
+        refreshes: AtomicUsize,
+        access_token: Mutex<String>,
+    }
+
+    async fn refresh(server: Data<FakeServer>, req: HttpRequest) -> HttpResponse {
+        if req.headers().get("RefreshToken").unwrap() != "refresh" {
+            return error_response(
+                ActixStatusCode::UNAUTHORIZED,
+                ErrorType::IncorrectCredential,
+            );
+        }
+
+        server.refreshes.fetch_add(1, Ordering::SeqCst);
+
+        let access_token = fake_auth_token(SystemTime::now() + Duration::from_secs(600));
+        *server.access_token.lock().unwrap() = access_token.clone();
+
+        HttpResponse::Ok().body(token_pair(&access_token, "new refresh").encode_to_vec())
+    }
+
+    async fn delete_entry(
+        server: Data<FakeServer>,
+        req: HttpRequest,
+        body: web::Bytes,
+    ) -> HttpResponse {
+        if req.headers().get("AccessToken").unwrap().to_str().unwrap()
+            != *server.access_token.lock().unwrap()
+        {
+            return error_response(ActixStatusCode::UNAUTHORIZED, ErrorType::TokenExpired);
+        }
+
+        // Stands in for an expired budget token
+        if req.headers().get("BudgetAccessToken").unwrap() == "expired" {
+            return error_response(ActixStatusCode::UNAUTHORIZED, ErrorType::TokenExpired);
+        }
+
+        if EntryId::decode(body).is_err() {
+            return error_response(ActixStatusCode::BAD_REQUEST, ErrorType::InvalidMessage);
+        }
+
+        HttpResponse::Ok().finish()
+    }
+
+    async fn start_fake_server(access_token: &str) -> (String, Data<FakeServer>) {
+        let server = Data::new(FakeServer {
+            refreshes: AtomicUsize::new(0),
+            access_token: Mutex::new(String::from(access_token)),
+        });
+
+        let server_data = server.clone();
+        let http_server = HttpServer::new(move || {
+            App::new()
+                .app_data(server_data.clone())
+                .route("/api/auth/token/refresh", web::post().to(refresh))
+                .route("/api/budget/entry", web::delete().to(delete_entry))
+        })
+        .workers(1)
+        .bind(("127.0.0.1", 0))
+        .unwrap();
+
+        let addr = http_server.addrs()[0];
+        actix_web::rt::spawn(http_server.run());
+
+        (format!("http://{addr}"), server)
+    }
+
+    #[actix_web::test]
+    async fn test_refreshes_expired_access_token() {
+        let expired_token = fake_auth_token(SystemTime::now() - Duration::from_secs(60));
+        let (url, server) = start_fake_server(&expired_token).await;
+
+        let entry_id = EntryId {
+            value: Uuid::now_v7().into(),
+        };
+
+        let client = Client::new(&url).unwrap();
+        assert!(matches!(
+            client.delete_entry("budget", &entry_id).await,
+            Err(ClientError::NotSignedIn)
+        ));
+
+        client.set_tokens(token_pair(&expired_token, "refresh"));
+        client.delete_entry("budget", &entry_id).await.unwrap();
+
+        assert_eq!(server.refreshes.load(Ordering::SeqCst), 1);
+
+        let tokens = client.tokens().unwrap();
+        assert_eq!(tokens.access_token, *server.access_token.lock().unwrap());
+        assert_eq!(tokens.refresh_token, "new refresh");
+
+        // The new access token is used without refreshing again
+        client.delete_entry("budget", &entry_id).await.unwrap();
+        assert_eq!(server.refreshes.load(Ordering::SeqCst), 1);
+    }
+
+    #[actix_web::test]
+    async fn test_expired_budget_token_does_not_refresh() {
+        let access_token = fake_auth_token(SystemTime::now() + Duration::from_secs(600));
+        let (url, server) = start_fake_server(&access_token).await;
+
+        let client = Client::new(&url).unwrap();
+        client.set_tokens(token_pair(&access_token, "refresh"));
+
+        let entry_id = EntryId {
+            value: Uuid::now_v7().into(),
+        };
+
+        let err = client.delete_entry("expired", &entry_id).await.unwrap_err();
+
+        let ClientError::Server(err) = err else {
+            panic!("Expected a server error, got {err:?}");
+        };
+
+        assert_eq!(err.status, StatusCode::UNAUTHORIZED);
+        assert_eq!(err.err_type, ErrorType::TokenExpired);
+        assert_eq!(server.refreshes.load(Ordering::SeqCst), 0);
+        assert_eq!(client.tokens().unwrap().access_token, access_token);
+    }
+}
@@ -0,0 +1,176 @@
+use entries_common::messages::SigninNonceAndHashParams;
+use entries_common::token::budget_accept_token::{BudgetAcceptToken, BudgetAcceptTokenClaims};
+use entries_common::token::budget_access_token::{BudgetAccessToken, BudgetAccessTokenClaims};
+use entries_common::token::budget_invite_sender_token::{
+    BudgetInviteSenderToken, BudgetInviteSenderTokenClaims,
+};
+
+use ed25519_dalek as ed25519;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use uuid::Uuid;
+
+use crate::error::ClientError;
+
+/// Parameters for deriving an auth string from a password with Argon2id. The server hands
+/// these back at sign-in so a client on any device can derive the same auth string.
+#[derive(Clone, Copy, Debug)]
+pub struct AuthStringParams<'a> {
+    pub salt: &'a [u8],
+    pub memory_cost_kib: u32,
+    pub parallelism_factor: u32,
+    pub iters: u32,
+}
+
+impl<'a> From<&'a SigninNonceAndHashParams> for AuthStringParams<'a> {
+    fn from(params: &'a SigninNonceAndHashParams) -> Self {
+        Self {
+            salt: &params.auth_string_salt,
+            memory_cost_kib: params.auth_string_memory_cost_kib as u32,
+            parallelism_factor: params.auth_string_parallelism_factor as u32,
+            iters: params.auth_string_iters as u32,
+        }
+    }
+}
+
+/// Derives the auth string that is sent to the server in place of the user's password.
+/// Hashing is CPU-bound, so async callers should run this off of the runtime's worker threads.
+pub fn derive_auth_string(
+    password: &str,
+    params: AuthStringParams,
+) -> Result<Vec<u8>, ClientError> {
+    let hash = argon2_kdf::Hasher::default()
+        .algorithm(argon2_kdf::Algorithm::Argon2id)
+        .custom_salt(params.salt)
+        .memory_cost_kib(params.memory_cost_kib)
+        .threads(params.parallelism_factor)
+        .iterations(params.iters)
+        .hash(password.as_bytes())?;
+
+    Ok(Vec::from(hash.as_bytes()))
+}
+
+/// Signs a `BudgetAccessToken` with the user's private key for the budget
+pub fn sign_budget_access_token(
+    key_id: Uuid,
+    budget_id: Uuid,
+    signing_key: &ed25519::SigningKey,
+    lifetime: Duration,
+) -> String {
+    BudgetAccessToken::sign_new(
+        BudgetAccessTokenClaims {
+            key_id,
+            budget_id,
+            expiration: expiration(lifetime),
+        },
+        signing_key,
+    )
+}
+
+/// Signs a `BudgetAcceptToken` with the private key the server sent along with the invitation
+pub fn sign_budget_accept_token(
+    invite_id: Uuid,
+    key_id: Uuid,
+    budget_id: Uuid,
+    signing_key: &ed25519::SigningKey,
+    lifetime: Duration,
+) -> String {
+    BudgetAcceptToken::sign_new(
+        BudgetAcceptTokenClaims {
+            invite_id,
+            key_id,
+            budget_id,
+            expiration: expiration(lifetime),
+        },
+        signing_key,
+    )
+}
+
+/// Signs a `BudgetInviteSenderToken` with the private key the sender generated for the
+/// invitation
+pub fn sign_budget_invite_sender_token(
+    invite_id: Uuid,
+    signing_key: &ed25519::SigningKey,
+    lifetime: Duration,
+) -> String {
+    BudgetInviteSenderToken::sign_new(
+        BudgetInviteSenderTokenClaims {
+            invite_id,
+            expiration: expiration(lifetime),
+        },
+        signing_key,
+    )
+}
+
+fn expiration(lifetime: Duration) -> u64 {
+    (SystemTime::now() + lifetime)
+        .duration_since(UNIX_EPOCH)
+        .expect("System time should be after Unix Epoch")
+        .as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use entries_common::token::Token;
+
+    #[test]
+    fn test_sign_budget_tokens() {
+        let signing_key = ed25519::SigningKey::generate(&mut rand::rngs::OsRng);
+        let public_key = signing_key.verifying_key().to_bytes();
+
+        let key_id = Uuid::now_v7();
+        let budget_id = Uuid::now_v7();
+        let invite_id = Uuid::now_v7();
+
+        let token =
+            sign_budget_access_token(key_id, budget_id, &signing_key, Duration::from_secs(60));
+        let decoded = BudgetAccessToken::decode(&token).unwrap();
+        let claims = decoded.verify(&public_key).unwrap();
+        assert_eq!(claims.key_id, key_id);
+        assert_eq!(claims.budget_id, budget_id);
+
+        let token = sign_budget_accept_token(
+            invite_id,
+            key_id,
+            budget_id,
+            &signing_key,
+            Duration::from_secs(60),
+        );
+        let decoded = BudgetAcceptToken::decode(&token).unwrap();
+        let claims = decoded.verify(&public_key).unwrap();
+        assert_eq!(claims.invite_id, invite_id);
+        assert_eq!(claims.key_id, key_id);
+        assert_eq!(claims.budget_id, budget_id);
+
+        let token = sign_budget_invite_sender_token(invite_id, &signing_key, Duration::ZERO);
+        let decoded = BudgetInviteSenderToken::decode(&token).unwrap();
+        assert_eq!(decoded.claims.invite_id, invite_id);
+        assert!(decoded.verify(&public_key).is_err());
+    }
+
+    #[test]
+    fn test_derive_auth_string() {
+        let salt = [7; 16];
+        let params = AuthStringParams {
+            salt: &salt,
+            memory_cost_kib: 128,
+            parallelism_factor: 1,
+            iters: 2,
+        };
+
+        let auth_string = derive_auth_string("password", params).unwrap();
+
+        // Matches how the auth string was created, so the same password gives the same bytes
+        let expected = argon2_kdf::Hasher::new()
+            .custom_salt(&salt)
+            .iterations(2)
+            .memory_cost_kib(128)
+            .threads(1)
+            .hash(b"password")
+            .unwrap();
+
+        assert_eq!(auth_string, expected.as_bytes());
+        assert_ne!(auth_string, derive_auth_string("passw0rd", params).unwrap());
+    }
+}
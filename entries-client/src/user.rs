@@ -0,0 +1,124 @@
+use entries_common::messages::{
+    AuthStringAndEncryptedPasswordUpdate, BackupCodesAndVerificationEmailSent,
+    BudgetAccessTokenList, EncryptedBlob, EncryptedBlobUpdate, IsUserListedForDeletion, NewUser,
+    NewUserPublicKey, RecoveryKeyUpdate, UserBootstrap, UserPublicKey, VerificationEmailSent,
+};
+
+use reqwest::Method;
+
+use crate::client::{Call, Client};
+use crate::error::ClientError;
+
+impl Client {
+    pub async fn create_user(
+        &self,
+        new_user: &NewUser,
+    ) -> Result<BackupCodesAndVerificationEmailSent, ClientError> {
+        let call = Call::new(Method::POST, "/api/user").body(new_user);
+        self.send(call).await
+    }
+
+    /// Follows the link in the verification email sent when the user was created
+    pub async fn verify_user_creation(&self, user_creation_token: &str) -> Result<(), ClientError> {
+        // The server reads tokens from the query string as-is, so they can't be percent-encoded
+        let path = format!("/api/user/verify?UserCreationToken={user_creation_token}");
+        self.send_ignoring_body(Call::new(Method::GET, &path)).await
+    }
+
+    pub async fn lookup_user_public_key(&self, email: &str) -> Result<UserPublicKey, ClientError> {
+        let call = Call::new(Method::GET, "/api/user/public_key")
+            .with_access_token()
+            .query("email", email);
+
+        self.send(call).await
+    }
+
+    pub async fn rotate_user_public_key(
+        &self,
+        new_key: &NewUserPublicKey,
+    ) -> Result<(), ClientError> {
+        let call = Call::new(Method::PUT, "/api/user/public_key")
+            .with_access_token()
+            .body(new_key);
+
+        self.send(call).await
+    }
+
+    /// Fetches everything a new device needs from the user's account
+    pub async fn get_bootstrap(&self) -> Result<UserBootstrap, ClientError> {
+        let call = Call::new(Method::GET, "/api/user/bootstrap").with_access_token();
+        self.send(call).await
+    }
+
+    pub async fn get_preferences(&self) -> Result<EncryptedBlob, ClientError> {
+        let call = Call::new(Method::GET, "/api/user/preferences").with_access_token();
+        self.send(call).await
+    }
+
+    pub async fn get_keystore(&self) -> Result<EncryptedBlob, ClientError> {
+        let call = Call::new(Method::GET, "/api/user/keystore").with_access_token();
+        self.send(call).await
+    }
+
+    pub async fn edit_preferences(&self, update: &EncryptedBlobUpdate) -> Result<(), ClientError> {
+        let call = Call::new(Method::PUT, "/api/user/preferences")
+            .with_access_token()
+            .body(update);
+
+        self.send(call).await
+    }
+
+    pub async fn edit_keystore(&self, update: &EncryptedBlobUpdate) -> Result<(), ClientError> {
+        let call = Call::new(Method::PUT, "/api/user/keystore")
+            .with_access_token()
+            .body(update);
+
+        self.send(call).await
+    }
+
+    /// The OTP in the update stands in for an access token, so this works while signed out
+    pub async fn change_password(
+        &self,
+        update: &AuthStringAndEncryptedPasswordUpdate,
+    ) -> Result<(), ClientError> {
+        let call = Call::new(Method::PUT, "/api/user/password").body(update);
+        self.send(call).await
+    }
+
+    pub async fn change_recovery_key(&self, update: &RecoveryKeyUpdate) -> Result<(), ClientError> {
+        let call = Call::new(Method::PUT, "/api/user/recovery_key")
+            .with_access_token()
+            .body(update);
+
+        self.send(call).await
+    }
+
+    /// Starts deleting the user. `budget_access_tokens` must cover every budget the user
+    /// belongs to. The user confirms the deletion through an emailed link.
+    pub async fn init_user_deletion(
+        &self,
+        budget_access_tokens: &BudgetAccessTokenList,
+    ) -> Result<VerificationEmailSent, ClientError> {
+        let call = Call::new(Method::DELETE, "/api/user")
+            .with_access_token()
+            .body(budget_access_tokens);
+
+        self.send(call).await
+    }
+
+    /// Follows the link in the email sent by `init_user_deletion`
+    pub async fn verify_user_deletion(&self, user_deletion_token: &str) -> Result<(), ClientError> {
+        let path = format!("/api/user/deletion/verify?UserDeletionToken={user_deletion_token}");
+        self.send_ignoring_body(Call::new(Method::GET, &path)).await
+    }
+
+    pub async fn is_listed_for_deletion(&self) -> Result<IsUserListedForDeletion, ClientError> {
+        let call = Call::new(Method::GET, "/api/user/deletion").with_access_token();
+        self.send(call).await
+    }
+
+    pub async fn cancel_user_deletion(&self) -> Result<(), ClientError> {
+        let call = Call::new(Method::DELETE, "/api/user/deletion").with_access_token();
+        self.send(call).await
+    }
+}
@@ -0,0 +1,210 @@
+//! Compares the throughput of the blocking DAOs, run on Tokio's blocking thread pool the way the
+//! server used to run them, with the async DAOs on the budget fetch and entry edit paths.
+//!
+//! Run with `cargo bench -p entries_common --bench dao_throughput`. The database is configured
+//! with the same `ENTRIES_DB_*` variables the server uses, and the following optional variables:
+//!
+//! * `ENTRIES_BENCH_CONCURRENCY` - Number of requests in flight at once (default 64)
+//! * `ENTRIES_BENCH_REQUESTS` - Number of requests per run (default 5000)
+//! * `ENTRIES_BENCH_POOL_SIZE` - Max connections in each pool (default 16)
+
+use entries_common::config::ConfigSource;
+use entries_common::db::budget::BudgetDao;
+use entries_common::db::nonblocking::budget::AsyncBudgetDao;
+use entries_common::db::{self, DbThreadPool};
+use entries_common::messages::CategoryWithTempId;
+
+use futures::stream::{self, StreamExt};
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+const ENTRY_COUNT: usize = 100;
+
+struct Fixture {
+    budget_id: Uuid,
+    key_id: Uuid,
+}
+
+fn main() {
+    let source = ConfigSource::env_only();
+    let db_uri = format!(
+        "postgres://{}:{}@{}:{}/{}",
+        source.get::<String>("ENTRIES_DB_USERNAME").unwrap(),
+        source.get_secret::<String>("ENTRIES_DB_PASSWORD").unwrap(),
+        source.get::<String>("ENTRIES_DB_HOSTNAME").unwrap(),
+        source.get::<u16>("ENTRIES_DB_PORT").unwrap(),
+        source.get::<String>("ENTRIES_DB_NAME").unwrap(),
+    );
+    let concurrency = source.get_or("ENTRIES_BENCH_CONCURRENCY", 64).unwrap();
+    let request_count = source.get_or("ENTRIES_BENCH_REQUESTS", 5000).unwrap();
+    let pool_size = source.get_or("ENTRIES_BENCH_POOL_SIZE", 16).unwrap();
+
+    // A small blocking pool mimics a server whose blocking threads are contended
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .max_blocking_threads(pool_size as usize)
+        .enable_all()
+        .build()
+        .unwrap();
+
+    let db_thread_pool =
+        db::create_db_thread_pool(&db_uri, pool_size, Duration::from_secs(30), None);
+    let async_db_pool = db::create_async_db_pool(&db_uri, pool_size, None);
+
+    // r2d2 opens its connections up front but deadpool opens them on demand, so open them all
+    // before timing anything
+    runtime.block_on(async {
+        let connections = futures::future::join_all((0..pool_size).map(|_| async_db_pool.get()))
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        drop(connections);
+    });
+
+    let async_db_pools = db::AsyncDbPools::from(async_db_pool);
+
+    let fixture = Arc::new(create_fixture(&db_thread_pool));
+
+    println!("{request_count} requests, {concurrency} concurrent, {pool_size} connections\n");
+
+    let fetch_blocking = {
+        let db_thread_pool = db_thread_pool.clone();
+        let fixture = Arc::clone(&fixture);
+        runtime.block_on(run(concurrency, request_count, move |_| {
+            let db_thread_pool = db_thread_pool.clone();
+            let budget_id = fixture.budget_id;
+            async move {
+                tokio::task::spawn_blocking(move || {
+                    db::budget::Dao::new(&db_thread_pool)
+                        .get_budget(budget_id)
+                        .unwrap()
+                })
+                .await
+                .unwrap();
+            }
+        }))
+    };
+    report("Budget fetch (blocking)", request_count, fetch_blocking);
+
+    let fetch_async = {
+        let async_db_pools = async_db_pools.clone();
+        let fixture = Arc::clone(&fixture);
+        runtime.block_on(run(concurrency, request_count, move |_| {
+            let async_db_pools = async_db_pools.clone();
+            let budget_id = fixture.budget_id;
+            async move {
+                db::nonblocking::budget::Dao::new(&async_db_pools)
+                    .get_budget(budget_id)
+                    .await
+                    .unwrap();
+            }
+        }))
+    };
+    report("Budget fetch (async)", request_count, fetch_async);
+
+    // Each request edits a fresh entry so concurrent edits don't conflict on version nonces
+    let edit_blocking = {
+        let db_thread_pool = db_thread_pool.clone();
+        let entry_ids = create_entries(&db_thread_pool, fixture.budget_id, request_count);
+        let budget_id = fixture.budget_id;
+        runtime.block_on(run(concurrency, request_count, move |i| {
+            let db_thread_pool = db_thread_pool.clone();
+            let entry_id = entry_ids[i];
+            async move {
+                tokio::task::spawn_blocking(move || {
+                    db::budget::Dao::new(&db_thread_pool)
+                        .update_entry(entry_id, &[1; 128], 1, 0, None, budget_id)
+                        .unwrap()
+                })
+                .await
+                .unwrap();
+            }
+        }))
+    };
+    report("Entry edit (blocking)", request_count, edit_blocking);
+
+    let edit_async = {
+        let async_db_pools = async_db_pools.clone();
+        let entry_ids = create_entries(&db_thread_pool, fixture.budget_id, request_count);
+        let budget_id = fixture.budget_id;
+        runtime.block_on(run(concurrency, request_count, move |i| {
+            let async_db_pools = async_db_pools.clone();
+            let entry_id = entry_ids[i];
+            async move {
+                db::nonblocking::budget::Dao::new(&async_db_pools)
+                    .update_entry(entry_id, &[1; 128], 1, 0, None, budget_id)
+                    .await
+                    .unwrap();
+            }
+        }))
+    };
+    report("Entry edit (async)", request_count, edit_async);
+
+    db::budget::Dao::new(&db_thread_pool)
+        .leave_budget(fixture.budget_id, fixture.key_id)
+        .unwrap();
+}
+
+async fn run<F, Fut>(concurrency: usize, request_count: usize, request: F) -> Duration
+where
+    F: Fn(usize) -> Fut,
+    Fut: Future<Output = ()>,
+{
+    let start = Instant::now();
+
+    stream::iter(0..request_count)
+        .map(request)
+        .buffer_unordered(concurrency)
+        .collect::<()>()
+        .await;
+
+    start.elapsed()
+}
+
+fn report(name: &str, request_count: usize, elapsed: Duration) {
+    println!(
+        "{name:<24} {:>10.0} req/s ({:.2?} total)",
+        request_count as f64 / elapsed.as_secs_f64(),
+        elapsed,
+    );
+}
+
+fn create_fixture(db_thread_pool: &DbThreadPool) -> Fixture {
+    let budget_dao = db::budget::Dao::new(db_thread_pool);
+
+    let categories = (0..5)
+        .map(|i| CategoryWithTempId {
+            id: None,
+            temp_id: i,
+            encrypted_blob: vec![0; 64],
+            version_nonce: 0,
+        })
+        .collect::<Vec<_>>();
+
+    let budget = budget_dao
+        .create_budget(&[0; 256], 0, &categories, &[0; 32])
+        .unwrap();
+    let budget_id = (&budget.id).try_into().unwrap();
+    let key_id = (&budget.access_key_id).try_into().unwrap();
+
+    // Gives the budget fetches a realistic amount of data to load
+    create_entries(db_thread_pool, budget_id, ENTRY_COUNT);
+
+    Fixture { budget_id, key_id }
+}
+
+fn create_entries(db_thread_pool: &DbThreadPool, budget_id: Uuid, count: usize) -> Arc<Vec<Uuid>> {
+    let budget_dao = db::budget::Dao::new(db_thread_pool);
+
+    let entry_ids = (0..count)
+        .map(|_| {
+            budget_dao
+                .create_entry(None, &[0; 128], 0, None, budget_id)
+                .unwrap()
+        })
+        .collect();
+
+    Arc::new(entry_ids)
+}
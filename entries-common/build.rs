@@ -5,7 +5,10 @@ use std::path::PathBuf;
 fn main() -> std::io::Result<()> {
     const PROTO_DIR: &[&str] = &["protobuf"];
     const SCHEMA_FILE: &str = "schema.proto";
+    const PROTO_PACKAGE: &str = ".entries.serverschema";
     const PROTO_RS_FILE: &str = "entries.serverschema.rs";
+    const PROTO_SERDE_RS_FILE: &str = "entries.serverschema.serde.rs";
+    const DESCRIPTOR_SET_FILE: &str = "descriptor_set.bin";
     const PROTO_RS_DEST: &[&str] = &["entries-common", "src", "messages", "protobuf.rs"];
 
     let cwd = std::env::current_dir()?;
@@ -15,12 +18,24 @@ fn main() -> std::io::Result<()> {
     let server_schema = PathBuf::from_iter([&import_dir, &SCHEMA_FILE.into()]);
 
     println!("cargo:rerun-if-changed={}", server_schema.display());
+    println!("cargo:rerun-if-changed=migrations");
+
+    let descriptor_set = PathBuf::from_iter([&out_dir, &DESCRIPTOR_SET_FILE.into()]);
 
     let mut prost_build_config = prost_build::Config::new();
     prost_build_config.message_attribute(".", "#[derive(Zeroize)]");
+    prost_build_config.file_descriptor_set_path(&descriptor_set);
     prost_build_config.compile_protos(&[server_schema], &[import_dir])?;
 
-    let proto_rs = PathBuf::from_iter([out_dir, PROTO_RS_FILE.into()]);
+    // Serde implementations following the protobuf JSON mapping, so messages can also be sent
+    // as JSON
+    pbjson_build::Builder::new()
+        .register_descriptors(&std::fs::read(&descriptor_set)?)?
+        .out_dir(&out_dir)
+        .build(&[PROTO_PACKAGE])?;
+
+    let proto_rs = PathBuf::from_iter([&out_dir, &PROTO_RS_FILE.into()]);
+    let proto_serde_rs = PathBuf::from_iter([&out_dir, &PROTO_SERDE_RS_FILE.into()]);
     let dest = PathBuf::from_iter([cwd, "..".into(), PROTO_RS_DEST.iter().collect()]);
 
     let mut dest_file = File::create(dest)?;
@@ -29,6 +44,7 @@ fn main() -> std::io::Result<()> {
     writeln!(dest_file)?;
 
     dest_file.write_all(&std::fs::read(proto_rs)?)?;
+    dest_file.write_all(&std::fs::read(proto_serde_rs)?)?;
 
     Ok(())
 }
@@ -0,0 +1,18 @@
+-- This file should undo everything in `up.sql`
+
+DROP TABLE user_preferences;
+DROP TABLE user_otps;
+DROP TABLE user_keystores;
+DROP TABLE user_deletion_request_budget_keys;
+DROP TABLE user_deletion_requests;
+DROP TABLE user_backup_codes;
+DROP TABLE signin_nonces;
+DROP TABLE job_registry;
+DROP TABLE entries;
+DROP TABLE categories;
+DROP TABLE budget_share_invites;
+DROP TABLE users;
+DROP TABLE budget_access_keys;
+DROP TABLE budget_accept_keys;
+DROP TABLE budgets;
+DROP TABLE blacklisted_tokens;
@@ -0,0 +1,185 @@
+-- The SQLite counterpart of migrations/00000000000001_init. UUIDs are stored as 16-byte
+-- BLOBs and timestamps as INTEGER microseconds since the Unix epoch. SQLite can't add
+-- constraints to an existing table, so foreign keys are declared inline and tables are created
+-- in dependency order. Foreign keys are only enforced on connections that enable them, which
+-- the pool does for every connection.
+
+CREATE TABLE blacklisted_tokens (
+    token_signature BLOB PRIMARY KEY NOT NULL,
+    token_expiration INTEGER NOT NULL
+);
+
+CREATE TABLE budgets (
+    id BLOB PRIMARY KEY NOT NULL,
+    encrypted_blob BLOB NOT NULL,
+    version_nonce INTEGER NOT NULL,
+    modified_timestamp INTEGER NOT NULL
+);
+
+-- See migrations/00000000000001_init for why accept keys aren't tied to an invitation
+CREATE TABLE budget_accept_keys (
+    key_id BLOB UNIQUE NOT NULL,
+    budget_id BLOB NOT NULL REFERENCES budgets(id) ON DELETE CASCADE,
+
+    public_key BLOB NOT NULL, -- Ed25519
+
+    expiration INTEGER NOT NULL,
+    read_only BOOLEAN NOT NULL,
+
+    PRIMARY KEY (key_id, budget_id)
+);
+
+CREATE TABLE budget_access_keys (
+    key_id BLOB UNIQUE NOT NULL,
+    budget_id BLOB NOT NULL REFERENCES budgets(id) ON DELETE CASCADE,
+    public_key BLOB NOT NULL, -- Ed25519
+    read_only BOOLEAN NOT NULL,
+
+    PRIMARY KEY (key_id, budget_id)
+);
+
+CREATE INDEX budget_access_keys_budget_id_idx ON budget_access_keys (budget_id);
+
+CREATE TABLE users (
+    id BLOB PRIMARY KEY NOT NULL,
+
+    email TEXT UNIQUE NOT NULL,
+    is_verified BOOLEAN NOT NULL,
+
+    public_key_id BLOB NOT NULL,
+    public_key BLOB NOT NULL,
+
+    created_timestamp INTEGER NOT NULL,
+
+    auth_string_hash TEXT NOT NULL,
+
+    auth_string_salt BLOB NOT NULL,
+    auth_string_memory_cost_kib INTEGER NOT NULL,
+    auth_string_parallelism_factor INTEGER NOT NULL,
+    auth_string_iters INTEGER NOT NULL,
+
+    password_encryption_salt BLOB NOT NULL,
+    password_encryption_memory_cost_kib INTEGER NOT NULL,
+    password_encryption_parallelism_factor INTEGER NOT NULL,
+    password_encryption_iters INTEGER NOT NULL,
+
+    recovery_key_salt BLOB NOT NULL,
+    recovery_key_memory_cost_kib INTEGER NOT NULL,
+    recovery_key_parallelism_factor INTEGER NOT NULL,
+    recovery_key_iters INTEGER NOT NULL,
+
+    encryption_key_encrypted_with_password BLOB NOT NULL,
+    encryption_key_encrypted_with_recovery_key BLOB NOT NULL,
+
+    CONSTRAINT chk_email_length CHECK (length(email) <= 255),
+    CONSTRAINT chk_auth_string_hash_length CHECK (length(auth_string_hash) <= 1024)
+);
+
+-- See migrations/00000000000001_init for what each of these columns holds
+CREATE TABLE budget_share_invites (
+    id BLOB PRIMARY KEY NOT NULL,
+
+    recipient_user_email TEXT NOT NULL REFERENCES users(email) ON DELETE CASCADE,
+    sender_public_key BLOB NOT NULL, -- Ed25519
+
+    encryption_key_encrypted BLOB NOT NULL,
+    budget_accept_private_key_encrypted BLOB NOT NULL,
+
+    budget_info_encrypted BLOB NOT NULL,
+    sender_info_encrypted BLOB NOT NULL,
+    budget_accept_key_info_encrypted BLOB NOT NULL,
+    budget_accept_key_id_encrypted BLOB NOT NULL,
+    share_info_symmetric_key_encrypted BLOB NOT NULL,
+
+    recipient_public_key_id_used_by_sender BLOB NOT NULL,
+    recipient_public_key_id_used_by_server BLOB NOT NULL,
+
+    created_unix_timestamp_intdiv_five_million INTEGER NOT NULL,
+
+    CONSTRAINT chk_recipient_user_email_length CHECK (length(recipient_user_email) <= 255)
+);
+
+CREATE INDEX budget_share_invites_recipient_user_email_idx
+    ON budget_share_invites (recipient_user_email);
+
+CREATE TABLE categories (
+    id BLOB PRIMARY KEY NOT NULL,
+    budget_id BLOB NOT NULL REFERENCES budgets(id) ON DELETE CASCADE,
+
+    encrypted_blob BLOB NOT NULL,
+    version_nonce INTEGER NOT NULL,
+
+    modified_timestamp INTEGER NOT NULL
+);
+
+CREATE INDEX categories_budget_id_idx ON categories (budget_id);
+
+CREATE TABLE entries (
+    id BLOB PRIMARY KEY NOT NULL,
+    budget_id BLOB NOT NULL REFERENCES budgets(id) ON DELETE CASCADE,
+
+    category_id BLOB REFERENCES categories(id) ON DELETE SET NULL, -- Intentionally nullable
+
+    encrypted_blob BLOB NOT NULL,
+    version_nonce INTEGER NOT NULL,
+
+    modified_timestamp INTEGER NOT NULL
+);
+
+CREATE INDEX entries_budget_id_idx ON entries (budget_id);
+CREATE INDEX entries_category_id_idx ON entries (category_id);
+
+CREATE TABLE job_registry (
+    job_name TEXT PRIMARY KEY NOT NULL,
+    last_run_timestamp INTEGER NOT NULL
+);
+
+CREATE TABLE signin_nonces (
+    user_email TEXT PRIMARY KEY NOT NULL REFERENCES users(email) ON DELETE CASCADE,
+    nonce INTEGER NOT NULL,
+
+    CONSTRAINT chk_user_email_length CHECK (length(user_email) <= 255)
+);
+
+CREATE TABLE user_backup_codes (
+    user_id BLOB NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    code TEXT NOT NULL,
+
+    PRIMARY KEY (user_id, code)
+);
+
+CREATE TABLE user_deletion_requests (
+    user_id BLOB PRIMARY KEY NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    ready_for_deletion_time INTEGER NOT NULL
+);
+
+CREATE TABLE user_deletion_request_budget_keys (
+    key_id BLOB PRIMARY KEY NOT NULL REFERENCES budget_access_keys(key_id) ON DELETE CASCADE,
+    user_id BLOB NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    delete_me_time INTEGER NOT NULL
+);
+
+CREATE INDEX user_deletion_request_budget_keys_user_id_idx
+    ON user_deletion_request_budget_keys (user_id);
+CREATE INDEX user_deletion_request_budget_keys_delete_me_time_idx
+    ON user_deletion_request_budget_keys (delete_me_time);
+
+CREATE TABLE user_keystores (
+    user_id BLOB PRIMARY KEY NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    encrypted_blob BLOB NOT NULL,
+    version_nonce INTEGER NOT NULL
+);
+
+CREATE TABLE user_otps (
+    user_email TEXT PRIMARY KEY NOT NULL REFERENCES users(email) ON DELETE CASCADE,
+    otp TEXT NOT NULL,
+    expiration INTEGER NOT NULL,
+
+    CONSTRAINT chk_user_email_length CHECK (length(user_email) <= 255)
+);
+
+CREATE TABLE user_preferences (
+    user_id BLOB PRIMARY KEY NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    encrypted_blob BLOB NOT NULL,
+    version_nonce INTEGER NOT NULL
+);
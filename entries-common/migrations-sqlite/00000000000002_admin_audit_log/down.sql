@@ -0,0 +1,3 @@
+-- This file should undo everything in `up.sql`
+
+DROP TABLE admin_audit_log;
@@ -0,0 +1,13 @@
+-- Records each action taken with the entries-admin tool
+CREATE TABLE admin_audit_log (
+    id BLOB PRIMARY KEY NOT NULL,
+    timestamp INTEGER NOT NULL,
+    operator TEXT NOT NULL,
+    action TEXT NOT NULL,
+    target_user_email TEXT NOT NULL,
+    details TEXT NOT NULL,
+    succeeded BOOLEAN NOT NULL
+);
+
+CREATE INDEX admin_audit_log_target_user_email_idx ON admin_audit_log (target_user_email);
+CREATE INDEX admin_audit_log_timestamp_idx ON admin_audit_log (timestamp);
@@ -0,0 +1,22 @@
+-- Responses to requests sent with an Idempotency-Key header, replayed when a client retries
+-- the request with the same key
+CREATE TABLE idempotency_keys (
+    user_id BLOB NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    key TEXT NOT NULL,
+    request_hash BLOB NOT NULL,
+
+    -- When the request that reserved the key started. A reservation that is never followed by a
+    -- response (e.g. because the server was killed) can be taken over once it is old enough.
+    reserved_at INTEGER NOT NULL,
+
+    -- Both are NULL while the original request is still being handled
+    response_status INTEGER,
+    response_content_type TEXT,
+    response_body BLOB,
+
+    expiration INTEGER NOT NULL,
+
+    PRIMARY KEY (user_id, key)
+);
+
+CREATE INDEX idempotency_keys_expiration_idx ON idempotency_keys (expiration);
@@ -0,0 +1,24 @@
+-- Responses to requests sent with an Idempotency-Key header, replayed when a client retries
+-- the request with the same key
+CREATE TABLE idempotency_keys (
+    user_id UUID NOT NULL,
+    key TEXT NOT NULL,
+    request_hash BYTEA NOT NULL,
+
+    -- When the request that reserved the key started. A reservation that is never followed by a
+    -- response (e.g. because the server was killed) can be taken over once it is old enough.
+    reserved_at TIMESTAMP NOT NULL,
+
+    -- Both are NULL while the original request is still being handled
+    response_status SMALLINT,
+    response_content_type TEXT,
+    response_body BYTEA,
+
+    expiration TIMESTAMP NOT NULL,
+
+    PRIMARY KEY (user_id, key)
+);
+
+CREATE INDEX ON idempotency_keys (expiration);
+
+ALTER TABLE idempotency_keys ADD CONSTRAINT user_key FOREIGN KEY(user_id) REFERENCES users(id) ON DELETE CASCADE;
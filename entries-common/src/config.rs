@@ -0,0 +1,634 @@
+use base64::engine::general_purpose::STANDARD as b64;
+use base64::Engine;
+use lettre::message::Mailbox;
+use lettre::Address;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{self, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use zeroize::Zeroizing;
+
+/// Path of an optional TOML config file to read values from
+pub const CONFIG_FILE_VAR: &str = "ENTRIES_CONFIG_FILE";
+
+const VAR_PREFIX: &str = "ENTRIES_";
+const SECRET_FILE_SUFFIX: &str = "_FILE";
+const REDACTED: &str = "<redacted>";
+
+/// Looks up configuration values by their environment variable name (e.g.
+/// `ENTRIES_DB_PORT`). Values are taken from the first of these that is set:
+///
+/// 1. The environment variable itself
+/// 2. A file named by the variable with a `_FILE` suffix (e.g. `ENTRIES_DB_PASSWORD_FILE`),
+///    which is useful for secrets mounted as files
+/// 3. The TOML config file named by `ENTRIES_CONFIG_FILE`
+/// 4. A file named in the TOML config file by the key with a `_file` suffix
+///
+/// Keys in the TOML file are the variable names without the `ENTRIES_` prefix, in any case.
+/// Tables are joined to their keys with an underscore, so `port` in a `[db]` table is the
+/// same as a top-level `db_port`.
+///
+/// Every value that is looked up is recorded so the effective configuration can be printed
+/// with `effective_config()`.
+pub struct ConfigSource {
+    file_path: Option<PathBuf>,
+    file_values: HashMap<String, Zeroizing<String>>,
+    resolved: RefCell<BTreeMap<&'static str, ResolvedValue>>,
+    collected_errors: Option<RefCell<Vec<ConfigError>>>,
+}
+
+struct ResolvedValue {
+    value: Option<String>,
+    origin: Origin,
+}
+
+enum Origin {
+    Environment,
+    ConfigFile,
+    SecretFile(PathBuf),
+    Default,
+    Unset,
+}
+
+impl ConfigSource {
+    /// Reads the config file named by `ENTRIES_CONFIG_FILE`, if it is set
+    pub fn load() -> Result<Self, ConfigError> {
+        match std::env::var(CONFIG_FILE_VAR) {
+            Ok(path) => Self::from_file(path),
+            Err(_) => Ok(Self::env_only()),
+        }
+    }
+
+    pub fn env_only() -> Self {
+        Self {
+            file_path: None,
+            file_values: HashMap::new(),
+            resolved: RefCell::new(BTreeMap::new()),
+            collected_errors: None,
+        }
+    }
+
+    pub fn from_file(path: impl Into<PathBuf>) -> Result<Self, ConfigError> {
+        let path = path.into();
+        let contents = Zeroizing::new(
+            std::fs::read_to_string(&path)
+                .map_err(|e| ConfigError::UnreadableFile(path.clone(), e))?,
+        );
+
+        let mut source = Self::from_toml(&contents)
+            .map_err(|e| ConfigError::InvalidFile(path.clone(), e.to_string()))?;
+        source.file_path = Some(path);
+
+        Ok(source)
+    }
+
+    pub fn from_toml(toml: &str) -> Result<Self, ConfigError> {
+        let table: toml::Table = toml
+            .parse()
+            .map_err(|e: toml::de::Error| ConfigError::InvalidToml(e.message().to_owned()))?;
+
+        let mut file_values = HashMap::new();
+        flatten_table(VAR_PREFIX, &table, &mut file_values)?;
+
+        Ok(Self {
+            file_path: None,
+            file_values,
+            resolved: RefCell::new(BTreeMap::new()),
+            collected_errors: None,
+        })
+    }
+
+    pub fn file_path(&self) -> Option<&Path> {
+        self.file_path.as_deref()
+    }
+
+    /// Makes lookups record a missing or invalid value instead of failing, so that every
+    /// problem with the configuration can be reported at once. In place of the value, lookups
+    /// return the default (or `T::default()` for a required value), so a config built from this
+    /// source is only good for validation. The errors are returned by `take_errors()`.
+    pub fn collect_errors(mut self) -> Self {
+        self.collected_errors = Some(RefCell::new(Vec::new()));
+        self
+    }
+
+    /// Errors recorded since `collect_errors()` was called, at most one for each variable
+    pub fn take_errors(&self) -> Vec<ConfigError> {
+        self.collected_errors
+            .as_ref()
+            .map(RefCell::take)
+            .unwrap_or_default()
+    }
+
+    /// Returns the result as is unless errors are being collected, in which case an error is
+    /// recorded and replaced with `fallback()`
+    pub fn recover<T>(
+        &self,
+        result: Result<T, ConfigError>,
+        fallback: impl FnOnce() -> T,
+    ) -> Result<T, ConfigError> {
+        let (Err(e), Some(errors)) = (&result, &self.collected_errors) else {
+            return result;
+        };
+
+        let mut errors = errors.borrow_mut();
+        let is_reported = e.var().is_some() && errors.iter().any(|other| other.var() == e.var());
+        if !is_reported {
+            errors.push(result.err().unwrap());
+        }
+
+        Ok(fallback())
+    }
+
+    pub fn get<T: FromStr + Default>(&self, key: &'static str) -> Result<T, ConfigError> {
+        let value = self
+            .lookup_opt(key)
+            .and_then(|v| v.ok_or(ConfigError::MissingVar(key)));
+        self.recover(value, T::default)
+    }
+
+    /// Same as `get()`, but the value is redacted from `effective_config()`
+    pub fn get_secret<T: FromStr + Default>(&self, key: &'static str) -> Result<T, ConfigError> {
+        let value = self.lookup(key).and_then(|v| {
+            let (value, origin) = v.ok_or(ConfigError::MissingVar(key))?;
+            self.record(key, Some(String::from(REDACTED)), origin);
+            value.parse().map_err(|_| ConfigError::InvalidVar(key))
+        });
+        self.recover(value, T::default)
+    }
+
+    /// Decodes a base64-encoded secret key. Keys longer than `N` bytes are truncated.
+    pub fn get_secret_key<const N: usize>(
+        &self,
+        key: &'static str,
+    ) -> Result<[u8; N], ConfigError> {
+        let value = self.get_secret::<String>(key).and_then(|encoded| {
+            let decoded = Zeroizing::new(
+                b64.decode(encoded.as_bytes())
+                    .map_err(|_| ConfigError::InvalidVar(key))?,
+            );
+
+            decoded
+                .get(..N)
+                .and_then(|k| k.try_into().ok())
+                .ok_or(ConfigError::InvalidVar(key))
+        });
+
+        self.recover(value, || [0; N])
+    }
+
+    /// Parses an email address, optionally with a name (e.g. `Entries <no-reply@entries.app>`)
+    pub fn get_mailbox(&self, key: &'static str) -> Result<Mailbox, ConfigError> {
+        let value = self
+            .lookup_opt(key)
+            .and_then(|v| v.ok_or(ConfigError::MissingVar(key)));
+
+        self.recover(value, || {
+            Mailbox::new(None, Address::new("invalid", "localhost").unwrap())
+        })
+    }
+
+    pub fn get_or<T: FromStr + fmt::Display>(
+        &self,
+        key: &'static str,
+        default: T,
+    ) -> Result<T, ConfigError> {
+        let value = match self.lookup(key) {
+            Ok(Some((value, origin))) => {
+                let parsed = value.parse().map_err(|_| ConfigError::InvalidVar(key));
+                self.record(key, Some(value.to_string()), origin);
+                parsed
+            }
+            Ok(None) => {
+                self.record(key, Some(default.to_string()), Origin::Default);
+                return Ok(default);
+            }
+            Err(e) => Err(e),
+        };
+
+        self.recover(value, || default)
+    }
+
+    pub fn get_opt<T: FromStr>(&self, key: &'static str) -> Result<Option<T>, ConfigError> {
+        let value = self.lookup_opt(key);
+        self.recover(value, || None)
+    }
+
+    /// Parses a comma-separated list (or an array in the config file). Returns an empty list
+    /// if the value isn't set.
+    pub fn get_list<T: FromStr>(&self, key: &'static str) -> Result<Vec<T>, ConfigError> {
+        let value = self.lookup_opt::<String>(key).and_then(|value| {
+            let Some(value) = value else {
+                return Ok(Vec::new());
+            };
+
+            value
+                .split(',')
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .map(|item| item.parse().map_err(|_| ConfigError::InvalidVar(key)))
+                .collect()
+        });
+
+        self.recover(value, Vec::new)
+    }
+
+    fn lookup_opt<T: FromStr>(&self, key: &'static str) -> Result<Option<T>, ConfigError> {
+        match self.lookup(key)? {
+            Some((value, origin)) => {
+                let parsed = value
+                    .parse()
+                    .map(Some)
+                    .map_err(|_| ConfigError::InvalidVar(key));
+                self.record(key, Some(value.to_string()), origin);
+                parsed
+            }
+            None => {
+                self.record(key, None, Origin::Unset);
+                Ok(None)
+            }
+        }
+    }
+
+    /// Keys in the config file that haven't been looked up. These are either misspelled or
+    /// used by a different binary that shares the file.
+    pub fn unused_file_keys(&self) -> Vec<String> {
+        let resolved = self.resolved.borrow();
+
+        let mut unused: Vec<String> = self
+            .file_values
+            .keys()
+            .filter(|key| {
+                let is_secret_file_for_resolved_key = key
+                    .strip_suffix(SECRET_FILE_SUFFIX)
+                    .is_some_and(|k| resolved.contains_key(k));
+
+                !resolved.contains_key(key.as_str()) && !is_secret_file_for_resolved_key
+            })
+            .map(|key| key[VAR_PREFIX.len()..].to_lowercase())
+            .collect();
+
+        unused.sort_unstable();
+        unused
+    }
+
+    /// Lists every value that has been looked up along with where it came from. Secrets are
+    /// redacted.
+    pub fn effective_config(&self) -> String {
+        let mut output = String::new();
+
+        for (key, resolved) in self.resolved.borrow().iter() {
+            let value = resolved.value.as_deref().unwrap_or("");
+
+            let origin = match &resolved.origin {
+                Origin::Environment => String::from("environment"),
+                Origin::ConfigFile => String::from("config file"),
+                Origin::SecretFile(path) => format!("file {}", path.display()),
+                Origin::Default => String::from("default"),
+                Origin::Unset => String::from("not set"),
+            };
+
+            let _ = writeln!(output, "{key}={value} ({origin})");
+        }
+
+        output
+    }
+
+    fn lookup(
+        &self,
+        key: &'static str,
+    ) -> Result<Option<(Zeroizing<String>, Origin)>, ConfigError> {
+        let file_key = format!("{key}{SECRET_FILE_SUFFIX}");
+
+        if let Ok(value) = std::env::var(key) {
+            return Ok(Some((Zeroizing::new(value), Origin::Environment)));
+        }
+
+        if let Ok(path) = std::env::var(&file_key) {
+            return read_secret_file(key, path).map(Some);
+        }
+
+        if let Some(value) = self.file_values.get(key) {
+            return Ok(Some((value.clone(), Origin::ConfigFile)));
+        }
+
+        if let Some(path) = self.file_values.get(&file_key) {
+            return read_secret_file(key, path.as_str()).map(Some);
+        }
+
+        Ok(None)
+    }
+
+    fn record(&self, key: &'static str, value: Option<String>, origin: Origin) {
+        self.resolved
+            .borrow_mut()
+            .insert(key, ResolvedValue { value, origin });
+    }
+}
+
+fn read_secret_file(
+    key: &'static str,
+    path: impl Into<PathBuf>,
+) -> Result<(Zeroizing<String>, Origin), ConfigError> {
+    let path = path.into();
+    let mut contents = Zeroizing::new(
+        std::fs::read_to_string(&path)
+            .map_err(|e| ConfigError::UnreadableSecretFile(key, path.clone(), e))?,
+    );
+
+    // Files written by editors and `echo` usually end with a newline that isn't part of the
+    // secret
+    let trimmed_len = contents.trim_end_matches(['\r', '\n']).len();
+    contents.truncate(trimmed_len);
+
+    Ok((contents, Origin::SecretFile(path)))
+}
+
+fn flatten_table(
+    prefix: &str,
+    table: &toml::Table,
+    values: &mut HashMap<String, Zeroizing<String>>,
+) -> Result<(), ConfigError> {
+    for (key, value) in table {
+        let name = format!("{prefix}{}", key.to_uppercase().replace('-', "_"));
+
+        let value = match value {
+            toml::Value::Table(table) => {
+                flatten_table(&format!("{name}_"), table, values)?;
+                continue;
+            }
+            toml::Value::Array(items) => items
+                .iter()
+                .map(|item| scalar_to_string(item).ok_or(ConfigError::InvalidFileKey(name.clone())))
+                .collect::<Result<Vec<_>, _>>()?
+                .join(","),
+            value => scalar_to_string(value).ok_or(ConfigError::InvalidFileKey(name.clone()))?,
+        };
+
+        values.insert(name, Zeroizing::new(value));
+    }
+
+    Ok(())
+}
+
+fn scalar_to_string(value: &toml::Value) -> Option<String> {
+    match value {
+        toml::Value::String(s) => Some(s.clone()),
+        toml::Value::Integer(i) => Some(i.to_string()),
+        toml::Value::Float(f) => Some(f.to_string()),
+        toml::Value::Boolean(b) => Some(b.to_string()),
+        toml::Value::Datetime(d) => Some(d.to_string()),
+        toml::Value::Array(_) | toml::Value::Table(_) => None,
+    }
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    MissingVar(&'static str),
+    InvalidVar(&'static str),
+    UnreadableSecretFile(&'static str, PathBuf, std::io::Error),
+    UnreadableFile(PathBuf, std::io::Error),
+    InvalidFile(PathBuf, String),
+    InvalidToml(String),
+    InvalidFileKey(String),
+}
+
+impl ConfigError {
+    /// The configuration variable the error is about, if it is about a single variable
+    pub fn var(&self) -> Option<&str> {
+        match self {
+            Self::MissingVar(key)
+            | Self::InvalidVar(key)
+            | Self::UnreadableSecretFile(key, _, _) => Some(key),
+            Self::InvalidFileKey(key) => Some(key),
+            Self::UnreadableFile(..) | Self::InvalidFile(..) | Self::InvalidToml(_) => None,
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingVar(key) => write!(f, "Missing configuration variable '{key}'"),
+            Self::InvalidVar(key) => write!(f, "Configuration variable '{key}' is invalid"),
+            Self::UnreadableSecretFile(key, path, e) => write!(
+                f,
+                "Failed to read '{}' for configuration variable '{key}': {e}",
+                path.display(),
+            ),
+            Self::UnreadableFile(path, e) => {
+                write!(f, "Failed to read config file '{}': {e}", path.display())
+            }
+            Self::InvalidFile(path, e) => {
+                write!(f, "Config file '{}' is invalid: {e}", path.display())
+            }
+            Self::InvalidToml(e) => write!(f, "Config file is invalid: {e}"),
+            Self::InvalidFileKey(key) => write!(
+                f,
+                "Config file value for '{key}' must be a string, number, boolean, or a list of those",
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_file_values() {
+        let source = ConfigSource::from_toml(
+            r#"
+            log_level = "debug"
+            bind_addresses = ["0.0.0.0:9000", "[::]:9000"]
+            unused_key = 1
+
+            [test_config_db]
+            port = 5433
+            max-connections = 12
+            enabled = true
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            source.get::<u16>("ENTRIES_TEST_CONFIG_DB_PORT").unwrap(),
+            5433
+        );
+        assert_eq!(
+            source
+                .get_or::<u32>("ENTRIES_TEST_CONFIG_DB_MAX_CONNECTIONS", 48)
+                .unwrap(),
+            12
+        );
+        assert!(source
+            .get::<bool>("ENTRIES_TEST_CONFIG_DB_ENABLED")
+            .unwrap());
+        assert_eq!(
+            source.get_list::<String>("ENTRIES_BIND_ADDRESSES").unwrap(),
+            vec!["0.0.0.0:9000", "[::]:9000"]
+        );
+        assert_eq!(
+            source
+                .get_or::<u32>("ENTRIES_TEST_CONFIG_MISSING", 7)
+                .unwrap(),
+            7
+        );
+        assert!(matches!(
+            source.get::<u32>("ENTRIES_TEST_CONFIG_MISSING_REQUIRED"),
+            Err(ConfigError::MissingVar(
+                "ENTRIES_TEST_CONFIG_MISSING_REQUIRED"
+            ))
+        ));
+        assert!(matches!(
+            source.get::<bool>("ENTRIES_TEST_CONFIG_DB_PORT"),
+            Err(ConfigError::InvalidVar("ENTRIES_TEST_CONFIG_DB_PORT"))
+        ));
+
+        assert_eq!(source.unused_file_keys(), vec!["log_level", "unused_key"]);
+
+        let effective = source.effective_config();
+        assert!(effective.contains("ENTRIES_TEST_CONFIG_DB_PORT=5433 (config file)\n"));
+        assert!(effective.contains("ENTRIES_TEST_CONFIG_MISSING=7 (default)\n"));
+
+        assert!(matches!(
+            ConfigSource::from_toml("nested = [[1]]"),
+            Err(ConfigError::InvalidFileKey(_))
+        ));
+        assert!(matches!(
+            ConfigSource::from_toml("not toml"),
+            Err(ConfigError::InvalidToml(_))
+        ));
+    }
+
+    #[test]
+    fn test_collect_errors() {
+        let source = ConfigSource::from_toml(
+            r#"
+            test_collect_port = "not a port"
+            test_collect_list = "1, two"
+            test_collect_short_key = "AAAA"
+            test_collect_key = "AAECAw=="
+            test_collect_mailbox = "not an address"
+            "#,
+        )
+        .unwrap();
+
+        assert!(matches!(
+            source.get::<u16>("ENTRIES_TEST_COLLECT_PORT"),
+            Err(ConfigError::InvalidVar("ENTRIES_TEST_COLLECT_PORT"))
+        ));
+        assert!(source.take_errors().is_empty());
+
+        let source = source.collect_errors();
+
+        assert_eq!(source.get::<u16>("ENTRIES_TEST_COLLECT_PORT").unwrap(), 0);
+        assert_eq!(
+            source
+                .get_or::<u16>("ENTRIES_TEST_COLLECT_PORT", 7)
+                .unwrap(),
+            7
+        );
+        assert_eq!(
+            source
+                .get::<String>("ENTRIES_TEST_COLLECT_MISSING")
+                .unwrap(),
+            ""
+        );
+        assert_eq!(
+            source.get_list::<u32>("ENTRIES_TEST_COLLECT_LIST").unwrap(),
+            Vec::<u32>::new()
+        );
+        assert_eq!(
+            source
+                .get_secret_key::<4>("ENTRIES_TEST_COLLECT_SHORT_KEY")
+                .unwrap(),
+            [0; 4]
+        );
+        assert_eq!(
+            source
+                .get_secret_key::<4>("ENTRIES_TEST_COLLECT_KEY")
+                .unwrap(),
+            [0, 1, 2, 3]
+        );
+        assert!(source.get_mailbox("ENTRIES_TEST_COLLECT_MAILBOX").is_ok());
+
+        let errors: Vec<String> = source
+            .take_errors()
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(
+            errors,
+            vec![
+                ConfigError::InvalidVar("ENTRIES_TEST_COLLECT_PORT").to_string(),
+                ConfigError::MissingVar("ENTRIES_TEST_COLLECT_MISSING").to_string(),
+                ConfigError::InvalidVar("ENTRIES_TEST_COLLECT_LIST").to_string(),
+                ConfigError::InvalidVar("ENTRIES_TEST_COLLECT_SHORT_KEY").to_string(),
+                ConfigError::InvalidVar("ENTRIES_TEST_COLLECT_MAILBOX").to_string(),
+            ]
+        );
+        assert!(source.take_errors().is_empty());
+    }
+
+    #[test]
+    fn test_env_and_secret_file_precedence() {
+        let dir =
+            std::env::temp_dir().join(format!("entries-config-test-{}", rand::random::<u64>()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let env_secret_path = dir.join("env_secret");
+        let file_secret_path = dir.join("file_secret");
+        std::fs::write(&env_secret_path, "from env secret file\n").unwrap();
+        std::fs::write(&file_secret_path, "from config secret file").unwrap();
+
+        std::env::set_var("ENTRIES_TEST_PRECEDENCE_ENV", "from env");
+        std::env::set_var("ENTRIES_TEST_PRECEDENCE_ENV_FILE", &env_secret_path);
+
+        let source = ConfigSource::from_toml(&format!(
+            r#"
+            test_precedence_env = "from config file"
+            test_precedence_file = "from config file"
+            test_precedence_secret_file = "{}"
+            "#,
+            file_secret_path.display(),
+        ))
+        .unwrap();
+
+        assert_eq!(
+            source.get::<String>("ENTRIES_TEST_PRECEDENCE_ENV").unwrap(),
+            "from env"
+        );
+        assert_eq!(
+            source
+                .get::<String>("ENTRIES_TEST_PRECEDENCE_FILE")
+                .unwrap(),
+            "from config file"
+        );
+        assert_eq!(
+            source
+                .get_secret::<String>("ENTRIES_TEST_PRECEDENCE_SECRET")
+                .unwrap(),
+            "from config secret file"
+        );
+
+        std::env::remove_var("ENTRIES_TEST_PRECEDENCE_ENV");
+        assert_eq!(
+            source.get::<String>("ENTRIES_TEST_PRECEDENCE_ENV").unwrap(),
+            "from env secret file"
+        );
+
+        let effective = source.effective_config();
+        assert!(effective.contains(&format!(
+            "ENTRIES_TEST_PRECEDENCE_SECRET={REDACTED} (file {})\n",
+            file_secret_path.display(),
+        )));
+        assert!(!effective.contains("from config secret file"));
+        assert!(source.unused_file_keys().is_empty());
+
+        std::env::remove_var("ENTRIES_TEST_PRECEDENCE_ENV_FILE");
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,65 @@
+use diesel::{dsl, ExpressionMethods, QueryDsl, RunQueryDsl};
+use std::time::SystemTime;
+use uuid::Uuid;
+
+use crate::db::{DaoError, DbThreadPool};
+use crate::models::admin_audit_log_entry::{AdminAuditLogEntry, NewAdminAuditLogEntry};
+use crate::schema::admin_audit_log as admin_audit_log_fields;
+use crate::schema::admin_audit_log::dsl::admin_audit_log;
+
+pub struct Dao {
+    db_thread_pool: DbThreadPool,
+}
+
+impl Dao {
+    pub fn new(db_thread_pool: &DbThreadPool) -> Self {
+        Self {
+            db_thread_pool: db_thread_pool.clone(),
+        }
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    pub fn record(
+        &self,
+        operator: &str,
+        action: &str,
+        target_user_email: &str,
+        details: &str,
+        succeeded: bool,
+    ) -> Result<(), DaoError> {
+        let entry = NewAdminAuditLogEntry {
+            id: Uuid::now_v7(),
+            timestamp: SystemTime::now(),
+            operator,
+            action,
+            target_user_email,
+            details,
+            succeeded,
+        };
+
+        dsl::insert_into(admin_audit_log)
+            .values(&entry)
+            .execute(&mut self.db_thread_pool.get()?)?;
+
+        Ok(())
+    }
+
+    /// Returns the most recent entries, newest first, optionally only those for one user
+    #[tracing::instrument(level = "debug", skip_all)]
+    pub fn get_recent_entries(
+        &self,
+        target_user_email: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<AdminAuditLogEntry>, DaoError> {
+        let mut query = admin_audit_log
+            .order(admin_audit_log_fields::timestamp.desc())
+            .limit(limit)
+            .into_boxed();
+
+        if let Some(email) = target_user_email {
+            query = query.filter(admin_audit_log_fields::target_user_email.eq(email));
+        }
+
+        Ok(query.load(&mut self.db_thread_pool.get()?)?)
+    }
+}
@@ -25,6 +25,51 @@ pub struct UserAuthStringHashAndStatus {
     pub auth_string_hash: String,
 }
 
+/// Queries for signing in and the tokens, OTPs and backup codes that go with it
+pub trait AuthDao: Send + Sync {
+    fn get_user_auth_string_hash_and_status(
+        &self,
+        user_email: &str,
+    ) -> Result<UserAuthStringHashAndStatus, DaoError>;
+
+    fn blacklist_token(
+        &self,
+        token_signature: &[u8],
+        token_expiration: u64,
+    ) -> Result<(), DaoError>;
+
+    fn check_is_token_on_blacklist_and_blacklist(
+        &self,
+        token_signature: &[u8],
+        token_expiration: u64,
+    ) -> Result<bool, DaoError>;
+
+    fn save_otp(&self, otp: &str, user_email: &str, expiration: SystemTime)
+        -> Result<(), DaoError>;
+
+    fn check_unexpired_otp(&self, otp: &str, user_email: &str) -> Result<bool, DaoError>;
+
+    fn delete_otp(&self, otp: &str, user_email: &str) -> Result<(), DaoError>;
+
+    /// Returns the number of OTPs deleted
+    fn delete_all_otps_for_user(&self, user_email: &str) -> Result<usize, DaoError>;
+
+    fn delete_all_expired_otps(&self) -> Result<(), DaoError>;
+
+    fn replace_backup_codes(&self, user_id: Uuid, codes: &[String]) -> Result<(), DaoError>;
+
+    fn delete_backup_code(&self, code: &str, user_id: Uuid) -> Result<(), DaoError>;
+
+    fn clear_all_expired_tokens(&self) -> Result<usize, DaoError>;
+
+    fn get_and_refresh_signin_nonce(&self, user_email: &str) -> Result<i32, DaoError>;
+
+    fn get_auth_string_data_signin_nonce(
+        &self,
+        user_email: &str,
+    ) -> Result<SigninNonceAndHashParams, DaoError>;
+}
+
 pub struct Dao {
     db_thread_pool: DbThreadPool,
 }
@@ -35,8 +80,11 @@ impl Dao {
             db_thread_pool: db_thread_pool.clone(),
         }
     }
+}
 
-    pub fn get_user_auth_string_hash_and_status(
+impl AuthDao for Dao {
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn get_user_auth_string_hash_and_status(
         &self,
         user_email: &str,
     ) -> Result<UserAuthStringHashAndStatus, DaoError> {
@@ -64,7 +112,8 @@ impl Dao {
         })
     }
 
-    pub fn blacklist_token(
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn blacklist_token(
         &self,
         token_signature: &[u8],
         token_expiration: u64,
@@ -83,7 +132,8 @@ impl Dao {
         Ok(())
     }
 
-    pub fn check_is_token_on_blacklist_and_blacklist(
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn check_is_token_on_blacklist_and_blacklist(
         &self,
         token_signature: &[u8],
         token_expiration: u64,
@@ -111,7 +161,8 @@ impl Dao {
         }
     }
 
-    pub fn save_otp(
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn save_otp(
         &self,
         otp: &str,
         user_email: &str,
@@ -136,7 +187,8 @@ impl Dao {
         Ok(())
     }
 
-    pub fn check_unexpired_otp(&self, otp: &str, user_email: &str) -> Result<bool, DaoError> {
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn check_unexpired_otp(&self, otp: &str, user_email: &str) -> Result<bool, DaoError> {
         Ok(dsl::select(dsl::exists(
             user_otps
                 .find(user_email)
@@ -146,7 +198,8 @@ impl Dao {
         .get_result(&mut self.db_thread_pool.get()?)?)
     }
 
-    pub fn delete_otp(&self, otp: &str, user_email: &str) -> Result<(), DaoError> {
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn delete_otp(&self, otp: &str, user_email: &str) -> Result<(), DaoError> {
         diesel::delete(
             user_otps
                 .find(user_email)
@@ -157,14 +210,21 @@ impl Dao {
         Ok(())
     }
 
-    pub fn delete_all_expired_otps(&self) -> Result<(), DaoError> {
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn delete_all_otps_for_user(&self, user_email: &str) -> Result<usize, DaoError> {
+        Ok(diesel::delete(user_otps.find(user_email)).execute(&mut self.db_thread_pool.get()?)?)
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn delete_all_expired_otps(&self) -> Result<(), DaoError> {
         dsl::delete(user_otps.filter(user_otp_fields::expiration.lt(SystemTime::now())))
             .execute(&mut self.db_thread_pool.get()?)?;
 
         Ok(())
     }
 
-    pub fn replace_backup_codes(&self, user_id: Uuid, codes: &[String]) -> Result<(), DaoError> {
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn replace_backup_codes(&self, user_id: Uuid, codes: &[String]) -> Result<(), DaoError> {
         let codes = codes
             .iter()
             .map(|code| NewUserBackupCode { user_id, code })
@@ -190,14 +250,16 @@ impl Dao {
         Ok(())
     }
 
-    pub fn delete_backup_code(&self, code: &str, user_id: Uuid) -> Result<(), DaoError> {
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn delete_backup_code(&self, code: &str, user_id: Uuid) -> Result<(), DaoError> {
         diesel::delete(user_backup_codes.find((user_id, code)))
             .execute(&mut self.db_thread_pool.get()?)?;
 
         Ok(())
     }
 
-    pub fn clear_all_expired_tokens(&self) -> Result<usize, DaoError> {
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn clear_all_expired_tokens(&self) -> Result<usize, DaoError> {
         // Add two minutes to current time to prevent slight clock differences/inaccuracies from
         // opening a window for an attacker to use an expired refresh token
         Ok(diesel::delete(
@@ -207,7 +269,8 @@ impl Dao {
         .execute(&mut self.db_thread_pool.get()?)?)
     }
 
-    pub fn get_and_refresh_signin_nonce(&self, user_email: &str) -> Result<i32, DaoError> {
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn get_and_refresh_signin_nonce(&self, user_email: &str) -> Result<i32, DaoError> {
         let mut db_connection = self.db_thread_pool.get()?;
 
         let nonce = db_connection
@@ -228,7 +291,8 @@ impl Dao {
         Ok(nonce)
     }
 
-    pub fn get_auth_string_data_signin_nonce(
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn get_auth_string_data_signin_nonce(
         &self,
         user_email: &str,
     ) -> Result<SigninNonceAndHashParams, DaoError> {
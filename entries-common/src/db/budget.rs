@@ -1,10 +1,9 @@
 use diesel::associations::GroupedBy;
-use diesel::{
-    dsl, BelongingToDsl, BoolExpressionMethods, ExpressionMethods, QueryDsl, RunQueryDsl,
-};
+use diesel::{dsl, ExpressionMethods, QueryDsl, RunQueryDsl};
 use std::time::{SystemTime, UNIX_EPOCH};
 use uuid::Uuid;
 
+use crate::db::transaction::run_repeatable_read;
 use crate::db::{DaoError, DbThreadPool};
 use crate::messages::{
     Budget as BudgetMessage, BudgetList, EntryIdAndCategoryId, InvitationId, Uuid as UuidMessage,
@@ -24,13 +23,162 @@ use crate::schema::budget_access_keys as budget_access_key_fields;
 use crate::schema::budget_access_keys::dsl::budget_access_keys;
 use crate::schema::budget_share_invites as budget_share_invite_fields;
 use crate::schema::budget_share_invites::dsl::budget_share_invites;
-use crate::schema::budgets as budget_fields;
 use crate::schema::budgets::dsl::budgets;
-use crate::schema::categories as category_fields;
 use crate::schema::categories::dsl::categories;
-use crate::schema::entries as entry_fields;
 use crate::schema::entries::dsl::entries;
 
+/// Queries for budgets, their entries and categories, and the invitations to share them
+pub trait BudgetDao: Send + Sync {
+    fn get_public_budget_key(
+        &self,
+        key_id: Uuid,
+        budget_id: Uuid,
+    ) -> Result<BudgetAccessKey, DaoError>;
+
+    fn get_multiple_public_budget_keys(
+        &self,
+        key_ids: &[Uuid],
+        budget_ids: &[Uuid],
+    ) -> Result<Vec<BudgetAccessKey>, DaoError>;
+
+    fn get_budget_accept_public_key(
+        &self,
+        key_id: Uuid,
+        budget_id: Uuid,
+    ) -> Result<BudgetAcceptKey, DaoError>;
+
+    fn get_budget_invite_sender_public_key(&self, invitation_id: Uuid)
+        -> Result<Vec<u8>, DaoError>;
+
+    fn get_budget(&self, budget_id: Uuid) -> Result<BudgetMessage, DaoError>;
+
+    fn get_multiple_budgets_by_id(&self, budget_ids: &[Uuid]) -> Result<BudgetList, DaoError>;
+
+    fn create_budget(
+        &self,
+        encrypted_blob: &[u8],
+        version_nonce: i64,
+        budget_categories: &[CategoryWithTempId],
+        user_public_budget_key: &[u8],
+    ) -> Result<BudgetFrame, DaoError>;
+
+    fn update_budget(
+        &self,
+        budget_id: Uuid,
+        edited_budget_data: &[u8],
+        version_nonce: i64,
+        expected_previous_version_nonce: i64,
+    ) -> Result<(), DaoError>;
+
+    #[allow(clippy::too_many_arguments)]
+    fn invite_user(
+        &self,
+        recipient_user_email: &str,
+        sender_public_key: &[u8],
+        encryption_key_encrypted: &[u8],
+        budget_info_encrypted: &[u8],
+        sender_info_encrypted: &[u8],
+        share_info_symmetric_key_encrypted: &[u8],
+        recipient_public_key_id_used_by_sender: Uuid,
+        recipient_public_key_id_used_by_server: Uuid,
+        budget_id: Uuid,
+        expiration: SystemTime,
+        read_only: bool,
+        budget_accept_key_id: Uuid,
+        budget_accept_key_id_encrypted: &[u8],
+        budget_accept_public_key: &[u8],
+        budget_accept_private_key_encrypted: &[u8],
+        budget_accept_key_info_encrypted: &[u8],
+    ) -> Result<InvitationId, DaoError>;
+
+    #[allow(clippy::too_many_arguments)]
+    fn accept_invitation(
+        &self,
+        accept_key_id: Uuid,
+        budget_id: Uuid,
+        read_only: bool,
+        invitation_id: Uuid,
+        recipient_user_email: &str,
+        recipient_budget_user_access_public_key: &[u8],
+    ) -> Result<BudgetIdAndEncryptionKey, DaoError>;
+
+    fn reject_invitation(
+        &self,
+        invitation_id: Uuid,
+        accept_key_id: Uuid,
+        recipient_user_email: &str,
+    ) -> Result<(), DaoError>;
+
+    fn delete_invitation(&self, invitation_id: Uuid) -> Result<(), DaoError>;
+
+    /// Returns the number of invitations deleted
+    fn delete_all_invitations_for_user(&self, user_email: &str) -> Result<usize, DaoError>;
+
+    fn delete_all_expired_invitations(&self) -> Result<(), DaoError>;
+
+    /// Invitation IDs are v7 UUIDs, so the time each invitation was sent can be read from its ID
+    fn get_pending_invitation_ids(&self, user_email: &str) -> Result<Vec<Uuid>, DaoError>;
+
+    fn get_all_pending_invitations(
+        &self,
+        user_email: &str,
+    ) -> Result<BudgetShareInviteList, DaoError>;
+
+    fn leave_budget(&self, budget_id: Uuid, key_id: Uuid) -> Result<(), DaoError>;
+
+    fn create_entry(
+        &self,
+        entry_id: Option<Uuid>,
+        encrypted_blob: &[u8],
+        version_nonce: i64,
+        category_id: Option<Uuid>,
+        budget_id: Uuid,
+    ) -> Result<Uuid, DaoError>;
+
+    #[allow(clippy::too_many_arguments)]
+    fn create_entry_and_category(
+        &self,
+        entry_id: Option<Uuid>,
+        entry_encrypted_blob: &[u8],
+        entry_version_nonce: i64,
+        category_id: Option<Uuid>,
+        category_encrypted_blob: &[u8],
+        category_version_nonce: i64,
+        budget_id: Uuid,
+    ) -> Result<EntryIdAndCategoryId, DaoError>;
+
+    fn update_entry(
+        &self,
+        entry_id: Uuid,
+        entry_encrypted_blob: &[u8],
+        version_nonce: i64,
+        expected_previous_version_nonce: i64,
+        category_id: Option<Uuid>,
+        budget_id: Uuid,
+    ) -> Result<(), DaoError>;
+
+    fn delete_entry(&self, entry_id: Uuid, budget_id: Uuid) -> Result<(), DaoError>;
+
+    fn create_category(
+        &self,
+        category_id: Option<Uuid>,
+        encrypted_blob: &[u8],
+        version_nonce: i64,
+        budget_id: Uuid,
+    ) -> Result<Uuid, DaoError>;
+
+    fn update_category(
+        &self,
+        category_id: Uuid,
+        category_encrypted_blob: &[u8],
+        version_nonce: i64,
+        expected_previous_version_nonce: i64,
+        budget_id: Uuid,
+    ) -> Result<(), DaoError>;
+
+    fn delete_category(&self, category_id: Uuid, budget_id: Uuid) -> Result<(), DaoError>;
+}
+
 pub struct Dao {
     db_thread_pool: DbThreadPool,
 }
@@ -41,8 +189,11 @@ impl Dao {
             db_thread_pool: db_thread_pool.clone(),
         }
     }
+}
 
-    pub fn get_public_budget_key(
+impl BudgetDao for Dao {
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn get_public_budget_key(
         &self,
         key_id: Uuid,
         budget_id: Uuid,
@@ -52,21 +203,18 @@ impl Dao {
             .get_result::<BudgetAccessKey>(&mut self.db_thread_pool.get()?)?)
     }
 
-    pub fn get_multiple_public_budget_keys(
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn get_multiple_public_budget_keys(
         &self,
         key_ids: &[Uuid],
         budget_ids: &[Uuid],
     ) -> Result<Vec<BudgetAccessKey>, DaoError> {
-        Ok(budget_access_keys
-            .filter(
-                budget_access_key_fields::key_id
-                    .eq_any(key_ids)
-                    .and(budget_access_key_fields::budget_id.eq_any(budget_ids)),
-            )
+        Ok(queries::multiple_public_budget_keys(key_ids, budget_ids)
             .get_results::<BudgetAccessKey>(&mut self.db_thread_pool.get()?)?)
     }
 
-    pub fn get_budget_accept_public_key(
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn get_budget_accept_public_key(
         &self,
         key_id: Uuid,
         budget_id: Uuid,
@@ -76,7 +224,8 @@ impl Dao {
             .get_result::<BudgetAcceptKey>(&mut self.db_thread_pool.get()?)?)
     }
 
-    pub fn get_budget_invite_sender_public_key(
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn get_budget_invite_sender_public_key(
         &self,
         invitation_id: Uuid,
     ) -> Result<Vec<u8>, DaoError> {
@@ -86,112 +235,48 @@ impl Dao {
             .get_result::<Vec<u8>>(&mut self.db_thread_pool.get()?)?)
     }
 
-    pub fn get_budget(&self, budget_id: Uuid) -> Result<BudgetMessage, DaoError> {
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn get_budget(&self, budget_id: Uuid) -> Result<BudgetMessage, DaoError> {
         let mut db_connection = self.db_thread_pool.get()?;
 
         let output_budget = db_connection
             .build_transaction()
             .run::<_, diesel::result::Error, _>(|conn| {
                 let budget = budgets.find(budget_id).get_result::<Budget>(conn)?;
-                let loaded_categories = Category::belonging_to(&budget).load::<Category>(conn)?;
-                let loaded_entries = Entry::belonging_to(&budget).load::<Entry>(conn)?;
+                let loaded_categories =
+                    queries::categories_of_budget(&budget).load::<Category>(conn)?;
+                let loaded_entries = queries::entries_of_budget(&budget).load::<Entry>(conn)?;
 
-                let category_messages = loaded_categories
-                    .into_iter()
-                    .map(|c| CategoryMessage {
-                        id: c.id.into(),
-                        budget_id: c.budget_id.into(),
-                        encrypted_blob: c.encrypted_blob,
-                        version_nonce: c.version_nonce,
-                        modified_timestamp: c.modified_timestamp.try_into().unwrap_or_default(),
-                    })
-                    .collect();
-
-                let entry_messages = loaded_entries
-                    .into_iter()
-                    .map(|e| EntryMessage {
-                        id: e.id.into(),
-                        budget_id: e.budget_id.into(),
-                        category_id: e.category_id.as_ref().map(UuidMessage::from),
-                        encrypted_blob: e.encrypted_blob,
-                        version_nonce: e.version_nonce,
-                        modified_timestamp: e.modified_timestamp.try_into().unwrap_or_default(),
-                    })
-                    .collect();
-
-                Ok(BudgetMessage {
-                    id: budget.id.into(),
-                    encrypted_blob: budget.encrypted_blob,
-                    categories: category_messages,
-                    entries: entry_messages,
-                    version_nonce: budget.version_nonce,
-                    modified_timestamp: budget.modified_timestamp.try_into().unwrap_or_default(),
-                })
+                Ok(budget_message(budget, loaded_categories, loaded_entries))
             })?;
 
         Ok(output_budget)
     }
 
-    pub fn get_multiple_budgets_by_id(&self, budget_ids: &[Uuid]) -> Result<BudgetList, DaoError> {
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn get_multiple_budgets_by_id(&self, budget_ids: &[Uuid]) -> Result<BudgetList, DaoError> {
         let mut db_connection = self.db_thread_pool.get()?;
 
         let output_budgets = db_connection
             .build_transaction()
             .run::<_, diesel::result::Error, _>(|conn| {
-                let loaded_budgets = budgets
-                    .filter(budget_fields::id.eq_any(budget_ids))
-                    .get_results::<Budget>(conn)?;
-                let loaded_categories = Category::belonging_to(&loaded_budgets)
+                let loaded_budgets =
+                    queries::budgets_by_id(budget_ids).get_results::<Budget>(conn)?;
+                let loaded_categories = queries::categories_of_budgets(&loaded_budgets)
                     .load::<Category>(conn)?
                     .grouped_by(&loaded_budgets);
-                let loaded_entries = Entry::belonging_to(&loaded_budgets)
+                let loaded_entries = queries::entries_of_budgets(&loaded_budgets)
                     .load::<Entry>(conn)?
                     .grouped_by(&loaded_budgets);
 
-                let zipped_budgets = loaded_budgets
+                let output_budgets = loaded_budgets
                     .into_iter()
-                    .zip(loaded_categories.into_iter())
-                    .zip(loaded_entries.into_iter());
-                let mut output_budgets = Vec::new();
-
-                for ((budget, budget_categories), budget_entries) in zipped_budgets {
-                    let category_messages = budget_categories
-                        .into_iter()
-                        .map(|c| CategoryMessage {
-                            id: c.id.into(),
-                            budget_id: c.budget_id.into(),
-                            encrypted_blob: c.encrypted_blob,
-                            version_nonce: c.version_nonce,
-                            modified_timestamp: c.modified_timestamp.try_into().unwrap_or_default(),
-                        })
-                        .collect();
-
-                    let entry_messages = budget_entries
-                        .into_iter()
-                        .map(|e| EntryMessage {
-                            id: e.id.into(),
-                            budget_id: e.budget_id.into(),
-                            category_id: e.category_id.as_ref().map(UuidMessage::from),
-                            encrypted_blob: e.encrypted_blob,
-                            version_nonce: e.version_nonce,
-                            modified_timestamp: e.modified_timestamp.try_into().unwrap_or_default(),
-                        })
-                        .collect();
-
-                    let output_budget = BudgetMessage {
-                        id: budget.id.into(),
-                        encrypted_blob: budget.encrypted_blob,
-                        version_nonce: budget.version_nonce,
-                        modified_timestamp: budget
-                            .modified_timestamp
-                            .try_into()
-                            .unwrap_or_default(),
-                        categories: category_messages,
-                        entries: entry_messages,
-                    };
-
-                    output_budgets.push(output_budget);
-                }
+                    .zip(loaded_categories)
+                    .zip(loaded_entries)
+                    .map(|((budget, budget_categories), budget_entries)| {
+                        budget_message(budget, budget_categories, budget_entries)
+                    })
+                    .collect();
 
                 Ok(output_budgets)
             })?;
@@ -201,7 +286,8 @@ impl Dao {
         })
     }
 
-    pub fn create_budget(
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn create_budget(
         &self,
         encrypted_blob: &[u8],
         version_nonce: i64,
@@ -230,9 +316,16 @@ impl Dao {
         let mut new_category_temp_ids = Vec::new();
 
         for category in budget_categories.iter() {
+            // Client-generated IDs are validated by the caller
+            let category_id = category
+                .id
+                .as_ref()
+                .and_then(|id| Uuid::try_from(id).ok())
+                .unwrap_or_else(Uuid::now_v7);
+
             let new_category = NewCategory {
                 budget_id,
-                id: Uuid::now_v7(),
+                id: category_id,
                 encrypted_blob: &category.encrypted_blob,
                 version_nonce: category.version_nonce,
                 modified_timestamp: current_time,
@@ -279,7 +372,8 @@ impl Dao {
         Ok(output_budget)
     }
 
-    pub fn update_budget(
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn update_budget(
         &self,
         budget_id: Uuid,
         edited_budget_data: &[u8],
@@ -288,50 +382,33 @@ impl Dao {
     ) -> Result<(), DaoError> {
         let mut db_connection = self.db_thread_pool.get()?;
 
-        db_connection
-            .build_transaction()
-            .repeatable_read()
-            .run::<_, DaoError, _>(|conn| {
-                let affected_row_count = dsl::update(
-                    budgets
-                        .find(budget_id)
-                        .filter(budget_fields::version_nonce.eq(expected_previous_version_nonce)),
-                )
-                .set((
-                    budget_fields::modified_timestamp.eq(dsl::now),
-                    budget_fields::encrypted_blob.eq(edited_budget_data),
-                    budget_fields::version_nonce.eq(version_nonce),
-                ))
-                .execute(conn)?;
-
-                if affected_row_count == 0 {
-                    // Check whether the update failed because the record wasn't found or because
-                    // the version_nonce was out-of-date
-                    let current_version_nonce = budgets
-                        .select(budget_fields::version_nonce)
-                        .find(budget_id)
-                        .first::<i64>(conn);
-
-                    match current_version_nonce {
-                        Ok(existing_nonce) => {
-                            if existing_nonce != expected_previous_version_nonce {
-                                return Err(DaoError::OutOfDate);
-                            }
-
-                            // This case should never happen because we filtered on version_nonce
-                            // in the update query
-                            unreachable!();
-                        }
-                        Err(e) => return Err(DaoError::from(e)),
-                    }
-                }
+        run_repeatable_read(&mut db_connection, |conn| {
+            let affected_row_count = queries::update_budget(
+                budget_id,
+                edited_budget_data,
+                version_nonce,
+                expected_previous_version_nonce,
+            )
+            .execute(conn)?;
 
-                Ok(())
-            })
+            if affected_row_count == 0 {
+                // Check whether the update failed because the record wasn't found or because
+                // the version_nonce was out-of-date
+                let current_version_nonce =
+                    queries::budget_version_nonce(budget_id).first::<i64>(conn)?;
+
+                return Err(out_of_date(
+                    current_version_nonce,
+                    expected_previous_version_nonce,
+                ));
+            }
+
+            Ok(())
+        })
     }
 
-    #[allow(clippy::too_many_arguments)]
-    pub fn invite_user(
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn invite_user(
         &self,
         recipient_user_email: &str,
         sender_public_key: &[u8],
@@ -402,8 +479,8 @@ impl Dao {
         })
     }
 
-    #[allow(clippy::too_many_arguments)]
-    pub fn accept_invitation(
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn accept_invitation(
         &self,
         accept_key_id: Uuid,
         budget_id: Uuid,
@@ -450,7 +527,8 @@ impl Dao {
     }
 
     // Used when the recipient deletes the invitation
-    pub fn reject_invitation(
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn reject_invitation(
         &self,
         invitation_id: Uuid,
         accept_key_id: Uuid,
@@ -480,13 +558,24 @@ impl Dao {
         Ok(())
     }
 
-    pub fn delete_invitation(&self, invitation_id: Uuid) -> Result<(), DaoError> {
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn delete_invitation(&self, invitation_id: Uuid) -> Result<(), DaoError> {
         diesel::delete(budget_share_invites.find(invitation_id))
             .execute(&mut self.db_thread_pool.get()?)?;
         Ok(())
     }
 
-    pub fn delete_all_expired_invitations(&self) -> Result<(), DaoError> {
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn delete_all_invitations_for_user(&self, user_email: &str) -> Result<usize, DaoError> {
+        Ok(diesel::delete(
+            budget_share_invites
+                .filter(budget_share_invite_fields::recipient_user_email.eq(user_email)),
+        )
+        .execute(&mut self.db_thread_pool.get()?)?)
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn delete_all_expired_invitations(&self) -> Result<(), DaoError> {
         let mut db_connection = self.db_thread_pool.get()?;
 
         // Not using a database transaction here because these can be deleted separately from
@@ -518,48 +607,31 @@ impl Dao {
         Ok(())
     }
 
-    pub fn get_all_pending_invitations(
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn get_pending_invitation_ids(&self, user_email: &str) -> Result<Vec<Uuid>, DaoError> {
+        Ok(budget_share_invites
+            .select(budget_share_invite_fields::id)
+            .filter(budget_share_invite_fields::recipient_user_email.eq(user_email))
+            .order(budget_share_invite_fields::id)
+            .load::<Uuid>(&mut self.db_thread_pool.get()?)?)
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn get_all_pending_invitations(
         &self,
         user_email: &str,
     ) -> Result<BudgetShareInviteList, DaoError> {
-        let invites = budget_share_invites
-            .select((
-                budget_share_invite_fields::id,
-                budget_share_invite_fields::budget_info_encrypted,
-                budget_share_invite_fields::sender_info_encrypted,
-                budget_share_invite_fields::share_info_symmetric_key_encrypted,
-                budget_share_invite_fields::budget_accept_key_info_encrypted,
-                budget_share_invite_fields::budget_accept_private_key_encrypted,
-                budget_share_invite_fields::budget_accept_key_id_encrypted,
-                budget_share_invite_fields::recipient_public_key_id_used_by_sender,
-                budget_share_invite_fields::recipient_public_key_id_used_by_server,
-            ))
-            .filter(budget_share_invite_fields::recipient_user_email.eq(user_email))
-            .load::<BudgetShareInvitePublicData>(&mut self.db_thread_pool.get()?)?;
-
-        let invites = invites
+        let invites = queries::pending_invitations(user_email)
+            .load::<BudgetShareInvitePublicData>(&mut self.db_thread_pool.get()?)?
             .into_iter()
-            .map(|i| BudgetShareInvite {
-                id: i.id.into(),
-                budget_accept_key_encrypted: i.budget_accept_key_encrypted,
-                budget_accept_key_id_encrypted: i.budget_accept_key_id_encrypted,
-                budget_info_encrypted: i.budget_info_encrypted,
-                sender_info_encrypted: i.sender_info_encrypted,
-                budget_accept_key_info_encrypted: i.budget_accept_key_info_encrypted,
-                share_info_symmetric_key_encrypted: i.share_info_symmetric_key_encrypted,
-                recipient_public_key_id_used_by_sender: i
-                    .recipient_public_key_id_used_by_sender
-                    .into(),
-                recipient_public_key_id_used_by_server: i
-                    .recipient_public_key_id_used_by_server
-                    .into(),
-            })
+            .map(invite_message)
             .collect();
 
         Ok(BudgetShareInviteList { invites })
     }
 
-    pub fn leave_budget(&self, budget_id: Uuid, key_id: Uuid) -> Result<(), DaoError> {
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn leave_budget(&self, budget_id: Uuid, key_id: Uuid) -> Result<(), DaoError> {
         let mut db_connection = self.db_thread_pool.get()?;
 
         db_connection
@@ -582,15 +654,17 @@ impl Dao {
         Ok(())
     }
 
-    pub fn create_entry(
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn create_entry(
         &self,
+        entry_id: Option<Uuid>,
         encrypted_blob: &[u8],
         version_nonce: i64,
         category_id: Option<Uuid>,
         budget_id: Uuid,
     ) -> Result<Uuid, DaoError> {
         let current_time = SystemTime::now();
-        let entry_id = Uuid::now_v7();
+        let entry_id = entry_id.unwrap_or_else(Uuid::now_v7);
 
         let new_entry = NewEntry {
             id: entry_id,
@@ -601,24 +675,35 @@ impl Dao {
             modified_timestamp: current_time,
         };
 
-        dsl::insert_into(entries)
-            .values(&new_entry)
-            .execute(&mut self.db_thread_pool.get()?)?;
+        let mut db_connection = self.db_thread_pool.get()?;
+
+        db_connection
+            .build_transaction()
+            .run::<_, diesel::result::Error, _>(|conn| {
+                if let Some(category_id) = category_id {
+                    queries::category_in_budget(category_id, budget_id).get_result::<Uuid>(conn)?;
+                }
+
+                dsl::insert_into(entries).values(&new_entry).execute(conn)
+            })?;
 
         Ok(entry_id)
     }
 
-    pub fn create_entry_and_category(
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn create_entry_and_category(
         &self,
+        entry_id: Option<Uuid>,
         entry_encrypted_blob: &[u8],
         entry_version_nonce: i64,
+        category_id: Option<Uuid>,
         category_encrypted_blob: &[u8],
         category_version_nonce: i64,
         budget_id: Uuid,
     ) -> Result<EntryIdAndCategoryId, DaoError> {
         let current_time = SystemTime::now();
-        let category_id = Uuid::now_v7();
-        let entry_id = Uuid::now_v7();
+        let category_id = category_id.unwrap_or_else(Uuid::now_v7);
+        let entry_id = entry_id.unwrap_or_else(Uuid::now_v7);
 
         let new_category = NewCategory {
             id: category_id,
@@ -657,7 +742,8 @@ impl Dao {
         })
     }
 
-    pub fn update_entry(
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn update_entry(
         &self,
         entry_id: Uuid,
         entry_encrypted_blob: &[u8],
@@ -668,69 +754,50 @@ impl Dao {
     ) -> Result<(), DaoError> {
         let mut db_connection = self.db_thread_pool.get()?;
 
-        db_connection
-            .build_transaction()
-            .repeatable_read()
-            .run::<_, DaoError, _>(|conn| {
-                let affected_row_count = diesel::update(
-                    entries
-                        .find(entry_id)
-                        .filter(entry_fields::budget_id.eq(budget_id))
-                        .filter(entry_fields::version_nonce.eq(expected_previous_version_nonce)),
-                )
-                .set((
-                    entry_fields::category_id.eq(category_id),
-                    entry_fields::encrypted_blob.eq(entry_encrypted_blob),
-                    entry_fields::version_nonce.eq(version_nonce),
-                    entry_fields::modified_timestamp.eq(dsl::now),
-                ))
-                .execute(conn)?;
-
-                if affected_row_count == 0 {
-                    // Check whether the update failed because the record wasn't found or because
-                    // the version_nonce was out-of-date
-                    let current_version_nonce = entries
-                        .select(entry_fields::version_nonce)
-                        .find(entry_id)
-                        .first::<i64>(conn);
-
-                    match current_version_nonce {
-                        Ok(existing_nonce) => {
-                            if existing_nonce != expected_previous_version_nonce {
-                                return Err(DaoError::OutOfDate);
-                            }
-
-                            // This case should never happen because we filtered on version_nonce
-                            // in the update query
-                            unreachable!();
-                        }
-                        Err(e) => return Err(DaoError::from(e)),
-                    }
-                }
+        run_repeatable_read(&mut db_connection, |conn| {
+            let affected_row_count = queries::update_entry(
+                entry_id,
+                entry_encrypted_blob,
+                version_nonce,
+                expected_previous_version_nonce,
+                category_id,
+                budget_id,
+            )
+            .execute(conn)?;
 
-                Ok(())
-            })
+            if affected_row_count == 0 {
+                // Check whether the update failed because the record wasn't found or because
+                // the version_nonce was out-of-date
+                let current_version_nonce =
+                    queries::entry_version_nonce(entry_id, budget_id).first::<i64>(conn)?;
+
+                return Err(out_of_date(
+                    current_version_nonce,
+                    expected_previous_version_nonce,
+                ));
+            }
+
+            Ok(())
+        })
     }
 
-    pub fn delete_entry(&self, entry_id: Uuid, budget_id: Uuid) -> Result<(), DaoError> {
-        diesel::delete(
-            entries
-                .find(entry_id)
-                .filter(entry_fields::budget_id.eq(budget_id)),
-        )
-        .execute(&mut self.db_thread_pool.get()?)?;
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn delete_entry(&self, entry_id: Uuid, budget_id: Uuid) -> Result<(), DaoError> {
+        queries::delete_entry(entry_id, budget_id).execute(&mut self.db_thread_pool.get()?)?;
 
         Ok(())
     }
 
-    pub fn create_category(
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn create_category(
         &self,
+        category_id: Option<Uuid>,
         encrypted_blob: &[u8],
         version_nonce: i64,
         budget_id: Uuid,
     ) -> Result<Uuid, DaoError> {
         let current_time = SystemTime::now();
-        let category_id = Uuid::now_v7();
+        let category_id = category_id.unwrap_or_else(Uuid::now_v7);
 
         let new_category = NewCategory {
             id: category_id,
@@ -747,7 +814,8 @@ impl Dao {
         Ok(category_id)
     }
 
-    pub fn update_category(
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn update_category(
         &self,
         category_id: Uuid,
         category_encrypted_blob: &[u8],
@@ -757,57 +825,485 @@ impl Dao {
     ) -> Result<(), DaoError> {
         let mut db_connection = self.db_thread_pool.get()?;
 
-        db_connection
-            .build_transaction()
-            .repeatable_read()
-            .run::<_, DaoError, _>(|conn| {
-                let affected_row_count = diesel::update(
-                    categories
-                        .find(category_id)
-                        .filter(category_fields::budget_id.eq(budget_id))
-                        .filter(category_fields::version_nonce.eq(expected_previous_version_nonce)),
-                )
-                .set((
-                    category_fields::encrypted_blob.eq(category_encrypted_blob),
-                    category_fields::version_nonce.eq(version_nonce),
-                    category_fields::modified_timestamp.eq(dsl::now),
-                ))
-                .execute(conn)?;
-
-                if affected_row_count == 0 {
-                    // Check whether the update failed because the record wasn't found or because
-                    // the version_nonce was out-of-date
-                    let current_version_nonce = categories
-                        .select(category_fields::version_nonce)
-                        .find(category_id)
-                        .first::<i64>(conn);
-
-                    match current_version_nonce {
-                        Ok(existing_nonce) => {
-                            if existing_nonce != expected_previous_version_nonce {
-                                return Err(DaoError::OutOfDate);
-                            }
-
-                            // This case should never happen because we filtered on version_nonce
-                            // in the update query
-                            unreachable!();
-                        }
-                        Err(e) => return Err(DaoError::from(e)),
-                    }
-                }
+        run_repeatable_read(&mut db_connection, |conn| {
+            let affected_row_count = queries::update_category(
+                category_id,
+                category_encrypted_blob,
+                version_nonce,
+                expected_previous_version_nonce,
+                budget_id,
+            )
+            .execute(conn)?;
 
-                Ok(())
-            })
+            if affected_row_count == 0 {
+                // Check whether the update failed because the record wasn't found or because
+                // the version_nonce was out-of-date
+                let current_version_nonce =
+                    queries::category_version_nonce(category_id, budget_id).first::<i64>(conn)?;
+
+                return Err(out_of_date(
+                    current_version_nonce,
+                    expected_previous_version_nonce,
+                ));
+            }
+
+            Ok(())
+        })
     }
 
-    pub fn delete_category(&self, category_id: Uuid, budget_id: Uuid) -> Result<(), DaoError> {
-        diesel::delete(
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn delete_category(&self, category_id: Uuid, budget_id: Uuid) -> Result<(), DaoError> {
+        queries::delete_category(category_id, budget_id)
+            .execute(&mut self.db_thread_pool.get()?)?;
+
+        Ok(())
+    }
+}
+
+pub(crate) fn out_of_date(existing_nonce: i64, expected_previous_version_nonce: i64) -> DaoError {
+    // This case should never happen because the update query filters on version_nonce
+    assert_ne!(existing_nonce, expected_previous_version_nonce);
+    DaoError::OutOfDate(Some(existing_nonce))
+}
+
+pub(crate) fn budget_message(
+    budget: Budget,
+    budget_categories: Vec<Category>,
+    budget_entries: Vec<Entry>,
+) -> BudgetMessage {
+    let category_messages = budget_categories
+        .into_iter()
+        .map(|c| CategoryMessage {
+            id: c.id.into(),
+            budget_id: c.budget_id.into(),
+            encrypted_blob: c.encrypted_blob,
+            version_nonce: c.version_nonce,
+            modified_timestamp: c.modified_timestamp.try_into().unwrap_or_default(),
+        })
+        .collect();
+
+    let entry_messages = budget_entries
+        .into_iter()
+        .map(|e| EntryMessage {
+            id: e.id.into(),
+            budget_id: e.budget_id.into(),
+            category_id: e.category_id.as_ref().map(UuidMessage::from),
+            encrypted_blob: e.encrypted_blob,
+            version_nonce: e.version_nonce,
+            modified_timestamp: e.modified_timestamp.try_into().unwrap_or_default(),
+        })
+        .collect();
+
+    BudgetMessage {
+        id: budget.id.into(),
+        encrypted_blob: budget.encrypted_blob,
+        version_nonce: budget.version_nonce,
+        modified_timestamp: budget.modified_timestamp.try_into().unwrap_or_default(),
+        categories: category_messages,
+        entries: entry_messages,
+    }
+}
+
+pub(crate) fn invite_message(invite: BudgetShareInvitePublicData) -> BudgetShareInvite {
+    BudgetShareInvite {
+        id: invite.id.into(),
+        budget_accept_key_encrypted: invite.budget_accept_key_encrypted,
+        budget_accept_key_id_encrypted: invite.budget_accept_key_id_encrypted,
+        budget_info_encrypted: invite.budget_info_encrypted,
+        sender_info_encrypted: invite.sender_info_encrypted,
+        budget_accept_key_info_encrypted: invite.budget_accept_key_info_encrypted,
+        share_info_symmetric_key_encrypted: invite.share_info_symmetric_key_encrypted,
+        recipient_public_key_id_used_by_sender: invite
+            .recipient_public_key_id_used_by_sender
+            .into(),
+        recipient_public_key_id_used_by_server: invite
+            .recipient_public_key_id_used_by_server
+            .into(),
+    }
+}
+
+/// Statements shared by the blocking DAO above and the async DAO in
+/// [`crate::db::nonblocking::budget`]. Diesel builds the same query for either connection type,
+/// so only running it differs between the two.
+pub(crate) mod queries {
+    use diesel::{dsl, BelongingToDsl, BoolExpressionMethods, ExpressionMethods, QueryDsl};
+    use uuid::Uuid;
+
+    use crate::models::budget::Budget;
+    use crate::models::category::Category;
+    use crate::models::entry::Entry;
+    use crate::schema::budget_access_keys as budget_access_key_fields;
+    use crate::schema::budget_access_keys::dsl::budget_access_keys;
+    use crate::schema::budget_share_invites as budget_share_invite_fields;
+    use crate::schema::budget_share_invites::dsl::budget_share_invites;
+    use crate::schema::budgets as budget_fields;
+    use crate::schema::budgets::dsl::budgets;
+    use crate::schema::categories as category_fields;
+    use crate::schema::categories::dsl::categories;
+    use crate::schema::entries as entry_fields;
+    use crate::schema::entries::dsl::entries;
+
+    #[dsl::auto_type(no_type_alias)]
+    pub fn multiple_public_budget_keys<'a>(key_ids: &'a [Uuid], budget_ids: &'a [Uuid]) -> _ {
+        budget_access_keys.filter(
+            budget_access_key_fields::key_id
+                .eq_any(key_ids)
+                .and(budget_access_key_fields::budget_id.eq_any(budget_ids)),
+        )
+    }
+
+    // Ordered so the same data always produces the same response, which ETags depend on
+    #[dsl::auto_type(no_type_alias)]
+    pub fn budgets_by_id<'a>(budget_ids: &'a [Uuid]) -> _ {
+        budgets
+            .filter(budget_fields::id.eq_any(budget_ids))
+            .order(budget_fields::id)
+    }
+
+    pub fn categories_of_budget(
+        budget: &Budget,
+    ) -> dsl::Order<<Category as BelongingToDsl<&Budget>>::Output, category_fields::id> {
+        Category::belonging_to(budget).order(category_fields::id)
+    }
+
+    pub fn entries_of_budget(
+        budget: &Budget,
+    ) -> dsl::Order<<Entry as BelongingToDsl<&Budget>>::Output, entry_fields::id> {
+        Entry::belonging_to(budget).order(entry_fields::id)
+    }
+
+    pub fn categories_of_budgets(
+        loaded_budgets: &[Budget],
+    ) -> dsl::Order<<Category as BelongingToDsl<&[Budget]>>::Output, category_fields::id> {
+        Category::belonging_to(loaded_budgets).order(category_fields::id)
+    }
+
+    pub fn entries_of_budgets(
+        loaded_budgets: &[Budget],
+    ) -> dsl::Order<<Entry as BelongingToDsl<&[Budget]>>::Output, entry_fields::id> {
+        Entry::belonging_to(loaded_budgets).order(entry_fields::id)
+    }
+
+    #[dsl::auto_type(no_type_alias)]
+    pub fn pending_invitations<'a>(user_email: &'a str) -> _ {
+        budget_share_invites
+            .select((
+                budget_share_invite_fields::id,
+                budget_share_invite_fields::budget_info_encrypted,
+                budget_share_invite_fields::sender_info_encrypted,
+                budget_share_invite_fields::share_info_symmetric_key_encrypted,
+                budget_share_invite_fields::budget_accept_key_info_encrypted,
+                budget_share_invite_fields::budget_accept_private_key_encrypted,
+                budget_share_invite_fields::budget_accept_key_id_encrypted,
+                budget_share_invite_fields::recipient_public_key_id_used_by_sender,
+                budget_share_invite_fields::recipient_public_key_id_used_by_server,
+            ))
+            .filter(budget_share_invite_fields::recipient_user_email.eq(user_email))
+    }
+
+    pub fn update_budget(
+        budget_id: Uuid,
+        edited_budget_data: &[u8],
+        version_nonce: i64,
+        expected_previous_version_nonce: i64,
+    ) -> dsl::Update<budget_at_version, budget_changes<'_>> {
+        diesel::update(budget_at_version(
+            budget_id,
+            expected_previous_version_nonce,
+        ))
+        .set(budget_changes(edited_budget_data, version_nonce))
+    }
+
+    // Auto-typed update statements aren't supported, so `update_budget` names its type from
+    // these two halves
+
+    #[dsl::auto_type]
+    fn budget_at_version(budget_id: Uuid, expected_previous_version_nonce: i64) -> _ {
+        budgets
+            .find(budget_id)
+            .filter(budget_fields::version_nonce.eq(expected_previous_version_nonce))
+    }
+
+    #[dsl::auto_type]
+    fn budget_changes<'a>(edited_budget_data: &'a [u8], version_nonce: i64) -> _ {
+        (
+            budget_fields::modified_timestamp.eq(dsl::now),
+            budget_fields::encrypted_blob.eq(edited_budget_data),
+            budget_fields::version_nonce.eq(version_nonce),
+        )
+    }
+
+    #[dsl::auto_type(no_type_alias)]
+    pub fn budget_version_nonce(budget_id: Uuid) -> _ {
+        budgets.select(budget_fields::version_nonce).find(budget_id)
+    }
+
+    /// The foreign key only ensures an entry's category exists, not that it is in the entry's
+    /// budget
+    #[dsl::auto_type(no_type_alias)]
+    pub fn category_in_budget(category_id: Uuid, budget_id: Uuid) -> _ {
+        categories
+            .select(category_fields::id)
+            .find(category_id)
+            .filter(category_fields::budget_id.eq(budget_id))
+    }
+
+    pub fn update_entry(
+        entry_id: Uuid,
+        entry_encrypted_blob: &[u8],
+        version_nonce: i64,
+        expected_previous_version_nonce: i64,
+        category_id: Option<Uuid>,
+        budget_id: Uuid,
+    ) -> dsl::Update<entry_at_version, entry_changes<'_>> {
+        diesel::update(entry_at_version(
+            entry_id,
+            budget_id,
+            expected_previous_version_nonce,
+        ))
+        .set(entry_changes(
+            category_id,
+            entry_encrypted_blob,
+            version_nonce,
+        ))
+    }
+
+    #[dsl::auto_type]
+    fn entry_at_version(
+        entry_id: Uuid,
+        budget_id: Uuid,
+        expected_previous_version_nonce: i64,
+    ) -> _ {
+        entries
+            .find(entry_id)
+            .filter(entry_fields::budget_id.eq(budget_id))
+            .filter(entry_fields::version_nonce.eq(expected_previous_version_nonce))
+    }
+
+    #[dsl::auto_type]
+    fn entry_changes<'a>(
+        category_id: Option<Uuid>,
+        entry_encrypted_blob: &'a [u8],
+        version_nonce: i64,
+    ) -> _ {
+        (
+            entry_fields::category_id.eq(category_id),
+            entry_fields::encrypted_blob.eq(entry_encrypted_blob),
+            entry_fields::version_nonce.eq(version_nonce),
+            entry_fields::modified_timestamp.eq(dsl::now),
+        )
+    }
+
+    /// Filtered on the budget so that a failed update of another budget's entry is reported as
+    /// not found rather than out of date
+    #[dsl::auto_type(no_type_alias)]
+    pub fn entry_version_nonce(entry_id: Uuid, budget_id: Uuid) -> _ {
+        entries
+            .select(entry_fields::version_nonce)
+            .find(entry_id)
+            .filter(entry_fields::budget_id.eq(budget_id))
+    }
+
+    #[dsl::auto_type(no_type_alias)]
+    pub fn delete_entry(entry_id: Uuid, budget_id: Uuid) -> _ {
+        dsl::delete(
+            entries
+                .find(entry_id)
+                .filter(entry_fields::budget_id.eq(budget_id)),
+        )
+    }
+
+    pub fn update_category(
+        category_id: Uuid,
+        category_encrypted_blob: &[u8],
+        version_nonce: i64,
+        expected_previous_version_nonce: i64,
+        budget_id: Uuid,
+    ) -> dsl::Update<category_at_version, category_changes<'_>> {
+        diesel::update(category_at_version(
+            category_id,
+            budget_id,
+            expected_previous_version_nonce,
+        ))
+        .set(category_changes(category_encrypted_blob, version_nonce))
+    }
+
+    #[dsl::auto_type]
+    fn category_at_version(
+        category_id: Uuid,
+        budget_id: Uuid,
+        expected_previous_version_nonce: i64,
+    ) -> _ {
+        categories
+            .find(category_id)
+            .filter(category_fields::budget_id.eq(budget_id))
+            .filter(category_fields::version_nonce.eq(expected_previous_version_nonce))
+    }
+
+    #[dsl::auto_type]
+    fn category_changes<'a>(category_encrypted_blob: &'a [u8], version_nonce: i64) -> _ {
+        (
+            category_fields::encrypted_blob.eq(category_encrypted_blob),
+            category_fields::version_nonce.eq(version_nonce),
+            category_fields::modified_timestamp.eq(dsl::now),
+        )
+    }
+
+    #[dsl::auto_type(no_type_alias)]
+    pub fn category_version_nonce(category_id: Uuid, budget_id: Uuid) -> _ {
+        categories
+            .select(category_fields::version_nonce)
+            .find(category_id)
+            .filter(category_fields::budget_id.eq(budget_id))
+    }
+
+    #[dsl::auto_type(no_type_alias)]
+    pub fn delete_category(category_id: Uuid, budget_id: Uuid) -> _ {
+        dsl::delete(
             categories
                 .find(category_id)
                 .filter(category_fields::budget_id.eq(budget_id)),
         )
-        .execute(&mut self.db_thread_pool.get()?)?;
+    }
+}
 
-        Ok(())
+/// Behavior that every `BudgetDao` implementation must share. Each backend runs
+/// [`run_budget_dao_tests`](testing::run_budget_dao_tests) against a fresh DAO from its own tests.
+#[cfg(test)]
+pub(crate) mod testing {
+    use super::BudgetDao;
+
+    use crate::db::DaoError;
+
+    use diesel::result::Error as DieselError;
+    use uuid::Uuid;
+
+    pub fn run_budget_dao_tests(dao: &dyn BudgetDao) {
+        test_update_entry_checks_version_nonce(dao);
+        test_update_category_checks_version_nonce(dao);
+        test_delete_category_clears_entry_categories(dao);
+        test_leaving_budget_deletes_abandoned_budget(dao);
+    }
+
+    fn create_budget(dao: &dyn BudgetDao) -> (Uuid, Uuid) {
+        let budget = dao.create_budget(&[0; 32], 1, &[], &[0; 32]).unwrap();
+
+        (
+            (&budget.id).try_into().unwrap(),
+            (&budget.access_key_id).try_into().unwrap(),
+        )
+    }
+
+    fn test_update_entry_checks_version_nonce(dao: &dyn BudgetDao) {
+        let (budget_id, _) = create_budget(dao);
+        let entry_id = dao
+            .create_entry(None, &[1; 8], 10, None, budget_id)
+            .unwrap();
+
+        assert!(matches!(
+            dao.update_entry(entry_id, &[2; 8], 11, 9, None, budget_id),
+            Err(DaoError::OutOfDate(Some(10)))
+        ));
+
+        dao.update_entry(entry_id, &[2; 8], 11, 10, None, budget_id)
+            .unwrap();
+
+        // Another budget's entry is missing, whatever the expected version_nonce
+        assert!(matches!(
+            dao.update_entry(entry_id, &[3; 8], 12, 11, None, Uuid::now_v7()),
+            Err(DaoError::QueryFailure(DieselError::NotFound))
+        ));
+        assert!(matches!(
+            dao.update_entry(entry_id, &[3; 8], 12, 9, None, Uuid::now_v7()),
+            Err(DaoError::QueryFailure(DieselError::NotFound))
+        ));
+
+        let budget = dao.get_budget(budget_id).unwrap();
+        assert_eq!(budget.entries[0].encrypted_blob, vec![2; 8]);
+        assert_eq!(budget.entries[0].version_nonce, 11);
+    }
+
+    fn test_update_category_checks_version_nonce(dao: &dyn BudgetDao) {
+        let (budget_id, _) = create_budget(dao);
+        let category_id = dao.create_category(None, &[1; 8], 10, budget_id).unwrap();
+
+        assert!(matches!(
+            dao.update_category(category_id, &[2; 8], 11, 9, budget_id),
+            Err(DaoError::OutOfDate(Some(10)))
+        ));
+
+        dao.update_category(category_id, &[2; 8], 11, 10, budget_id)
+            .unwrap();
+
+        assert!(matches!(
+            dao.update_category(category_id, &[3; 8], 12, 11, Uuid::now_v7()),
+            Err(DaoError::QueryFailure(DieselError::NotFound))
+        ));
+        assert!(matches!(
+            dao.update_category(category_id, &[3; 8], 12, 9, Uuid::now_v7()),
+            Err(DaoError::QueryFailure(DieselError::NotFound))
+        ));
+
+        let budget = dao.get_budget(budget_id).unwrap();
+        assert_eq!(budget.categories[0].encrypted_blob, vec![2; 8]);
+        assert_eq!(budget.categories[0].version_nonce, 11);
+    }
+
+    fn test_delete_category_clears_entry_categories(dao: &dyn BudgetDao) {
+        let (budget_id, _) = create_budget(dao);
+        let category_id = dao.create_category(None, &[1; 8], 1, budget_id).unwrap();
+        dao.create_entry(None, &[1; 8], 1, Some(category_id), budget_id)
+            .unwrap();
+
+        dao.delete_category(category_id, budget_id).unwrap();
+
+        let budget = dao.get_budget(budget_id).unwrap();
+        assert!(budget.categories.is_empty());
+        assert_eq!(budget.entries.len(), 1);
+        assert!(budget.entries[0].category_id.is_none());
+    }
+
+    fn test_leaving_budget_deletes_abandoned_budget(dao: &dyn BudgetDao) {
+        let (budget_id, key_id) = create_budget(dao);
+        let entry_id = dao.create_entry(None, &[1; 8], 1, None, budget_id).unwrap();
+
+        dao.leave_budget(budget_id, key_id).unwrap();
+
+        assert!(matches!(
+            dao.get_budget(budget_id),
+            Err(DaoError::QueryFailure(DieselError::NotFound))
+        ));
+
+        // An entry left behind would still accept an update with its current version_nonce
+        assert!(matches!(
+            dao.update_entry(entry_id, &[2; 8], 2, 1, None, budget_id),
+            Err(DaoError::QueryFailure(DieselError::NotFound))
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::config::ConfigSource;
+    use crate::db::create_db_thread_pool;
+
+    use std::time::Duration;
+
+    #[test]
+    fn test_budget_dao() {
+        let source = ConfigSource::env_only();
+        let db_uri = format!(
+            "postgres://{}:{}@{}:{}/{}",
+            source.get::<String>("ENTRIES_DB_USERNAME").unwrap(),
+            source.get_secret::<String>("ENTRIES_DB_PASSWORD").unwrap(),
+            source.get::<String>("ENTRIES_DB_HOSTNAME").unwrap(),
+            source.get::<u16>("ENTRIES_DB_PORT").unwrap(),
+            source.get::<String>("ENTRIES_DB_NAME").unwrap(),
+        );
+        let db_thread_pool = create_db_thread_pool(&db_uri, 1, Duration::from_secs(30), None);
+
+        testing::run_budget_dao_tests(&Dao::new(&db_thread_pool));
     }
 }
@@ -0,0 +1,43 @@
+use diesel::{sql_query, RunQueryDsl};
+use diesel_migrations::MigrationHarness;
+
+use crate::db::{DaoError, DbThreadPool};
+
+/// Queries the readiness check runs to confirm the database is usable
+pub trait HealthDao: Send + Sync {
+    fn check_connection(&self) -> Result<(), DaoError>;
+
+    /// Returns the version of the newest migration applied to the database
+    fn get_latest_applied_migration_version(&self) -> Result<Option<String>, DaoError>;
+}
+
+pub struct Dao {
+    db_thread_pool: DbThreadPool,
+}
+
+impl Dao {
+    pub fn new(db_thread_pool: &DbThreadPool) -> Self {
+        Self {
+            db_thread_pool: db_thread_pool.clone(),
+        }
+    }
+}
+
+impl HealthDao for Dao {
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn check_connection(&self) -> Result<(), DaoError> {
+        sql_query("SELECT 1").execute(&mut self.db_thread_pool.get()?)?;
+        Ok(())
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn get_latest_applied_migration_version(&self) -> Result<Option<String>, DaoError> {
+        let applied = self
+            .db_thread_pool
+            .get()?
+            .applied_migrations()
+            .map_err(DaoError::MigrationFailure)?;
+
+        Ok(applied.iter().map(|v| v.to_string()).max())
+    }
+}
@@ -0,0 +1,153 @@
+use diesel::{dsl, BoolExpressionMethods, ExpressionMethods, QueryDsl, RunQueryDsl};
+use std::time::{Duration, SystemTime};
+use uuid::Uuid;
+
+use crate::db::{DaoError, DbThreadPool};
+use crate::models::idempotency_key::{IdempotencyKey, NewIdempotencyKey};
+use crate::schema::idempotency_keys as idempotency_key_fields;
+use crate::schema::idempotency_keys::dsl::idempotency_keys;
+
+/// How long a reservation can go without a response before it is assumed to have been abandoned
+/// (e.g. because the server was killed while handling the request) and the key is free to be used
+/// again. This is well beyond the time any request should take to handle.
+pub const RESERVATION_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+pub enum KeyReservation {
+    /// The key hadn't been used. The caller should handle the request, then either save the
+    /// response or release the key.
+    Reserved,
+    /// The key was used before. The response is missing if the earlier request is still being
+    /// handled.
+    Existing(IdempotencyKey),
+}
+
+/// Queries for the responses saved for requests sent with an `Idempotency-Key` header
+pub trait IdempotencyDao: Send + Sync {
+    fn reserve_key(
+        &self,
+        user_id: Uuid,
+        key: &str,
+        request_hash: &[u8],
+        expiration: SystemTime,
+    ) -> Result<KeyReservation, DaoError>;
+
+    fn save_response(
+        &self,
+        user_id: Uuid,
+        key: &str,
+        status: i16,
+        content_type: Option<&str>,
+        body: &[u8],
+    ) -> Result<(), DaoError>;
+
+    /// Frees a reserved key without saving a response so the request can be tried again
+    fn release_key(&self, user_id: Uuid, key: &str) -> Result<(), DaoError>;
+
+    fn delete_all_expired_keys(&self) -> Result<usize, DaoError>;
+}
+
+pub struct Dao {
+    db_thread_pool: DbThreadPool,
+}
+
+impl Dao {
+    pub fn new(db_thread_pool: &DbThreadPool) -> Self {
+        Self {
+            db_thread_pool: db_thread_pool.clone(),
+        }
+    }
+}
+
+impl IdempotencyDao for Dao {
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn reserve_key(
+        &self,
+        user_id: Uuid,
+        key: &str,
+        request_hash: &[u8],
+        expiration: SystemTime,
+    ) -> Result<KeyReservation, DaoError> {
+        let now = SystemTime::now();
+        let new_key = NewIdempotencyKey {
+            user_id,
+            key,
+            request_hash,
+            reserved_at: now,
+            expiration,
+        };
+
+        let mut db_connection = self.db_thread_pool.get()?;
+
+        db_connection
+            .build_transaction()
+            .run::<_, DaoError, _>(|conn| {
+                // An expired key that hasn't been purged yet or an abandoned reservation is free
+                // to be used again
+                diesel::delete(
+                    idempotency_keys.find((user_id, key)).filter(
+                        idempotency_key_fields::expiration.le(now).or(
+                            idempotency_key_fields::response_status.is_null().and(
+                                idempotency_key_fields::reserved_at.le(now - RESERVATION_TIMEOUT),
+                            ),
+                        ),
+                    ),
+                )
+                .execute(conn)?;
+
+                let inserted_count = dsl::insert_into(idempotency_keys)
+                    .values(&new_key)
+                    .on_conflict_do_nothing()
+                    .execute(conn)?;
+
+                if inserted_count == 1 {
+                    return Ok(KeyReservation::Reserved);
+                }
+
+                let existing = idempotency_keys
+                    .find((user_id, key))
+                    .get_result::<IdempotencyKey>(conn)?;
+
+                Ok(KeyReservation::Existing(existing))
+            })
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn save_response(
+        &self,
+        user_id: Uuid,
+        key: &str,
+        status: i16,
+        content_type: Option<&str>,
+        body: &[u8],
+    ) -> Result<(), DaoError> {
+        dsl::update(idempotency_keys.find((user_id, key)))
+            .set((
+                idempotency_key_fields::response_status.eq(status),
+                idempotency_key_fields::response_content_type.eq(content_type),
+                idempotency_key_fields::response_body.eq(body),
+            ))
+            .execute(&mut self.db_thread_pool.get()?)?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn release_key(&self, user_id: Uuid, key: &str) -> Result<(), DaoError> {
+        diesel::delete(
+            idempotency_keys
+                .find((user_id, key))
+                .filter(idempotency_key_fields::response_status.is_null()),
+        )
+        .execute(&mut self.db_thread_pool.get()?)?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn delete_all_expired_keys(&self) -> Result<usize, DaoError> {
+        Ok(diesel::delete(
+            idempotency_keys.filter(idempotency_key_fields::expiration.le(SystemTime::now())),
+        )
+        .execute(&mut self.db_thread_pool.get()?)?)
+    }
+}
@@ -6,6 +6,20 @@ use crate::models::job_registry_item::NewJobRegistryItem;
 use crate::schema::job_registry as job_registry_fields;
 use crate::schema::job_registry::dsl::job_registry;
 
+/// Queries for when each job in the job scheduler last ran
+pub trait JobRegistryDao: Send + Sync {
+    fn get_job_last_run_timestamp(&self, name: &str) -> Result<Option<SystemTime>, DaoError>;
+
+    /// Returns each job's name and last run time, ordered by name
+    fn get_all_job_last_run_timestamps(&self) -> Result<Vec<(String, SystemTime)>, DaoError>;
+
+    fn set_job_last_run_timestamp(
+        &self,
+        job_name: &str,
+        timestamp: SystemTime,
+    ) -> Result<(), DaoError>;
+}
+
 pub struct Dao {
     db_thread_pool: DbThreadPool,
 }
@@ -16,8 +30,11 @@ impl Dao {
             db_thread_pool: db_thread_pool.clone(),
         }
     }
+}
 
-    pub fn get_job_last_run_timestamp(&self, name: &str) -> Result<Option<SystemTime>, DaoError> {
+impl JobRegistryDao for Dao {
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn get_job_last_run_timestamp(&self, name: &str) -> Result<Option<SystemTime>, DaoError> {
         Ok(job_registry
             .select(job_registry_fields::last_run_timestamp)
             .find(name)
@@ -25,7 +42,19 @@ impl Dao {
             .optional()?)
     }
 
-    pub fn set_job_last_run_timestamp(
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn get_all_job_last_run_timestamps(&self) -> Result<Vec<(String, SystemTime)>, DaoError> {
+        Ok(job_registry
+            .select((
+                job_registry_fields::job_name,
+                job_registry_fields::last_run_timestamp,
+            ))
+            .order(job_registry_fields::job_name)
+            .load(&mut self.db_thread_pool.get()?)?)
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn set_job_last_run_timestamp(
         &self,
         job_name: &str,
         timestamp: SystemTime,
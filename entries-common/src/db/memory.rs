@@ -0,0 +1,1843 @@
+//! A DAO that keeps everything in memory rather than in Postgres, so handlers and jobs can be
+//! tested without a database.
+//!
+//! It implements the same traits as the Postgres DAOs and mirrors their behavior, including the
+//! errors they return: `NotFound` for missing rows, unique and foreign key violations,
+//! `OutOfDate` when a `version_nonce` doesn't match, and the cascading deletes set up by the
+//! migrations. Clones share the same data.
+
+use async_trait::async_trait;
+use diesel::result::{DatabaseErrorInformation, DatabaseErrorKind, Error as DieselError};
+use rand::{rngs::OsRng, Rng};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use uuid::Uuid;
+
+use crate::db::auth::{AuthDao, UserAuthStringHashAndStatus};
+use crate::db::budget::{budget_message, BudgetDao};
+use crate::db::health::HealthDao;
+use crate::db::idempotency::{IdempotencyDao, KeyReservation, RESERVATION_TIMEOUT};
+use crate::db::job_registry::JobRegistryDao;
+use crate::db::nonblocking::budget::AsyncBudgetDao;
+use crate::db::user::{UserDao, UserStatus};
+use crate::db::{migrations, DaoError};
+use crate::messages::{
+    Budget as BudgetMessage, BudgetFrame, BudgetFrameCategory, BudgetIdAndEncryptionKey,
+    BudgetList, BudgetShareInvite as BudgetShareInviteMessage, BudgetShareInviteList,
+    CategoryWithTempId, EncryptedBlob, EntryIdAndCategoryId, InvitationId,
+    SigninNonceAndHashParams, UserBootstrap, UserPublicKey,
+};
+use crate::models::budget::Budget;
+use crate::models::budget_accept_key::BudgetAcceptKey;
+use crate::models::budget_access_key::BudgetAccessKey;
+use crate::models::budget_share_invite::BudgetShareInvite;
+use crate::models::category::Category;
+use crate::models::entry::Entry;
+use crate::models::idempotency_key::IdempotencyKey;
+use crate::models::user::User;
+use crate::models::user_deletion_request::UserDeletionRequest;
+use crate::models::user_keystore::UserKeystore;
+use crate::models::user_preferences::UserPreferences;
+
+#[derive(Clone, Default)]
+pub struct Dao {
+    tables: Arc<Mutex<Tables>>,
+}
+
+impl Dao {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn tables(&self) -> MutexGuard<'_, Tables> {
+        self.tables.lock().expect("Lock was poisoned")
+    }
+}
+
+#[derive(Default)]
+struct Tables {
+    users: BTreeMap<Uuid, User>,
+    user_preferences: HashMap<Uuid, VersionedBlob>,
+    user_keystores: HashMap<Uuid, VersionedBlob>,
+    user_backup_codes: HashSet<(Uuid, String)>,
+    user_deletion_requests: HashMap<Uuid, SystemTime>,
+    user_deletion_request_budget_keys: HashMap<Uuid, DeletionRequestBudgetKey>,
+    signin_nonces: HashMap<String, i32>,
+    user_otps: HashMap<String, Otp>,
+    blacklisted_tokens: HashMap<Vec<u8>, SystemTime>,
+    idempotency_keys: HashMap<(Uuid, String), IdempotencyKey>,
+
+    budgets: BTreeMap<Uuid, Budget>,
+    // Keyed by key ID, which is unique on its own
+    budget_access_keys: BTreeMap<Uuid, BudgetAccessKey>,
+    budget_accept_keys: BTreeMap<Uuid, BudgetAcceptKey>,
+    budget_share_invites: BTreeMap<Uuid, BudgetShareInvite>,
+    categories: BTreeMap<Uuid, Category>,
+    entries: BTreeMap<Uuid, Entry>,
+
+    job_registry: BTreeMap<String, SystemTime>,
+}
+
+struct VersionedBlob {
+    encrypted_blob: Vec<u8>,
+    version_nonce: i64,
+}
+
+struct DeletionRequestBudgetKey {
+    user_id: Uuid,
+    delete_me_time: SystemTime,
+}
+
+struct Otp {
+    otp: String,
+    expiration: SystemTime,
+}
+
+impl Tables {
+    fn user_by_email(&self, user_email: &str) -> Result<&User, DaoError> {
+        self.users
+            .values()
+            .find(|u| u.email == user_email)
+            .ok_or(not_found())
+    }
+
+    fn user_id_by_email(&self, user_email: &str) -> Option<Uuid> {
+        self.users
+            .values()
+            .find(|u| u.email == user_email)
+            .map(|u| u.id)
+    }
+
+    fn require_user(&self, user_id: Uuid) -> Result<(), DaoError> {
+        if self.users.contains_key(&user_id) {
+            Ok(())
+        } else {
+            Err(violation(
+                DatabaseErrorKind::ForeignKeyViolation,
+                "user_key",
+            ))
+        }
+    }
+
+    fn require_budget(&self, budget_id: Uuid) -> Result<(), DaoError> {
+        if self.budgets.contains_key(&budget_id) {
+            Ok(())
+        } else {
+            Err(violation(
+                DatabaseErrorKind::ForeignKeyViolation,
+                "budget_key",
+            ))
+        }
+    }
+
+    fn require_category(&self, category_id: Option<Uuid>) -> Result<(), DaoError> {
+        match category_id {
+            Some(id) if !self.categories.contains_key(&id) => Err(violation(
+                DatabaseErrorKind::ForeignKeyViolation,
+                "category_key",
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    fn delete_user(&mut self, user_id: Uuid) {
+        let Some(user) = self.users.remove(&user_id) else {
+            return;
+        };
+
+        self.user_preferences.remove(&user_id);
+        self.user_keystores.remove(&user_id);
+        self.user_backup_codes.retain(|(id, _)| *id != user_id);
+        self.user_deletion_requests.remove(&user_id);
+        self.user_deletion_request_budget_keys
+            .retain(|_, k| k.user_id != user_id);
+        self.signin_nonces.remove(&user.email);
+        self.user_otps.remove(&user.email);
+        self.idempotency_keys.retain(|(id, _), _| *id != user_id);
+        self.budget_share_invites
+            .retain(|_, i| i.recipient_user_email != user.email);
+    }
+
+    fn delete_budget(&mut self, budget_id: Uuid) {
+        if self.budgets.remove(&budget_id).is_none() {
+            return;
+        }
+
+        let access_key_ids = self
+            .budget_access_keys
+            .values()
+            .filter(|k| k.budget_id == budget_id)
+            .map(|k| k.key_id)
+            .collect::<Vec<_>>();
+
+        for key_id in access_key_ids {
+            self.delete_budget_access_key(key_id);
+        }
+
+        self.budget_accept_keys
+            .retain(|_, k| k.budget_id != budget_id);
+        self.categories.retain(|_, c| c.budget_id != budget_id);
+        self.entries.retain(|_, e| e.budget_id != budget_id);
+    }
+
+    fn delete_budget_access_key(&mut self, key_id: Uuid) -> Option<BudgetAccessKey> {
+        let key = self.budget_access_keys.remove(&key_id)?;
+        self.user_deletion_request_budget_keys.remove(&key_id);
+        Some(key)
+    }
+
+    /// Deletes the budget if no users have access to it anymore
+    fn delete_budget_if_abandoned(&mut self, budget_id: Uuid) {
+        let users_remaining_in_budget = self
+            .budget_access_keys
+            .values()
+            .any(|k| k.budget_id == budget_id);
+
+        if !users_remaining_in_budget {
+            self.delete_budget(budget_id);
+        }
+    }
+
+    fn delete_category(&mut self, category_id: Uuid) {
+        self.categories.remove(&category_id);
+
+        for entry in self.entries.values_mut() {
+            if entry.category_id == Some(category_id) {
+                entry.category_id = None;
+            }
+        }
+    }
+
+    fn budget_message(&self, budget: &Budget) -> BudgetMessage {
+        let budget_categories = self
+            .categories
+            .values()
+            .filter(|c| c.budget_id == budget.id)
+            .cloned()
+            .collect();
+        let budget_entries = self
+            .entries
+            .values()
+            .filter(|e| e.budget_id == budget.id)
+            .cloned()
+            .collect();
+
+        budget_message(budget.clone(), budget_categories, budget_entries)
+    }
+}
+
+fn not_found() -> DaoError {
+    DaoError::QueryFailure(DieselError::NotFound)
+}
+
+fn violation(kind: DatabaseErrorKind, constraint: &str) -> DaoError {
+    let info: Box<dyn DatabaseErrorInformation + Send + Sync> =
+        Box::new(format!("violates constraint \"{constraint}\""));
+    DaoError::QueryFailure(DieselError::DatabaseError(kind, info))
+}
+
+fn unique_violation() -> DaoError {
+    violation(DatabaseErrorKind::UniqueViolation, "pkey")
+}
+
+/// The result of an update guarded by `version_nonce`, given the current nonce of the row (if
+/// the row exists) and whether the row matched the rest of the update's filter
+fn check_version_nonce(
+    current_version_nonce: Option<i64>,
+    expected_previous_version_nonce: i64,
+    matches_filter: bool,
+) -> Result<(), DaoError> {
+    match current_version_nonce {
+        None => Err(not_found()),
+        Some(_) if !matches_filter => Err(not_found()),
+        Some(nonce) if nonce != expected_previous_version_nonce => {
+            Err(DaoError::OutOfDate(Some(nonce)))
+        }
+        Some(_) => Ok(()),
+    }
+}
+
+impl AuthDao for Dao {
+    fn get_user_auth_string_hash_and_status(
+        &self,
+        user_email: &str,
+    ) -> Result<UserAuthStringHashAndStatus, DaoError> {
+        let tables = self.tables();
+        let user = tables.user_by_email(user_email)?;
+
+        Ok(UserAuthStringHashAndStatus {
+            user_id: user.id,
+            is_user_verified: user.is_verified,
+            auth_string_hash: if user.is_verified {
+                user.auth_string_hash.clone()
+            } else {
+                String::new()
+            },
+        })
+    }
+
+    fn blacklist_token(
+        &self,
+        token_signature: &[u8],
+        token_expiration: u64,
+    ) -> Result<(), DaoError> {
+        let mut tables = self.tables();
+
+        if tables.blacklisted_tokens.contains_key(token_signature) {
+            return Err(unique_violation());
+        }
+
+        tables.blacklisted_tokens.insert(
+            token_signature.to_vec(),
+            UNIX_EPOCH + Duration::from_secs(token_expiration),
+        );
+
+        Ok(())
+    }
+
+    fn check_is_token_on_blacklist_and_blacklist(
+        &self,
+        token_signature: &[u8],
+        token_expiration: u64,
+    ) -> Result<bool, DaoError> {
+        let mut tables = self.tables();
+
+        if tables.blacklisted_tokens.contains_key(token_signature) {
+            return Ok(true);
+        }
+
+        tables.blacklisted_tokens.insert(
+            token_signature.to_vec(),
+            UNIX_EPOCH + Duration::from_secs(token_expiration),
+        );
+
+        Ok(false)
+    }
+
+    fn save_otp(
+        &self,
+        otp: &str,
+        user_email: &str,
+        expiration: SystemTime,
+    ) -> Result<(), DaoError> {
+        let mut tables = self.tables();
+
+        if tables.user_id_by_email(user_email).is_none() {
+            return Err(violation(
+                DatabaseErrorKind::ForeignKeyViolation,
+                "user_key",
+            ));
+        }
+
+        tables.user_otps.insert(
+            user_email.to_owned(),
+            Otp {
+                otp: otp.to_owned(),
+                expiration,
+            },
+        );
+
+        Ok(())
+    }
+
+    fn check_unexpired_otp(&self, otp: &str, user_email: &str) -> Result<bool, DaoError> {
+        Ok(self
+            .tables()
+            .user_otps
+            .get(user_email)
+            .is_some_and(|o| o.otp == otp && o.expiration > SystemTime::now()))
+    }
+
+    fn delete_otp(&self, otp: &str, user_email: &str) -> Result<(), DaoError> {
+        let mut tables = self.tables();
+
+        if tables
+            .user_otps
+            .get(user_email)
+            .is_some_and(|o| o.otp == otp)
+        {
+            tables.user_otps.remove(user_email);
+        }
+
+        Ok(())
+    }
+
+    fn delete_all_otps_for_user(&self, user_email: &str) -> Result<usize, DaoError> {
+        Ok(self
+            .tables()
+            .user_otps
+            .remove(user_email)
+            .into_iter()
+            .count())
+    }
+
+    fn delete_all_expired_otps(&self) -> Result<(), DaoError> {
+        let now = SystemTime::now();
+        self.tables().user_otps.retain(|_, o| o.expiration >= now);
+        Ok(())
+    }
+
+    fn replace_backup_codes(&self, user_id: Uuid, codes: &[String]) -> Result<(), DaoError> {
+        let mut tables = self.tables();
+
+        if !codes.is_empty() {
+            tables.require_user(user_id)?;
+        }
+
+        let unique_codes = codes.iter().collect::<HashSet<_>>();
+        if unique_codes.len() != codes.len() {
+            return Err(unique_violation());
+        }
+
+        tables.user_backup_codes.retain(|(id, _)| *id != user_id);
+        tables
+            .user_backup_codes
+            .extend(codes.iter().map(|code| (user_id, code.clone())));
+
+        Ok(())
+    }
+
+    fn delete_backup_code(&self, code: &str, user_id: Uuid) -> Result<(), DaoError> {
+        self.tables()
+            .user_backup_codes
+            .remove(&(user_id, code.to_owned()));
+        Ok(())
+    }
+
+    fn clear_all_expired_tokens(&self) -> Result<usize, DaoError> {
+        let now = SystemTime::now();
+        let mut tables = self.tables();
+
+        let count_before = tables.blacklisted_tokens.len();
+        tables.blacklisted_tokens.retain(|_, exp| *exp >= now);
+
+        Ok(count_before - tables.blacklisted_tokens.len())
+    }
+
+    fn get_and_refresh_signin_nonce(&self, user_email: &str) -> Result<i32, DaoError> {
+        let mut tables = self.tables();
+        let nonce = tables
+            .signin_nonces
+            .get_mut(user_email)
+            .ok_or(not_found())?;
+
+        Ok(std::mem::replace(nonce, OsRng.gen()))
+    }
+
+    fn get_auth_string_data_signin_nonce(
+        &self,
+        user_email: &str,
+    ) -> Result<SigninNonceAndHashParams, DaoError> {
+        let tables = self.tables();
+        let user = tables.user_by_email(user_email)?;
+        let nonce = tables.signin_nonces.get(user_email).ok_or(not_found())?;
+
+        Ok(SigninNonceAndHashParams {
+            auth_string_salt: user.auth_string_salt.clone(),
+            auth_string_memory_cost_kib: user.auth_string_memory_cost_kib,
+            auth_string_parallelism_factor: user.auth_string_parallelism_factor,
+            auth_string_iters: user.auth_string_iters,
+            nonce: *nonce,
+        })
+    }
+}
+
+impl UserDao for Dao {
+    fn get_user_public_key(&self, user_email: &str) -> Result<UserPublicKey, DaoError> {
+        let tables = self.tables();
+        let user = tables.user_by_email(user_email)?;
+
+        Ok(UserPublicKey {
+            id: user.public_key_id.into(),
+            value: user.public_key.clone(),
+        })
+    }
+
+    fn get_user_status(&self, user_email: &str) -> Result<UserStatus, DaoError> {
+        let tables = self.tables();
+        let user = tables.user_by_email(user_email)?;
+
+        let budgets_pending_deletion = tables
+            .user_deletion_request_budget_keys
+            .values()
+            .filter(|k| k.user_id == user.id)
+            .count();
+
+        Ok(UserStatus {
+            id: user.id,
+            email: user.email.clone(),
+            is_verified: user.is_verified,
+            created_timestamp: user.created_timestamp,
+            ready_for_deletion_time: tables.user_deletion_requests.get(&user.id).copied(),
+            budgets_pending_deletion: budgets_pending_deletion as i64,
+        })
+    }
+
+    fn create_user(
+        &self,
+        email: &str,
+        auth_string_hash: &str,
+        auth_string_salt: &[u8],
+        auth_string_memory_cost_kib: i32,
+        auth_string_parallelism_factor: i32,
+        auth_string_iters: i32,
+        password_encryption_salt: &[u8],
+        password_encryption_memory_cost_kib: i32,
+        password_encryption_parallelism_factor: i32,
+        password_encryption_iters: i32,
+        recovery_key_salt: &[u8],
+        recovery_key_memory_cost_kib: i32,
+        recovery_key_parallelism_factor: i32,
+        recovery_key_iters: i32,
+        encryption_key_encrypted_with_password: &[u8],
+        encryption_key_encrypted_with_recovery_key: &[u8],
+        public_key_id: Uuid,
+        public_key: &[u8],
+        preferences_encrypted: &[u8],
+        preferences_version_nonce: i64,
+        user_keystore_encrypted: &[u8],
+        user_keystore_version_nonce: i64,
+        backup_codes: &[String],
+    ) -> Result<Uuid, DaoError> {
+        let user_id = Uuid::now_v7();
+        let email_lowercase = email.to_lowercase();
+
+        let mut tables = self.tables();
+
+        let unique_codes = backup_codes.iter().collect::<HashSet<_>>();
+        if tables.user_id_by_email(&email_lowercase).is_some()
+            || unique_codes.len() != backup_codes.len()
+        {
+            return Err(unique_violation());
+        }
+
+        tables.users.insert(
+            user_id,
+            User {
+                id: user_id,
+                email: email_lowercase.clone(),
+                is_verified: false,
+                public_key_id,
+                public_key: public_key.to_vec(),
+                created_timestamp: SystemTime::now(),
+                auth_string_hash: auth_string_hash.to_owned(),
+                auth_string_salt: auth_string_salt.to_vec(),
+                auth_string_memory_cost_kib,
+                auth_string_parallelism_factor,
+                auth_string_iters,
+                password_encryption_salt: password_encryption_salt.to_vec(),
+                password_encryption_memory_cost_kib,
+                password_encryption_parallelism_factor,
+                password_encryption_iters,
+                recovery_key_salt: recovery_key_salt.to_vec(),
+                recovery_key_memory_cost_kib,
+                recovery_key_parallelism_factor,
+                recovery_key_iters,
+                encryption_key_encrypted_with_password: encryption_key_encrypted_with_password
+                    .to_vec(),
+                encryption_key_encrypted_with_recovery_key:
+                    encryption_key_encrypted_with_recovery_key.to_vec(),
+            },
+        );
+
+        tables.user_preferences.insert(
+            user_id,
+            VersionedBlob {
+                encrypted_blob: preferences_encrypted.to_vec(),
+                version_nonce: preferences_version_nonce,
+            },
+        );
+
+        tables.user_keystores.insert(
+            user_id,
+            VersionedBlob {
+                encrypted_blob: user_keystore_encrypted.to_vec(),
+                version_nonce: user_keystore_version_nonce,
+            },
+        );
+
+        tables.signin_nonces.insert(email_lowercase, OsRng.gen());
+        tables
+            .user_backup_codes
+            .extend(backup_codes.iter().map(|code| (user_id, code.clone())));
+
+        Ok(user_id)
+    }
+
+    fn verify_user_creation(&self, user_id: Uuid) -> Result<(), DaoError> {
+        if let Some(user) = self.tables().users.get_mut(&user_id) {
+            user.is_verified = true;
+        }
+
+        Ok(())
+    }
+
+    fn clear_unverified_users(&self, max_unverified_user_age: Duration) -> Result<(), DaoError> {
+        let cutoff = SystemTime::now() - max_unverified_user_age;
+        let mut tables = self.tables();
+
+        let user_ids = tables
+            .users
+            .values()
+            .filter(|u| !u.is_verified && u.created_timestamp < cutoff)
+            .map(|u| u.id)
+            .collect::<Vec<_>>();
+
+        for user_id in user_ids {
+            tables.delete_user(user_id);
+        }
+
+        Ok(())
+    }
+
+    fn rotate_user_public_key(
+        &self,
+        user_id: Uuid,
+        public_key_id: Uuid,
+        public_key: &[u8],
+        expected_previous_public_key_id: Uuid,
+    ) -> Result<(), DaoError> {
+        let mut tables = self.tables();
+        let user = tables.users.get_mut(&user_id).ok_or(not_found())?;
+
+        if user.public_key_id != expected_previous_public_key_id {
+            return Err(DaoError::OutOfDate(None));
+        }
+
+        user.public_key_id = public_key_id;
+        user.public_key = public_key.to_vec();
+
+        Ok(())
+    }
+
+    fn get_user_prefs(&self, user_id: Uuid) -> Result<UserPreferences, DaoError> {
+        let tables = self.tables();
+        let prefs = tables.user_preferences.get(&user_id).ok_or(not_found())?;
+
+        Ok(UserPreferences {
+            user_id,
+            encrypted_blob: prefs.encrypted_blob.clone(),
+            version_nonce: prefs.version_nonce,
+        })
+    }
+
+    fn get_user_keystore(&self, user_id: Uuid) -> Result<UserKeystore, DaoError> {
+        let tables = self.tables();
+        let keystore = tables.user_keystores.get(&user_id).ok_or(not_found())?;
+
+        Ok(UserKeystore {
+            user_id,
+            encrypted_blob: keystore.encrypted_blob.clone(),
+            version_nonce: keystore.version_nonce,
+        })
+    }
+
+    fn get_user_bootstrap(&self, user_id: Uuid) -> Result<UserBootstrap, DaoError> {
+        let tables = self.tables();
+        let user = tables.users.get(&user_id).ok_or(not_found())?;
+        let prefs = tables.user_preferences.get(&user_id).ok_or(not_found())?;
+        let keystore = tables.user_keystores.get(&user_id).ok_or(not_found())?;
+
+        Ok(UserBootstrap {
+            preferences: EncryptedBlob {
+                encrypted_blob: prefs.encrypted_blob.clone(),
+                version_nonce: prefs.version_nonce,
+            },
+            keystore: EncryptedBlob {
+                encrypted_blob: keystore.encrypted_blob.clone(),
+                version_nonce: keystore.version_nonce,
+            },
+            public_key: UserPublicKey {
+                id: user.public_key_id.into(),
+                value: user.public_key.clone(),
+            },
+            is_listed_for_deletion: tables.user_deletion_requests.contains_key(&user_id),
+        })
+    }
+
+    fn update_user_prefs(
+        &self,
+        user_id: Uuid,
+        prefs_encrypted_blob: &[u8],
+        version_nonce: i64,
+        expected_previous_version_nonce: i64,
+    ) -> Result<(), DaoError> {
+        let mut tables = self.tables();
+        let prefs = tables.user_preferences.get_mut(&user_id);
+
+        check_version_nonce(
+            prefs.as_ref().map(|p| p.version_nonce),
+            expected_previous_version_nonce,
+            true,
+        )?;
+
+        let prefs = prefs.expect("Preferences should exist after version check");
+        prefs.encrypted_blob = prefs_encrypted_blob.to_vec();
+        prefs.version_nonce = version_nonce;
+
+        Ok(())
+    }
+
+    fn update_user_keystore(
+        &self,
+        user_id: Uuid,
+        keystore_encrypted_blob: &[u8],
+        version_nonce: i64,
+        expected_previous_version_nonce: i64,
+    ) -> Result<(), DaoError> {
+        let mut tables = self.tables();
+        let keystore = tables.user_keystores.get_mut(&user_id);
+
+        check_version_nonce(
+            keystore.as_ref().map(|k| k.version_nonce),
+            expected_previous_version_nonce,
+            true,
+        )?;
+
+        let keystore = keystore.expect("Keystore should exist after version check");
+        keystore.encrypted_blob = keystore_encrypted_blob.to_vec();
+        keystore.version_nonce = version_nonce;
+
+        Ok(())
+    }
+
+    fn update_password(
+        &self,
+        user_email: &str,
+        new_auth_string_hash: &str,
+        new_auth_string_salt: &[u8],
+        new_auth_string_memory_cost_kib: i32,
+        new_auth_string_parallelism_factor: i32,
+        new_auth_string_iters: i32,
+        new_password_encryption_salt: &[u8],
+        new_password_encryption_memory_cost_kib: i32,
+        new_password_encryption_parallelism_factor: i32,
+        new_password_encryption_iters: i32,
+        encrypted_encryption_key: &[u8],
+    ) -> Result<(), DaoError> {
+        let mut tables = self.tables();
+
+        if let Some(user) = tables.users.values_mut().find(|u| u.email == user_email) {
+            user.auth_string_hash = new_auth_string_hash.to_owned();
+            user.auth_string_salt = new_auth_string_salt.to_vec();
+            user.auth_string_memory_cost_kib = new_auth_string_memory_cost_kib;
+            user.auth_string_parallelism_factor = new_auth_string_parallelism_factor;
+            user.auth_string_iters = new_auth_string_iters;
+            user.password_encryption_salt = new_password_encryption_salt.to_vec();
+            user.password_encryption_memory_cost_kib = new_password_encryption_memory_cost_kib;
+            user.password_encryption_parallelism_factor =
+                new_password_encryption_parallelism_factor;
+            user.password_encryption_iters = new_password_encryption_iters;
+            user.encryption_key_encrypted_with_password = encrypted_encryption_key.to_vec();
+        }
+
+        Ok(())
+    }
+
+    fn update_recovery_key(
+        &self,
+        user_id: Uuid,
+        new_recovery_key_salt: &[u8],
+        new_recovery_key_memory_cost_kib: i32,
+        new_recovery_key_parallelism_factor: i32,
+        new_recovery_key_iters: i32,
+        encrypted_encryption_key: &[u8],
+    ) -> Result<(), DaoError> {
+        if let Some(user) = self.tables().users.get_mut(&user_id) {
+            user.recovery_key_salt = new_recovery_key_salt.to_vec();
+            user.recovery_key_memory_cost_kib = new_recovery_key_memory_cost_kib;
+            user.recovery_key_parallelism_factor = new_recovery_key_parallelism_factor;
+            user.recovery_key_iters = new_recovery_key_iters;
+            user.encryption_key_encrypted_with_recovery_key = encrypted_encryption_key.to_vec();
+        }
+
+        Ok(())
+    }
+
+    fn save_user_deletion_budget_keys(
+        &self,
+        budget_access_key_ids: &[Uuid],
+        user_id: Uuid,
+        delete_me_time: SystemTime,
+    ) -> Result<(), DaoError> {
+        let mut tables = self.tables();
+
+        if budget_access_key_ids.is_empty() {
+            return Ok(());
+        }
+
+        tables.require_user(user_id)?;
+
+        let mut unique_key_ids = HashSet::new();
+        for key_id in budget_access_key_ids {
+            if !tables.budget_access_keys.contains_key(key_id) {
+                return Err(violation(DatabaseErrorKind::ForeignKeyViolation, "key_key"));
+            }
+
+            if !unique_key_ids.insert(key_id)
+                || tables
+                    .user_deletion_request_budget_keys
+                    .contains_key(key_id)
+            {
+                return Err(unique_violation());
+            }
+        }
+
+        for key_id in budget_access_key_ids {
+            tables.user_deletion_request_budget_keys.insert(
+                *key_id,
+                DeletionRequestBudgetKey {
+                    user_id,
+                    delete_me_time,
+                },
+            );
+        }
+
+        Ok(())
+    }
+
+    fn initiate_user_deletion(
+        &self,
+        user_id: Uuid,
+        time_until_deletion: Duration,
+    ) -> Result<(), DaoError> {
+        let mut tables = self.tables();
+
+        tables.require_user(user_id)?;
+
+        if tables.user_deletion_requests.contains_key(&user_id) {
+            return Err(unique_violation());
+        }
+
+        tables
+            .user_deletion_requests
+            .insert(user_id, SystemTime::now() + time_until_deletion);
+
+        Ok(())
+    }
+
+    fn cancel_user_deletion(&self, user_id: Uuid) -> Result<(), DaoError> {
+        self.tables().user_deletion_requests.remove(&user_id);
+        Ok(())
+    }
+
+    fn expedite_user_deletion(&self, user_id: Uuid) -> Result<bool, DaoError> {
+        let now = SystemTime::now();
+        let mut tables = self.tables();
+
+        let updated = match tables.user_deletion_requests.get_mut(&user_id) {
+            Some(ready_for_deletion_time) => {
+                *ready_for_deletion_time = now;
+                true
+            }
+            None => false,
+        };
+
+        for key in tables.user_deletion_request_budget_keys.values_mut() {
+            if key.user_id == user_id {
+                key.delete_me_time = now;
+            }
+        }
+
+        Ok(updated)
+    }
+
+    fn delete_user(&self, user_deletion_request: &UserDeletionRequest) -> Result<(), DaoError> {
+        let user_id = user_deletion_request.user_id;
+        let mut tables = self.tables();
+
+        let budget_key_ids = tables
+            .user_deletion_request_budget_keys
+            .iter()
+            .filter(|(_, k)| k.user_id == user_id)
+            .map(|(key_id, _)| *key_id)
+            .collect::<Vec<_>>();
+
+        for key_id in budget_key_ids {
+            if let Some(key) = tables.delete_budget_access_key(key_id) {
+                tables.delete_budget_if_abandoned(key.budget_id);
+            }
+        }
+
+        tables.delete_user(user_id);
+
+        Ok(())
+    }
+
+    fn get_all_users_ready_for_deletion(&self) -> Result<Vec<UserDeletionRequest>, DaoError> {
+        let now = SystemTime::now();
+
+        Ok(self
+            .tables()
+            .user_deletion_requests
+            .iter()
+            .filter(|(_, time)| **time < now)
+            .map(|(user_id, time)| UserDeletionRequest {
+                user_id: *user_id,
+                ready_for_deletion_time: *time,
+            })
+            .collect())
+    }
+
+    fn check_is_user_listed_for_deletion(&self, user_id: Uuid) -> Result<bool, DaoError> {
+        Ok(self.tables().user_deletion_requests.contains_key(&user_id))
+    }
+
+    fn delete_old_user_deletion_requests(&self) -> Result<(), DaoError> {
+        let now = SystemTime::now();
+        let mut tables = self.tables();
+
+        let mut user_ids = HashSet::new();
+        tables.user_deletion_request_budget_keys.retain(|_, k| {
+            if k.delete_me_time <= now {
+                user_ids.insert(k.user_id);
+                false
+            } else {
+                true
+            }
+        });
+
+        tables
+            .user_deletion_requests
+            .retain(|user_id, _| !user_ids.contains(user_id));
+
+        Ok(())
+    }
+}
+
+impl BudgetDao for Dao {
+    fn get_public_budget_key(
+        &self,
+        key_id: Uuid,
+        budget_id: Uuid,
+    ) -> Result<BudgetAccessKey, DaoError> {
+        self.tables()
+            .budget_access_keys
+            .get(&key_id)
+            .filter(|k| k.budget_id == budget_id)
+            .cloned()
+            .ok_or(not_found())
+    }
+
+    fn get_multiple_public_budget_keys(
+        &self,
+        key_ids: &[Uuid],
+        budget_ids: &[Uuid],
+    ) -> Result<Vec<BudgetAccessKey>, DaoError> {
+        Ok(self
+            .tables()
+            .budget_access_keys
+            .values()
+            .filter(|k| key_ids.contains(&k.key_id) && budget_ids.contains(&k.budget_id))
+            .cloned()
+            .collect())
+    }
+
+    fn get_budget_accept_public_key(
+        &self,
+        key_id: Uuid,
+        budget_id: Uuid,
+    ) -> Result<BudgetAcceptKey, DaoError> {
+        self.tables()
+            .budget_accept_keys
+            .get(&key_id)
+            .filter(|k| k.budget_id == budget_id)
+            .cloned()
+            .ok_or(not_found())
+    }
+
+    fn get_budget_invite_sender_public_key(
+        &self,
+        invitation_id: Uuid,
+    ) -> Result<Vec<u8>, DaoError> {
+        self.tables()
+            .budget_share_invites
+            .get(&invitation_id)
+            .map(|i| i.sender_public_key.clone())
+            .ok_or(not_found())
+    }
+
+    fn get_budget(&self, budget_id: Uuid) -> Result<BudgetMessage, DaoError> {
+        let tables = self.tables();
+        let budget = tables.budgets.get(&budget_id).ok_or(not_found())?;
+        Ok(tables.budget_message(budget))
+    }
+
+    fn get_multiple_budgets_by_id(&self, budget_ids: &[Uuid]) -> Result<BudgetList, DaoError> {
+        let tables = self.tables();
+
+        let budgets = tables
+            .budgets
+            .values()
+            .filter(|b| budget_ids.contains(&b.id))
+            .map(|b| tables.budget_message(b))
+            .collect();
+
+        Ok(BudgetList { budgets })
+    }
+
+    fn create_budget(
+        &self,
+        encrypted_blob: &[u8],
+        version_nonce: i64,
+        budget_categories: &[CategoryWithTempId],
+        user_public_budget_key: &[u8],
+    ) -> Result<BudgetFrame, DaoError> {
+        let current_time = SystemTime::now();
+        let budget_id = Uuid::now_v7();
+        let key_id = Uuid::now_v7();
+
+        let new_categories = budget_categories
+            .iter()
+            .map(|category| {
+                // Client-generated IDs are validated by the caller
+                let category_id = category
+                    .id
+                    .as_ref()
+                    .and_then(|id| Uuid::try_from(id).ok())
+                    .unwrap_or_else(Uuid::now_v7);
+
+                Category {
+                    id: category_id,
+                    budget_id,
+                    encrypted_blob: category.encrypted_blob.clone(),
+                    version_nonce: category.version_nonce,
+                    modified_timestamp: current_time,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let mut tables = self.tables();
+
+        let unique_ids = new_categories.iter().map(|c| c.id).collect::<HashSet<_>>();
+        if unique_ids.len() != new_categories.len()
+            || unique_ids
+                .iter()
+                .any(|id| tables.categories.contains_key(id))
+        {
+            return Err(unique_violation());
+        }
+
+        let output_budget = BudgetFrame {
+            access_key_id: key_id.into(),
+            id: budget_id.into(),
+            category_ids: budget_categories
+                .iter()
+                .zip(new_categories.iter())
+                .map(|(category, new_category)| BudgetFrameCategory {
+                    temp_id: category.temp_id,
+                    real_id: new_category.id.into(),
+                })
+                .collect(),
+            modified_timestamp: current_time.try_into().unwrap_or_default(),
+        };
+
+        tables.budgets.insert(
+            budget_id,
+            Budget {
+                id: budget_id,
+                encrypted_blob: encrypted_blob.to_vec(),
+                version_nonce,
+                modified_timestamp: current_time,
+            },
+        );
+
+        tables.budget_access_keys.insert(
+            key_id,
+            BudgetAccessKey {
+                key_id,
+                budget_id,
+                public_key: user_public_budget_key.to_vec(),
+                read_only: false,
+            },
+        );
+
+        tables
+            .categories
+            .extend(new_categories.into_iter().map(|c| (c.id, c)));
+
+        Ok(output_budget)
+    }
+
+    fn update_budget(
+        &self,
+        budget_id: Uuid,
+        edited_budget_data: &[u8],
+        version_nonce: i64,
+        expected_previous_version_nonce: i64,
+    ) -> Result<(), DaoError> {
+        let mut tables = self.tables();
+        let budget = tables.budgets.get_mut(&budget_id);
+
+        check_version_nonce(
+            budget.as_ref().map(|b| b.version_nonce),
+            expected_previous_version_nonce,
+            true,
+        )?;
+
+        let budget = budget.expect("Budget should exist after version check");
+        budget.encrypted_blob = edited_budget_data.to_vec();
+        budget.version_nonce = version_nonce;
+        budget.modified_timestamp = SystemTime::now();
+
+        Ok(())
+    }
+
+    fn invite_user(
+        &self,
+        recipient_user_email: &str,
+        sender_public_key: &[u8],
+        encryption_key_encrypted: &[u8],
+        budget_info_encrypted: &[u8],
+        sender_info_encrypted: &[u8],
+        share_info_symmetric_key_encrypted: &[u8],
+        recipient_public_key_id_used_by_sender: Uuid,
+        recipient_public_key_id_used_by_server: Uuid,
+        budget_id: Uuid,
+        expiration: SystemTime,
+        read_only: bool,
+        budget_accept_key_id: Uuid,
+        budget_accept_key_id_encrypted: &[u8],
+        budget_accept_public_key: &[u8],
+        budget_accept_private_key_encrypted: &[u8],
+        budget_accept_key_info_encrypted: &[u8],
+    ) -> Result<InvitationId, DaoError> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("Failed to get time");
+        let created_unix_timestamp_intdiv_five_million: i16 = (now.as_secs() / 5_000_000)
+            .try_into()
+            .expect("Current timestamp divided by 5,000,00 should fit into an i16");
+
+        let invitation_id = Uuid::now_v7();
+
+        let mut tables = self.tables();
+
+        if tables.user_id_by_email(recipient_user_email).is_none() {
+            return Err(violation(
+                DatabaseErrorKind::ForeignKeyViolation,
+                "recipient_key",
+            ));
+        }
+
+        tables.require_budget(budget_id)?;
+
+        if tables
+            .budget_accept_keys
+            .contains_key(&budget_accept_key_id)
+        {
+            return Err(unique_violation());
+        }
+
+        tables.budget_share_invites.insert(
+            invitation_id,
+            BudgetShareInvite {
+                id: invitation_id,
+                recipient_user_email: recipient_user_email.to_owned(),
+                sender_public_key: sender_public_key.to_vec(),
+                encryption_key_encrypted: encryption_key_encrypted.to_vec(),
+                budget_accept_private_key_encrypted: budget_accept_private_key_encrypted.to_vec(),
+                budget_info_encrypted: budget_info_encrypted.to_vec(),
+                sender_info_encrypted: sender_info_encrypted.to_vec(),
+                budget_accept_key_info_encrypted: budget_accept_key_info_encrypted.to_vec(),
+                budget_accept_key_id_encrypted: budget_accept_key_id_encrypted.to_vec(),
+                share_info_symmetric_key_encrypted: share_info_symmetric_key_encrypted.to_vec(),
+                recipient_public_key_id_used_by_sender,
+                recipient_public_key_id_used_by_server,
+                created_unix_timestamp_intdiv_five_million,
+            },
+        );
+
+        tables.budget_accept_keys.insert(
+            budget_accept_key_id,
+            BudgetAcceptKey {
+                key_id: budget_accept_key_id,
+                budget_id,
+                public_key: budget_accept_public_key.to_vec(),
+                expiration,
+                read_only,
+            },
+        );
+
+        Ok(InvitationId {
+            value: invitation_id.into(),
+        })
+    }
+
+    fn accept_invitation(
+        &self,
+        accept_key_id: Uuid,
+        budget_id: Uuid,
+        read_only: bool,
+        invitation_id: Uuid,
+        recipient_user_email: &str,
+        recipient_budget_user_access_public_key: &[u8],
+    ) -> Result<BudgetIdAndEncryptionKey, DaoError> {
+        let mut tables = self.tables();
+
+        tables.require_budget(budget_id)?;
+
+        let is_recipient = tables
+            .budget_share_invites
+            .get(&invitation_id)
+            .is_some_and(|i| i.recipient_user_email == recipient_user_email);
+
+        if !is_recipient {
+            return Err(not_found());
+        }
+
+        let invite = tables
+            .budget_share_invites
+            .remove(&invitation_id)
+            .expect("Invitation should exist");
+
+        let key_id = Uuid::now_v7();
+        tables.budget_access_keys.insert(
+            key_id,
+            BudgetAccessKey {
+                key_id,
+                budget_id,
+                public_key: recipient_budget_user_access_public_key.to_vec(),
+                read_only,
+            },
+        );
+
+        if tables
+            .budget_accept_keys
+            .get(&accept_key_id)
+            .is_some_and(|k| k.budget_id == budget_id)
+        {
+            tables.budget_accept_keys.remove(&accept_key_id);
+        }
+
+        Ok(BudgetIdAndEncryptionKey {
+            budget_id: budget_id.into(),
+            budget_access_key_id: key_id.into(),
+            encryption_key_encrypted: invite.encryption_key_encrypted,
+            read_only,
+        })
+    }
+
+    fn reject_invitation(
+        &self,
+        invitation_id: Uuid,
+        accept_key_id: Uuid,
+        recipient_user_email: &str,
+    ) -> Result<(), DaoError> {
+        let mut tables = self.tables();
+
+        let is_recipient = tables
+            .budget_share_invites
+            .get(&invitation_id)
+            .is_some_and(|i| i.recipient_user_email == recipient_user_email);
+
+        if !is_recipient {
+            return Err(not_found());
+        }
+
+        tables.budget_share_invites.remove(&invitation_id);
+        tables.budget_accept_keys.remove(&accept_key_id);
+
+        Ok(())
+    }
+
+    fn delete_invitation(&self, invitation_id: Uuid) -> Result<(), DaoError> {
+        self.tables().budget_share_invites.remove(&invitation_id);
+        Ok(())
+    }
+
+    fn delete_all_invitations_for_user(&self, user_email: &str) -> Result<usize, DaoError> {
+        let mut tables = self.tables();
+
+        let count_before = tables.budget_share_invites.len();
+        tables
+            .budget_share_invites
+            .retain(|_, i| i.recipient_user_email != user_email);
+
+        Ok(count_before - tables.budget_share_invites.len())
+    }
+
+    fn delete_all_expired_invitations(&self) -> Result<(), DaoError> {
+        let now = SystemTime::now();
+        let segment_intdiv_five_million: i16 = ((now
+            .duration_since(UNIX_EPOCH)
+            .expect("now() should be after UNIX_EPOCH")
+            .as_secs()
+            - 5_000_000)
+            / 5_000_000)
+            .try_into()
+            .expect("Unix epoch time divided by 5 million should fit in an i16");
+
+        let mut tables = self.tables();
+
+        tables.budget_accept_keys.retain(|_, k| k.expiration >= now);
+        tables.budget_share_invites.retain(|_, i| {
+            i.created_unix_timestamp_intdiv_five_million >= segment_intdiv_five_million
+        });
+
+        Ok(())
+    }
+
+    fn get_pending_invitation_ids(&self, user_email: &str) -> Result<Vec<Uuid>, DaoError> {
+        Ok(self
+            .tables()
+            .budget_share_invites
+            .values()
+            .filter(|i| i.recipient_user_email == user_email)
+            .map(|i| i.id)
+            .collect())
+    }
+
+    fn get_all_pending_invitations(
+        &self,
+        user_email: &str,
+    ) -> Result<BudgetShareInviteList, DaoError> {
+        let invites = self
+            .tables()
+            .budget_share_invites
+            .values()
+            .filter(|i| i.recipient_user_email == user_email)
+            .map(|i| BudgetShareInviteMessage {
+                id: i.id.into(),
+                budget_accept_key_encrypted: i.budget_accept_private_key_encrypted.clone(),
+                budget_accept_key_id_encrypted: i.budget_accept_key_id_encrypted.clone(),
+                budget_info_encrypted: i.budget_info_encrypted.clone(),
+                sender_info_encrypted: i.sender_info_encrypted.clone(),
+                budget_accept_key_info_encrypted: i.budget_accept_key_info_encrypted.clone(),
+                share_info_symmetric_key_encrypted: i.share_info_symmetric_key_encrypted.clone(),
+                recipient_public_key_id_used_by_sender: i
+                    .recipient_public_key_id_used_by_sender
+                    .into(),
+                recipient_public_key_id_used_by_server: i
+                    .recipient_public_key_id_used_by_server
+                    .into(),
+            })
+            .collect();
+
+        Ok(BudgetShareInviteList { invites })
+    }
+
+    fn leave_budget(&self, budget_id: Uuid, key_id: Uuid) -> Result<(), DaoError> {
+        let mut tables = self.tables();
+
+        if tables
+            .budget_access_keys
+            .get(&key_id)
+            .is_some_and(|k| k.budget_id == budget_id)
+        {
+            tables.delete_budget_access_key(key_id);
+        }
+
+        tables.delete_budget_if_abandoned(budget_id);
+
+        Ok(())
+    }
+
+    fn create_entry(
+        &self,
+        entry_id: Option<Uuid>,
+        encrypted_blob: &[u8],
+        version_nonce: i64,
+        category_id: Option<Uuid>,
+        budget_id: Uuid,
+    ) -> Result<Uuid, DaoError> {
+        let entry_id = entry_id.unwrap_or_else(Uuid::now_v7);
+        let mut tables = self.tables();
+
+        // The foreign key only ensures the category exists, not that it is in this budget
+        if let Some(category_id) = category_id {
+            tables
+                .categories
+                .get(&category_id)
+                .filter(|c| c.budget_id == budget_id)
+                .ok_or(not_found())?;
+        }
+
+        tables.require_budget(budget_id)?;
+
+        if tables.entries.contains_key(&entry_id) {
+            return Err(unique_violation());
+        }
+
+        tables.entries.insert(
+            entry_id,
+            Entry {
+                id: entry_id,
+                budget_id,
+                category_id,
+                encrypted_blob: encrypted_blob.to_vec(),
+                version_nonce,
+                modified_timestamp: SystemTime::now(),
+            },
+        );
+
+        Ok(entry_id)
+    }
+
+    fn create_entry_and_category(
+        &self,
+        entry_id: Option<Uuid>,
+        entry_encrypted_blob: &[u8],
+        entry_version_nonce: i64,
+        category_id: Option<Uuid>,
+        category_encrypted_blob: &[u8],
+        category_version_nonce: i64,
+        budget_id: Uuid,
+    ) -> Result<EntryIdAndCategoryId, DaoError> {
+        let current_time = SystemTime::now();
+        let category_id = category_id.unwrap_or_else(Uuid::now_v7);
+        let entry_id = entry_id.unwrap_or_else(Uuid::now_v7);
+
+        let mut tables = self.tables();
+
+        tables.require_budget(budget_id)?;
+
+        if tables.categories.contains_key(&category_id) || tables.entries.contains_key(&entry_id) {
+            return Err(unique_violation());
+        }
+
+        tables.categories.insert(
+            category_id,
+            Category {
+                id: category_id,
+                budget_id,
+                encrypted_blob: category_encrypted_blob.to_vec(),
+                version_nonce: category_version_nonce,
+                modified_timestamp: current_time,
+            },
+        );
+
+        tables.entries.insert(
+            entry_id,
+            Entry {
+                id: entry_id,
+                budget_id,
+                category_id: Some(category_id),
+                encrypted_blob: entry_encrypted_blob.to_vec(),
+                version_nonce: entry_version_nonce,
+                modified_timestamp: current_time,
+            },
+        );
+
+        Ok(EntryIdAndCategoryId {
+            entry_id: entry_id.into(),
+            category_id: category_id.into(),
+        })
+    }
+
+    fn update_entry(
+        &self,
+        entry_id: Uuid,
+        entry_encrypted_blob: &[u8],
+        version_nonce: i64,
+        expected_previous_version_nonce: i64,
+        category_id: Option<Uuid>,
+        budget_id: Uuid,
+    ) -> Result<(), DaoError> {
+        let mut tables = self.tables();
+        let entry = tables.entries.get(&entry_id);
+
+        check_version_nonce(
+            entry.map(|e| e.version_nonce),
+            expected_previous_version_nonce,
+            entry.is_some_and(|e| e.budget_id == budget_id),
+        )?;
+
+        tables.require_category(category_id)?;
+
+        let entry = tables
+            .entries
+            .get_mut(&entry_id)
+            .expect("Entry should exist after version check");
+        entry.category_id = category_id;
+        entry.encrypted_blob = entry_encrypted_blob.to_vec();
+        entry.version_nonce = version_nonce;
+        entry.modified_timestamp = SystemTime::now();
+
+        Ok(())
+    }
+
+    fn delete_entry(&self, entry_id: Uuid, budget_id: Uuid) -> Result<(), DaoError> {
+        let mut tables = self.tables();
+
+        if tables
+            .entries
+            .get(&entry_id)
+            .is_some_and(|e| e.budget_id == budget_id)
+        {
+            tables.entries.remove(&entry_id);
+        }
+
+        Ok(())
+    }
+
+    fn create_category(
+        &self,
+        category_id: Option<Uuid>,
+        encrypted_blob: &[u8],
+        version_nonce: i64,
+        budget_id: Uuid,
+    ) -> Result<Uuid, DaoError> {
+        let category_id = category_id.unwrap_or_else(Uuid::now_v7);
+        let mut tables = self.tables();
+
+        tables.require_budget(budget_id)?;
+
+        if tables.categories.contains_key(&category_id) {
+            return Err(unique_violation());
+        }
+
+        tables.categories.insert(
+            category_id,
+            Category {
+                id: category_id,
+                budget_id,
+                encrypted_blob: encrypted_blob.to_vec(),
+                version_nonce,
+                modified_timestamp: SystemTime::now(),
+            },
+        );
+
+        Ok(category_id)
+    }
+
+    fn update_category(
+        &self,
+        category_id: Uuid,
+        category_encrypted_blob: &[u8],
+        version_nonce: i64,
+        expected_previous_version_nonce: i64,
+        budget_id: Uuid,
+    ) -> Result<(), DaoError> {
+        let mut tables = self.tables();
+        let category = tables.categories.get_mut(&category_id);
+
+        check_version_nonce(
+            category.as_ref().map(|c| c.version_nonce),
+            expected_previous_version_nonce,
+            category.as_ref().is_some_and(|c| c.budget_id == budget_id),
+        )?;
+
+        let category = category.expect("Category should exist after version check");
+        category.encrypted_blob = category_encrypted_blob.to_vec();
+        category.version_nonce = version_nonce;
+        category.modified_timestamp = SystemTime::now();
+
+        Ok(())
+    }
+
+    fn delete_category(&self, category_id: Uuid, budget_id: Uuid) -> Result<(), DaoError> {
+        let mut tables = self.tables();
+
+        if tables
+            .categories
+            .get(&category_id)
+            .is_some_and(|c| c.budget_id == budget_id)
+        {
+            tables.delete_category(category_id);
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl AsyncBudgetDao for Dao {
+    fn reads_from_replica(&self) -> bool {
+        false
+    }
+
+    fn primary_only(&self) -> Arc<dyn AsyncBudgetDao> {
+        Arc::new(self.clone())
+    }
+
+    async fn get_public_budget_key(
+        &self,
+        key_id: Uuid,
+        budget_id: Uuid,
+    ) -> Result<BudgetAccessKey, DaoError> {
+        BudgetDao::get_public_budget_key(self, key_id, budget_id)
+    }
+
+    async fn get_multiple_public_budget_keys(
+        &self,
+        key_ids: &[Uuid],
+        budget_ids: &[Uuid],
+    ) -> Result<Vec<BudgetAccessKey>, DaoError> {
+        BudgetDao::get_multiple_public_budget_keys(self, key_ids, budget_ids)
+    }
+
+    async fn get_budget(&self, budget_id: Uuid) -> Result<BudgetMessage, DaoError> {
+        BudgetDao::get_budget(self, budget_id)
+    }
+
+    async fn get_multiple_budgets_by_id(
+        &self,
+        budget_ids: &[Uuid],
+    ) -> Result<BudgetList, DaoError> {
+        BudgetDao::get_multiple_budgets_by_id(self, budget_ids)
+    }
+
+    async fn get_all_pending_invitations(
+        &self,
+        user_email: &str,
+    ) -> Result<BudgetShareInviteList, DaoError> {
+        BudgetDao::get_all_pending_invitations(self, user_email)
+    }
+
+    async fn update_budget(
+        &self,
+        budget_id: Uuid,
+        edited_budget_data: &[u8],
+        version_nonce: i64,
+        expected_previous_version_nonce: i64,
+    ) -> Result<(), DaoError> {
+        BudgetDao::update_budget(
+            self,
+            budget_id,
+            edited_budget_data,
+            version_nonce,
+            expected_previous_version_nonce,
+        )
+    }
+
+    async fn create_entry(
+        &self,
+        entry_id: Option<Uuid>,
+        encrypted_blob: &[u8],
+        version_nonce: i64,
+        category_id: Option<Uuid>,
+        budget_id: Uuid,
+    ) -> Result<Uuid, DaoError> {
+        BudgetDao::create_entry(
+            self,
+            entry_id,
+            encrypted_blob,
+            version_nonce,
+            category_id,
+            budget_id,
+        )
+    }
+
+    async fn create_entry_and_category(
+        &self,
+        entry_id: Option<Uuid>,
+        entry_encrypted_blob: &[u8],
+        entry_version_nonce: i64,
+        category_id: Option<Uuid>,
+        category_encrypted_blob: &[u8],
+        category_version_nonce: i64,
+        budget_id: Uuid,
+    ) -> Result<EntryIdAndCategoryId, DaoError> {
+        BudgetDao::create_entry_and_category(
+            self,
+            entry_id,
+            entry_encrypted_blob,
+            entry_version_nonce,
+            category_id,
+            category_encrypted_blob,
+            category_version_nonce,
+            budget_id,
+        )
+    }
+
+    async fn update_entry(
+        &self,
+        entry_id: Uuid,
+        entry_encrypted_blob: &[u8],
+        version_nonce: i64,
+        expected_previous_version_nonce: i64,
+        category_id: Option<Uuid>,
+        budget_id: Uuid,
+    ) -> Result<(), DaoError> {
+        BudgetDao::update_entry(
+            self,
+            entry_id,
+            entry_encrypted_blob,
+            version_nonce,
+            expected_previous_version_nonce,
+            category_id,
+            budget_id,
+        )
+    }
+
+    async fn delete_entry(&self, entry_id: Uuid, budget_id: Uuid) -> Result<(), DaoError> {
+        BudgetDao::delete_entry(self, entry_id, budget_id)
+    }
+
+    async fn create_category(
+        &self,
+        category_id: Option<Uuid>,
+        encrypted_blob: &[u8],
+        version_nonce: i64,
+        budget_id: Uuid,
+    ) -> Result<Uuid, DaoError> {
+        BudgetDao::create_category(self, category_id, encrypted_blob, version_nonce, budget_id)
+    }
+
+    async fn update_category(
+        &self,
+        category_id: Uuid,
+        category_encrypted_blob: &[u8],
+        version_nonce: i64,
+        expected_previous_version_nonce: i64,
+        budget_id: Uuid,
+    ) -> Result<(), DaoError> {
+        BudgetDao::update_category(
+            self,
+            category_id,
+            category_encrypted_blob,
+            version_nonce,
+            expected_previous_version_nonce,
+            budget_id,
+        )
+    }
+
+    async fn delete_category(&self, category_id: Uuid, budget_id: Uuid) -> Result<(), DaoError> {
+        BudgetDao::delete_category(self, category_id, budget_id)
+    }
+}
+
+impl IdempotencyDao for Dao {
+    fn reserve_key(
+        &self,
+        user_id: Uuid,
+        key: &str,
+        request_hash: &[u8],
+        expiration: SystemTime,
+    ) -> Result<KeyReservation, DaoError> {
+        let mut tables = self.tables();
+        tables.require_user(user_id)?;
+
+        let now = SystemTime::now();
+        let map_key = (user_id, String::from(key));
+
+        match tables.idempotency_keys.get(&map_key) {
+            // An expired key that hasn't been purged yet or an abandoned reservation is free to be
+            // used again
+            Some(existing)
+                if existing.expiration > now
+                    && (existing.response_status.is_some()
+                        || existing.reserved_at > now - RESERVATION_TIMEOUT) =>
+            {
+                Ok(KeyReservation::Existing(existing.clone()))
+            }
+            _ => {
+                tables.idempotency_keys.insert(
+                    map_key,
+                    IdempotencyKey {
+                        user_id,
+                        key: String::from(key),
+                        request_hash: request_hash.to_vec(),
+                        reserved_at: now,
+                        response_status: None,
+                        response_content_type: None,
+                        response_body: None,
+                        expiration,
+                    },
+                );
+
+                Ok(KeyReservation::Reserved)
+            }
+        }
+    }
+
+    fn save_response(
+        &self,
+        user_id: Uuid,
+        key: &str,
+        status: i16,
+        content_type: Option<&str>,
+        body: &[u8],
+    ) -> Result<(), DaoError> {
+        let mut tables = self.tables();
+
+        if let Some(existing) = tables
+            .idempotency_keys
+            .get_mut(&(user_id, String::from(key)))
+        {
+            existing.response_status = Some(status);
+            existing.response_content_type = content_type.map(String::from);
+            existing.response_body = Some(body.to_vec());
+        }
+
+        Ok(())
+    }
+
+    fn release_key(&self, user_id: Uuid, key: &str) -> Result<(), DaoError> {
+        let mut tables = self.tables();
+        let map_key = (user_id, String::from(key));
+
+        let is_unanswered = tables
+            .idempotency_keys
+            .get(&map_key)
+            .is_some_and(|k| k.response_status.is_none());
+
+        if is_unanswered {
+            tables.idempotency_keys.remove(&map_key);
+        }
+
+        Ok(())
+    }
+
+    fn delete_all_expired_keys(&self) -> Result<usize, DaoError> {
+        let mut tables = self.tables();
+        let now = SystemTime::now();
+
+        let count_before = tables.idempotency_keys.len();
+        tables.idempotency_keys.retain(|_, k| k.expiration > now);
+
+        Ok(count_before - tables.idempotency_keys.len())
+    }
+}
+
+// The tables always match the schema this binary was built for
+impl HealthDao for Dao {
+    fn check_connection(&self) -> Result<(), DaoError> {
+        Ok(())
+    }
+
+    fn get_latest_applied_migration_version(&self) -> Result<Option<String>, DaoError> {
+        Ok(migrations::expected_version())
+    }
+}
+
+impl JobRegistryDao for Dao {
+    fn get_job_last_run_timestamp(&self, name: &str) -> Result<Option<SystemTime>, DaoError> {
+        Ok(self.tables().job_registry.get(name).copied())
+    }
+
+    fn get_all_job_last_run_timestamps(&self) -> Result<Vec<(String, SystemTime)>, DaoError> {
+        Ok(self
+            .tables()
+            .job_registry
+            .iter()
+            .map(|(name, timestamp)| (name.clone(), *timestamp))
+            .collect())
+    }
+
+    fn set_job_last_run_timestamp(
+        &self,
+        job_name: &str,
+        timestamp: SystemTime,
+    ) -> Result<(), DaoError> {
+        self.tables()
+            .job_registry
+            .insert(String::from(job_name), timestamp);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Dao;
+
+    use crate::db::budget::testing::run_budget_dao_tests;
+
+    #[test]
+    fn test_budget_dao() {
+        run_budget_dao_tests(&Dao::new());
+    }
+}
@@ -0,0 +1,101 @@
+use diesel::migration::{Migration, MigrationSource};
+use diesel::pg::Pg;
+use diesel::sql_types::BigInt;
+use diesel::{sql_query, RunQueryDsl};
+use diesel_migrations::{embed_migrations, EmbeddedMigrations, MigrationHarness};
+
+use crate::db::{DaoError, DbConnection, DbThreadPool};
+
+pub const MIGRATIONS: EmbeddedMigrations = embed_migrations!("migrations");
+
+// Arbitrary key for the advisory lock held while migrations run
+const MIGRATION_LOCK_KEY: i64 = 0x0065_6e74_7269_6573;
+
+/// The version of the newest migration compiled into this binary, which is the schema version
+/// the binary expects the database to be at.
+pub fn expected_version() -> Option<String> {
+    MigrationSource::<Pg>::migrations(&MIGRATIONS)
+        .expect("Embedded migrations should be valid")
+        .iter()
+        .map(|m| m.name().version().to_string())
+        .max()
+}
+
+/// Reads and applies the migrations compiled into the binary
+pub trait MigrationsDao: Send + Sync {
+    /// Returns the names of the embedded migrations that haven't been applied to the database,
+    /// oldest first.
+    fn get_pending_migrations(&self) -> Result<Vec<String>, DaoError>;
+
+    /// Applies the pending migrations and returns their names. Each migration runs in its own
+    /// transaction.
+    fn run_pending_migrations(&self) -> Result<Vec<String>, DaoError>;
+}
+
+pub struct Dao {
+    db_thread_pool: DbThreadPool,
+}
+
+impl Dao {
+    pub fn new(db_thread_pool: &DbThreadPool) -> Self {
+        Self {
+            db_thread_pool: db_thread_pool.clone(),
+        }
+    }
+}
+
+impl MigrationsDao for Dao {
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn get_pending_migrations(&self) -> Result<Vec<String>, DaoError> {
+        let pending = self
+            .db_thread_pool
+            .get()?
+            .pending_migrations(MIGRATIONS)
+            .map_err(DaoError::MigrationFailure)?;
+
+        Ok(migration_names(&pending))
+    }
+
+    // An advisory lock is held while migrating so server instances that start at the same time
+    // don't race each other
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn run_pending_migrations(&self) -> Result<Vec<String>, DaoError> {
+        let mut db_connection = self.db_thread_pool.get()?;
+
+        sql_query("SELECT pg_advisory_lock($1)")
+            .bind::<BigInt, _>(MIGRATION_LOCK_KEY)
+            .execute(&mut db_connection)?;
+
+        let result = run_pending_migrations_locked(&mut db_connection);
+
+        sql_query("SELECT pg_advisory_unlock($1)")
+            .bind::<BigInt, _>(MIGRATION_LOCK_KEY)
+            .execute(&mut db_connection)?;
+
+        result
+    }
+}
+
+fn run_pending_migrations_locked(
+    db_connection: &mut DbConnection,
+) -> Result<Vec<String>, DaoError> {
+    // Another instance may have applied some migrations while this one waited for the lock
+    let pending = db_connection
+        .pending_migrations(MIGRATIONS)
+        .map_err(DaoError::MigrationFailure)?;
+    let names = migration_names(&pending);
+
+    for migration in pending {
+        db_connection
+            .run_migration(&migration)
+            .map_err(DaoError::MigrationFailure)?;
+    }
+
+    Ok(names)
+}
+
+fn migration_names(migrations: &[Box<dyn Migration<Pg>>]) -> Vec<String> {
+    let mut names: Vec<String> = migrations.iter().map(|m| m.name().to_string()).collect();
+    names.sort_unstable();
+    names
+}
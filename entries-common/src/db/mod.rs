@@ -1,35 +1,184 @@
 use diesel::pg::PgConnection;
-use diesel::r2d2::{ConnectionManager, PooledConnection};
+use diesel::r2d2::{
+    ConnectionManager, CustomizeConnection, HandleEvent, NopEventHandler, PooledConnection,
+};
+use diesel::{sql_query, ConnectionError};
+use diesel_async::pooled_connection::deadpool::{self, Pool};
+use diesel_async::pooled_connection::{AsyncDieselConnectionManager, ManagerConfig};
+use diesel_async::{AsyncConnection, AsyncPgConnection};
 use std::fmt;
 use std::time::Duration;
 
+pub mod admin_audit_log;
 pub mod auth;
 pub mod budget;
+pub mod health;
+pub mod idempotency;
 pub mod job_registry;
+pub mod memory;
+pub mod migrations;
+pub mod nonblocking;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+pub mod transaction;
 pub mod user;
 
 pub type DbThreadPool = diesel::r2d2::Pool<ConnectionManager<PgConnection>>;
 pub type DbConnection = PooledConnection<ConnectionManager<PgConnection>>;
 
+/// A pool of connections that are driven by the async runtime rather than a blocking thread.
+/// Used by the DAOs in [`nonblocking`].
+pub type AsyncDbPool = Pool<AsyncPgConnection>;
+pub type AsyncDbConnection = deadpool::Object<AsyncPgConnection>;
+
+/// The async pool for the primary database along with an optional pool for a read replica.
+/// DAOs in [`nonblocking`] send read-only queries to the replica when there is one.
+#[derive(Clone)]
+pub struct AsyncDbPools {
+    primary: AsyncDbPool,
+    replica: Option<AsyncDbPool>,
+}
+
+impl AsyncDbPools {
+    pub fn new(primary: AsyncDbPool, replica: Option<AsyncDbPool>) -> Self {
+        Self { primary, replica }
+    }
+
+    pub fn primary(&self) -> &AsyncDbPool {
+        &self.primary
+    }
+
+    pub fn replica(&self) -> Option<&AsyncDbPool> {
+        self.replica.as_ref()
+    }
+
+    /// The pool read-only queries should use: the replica if one is configured, otherwise the
+    /// primary.
+    pub fn for_reads(&self) -> &AsyncDbPool {
+        self.replica.as_ref().unwrap_or(&self.primary)
+    }
+
+    /// Returns pools that send every query to the primary. Used when the caller must see its
+    /// own writes, which may not have reached the replica yet.
+    pub fn primary_only(&self) -> Self {
+        Self {
+            primary: self.primary.clone(),
+            replica: None,
+        }
+    }
+}
+
+impl From<AsyncDbPool> for AsyncDbPools {
+    fn from(primary: AsyncDbPool) -> Self {
+        Self::new(primary, None)
+    }
+}
+
+/// If `statement_timeout` is set, Postgres cancels any statement on the pool's connections that
+/// runs for longer than that.
 pub fn create_db_thread_pool(
     database_uri: &str,
     max_db_connections: u32,
     idle_timeout: Duration,
+    statement_timeout: Option<Duration>,
+) -> DbThreadPool {
+    create_db_thread_pool_with_event_handler(
+        database_uri,
+        max_db_connections,
+        idle_timeout,
+        statement_timeout,
+        NopEventHandler,
+    )
+}
+
+pub fn create_db_thread_pool_with_event_handler(
+    database_uri: &str,
+    max_db_connections: u32,
+    idle_timeout: Duration,
+    statement_timeout: Option<Duration>,
+    event_handler: impl HandleEvent + 'static,
 ) -> DbThreadPool {
-    r2d2::Pool::builder()
+    let mut builder = r2d2::Pool::builder()
         .max_size(max_db_connections)
         .idle_timeout(Some(idle_timeout))
+        .event_handler(Box::new(event_handler));
+
+    if let Some(statement_timeout) = statement_timeout {
+        builder = builder.connection_customizer(Box::new(StatementTimeout(statement_timeout)));
+    }
+
+    builder
         .build(ConnectionManager::<PgConnection>::new(database_uri))
         .expect("Failed to create DB thread pool")
 }
 
+pub fn create_async_db_pool(
+    database_uri: &str,
+    max_db_connections: u32,
+    statement_timeout: Option<Duration>,
+) -> AsyncDbPool {
+    let mut manager_config = ManagerConfig::default();
+
+    if let Some(statement_timeout) = statement_timeout {
+        manager_config.custom_setup = Box::new(move |url| {
+            let url = url.to_owned();
+
+            Box::pin(async move {
+                let mut conn = AsyncPgConnection::establish(&url).await?;
+                diesel_async::RunQueryDsl::execute(
+                    sql_query(set_statement_timeout_sql(statement_timeout)),
+                    &mut conn,
+                )
+                .await
+                .map_err(ConnectionError::CouldntSetupConfiguration)?;
+
+                Ok(conn)
+            })
+        });
+    }
+
+    Pool::builder(
+        AsyncDieselConnectionManager::<AsyncPgConnection>::new_with_config(
+            database_uri,
+            manager_config,
+        ),
+    )
+    .max_size(max_db_connections as usize)
+    .build()
+    .expect("Failed to create async DB pool")
+}
+
+#[derive(Debug)]
+struct StatementTimeout(Duration);
+
+impl CustomizeConnection<PgConnection, diesel::r2d2::Error> for StatementTimeout {
+    fn on_acquire(&self, conn: &mut PgConnection) -> Result<(), diesel::r2d2::Error> {
+        diesel::RunQueryDsl::execute(sql_query(set_statement_timeout_sql(self.0)), conn)
+            .map_err(diesel::r2d2::Error::QueryError)?;
+
+        Ok(())
+    }
+}
+
+fn set_statement_timeout_sql(statement_timeout: Duration) -> String {
+    // A timeout of 0 disables the timeout in Postgres, so round up to at least 1ms
+    format!(
+        "SET statement_timeout = {}",
+        statement_timeout.as_millis().max(1)
+    )
+}
+
 #[derive(Debug)]
 pub enum DaoError {
     DbThreadPoolFailure(r2d2::Error),
+    AsyncDbPoolFailure(deadpool::PoolError),
     QueryFailure(diesel::result::Error),
-    OutOfDate,
+    /// The expected previous version didn't match. Carries the current version nonce of the
+    /// object, if it has one.
+    OutOfDate(Option<i64>),
     CannotRunQuery(&'static str),
     WontRunQuery, // This error indicates that the DAO refuses to run a query
+    MigrationFailure(Box<dyn std::error::Error + Send + Sync>),
 }
 
 impl std::error::Error for DaoError {}
@@ -40,10 +189,13 @@ impl fmt::Display for DaoError {
             DaoError::DbThreadPoolFailure(e) => {
                 write!(f, "DaoError: Failed to obtain DB connection: {e}")
             }
+            DaoError::AsyncDbPoolFailure(e) => {
+                write!(f, "DaoError: Failed to obtain async DB connection: {e}")
+            }
             DaoError::QueryFailure(e) => {
                 write!(f, "DaoError: Query failed: {e}")
             }
-            DaoError::OutOfDate => {
+            DaoError::OutOfDate(_) => {
                 write!(f, "DaoError: Version nonce was out of date")
             }
             DaoError::CannotRunQuery(msg) => {
@@ -52,6 +204,9 @@ impl fmt::Display for DaoError {
             DaoError::WontRunQuery => {
                 write!(f, "DaoError: DAO will not run query")
             }
+            DaoError::MigrationFailure(e) => {
+                write!(f, "DaoError: Failed to read or run migrations: {e}")
+            }
         }
     }
 }
@@ -62,6 +217,12 @@ impl From<r2d2::Error> for DaoError {
     }
 }
 
+impl From<deadpool::PoolError> for DaoError {
+    fn from(error: deadpool::PoolError) -> Self {
+        DaoError::AsyncDbPoolFailure(error)
+    }
+}
+
 impl From<diesel::result::Error> for DaoError {
     fn from(error: diesel::result::Error) -> Self {
         DaoError::QueryFailure(error)
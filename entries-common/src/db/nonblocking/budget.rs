@@ -0,0 +1,530 @@
+use async_trait::async_trait;
+use diesel::associations::GroupedBy;
+use diesel::{dsl, QueryDsl};
+use diesel_async::scoped_futures::ScopedFutureExt;
+use diesel_async::RunQueryDsl;
+use std::sync::Arc;
+use std::time::SystemTime;
+use uuid::Uuid;
+
+use crate::db::budget::{budget_message, invite_message, out_of_date, queries};
+use crate::db::transaction::run_repeatable_read_async;
+use crate::db::{AsyncDbPool, AsyncDbPools, DaoError};
+use crate::messages::BudgetShareInviteList;
+use crate::messages::{Budget as BudgetMessage, BudgetList, EntryIdAndCategoryId};
+use crate::models::budget::Budget;
+use crate::models::budget_access_key::BudgetAccessKey;
+use crate::models::budget_share_invite::BudgetShareInvitePublicData;
+use crate::models::category::{Category, NewCategory};
+use crate::models::entry::{Entry, NewEntry};
+use crate::schema::budget_access_keys::dsl::budget_access_keys;
+use crate::schema::budgets::dsl::budgets;
+use crate::schema::categories::dsl::categories;
+use crate::schema::entries::dsl::entries;
+
+/// The budget queries that handle most request traffic, run on the async runtime
+#[async_trait]
+pub trait AsyncBudgetDao: Send + Sync {
+    /// Whether read-only methods may return slightly stale data from a read replica
+    fn reads_from_replica(&self) -> bool;
+
+    /// Returns a DAO that sends every query, including reads, to the primary. Used when the
+    /// caller must see its own writes, which may not have reached the replica yet.
+    fn primary_only(&self) -> Arc<dyn AsyncBudgetDao>;
+
+    async fn get_public_budget_key(
+        &self,
+        key_id: Uuid,
+        budget_id: Uuid,
+    ) -> Result<BudgetAccessKey, DaoError>;
+
+    async fn get_multiple_public_budget_keys(
+        &self,
+        key_ids: &[Uuid],
+        budget_ids: &[Uuid],
+    ) -> Result<Vec<BudgetAccessKey>, DaoError>;
+
+    async fn get_budget(&self, budget_id: Uuid) -> Result<BudgetMessage, DaoError>;
+
+    async fn get_multiple_budgets_by_id(&self, budget_ids: &[Uuid])
+        -> Result<BudgetList, DaoError>;
+
+    async fn get_all_pending_invitations(
+        &self,
+        user_email: &str,
+    ) -> Result<BudgetShareInviteList, DaoError>;
+
+    async fn update_budget(
+        &self,
+        budget_id: Uuid,
+        edited_budget_data: &[u8],
+        version_nonce: i64,
+        expected_previous_version_nonce: i64,
+    ) -> Result<(), DaoError>;
+
+    async fn create_entry(
+        &self,
+        entry_id: Option<Uuid>,
+        encrypted_blob: &[u8],
+        version_nonce: i64,
+        category_id: Option<Uuid>,
+        budget_id: Uuid,
+    ) -> Result<Uuid, DaoError>;
+
+    #[allow(clippy::too_many_arguments)]
+    async fn create_entry_and_category(
+        &self,
+        entry_id: Option<Uuid>,
+        entry_encrypted_blob: &[u8],
+        entry_version_nonce: i64,
+        category_id: Option<Uuid>,
+        category_encrypted_blob: &[u8],
+        category_version_nonce: i64,
+        budget_id: Uuid,
+    ) -> Result<EntryIdAndCategoryId, DaoError>;
+
+    async fn update_entry(
+        &self,
+        entry_id: Uuid,
+        entry_encrypted_blob: &[u8],
+        version_nonce: i64,
+        expected_previous_version_nonce: i64,
+        category_id: Option<Uuid>,
+        budget_id: Uuid,
+    ) -> Result<(), DaoError>;
+
+    async fn delete_entry(&self, entry_id: Uuid, budget_id: Uuid) -> Result<(), DaoError>;
+
+    async fn create_category(
+        &self,
+        category_id: Option<Uuid>,
+        encrypted_blob: &[u8],
+        version_nonce: i64,
+        budget_id: Uuid,
+    ) -> Result<Uuid, DaoError>;
+
+    async fn update_category(
+        &self,
+        category_id: Uuid,
+        category_encrypted_blob: &[u8],
+        version_nonce: i64,
+        expected_previous_version_nonce: i64,
+        budget_id: Uuid,
+    ) -> Result<(), DaoError>;
+
+    async fn delete_category(&self, category_id: Uuid, budget_id: Uuid) -> Result<(), DaoError>;
+}
+
+pub struct Dao {
+    db_pools: AsyncDbPools,
+    db_pool: AsyncDbPool,
+    read_db_pool: AsyncDbPool,
+}
+
+impl Dao {
+    pub fn new(db_pools: &AsyncDbPools) -> Self {
+        Self {
+            db_pools: db_pools.clone(),
+            db_pool: db_pools.primary().clone(),
+            read_db_pool: db_pools.for_reads().clone(),
+        }
+    }
+}
+
+#[async_trait]
+impl AsyncBudgetDao for Dao {
+    fn reads_from_replica(&self) -> bool {
+        self.db_pools.replica().is_some()
+    }
+
+    fn primary_only(&self) -> Arc<dyn AsyncBudgetDao> {
+        Arc::new(Dao::new(&self.db_pools.primary_only()))
+    }
+    // Access keys authorize requests, so they are always read from the primary. A replica
+    // that hasn't caught up could still hold the key of a user who has left the budget.
+    #[tracing::instrument(level = "debug", skip_all)]
+    async fn get_public_budget_key(
+        &self,
+        key_id: Uuid,
+        budget_id: Uuid,
+    ) -> Result<BudgetAccessKey, DaoError> {
+        Ok(budget_access_keys
+            .find((key_id, budget_id))
+            .get_result::<BudgetAccessKey>(&mut self.db_pool.get().await?)
+            .await?)
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    async fn get_multiple_public_budget_keys(
+        &self,
+        key_ids: &[Uuid],
+        budget_ids: &[Uuid],
+    ) -> Result<Vec<BudgetAccessKey>, DaoError> {
+        Ok(queries::multiple_public_budget_keys(key_ids, budget_ids)
+            .get_results::<BudgetAccessKey>(&mut self.db_pool.get().await?)
+            .await?)
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    async fn get_budget(&self, budget_id: Uuid) -> Result<BudgetMessage, DaoError> {
+        let mut db_connection = self.read_db_pool.get().await?;
+
+        let output_budget = db_connection
+            .build_transaction()
+            .run::<_, diesel::result::Error, _>(|conn| {
+                async move {
+                    let budget = budgets.find(budget_id).get_result::<Budget>(conn).await?;
+                    let loaded_categories = queries::categories_of_budget(&budget)
+                        .load::<Category>(conn)
+                        .await?;
+                    let loaded_entries = queries::entries_of_budget(&budget)
+                        .load::<Entry>(conn)
+                        .await?;
+
+                    Ok(budget_message(budget, loaded_categories, loaded_entries))
+                }
+                .scope_boxed()
+            })
+            .await?;
+
+        Ok(output_budget)
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    async fn get_multiple_budgets_by_id(
+        &self,
+        budget_ids: &[Uuid],
+    ) -> Result<BudgetList, DaoError> {
+        let mut db_connection = self.read_db_pool.get().await?;
+
+        let output_budgets = db_connection
+            .build_transaction()
+            .run::<_, diesel::result::Error, _>(|conn| {
+                async move {
+                    let loaded_budgets = queries::budgets_by_id(budget_ids)
+                        .get_results::<Budget>(conn)
+                        .await?;
+                    let loaded_categories = queries::categories_of_budgets(&loaded_budgets)
+                        .load::<Category>(conn)
+                        .await?
+                        .grouped_by(&loaded_budgets);
+                    let loaded_entries = queries::entries_of_budgets(&loaded_budgets)
+                        .load::<Entry>(conn)
+                        .await?
+                        .grouped_by(&loaded_budgets);
+
+                    let output_budgets = loaded_budgets
+                        .into_iter()
+                        .zip(loaded_categories)
+                        .zip(loaded_entries)
+                        .map(|((budget, budget_categories), budget_entries)| {
+                            budget_message(budget, budget_categories, budget_entries)
+                        })
+                        .collect();
+
+                    Ok(output_budgets)
+                }
+                .scope_boxed()
+            })
+            .await?;
+
+        Ok(BudgetList {
+            budgets: output_budgets,
+        })
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    async fn get_all_pending_invitations(
+        &self,
+        user_email: &str,
+    ) -> Result<BudgetShareInviteList, DaoError> {
+        let invites = queries::pending_invitations(user_email)
+            .load::<BudgetShareInvitePublicData>(&mut self.read_db_pool.get().await?)
+            .await?
+            .into_iter()
+            .map(invite_message)
+            .collect();
+
+        Ok(BudgetShareInviteList { invites })
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    async fn update_budget(
+        &self,
+        budget_id: Uuid,
+        edited_budget_data: &[u8],
+        version_nonce: i64,
+        expected_previous_version_nonce: i64,
+    ) -> Result<(), DaoError> {
+        let mut db_connection = self.db_pool.get().await?;
+
+        run_repeatable_read_async(&mut db_connection, |conn| {
+            async move {
+                let affected_row_count = queries::update_budget(
+                    budget_id,
+                    edited_budget_data,
+                    version_nonce,
+                    expected_previous_version_nonce,
+                )
+                .execute(conn)
+                .await?;
+
+                if affected_row_count == 0 {
+                    // Check whether the update failed because the record wasn't found or
+                    // because the version_nonce was out-of-date
+                    let current_version_nonce = queries::budget_version_nonce(budget_id)
+                        .first::<i64>(conn)
+                        .await?;
+
+                    return Err(out_of_date(
+                        current_version_nonce,
+                        expected_previous_version_nonce,
+                    ));
+                }
+
+                Ok(())
+            }
+            .scope_boxed()
+        })
+        .await
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    async fn create_entry(
+        &self,
+        entry_id: Option<Uuid>,
+        encrypted_blob: &[u8],
+        version_nonce: i64,
+        category_id: Option<Uuid>,
+        budget_id: Uuid,
+    ) -> Result<Uuid, DaoError> {
+        let current_time = SystemTime::now();
+        let entry_id = entry_id.unwrap_or_else(Uuid::now_v7);
+
+        let new_entry = NewEntry {
+            id: entry_id,
+            budget_id,
+            category_id,
+            encrypted_blob,
+            version_nonce,
+            modified_timestamp: current_time,
+        };
+
+        let mut db_connection = self.db_pool.get().await?;
+
+        db_connection
+            .build_transaction()
+            .run::<_, diesel::result::Error, _>(|conn| {
+                async move {
+                    if let Some(category_id) = category_id {
+                        queries::category_in_budget(category_id, budget_id)
+                            .get_result::<Uuid>(conn)
+                            .await?;
+                    }
+
+                    dsl::insert_into(entries)
+                        .values(&new_entry)
+                        .execute(conn)
+                        .await
+                }
+                .scope_boxed()
+            })
+            .await?;
+
+        Ok(entry_id)
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    async fn create_entry_and_category(
+        &self,
+        entry_id: Option<Uuid>,
+        entry_encrypted_blob: &[u8],
+        entry_version_nonce: i64,
+        category_id: Option<Uuid>,
+        category_encrypted_blob: &[u8],
+        category_version_nonce: i64,
+        budget_id: Uuid,
+    ) -> Result<EntryIdAndCategoryId, DaoError> {
+        let current_time = SystemTime::now();
+        let category_id = category_id.unwrap_or_else(Uuid::now_v7);
+        let entry_id = entry_id.unwrap_or_else(Uuid::now_v7);
+
+        let new_category = NewCategory {
+            id: category_id,
+            budget_id,
+            encrypted_blob: category_encrypted_blob,
+            version_nonce: category_version_nonce,
+            modified_timestamp: current_time,
+        };
+
+        let new_entry = NewEntry {
+            id: entry_id,
+            budget_id,
+            category_id: Some(category_id),
+            encrypted_blob: entry_encrypted_blob,
+            version_nonce: entry_version_nonce,
+            modified_timestamp: current_time,
+        };
+
+        let mut db_connection = self.db_pool.get().await?;
+
+        db_connection
+            .build_transaction()
+            .run::<_, diesel::result::Error, _>(|conn| {
+                async move {
+                    dsl::insert_into(categories)
+                        .values(&new_category)
+                        .execute(conn)
+                        .await?;
+
+                    dsl::insert_into(entries)
+                        .values(&new_entry)
+                        .execute(conn)
+                        .await?;
+
+                    Ok(())
+                }
+                .scope_boxed()
+            })
+            .await?;
+
+        Ok(EntryIdAndCategoryId {
+            entry_id: entry_id.into(),
+            category_id: category_id.into(),
+        })
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    async fn update_entry(
+        &self,
+        entry_id: Uuid,
+        entry_encrypted_blob: &[u8],
+        version_nonce: i64,
+        expected_previous_version_nonce: i64,
+        category_id: Option<Uuid>,
+        budget_id: Uuid,
+    ) -> Result<(), DaoError> {
+        let mut db_connection = self.db_pool.get().await?;
+
+        run_repeatable_read_async(&mut db_connection, |conn| {
+            async move {
+                let affected_row_count = queries::update_entry(
+                    entry_id,
+                    entry_encrypted_blob,
+                    version_nonce,
+                    expected_previous_version_nonce,
+                    category_id,
+                    budget_id,
+                )
+                .execute(conn)
+                .await?;
+
+                if affected_row_count == 0 {
+                    // Check whether the update failed because the record wasn't found or
+                    // because the version_nonce was out-of-date
+                    let current_version_nonce = queries::entry_version_nonce(entry_id, budget_id)
+                        .first::<i64>(conn)
+                        .await?;
+
+                    return Err(out_of_date(
+                        current_version_nonce,
+                        expected_previous_version_nonce,
+                    ));
+                }
+
+                Ok(())
+            }
+            .scope_boxed()
+        })
+        .await
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    async fn delete_entry(&self, entry_id: Uuid, budget_id: Uuid) -> Result<(), DaoError> {
+        queries::delete_entry(entry_id, budget_id)
+            .execute(&mut self.db_pool.get().await?)
+            .await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    async fn create_category(
+        &self,
+        category_id: Option<Uuid>,
+        encrypted_blob: &[u8],
+        version_nonce: i64,
+        budget_id: Uuid,
+    ) -> Result<Uuid, DaoError> {
+        let current_time = SystemTime::now();
+        let category_id = category_id.unwrap_or_else(Uuid::now_v7);
+
+        let new_category = NewCategory {
+            id: category_id,
+            budget_id,
+            encrypted_blob,
+            version_nonce,
+            modified_timestamp: current_time,
+        };
+
+        dsl::insert_into(categories)
+            .values(&new_category)
+            .execute(&mut self.db_pool.get().await?)
+            .await?;
+
+        Ok(category_id)
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    async fn update_category(
+        &self,
+        category_id: Uuid,
+        category_encrypted_blob: &[u8],
+        version_nonce: i64,
+        expected_previous_version_nonce: i64,
+        budget_id: Uuid,
+    ) -> Result<(), DaoError> {
+        let mut db_connection = self.db_pool.get().await?;
+
+        run_repeatable_read_async(&mut db_connection, |conn| {
+            async move {
+                let affected_row_count = queries::update_category(
+                    category_id,
+                    category_encrypted_blob,
+                    version_nonce,
+                    expected_previous_version_nonce,
+                    budget_id,
+                )
+                .execute(conn)
+                .await?;
+
+                if affected_row_count == 0 {
+                    // Check whether the update failed because the record wasn't found or
+                    // because the version_nonce was out-of-date
+                    let current_version_nonce =
+                        queries::category_version_nonce(category_id, budget_id)
+                            .first::<i64>(conn)
+                            .await?;
+
+                    return Err(out_of_date(
+                        current_version_nonce,
+                        expected_previous_version_nonce,
+                    ));
+                }
+
+                Ok(())
+            }
+            .scope_boxed()
+        })
+        .await
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    async fn delete_category(&self, category_id: Uuid, budget_id: Uuid) -> Result<(), DaoError> {
+        queries::delete_category(category_id, budget_id)
+            .execute(&mut self.db_pool.get().await?)
+            .await?;
+
+        Ok(())
+    }
+}
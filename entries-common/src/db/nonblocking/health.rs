@@ -0,0 +1,37 @@
+use diesel::dsl::sql;
+use diesel::sql_types::{Double, Nullable};
+use diesel_async::RunQueryDsl;
+use std::time::Duration;
+
+use crate::db::{AsyncDbPool, DaoError};
+
+pub struct Dao {
+    db_pool: AsyncDbPool,
+}
+
+impl Dao {
+    pub fn new(db_pool: &AsyncDbPool) -> Self {
+        Self {
+            db_pool: db_pool.clone(),
+        }
+    }
+
+    /// Returns how far the database behind the pool is behind its primary, measured from the
+    /// commit time of the last transaction it replayed. Returns `None` if the database isn't a
+    /// replica or hasn't replayed anything yet.
+    ///
+    /// If the primary has been idle, this grows even though the replica is up to date, so it is
+    /// an upper bound on the lag.
+    #[tracing::instrument(level = "debug", skip_all)]
+    pub async fn get_replication_lag(&self) -> Result<Option<Duration>, DaoError> {
+        let lag_secs = diesel::select(sql::<Nullable<Double>>(
+            "CASE WHEN pg_is_in_recovery() \
+             THEN EXTRACT(EPOCH FROM now() - pg_last_xact_replay_timestamp())::float8 \
+             END",
+        ))
+        .get_result::<Option<f64>>(&mut self.db_pool.get().await?)
+        .await?;
+
+        Ok(lag_secs.map(|s| Duration::from_secs_f64(s.max(0.0))))
+    }
+}
@@ -0,0 +1,17 @@
+//! DAOs that run their queries on the async runtime using an [`AsyncDbPool`] rather than
+//! blocking a thread for the duration of each query. These cover the paths that handle the
+//! bulk of request traffic; everything else (including the job scheduler) uses the blocking
+//! DAOs in the parent module. Statements both kinds of DAO run are built once in
+//! [`budget::queries`](super::budget::queries).
+//!
+//! Read-only methods use the replica in [`AsyncDbPools`] when one is configured, so they may
+//! return slightly stale data. Callers that need to see their own writes should pass
+//! [`AsyncDbPools::primary_only`]. Access key lookups are the exception: they authorize
+//! requests, so they always go to the primary.
+//!
+//! [`AsyncDbPool`]: super::AsyncDbPool
+//! [`AsyncDbPools`]: super::AsyncDbPools
+//! [`AsyncDbPools::primary_only`]: super::AsyncDbPools::primary_only
+
+pub mod budget;
+pub mod health;
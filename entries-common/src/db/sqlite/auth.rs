@@ -0,0 +1,254 @@
+use diesel::{dsl, ExpressionMethods, JoinOnDsl, NullableExpressionMethods, QueryDsl, RunQueryDsl};
+use rand::{rngs::OsRng, Rng};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use uuid::Uuid;
+
+use crate::db::auth::{AuthDao, UserAuthStringHashAndStatus};
+use crate::db::sqlite::schema::blacklisted_tokens as blacklisted_token_fields;
+use crate::db::sqlite::schema::blacklisted_tokens::dsl::blacklisted_tokens;
+use crate::db::sqlite::schema::signin_nonces as signin_nonce_fields;
+use crate::db::sqlite::schema::signin_nonces::dsl::signin_nonces;
+use crate::db::sqlite::schema::user_backup_codes as user_backup_code_fields;
+use crate::db::sqlite::schema::user_backup_codes::dsl::user_backup_codes;
+use crate::db::sqlite::schema::user_otps as user_otp_fields;
+use crate::db::sqlite::schema::user_otps::dsl::user_otps;
+use crate::db::sqlite::schema::users as user_fields;
+use crate::db::sqlite::schema::users::dsl::users;
+use crate::db::sqlite::sql_types::{TimestampValue, UuidValue};
+use crate::db::sqlite::Dao;
+use crate::db::DaoError;
+use crate::messages::SigninNonceAndHashParams;
+
+impl AuthDao for Dao {
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn get_user_auth_string_hash_and_status(
+        &self,
+        user_email: &str,
+    ) -> Result<UserAuthStringHashAndStatus, DaoError> {
+        let (user_id, is_user_verified, auth_string_hash) = users
+            .select((
+                user_fields::id,
+                user_fields::is_verified,
+                user_fields::auth_string_hash,
+            ))
+            .filter(user_fields::email.eq(user_email))
+            .get_result::<(Uuid, bool, String)>(&mut self.db_pool.get()?)?;
+
+        if !is_user_verified {
+            return Ok(UserAuthStringHashAndStatus {
+                user_id,
+                is_user_verified,
+                auth_string_hash: String::new(),
+            });
+        }
+
+        Ok(UserAuthStringHashAndStatus {
+            user_id,
+            is_user_verified,
+            auth_string_hash,
+        })
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn blacklist_token(
+        &self,
+        token_signature: &[u8],
+        token_expiration: u64,
+    ) -> Result<(), DaoError> {
+        let token_expiration = UNIX_EPOCH + Duration::from_secs(token_expiration);
+
+        dsl::insert_into(blacklisted_tokens)
+            .values((
+                blacklisted_token_fields::token_signature.eq(token_signature),
+                blacklisted_token_fields::token_expiration.eq(TimestampValue(token_expiration)),
+            ))
+            .execute(&mut self.db_pool.get()?)?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn check_is_token_on_blacklist_and_blacklist(
+        &self,
+        token_signature: &[u8],
+        token_expiration: u64,
+    ) -> Result<bool, DaoError> {
+        let token_expiration = UNIX_EPOCH + Duration::from_secs(token_expiration);
+
+        let mut db_connection = self.db_pool.get()?;
+
+        db_connection.immediate_transaction::<_, DaoError, _>(|conn| {
+            let count = blacklisted_tokens
+                .filter(blacklisted_token_fields::token_signature.eq(token_signature))
+                .count()
+                .get_result::<i64>(conn)?;
+
+            if count > 0 {
+                return Ok(true);
+            }
+
+            dsl::insert_into(blacklisted_tokens)
+                .values((
+                    blacklisted_token_fields::token_signature.eq(token_signature),
+                    blacklisted_token_fields::token_expiration.eq(TimestampValue(token_expiration)),
+                ))
+                .execute(conn)?;
+
+            Ok(false)
+        })
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn save_otp(
+        &self,
+        otp: &str,
+        user_email: &str,
+        expiration: SystemTime,
+    ) -> Result<(), DaoError> {
+        dsl::insert_into(user_otps)
+            .values((
+                user_otp_fields::user_email.eq(user_email),
+                user_otp_fields::otp.eq(otp),
+                user_otp_fields::expiration.eq(TimestampValue(expiration)),
+            ))
+            .on_conflict(user_otp_fields::user_email)
+            .do_update()
+            .set((
+                user_otp_fields::otp.eq(otp),
+                user_otp_fields::expiration.eq(TimestampValue(expiration)),
+            ))
+            .execute(&mut self.db_pool.get()?)?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn check_unexpired_otp(&self, otp: &str, user_email: &str) -> Result<bool, DaoError> {
+        Ok(dsl::select(dsl::exists(
+            user_otps
+                .find(user_email)
+                .filter(user_otp_fields::otp.eq(otp))
+                .filter(user_otp_fields::expiration.gt(TimestampValue(SystemTime::now()))),
+        ))
+        .get_result(&mut self.db_pool.get()?)?)
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn delete_otp(&self, otp: &str, user_email: &str) -> Result<(), DaoError> {
+        diesel::delete(
+            user_otps
+                .find(user_email)
+                .filter(user_otp_fields::otp.eq(otp)),
+        )
+        .execute(&mut self.db_pool.get()?)?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn delete_all_otps_for_user(&self, user_email: &str) -> Result<usize, DaoError> {
+        Ok(diesel::delete(user_otps.find(user_email)).execute(&mut self.db_pool.get()?)?)
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn delete_all_expired_otps(&self) -> Result<(), DaoError> {
+        dsl::delete(
+            user_otps.filter(user_otp_fields::expiration.lt(TimestampValue(SystemTime::now()))),
+        )
+        .execute(&mut self.db_pool.get()?)?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn replace_backup_codes(&self, user_id: Uuid, codes: &[String]) -> Result<(), DaoError> {
+        let codes = codes
+            .iter()
+            .map(|code| {
+                (
+                    user_backup_code_fields::user_id.eq(UuidValue(user_id)),
+                    user_backup_code_fields::code.eq(code),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let mut db_connection = self.db_pool.get()?;
+
+        db_connection.immediate_transaction::<_, DaoError, _>(|conn| {
+            diesel::delete(
+                user_backup_codes.filter(user_backup_code_fields::user_id.eq(UuidValue(user_id))),
+            )
+            .execute(conn)?;
+
+            dsl::insert_into(user_backup_codes)
+                .values(&codes)
+                .execute(conn)?;
+
+            Ok(())
+        })
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn delete_backup_code(&self, code: &str, user_id: Uuid) -> Result<(), DaoError> {
+        diesel::delete(user_backup_codes.find((UuidValue(user_id), code)))
+            .execute(&mut self.db_pool.get()?)?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn clear_all_expired_tokens(&self) -> Result<usize, DaoError> {
+        Ok(diesel::delete(blacklisted_tokens.filter(
+            blacklisted_token_fields::token_expiration.lt(TimestampValue(SystemTime::now())),
+        ))
+        .execute(&mut self.db_pool.get()?)?)
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn get_and_refresh_signin_nonce(&self, user_email: &str) -> Result<i32, DaoError> {
+        let mut db_connection = self.db_pool.get()?;
+
+        db_connection.immediate_transaction::<_, DaoError, _>(|conn| {
+            let nonce = signin_nonces
+                .select(signin_nonce_fields::nonce)
+                .find(user_email)
+                .get_result::<i32>(conn)?;
+
+            dsl::update(signin_nonces.find(user_email))
+                .set(signin_nonce_fields::nonce.eq(OsRng.gen::<i32>()))
+                .execute(conn)?;
+
+            Ok(nonce)
+        })
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn get_auth_string_data_signin_nonce(
+        &self,
+        user_email: &str,
+    ) -> Result<SigninNonceAndHashParams, DaoError> {
+        let (salt, mem_cost, parallel, iters, nonce) = users
+            .left_join(signin_nonces.on(signin_nonce_fields::user_email.eq(user_fields::email)))
+            .filter(user_fields::email.eq(user_email))
+            .select((
+                user_fields::auth_string_salt,
+                user_fields::auth_string_memory_cost_kib,
+                user_fields::auth_string_parallelism_factor,
+                user_fields::auth_string_iters,
+                signin_nonce_fields::nonce.nullable(),
+            ))
+            .first::<(Vec<u8>, i32, i32, i32, Option<i32>)>(&mut self.db_pool.get()?)?;
+
+        if let Some(n) = nonce {
+            Ok(SigninNonceAndHashParams {
+                auth_string_salt: salt,
+                auth_string_memory_cost_kib: mem_cost,
+                auth_string_parallelism_factor: parallel,
+                auth_string_iters: iters,
+                nonce: n,
+            })
+        } else {
+            Err(DaoError::QueryFailure(diesel::result::Error::NotFound))
+        }
+    }
+}
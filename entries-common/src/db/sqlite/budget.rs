@@ -0,0 +1,976 @@
+use async_trait::async_trait;
+use diesel::{dsl, BoolExpressionMethods, Connection, ExpressionMethods, QueryDsl, RunQueryDsl};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use uuid::Uuid;
+
+use crate::db::budget::BudgetDao;
+use crate::db::budget::{budget_message, out_of_date};
+use crate::db::nonblocking::budget::AsyncBudgetDao;
+use crate::db::sqlite::sql_types::{uuid_values, TimestampValue, UuidValue};
+use crate::db::sqlite::Dao;
+use crate::db::DaoError;
+use crate::messages::{Budget as BudgetMessage, BudgetList, EntryIdAndCategoryId, InvitationId};
+use crate::messages::{BudgetFrame, BudgetFrameCategory};
+use crate::messages::{BudgetIdAndEncryptionKey, CategoryWithTempId};
+use crate::messages::{BudgetShareInvite, BudgetShareInviteList};
+use crate::models::budget::Budget;
+use crate::models::budget_accept_key::BudgetAcceptKey;
+use crate::models::budget_access_key::BudgetAccessKey;
+use crate::models::budget_share_invite::BudgetShareInvitePublicData;
+use crate::models::category::Category;
+use crate::models::entry::Entry;
+
+use crate::db::sqlite::schema::budget_accept_keys as budget_accept_key_fields;
+use crate::db::sqlite::schema::budget_accept_keys::dsl::budget_accept_keys;
+use crate::db::sqlite::schema::budget_access_keys as budget_access_key_fields;
+use crate::db::sqlite::schema::budget_access_keys::dsl::budget_access_keys;
+use crate::db::sqlite::schema::budget_share_invites as budget_share_invite_fields;
+use crate::db::sqlite::schema::budget_share_invites::dsl::budget_share_invites;
+use crate::db::sqlite::schema::budgets as budget_fields;
+use crate::db::sqlite::schema::budgets::dsl::budgets;
+use crate::db::sqlite::schema::categories as category_fields;
+use crate::db::sqlite::schema::categories::dsl::categories;
+use crate::db::sqlite::schema::entries as entry_fields;
+use crate::db::sqlite::schema::entries::dsl::entries;
+
+impl BudgetDao for Dao {
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn get_public_budget_key(
+        &self,
+        key_id: Uuid,
+        budget_id: Uuid,
+    ) -> Result<BudgetAccessKey, DaoError> {
+        Ok(budget_access_keys
+            .find((UuidValue(key_id), UuidValue(budget_id)))
+            .get_result::<BudgetAccessKey>(&mut self.db_pool.get()?)?)
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn get_multiple_public_budget_keys(
+        &self,
+        key_ids: &[Uuid],
+        budget_ids: &[Uuid],
+    ) -> Result<Vec<BudgetAccessKey>, DaoError> {
+        Ok(budget_access_keys
+            .filter(
+                budget_access_key_fields::key_id
+                    .eq_any(uuid_values(key_ids))
+                    .and(budget_access_key_fields::budget_id.eq_any(uuid_values(budget_ids))),
+            )
+            .get_results::<BudgetAccessKey>(&mut self.db_pool.get()?)?)
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn get_budget_accept_public_key(
+        &self,
+        key_id: Uuid,
+        budget_id: Uuid,
+    ) -> Result<BudgetAcceptKey, DaoError> {
+        Ok(budget_accept_keys
+            .find((UuidValue(key_id), UuidValue(budget_id)))
+            .get_result::<BudgetAcceptKey>(&mut self.db_pool.get()?)?)
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn get_budget_invite_sender_public_key(
+        &self,
+        invitation_id: Uuid,
+    ) -> Result<Vec<u8>, DaoError> {
+        Ok(budget_share_invites
+            .select(budget_share_invite_fields::sender_public_key)
+            .find(UuidValue(invitation_id))
+            .get_result::<Vec<u8>>(&mut self.db_pool.get()?)?)
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn get_budget(&self, budget_id: Uuid) -> Result<BudgetMessage, DaoError> {
+        let mut db_connection = self.db_pool.get()?;
+
+        db_connection.transaction::<_, DaoError, _>(|conn| {
+            let budget = budgets
+                .find(UuidValue(budget_id))
+                .get_result::<Budget>(conn)?;
+            let loaded_categories = categories
+                .filter(category_fields::budget_id.eq(UuidValue(budget_id)))
+                .load::<Category>(conn)?;
+            let loaded_entries = entries
+                .filter(entry_fields::budget_id.eq(UuidValue(budget_id)))
+                .load::<Entry>(conn)?;
+
+            Ok(budget_message(budget, loaded_categories, loaded_entries))
+        })
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn get_multiple_budgets_by_id(&self, budget_ids: &[Uuid]) -> Result<BudgetList, DaoError> {
+        let mut db_connection = self.db_pool.get()?;
+
+        db_connection.transaction::<_, DaoError, _>(|conn| {
+            // Ordered so the same data always produces the same response, which ETags depend on
+            let loaded_budgets = budgets
+                .filter(budget_fields::id.eq_any(uuid_values(budget_ids)))
+                .order(budget_fields::id)
+                .get_results::<Budget>(conn)?;
+
+            let mut categories_by_budget = HashMap::<Uuid, Vec<Category>>::new();
+            for category in categories
+                .filter(category_fields::budget_id.eq_any(uuid_values(budget_ids)))
+                .order(category_fields::id)
+                .load::<Category>(conn)?
+            {
+                categories_by_budget
+                    .entry(category.budget_id)
+                    .or_default()
+                    .push(category);
+            }
+
+            let mut entries_by_budget = HashMap::<Uuid, Vec<Entry>>::new();
+            for entry in entries
+                .filter(entry_fields::budget_id.eq_any(uuid_values(budget_ids)))
+                .order(entry_fields::id)
+                .load::<Entry>(conn)?
+            {
+                entries_by_budget
+                    .entry(entry.budget_id)
+                    .or_default()
+                    .push(entry);
+            }
+
+            let budgets_out = loaded_budgets
+                .into_iter()
+                .map(|budget| {
+                    let budget_categories =
+                        categories_by_budget.remove(&budget.id).unwrap_or_default();
+                    let budget_entries = entries_by_budget.remove(&budget.id).unwrap_or_default();
+                    budget_message(budget, budget_categories, budget_entries)
+                })
+                .collect();
+
+            Ok(BudgetList {
+                budgets: budgets_out,
+            })
+        })
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn create_budget(
+        &self,
+        encrypted_blob: &[u8],
+        version_nonce: i64,
+        budget_categories: &[CategoryWithTempId],
+        user_public_budget_key: &[u8],
+    ) -> Result<BudgetFrame, DaoError> {
+        let current_time = SystemTime::now();
+        let budget_id = Uuid::now_v7();
+        let key_id = Uuid::now_v7();
+
+        let mut new_categories = Vec::new();
+        let mut category_ids = Vec::with_capacity(budget_categories.len());
+
+        for category in budget_categories.iter() {
+            // Client-generated IDs are validated by the caller
+            let category_id = category
+                .id
+                .as_ref()
+                .and_then(|id| Uuid::try_from(id).ok())
+                .unwrap_or_else(Uuid::now_v7);
+
+            new_categories.push((
+                category_fields::id.eq(UuidValue(category_id)),
+                category_fields::budget_id.eq(UuidValue(budget_id)),
+                category_fields::encrypted_blob.eq(&category.encrypted_blob),
+                category_fields::version_nonce.eq(category.version_nonce),
+                category_fields::modified_timestamp.eq(TimestampValue(current_time)),
+            ));
+
+            category_ids.push(BudgetFrameCategory {
+                temp_id: category.temp_id,
+                real_id: category_id.into(),
+            });
+        }
+
+        let mut db_connection = self.db_pool.get()?;
+
+        db_connection.immediate_transaction::<_, DaoError, _>(|conn| {
+            dsl::insert_into(budgets)
+                .values((
+                    budget_fields::id.eq(UuidValue(budget_id)),
+                    budget_fields::encrypted_blob.eq(encrypted_blob),
+                    budget_fields::version_nonce.eq(version_nonce),
+                    budget_fields::modified_timestamp.eq(TimestampValue(current_time)),
+                ))
+                .execute(conn)?;
+
+            dsl::insert_into(budget_access_keys)
+                .values((
+                    budget_access_key_fields::key_id.eq(UuidValue(key_id)),
+                    budget_access_key_fields::budget_id.eq(UuidValue(budget_id)),
+                    budget_access_key_fields::public_key.eq(user_public_budget_key),
+                    budget_access_key_fields::read_only.eq(false),
+                ))
+                .execute(conn)?;
+
+            dsl::insert_into(categories)
+                .values(&new_categories)
+                .execute(conn)?;
+
+            Ok(())
+        })?;
+
+        Ok(BudgetFrame {
+            access_key_id: key_id.into(),
+            id: budget_id.into(),
+            category_ids,
+            modified_timestamp: current_time.try_into().unwrap_or_default(),
+        })
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn update_budget(
+        &self,
+        budget_id: Uuid,
+        edited_budget_data: &[u8],
+        version_nonce: i64,
+        expected_previous_version_nonce: i64,
+    ) -> Result<(), DaoError> {
+        let mut db_connection = self.db_pool.get()?;
+
+        db_connection.immediate_transaction(|conn| {
+            let affected_row_count = dsl::update(
+                budgets
+                    .find(UuidValue(budget_id))
+                    .filter(budget_fields::version_nonce.eq(expected_previous_version_nonce)),
+            )
+            .set((
+                budget_fields::modified_timestamp.eq(TimestampValue(SystemTime::now())),
+                budget_fields::encrypted_blob.eq(edited_budget_data),
+                budget_fields::version_nonce.eq(version_nonce),
+            ))
+            .execute(conn)?;
+
+            if affected_row_count == 0 {
+                // Check whether the update failed because the record wasn't found or because
+                // the version_nonce was out-of-date
+                let current_version_nonce = budgets
+                    .select(budget_fields::version_nonce)
+                    .find(UuidValue(budget_id))
+                    .first::<i64>(conn)?;
+
+                return Err(out_of_date(
+                    current_version_nonce,
+                    expected_previous_version_nonce,
+                ));
+            }
+
+            Ok(())
+        })
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn invite_user(
+        &self,
+        recipient_user_email: &str,
+        sender_public_key: &[u8],
+        encryption_key_encrypted: &[u8],
+        budget_info_encrypted: &[u8],
+        sender_info_encrypted: &[u8],
+        share_info_symmetric_key_encrypted: &[u8],
+        recipient_public_key_id_used_by_sender: Uuid,
+        recipient_public_key_id_used_by_server: Uuid,
+        budget_id: Uuid,
+        expiration: SystemTime,
+        read_only: bool,
+        budget_accept_key_id: Uuid,
+        budget_accept_key_id_encrypted: &[u8],
+        budget_accept_public_key: &[u8],
+        budget_accept_private_key_encrypted: &[u8],
+        budget_accept_key_info_encrypted: &[u8],
+    ) -> Result<InvitationId, DaoError> {
+        let invitation_id = Uuid::now_v7();
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("Failed to get time");
+        let created_unix_timestamp_intdiv_five_million: i16 = (now.as_secs() / 5_000_000)
+            .try_into()
+            .expect("Current timestamp divided by 5,000,00 should fit into an i16");
+
+        let mut db_connection = self.db_pool.get()?;
+
+        db_connection.immediate_transaction::<_, DaoError, _>(|conn| {
+            dsl::insert_into(budget_share_invites)
+                .values((
+                    budget_share_invite_fields::id.eq(UuidValue(invitation_id)),
+                    budget_share_invite_fields::recipient_user_email.eq(recipient_user_email),
+                    budget_share_invite_fields::sender_public_key.eq(sender_public_key),
+                    budget_share_invite_fields::encryption_key_encrypted
+                        .eq(encryption_key_encrypted),
+                    budget_share_invite_fields::budget_accept_private_key_encrypted
+                        .eq(budget_accept_private_key_encrypted),
+                    budget_share_invite_fields::budget_info_encrypted.eq(budget_info_encrypted),
+                    budget_share_invite_fields::sender_info_encrypted.eq(sender_info_encrypted),
+                    budget_share_invite_fields::budget_accept_key_info_encrypted
+                        .eq(budget_accept_key_info_encrypted),
+                    budget_share_invite_fields::budget_accept_key_id_encrypted
+                        .eq(budget_accept_key_id_encrypted),
+                    budget_share_invite_fields::share_info_symmetric_key_encrypted
+                        .eq(share_info_symmetric_key_encrypted),
+                    budget_share_invite_fields::recipient_public_key_id_used_by_sender
+                        .eq(UuidValue(recipient_public_key_id_used_by_sender)),
+                    budget_share_invite_fields::recipient_public_key_id_used_by_server
+                        .eq(UuidValue(recipient_public_key_id_used_by_server)),
+                    budget_share_invite_fields::created_unix_timestamp_intdiv_five_million
+                        .eq(created_unix_timestamp_intdiv_five_million),
+                ))
+                .execute(conn)?;
+
+            dsl::insert_into(budget_accept_keys)
+                .values((
+                    budget_accept_key_fields::key_id.eq(UuidValue(budget_accept_key_id)),
+                    budget_accept_key_fields::budget_id.eq(UuidValue(budget_id)),
+                    budget_accept_key_fields::public_key.eq(budget_accept_public_key),
+                    budget_accept_key_fields::expiration.eq(TimestampValue(expiration)),
+                    budget_accept_key_fields::read_only.eq(read_only),
+                ))
+                .execute(conn)?;
+
+            Ok(())
+        })?;
+
+        Ok(InvitationId {
+            value: invitation_id.into(),
+        })
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn accept_invitation(
+        &self,
+        accept_key_id: Uuid,
+        budget_id: Uuid,
+        read_only: bool,
+        invitation_id: Uuid,
+        recipient_user_email: &str,
+        recipient_budget_user_access_public_key: &[u8],
+    ) -> Result<BudgetIdAndEncryptionKey, DaoError> {
+        let key_id = Uuid::now_v7();
+
+        let mut db_connection = self.db_pool.get()?;
+
+        db_connection.immediate_transaction::<_, DaoError, _>(|conn| {
+            diesel::insert_into(budget_access_keys)
+                .values((
+                    budget_access_key_fields::key_id.eq(UuidValue(key_id)),
+                    budget_access_key_fields::budget_id.eq(UuidValue(budget_id)),
+                    budget_access_key_fields::public_key
+                        .eq(recipient_budget_user_access_public_key),
+                    budget_access_key_fields::read_only.eq(read_only),
+                ))
+                .execute(conn)?;
+
+            let budget_encryption_key_encrypted =
+                diesel::delete(budget_share_invites.find(UuidValue(invitation_id)).filter(
+                    budget_share_invite_fields::recipient_user_email.eq(recipient_user_email),
+                ))
+                .returning(budget_share_invite_fields::encryption_key_encrypted)
+                .get_result::<Vec<u8>>(conn)?;
+
+            diesel::delete(
+                budget_accept_keys.find((UuidValue(accept_key_id), UuidValue(budget_id))),
+            )
+            .execute(conn)?;
+
+            Ok(BudgetIdAndEncryptionKey {
+                budget_id: budget_id.into(),
+                budget_access_key_id: key_id.into(),
+                encryption_key_encrypted: budget_encryption_key_encrypted,
+                read_only,
+            })
+        })
+    }
+
+    // Used when the recipient deletes the invitation
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn reject_invitation(
+        &self,
+        invitation_id: Uuid,
+        accept_key_id: Uuid,
+        recipient_user_email: &str,
+    ) -> Result<(), DaoError> {
+        let mut db_connection = self.db_pool.get()?;
+
+        db_connection.immediate_transaction::<_, DaoError, _>(|conn| {
+            let affected_row_count =
+                diesel::delete(budget_share_invites.find(UuidValue(invitation_id)).filter(
+                    budget_share_invite_fields::recipient_user_email.eq(recipient_user_email),
+                ))
+                .execute(conn)?;
+
+            if affected_row_count != 1 {
+                return Err(DaoError::QueryFailure(diesel::result::Error::NotFound));
+            }
+
+            diesel::delete(
+                budget_accept_keys
+                    .filter(budget_accept_key_fields::key_id.eq(UuidValue(accept_key_id))),
+            )
+            .execute(conn)?;
+
+            Ok(())
+        })
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn delete_invitation(&self, invitation_id: Uuid) -> Result<(), DaoError> {
+        diesel::delete(budget_share_invites.find(UuidValue(invitation_id)))
+            .execute(&mut self.db_pool.get()?)?;
+        Ok(())
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn delete_all_invitations_for_user(&self, user_email: &str) -> Result<usize, DaoError> {
+        Ok(diesel::delete(
+            budget_share_invites
+                .filter(budget_share_invite_fields::recipient_user_email.eq(user_email)),
+        )
+        .execute(&mut self.db_pool.get()?)?)
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn delete_all_expired_invitations(&self) -> Result<(), DaoError> {
+        let mut db_connection = self.db_pool.get()?;
+
+        // Not using a database transaction here because these can be deleted separately from
+        // each other
+        diesel::delete(
+            budget_accept_keys
+                .filter(budget_accept_key_fields::expiration.lt(TimestampValue(SystemTime::now()))),
+        )
+        .execute(&mut db_connection)?;
+
+        let now_minus_five_million_secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("now() should be after UNIX_EPOCH")
+            .as_secs()
+            - 5_000_000;
+
+        let segment_intdiv_five_million = now_minus_five_million_secs / 5_000_000;
+        let segment_intdiv_five_million: i16 = segment_intdiv_five_million
+            .try_into()
+            .expect("Unix epoch time divided by 5 million should fit in an i16");
+
+        diesel::delete(
+            budget_share_invites.filter(
+                budget_share_invite_fields::created_unix_timestamp_intdiv_five_million
+                    .lt(segment_intdiv_five_million),
+            ),
+        )
+        .execute(&mut db_connection)?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn get_pending_invitation_ids(&self, user_email: &str) -> Result<Vec<Uuid>, DaoError> {
+        Ok(budget_share_invites
+            .select(budget_share_invite_fields::id)
+            .filter(budget_share_invite_fields::recipient_user_email.eq(user_email))
+            .order(budget_share_invite_fields::id)
+            .load::<Uuid>(&mut self.db_pool.get()?)?)
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn get_all_pending_invitations(
+        &self,
+        user_email: &str,
+    ) -> Result<BudgetShareInviteList, DaoError> {
+        let invites = budget_share_invites
+            .select((
+                budget_share_invite_fields::id,
+                budget_share_invite_fields::budget_info_encrypted,
+                budget_share_invite_fields::sender_info_encrypted,
+                budget_share_invite_fields::share_info_symmetric_key_encrypted,
+                budget_share_invite_fields::budget_accept_key_info_encrypted,
+                budget_share_invite_fields::budget_accept_private_key_encrypted,
+                budget_share_invite_fields::budget_accept_key_id_encrypted,
+                budget_share_invite_fields::recipient_public_key_id_used_by_sender,
+                budget_share_invite_fields::recipient_public_key_id_used_by_server,
+            ))
+            .filter(budget_share_invite_fields::recipient_user_email.eq(user_email))
+            .load::<BudgetShareInvitePublicData>(&mut self.db_pool.get()?)?;
+
+        let invites = invites
+            .into_iter()
+            .map(|i| BudgetShareInvite {
+                id: i.id.into(),
+                budget_accept_key_encrypted: i.budget_accept_key_encrypted,
+                budget_accept_key_id_encrypted: i.budget_accept_key_id_encrypted,
+                budget_info_encrypted: i.budget_info_encrypted,
+                sender_info_encrypted: i.sender_info_encrypted,
+                budget_accept_key_info_encrypted: i.budget_accept_key_info_encrypted,
+                share_info_symmetric_key_encrypted: i.share_info_symmetric_key_encrypted,
+                recipient_public_key_id_used_by_sender: i
+                    .recipient_public_key_id_used_by_sender
+                    .into(),
+                recipient_public_key_id_used_by_server: i
+                    .recipient_public_key_id_used_by_server
+                    .into(),
+            })
+            .collect();
+
+        Ok(BudgetShareInviteList { invites })
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn leave_budget(&self, budget_id: Uuid, key_id: Uuid) -> Result<(), DaoError> {
+        let mut db_connection = self.db_pool.get()?;
+
+        db_connection.immediate_transaction::<_, DaoError, _>(|conn| {
+            diesel::delete(budget_access_keys.find((UuidValue(key_id), UuidValue(budget_id))))
+                .execute(conn)?;
+
+            let users_remaining_in_budget = budget_access_keys
+                .filter(budget_access_key_fields::budget_id.eq(UuidValue(budget_id)))
+                .count()
+                .get_result::<i64>(conn)?;
+
+            if users_remaining_in_budget == 0 {
+                diesel::delete(budgets.find(UuidValue(budget_id))).execute(conn)?;
+            }
+
+            Ok(())
+        })
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn create_entry(
+        &self,
+        entry_id: Option<Uuid>,
+        encrypted_blob: &[u8],
+        version_nonce: i64,
+        category_id: Option<Uuid>,
+        budget_id: Uuid,
+    ) -> Result<Uuid, DaoError> {
+        let entry_id = entry_id.unwrap_or_else(Uuid::now_v7);
+
+        let mut db_connection = self.db_pool.get()?;
+
+        db_connection.immediate_transaction::<_, DaoError, _>(|conn| {
+            // The foreign key only ensures the category exists, not that it is in this budget
+            if let Some(category_id) = category_id {
+                categories
+                    .select(category_fields::id)
+                    .find(UuidValue(category_id))
+                    .filter(category_fields::budget_id.eq(UuidValue(budget_id)))
+                    .get_result::<Uuid>(conn)?;
+            }
+
+            dsl::insert_into(entries)
+                .values((
+                    entry_fields::id.eq(UuidValue(entry_id)),
+                    entry_fields::budget_id.eq(UuidValue(budget_id)),
+                    entry_fields::category_id.eq(category_id.map(UuidValue)),
+                    entry_fields::encrypted_blob.eq(encrypted_blob),
+                    entry_fields::version_nonce.eq(version_nonce),
+                    entry_fields::modified_timestamp.eq(TimestampValue(SystemTime::now())),
+                ))
+                .execute(conn)?;
+
+            Ok(())
+        })?;
+
+        Ok(entry_id)
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn create_entry_and_category(
+        &self,
+        entry_id: Option<Uuid>,
+        entry_encrypted_blob: &[u8],
+        entry_version_nonce: i64,
+        category_id: Option<Uuid>,
+        category_encrypted_blob: &[u8],
+        category_version_nonce: i64,
+        budget_id: Uuid,
+    ) -> Result<EntryIdAndCategoryId, DaoError> {
+        let current_time = SystemTime::now();
+        let category_id = category_id.unwrap_or_else(Uuid::now_v7);
+        let entry_id = entry_id.unwrap_or_else(Uuid::now_v7);
+
+        let mut db_connection = self.db_pool.get()?;
+
+        db_connection.immediate_transaction::<_, DaoError, _>(|conn| {
+            dsl::insert_into(categories)
+                .values((
+                    category_fields::id.eq(UuidValue(category_id)),
+                    category_fields::budget_id.eq(UuidValue(budget_id)),
+                    category_fields::encrypted_blob.eq(category_encrypted_blob),
+                    category_fields::version_nonce.eq(category_version_nonce),
+                    category_fields::modified_timestamp.eq(TimestampValue(current_time)),
+                ))
+                .execute(conn)?;
+
+            dsl::insert_into(entries)
+                .values((
+                    entry_fields::id.eq(UuidValue(entry_id)),
+                    entry_fields::budget_id.eq(UuidValue(budget_id)),
+                    entry_fields::category_id.eq(UuidValue(category_id)),
+                    entry_fields::encrypted_blob.eq(entry_encrypted_blob),
+                    entry_fields::version_nonce.eq(entry_version_nonce),
+                    entry_fields::modified_timestamp.eq(TimestampValue(current_time)),
+                ))
+                .execute(conn)?;
+
+            Ok(())
+        })?;
+
+        Ok(EntryIdAndCategoryId {
+            entry_id: entry_id.into(),
+            category_id: category_id.into(),
+        })
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn update_entry(
+        &self,
+        entry_id: Uuid,
+        entry_encrypted_blob: &[u8],
+        version_nonce: i64,
+        expected_previous_version_nonce: i64,
+        category_id: Option<Uuid>,
+        budget_id: Uuid,
+    ) -> Result<(), DaoError> {
+        let mut db_connection = self.db_pool.get()?;
+
+        db_connection.immediate_transaction(|conn| {
+            let affected_row_count = diesel::update(
+                entries
+                    .find(UuidValue(entry_id))
+                    .filter(entry_fields::budget_id.eq(UuidValue(budget_id)))
+                    .filter(entry_fields::version_nonce.eq(expected_previous_version_nonce)),
+            )
+            .set((
+                entry_fields::category_id.eq(category_id.map(UuidValue)),
+                entry_fields::encrypted_blob.eq(entry_encrypted_blob),
+                entry_fields::version_nonce.eq(version_nonce),
+                entry_fields::modified_timestamp.eq(TimestampValue(SystemTime::now())),
+            ))
+            .execute(conn)?;
+
+            if affected_row_count == 0 {
+                // Check whether the update failed because the record wasn't found or because
+                // the version_nonce was out-of-date
+                let current_version_nonce = entries
+                    .select(entry_fields::version_nonce)
+                    .find(UuidValue(entry_id))
+                    .filter(entry_fields::budget_id.eq(UuidValue(budget_id)))
+                    .first::<i64>(conn)?;
+
+                return Err(out_of_date(
+                    current_version_nonce,
+                    expected_previous_version_nonce,
+                ));
+            }
+
+            Ok(())
+        })
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn delete_entry(&self, entry_id: Uuid, budget_id: Uuid) -> Result<(), DaoError> {
+        diesel::delete(
+            entries
+                .find(UuidValue(entry_id))
+                .filter(entry_fields::budget_id.eq(UuidValue(budget_id))),
+        )
+        .execute(&mut self.db_pool.get()?)?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn create_category(
+        &self,
+        category_id: Option<Uuid>,
+        encrypted_blob: &[u8],
+        version_nonce: i64,
+        budget_id: Uuid,
+    ) -> Result<Uuid, DaoError> {
+        let category_id = category_id.unwrap_or_else(Uuid::now_v7);
+
+        dsl::insert_into(categories)
+            .values((
+                category_fields::id.eq(UuidValue(category_id)),
+                category_fields::budget_id.eq(UuidValue(budget_id)),
+                category_fields::encrypted_blob.eq(encrypted_blob),
+                category_fields::version_nonce.eq(version_nonce),
+                category_fields::modified_timestamp.eq(TimestampValue(SystemTime::now())),
+            ))
+            .execute(&mut self.db_pool.get()?)?;
+
+        Ok(category_id)
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn update_category(
+        &self,
+        category_id: Uuid,
+        category_encrypted_blob: &[u8],
+        version_nonce: i64,
+        expected_previous_version_nonce: i64,
+        budget_id: Uuid,
+    ) -> Result<(), DaoError> {
+        let mut db_connection = self.db_pool.get()?;
+
+        db_connection.immediate_transaction(|conn| {
+            let affected_row_count = diesel::update(
+                categories
+                    .find(UuidValue(category_id))
+                    .filter(category_fields::budget_id.eq(UuidValue(budget_id)))
+                    .filter(category_fields::version_nonce.eq(expected_previous_version_nonce)),
+            )
+            .set((
+                category_fields::encrypted_blob.eq(category_encrypted_blob),
+                category_fields::version_nonce.eq(version_nonce),
+                category_fields::modified_timestamp.eq(TimestampValue(SystemTime::now())),
+            ))
+            .execute(conn)?;
+
+            if affected_row_count == 0 {
+                // Check whether the update failed because the record wasn't found or because
+                // the version_nonce was out-of-date
+                let current_version_nonce = categories
+                    .select(category_fields::version_nonce)
+                    .find(UuidValue(category_id))
+                    .filter(category_fields::budget_id.eq(UuidValue(budget_id)))
+                    .first::<i64>(conn)?;
+
+                return Err(out_of_date(
+                    current_version_nonce,
+                    expected_previous_version_nonce,
+                ));
+            }
+
+            Ok(())
+        })
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn delete_category(&self, category_id: Uuid, budget_id: Uuid) -> Result<(), DaoError> {
+        diesel::delete(
+            categories
+                .find(UuidValue(category_id))
+                .filter(category_fields::budget_id.eq(UuidValue(budget_id))),
+        )
+        .execute(&mut self.db_pool.get()?)?;
+
+        Ok(())
+    }
+}
+
+// SQLite has no async driver, so these hand the synchronous queries above to Tokio's blocking
+// thread pool. Borrowed arguments are copied first because the task must own its data.
+#[async_trait]
+impl AsyncBudgetDao for Dao {
+    fn reads_from_replica(&self) -> bool {
+        false
+    }
+
+    fn primary_only(&self) -> Arc<dyn AsyncBudgetDao> {
+        Arc::new(self.clone())
+    }
+
+    async fn get_public_budget_key(
+        &self,
+        key_id: Uuid,
+        budget_id: Uuid,
+    ) -> Result<BudgetAccessKey, DaoError> {
+        self.run_blocking(move |dao| BudgetDao::get_public_budget_key(&dao, key_id, budget_id))
+            .await
+    }
+
+    async fn get_multiple_public_budget_keys(
+        &self,
+        key_ids: &[Uuid],
+        budget_ids: &[Uuid],
+    ) -> Result<Vec<BudgetAccessKey>, DaoError> {
+        let key_ids = key_ids.to_vec();
+        let budget_ids = budget_ids.to_vec();
+
+        self.run_blocking(move |dao| {
+            BudgetDao::get_multiple_public_budget_keys(&dao, &key_ids, &budget_ids)
+        })
+        .await
+    }
+
+    async fn get_budget(&self, budget_id: Uuid) -> Result<BudgetMessage, DaoError> {
+        self.run_blocking(move |dao| BudgetDao::get_budget(&dao, budget_id))
+            .await
+    }
+
+    async fn get_multiple_budgets_by_id(
+        &self,
+        budget_ids: &[Uuid],
+    ) -> Result<BudgetList, DaoError> {
+        let budget_ids = budget_ids.to_vec();
+
+        self.run_blocking(move |dao| BudgetDao::get_multiple_budgets_by_id(&dao, &budget_ids))
+            .await
+    }
+
+    async fn get_all_pending_invitations(
+        &self,
+        user_email: &str,
+    ) -> Result<BudgetShareInviteList, DaoError> {
+        let user_email = user_email.to_owned();
+
+        self.run_blocking(move |dao| BudgetDao::get_all_pending_invitations(&dao, &user_email))
+            .await
+    }
+
+    async fn update_budget(
+        &self,
+        budget_id: Uuid,
+        edited_budget_data: &[u8],
+        version_nonce: i64,
+        expected_previous_version_nonce: i64,
+    ) -> Result<(), DaoError> {
+        let edited_budget_data = edited_budget_data.to_vec();
+
+        self.run_blocking(move |dao| {
+            BudgetDao::update_budget(
+                &dao,
+                budget_id,
+                &edited_budget_data,
+                version_nonce,
+                expected_previous_version_nonce,
+            )
+        })
+        .await
+    }
+
+    async fn create_entry(
+        &self,
+        entry_id: Option<Uuid>,
+        encrypted_blob: &[u8],
+        version_nonce: i64,
+        category_id: Option<Uuid>,
+        budget_id: Uuid,
+    ) -> Result<Uuid, DaoError> {
+        let encrypted_blob = encrypted_blob.to_vec();
+
+        self.run_blocking(move |dao| {
+            BudgetDao::create_entry(
+                &dao,
+                entry_id,
+                &encrypted_blob,
+                version_nonce,
+                category_id,
+                budget_id,
+            )
+        })
+        .await
+    }
+
+    async fn create_entry_and_category(
+        &self,
+        entry_id: Option<Uuid>,
+        entry_encrypted_blob: &[u8],
+        entry_version_nonce: i64,
+        category_id: Option<Uuid>,
+        category_encrypted_blob: &[u8],
+        category_version_nonce: i64,
+        budget_id: Uuid,
+    ) -> Result<EntryIdAndCategoryId, DaoError> {
+        let entry_encrypted_blob = entry_encrypted_blob.to_vec();
+        let category_encrypted_blob = category_encrypted_blob.to_vec();
+
+        self.run_blocking(move |dao| {
+            BudgetDao::create_entry_and_category(
+                &dao,
+                entry_id,
+                &entry_encrypted_blob,
+                entry_version_nonce,
+                category_id,
+                &category_encrypted_blob,
+                category_version_nonce,
+                budget_id,
+            )
+        })
+        .await
+    }
+
+    async fn update_entry(
+        &self,
+        entry_id: Uuid,
+        entry_encrypted_blob: &[u8],
+        version_nonce: i64,
+        expected_previous_version_nonce: i64,
+        category_id: Option<Uuid>,
+        budget_id: Uuid,
+    ) -> Result<(), DaoError> {
+        let entry_encrypted_blob = entry_encrypted_blob.to_vec();
+
+        self.run_blocking(move |dao| {
+            BudgetDao::update_entry(
+                &dao,
+                entry_id,
+                &entry_encrypted_blob,
+                version_nonce,
+                expected_previous_version_nonce,
+                category_id,
+                budget_id,
+            )
+        })
+        .await
+    }
+
+    async fn delete_entry(&self, entry_id: Uuid, budget_id: Uuid) -> Result<(), DaoError> {
+        self.run_blocking(move |dao| BudgetDao::delete_entry(&dao, entry_id, budget_id))
+            .await
+    }
+
+    async fn create_category(
+        &self,
+        category_id: Option<Uuid>,
+        encrypted_blob: &[u8],
+        version_nonce: i64,
+        budget_id: Uuid,
+    ) -> Result<Uuid, DaoError> {
+        let encrypted_blob = encrypted_blob.to_vec();
+
+        self.run_blocking(move |dao| {
+            BudgetDao::create_category(&dao, category_id, &encrypted_blob, version_nonce, budget_id)
+        })
+        .await
+    }
+
+    async fn update_category(
+        &self,
+        category_id: Uuid,
+        category_encrypted_blob: &[u8],
+        version_nonce: i64,
+        expected_previous_version_nonce: i64,
+        budget_id: Uuid,
+    ) -> Result<(), DaoError> {
+        let category_encrypted_blob = category_encrypted_blob.to_vec();
+
+        self.run_blocking(move |dao| {
+            BudgetDao::update_category(
+                &dao,
+                category_id,
+                &category_encrypted_blob,
+                version_nonce,
+                expected_previous_version_nonce,
+                budget_id,
+            )
+        })
+        .await
+    }
+
+    async fn delete_category(&self, category_id: Uuid, budget_id: Uuid) -> Result<(), DaoError> {
+        self.run_blocking(move |dao| BudgetDao::delete_category(&dao, category_id, budget_id))
+            .await
+    }
+}
@@ -0,0 +1,25 @@
+use diesel::{sql_query, RunQueryDsl};
+use diesel_migrations::MigrationHarness;
+
+use crate::db::health::HealthDao;
+use crate::db::sqlite::Dao;
+use crate::db::DaoError;
+
+impl HealthDao for Dao {
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn check_connection(&self) -> Result<(), DaoError> {
+        sql_query("SELECT 1").execute(&mut self.db_pool.get()?)?;
+        Ok(())
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn get_latest_applied_migration_version(&self) -> Result<Option<String>, DaoError> {
+        let applied = self
+            .db_pool
+            .get()?
+            .applied_migrations()
+            .map_err(DaoError::MigrationFailure)?;
+
+        Ok(applied.iter().map(|v| v.to_string()).max())
+    }
+}
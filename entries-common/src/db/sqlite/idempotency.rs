@@ -0,0 +1,103 @@
+use diesel::{dsl, BoolExpressionMethods, ExpressionMethods, QueryDsl, RunQueryDsl};
+use std::time::SystemTime;
+use uuid::Uuid;
+
+use crate::db::idempotency::{IdempotencyDao, KeyReservation, RESERVATION_TIMEOUT};
+use crate::db::sqlite::schema::idempotency_keys as idempotency_key_fields;
+use crate::db::sqlite::schema::idempotency_keys::dsl::idempotency_keys;
+use crate::db::sqlite::sql_types::{TimestampValue, UuidValue};
+use crate::db::sqlite::Dao;
+use crate::db::DaoError;
+use crate::models::idempotency_key::IdempotencyKey;
+
+impl IdempotencyDao for Dao {
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn reserve_key(
+        &self,
+        user_id: Uuid,
+        key: &str,
+        request_hash: &[u8],
+        expiration: SystemTime,
+    ) -> Result<KeyReservation, DaoError> {
+        let now = SystemTime::now();
+        let mut db_connection = self.db_pool.get()?;
+
+        db_connection.immediate_transaction::<_, DaoError, _>(|conn| {
+            // An expired key that hasn't been purged yet or an abandoned reservation is free to be
+            // used again
+            diesel::delete(
+                idempotency_keys.find((UuidValue(user_id), key)).filter(
+                    idempotency_key_fields::expiration
+                        .le(TimestampValue(now))
+                        .or(idempotency_key_fields::response_status.is_null().and(
+                            idempotency_key_fields::reserved_at
+                                .le(TimestampValue(now - RESERVATION_TIMEOUT)),
+                        )),
+                ),
+            )
+            .execute(conn)?;
+
+            let inserted_count = dsl::insert_into(idempotency_keys)
+                .values((
+                    idempotency_key_fields::user_id.eq(UuidValue(user_id)),
+                    idempotency_key_fields::key.eq(key),
+                    idempotency_key_fields::request_hash.eq(request_hash),
+                    idempotency_key_fields::reserved_at.eq(TimestampValue(now)),
+                    idempotency_key_fields::expiration.eq(TimestampValue(expiration)),
+                ))
+                .on_conflict_do_nothing()
+                .execute(conn)?;
+
+            if inserted_count == 1 {
+                return Ok(KeyReservation::Reserved);
+            }
+
+            let existing = idempotency_keys
+                .find((UuidValue(user_id), key))
+                .get_result::<IdempotencyKey>(conn)?;
+
+            Ok(KeyReservation::Existing(existing))
+        })
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn save_response(
+        &self,
+        user_id: Uuid,
+        key: &str,
+        status: i16,
+        content_type: Option<&str>,
+        body: &[u8],
+    ) -> Result<(), DaoError> {
+        dsl::update(idempotency_keys.find((UuidValue(user_id), key)))
+            .set((
+                idempotency_key_fields::response_status.eq(status),
+                idempotency_key_fields::response_content_type.eq(content_type),
+                idempotency_key_fields::response_body.eq(body),
+            ))
+            .execute(&mut self.db_pool.get()?)?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn release_key(&self, user_id: Uuid, key: &str) -> Result<(), DaoError> {
+        diesel::delete(
+            idempotency_keys
+                .find((UuidValue(user_id), key))
+                .filter(idempotency_key_fields::response_status.is_null()),
+        )
+        .execute(&mut self.db_pool.get()?)?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn delete_all_expired_keys(&self) -> Result<usize, DaoError> {
+        Ok(diesel::delete(
+            idempotency_keys
+                .filter(idempotency_key_fields::expiration.le(TimestampValue(SystemTime::now()))),
+        )
+        .execute(&mut self.db_pool.get()?)?)
+    }
+}
@@ -0,0 +1,50 @@
+use diesel::{dsl, ExpressionMethods, OptionalExtension, QueryDsl, RunQueryDsl};
+use std::time::SystemTime;
+
+use crate::db::job_registry::JobRegistryDao;
+use crate::db::sqlite::schema::job_registry as job_registry_fields;
+use crate::db::sqlite::schema::job_registry::dsl::job_registry;
+use crate::db::sqlite::sql_types::TimestampValue;
+use crate::db::sqlite::Dao;
+use crate::db::DaoError;
+
+impl JobRegistryDao for Dao {
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn get_job_last_run_timestamp(&self, name: &str) -> Result<Option<SystemTime>, DaoError> {
+        Ok(job_registry
+            .select(job_registry_fields::last_run_timestamp)
+            .find(name)
+            .get_result(&mut self.db_pool.get()?)
+            .optional()?)
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn get_all_job_last_run_timestamps(&self) -> Result<Vec<(String, SystemTime)>, DaoError> {
+        Ok(job_registry
+            .select((
+                job_registry_fields::job_name,
+                job_registry_fields::last_run_timestamp,
+            ))
+            .order(job_registry_fields::job_name)
+            .load(&mut self.db_pool.get()?)?)
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn set_job_last_run_timestamp(
+        &self,
+        job_name: &str,
+        timestamp: SystemTime,
+    ) -> Result<(), DaoError> {
+        dsl::insert_into(job_registry)
+            .values((
+                job_registry_fields::job_name.eq(job_name),
+                job_registry_fields::last_run_timestamp.eq(TimestampValue(timestamp)),
+            ))
+            .on_conflict(job_registry_fields::job_name)
+            .do_update()
+            .set(job_registry_fields::last_run_timestamp.eq(TimestampValue(timestamp)))
+            .execute(&mut self.db_pool.get()?)?;
+
+        Ok(())
+    }
+}
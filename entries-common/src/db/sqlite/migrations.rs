@@ -0,0 +1,72 @@
+use diesel::migration::{Migration, MigrationSource};
+use diesel::sqlite::Sqlite;
+use diesel_migrations::{embed_migrations, EmbeddedMigrations, MigrationHarness};
+
+use crate::db::migrations::MigrationsDao;
+use crate::db::sqlite::Dao;
+use crate::db::DaoError;
+
+pub const MIGRATIONS: EmbeddedMigrations = embed_migrations!("migrations-sqlite");
+
+/// The version of the newest SQLite migration compiled into this binary. Matches
+/// [`crate::db::migrations::expected_version`] because each Postgres migration has a SQLite
+/// counterpart with the same version.
+pub fn expected_version() -> Option<String> {
+    MigrationSource::<Sqlite>::migrations(&MIGRATIONS)
+        .expect("Embedded migrations should be valid")
+        .iter()
+        .map(|m| m.name().version().to_string())
+        .max()
+}
+
+impl MigrationsDao for Dao {
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn get_pending_migrations(&self) -> Result<Vec<String>, DaoError> {
+        let pending = self
+            .db_pool
+            .get()?
+            .pending_migrations(MIGRATIONS)
+            .map_err(DaoError::MigrationFailure)?;
+
+        Ok(migration_names(&pending))
+    }
+
+    // SQLite allows one writer at a time, so there is no need for a lock to keep two processes
+    // from migrating at once
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn run_pending_migrations(&self) -> Result<Vec<String>, DaoError> {
+        let mut db_connection = self.db_pool.get()?;
+
+        let pending = db_connection
+            .pending_migrations(MIGRATIONS)
+            .map_err(DaoError::MigrationFailure)?;
+        let names = migration_names(&pending);
+
+        for migration in pending {
+            db_connection
+                .run_migration(&migration)
+                .map_err(DaoError::MigrationFailure)?;
+        }
+
+        Ok(names)
+    }
+}
+
+fn migration_names(migrations: &[Box<dyn Migration<Sqlite>>]) -> Vec<String> {
+    let mut names: Vec<String> = migrations.iter().map(|m| m.name().to_string()).collect();
+    names.sort_unstable();
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::db::migrations;
+
+    #[test]
+    fn test_expected_version_matches_postgres() {
+        assert!(expected_version().is_some());
+        assert_eq!(expected_version(), migrations::expected_version());
+    }
+}
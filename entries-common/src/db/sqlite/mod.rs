@@ -0,0 +1,287 @@
+//! A SQLite backend for self-hosters who don't want to run Postgres, enabled with the `sqlite`
+//! feature. The whole database lives in a single file.
+//!
+//! [`Dao`] implements the same traits as the Postgres DAOs and behaves the same way, including
+//! the `version_nonce` checks and the cascading deletes set up by the migrations. The schema
+//! comes from the migrations in `migrations-sqlite`, which mirror the Postgres migrations
+//! version for version.
+//!
+//! SQLite allows one writer at a time, so transactions that write take the write lock when they
+//! begin (`BEGIN IMMEDIATE`) rather than when they first write. That makes them run one after
+//! another, which gives the same guarantees as the `REPEATABLE READ` transactions used with
+//! Postgres without any need to retry.
+
+use diesel::connection::SimpleConnection;
+use diesel::r2d2::{ConnectionManager, CustomizeConnection};
+use diesel::sqlite::SqliteConnection;
+use std::time::Duration;
+
+use crate::db::DaoError;
+
+mod auth;
+mod budget;
+mod health;
+mod idempotency;
+mod job_registry;
+pub mod migrations;
+pub mod schema;
+pub mod sql_types;
+mod user;
+
+pub type SqliteDbPool = diesel::r2d2::Pool<ConnectionManager<SqliteConnection>>;
+
+/// Opens a pool of connections to the database file at `database_path`, creating the file if it
+/// doesn't exist. A connection that finds the database locked by another writer waits up to
+/// `busy_timeout` before giving up.
+pub fn create_db_pool(
+    database_path: &str,
+    max_db_connections: u32,
+    busy_timeout: Duration,
+) -> SqliteDbPool {
+    diesel::r2d2::Pool::builder()
+        .max_size(max_db_connections)
+        .connection_customizer(Box::new(ConnectionOptions { busy_timeout }))
+        .build(ConnectionManager::<SqliteConnection>::new(database_path))
+        .expect("Failed to create SQLite DB pool")
+}
+
+#[derive(Debug)]
+struct ConnectionOptions {
+    busy_timeout: Duration,
+}
+
+impl CustomizeConnection<SqliteConnection, diesel::r2d2::Error> for ConnectionOptions {
+    fn on_acquire(&self, conn: &mut SqliteConnection) -> Result<(), diesel::r2d2::Error> {
+        // Foreign keys (and so the cascading deletes) are off unless each connection turns them
+        // on. WAL lets readers carry on while a write is in progress.
+        conn.batch_execute(&format!(
+            "PRAGMA busy_timeout = {}; \
+             PRAGMA foreign_keys = ON; \
+             PRAGMA journal_mode = WAL; \
+             PRAGMA synchronous = NORMAL;",
+            self.busy_timeout.as_millis(),
+        ))
+        .map_err(diesel::r2d2::Error::QueryError)
+    }
+}
+
+#[derive(Clone)]
+pub struct Dao {
+    db_pool: SqliteDbPool,
+}
+
+impl Dao {
+    pub fn new(db_pool: &SqliteDbPool) -> Self {
+        Self {
+            db_pool: db_pool.clone(),
+        }
+    }
+
+    /// Runs `f` on Tokio's blocking thread pool. SQLite has no async driver, so this is how the
+    /// async DAO traits are implemented.
+    async fn run_blocking<T, F>(&self, f: F) -> Result<T, DaoError>
+    where
+        F: FnOnce(Dao) -> Result<T, DaoError> + Send + 'static,
+        T: Send + 'static,
+    {
+        let dao = self.clone();
+
+        tokio::task::spawn_blocking(move || f(dao))
+            .await
+            .map_err(|_| DaoError::CannotRunQuery("SQLite query task failed to complete"))?
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod testing {
+    use super::*;
+
+    use diesel_migrations::MigrationHarness;
+    use std::path::PathBuf;
+    use uuid::Uuid;
+
+    /// A migrated database in a temporary file that is removed when this is dropped
+    pub struct TestDb {
+        pub dao: Dao,
+        path: PathBuf,
+    }
+
+    impl TestDb {
+        pub fn new() -> Self {
+            let path = std::env::temp_dir().join(format!("entries-test-{}.db", Uuid::now_v7()));
+            let db_pool = create_db_pool(path.to_str().unwrap(), 4, Duration::from_secs(5));
+
+            db_pool
+                .get()
+                .unwrap()
+                .run_pending_migrations(migrations::MIGRATIONS)
+                .unwrap();
+
+            Self {
+                dao: Dao::new(&db_pool),
+                path,
+            }
+        }
+    }
+
+    impl Drop for TestDb {
+        fn drop(&mut self) {
+            for suffix in ["", "-wal", "-shm"] {
+                let mut path = self.path.clone().into_os_string();
+                path.push(suffix);
+                let _ = std::fs::remove_file(path);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::testing::TestDb;
+
+    use crate::db::budget::testing::run_budget_dao_tests;
+    use crate::db::budget::BudgetDao;
+    use crate::db::sqlite::schema::entries::dsl::entries;
+    use crate::db::user::UserDao;
+    use crate::db::DaoError;
+    use crate::models::user_deletion_request::UserDeletionRequest;
+
+    use diesel::dsl::sql;
+    use diesel::result::{DatabaseErrorKind, Error as DieselError};
+    use diesel::sql_types::Integer;
+    use diesel::{QueryDsl, RunQueryDsl};
+    use std::time::{Duration, SystemTime};
+    use uuid::Uuid;
+
+    fn entry_count(db: &TestDb) -> i64 {
+        entries
+            .count()
+            .get_result(&mut db.dao.db_pool.get().unwrap())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_budget_dao() {
+        let db = TestDb::new();
+        run_budget_dao_tests(&db.dao);
+    }
+
+    #[test]
+    fn test_connections_enforce_foreign_keys() {
+        let db = TestDb::new();
+
+        let foreign_keys = sql::<Integer>("PRAGMA foreign_keys")
+            .get_result::<i32>(&mut db.dao.db_pool.get().unwrap())
+            .unwrap();
+        assert_eq!(foreign_keys, 1);
+
+        assert!(matches!(
+            db.dao.create_entry(None, &[1; 8], 1, None, Uuid::now_v7()),
+            Err(DaoError::QueryFailure(DieselError::DatabaseError(
+                DatabaseErrorKind::ForeignKeyViolation,
+                _,
+            )))
+        ));
+    }
+
+    #[test]
+    fn test_concurrent_updates_run_one_at_a_time() {
+        let db = TestDb::new();
+        let budget = db.dao.create_budget(&[0; 32], 1, &[], &[0; 32]).unwrap();
+        let budget_id = (&budget.id).try_into().unwrap();
+        let entry_id = db
+            .dao
+            .create_entry(None, &[1; 8], 1, None, budget_id)
+            .unwrap();
+
+        // Every writer expects the same version_nonce, so exactly one of them should win. The rest
+        // should wait for the write lock and then find the entry out of date, not fail with
+        // SQLITE_BUSY.
+        let results = std::thread::scope(|s| {
+            let handles = (0..4)
+                .map(|i| {
+                    let dao = &db.dao;
+                    s.spawn(move || dao.update_entry(entry_id, &[2; 8], 2 + i, 1, None, budget_id))
+                })
+                .collect::<Vec<_>>();
+
+            handles
+                .into_iter()
+                .map(|h| h.join().unwrap())
+                .collect::<Vec<_>>()
+        });
+
+        assert_eq!(results.iter().filter(|r| r.is_ok()).count(), 1);
+        assert!(results
+            .iter()
+            .filter_map(|r| r.as_ref().err())
+            .all(|e| matches!(e, DaoError::OutOfDate(Some(_)))));
+    }
+
+    #[test]
+    fn test_delete_user_deletes_user_data_and_abandoned_budgets() {
+        let db = TestDb::new();
+        let user_id = db
+            .dao
+            .create_user(
+                "Test@Example.com",
+                "hash",
+                &[0; 16],
+                1,
+                1,
+                1,
+                &[0; 16],
+                1,
+                1,
+                1,
+                &[0; 16],
+                1,
+                1,
+                1,
+                &[0; 32],
+                &[0; 32],
+                Uuid::now_v7(),
+                &[0; 32],
+                &[1; 8],
+                1,
+                &[2; 8],
+                1,
+                &[String::from("ABCDEFGHIJKL")],
+            )
+            .unwrap();
+        let budget = db.dao.create_budget(&[0; 32], 1, &[], &[0; 32]).unwrap();
+        let budget_id = (&budget.id).try_into().unwrap();
+        let key_id = (&budget.access_key_id).try_into().unwrap();
+        db.dao
+            .create_entry(None, &[1; 8], 1, None, budget_id)
+            .unwrap();
+
+        let status = db.dao.get_user_status("test@example.com").unwrap();
+        assert_eq!(status.id, user_id);
+        assert!(status.created_timestamp <= SystemTime::now());
+
+        db.dao
+            .save_user_deletion_budget_keys(&[key_id], user_id, SystemTime::now())
+            .unwrap();
+        db.dao
+            .initiate_user_deletion(user_id, Duration::ZERO)
+            .unwrap();
+        db.dao
+            .delete_user(&UserDeletionRequest {
+                user_id,
+                ready_for_deletion_time: SystemTime::now(),
+            })
+            .unwrap();
+
+        assert!(matches!(
+            db.dao.get_user_prefs(user_id),
+            Err(DaoError::QueryFailure(DieselError::NotFound))
+        ));
+        assert!(!db.dao.check_is_user_listed_for_deletion(user_id).unwrap());
+        assert!(matches!(
+            db.dao.get_budget(budget_id),
+            Err(DaoError::QueryFailure(DieselError::NotFound))
+        ));
+        assert_eq!(entry_count(&db), 0);
+    }
+}
@@ -0,0 +1,277 @@
+//! The SQLite counterpart of [`crate::schema`], matching the tables created by the migrations in
+//! `migrations-sqlite`. Columns keep the Postgres names and nullability. UUID and timestamp
+//! columns use the types in [`super::sql_types`], and `CHAR(n)` columns are plain text.
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use crate::db::sqlite::sql_types::{TimestampMicros, Uuid};
+
+    admin_audit_log (id) {
+        id -> Uuid,
+        timestamp -> TimestampMicros,
+        operator -> Text,
+        action -> Text,
+        target_user_email -> Text,
+        details -> Text,
+        succeeded -> Bool,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use crate::db::sqlite::sql_types::{TimestampMicros, Uuid};
+
+    blacklisted_tokens (token_signature) {
+        token_signature -> Binary,
+        token_expiration -> TimestampMicros,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use crate::db::sqlite::sql_types::{TimestampMicros, Uuid};
+
+    budget_accept_keys (key_id, budget_id) {
+        key_id -> Uuid,
+        budget_id -> Uuid,
+        public_key -> Binary,
+        expiration -> TimestampMicros,
+        read_only -> Bool,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use crate::db::sqlite::sql_types::{TimestampMicros, Uuid};
+
+    budget_access_keys (key_id, budget_id) {
+        key_id -> Uuid,
+        budget_id -> Uuid,
+        public_key -> Binary,
+        read_only -> Bool,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use crate::db::sqlite::sql_types::{TimestampMicros, Uuid};
+
+    budget_share_invites (id) {
+        id -> Uuid,
+        recipient_user_email -> Text,
+        sender_public_key -> Binary,
+        encryption_key_encrypted -> Binary,
+        budget_accept_private_key_encrypted -> Binary,
+        budget_info_encrypted -> Binary,
+        sender_info_encrypted -> Binary,
+        budget_accept_key_info_encrypted -> Binary,
+        budget_accept_key_id_encrypted -> Binary,
+        share_info_symmetric_key_encrypted -> Binary,
+        recipient_public_key_id_used_by_sender -> Uuid,
+        recipient_public_key_id_used_by_server -> Uuid,
+        created_unix_timestamp_intdiv_five_million -> Int2,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use crate::db::sqlite::sql_types::{TimestampMicros, Uuid};
+
+    budgets (id) {
+        id -> Uuid,
+        encrypted_blob -> Binary,
+        version_nonce -> Int8,
+        modified_timestamp -> TimestampMicros,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use crate::db::sqlite::sql_types::{TimestampMicros, Uuid};
+
+    categories (id) {
+        id -> Uuid,
+        budget_id -> Uuid,
+        encrypted_blob -> Binary,
+        version_nonce -> Int8,
+        modified_timestamp -> TimestampMicros,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use crate::db::sqlite::sql_types::{TimestampMicros, Uuid};
+
+    entries (id) {
+        id -> Uuid,
+        budget_id -> Uuid,
+        category_id -> Nullable<Uuid>,
+        encrypted_blob -> Binary,
+        version_nonce -> Int8,
+        modified_timestamp -> TimestampMicros,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use crate::db::sqlite::sql_types::{TimestampMicros, Uuid};
+
+    idempotency_keys (user_id, key) {
+        user_id -> Uuid,
+        key -> Text,
+        request_hash -> Binary,
+        reserved_at -> TimestampMicros,
+        response_status -> Nullable<Int2>,
+        response_content_type -> Nullable<Text>,
+        response_body -> Nullable<Binary>,
+        expiration -> TimestampMicros,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use crate::db::sqlite::sql_types::{TimestampMicros, Uuid};
+
+    job_registry (job_name) {
+        job_name -> Text,
+        last_run_timestamp -> TimestampMicros,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use crate::db::sqlite::sql_types::{TimestampMicros, Uuid};
+
+    signin_nonces (user_email) {
+        user_email -> Text,
+        nonce -> Int4,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use crate::db::sqlite::sql_types::{TimestampMicros, Uuid};
+
+    user_backup_codes (user_id, code) {
+        user_id -> Uuid,
+        #[max_length = 12]
+        code -> Text,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use crate::db::sqlite::sql_types::{TimestampMicros, Uuid};
+
+    user_deletion_request_budget_keys (key_id) {
+        key_id -> Uuid,
+        user_id -> Uuid,
+        delete_me_time -> TimestampMicros,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use crate::db::sqlite::sql_types::{TimestampMicros, Uuid};
+
+    user_deletion_requests (user_id) {
+        user_id -> Uuid,
+        ready_for_deletion_time -> TimestampMicros,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use crate::db::sqlite::sql_types::{TimestampMicros, Uuid};
+
+    user_keystores (user_id) {
+        user_id -> Uuid,
+        encrypted_blob -> Binary,
+        version_nonce -> Int8,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use crate::db::sqlite::sql_types::{TimestampMicros, Uuid};
+
+    user_otps (user_email) {
+        user_email -> Text,
+        #[max_length = 8]
+        otp -> Text,
+        expiration -> TimestampMicros,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use crate::db::sqlite::sql_types::{TimestampMicros, Uuid};
+
+    user_preferences (user_id) {
+        user_id -> Uuid,
+        encrypted_blob -> Binary,
+        version_nonce -> Int8,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use crate::db::sqlite::sql_types::{TimestampMicros, Uuid};
+
+    users (id) {
+        id -> Uuid,
+        email -> Text,
+        is_verified -> Bool,
+        public_key_id -> Uuid,
+        public_key -> Binary,
+        created_timestamp -> TimestampMicros,
+        auth_string_hash -> Text,
+        auth_string_salt -> Binary,
+        auth_string_memory_cost_kib -> Int4,
+        auth_string_parallelism_factor -> Int4,
+        auth_string_iters -> Int4,
+        password_encryption_salt -> Binary,
+        password_encryption_memory_cost_kib -> Int4,
+        password_encryption_parallelism_factor -> Int4,
+        password_encryption_iters -> Int4,
+        recovery_key_salt -> Binary,
+        recovery_key_memory_cost_kib -> Int4,
+        recovery_key_parallelism_factor -> Int4,
+        recovery_key_iters -> Int4,
+        encryption_key_encrypted_with_password -> Binary,
+        encryption_key_encrypted_with_recovery_key -> Binary,
+    }
+}
+
+diesel::joinable!(budget_accept_keys -> budgets (budget_id));
+diesel::joinable!(budget_access_keys -> budgets (budget_id));
+diesel::joinable!(categories -> budgets (budget_id));
+diesel::joinable!(entries -> budgets (budget_id));
+diesel::joinable!(entries -> categories (category_id));
+diesel::joinable!(idempotency_keys -> users (user_id));
+diesel::joinable!(user_backup_codes -> users (user_id));
+diesel::joinable!(user_deletion_request_budget_keys -> users (user_id));
+diesel::joinable!(user_deletion_requests -> users (user_id));
+diesel::joinable!(user_keystores -> users (user_id));
+diesel::joinable!(user_preferences -> users (user_id));
+
+diesel::allow_tables_to_appear_in_same_query!(
+    admin_audit_log,
+    blacklisted_tokens,
+    budget_accept_keys,
+    budget_access_keys,
+    budget_share_invites,
+    budgets,
+    categories,
+    entries,
+    idempotency_keys,
+    job_registry,
+    signin_nonces,
+    user_backup_codes,
+    user_deletion_request_budget_keys,
+    user_deletion_requests,
+    user_keystores,
+    user_otps,
+    user_preferences,
+    users,
+);
@@ -0,0 +1,75 @@
+//! SQL types for the columns SQLite has no native type for. UUIDs are stored as 16-byte BLOBs
+//! and timestamps as INTEGER microseconds since the Unix epoch. Both load into the same Rust
+//! types as their Postgres counterparts, so the models in [`crate::models`] can be loaded from
+//! either backend.
+//!
+//! Diesel can't be taught to bind a `uuid::Uuid` or `SystemTime` as a type defined outside of
+//! diesel, so values bound in queries are wrapped in [`UuidValue`] or [`TimestampValue`].
+
+use diesel::deserialize::{self, FromSql};
+use diesel::expression::AsExpression;
+use diesel::serialize::{self, IsNull, Output, ToSql};
+use diesel::sql_types::{BigInt, Binary};
+use diesel::sqlite::{Sqlite, SqliteValue};
+use diesel::{QueryId, SqlType};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+#[derive(Clone, Copy, Debug, Default, QueryId, SqlType)]
+#[diesel(sqlite_type(name = "Binary"))]
+pub struct Uuid;
+
+#[derive(Clone, Copy, Debug, Default, QueryId, SqlType)]
+#[diesel(sqlite_type(name = "Long"))]
+pub struct TimestampMicros;
+
+impl FromSql<Uuid, Sqlite> for uuid::Uuid {
+    fn from_sql(value: SqliteValue<'_, '_, '_>) -> deserialize::Result<Self> {
+        let bytes = <Vec<u8> as FromSql<Binary, Sqlite>>::from_sql(value)?;
+        Ok(uuid::Uuid::from_slice(&bytes)?)
+    }
+}
+
+impl FromSql<TimestampMicros, Sqlite> for SystemTime {
+    fn from_sql(value: SqliteValue<'_, '_, '_>) -> deserialize::Result<Self> {
+        let micros = <i64 as FromSql<BigInt, Sqlite>>::from_sql(value)?;
+        let since_epoch = Duration::from_micros(micros.unsigned_abs());
+
+        Ok(if micros >= 0 {
+            UNIX_EPOCH + since_epoch
+        } else {
+            UNIX_EPOCH - since_epoch
+        })
+    }
+}
+
+#[derive(AsExpression, Clone, Copy, Debug)]
+#[diesel(sql_type = Uuid)]
+pub struct UuidValue(pub uuid::Uuid);
+
+impl ToSql<Uuid, Sqlite> for UuidValue {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Sqlite>) -> serialize::Result {
+        out.set_value(self.0.as_bytes().as_slice());
+        Ok(IsNull::No)
+    }
+}
+
+#[derive(AsExpression, Clone, Copy, Debug)]
+#[diesel(sql_type = TimestampMicros)]
+pub struct TimestampValue(pub SystemTime);
+
+impl ToSql<TimestampMicros, Sqlite> for TimestampValue {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Sqlite>) -> serialize::Result {
+        let micros = match self.0.duration_since(UNIX_EPOCH) {
+            Ok(since_epoch) => i64::try_from(since_epoch.as_micros())?,
+            Err(e) => -i64::try_from(e.duration().as_micros())?,
+        };
+
+        out.set_value(micros);
+        Ok(IsNull::No)
+    }
+}
+
+/// Wraps each ID for binding, e.g. in an `eq_any()` filter
+pub fn uuid_values(ids: &[uuid::Uuid]) -> Vec<UuidValue> {
+    ids.iter().copied().map(UuidValue).collect()
+}
@@ -0,0 +1,617 @@
+use diesel::{dsl, Connection, ExpressionMethods, OptionalExtension, QueryDsl, RunQueryDsl};
+use rand::{rngs::OsRng, Rng};
+use std::time::{Duration, SystemTime};
+use uuid::Uuid;
+
+use crate::db::sqlite::sql_types::{uuid_values, TimestampValue, UuidValue};
+use crate::db::sqlite::Dao;
+use crate::db::user::{UserDao, UserStatus};
+use crate::db::DaoError;
+use crate::messages::{EncryptedBlob, UserBootstrap, UserPublicKey};
+use crate::models::user_deletion_request::UserDeletionRequest;
+use crate::models::user_keystore::UserKeystore;
+use crate::models::user_preferences::UserPreferences;
+
+use crate::db::sqlite::schema::budget_access_keys as budget_access_key_fields;
+use crate::db::sqlite::schema::budget_access_keys::dsl::budget_access_keys;
+use crate::db::sqlite::schema::budgets::dsl::budgets;
+use crate::db::sqlite::schema::signin_nonces as signin_nonce_fields;
+use crate::db::sqlite::schema::signin_nonces::dsl::signin_nonces;
+use crate::db::sqlite::schema::user_backup_codes as user_backup_code_fields;
+use crate::db::sqlite::schema::user_backup_codes::dsl::user_backup_codes;
+use crate::db::sqlite::schema::user_deletion_request_budget_keys as user_deletion_request_budget_key_fields;
+use crate::db::sqlite::schema::user_deletion_request_budget_keys::dsl::user_deletion_request_budget_keys;
+use crate::db::sqlite::schema::user_deletion_requests as user_deletion_request_fields;
+use crate::db::sqlite::schema::user_deletion_requests::dsl::user_deletion_requests;
+use crate::db::sqlite::schema::user_keystores as user_keystore_fields;
+use crate::db::sqlite::schema::user_keystores::dsl::user_keystores;
+use crate::db::sqlite::schema::user_preferences as user_preferences_fields;
+use crate::db::sqlite::schema::user_preferences::dsl::user_preferences;
+use crate::db::sqlite::schema::users as user_fields;
+use crate::db::sqlite::schema::users::dsl::users;
+
+impl UserDao for Dao {
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn get_user_public_key(&self, user_email: &str) -> Result<UserPublicKey, DaoError> {
+        let (key_id, key) = users
+            .select((user_fields::public_key_id, user_fields::public_key))
+            .filter(user_fields::email.eq(user_email))
+            .first::<(Uuid, Vec<u8>)>(&mut self.db_pool.get()?)?;
+
+        Ok(UserPublicKey {
+            id: key_id.into(),
+            value: key,
+        })
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn get_user_status(&self, user_email: &str) -> Result<UserStatus, DaoError> {
+        let mut db_connection = self.db_pool.get()?;
+
+        db_connection.transaction::<_, DaoError, _>(|conn| {
+            let (id, email, is_verified, created_timestamp) = users
+                .select((
+                    user_fields::id,
+                    user_fields::email,
+                    user_fields::is_verified,
+                    user_fields::created_timestamp,
+                ))
+                .filter(user_fields::email.eq(user_email))
+                .first::<(Uuid, String, bool, SystemTime)>(conn)?;
+
+            let ready_for_deletion_time = user_deletion_requests
+                .select(user_deletion_request_fields::ready_for_deletion_time)
+                .find(UuidValue(id))
+                .first::<SystemTime>(conn)
+                .optional()?;
+
+            let budgets_pending_deletion = user_deletion_request_budget_keys
+                .filter(user_deletion_request_budget_key_fields::user_id.eq(UuidValue(id)))
+                .count()
+                .get_result::<i64>(conn)?;
+
+            Ok(UserStatus {
+                id,
+                email,
+                is_verified,
+                created_timestamp,
+                ready_for_deletion_time,
+                budgets_pending_deletion,
+            })
+        })
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn create_user(
+        &self,
+        email: &str,
+        auth_string_hash: &str,
+        auth_string_salt: &[u8],
+        auth_string_memory_cost_kib: i32,
+        auth_string_parallelism_factor: i32,
+        auth_string_iters: i32,
+        password_encryption_salt: &[u8],
+        password_encryption_memory_cost_kib: i32,
+        password_encryption_parallelism_factor: i32,
+        password_encryption_iters: i32,
+        recovery_key_salt: &[u8],
+        recovery_key_memory_cost_kib: i32,
+        recovery_key_parallelism_factor: i32,
+        recovery_key_iters: i32,
+        encryption_key_encrypted_with_password: &[u8],
+        encryption_key_encrypted_with_recovery_key: &[u8],
+        public_key_id: Uuid,
+        public_key: &[u8],
+        preferences_encrypted: &[u8],
+        preferences_version_nonce: i64,
+        user_keystore_encrypted: &[u8],
+        user_keystore_version_nonce: i64,
+        backup_codes: &[String],
+    ) -> Result<Uuid, DaoError> {
+        let current_time = SystemTime::now();
+        let user_id = Uuid::now_v7();
+
+        let email_lowercase = email.to_lowercase();
+
+        let new_user = (
+            user_fields::id.eq(UuidValue(user_id)),
+            user_fields::email.eq(&email_lowercase),
+            user_fields::is_verified.eq(false),
+            user_fields::created_timestamp.eq(TimestampValue(current_time)),
+            user_fields::public_key_id.eq(UuidValue(public_key_id)),
+            user_fields::public_key.eq(public_key),
+            user_fields::auth_string_hash.eq(auth_string_hash),
+            user_fields::auth_string_salt.eq(auth_string_salt),
+            user_fields::auth_string_memory_cost_kib.eq(auth_string_memory_cost_kib),
+            user_fields::auth_string_parallelism_factor.eq(auth_string_parallelism_factor),
+            user_fields::auth_string_iters.eq(auth_string_iters),
+            user_fields::password_encryption_salt.eq(password_encryption_salt),
+            user_fields::password_encryption_memory_cost_kib
+                .eq(password_encryption_memory_cost_kib),
+            user_fields::password_encryption_parallelism_factor
+                .eq(password_encryption_parallelism_factor),
+            user_fields::password_encryption_iters.eq(password_encryption_iters),
+            user_fields::recovery_key_salt.eq(recovery_key_salt),
+            user_fields::recovery_key_memory_cost_kib.eq(recovery_key_memory_cost_kib),
+            user_fields::recovery_key_parallelism_factor.eq(recovery_key_parallelism_factor),
+            user_fields::recovery_key_iters.eq(recovery_key_iters),
+            user_fields::encryption_key_encrypted_with_password
+                .eq(encryption_key_encrypted_with_password),
+            user_fields::encryption_key_encrypted_with_recovery_key
+                .eq(encryption_key_encrypted_with_recovery_key),
+        );
+
+        let backup_codes = backup_codes
+            .iter()
+            .map(|code| {
+                (
+                    user_backup_code_fields::user_id.eq(UuidValue(user_id)),
+                    user_backup_code_fields::code.eq(code),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let mut db_connection = self.db_pool.get()?;
+
+        db_connection.immediate_transaction::<_, DaoError, _>(|conn| {
+            dsl::insert_into(users).values(new_user).execute(conn)?;
+
+            dsl::insert_into(user_preferences)
+                .values((
+                    user_preferences_fields::user_id.eq(UuidValue(user_id)),
+                    user_preferences_fields::encrypted_blob.eq(preferences_encrypted),
+                    user_preferences_fields::version_nonce.eq(preferences_version_nonce),
+                ))
+                .execute(conn)?;
+
+            dsl::insert_into(user_keystores)
+                .values((
+                    user_keystore_fields::user_id.eq(UuidValue(user_id)),
+                    user_keystore_fields::encrypted_blob.eq(user_keystore_encrypted),
+                    user_keystore_fields::version_nonce.eq(user_keystore_version_nonce),
+                ))
+                .execute(conn)?;
+
+            dsl::insert_into(signin_nonces)
+                .values((
+                    signin_nonce_fields::user_email.eq(&email_lowercase),
+                    signin_nonce_fields::nonce.eq(OsRng.gen::<i32>()),
+                ))
+                .execute(conn)?;
+
+            dsl::insert_into(user_backup_codes)
+                .values(&backup_codes)
+                .execute(conn)?;
+
+            Ok(())
+        })?;
+
+        Ok(user_id)
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn verify_user_creation(&self, user_id: Uuid) -> Result<(), DaoError> {
+        dsl::update(users.find(UuidValue(user_id)))
+            .set(user_fields::is_verified.eq(true))
+            .execute(&mut self.db_pool.get()?)?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn clear_unverified_users(&self, max_unverified_user_age: Duration) -> Result<(), DaoError> {
+        diesel::delete(
+            users.filter(user_fields::is_verified.eq(false)).filter(
+                user_fields::created_timestamp
+                    .lt(TimestampValue(SystemTime::now() - max_unverified_user_age)),
+            ),
+        )
+        .execute(&mut self.db_pool.get()?)?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn rotate_user_public_key(
+        &self,
+        user_id: Uuid,
+        public_key_id: Uuid,
+        public_key: &[u8],
+        expected_previous_public_key_id: Uuid,
+    ) -> Result<(), DaoError> {
+        let mut db_connection = self.db_pool.get()?;
+
+        db_connection.immediate_transaction(|conn| {
+            let affected_row_count =
+                dsl::update(users.find(UuidValue(user_id)).filter(
+                    user_fields::public_key_id.eq(UuidValue(expected_previous_public_key_id)),
+                ))
+                .set((
+                    user_fields::public_key_id.eq(UuidValue(public_key_id)),
+                    user_fields::public_key.eq(public_key),
+                ))
+                .execute(conn)?;
+
+            if affected_row_count == 0 {
+                // Check whether the update failed because the record wasn't found or because
+                // the key ID was out-of-date
+                let current_key_id = users
+                    .select(user_fields::public_key_id)
+                    .find(UuidValue(user_id))
+                    .first::<Uuid>(conn);
+
+                match current_key_id {
+                    Ok(current_key_id) => {
+                        if current_key_id != expected_previous_public_key_id {
+                            return Err(DaoError::OutOfDate(None));
+                        }
+
+                        // This case should never happen because we filtered on the key ID in
+                        // the update query
+                        unreachable!();
+                    }
+                    Err(e) => return Err(DaoError::from(e)),
+                }
+            }
+
+            Ok(())
+        })
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn get_user_prefs(&self, user_id: Uuid) -> Result<UserPreferences, DaoError> {
+        Ok(user_preferences
+            .find(UuidValue(user_id))
+            .get_result(&mut self.db_pool.get()?)?)
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn get_user_keystore(&self, user_id: Uuid) -> Result<UserKeystore, DaoError> {
+        Ok(user_keystores
+            .find(UuidValue(user_id))
+            .get_result(&mut self.db_pool.get()?)?)
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn get_user_bootstrap(&self, user_id: Uuid) -> Result<UserBootstrap, DaoError> {
+        let mut db_connection = self.db_pool.get()?;
+
+        db_connection.transaction::<_, DaoError, _>(|conn| {
+            let (public_key_id, public_key) = users
+                .select((user_fields::public_key_id, user_fields::public_key))
+                .find(UuidValue(user_id))
+                .get_result::<(Uuid, Vec<u8>)>(conn)?;
+            let prefs = user_preferences
+                .find(UuidValue(user_id))
+                .get_result::<UserPreferences>(conn)?;
+            let keystore = user_keystores
+                .find(UuidValue(user_id))
+                .get_result::<UserKeystore>(conn)?;
+            let is_listed_for_deletion =
+                dsl::select(dsl::exists(user_deletion_requests.find(UuidValue(user_id))))
+                    .get_result(conn)?;
+
+            Ok(UserBootstrap {
+                preferences: EncryptedBlob {
+                    encrypted_blob: prefs.encrypted_blob,
+                    version_nonce: prefs.version_nonce,
+                },
+                keystore: EncryptedBlob {
+                    encrypted_blob: keystore.encrypted_blob,
+                    version_nonce: keystore.version_nonce,
+                },
+                public_key: UserPublicKey {
+                    id: public_key_id.into(),
+                    value: public_key,
+                },
+                is_listed_for_deletion,
+            })
+        })
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn update_user_prefs(
+        &self,
+        user_id: Uuid,
+        prefs_encrypted_blob: &[u8],
+        version_nonce: i64,
+        expected_previous_version_nonce: i64,
+    ) -> Result<(), DaoError> {
+        let mut db_connection = self.db_pool.get()?;
+
+        db_connection.immediate_transaction(|conn| {
+            let affected_row_count = dsl::update(user_preferences.find(UuidValue(user_id)).filter(
+                user_preferences_fields::version_nonce.eq(expected_previous_version_nonce),
+            ))
+            .set((
+                user_preferences_fields::encrypted_blob.eq(prefs_encrypted_blob),
+                user_preferences_fields::version_nonce.eq(version_nonce),
+            ))
+            .execute(conn)?;
+
+            if affected_row_count == 0 {
+                // Check whether the update failed because the record wasn't found or because
+                // the version_nonce was out-of-date
+                let current_version_nonce = user_preferences
+                    .select(user_preferences_fields::version_nonce)
+                    .find(UuidValue(user_id))
+                    .first::<i64>(conn);
+
+                match current_version_nonce {
+                    Ok(current_version_nonce) => {
+                        if current_version_nonce != expected_previous_version_nonce {
+                            return Err(DaoError::OutOfDate(Some(current_version_nonce)));
+                        }
+
+                        // This case should never happen because we filtered on version_nonce
+                        // in the update query
+                        unreachable!();
+                    }
+                    Err(e) => return Err(DaoError::from(e)),
+                }
+            }
+
+            Ok(())
+        })
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn update_user_keystore(
+        &self,
+        user_id: Uuid,
+        keystore_encrypted_blob: &[u8],
+        version_nonce: i64,
+        expected_previous_version_nonce: i64,
+    ) -> Result<(), DaoError> {
+        let mut db_connection = self.db_pool.get()?;
+
+        db_connection.immediate_transaction(|conn| {
+            let affected_row_count =
+                dsl::update(user_keystores.find(UuidValue(user_id)).filter(
+                    user_keystore_fields::version_nonce.eq(expected_previous_version_nonce),
+                ))
+                .set((
+                    user_keystore_fields::encrypted_blob.eq(keystore_encrypted_blob),
+                    user_keystore_fields::version_nonce.eq(version_nonce),
+                ))
+                .execute(conn)?;
+
+            if affected_row_count == 0 {
+                // Check whether the update failed because the record wasn't found or because
+                // the version_nonce was out-of-date
+                let current_version_nonce = user_keystores
+                    .select(user_keystore_fields::version_nonce)
+                    .find(UuidValue(user_id))
+                    .first::<i64>(conn);
+
+                match current_version_nonce {
+                    Ok(current_version_nonce) => {
+                        if current_version_nonce != expected_previous_version_nonce {
+                            return Err(DaoError::OutOfDate(Some(current_version_nonce)));
+                        }
+
+                        // This case should never happen because we filtered on version_nonce
+                        // in the update query
+                        unreachable!();
+                    }
+                    Err(e) => return Err(DaoError::from(e)),
+                }
+            }
+
+            Ok(())
+        })
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn update_password(
+        &self,
+        user_email: &str,
+        new_auth_string_hash: &str,
+        new_auth_string_salt: &[u8],
+        new_auth_string_memory_cost_kib: i32,
+        new_auth_string_parallelism_factor: i32,
+        new_auth_string_iters: i32,
+        new_password_encryption_salt: &[u8],
+        new_password_encryption_memory_cost_kib: i32,
+        new_password_encryption_parallelism_factor: i32,
+        new_password_encryption_iters: i32,
+        encrypted_encryption_key: &[u8],
+    ) -> Result<(), DaoError> {
+        dsl::update(users.filter(user_fields::email.eq(user_email)))
+            .set((
+                user_fields::auth_string_hash.eq(new_auth_string_hash),
+                user_fields::auth_string_salt.eq(new_auth_string_salt),
+                user_fields::auth_string_memory_cost_kib.eq(new_auth_string_memory_cost_kib),
+                user_fields::auth_string_parallelism_factor.eq(new_auth_string_parallelism_factor),
+                user_fields::auth_string_iters.eq(new_auth_string_iters),
+                user_fields::password_encryption_salt.eq(new_password_encryption_salt),
+                user_fields::password_encryption_memory_cost_kib
+                    .eq(new_password_encryption_memory_cost_kib),
+                user_fields::password_encryption_parallelism_factor
+                    .eq(new_password_encryption_parallelism_factor),
+                user_fields::password_encryption_iters.eq(new_password_encryption_iters),
+                user_fields::encryption_key_encrypted_with_password.eq(encrypted_encryption_key),
+            ))
+            .execute(&mut self.db_pool.get()?)?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn update_recovery_key(
+        &self,
+        user_id: Uuid,
+        new_recovery_key_salt: &[u8],
+        new_recovery_key_memory_cost_kib: i32,
+        new_recovery_key_parallelism_factor: i32,
+        new_recovery_key_iters: i32,
+        encrypted_encryption_key: &[u8],
+    ) -> Result<(), DaoError> {
+        dsl::update(users.find(UuidValue(user_id)))
+            .set((
+                user_fields::recovery_key_salt.eq(new_recovery_key_salt),
+                user_fields::recovery_key_memory_cost_kib.eq(new_recovery_key_memory_cost_kib),
+                user_fields::recovery_key_parallelism_factor
+                    .eq(new_recovery_key_parallelism_factor),
+                user_fields::recovery_key_iters.eq(new_recovery_key_iters),
+                user_fields::encryption_key_encrypted_with_recovery_key
+                    .eq(encrypted_encryption_key),
+            ))
+            .execute(&mut self.db_pool.get()?)?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn save_user_deletion_budget_keys(
+        &self,
+        budget_access_key_ids: &[Uuid],
+        user_id: Uuid,
+        delete_me_time: SystemTime,
+    ) -> Result<(), DaoError> {
+        let deletion_request_budget_keys = budget_access_key_ids
+            .iter()
+            .map(|key_id| {
+                (
+                    user_deletion_request_budget_key_fields::key_id.eq(UuidValue(*key_id)),
+                    user_deletion_request_budget_key_fields::user_id.eq(UuidValue(user_id)),
+                    user_deletion_request_budget_key_fields::delete_me_time
+                        .eq(TimestampValue(delete_me_time)),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        dsl::insert_into(user_deletion_request_budget_keys)
+            .values(&deletion_request_budget_keys)
+            .execute(&mut self.db_pool.get()?)?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn initiate_user_deletion(
+        &self,
+        user_id: Uuid,
+        time_until_deletion: Duration,
+    ) -> Result<(), DaoError> {
+        dsl::insert_into(user_deletion_requests)
+            .values((
+                user_deletion_request_fields::user_id.eq(UuidValue(user_id)),
+                user_deletion_request_fields::ready_for_deletion_time
+                    .eq(TimestampValue(SystemTime::now() + time_until_deletion)),
+            ))
+            .execute(&mut self.db_pool.get()?)?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn cancel_user_deletion(&self, user_id: Uuid) -> Result<(), DaoError> {
+        diesel::delete(user_deletion_requests.find(UuidValue(user_id)))
+            .execute(&mut self.db_pool.get()?)?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn expedite_user_deletion(&self, user_id: Uuid) -> Result<bool, DaoError> {
+        let mut db_connection = self.db_pool.get()?;
+
+        let now = SystemTime::now();
+
+        db_connection.immediate_transaction::<_, DaoError, _>(|conn| {
+            let updated_count = dsl::update(user_deletion_requests.find(UuidValue(user_id)))
+                .set(user_deletion_request_fields::ready_for_deletion_time.eq(TimestampValue(now)))
+                .execute(conn)?;
+
+            dsl::update(
+                user_deletion_request_budget_keys.filter(
+                    user_deletion_request_budget_key_fields::user_id.eq(UuidValue(user_id)),
+                ),
+            )
+            .set(user_deletion_request_budget_key_fields::delete_me_time.eq(TimestampValue(now)))
+            .execute(conn)?;
+
+            Ok(updated_count > 0)
+        })
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn delete_user(&self, user_deletion_request: &UserDeletionRequest) -> Result<(), DaoError> {
+        let mut db_connection = self.db_pool.get()?;
+
+        db_connection.immediate_transaction::<_, DaoError, _>(|conn| {
+            let budget_key_ids = user_deletion_request_budget_keys
+                .select(user_deletion_request_budget_key_fields::key_id)
+                .filter(
+                    user_deletion_request_budget_key_fields::user_id
+                        .eq(UuidValue(user_deletion_request.user_id)),
+                )
+                .load::<Uuid>(conn)?;
+
+            let budget_ids = diesel::delete(
+                budget_access_keys
+                    .filter(budget_access_key_fields::key_id.eq_any(uuid_values(&budget_key_ids))),
+            )
+            .returning(budget_access_key_fields::budget_id)
+            .load::<Uuid>(conn)?;
+
+            for budget_id in budget_ids {
+                let users_remaining_in_budget = budget_access_keys
+                    .filter(budget_access_key_fields::budget_id.eq(UuidValue(budget_id)))
+                    .count()
+                    .get_result::<i64>(conn)?;
+
+                if users_remaining_in_budget == 0 {
+                    diesel::delete(budgets.find(UuidValue(budget_id))).execute(conn)?;
+                }
+            }
+
+            diesel::delete(users.find(UuidValue(user_deletion_request.user_id))).execute(conn)?;
+
+            Ok(())
+        })
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn get_all_users_ready_for_deletion(&self) -> Result<Vec<UserDeletionRequest>, DaoError> {
+        Ok(user_deletion_requests
+            .filter(
+                user_deletion_request_fields::ready_for_deletion_time
+                    .lt(TimestampValue(SystemTime::now())),
+            )
+            .get_results(&mut self.db_pool.get()?)?)
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn check_is_user_listed_for_deletion(&self, user_id: Uuid) -> Result<bool, DaoError> {
+        Ok(
+            dsl::select(dsl::exists(user_deletion_requests.find(UuidValue(user_id))))
+                .get_result(&mut self.db_pool.get()?)?,
+        )
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn delete_old_user_deletion_requests(&self) -> Result<(), DaoError> {
+        let mut db_connection = self.db_pool.get()?;
+
+        db_connection.immediate_transaction::<_, DaoError, _>(|conn| {
+            let user_ids = diesel::delete(
+                user_deletion_request_budget_keys.filter(
+                    user_deletion_request_budget_key_fields::delete_me_time
+                        .le(TimestampValue(SystemTime::now())),
+                ),
+            )
+            .returning(user_deletion_request_budget_key_fields::user_id)
+            .get_results::<Uuid>(conn)?;
+
+            diesel::delete(
+                user_deletion_requests
+                    .filter(user_deletion_request_fields::user_id.eq_any(uuid_values(&user_ids))),
+            )
+            .execute(conn)?;
+
+            Ok(())
+        })
+    }
+}
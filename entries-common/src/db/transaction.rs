@@ -0,0 +1,144 @@
+//! Helpers for running `REPEATABLE READ` transactions that Postgres may abort under contention.
+//!
+//! When two transactions touch the same rows, Postgres can abort one of them with a
+//! serialization failure (or, less often, a deadlock). Neither means the request was invalid,
+//! so the transaction is rolled back and run again after a short, jittered delay.
+
+use diesel::pg::PgConnection;
+use diesel::result::{DatabaseErrorKind, Error as DieselError};
+use diesel_async::scoped_futures::ScopedBoxFuture;
+use diesel_async::AsyncPgConnection;
+use rand::Rng;
+use std::time::Duration;
+
+use crate::db::DaoError;
+
+const MAX_ATTEMPTS: u32 = 5;
+const BASE_RETRY_DELAY: Duration = Duration::from_millis(10);
+const MAX_RETRY_DELAY: Duration = Duration::from_millis(250);
+
+/// Runs `f` in a `REPEATABLE READ` transaction on a blocking connection, running it again if
+/// Postgres aborts the transaction with a serialization failure or deadlock. Gives up and
+/// returns the error after a fixed number of attempts.
+///
+/// `f` may be called more than once, so it shouldn't have side effects outside the transaction.
+pub fn run_repeatable_read<T, F>(conn: &mut PgConnection, mut f: F) -> Result<T, DaoError>
+where
+    F: FnMut(&mut PgConnection) -> Result<T, DaoError>,
+{
+    let mut attempt = 1;
+
+    loop {
+        match conn.build_transaction().repeatable_read().run(&mut f) {
+            Err(e) if attempt < MAX_ATTEMPTS && is_retryable(&e) => {
+                log::debug!("Retrying transaction (attempt {attempt} failed): {e}");
+                std::thread::sleep(retry_delay(attempt));
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Async counterpart of [`run_repeatable_read`] for connections from an
+/// [`AsyncDbPool`](super::AsyncDbPool).
+pub async fn run_repeatable_read_async<'b, T, F>(
+    conn: &mut AsyncPgConnection,
+    mut f: F,
+) -> Result<T, DaoError>
+where
+    F: for<'r> FnMut(&'r mut AsyncPgConnection) -> ScopedBoxFuture<'b, 'r, Result<T, DaoError>>
+        + Send,
+    T: 'b,
+{
+    let mut attempt = 1;
+
+    loop {
+        let result = conn
+            .build_transaction()
+            .repeatable_read()
+            .run(|conn| f(conn))
+            .await;
+
+        match result {
+            Err(e) if attempt < MAX_ATTEMPTS && is_retryable(&e) => {
+                log::debug!("Retrying transaction (attempt {attempt} failed): {e}");
+                tokio::time::sleep(retry_delay(attempt)).await;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Whether the error means Postgres aborted the transaction because of a conflict with another
+/// transaction, in which case running it again may succeed.
+pub fn is_retryable(error: &DaoError) -> bool {
+    match error {
+        DaoError::QueryFailure(DieselError::DatabaseError(kind, info)) => match kind {
+            DatabaseErrorKind::SerializationFailure => true,
+            // Diesel doesn't have a kind for deadlocks (SQLSTATE 40P01) and doesn't expose the
+            // SQLSTATE, so this relies on the message Postgres sends
+            DatabaseErrorKind::Unknown => info.message().starts_with("deadlock detected"),
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/// Exponential backoff with full jitter: a random delay between zero and
+/// `BASE_RETRY_DELAY * 2^(attempt - 1)`, capped at `MAX_RETRY_DELAY`.
+fn retry_delay(attempt: u32) -> Duration {
+    let max_delay = BASE_RETRY_DELAY
+        .saturating_mul(1 << (attempt - 1).min(16))
+        .min(MAX_RETRY_DELAY);
+
+    Duration::from_micros(rand::thread_rng().gen_range(0..=max_delay.as_micros() as u64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use diesel::result::DatabaseErrorInformation;
+
+    fn db_error(kind: DatabaseErrorKind, message: &str) -> DaoError {
+        let info: Box<dyn DatabaseErrorInformation + Send + Sync> = Box::new(message.to_owned());
+        DaoError::QueryFailure(DieselError::DatabaseError(kind, info))
+    }
+
+    #[test]
+    fn test_is_retryable() {
+        assert!(is_retryable(&db_error(
+            DatabaseErrorKind::SerializationFailure,
+            "could not serialize access due to concurrent update",
+        )));
+        assert!(is_retryable(&db_error(
+            DatabaseErrorKind::Unknown,
+            "deadlock detected",
+        )));
+
+        assert!(!is_retryable(&db_error(
+            DatabaseErrorKind::Unknown,
+            "canceling statement due to statement timeout",
+        )));
+        assert!(!is_retryable(&db_error(
+            DatabaseErrorKind::UniqueViolation,
+            "duplicate key value violates unique constraint",
+        )));
+        assert!(!is_retryable(&DaoError::QueryFailure(
+            DieselError::NotFound
+        )));
+        assert!(!is_retryable(&DaoError::OutOfDate(Some(1))));
+    }
+
+    #[test]
+    fn test_retry_delay() {
+        for attempt in 1..MAX_ATTEMPTS {
+            let max_delay = BASE_RETRY_DELAY * 2u32.pow(attempt - 1);
+            assert!(retry_delay(attempt) <= max_delay.min(MAX_RETRY_DELAY));
+        }
+
+        assert!(retry_delay(u32::MAX) <= MAX_RETRY_DELAY);
+    }
+}
@@ -1,17 +1,18 @@
-use diesel::{dsl, ExpressionMethods, QueryDsl, RunQueryDsl};
+use diesel::{dsl, ExpressionMethods, OptionalExtension, QueryDsl, RunQueryDsl};
 use rand::{rngs::OsRng, Rng};
 use std::time::{Duration, SystemTime};
 use uuid::Uuid;
 
+use crate::db::transaction::run_repeatable_read;
 use crate::db::{DaoError, DbThreadPool};
-use crate::messages::UserPublicKey;
+use crate::messages::{EncryptedBlob, UserBootstrap, UserPublicKey};
 use crate::models::signin_nonce::NewSigninNonce;
 use crate::models::user::NewUser;
 use crate::models::user_backup_code::NewUserBackupCode;
 use crate::models::user_deletion_request::{NewUserDeletionRequest, UserDeletionRequest};
 use crate::models::user_deletion_request_budget_key::NewUserDeletionRequestBudgetKey;
-use crate::models::user_keystore::NewUserKeystore;
-use crate::models::user_preferences::NewUserPreferences;
+use crate::models::user_keystore::{NewUserKeystore, UserKeystore};
+use crate::models::user_preferences::{NewUserPreferences, UserPreferences};
 
 use crate::schema::budget_access_keys as budget_access_key_fields;
 use crate::schema::budget_access_keys::dsl::budget_access_keys;
@@ -29,6 +30,141 @@ use crate::schema::user_preferences::dsl::user_preferences;
 use crate::schema::users as user_fields;
 use crate::schema::users::dsl::users;
 
+/// A summary of a user's account for support purposes
+#[derive(Clone, Debug)]
+pub struct UserStatus {
+    pub id: Uuid,
+    pub email: String,
+    pub is_verified: bool,
+    pub created_timestamp: SystemTime,
+    pub ready_for_deletion_time: Option<SystemTime>,
+    /// Budget membership is end-to-end encrypted, so the server only knows which budgets a user
+    /// belongs to once they've requested deletion and handed over their budget keys
+    pub budgets_pending_deletion: i64,
+}
+
+/// Queries for user accounts and the data stored with them
+pub trait UserDao: Send + Sync {
+    fn get_user_public_key(&self, user_email: &str) -> Result<UserPublicKey, DaoError>;
+
+    fn get_user_status(&self, user_email: &str) -> Result<UserStatus, DaoError>;
+
+    #[allow(clippy::too_many_arguments)]
+    fn create_user(
+        &self,
+        email: &str,
+        auth_string_hash: &str,
+        auth_string_salt: &[u8],
+        auth_string_memory_cost_kib: i32,
+        auth_string_parallelism_factor: i32,
+        auth_string_iters: i32,
+        password_encryption_salt: &[u8],
+        password_encryption_memory_cost_kib: i32,
+        password_encryption_parallelism_factor: i32,
+        password_encryption_iters: i32,
+        recovery_key_salt: &[u8],
+        recovery_key_memory_cost_kib: i32,
+        recovery_key_parallelism_factor: i32,
+        recovery_key_iters: i32,
+        encryption_key_encrypted_with_password: &[u8],
+        encryption_key_encrypted_with_recovery_key: &[u8],
+        public_key_id: Uuid,
+        public_key: &[u8],
+        preferences_encrypted: &[u8],
+        preferences_version_nonce: i64,
+        user_keystore_encrypted: &[u8],
+        user_keystore_version_nonce: i64,
+        backup_codes: &[String],
+    ) -> Result<Uuid, DaoError>;
+
+    fn verify_user_creation(&self, user_id: Uuid) -> Result<(), DaoError>;
+
+    fn clear_unverified_users(&self, max_unverified_user_age: Duration) -> Result<(), DaoError>;
+
+    fn rotate_user_public_key(
+        &self,
+        user_id: Uuid,
+        public_key_id: Uuid,
+        public_key: &[u8],
+        expected_previous_public_key_id: Uuid,
+    ) -> Result<(), DaoError>;
+
+    fn get_user_prefs(&self, user_id: Uuid) -> Result<UserPreferences, DaoError>;
+
+    fn get_user_keystore(&self, user_id: Uuid) -> Result<UserKeystore, DaoError>;
+
+    fn get_user_bootstrap(&self, user_id: Uuid) -> Result<UserBootstrap, DaoError>;
+
+    fn update_user_prefs(
+        &self,
+        user_id: Uuid,
+        prefs_encrypted_blob: &[u8],
+        version_nonce: i64,
+        expected_previous_version_nonce: i64,
+    ) -> Result<(), DaoError>;
+
+    fn update_user_keystore(
+        &self,
+        user_id: Uuid,
+        keystore_encrypted_blob: &[u8],
+        version_nonce: i64,
+        expected_previous_version_nonce: i64,
+    ) -> Result<(), DaoError>;
+
+    #[allow(clippy::too_many_arguments)]
+    fn update_password(
+        &self,
+        user_email: &str,
+        new_auth_string_hash: &str,
+        new_auth_string_salt: &[u8],
+        new_auth_string_memory_cost_kib: i32,
+        new_auth_string_parallelism_factor: i32,
+        new_auth_string_iters: i32,
+        new_password_encryption_salt: &[u8],
+        new_password_encryption_memory_cost_kib: i32,
+        new_password_encryption_parallelism_factor: i32,
+        new_password_encryption_iters: i32,
+        encrypted_encryption_key: &[u8],
+    ) -> Result<(), DaoError>;
+
+    fn update_recovery_key(
+        &self,
+        user_id: Uuid,
+        new_recovery_key_salt: &[u8],
+        new_recovery_key_memory_cost_kib: i32,
+        new_recovery_key_parallelism_factor: i32,
+        new_recovery_key_iters: i32,
+        encrypted_encryption_key: &[u8],
+    ) -> Result<(), DaoError>;
+
+    fn save_user_deletion_budget_keys(
+        &self,
+        budget_access_key_ids: &[Uuid],
+        user_id: Uuid,
+        delete_me_time: SystemTime,
+    ) -> Result<(), DaoError>;
+
+    fn initiate_user_deletion(
+        &self,
+        user_id: Uuid,
+        time_until_deletion: Duration,
+    ) -> Result<(), DaoError>;
+
+    fn cancel_user_deletion(&self, user_id: Uuid) -> Result<(), DaoError>;
+
+    /// Makes a pending deletion due immediately so the next run of the user deletion job removes
+    /// the user. Returns false if the user has no pending deletion.
+    fn expedite_user_deletion(&self, user_id: Uuid) -> Result<bool, DaoError>;
+
+    fn delete_user(&self, user_deletion_request: &UserDeletionRequest) -> Result<(), DaoError>;
+
+    fn get_all_users_ready_for_deletion(&self) -> Result<Vec<UserDeletionRequest>, DaoError>;
+
+    fn check_is_user_listed_for_deletion(&self, user_id: Uuid) -> Result<bool, DaoError>;
+
+    fn delete_old_user_deletion_requests(&self) -> Result<(), DaoError>;
+}
+
 pub struct Dao {
     db_thread_pool: DbThreadPool,
 }
@@ -39,8 +175,11 @@ impl Dao {
             db_thread_pool: db_thread_pool.clone(),
         }
     }
+}
 
-    pub fn get_user_public_key(&self, user_email: &str) -> Result<UserPublicKey, DaoError> {
+impl UserDao for Dao {
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn get_user_public_key(&self, user_email: &str) -> Result<UserPublicKey, DaoError> {
         let (key_id, key) = users
             .select((user_fields::public_key_id, user_fields::public_key))
             .filter(user_fields::email.eq(user_email))
@@ -52,8 +191,43 @@ impl Dao {
         })
     }
 
-    #[allow(clippy::too_many_arguments)]
-    pub fn create_user(
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn get_user_status(&self, user_email: &str) -> Result<UserStatus, DaoError> {
+        let mut db_connection = self.db_thread_pool.get()?;
+
+        let (id, email, is_verified, created_timestamp) = users
+            .select((
+                user_fields::id,
+                user_fields::email,
+                user_fields::is_verified,
+                user_fields::created_timestamp,
+            ))
+            .filter(user_fields::email.eq(user_email))
+            .first::<(Uuid, String, bool, SystemTime)>(&mut db_connection)?;
+
+        let ready_for_deletion_time = user_deletion_requests
+            .select(user_deletion_request_fields::ready_for_deletion_time)
+            .find(id)
+            .first::<SystemTime>(&mut db_connection)
+            .optional()?;
+
+        let budgets_pending_deletion = user_deletion_request_budget_keys
+            .filter(user_deletion_request_budget_key_fields::user_id.eq(id))
+            .count()
+            .get_result::<i64>(&mut db_connection)?;
+
+        Ok(UserStatus {
+            id,
+            email,
+            is_verified,
+            created_timestamp,
+            ready_for_deletion_time,
+            budgets_pending_deletion,
+        })
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn create_user(
         &self,
         email: &str,
         auth_string_hash: &str,
@@ -165,7 +339,8 @@ impl Dao {
         Ok(user_id)
     }
 
-    pub fn verify_user_creation(&self, user_id: Uuid) -> Result<(), DaoError> {
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn verify_user_creation(&self, user_id: Uuid) -> Result<(), DaoError> {
         dsl::update(users.find(user_id))
             .set(user_fields::is_verified.eq(true))
             .execute(&mut self.db_thread_pool.get()?)?;
@@ -173,10 +348,8 @@ impl Dao {
         Ok(())
     }
 
-    pub fn clear_unverified_users(
-        &self,
-        max_unverified_user_age: Duration,
-    ) -> Result<(), DaoError> {
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn clear_unverified_users(&self, max_unverified_user_age: Duration) -> Result<(), DaoError> {
         diesel::delete(users.filter(user_fields::is_verified.eq(false)).filter(
             user_fields::created_timestamp.lt(SystemTime::now() - max_unverified_user_age),
         ))
@@ -185,7 +358,8 @@ impl Dao {
         Ok(())
     }
 
-    pub fn rotate_user_public_key(
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn rotate_user_public_key(
         &self,
         user_id: Uuid,
         public_key_id: Uuid,
@@ -194,48 +368,103 @@ impl Dao {
     ) -> Result<(), DaoError> {
         let mut db_connection = self.db_thread_pool.get()?;
 
-        db_connection
-            .build_transaction()
-            .repeatable_read()
-            .run::<_, DaoError, _>(|conn| {
-                let affected_row_count = dsl::update(
-                    users
-                        .find(user_id)
-                        .filter(user_fields::public_key_id.eq(expected_previous_public_key_id)),
-                )
-                .set((
-                    user_fields::public_key_id.eq(public_key_id),
-                    user_fields::public_key.eq(public_key),
-                ))
-                .execute(conn)?;
-
-                if affected_row_count == 0 {
-                    // Check whether the update failed because the record wasn't found or because
-                    // the key ID was out-of-date
-                    let current_key_id = users
-                        .select(user_fields::public_key_id)
-                        .find(user_id)
-                        .first::<Uuid>(conn);
-
-                    match current_key_id {
-                        Ok(current_key_id) => {
-                            if current_key_id != expected_previous_public_key_id {
-                                return Err(DaoError::OutOfDate);
-                            }
-
-                            // This case should never happen because we filtered on version_nonce
-                            // in the update query
-                            unreachable!();
+        run_repeatable_read(&mut db_connection, |conn| {
+            let affected_row_count = dsl::update(
+                users
+                    .find(user_id)
+                    .filter(user_fields::public_key_id.eq(expected_previous_public_key_id)),
+            )
+            .set((
+                user_fields::public_key_id.eq(public_key_id),
+                user_fields::public_key.eq(public_key),
+            ))
+            .execute(conn)?;
+
+            if affected_row_count == 0 {
+                // Check whether the update failed because the record wasn't found or because
+                // the key ID was out-of-date
+                let current_key_id = users
+                    .select(user_fields::public_key_id)
+                    .find(user_id)
+                    .first::<Uuid>(conn);
+
+                match current_key_id {
+                    Ok(current_key_id) => {
+                        if current_key_id != expected_previous_public_key_id {
+                            return Err(DaoError::OutOfDate(None));
                         }
-                        Err(e) => return Err(DaoError::from(e)),
+
+                        // This case should never happen because we filtered on version_nonce
+                        // in the update query
+                        unreachable!();
                     }
+                    Err(e) => return Err(DaoError::from(e)),
                 }
+            }
 
-                Ok(())
-            })
+            Ok(())
+        })
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn get_user_prefs(&self, user_id: Uuid) -> Result<UserPreferences, DaoError> {
+        Ok(user_preferences
+            .find(user_id)
+            .get_result(&mut self.db_thread_pool.get()?)?)
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn get_user_keystore(&self, user_id: Uuid) -> Result<UserKeystore, DaoError> {
+        Ok(user_keystores
+            .find(user_id)
+            .get_result(&mut self.db_thread_pool.get()?)?)
     }
 
-    pub fn update_user_prefs(
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn get_user_bootstrap(&self, user_id: Uuid) -> Result<UserBootstrap, DaoError> {
+        let mut db_connection = self.db_thread_pool.get()?;
+
+        let bootstrap = db_connection
+            .build_transaction()
+            .repeatable_read()
+            .read_only()
+            .run::<_, diesel::result::Error, _>(|conn| {
+                let (public_key_id, public_key) = users
+                    .select((user_fields::public_key_id, user_fields::public_key))
+                    .find(user_id)
+                    .get_result::<(Uuid, Vec<u8>)>(conn)?;
+                let prefs = user_preferences
+                    .find(user_id)
+                    .get_result::<UserPreferences>(conn)?;
+                let keystore = user_keystores
+                    .find(user_id)
+                    .get_result::<UserKeystore>(conn)?;
+                let is_listed_for_deletion =
+                    dsl::select(dsl::exists(user_deletion_requests.find(user_id)))
+                        .get_result(conn)?;
+
+                Ok(UserBootstrap {
+                    preferences: EncryptedBlob {
+                        encrypted_blob: prefs.encrypted_blob,
+                        version_nonce: prefs.version_nonce,
+                    },
+                    keystore: EncryptedBlob {
+                        encrypted_blob: keystore.encrypted_blob,
+                        version_nonce: keystore.version_nonce,
+                    },
+                    public_key: UserPublicKey {
+                        id: public_key_id.into(),
+                        value: public_key,
+                    },
+                    is_listed_for_deletion,
+                })
+            })?;
+
+        Ok(bootstrap)
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn update_user_prefs(
         &self,
         user_id: Uuid,
         prefs_encrypted_blob: &[u8],
@@ -244,46 +473,44 @@ impl Dao {
     ) -> Result<(), DaoError> {
         let mut db_connection = self.db_thread_pool.get()?;
 
-        db_connection
-            .build_transaction()
-            .repeatable_read()
-            .run::<_, DaoError, _>(|conn| {
-                let affected_row_count = dsl::update(user_preferences.find(user_id).filter(
-                    user_preferences_fields::version_nonce.eq(expected_previous_version_nonce),
-                ))
-                .set((
-                    user_preferences_fields::encrypted_blob.eq(prefs_encrypted_blob),
-                    user_preferences_fields::version_nonce.eq(version_nonce),
-                ))
-                .execute(conn)?;
-
-                if affected_row_count == 0 {
-                    // Check whether the update failed because the record wasn't found or because
-                    // the version_nonce was out-of-date
-                    let current_version_nonce = user_preferences
-                        .select(user_preferences_fields::version_nonce)
-                        .find(user_id)
-                        .first::<i64>(conn);
-
-                    match current_version_nonce {
-                        Ok(current_version_nonce) => {
-                            if current_version_nonce != expected_previous_version_nonce {
-                                return Err(DaoError::OutOfDate);
-                            }
-
-                            // This case should never happen because we filtered on version_nonce
-                            // in the update query
-                            unreachable!();
+        run_repeatable_read(&mut db_connection, |conn| {
+            let affected_row_count = dsl::update(user_preferences.find(user_id).filter(
+                user_preferences_fields::version_nonce.eq(expected_previous_version_nonce),
+            ))
+            .set((
+                user_preferences_fields::encrypted_blob.eq(prefs_encrypted_blob),
+                user_preferences_fields::version_nonce.eq(version_nonce),
+            ))
+            .execute(conn)?;
+
+            if affected_row_count == 0 {
+                // Check whether the update failed because the record wasn't found or because
+                // the version_nonce was out-of-date
+                let current_version_nonce = user_preferences
+                    .select(user_preferences_fields::version_nonce)
+                    .find(user_id)
+                    .first::<i64>(conn);
+
+                match current_version_nonce {
+                    Ok(current_version_nonce) => {
+                        if current_version_nonce != expected_previous_version_nonce {
+                            return Err(DaoError::OutOfDate(Some(current_version_nonce)));
                         }
-                        Err(e) => return Err(DaoError::from(e)),
+
+                        // This case should never happen because we filtered on version_nonce
+                        // in the update query
+                        unreachable!();
                     }
+                    Err(e) => return Err(DaoError::from(e)),
                 }
+            }
 
-                Ok(())
-            })
+            Ok(())
+        })
     }
 
-    pub fn update_user_keystore(
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn update_user_keystore(
         &self,
         user_id: Uuid,
         keystore_encrypted_blob: &[u8],
@@ -292,11 +519,9 @@ impl Dao {
     ) -> Result<(), DaoError> {
         let mut db_connection = self.db_thread_pool.get()?;
 
-        db_connection
-            .build_transaction()
-            .repeatable_read()
-            .run::<_, DaoError, _>(|conn| {
-                let affected_row_count = dsl::update(user_keystores.find(user_id).filter(
+        run_repeatable_read(&mut db_connection, |conn| {
+            let affected_row_count =
+                dsl::update(user_keystores.find(user_id).filter(
                     user_keystore_fields::version_nonce.eq(expected_previous_version_nonce),
                 ))
                 .set((
@@ -305,34 +530,34 @@ impl Dao {
                 ))
                 .execute(conn)?;
 
-                if affected_row_count == 0 {
-                    // Check whether the update failed because the record wasn't found or because
-                    // the version_nonce was out-of-date
-                    let current_version_nonce = user_keystores
-                        .select(user_keystore_fields::version_nonce)
-                        .find(user_id)
-                        .first::<i64>(conn);
-
-                    match current_version_nonce {
-                        Ok(current_version_nonce) => {
-                            if current_version_nonce != expected_previous_version_nonce {
-                                return Err(DaoError::OutOfDate);
-                            }
-
-                            // This case should never happen because we filtered on version_nonce
-                            // in the update query
-                            unreachable!();
+            if affected_row_count == 0 {
+                // Check whether the update failed because the record wasn't found or because
+                // the version_nonce was out-of-date
+                let current_version_nonce = user_keystores
+                    .select(user_keystore_fields::version_nonce)
+                    .find(user_id)
+                    .first::<i64>(conn);
+
+                match current_version_nonce {
+                    Ok(current_version_nonce) => {
+                        if current_version_nonce != expected_previous_version_nonce {
+                            return Err(DaoError::OutOfDate(Some(current_version_nonce)));
                         }
-                        Err(e) => return Err(DaoError::from(e)),
+
+                        // This case should never happen because we filtered on version_nonce
+                        // in the update query
+                        unreachable!();
                     }
+                    Err(e) => return Err(DaoError::from(e)),
                 }
+            }
 
-                Ok(())
-            })
+            Ok(())
+        })
     }
 
-    #[allow(clippy::too_many_arguments)]
-    pub fn update_password(
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn update_password(
         &self,
         user_email: &str,
         new_auth_string_hash: &str,
@@ -366,7 +591,8 @@ impl Dao {
         Ok(())
     }
 
-    pub fn update_recovery_key(
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn update_recovery_key(
         &self,
         user_id: Uuid,
         new_recovery_key_salt: &[u8],
@@ -390,7 +616,8 @@ impl Dao {
         Ok(())
     }
 
-    pub fn save_user_deletion_budget_keys(
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn save_user_deletion_budget_keys(
         &self,
         budget_access_key_ids: &[Uuid],
         user_id: Uuid,
@@ -412,7 +639,8 @@ impl Dao {
         Ok(())
     }
 
-    pub fn initiate_user_deletion(
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn initiate_user_deletion(
         &self,
         user_id: Uuid,
         time_until_deletion: Duration,
@@ -429,14 +657,41 @@ impl Dao {
         Ok(())
     }
 
-    pub fn cancel_user_deletion(&self, user_id: Uuid) -> Result<(), DaoError> {
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn cancel_user_deletion(&self, user_id: Uuid) -> Result<(), DaoError> {
         diesel::delete(user_deletion_requests.find(user_id))
             .execute(&mut self.db_thread_pool.get()?)?;
 
         Ok(())
     }
 
-    pub fn delete_user(&self, user_deletion_request: &UserDeletionRequest) -> Result<(), DaoError> {
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn expedite_user_deletion(&self, user_id: Uuid) -> Result<bool, DaoError> {
+        let mut db_connection = self.db_thread_pool.get()?;
+
+        let now = SystemTime::now();
+
+        db_connection
+            .build_transaction()
+            .run::<_, diesel::result::Error, _>(|conn| {
+                let updated_count = dsl::update(user_deletion_requests.find(user_id))
+                    .set(user_deletion_request_fields::ready_for_deletion_time.eq(now))
+                    .execute(conn)?;
+
+                dsl::update(
+                    user_deletion_request_budget_keys
+                        .filter(user_deletion_request_budget_key_fields::user_id.eq(user_id)),
+                )
+                .set(user_deletion_request_budget_key_fields::delete_me_time.eq(now))
+                .execute(conn)?;
+
+                Ok(updated_count > 0)
+            })
+            .map_err(DaoError::from)
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn delete_user(&self, user_deletion_request: &UserDeletionRequest) -> Result<(), DaoError> {
         let mut db_connection = self.db_thread_pool.get()?;
 
         db_connection
@@ -474,20 +729,23 @@ impl Dao {
         Ok(())
     }
 
-    pub fn get_all_users_ready_for_deletion(&self) -> Result<Vec<UserDeletionRequest>, DaoError> {
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn get_all_users_ready_for_deletion(&self) -> Result<Vec<UserDeletionRequest>, DaoError> {
         Ok(user_deletion_requests
             .filter(user_deletion_request_fields::ready_for_deletion_time.lt(SystemTime::now()))
             .get_results(&mut self.db_thread_pool.get()?)?)
     }
 
-    pub fn check_is_user_listed_for_deletion(&self, user_id: Uuid) -> Result<bool, DaoError> {
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn check_is_user_listed_for_deletion(&self, user_id: Uuid) -> Result<bool, DaoError> {
         Ok(
             dsl::select(dsl::exists(user_deletion_requests.find(user_id)))
                 .get_result(&mut self.db_thread_pool.get()?)?,
         )
     }
 
-    pub fn delete_old_user_deletion_requests(&self) -> Result<(), DaoError> {
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn delete_old_user_deletion_requests(&self) -> Result<(), DaoError> {
         let mut db_connection = self.db_thread_pool.get()?;
 
         db_connection
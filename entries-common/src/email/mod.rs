@@ -42,6 +42,7 @@ pub struct EmailMessage<'a> {
 #[async_trait]
 pub trait SendEmail: Send + Sync {
     async fn send<'a>(&self, message: EmailMessage<'a>) -> Result<(), EmailError>;
+    async fn test_connection(&self) -> Result<bool, EmailError>;
 }
 
 pub type EmailSender = Arc<Box<dyn SendEmail>>;
@@ -38,17 +38,11 @@ impl AmazonSes {
 
         Ok(Self { smtp_thread_pool })
     }
-
-    pub async fn test_connection(&self) -> Result<bool, EmailError> {
-        self.smtp_thread_pool
-            .test_connection()
-            .await
-            .map_err(|e| EmailError::RelayConnectionFailed(e.to_string()))
-    }
 }
 
 #[async_trait]
 impl SendEmail for AmazonSes {
+    #[tracing::instrument(level = "debug", skip_all)]
     async fn send<'a>(&self, message: EmailMessage<'a>) -> Result<(), EmailError> {
         let content_type = if message.is_html {
             ContentType::TEXT_HTML
@@ -73,4 +67,12 @@ impl SendEmail for AmazonSes {
             Err(e) => Err(EmailError::FailedToSend(e)),
         }
     }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    async fn test_connection(&self) -> Result<bool, EmailError> {
+        self.smtp_thread_pool
+            .test_connection()
+            .await
+            .map_err(|e| EmailError::RelayConnectionFailed(e.to_string()))
+    }
 }
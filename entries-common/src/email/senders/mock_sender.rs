@@ -17,4 +17,8 @@ impl SendEmail for MockSender {
         println!("\n\n{:#?}\n\n", message);
         Ok(())
     }
+
+    async fn test_connection(&self) -> Result<bool, EmailError> {
+        Ok(true)
+    }
 }
@@ -3,6 +3,7 @@
 #[macro_use]
 extern crate diesel;
 
+pub mod config;
 pub mod db;
 pub mod email;
 pub mod html;
@@ -10,5 +11,6 @@ pub mod messages;
 pub mod models;
 pub mod otp;
 pub mod schema;
+pub mod shutdown;
 pub mod token;
 pub mod validators;
@@ -112,6 +112,9 @@ pub struct CategoryWithTempId {
     pub encrypted_blob: ::prost::alloc::vec::Vec<u8>,
     #[prost(int64, required, tag = "3")]
     pub version_nonce: i64,
+    /// A UUIDv7 generated by the client. The server generates one if missing.
+    #[prost(message, optional, tag = "4")]
+    pub id: ::core::option::Option<Uuid>,
 }
 #[derive(Zeroize)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -132,6 +135,9 @@ pub struct EncryptedBlobAndCategoryId {
     pub version_nonce: i64,
     #[prost(message, optional, tag = "3")]
     pub category_id: ::core::option::Option<Uuid>,
+    /// A UUIDv7 generated by the client for the entry. The server generates one if missing.
+    #[prost(message, optional, tag = "4")]
+    pub id: ::core::option::Option<Uuid>,
 }
 #[derive(Zeroize)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -154,6 +160,11 @@ pub struct EntryAndCategory {
     pub category_encrypted_blob: ::prost::alloc::vec::Vec<u8>,
     #[prost(int64, required, tag = "4")]
     pub category_version_nonce: i64,
+    /// UUIDv7s generated by the client. The server generates any that are missing.
+    #[prost(message, optional, tag = "5")]
+    pub entry_id: ::core::option::Option<Uuid>,
+    #[prost(message, optional, tag = "6")]
+    pub category_id: ::core::option::Option<Uuid>,
 }
 #[derive(Zeroize)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -194,6 +205,9 @@ pub struct NewEncryptedBlob {
     pub value: ::prost::alloc::vec::Vec<u8>,
     #[prost(int64, required, tag = "2")]
     pub version_nonce: i64,
+    /// A UUIDv7 generated by the client. The server generates one if missing.
+    #[prost(message, optional, tag = "3")]
+    pub id: ::core::option::Option<Uuid>,
 }
 #[derive(Zeroize)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -413,6 +427,14 @@ pub struct BudgetShareInviteList {
 }
 #[derive(Zeroize)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct EncryptedBlob {
+    #[prost(bytes = "vec", required, tag = "1")]
+    pub encrypted_blob: ::prost::alloc::vec::Vec<u8>,
+    #[prost(int64, required, tag = "2")]
+    pub version_nonce: i64,
+}
+#[derive(Zeroize)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct EntryIdAndCategoryId {
     #[prost(message, required, tag = "1")]
     pub entry_id: Uuid,
@@ -443,6 +465,23 @@ pub struct ServerErrorResponse {
     pub err_type: i32,
     #[prost(string, required, tag = "2")]
     pub err_message: ::prost::alloc::string::String,
+    #[prost(string, optional, tag = "3")]
+    pub request_id: ::core::option::Option<::prost::alloc::string::String>,
+    /// The request field that was too large (INPUT_TOO_LARGE). Unset if the whole body was.
+    #[prost(string, optional, tag = "4")]
+    pub field: ::core::option::Option<::prost::alloc::string::String>,
+    /// The largest allowed size of the field or body, in bytes or items (INPUT_TOO_LARGE)
+    #[prost(uint64, optional, tag = "5")]
+    pub max_size: ::core::option::Option<u64>,
+    /// How long to wait before trying again (TOO_MANY_ATTEMPTS)
+    #[prost(uint64, optional, tag = "6")]
+    pub retry_after_secs: ::core::option::Option<u64>,
+    /// The ID that is already taken (CONFLICT_WITH_EXISTING)
+    #[prost(message, optional, tag = "7")]
+    pub conflicting_id: ::core::option::Option<Uuid>,
+    /// The version_nonce currently stored for the object (OUT_OF_DATE)
+    #[prost(int64, optional, tag = "8")]
+    pub current_version_nonce: ::core::option::Option<i64>,
 }
 #[derive(Zeroize)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -474,6 +513,19 @@ pub struct TokenPair {
     #[prost(message, required, tag = "3")]
     pub server_time: Timestamp,
 }
+/// Everything a new device needs from the user's account on first launch
+#[derive(Zeroize)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UserBootstrap {
+    #[prost(message, required, tag = "1")]
+    pub preferences: EncryptedBlob,
+    #[prost(message, required, tag = "2")]
+    pub keystore: EncryptedBlob,
+    #[prost(message, required, tag = "3")]
+    pub public_key: UserPublicKey,
+    #[prost(bool, required, tag = "4")]
+    pub is_listed_for_deletion: bool,
+}
 #[derive(Zeroize)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct UserPublicKey {
@@ -523,6 +575,8 @@ pub enum ErrorType {
     InputTooLarge = 22,
     /// 418
     TooManyRequested = 23,
+    /// 426
+    ClientUpdateRequired = 26,
     /// 500
     InternalError = 24,
     /// Anything that Actix Web returns before the handler is reached
@@ -559,6 +613,7 @@ impl ErrorType {
             Self::ForeignKeyDoesNotExist => "FOREIGN_KEY_DOES_NOT_EXIST",
             Self::InputTooLarge => "INPUT_TOO_LARGE",
             Self::TooManyRequested => "TOO_MANY_REQUESTED",
+            Self::ClientUpdateRequired => "CLIENT_UPDATE_REQUIRED",
             Self::InternalError => "INTERNAL_ERROR",
             Self::ActixWebPrehandler => "ACTIX_WEB_PREHANDLER",
         }
@@ -590,9 +645,6079 @@ impl ErrorType {
             "FOREIGN_KEY_DOES_NOT_EXIST" => Some(Self::ForeignKeyDoesNotExist),
             "INPUT_TOO_LARGE" => Some(Self::InputTooLarge),
             "TOO_MANY_REQUESTED" => Some(Self::TooManyRequested),
+            "CLIENT_UPDATE_REQUIRED" => Some(Self::ClientUpdateRequired),
             "INTERNAL_ERROR" => Some(Self::InternalError),
             "ACTIX_WEB_PREHANDLER" => Some(Self::ActixWebPrehandler),
             _ => None,
         }
     }
 }
+impl serde::Serialize for AcceptKeyInfo {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let len = 2;
+        let mut struct_ser = serializer.serialize_struct("entries.serverschema.AcceptKeyInfo", len)?;
+        struct_ser.serialize_field("readOnly", &self.read_only)?;
+        #[allow(clippy::needless_borrow)]
+        struct_ser.serialize_field("expiration", ToString::to_string(&self.expiration).as_str())?;
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for AcceptKeyInfo {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "read_only",
+            "readOnly",
+            "expiration",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            ReadOnly,
+            Expiration,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "readOnly" | "read_only" => Ok(GeneratedField::ReadOnly),
+                            "expiration" => Ok(GeneratedField::Expiration),
+                            _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = AcceptKeyInfo;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct entries.serverschema.AcceptKeyInfo")
+            }
+
+            fn visit_map<V>(self, mut map_: V) -> std::result::Result<AcceptKeyInfo, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut read_only__ = None;
+                let mut expiration__ = None;
+                while let Some(k) = map_.next_key()? {
+                    match k {
+                        GeneratedField::ReadOnly => {
+                            if read_only__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("readOnly"));
+                            }
+                            read_only__ = Some(map_.next_value()?);
+                        }
+                        GeneratedField::Expiration => {
+                            if expiration__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("expiration"));
+                            }
+                            expiration__ = 
+                                Some(map_.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                    }
+                }
+                Ok(AcceptKeyInfo {
+                    read_only: read_only__.ok_or_else(|| serde::de::Error::missing_field("readOnly"))?,
+                    expiration: expiration__.ok_or_else(|| serde::de::Error::missing_field("expiration"))?,
+                })
+            }
+        }
+        deserializer.deserialize_struct("entries.serverschema.AcceptKeyInfo", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for AuthStringAndEncryptedPasswordUpdate {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let len = 12;
+        let mut struct_ser = serializer.serialize_struct("entries.serverschema.AuthStringAndEncryptedPasswordUpdate", len)?;
+        struct_ser.serialize_field("userEmail", &self.user_email)?;
+        struct_ser.serialize_field("otp", &self.otp)?;
+        #[allow(clippy::needless_borrow)]
+        struct_ser.serialize_field("newAuthString", pbjson::private::base64::encode(&self.new_auth_string).as_str())?;
+        #[allow(clippy::needless_borrow)]
+        struct_ser.serialize_field("authStringSalt", pbjson::private::base64::encode(&self.auth_string_salt).as_str())?;
+        struct_ser.serialize_field("authStringMemoryCostKib", &self.auth_string_memory_cost_kib)?;
+        struct_ser.serialize_field("authStringParallelismFactor", &self.auth_string_parallelism_factor)?;
+        struct_ser.serialize_field("authStringIters", &self.auth_string_iters)?;
+        #[allow(clippy::needless_borrow)]
+        struct_ser.serialize_field("passwordEncryptionSalt", pbjson::private::base64::encode(&self.password_encryption_salt).as_str())?;
+        struct_ser.serialize_field("passwordEncryptionMemoryCostKib", &self.password_encryption_memory_cost_kib)?;
+        struct_ser.serialize_field("passwordEncryptionParallelismFactor", &self.password_encryption_parallelism_factor)?;
+        struct_ser.serialize_field("passwordEncryptionIters", &self.password_encryption_iters)?;
+        #[allow(clippy::needless_borrow)]
+        struct_ser.serialize_field("encryptedEncryptionKey", pbjson::private::base64::encode(&self.encrypted_encryption_key).as_str())?;
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for AuthStringAndEncryptedPasswordUpdate {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "user_email",
+            "userEmail",
+            "otp",
+            "new_auth_string",
+            "newAuthString",
+            "auth_string_salt",
+            "authStringSalt",
+            "auth_string_memory_cost_kib",
+            "authStringMemoryCostKib",
+            "auth_string_parallelism_factor",
+            "authStringParallelismFactor",
+            "auth_string_iters",
+            "authStringIters",
+            "password_encryption_salt",
+            "passwordEncryptionSalt",
+            "password_encryption_memory_cost_kib",
+            "passwordEncryptionMemoryCostKib",
+            "password_encryption_parallelism_factor",
+            "passwordEncryptionParallelismFactor",
+            "password_encryption_iters",
+            "passwordEncryptionIters",
+            "encrypted_encryption_key",
+            "encryptedEncryptionKey",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            UserEmail,
+            Otp,
+            NewAuthString,
+            AuthStringSalt,
+            AuthStringMemoryCostKib,
+            AuthStringParallelismFactor,
+            AuthStringIters,
+            PasswordEncryptionSalt,
+            PasswordEncryptionMemoryCostKib,
+            PasswordEncryptionParallelismFactor,
+            PasswordEncryptionIters,
+            EncryptedEncryptionKey,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "userEmail" | "user_email" => Ok(GeneratedField::UserEmail),
+                            "otp" => Ok(GeneratedField::Otp),
+                            "newAuthString" | "new_auth_string" => Ok(GeneratedField::NewAuthString),
+                            "authStringSalt" | "auth_string_salt" => Ok(GeneratedField::AuthStringSalt),
+                            "authStringMemoryCostKib" | "auth_string_memory_cost_kib" => Ok(GeneratedField::AuthStringMemoryCostKib),
+                            "authStringParallelismFactor" | "auth_string_parallelism_factor" => Ok(GeneratedField::AuthStringParallelismFactor),
+                            "authStringIters" | "auth_string_iters" => Ok(GeneratedField::AuthStringIters),
+                            "passwordEncryptionSalt" | "password_encryption_salt" => Ok(GeneratedField::PasswordEncryptionSalt),
+                            "passwordEncryptionMemoryCostKib" | "password_encryption_memory_cost_kib" => Ok(GeneratedField::PasswordEncryptionMemoryCostKib),
+                            "passwordEncryptionParallelismFactor" | "password_encryption_parallelism_factor" => Ok(GeneratedField::PasswordEncryptionParallelismFactor),
+                            "passwordEncryptionIters" | "password_encryption_iters" => Ok(GeneratedField::PasswordEncryptionIters),
+                            "encryptedEncryptionKey" | "encrypted_encryption_key" => Ok(GeneratedField::EncryptedEncryptionKey),
+                            _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = AuthStringAndEncryptedPasswordUpdate;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct entries.serverschema.AuthStringAndEncryptedPasswordUpdate")
+            }
+
+            fn visit_map<V>(self, mut map_: V) -> std::result::Result<AuthStringAndEncryptedPasswordUpdate, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut user_email__ = None;
+                let mut otp__ = None;
+                let mut new_auth_string__ = None;
+                let mut auth_string_salt__ = None;
+                let mut auth_string_memory_cost_kib__ = None;
+                let mut auth_string_parallelism_factor__ = None;
+                let mut auth_string_iters__ = None;
+                let mut password_encryption_salt__ = None;
+                let mut password_encryption_memory_cost_kib__ = None;
+                let mut password_encryption_parallelism_factor__ = None;
+                let mut password_encryption_iters__ = None;
+                let mut encrypted_encryption_key__ = None;
+                while let Some(k) = map_.next_key()? {
+                    match k {
+                        GeneratedField::UserEmail => {
+                            if user_email__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("userEmail"));
+                            }
+                            user_email__ = Some(map_.next_value()?);
+                        }
+                        GeneratedField::Otp => {
+                            if otp__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("otp"));
+                            }
+                            otp__ = Some(map_.next_value()?);
+                        }
+                        GeneratedField::NewAuthString => {
+                            if new_auth_string__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("newAuthString"));
+                            }
+                            new_auth_string__ = 
+                                Some(map_.next_value::<::pbjson::private::BytesDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::AuthStringSalt => {
+                            if auth_string_salt__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("authStringSalt"));
+                            }
+                            auth_string_salt__ = 
+                                Some(map_.next_value::<::pbjson::private::BytesDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::AuthStringMemoryCostKib => {
+                            if auth_string_memory_cost_kib__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("authStringMemoryCostKib"));
+                            }
+                            auth_string_memory_cost_kib__ = 
+                                Some(map_.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::AuthStringParallelismFactor => {
+                            if auth_string_parallelism_factor__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("authStringParallelismFactor"));
+                            }
+                            auth_string_parallelism_factor__ = 
+                                Some(map_.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::AuthStringIters => {
+                            if auth_string_iters__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("authStringIters"));
+                            }
+                            auth_string_iters__ = 
+                                Some(map_.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::PasswordEncryptionSalt => {
+                            if password_encryption_salt__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("passwordEncryptionSalt"));
+                            }
+                            password_encryption_salt__ = 
+                                Some(map_.next_value::<::pbjson::private::BytesDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::PasswordEncryptionMemoryCostKib => {
+                            if password_encryption_memory_cost_kib__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("passwordEncryptionMemoryCostKib"));
+                            }
+                            password_encryption_memory_cost_kib__ = 
+                                Some(map_.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::PasswordEncryptionParallelismFactor => {
+                            if password_encryption_parallelism_factor__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("passwordEncryptionParallelismFactor"));
+                            }
+                            password_encryption_parallelism_factor__ = 
+                                Some(map_.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::PasswordEncryptionIters => {
+                            if password_encryption_iters__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("passwordEncryptionIters"));
+                            }
+                            password_encryption_iters__ = 
+                                Some(map_.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::EncryptedEncryptionKey => {
+                            if encrypted_encryption_key__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("encryptedEncryptionKey"));
+                            }
+                            encrypted_encryption_key__ = 
+                                Some(map_.next_value::<::pbjson::private::BytesDeserialize<_>>()?.0)
+                            ;
+                        }
+                    }
+                }
+                Ok(AuthStringAndEncryptedPasswordUpdate {
+                    user_email: user_email__.ok_or_else(|| serde::de::Error::missing_field("userEmail"))?,
+                    otp: otp__.ok_or_else(|| serde::de::Error::missing_field("otp"))?,
+                    new_auth_string: new_auth_string__.ok_or_else(|| serde::de::Error::missing_field("newAuthString"))?,
+                    auth_string_salt: auth_string_salt__.ok_or_else(|| serde::de::Error::missing_field("authStringSalt"))?,
+                    auth_string_memory_cost_kib: auth_string_memory_cost_kib__.ok_or_else(|| serde::de::Error::missing_field("authStringMemoryCostKib"))?,
+                    auth_string_parallelism_factor: auth_string_parallelism_factor__.ok_or_else(|| serde::de::Error::missing_field("authStringParallelismFactor"))?,
+                    auth_string_iters: auth_string_iters__.ok_or_else(|| serde::de::Error::missing_field("authStringIters"))?,
+                    password_encryption_salt: password_encryption_salt__.ok_or_else(|| serde::de::Error::missing_field("passwordEncryptionSalt"))?,
+                    password_encryption_memory_cost_kib: password_encryption_memory_cost_kib__.ok_or_else(|| serde::de::Error::missing_field("passwordEncryptionMemoryCostKib"))?,
+                    password_encryption_parallelism_factor: password_encryption_parallelism_factor__.ok_or_else(|| serde::de::Error::missing_field("passwordEncryptionParallelismFactor"))?,
+                    password_encryption_iters: password_encryption_iters__.ok_or_else(|| serde::de::Error::missing_field("passwordEncryptionIters"))?,
+                    encrypted_encryption_key: encrypted_encryption_key__.ok_or_else(|| serde::de::Error::missing_field("encryptedEncryptionKey"))?,
+                })
+            }
+        }
+        deserializer.deserialize_struct("entries.serverschema.AuthStringAndEncryptedPasswordUpdate", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for BackupCode {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let len = 1;
+        let mut struct_ser = serializer.serialize_struct("entries.serverschema.BackupCode", len)?;
+        struct_ser.serialize_field("value", &self.value)?;
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for BackupCode {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "value",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            Value,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "value" => Ok(GeneratedField::Value),
+                            _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = BackupCode;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct entries.serverschema.BackupCode")
+            }
+
+            fn visit_map<V>(self, mut map_: V) -> std::result::Result<BackupCode, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut value__ = None;
+                while let Some(k) = map_.next_key()? {
+                    match k {
+                        GeneratedField::Value => {
+                            if value__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("value"));
+                            }
+                            value__ = Some(map_.next_value()?);
+                        }
+                    }
+                }
+                Ok(BackupCode {
+                    value: value__.ok_or_else(|| serde::de::Error::missing_field("value"))?,
+                })
+            }
+        }
+        deserializer.deserialize_struct("entries.serverschema.BackupCode", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for BackupCodeList {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut len = 0;
+        if !self.backup_codes.is_empty() {
+            len += 1;
+        }
+        let mut struct_ser = serializer.serialize_struct("entries.serverschema.BackupCodeList", len)?;
+        if !self.backup_codes.is_empty() {
+            struct_ser.serialize_field("backupCodes", &self.backup_codes)?;
+        }
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for BackupCodeList {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "backup_codes",
+            "backupCodes",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            BackupCodes,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "backupCodes" | "backup_codes" => Ok(GeneratedField::BackupCodes),
+                            _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = BackupCodeList;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct entries.serverschema.BackupCodeList")
+            }
+
+            fn visit_map<V>(self, mut map_: V) -> std::result::Result<BackupCodeList, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut backup_codes__ = None;
+                while let Some(k) = map_.next_key()? {
+                    match k {
+                        GeneratedField::BackupCodes => {
+                            if backup_codes__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("backupCodes"));
+                            }
+                            backup_codes__ = Some(map_.next_value()?);
+                        }
+                    }
+                }
+                Ok(BackupCodeList {
+                    backup_codes: backup_codes__.unwrap_or_default(),
+                })
+            }
+        }
+        deserializer.deserialize_struct("entries.serverschema.BackupCodeList", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for BackupCodesAndVerificationEmailSent {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut len = 2;
+        if !self.backup_codes.is_empty() {
+            len += 1;
+        }
+        let mut struct_ser = serializer.serialize_struct("entries.serverschema.BackupCodesAndVerificationEmailSent", len)?;
+        struct_ser.serialize_field("emailSent", &self.email_sent)?;
+        #[allow(clippy::needless_borrow)]
+        struct_ser.serialize_field("emailTokenLifetimeHours", ToString::to_string(&self.email_token_lifetime_hours).as_str())?;
+        if !self.backup_codes.is_empty() {
+            struct_ser.serialize_field("backupCodes", &self.backup_codes)?;
+        }
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for BackupCodesAndVerificationEmailSent {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "email_sent",
+            "emailSent",
+            "email_token_lifetime_hours",
+            "emailTokenLifetimeHours",
+            "backup_codes",
+            "backupCodes",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            EmailSent,
+            EmailTokenLifetimeHours,
+            BackupCodes,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "emailSent" | "email_sent" => Ok(GeneratedField::EmailSent),
+                            "emailTokenLifetimeHours" | "email_token_lifetime_hours" => Ok(GeneratedField::EmailTokenLifetimeHours),
+                            "backupCodes" | "backup_codes" => Ok(GeneratedField::BackupCodes),
+                            _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = BackupCodesAndVerificationEmailSent;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct entries.serverschema.BackupCodesAndVerificationEmailSent")
+            }
+
+            fn visit_map<V>(self, mut map_: V) -> std::result::Result<BackupCodesAndVerificationEmailSent, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut email_sent__ = None;
+                let mut email_token_lifetime_hours__ = None;
+                let mut backup_codes__ = None;
+                while let Some(k) = map_.next_key()? {
+                    match k {
+                        GeneratedField::EmailSent => {
+                            if email_sent__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("emailSent"));
+                            }
+                            email_sent__ = Some(map_.next_value()?);
+                        }
+                        GeneratedField::EmailTokenLifetimeHours => {
+                            if email_token_lifetime_hours__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("emailTokenLifetimeHours"));
+                            }
+                            email_token_lifetime_hours__ = 
+                                Some(map_.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::BackupCodes => {
+                            if backup_codes__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("backupCodes"));
+                            }
+                            backup_codes__ = Some(map_.next_value()?);
+                        }
+                    }
+                }
+                Ok(BackupCodesAndVerificationEmailSent {
+                    email_sent: email_sent__.ok_or_else(|| serde::de::Error::missing_field("emailSent"))?,
+                    email_token_lifetime_hours: email_token_lifetime_hours__.ok_or_else(|| serde::de::Error::missing_field("emailTokenLifetimeHours"))?,
+                    backup_codes: backup_codes__.unwrap_or_default(),
+                })
+            }
+        }
+        deserializer.deserialize_struct("entries.serverschema.BackupCodesAndVerificationEmailSent", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for Budget {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut len = 4;
+        if !self.categories.is_empty() {
+            len += 1;
+        }
+        if !self.entries.is_empty() {
+            len += 1;
+        }
+        let mut struct_ser = serializer.serialize_struct("entries.serverschema.Budget", len)?;
+        struct_ser.serialize_field("id", &self.id)?;
+        #[allow(clippy::needless_borrow)]
+        struct_ser.serialize_field("encryptedBlob", pbjson::private::base64::encode(&self.encrypted_blob).as_str())?;
+        #[allow(clippy::needless_borrow)]
+        struct_ser.serialize_field("versionNonce", ToString::to_string(&self.version_nonce).as_str())?;
+        struct_ser.serialize_field("modifiedTimestamp", &self.modified_timestamp)?;
+        if !self.categories.is_empty() {
+            struct_ser.serialize_field("categories", &self.categories)?;
+        }
+        if !self.entries.is_empty() {
+            struct_ser.serialize_field("entries", &self.entries)?;
+        }
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for Budget {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "id",
+            "encrypted_blob",
+            "encryptedBlob",
+            "version_nonce",
+            "versionNonce",
+            "modified_timestamp",
+            "modifiedTimestamp",
+            "categories",
+            "entries",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            Id,
+            EncryptedBlob,
+            VersionNonce,
+            ModifiedTimestamp,
+            Categories,
+            Entries,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "id" => Ok(GeneratedField::Id),
+                            "encryptedBlob" | "encrypted_blob" => Ok(GeneratedField::EncryptedBlob),
+                            "versionNonce" | "version_nonce" => Ok(GeneratedField::VersionNonce),
+                            "modifiedTimestamp" | "modified_timestamp" => Ok(GeneratedField::ModifiedTimestamp),
+                            "categories" => Ok(GeneratedField::Categories),
+                            "entries" => Ok(GeneratedField::Entries),
+                            _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = Budget;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct entries.serverschema.Budget")
+            }
+
+            fn visit_map<V>(self, mut map_: V) -> std::result::Result<Budget, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut id__ = None;
+                let mut encrypted_blob__ = None;
+                let mut version_nonce__ = None;
+                let mut modified_timestamp__ = None;
+                let mut categories__ = None;
+                let mut entries__ = None;
+                while let Some(k) = map_.next_key()? {
+                    match k {
+                        GeneratedField::Id => {
+                            if id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("id"));
+                            }
+                            id__ = map_.next_value()?;
+                        }
+                        GeneratedField::EncryptedBlob => {
+                            if encrypted_blob__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("encryptedBlob"));
+                            }
+                            encrypted_blob__ = 
+                                Some(map_.next_value::<::pbjson::private::BytesDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::VersionNonce => {
+                            if version_nonce__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("versionNonce"));
+                            }
+                            version_nonce__ = 
+                                Some(map_.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::ModifiedTimestamp => {
+                            if modified_timestamp__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("modifiedTimestamp"));
+                            }
+                            modified_timestamp__ = map_.next_value()?;
+                        }
+                        GeneratedField::Categories => {
+                            if categories__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("categories"));
+                            }
+                            categories__ = Some(map_.next_value()?);
+                        }
+                        GeneratedField::Entries => {
+                            if entries__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("entries"));
+                            }
+                            entries__ = Some(map_.next_value()?);
+                        }
+                    }
+                }
+                Ok(Budget {
+                    id: id__.ok_or_else(|| serde::de::Error::missing_field("id"))?,
+                    encrypted_blob: encrypted_blob__.ok_or_else(|| serde::de::Error::missing_field("encryptedBlob"))?,
+                    version_nonce: version_nonce__.ok_or_else(|| serde::de::Error::missing_field("versionNonce"))?,
+                    modified_timestamp: modified_timestamp__.ok_or_else(|| serde::de::Error::missing_field("modifiedTimestamp"))?,
+                    categories: categories__.unwrap_or_default(),
+                    entries: entries__.unwrap_or_default(),
+                })
+            }
+        }
+        deserializer.deserialize_struct("entries.serverschema.Budget", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for BudgetAccessTokenList {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut len = 0;
+        if !self.tokens.is_empty() {
+            len += 1;
+        }
+        let mut struct_ser = serializer.serialize_struct("entries.serverschema.BudgetAccessTokenList", len)?;
+        if !self.tokens.is_empty() {
+            struct_ser.serialize_field("tokens", &self.tokens)?;
+        }
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for BudgetAccessTokenList {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "tokens",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            Tokens,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "tokens" => Ok(GeneratedField::Tokens),
+                            _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = BudgetAccessTokenList;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct entries.serverschema.BudgetAccessTokenList")
+            }
+
+            fn visit_map<V>(self, mut map_: V) -> std::result::Result<BudgetAccessTokenList, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut tokens__ = None;
+                while let Some(k) = map_.next_key()? {
+                    match k {
+                        GeneratedField::Tokens => {
+                            if tokens__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("tokens"));
+                            }
+                            tokens__ = Some(map_.next_value()?);
+                        }
+                    }
+                }
+                Ok(BudgetAccessTokenList {
+                    tokens: tokens__.unwrap_or_default(),
+                })
+            }
+        }
+        deserializer.deserialize_struct("entries.serverschema.BudgetAccessTokenList", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for BudgetFrame {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut len = 3;
+        if !self.category_ids.is_empty() {
+            len += 1;
+        }
+        let mut struct_ser = serializer.serialize_struct("entries.serverschema.BudgetFrame", len)?;
+        struct_ser.serialize_field("accessKeyId", &self.access_key_id)?;
+        struct_ser.serialize_field("id", &self.id)?;
+        if !self.category_ids.is_empty() {
+            struct_ser.serialize_field("categoryIds", &self.category_ids)?;
+        }
+        struct_ser.serialize_field("modifiedTimestamp", &self.modified_timestamp)?;
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for BudgetFrame {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "access_key_id",
+            "accessKeyId",
+            "id",
+            "category_ids",
+            "categoryIds",
+            "modified_timestamp",
+            "modifiedTimestamp",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            AccessKeyId,
+            Id,
+            CategoryIds,
+            ModifiedTimestamp,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "accessKeyId" | "access_key_id" => Ok(GeneratedField::AccessKeyId),
+                            "id" => Ok(GeneratedField::Id),
+                            "categoryIds" | "category_ids" => Ok(GeneratedField::CategoryIds),
+                            "modifiedTimestamp" | "modified_timestamp" => Ok(GeneratedField::ModifiedTimestamp),
+                            _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = BudgetFrame;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct entries.serverschema.BudgetFrame")
+            }
+
+            fn visit_map<V>(self, mut map_: V) -> std::result::Result<BudgetFrame, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut access_key_id__ = None;
+                let mut id__ = None;
+                let mut category_ids__ = None;
+                let mut modified_timestamp__ = None;
+                while let Some(k) = map_.next_key()? {
+                    match k {
+                        GeneratedField::AccessKeyId => {
+                            if access_key_id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("accessKeyId"));
+                            }
+                            access_key_id__ = map_.next_value()?;
+                        }
+                        GeneratedField::Id => {
+                            if id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("id"));
+                            }
+                            id__ = map_.next_value()?;
+                        }
+                        GeneratedField::CategoryIds => {
+                            if category_ids__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("categoryIds"));
+                            }
+                            category_ids__ = Some(map_.next_value()?);
+                        }
+                        GeneratedField::ModifiedTimestamp => {
+                            if modified_timestamp__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("modifiedTimestamp"));
+                            }
+                            modified_timestamp__ = map_.next_value()?;
+                        }
+                    }
+                }
+                Ok(BudgetFrame {
+                    access_key_id: access_key_id__.ok_or_else(|| serde::de::Error::missing_field("accessKeyId"))?,
+                    id: id__.ok_or_else(|| serde::de::Error::missing_field("id"))?,
+                    category_ids: category_ids__.unwrap_or_default(),
+                    modified_timestamp: modified_timestamp__.ok_or_else(|| serde::de::Error::missing_field("modifiedTimestamp"))?,
+                })
+            }
+        }
+        deserializer.deserialize_struct("entries.serverschema.BudgetFrame", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for BudgetFrameCategory {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let len = 2;
+        let mut struct_ser = serializer.serialize_struct("entries.serverschema.BudgetFrameCategory", len)?;
+        struct_ser.serialize_field("tempId", &self.temp_id)?;
+        struct_ser.serialize_field("realId", &self.real_id)?;
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for BudgetFrameCategory {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "temp_id",
+            "tempId",
+            "real_id",
+            "realId",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            TempId,
+            RealId,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "tempId" | "temp_id" => Ok(GeneratedField::TempId),
+                            "realId" | "real_id" => Ok(GeneratedField::RealId),
+                            _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = BudgetFrameCategory;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct entries.serverschema.BudgetFrameCategory")
+            }
+
+            fn visit_map<V>(self, mut map_: V) -> std::result::Result<BudgetFrameCategory, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut temp_id__ = None;
+                let mut real_id__ = None;
+                while let Some(k) = map_.next_key()? {
+                    match k {
+                        GeneratedField::TempId => {
+                            if temp_id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("tempId"));
+                            }
+                            temp_id__ = 
+                                Some(map_.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::RealId => {
+                            if real_id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("realId"));
+                            }
+                            real_id__ = map_.next_value()?;
+                        }
+                    }
+                }
+                Ok(BudgetFrameCategory {
+                    temp_id: temp_id__.ok_or_else(|| serde::de::Error::missing_field("tempId"))?,
+                    real_id: real_id__.ok_or_else(|| serde::de::Error::missing_field("realId"))?,
+                })
+            }
+        }
+        deserializer.deserialize_struct("entries.serverschema.BudgetFrameCategory", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for BudgetIdAndEncryptionKey {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let len = 4;
+        let mut struct_ser = serializer.serialize_struct("entries.serverschema.BudgetIdAndEncryptionKey", len)?;
+        struct_ser.serialize_field("budgetId", &self.budget_id)?;
+        struct_ser.serialize_field("budgetAccessKeyId", &self.budget_access_key_id)?;
+        #[allow(clippy::needless_borrow)]
+        struct_ser.serialize_field("encryptionKeyEncrypted", pbjson::private::base64::encode(&self.encryption_key_encrypted).as_str())?;
+        struct_ser.serialize_field("readOnly", &self.read_only)?;
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for BudgetIdAndEncryptionKey {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "budget_id",
+            "budgetId",
+            "budget_access_key_id",
+            "budgetAccessKeyId",
+            "encryption_key_encrypted",
+            "encryptionKeyEncrypted",
+            "read_only",
+            "readOnly",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            BudgetId,
+            BudgetAccessKeyId,
+            EncryptionKeyEncrypted,
+            ReadOnly,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "budgetId" | "budget_id" => Ok(GeneratedField::BudgetId),
+                            "budgetAccessKeyId" | "budget_access_key_id" => Ok(GeneratedField::BudgetAccessKeyId),
+                            "encryptionKeyEncrypted" | "encryption_key_encrypted" => Ok(GeneratedField::EncryptionKeyEncrypted),
+                            "readOnly" | "read_only" => Ok(GeneratedField::ReadOnly),
+                            _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = BudgetIdAndEncryptionKey;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct entries.serverschema.BudgetIdAndEncryptionKey")
+            }
+
+            fn visit_map<V>(self, mut map_: V) -> std::result::Result<BudgetIdAndEncryptionKey, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut budget_id__ = None;
+                let mut budget_access_key_id__ = None;
+                let mut encryption_key_encrypted__ = None;
+                let mut read_only__ = None;
+                while let Some(k) = map_.next_key()? {
+                    match k {
+                        GeneratedField::BudgetId => {
+                            if budget_id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("budgetId"));
+                            }
+                            budget_id__ = map_.next_value()?;
+                        }
+                        GeneratedField::BudgetAccessKeyId => {
+                            if budget_access_key_id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("budgetAccessKeyId"));
+                            }
+                            budget_access_key_id__ = map_.next_value()?;
+                        }
+                        GeneratedField::EncryptionKeyEncrypted => {
+                            if encryption_key_encrypted__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("encryptionKeyEncrypted"));
+                            }
+                            encryption_key_encrypted__ = 
+                                Some(map_.next_value::<::pbjson::private::BytesDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::ReadOnly => {
+                            if read_only__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("readOnly"));
+                            }
+                            read_only__ = Some(map_.next_value()?);
+                        }
+                    }
+                }
+                Ok(BudgetIdAndEncryptionKey {
+                    budget_id: budget_id__.ok_or_else(|| serde::de::Error::missing_field("budgetId"))?,
+                    budget_access_key_id: budget_access_key_id__.ok_or_else(|| serde::de::Error::missing_field("budgetAccessKeyId"))?,
+                    encryption_key_encrypted: encryption_key_encrypted__.ok_or_else(|| serde::de::Error::missing_field("encryptionKeyEncrypted"))?,
+                    read_only: read_only__.ok_or_else(|| serde::de::Error::missing_field("readOnly"))?,
+                })
+            }
+        }
+        deserializer.deserialize_struct("entries.serverschema.BudgetIdAndEncryptionKey", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for BudgetList {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut len = 0;
+        if !self.budgets.is_empty() {
+            len += 1;
+        }
+        let mut struct_ser = serializer.serialize_struct("entries.serverschema.BudgetList", len)?;
+        if !self.budgets.is_empty() {
+            struct_ser.serialize_field("budgets", &self.budgets)?;
+        }
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for BudgetList {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "budgets",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            Budgets,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "budgets" => Ok(GeneratedField::Budgets),
+                            _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = BudgetList;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct entries.serverschema.BudgetList")
+            }
+
+            fn visit_map<V>(self, mut map_: V) -> std::result::Result<BudgetList, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut budgets__ = None;
+                while let Some(k) = map_.next_key()? {
+                    match k {
+                        GeneratedField::Budgets => {
+                            if budgets__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("budgets"));
+                            }
+                            budgets__ = Some(map_.next_value()?);
+                        }
+                    }
+                }
+                Ok(BudgetList {
+                    budgets: budgets__.unwrap_or_default(),
+                })
+            }
+        }
+        deserializer.deserialize_struct("entries.serverschema.BudgetList", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for BudgetShareInvite {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let len = 9;
+        let mut struct_ser = serializer.serialize_struct("entries.serverschema.BudgetShareInvite", len)?;
+        struct_ser.serialize_field("id", &self.id)?;
+        #[allow(clippy::needless_borrow)]
+        struct_ser.serialize_field("budgetAcceptKeyEncrypted", pbjson::private::base64::encode(&self.budget_accept_key_encrypted).as_str())?;
+        #[allow(clippy::needless_borrow)]
+        struct_ser.serialize_field("budgetAcceptKeyIdEncrypted", pbjson::private::base64::encode(&self.budget_accept_key_id_encrypted).as_str())?;
+        #[allow(clippy::needless_borrow)]
+        struct_ser.serialize_field("budgetInfoEncrypted", pbjson::private::base64::encode(&self.budget_info_encrypted).as_str())?;
+        #[allow(clippy::needless_borrow)]
+        struct_ser.serialize_field("senderInfoEncrypted", pbjson::private::base64::encode(&self.sender_info_encrypted).as_str())?;
+        #[allow(clippy::needless_borrow)]
+        struct_ser.serialize_field("budgetAcceptKeyInfoEncrypted", pbjson::private::base64::encode(&self.budget_accept_key_info_encrypted).as_str())?;
+        #[allow(clippy::needless_borrow)]
+        struct_ser.serialize_field("shareInfoSymmetricKeyEncrypted", pbjson::private::base64::encode(&self.share_info_symmetric_key_encrypted).as_str())?;
+        struct_ser.serialize_field("recipientPublicKeyIdUsedBySender", &self.recipient_public_key_id_used_by_sender)?;
+        struct_ser.serialize_field("recipientPublicKeyIdUsedByServer", &self.recipient_public_key_id_used_by_server)?;
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for BudgetShareInvite {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "id",
+            "budget_accept_key_encrypted",
+            "budgetAcceptKeyEncrypted",
+            "budget_accept_key_id_encrypted",
+            "budgetAcceptKeyIdEncrypted",
+            "budget_info_encrypted",
+            "budgetInfoEncrypted",
+            "sender_info_encrypted",
+            "senderInfoEncrypted",
+            "budget_accept_key_info_encrypted",
+            "budgetAcceptKeyInfoEncrypted",
+            "share_info_symmetric_key_encrypted",
+            "shareInfoSymmetricKeyEncrypted",
+            "recipient_public_key_id_used_by_sender",
+            "recipientPublicKeyIdUsedBySender",
+            "recipient_public_key_id_used_by_server",
+            "recipientPublicKeyIdUsedByServer",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            Id,
+            BudgetAcceptKeyEncrypted,
+            BudgetAcceptKeyIdEncrypted,
+            BudgetInfoEncrypted,
+            SenderInfoEncrypted,
+            BudgetAcceptKeyInfoEncrypted,
+            ShareInfoSymmetricKeyEncrypted,
+            RecipientPublicKeyIdUsedBySender,
+            RecipientPublicKeyIdUsedByServer,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "id" => Ok(GeneratedField::Id),
+                            "budgetAcceptKeyEncrypted" | "budget_accept_key_encrypted" => Ok(GeneratedField::BudgetAcceptKeyEncrypted),
+                            "budgetAcceptKeyIdEncrypted" | "budget_accept_key_id_encrypted" => Ok(GeneratedField::BudgetAcceptKeyIdEncrypted),
+                            "budgetInfoEncrypted" | "budget_info_encrypted" => Ok(GeneratedField::BudgetInfoEncrypted),
+                            "senderInfoEncrypted" | "sender_info_encrypted" => Ok(GeneratedField::SenderInfoEncrypted),
+                            "budgetAcceptKeyInfoEncrypted" | "budget_accept_key_info_encrypted" => Ok(GeneratedField::BudgetAcceptKeyInfoEncrypted),
+                            "shareInfoSymmetricKeyEncrypted" | "share_info_symmetric_key_encrypted" => Ok(GeneratedField::ShareInfoSymmetricKeyEncrypted),
+                            "recipientPublicKeyIdUsedBySender" | "recipient_public_key_id_used_by_sender" => Ok(GeneratedField::RecipientPublicKeyIdUsedBySender),
+                            "recipientPublicKeyIdUsedByServer" | "recipient_public_key_id_used_by_server" => Ok(GeneratedField::RecipientPublicKeyIdUsedByServer),
+                            _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = BudgetShareInvite;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct entries.serverschema.BudgetShareInvite")
+            }
+
+            fn visit_map<V>(self, mut map_: V) -> std::result::Result<BudgetShareInvite, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut id__ = None;
+                let mut budget_accept_key_encrypted__ = None;
+                let mut budget_accept_key_id_encrypted__ = None;
+                let mut budget_info_encrypted__ = None;
+                let mut sender_info_encrypted__ = None;
+                let mut budget_accept_key_info_encrypted__ = None;
+                let mut share_info_symmetric_key_encrypted__ = None;
+                let mut recipient_public_key_id_used_by_sender__ = None;
+                let mut recipient_public_key_id_used_by_server__ = None;
+                while let Some(k) = map_.next_key()? {
+                    match k {
+                        GeneratedField::Id => {
+                            if id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("id"));
+                            }
+                            id__ = map_.next_value()?;
+                        }
+                        GeneratedField::BudgetAcceptKeyEncrypted => {
+                            if budget_accept_key_encrypted__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("budgetAcceptKeyEncrypted"));
+                            }
+                            budget_accept_key_encrypted__ = 
+                                Some(map_.next_value::<::pbjson::private::BytesDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::BudgetAcceptKeyIdEncrypted => {
+                            if budget_accept_key_id_encrypted__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("budgetAcceptKeyIdEncrypted"));
+                            }
+                            budget_accept_key_id_encrypted__ = 
+                                Some(map_.next_value::<::pbjson::private::BytesDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::BudgetInfoEncrypted => {
+                            if budget_info_encrypted__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("budgetInfoEncrypted"));
+                            }
+                            budget_info_encrypted__ = 
+                                Some(map_.next_value::<::pbjson::private::BytesDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::SenderInfoEncrypted => {
+                            if sender_info_encrypted__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("senderInfoEncrypted"));
+                            }
+                            sender_info_encrypted__ = 
+                                Some(map_.next_value::<::pbjson::private::BytesDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::BudgetAcceptKeyInfoEncrypted => {
+                            if budget_accept_key_info_encrypted__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("budgetAcceptKeyInfoEncrypted"));
+                            }
+                            budget_accept_key_info_encrypted__ = 
+                                Some(map_.next_value::<::pbjson::private::BytesDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::ShareInfoSymmetricKeyEncrypted => {
+                            if share_info_symmetric_key_encrypted__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("shareInfoSymmetricKeyEncrypted"));
+                            }
+                            share_info_symmetric_key_encrypted__ = 
+                                Some(map_.next_value::<::pbjson::private::BytesDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::RecipientPublicKeyIdUsedBySender => {
+                            if recipient_public_key_id_used_by_sender__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("recipientPublicKeyIdUsedBySender"));
+                            }
+                            recipient_public_key_id_used_by_sender__ = map_.next_value()?;
+                        }
+                        GeneratedField::RecipientPublicKeyIdUsedByServer => {
+                            if recipient_public_key_id_used_by_server__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("recipientPublicKeyIdUsedByServer"));
+                            }
+                            recipient_public_key_id_used_by_server__ = map_.next_value()?;
+                        }
+                    }
+                }
+                Ok(BudgetShareInvite {
+                    id: id__.ok_or_else(|| serde::de::Error::missing_field("id"))?,
+                    budget_accept_key_encrypted: budget_accept_key_encrypted__.ok_or_else(|| serde::de::Error::missing_field("budgetAcceptKeyEncrypted"))?,
+                    budget_accept_key_id_encrypted: budget_accept_key_id_encrypted__.ok_or_else(|| serde::de::Error::missing_field("budgetAcceptKeyIdEncrypted"))?,
+                    budget_info_encrypted: budget_info_encrypted__.ok_or_else(|| serde::de::Error::missing_field("budgetInfoEncrypted"))?,
+                    sender_info_encrypted: sender_info_encrypted__.ok_or_else(|| serde::de::Error::missing_field("senderInfoEncrypted"))?,
+                    budget_accept_key_info_encrypted: budget_accept_key_info_encrypted__.ok_or_else(|| serde::de::Error::missing_field("budgetAcceptKeyInfoEncrypted"))?,
+                    share_info_symmetric_key_encrypted: share_info_symmetric_key_encrypted__.ok_or_else(|| serde::de::Error::missing_field("shareInfoSymmetricKeyEncrypted"))?,
+                    recipient_public_key_id_used_by_sender: recipient_public_key_id_used_by_sender__.ok_or_else(|| serde::de::Error::missing_field("recipientPublicKeyIdUsedBySender"))?,
+                    recipient_public_key_id_used_by_server: recipient_public_key_id_used_by_server__.ok_or_else(|| serde::de::Error::missing_field("recipientPublicKeyIdUsedByServer"))?,
+                })
+            }
+        }
+        deserializer.deserialize_struct("entries.serverschema.BudgetShareInvite", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for BudgetShareInviteList {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut len = 0;
+        if !self.invites.is_empty() {
+            len += 1;
+        }
+        let mut struct_ser = serializer.serialize_struct("entries.serverschema.BudgetShareInviteList", len)?;
+        if !self.invites.is_empty() {
+            struct_ser.serialize_field("invites", &self.invites)?;
+        }
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for BudgetShareInviteList {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "invites",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            Invites,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "invites" => Ok(GeneratedField::Invites),
+                            _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = BudgetShareInviteList;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct entries.serverschema.BudgetShareInviteList")
+            }
+
+            fn visit_map<V>(self, mut map_: V) -> std::result::Result<BudgetShareInviteList, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut invites__ = None;
+                while let Some(k) = map_.next_key()? {
+                    match k {
+                        GeneratedField::Invites => {
+                            if invites__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("invites"));
+                            }
+                            invites__ = Some(map_.next_value()?);
+                        }
+                    }
+                }
+                Ok(BudgetShareInviteList {
+                    invites: invites__.unwrap_or_default(),
+                })
+            }
+        }
+        deserializer.deserialize_struct("entries.serverschema.BudgetShareInviteList", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for Category {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let len = 5;
+        let mut struct_ser = serializer.serialize_struct("entries.serverschema.Category", len)?;
+        struct_ser.serialize_field("id", &self.id)?;
+        struct_ser.serialize_field("budgetId", &self.budget_id)?;
+        #[allow(clippy::needless_borrow)]
+        struct_ser.serialize_field("encryptedBlob", pbjson::private::base64::encode(&self.encrypted_blob).as_str())?;
+        #[allow(clippy::needless_borrow)]
+        struct_ser.serialize_field("versionNonce", ToString::to_string(&self.version_nonce).as_str())?;
+        struct_ser.serialize_field("modifiedTimestamp", &self.modified_timestamp)?;
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for Category {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "id",
+            "budget_id",
+            "budgetId",
+            "encrypted_blob",
+            "encryptedBlob",
+            "version_nonce",
+            "versionNonce",
+            "modified_timestamp",
+            "modifiedTimestamp",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            Id,
+            BudgetId,
+            EncryptedBlob,
+            VersionNonce,
+            ModifiedTimestamp,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "id" => Ok(GeneratedField::Id),
+                            "budgetId" | "budget_id" => Ok(GeneratedField::BudgetId),
+                            "encryptedBlob" | "encrypted_blob" => Ok(GeneratedField::EncryptedBlob),
+                            "versionNonce" | "version_nonce" => Ok(GeneratedField::VersionNonce),
+                            "modifiedTimestamp" | "modified_timestamp" => Ok(GeneratedField::ModifiedTimestamp),
+                            _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = Category;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct entries.serverschema.Category")
+            }
+
+            fn visit_map<V>(self, mut map_: V) -> std::result::Result<Category, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut id__ = None;
+                let mut budget_id__ = None;
+                let mut encrypted_blob__ = None;
+                let mut version_nonce__ = None;
+                let mut modified_timestamp__ = None;
+                while let Some(k) = map_.next_key()? {
+                    match k {
+                        GeneratedField::Id => {
+                            if id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("id"));
+                            }
+                            id__ = map_.next_value()?;
+                        }
+                        GeneratedField::BudgetId => {
+                            if budget_id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("budgetId"));
+                            }
+                            budget_id__ = map_.next_value()?;
+                        }
+                        GeneratedField::EncryptedBlob => {
+                            if encrypted_blob__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("encryptedBlob"));
+                            }
+                            encrypted_blob__ = 
+                                Some(map_.next_value::<::pbjson::private::BytesDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::VersionNonce => {
+                            if version_nonce__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("versionNonce"));
+                            }
+                            version_nonce__ = 
+                                Some(map_.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::ModifiedTimestamp => {
+                            if modified_timestamp__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("modifiedTimestamp"));
+                            }
+                            modified_timestamp__ = map_.next_value()?;
+                        }
+                    }
+                }
+                Ok(Category {
+                    id: id__.ok_or_else(|| serde::de::Error::missing_field("id"))?,
+                    budget_id: budget_id__.ok_or_else(|| serde::de::Error::missing_field("budgetId"))?,
+                    encrypted_blob: encrypted_blob__.ok_or_else(|| serde::de::Error::missing_field("encryptedBlob"))?,
+                    version_nonce: version_nonce__.ok_or_else(|| serde::de::Error::missing_field("versionNonce"))?,
+                    modified_timestamp: modified_timestamp__.ok_or_else(|| serde::de::Error::missing_field("modifiedTimestamp"))?,
+                })
+            }
+        }
+        deserializer.deserialize_struct("entries.serverschema.Category", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for CategoryId {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let len = 1;
+        let mut struct_ser = serializer.serialize_struct("entries.serverschema.CategoryId", len)?;
+        struct_ser.serialize_field("value", &self.value)?;
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for CategoryId {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "value",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            Value,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "value" => Ok(GeneratedField::Value),
+                            _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = CategoryId;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct entries.serverschema.CategoryId")
+            }
+
+            fn visit_map<V>(self, mut map_: V) -> std::result::Result<CategoryId, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut value__ = None;
+                while let Some(k) = map_.next_key()? {
+                    match k {
+                        GeneratedField::Value => {
+                            if value__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("value"));
+                            }
+                            value__ = map_.next_value()?;
+                        }
+                    }
+                }
+                Ok(CategoryId {
+                    value: value__.ok_or_else(|| serde::de::Error::missing_field("value"))?,
+                })
+            }
+        }
+        deserializer.deserialize_struct("entries.serverschema.CategoryId", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for CategoryUpdate {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let len = 4;
+        let mut struct_ser = serializer.serialize_struct("entries.serverschema.CategoryUpdate", len)?;
+        struct_ser.serialize_field("categoryId", &self.category_id)?;
+        #[allow(clippy::needless_borrow)]
+        struct_ser.serialize_field("encryptedBlob", pbjson::private::base64::encode(&self.encrypted_blob).as_str())?;
+        #[allow(clippy::needless_borrow)]
+        struct_ser.serialize_field("versionNonce", ToString::to_string(&self.version_nonce).as_str())?;
+        #[allow(clippy::needless_borrow)]
+        struct_ser.serialize_field("expectedPreviousVersionNonce", ToString::to_string(&self.expected_previous_version_nonce).as_str())?;
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for CategoryUpdate {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "category_id",
+            "categoryId",
+            "encrypted_blob",
+            "encryptedBlob",
+            "version_nonce",
+            "versionNonce",
+            "expected_previous_version_nonce",
+            "expectedPreviousVersionNonce",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            CategoryId,
+            EncryptedBlob,
+            VersionNonce,
+            ExpectedPreviousVersionNonce,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "categoryId" | "category_id" => Ok(GeneratedField::CategoryId),
+                            "encryptedBlob" | "encrypted_blob" => Ok(GeneratedField::EncryptedBlob),
+                            "versionNonce" | "version_nonce" => Ok(GeneratedField::VersionNonce),
+                            "expectedPreviousVersionNonce" | "expected_previous_version_nonce" => Ok(GeneratedField::ExpectedPreviousVersionNonce),
+                            _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = CategoryUpdate;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct entries.serverschema.CategoryUpdate")
+            }
+
+            fn visit_map<V>(self, mut map_: V) -> std::result::Result<CategoryUpdate, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut category_id__ = None;
+                let mut encrypted_blob__ = None;
+                let mut version_nonce__ = None;
+                let mut expected_previous_version_nonce__ = None;
+                while let Some(k) = map_.next_key()? {
+                    match k {
+                        GeneratedField::CategoryId => {
+                            if category_id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("categoryId"));
+                            }
+                            category_id__ = map_.next_value()?;
+                        }
+                        GeneratedField::EncryptedBlob => {
+                            if encrypted_blob__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("encryptedBlob"));
+                            }
+                            encrypted_blob__ = 
+                                Some(map_.next_value::<::pbjson::private::BytesDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::VersionNonce => {
+                            if version_nonce__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("versionNonce"));
+                            }
+                            version_nonce__ = 
+                                Some(map_.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::ExpectedPreviousVersionNonce => {
+                            if expected_previous_version_nonce__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("expectedPreviousVersionNonce"));
+                            }
+                            expected_previous_version_nonce__ = 
+                                Some(map_.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                    }
+                }
+                Ok(CategoryUpdate {
+                    category_id: category_id__.ok_or_else(|| serde::de::Error::missing_field("categoryId"))?,
+                    encrypted_blob: encrypted_blob__.ok_or_else(|| serde::de::Error::missing_field("encryptedBlob"))?,
+                    version_nonce: version_nonce__.ok_or_else(|| serde::de::Error::missing_field("versionNonce"))?,
+                    expected_previous_version_nonce: expected_previous_version_nonce__.ok_or_else(|| serde::de::Error::missing_field("expectedPreviousVersionNonce"))?,
+                })
+            }
+        }
+        deserializer.deserialize_struct("entries.serverschema.CategoryUpdate", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for CategoryWithTempId {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut len = 3;
+        if self.id.is_some() {
+            len += 1;
+        }
+        let mut struct_ser = serializer.serialize_struct("entries.serverschema.CategoryWithTempId", len)?;
+        struct_ser.serialize_field("tempId", &self.temp_id)?;
+        #[allow(clippy::needless_borrow)]
+        struct_ser.serialize_field("encryptedBlob", pbjson::private::base64::encode(&self.encrypted_blob).as_str())?;
+        #[allow(clippy::needless_borrow)]
+        struct_ser.serialize_field("versionNonce", ToString::to_string(&self.version_nonce).as_str())?;
+        if let Some(v) = self.id.as_ref() {
+            struct_ser.serialize_field("id", v)?;
+        }
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for CategoryWithTempId {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "temp_id",
+            "tempId",
+            "encrypted_blob",
+            "encryptedBlob",
+            "version_nonce",
+            "versionNonce",
+            "id",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            TempId,
+            EncryptedBlob,
+            VersionNonce,
+            Id,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "tempId" | "temp_id" => Ok(GeneratedField::TempId),
+                            "encryptedBlob" | "encrypted_blob" => Ok(GeneratedField::EncryptedBlob),
+                            "versionNonce" | "version_nonce" => Ok(GeneratedField::VersionNonce),
+                            "id" => Ok(GeneratedField::Id),
+                            _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = CategoryWithTempId;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct entries.serverschema.CategoryWithTempId")
+            }
+
+            fn visit_map<V>(self, mut map_: V) -> std::result::Result<CategoryWithTempId, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut temp_id__ = None;
+                let mut encrypted_blob__ = None;
+                let mut version_nonce__ = None;
+                let mut id__ = None;
+                while let Some(k) = map_.next_key()? {
+                    match k {
+                        GeneratedField::TempId => {
+                            if temp_id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("tempId"));
+                            }
+                            temp_id__ = 
+                                Some(map_.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::EncryptedBlob => {
+                            if encrypted_blob__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("encryptedBlob"));
+                            }
+                            encrypted_blob__ = 
+                                Some(map_.next_value::<::pbjson::private::BytesDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::VersionNonce => {
+                            if version_nonce__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("versionNonce"));
+                            }
+                            version_nonce__ = 
+                                Some(map_.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::Id => {
+                            if id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("id"));
+                            }
+                            id__ = map_.next_value()?;
+                        }
+                    }
+                }
+                Ok(CategoryWithTempId {
+                    temp_id: temp_id__.ok_or_else(|| serde::de::Error::missing_field("tempId"))?,
+                    encrypted_blob: encrypted_blob__.ok_or_else(|| serde::de::Error::missing_field("encryptedBlob"))?,
+                    version_nonce: version_nonce__.ok_or_else(|| serde::de::Error::missing_field("versionNonce"))?,
+                    id: id__,
+                })
+            }
+        }
+        deserializer.deserialize_struct("entries.serverschema.CategoryWithTempId", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for CredentialPair {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let len = 3;
+        let mut struct_ser = serializer.serialize_struct("entries.serverschema.CredentialPair", len)?;
+        struct_ser.serialize_field("email", &self.email)?;
+        #[allow(clippy::needless_borrow)]
+        struct_ser.serialize_field("authString", pbjson::private::base64::encode(&self.auth_string).as_str())?;
+        struct_ser.serialize_field("nonce", &self.nonce)?;
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for CredentialPair {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "email",
+            "auth_string",
+            "authString",
+            "nonce",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            Email,
+            AuthString,
+            Nonce,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "email" => Ok(GeneratedField::Email),
+                            "authString" | "auth_string" => Ok(GeneratedField::AuthString),
+                            "nonce" => Ok(GeneratedField::Nonce),
+                            _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = CredentialPair;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct entries.serverschema.CredentialPair")
+            }
+
+            fn visit_map<V>(self, mut map_: V) -> std::result::Result<CredentialPair, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut email__ = None;
+                let mut auth_string__ = None;
+                let mut nonce__ = None;
+                while let Some(k) = map_.next_key()? {
+                    match k {
+                        GeneratedField::Email => {
+                            if email__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("email"));
+                            }
+                            email__ = Some(map_.next_value()?);
+                        }
+                        GeneratedField::AuthString => {
+                            if auth_string__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("authString"));
+                            }
+                            auth_string__ = 
+                                Some(map_.next_value::<::pbjson::private::BytesDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::Nonce => {
+                            if nonce__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("nonce"));
+                            }
+                            nonce__ = 
+                                Some(map_.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                    }
+                }
+                Ok(CredentialPair {
+                    email: email__.ok_or_else(|| serde::de::Error::missing_field("email"))?,
+                    auth_string: auth_string__.ok_or_else(|| serde::de::Error::missing_field("authString"))?,
+                    nonce: nonce__.ok_or_else(|| serde::de::Error::missing_field("nonce"))?,
+                })
+            }
+        }
+        deserializer.deserialize_struct("entries.serverschema.CredentialPair", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for EncryptedBlob {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let len = 2;
+        let mut struct_ser = serializer.serialize_struct("entries.serverschema.EncryptedBlob", len)?;
+        #[allow(clippy::needless_borrow)]
+        struct_ser.serialize_field("encryptedBlob", pbjson::private::base64::encode(&self.encrypted_blob).as_str())?;
+        #[allow(clippy::needless_borrow)]
+        struct_ser.serialize_field("versionNonce", ToString::to_string(&self.version_nonce).as_str())?;
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for EncryptedBlob {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "encrypted_blob",
+            "encryptedBlob",
+            "version_nonce",
+            "versionNonce",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            EncryptedBlob,
+            VersionNonce,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "encryptedBlob" | "encrypted_blob" => Ok(GeneratedField::EncryptedBlob),
+                            "versionNonce" | "version_nonce" => Ok(GeneratedField::VersionNonce),
+                            _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = EncryptedBlob;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct entries.serverschema.EncryptedBlob")
+            }
+
+            fn visit_map<V>(self, mut map_: V) -> std::result::Result<EncryptedBlob, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut encrypted_blob__ = None;
+                let mut version_nonce__ = None;
+                while let Some(k) = map_.next_key()? {
+                    match k {
+                        GeneratedField::EncryptedBlob => {
+                            if encrypted_blob__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("encryptedBlob"));
+                            }
+                            encrypted_blob__ = 
+                                Some(map_.next_value::<::pbjson::private::BytesDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::VersionNonce => {
+                            if version_nonce__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("versionNonce"));
+                            }
+                            version_nonce__ = 
+                                Some(map_.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                    }
+                }
+                Ok(EncryptedBlob {
+                    encrypted_blob: encrypted_blob__.ok_or_else(|| serde::de::Error::missing_field("encryptedBlob"))?,
+                    version_nonce: version_nonce__.ok_or_else(|| serde::de::Error::missing_field("versionNonce"))?,
+                })
+            }
+        }
+        deserializer.deserialize_struct("entries.serverschema.EncryptedBlob", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for EncryptedBlobAndCategoryId {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut len = 2;
+        if self.category_id.is_some() {
+            len += 1;
+        }
+        if self.id.is_some() {
+            len += 1;
+        }
+        let mut struct_ser = serializer.serialize_struct("entries.serverschema.EncryptedBlobAndCategoryId", len)?;
+        #[allow(clippy::needless_borrow)]
+        struct_ser.serialize_field("encryptedBlob", pbjson::private::base64::encode(&self.encrypted_blob).as_str())?;
+        #[allow(clippy::needless_borrow)]
+        struct_ser.serialize_field("versionNonce", ToString::to_string(&self.version_nonce).as_str())?;
+        if let Some(v) = self.category_id.as_ref() {
+            struct_ser.serialize_field("categoryId", v)?;
+        }
+        if let Some(v) = self.id.as_ref() {
+            struct_ser.serialize_field("id", v)?;
+        }
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for EncryptedBlobAndCategoryId {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "encrypted_blob",
+            "encryptedBlob",
+            "version_nonce",
+            "versionNonce",
+            "category_id",
+            "categoryId",
+            "id",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            EncryptedBlob,
+            VersionNonce,
+            CategoryId,
+            Id,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "encryptedBlob" | "encrypted_blob" => Ok(GeneratedField::EncryptedBlob),
+                            "versionNonce" | "version_nonce" => Ok(GeneratedField::VersionNonce),
+                            "categoryId" | "category_id" => Ok(GeneratedField::CategoryId),
+                            "id" => Ok(GeneratedField::Id),
+                            _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = EncryptedBlobAndCategoryId;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct entries.serverschema.EncryptedBlobAndCategoryId")
+            }
+
+            fn visit_map<V>(self, mut map_: V) -> std::result::Result<EncryptedBlobAndCategoryId, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut encrypted_blob__ = None;
+                let mut version_nonce__ = None;
+                let mut category_id__ = None;
+                let mut id__ = None;
+                while let Some(k) = map_.next_key()? {
+                    match k {
+                        GeneratedField::EncryptedBlob => {
+                            if encrypted_blob__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("encryptedBlob"));
+                            }
+                            encrypted_blob__ = 
+                                Some(map_.next_value::<::pbjson::private::BytesDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::VersionNonce => {
+                            if version_nonce__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("versionNonce"));
+                            }
+                            version_nonce__ = 
+                                Some(map_.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::CategoryId => {
+                            if category_id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("categoryId"));
+                            }
+                            category_id__ = map_.next_value()?;
+                        }
+                        GeneratedField::Id => {
+                            if id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("id"));
+                            }
+                            id__ = map_.next_value()?;
+                        }
+                    }
+                }
+                Ok(EncryptedBlobAndCategoryId {
+                    encrypted_blob: encrypted_blob__.ok_or_else(|| serde::de::Error::missing_field("encryptedBlob"))?,
+                    version_nonce: version_nonce__.ok_or_else(|| serde::de::Error::missing_field("versionNonce"))?,
+                    category_id: category_id__,
+                    id: id__,
+                })
+            }
+        }
+        deserializer.deserialize_struct("entries.serverschema.EncryptedBlobAndCategoryId", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for EncryptedBlobUpdate {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let len = 3;
+        let mut struct_ser = serializer.serialize_struct("entries.serverschema.EncryptedBlobUpdate", len)?;
+        #[allow(clippy::needless_borrow)]
+        struct_ser.serialize_field("encryptedBlob", pbjson::private::base64::encode(&self.encrypted_blob).as_str())?;
+        #[allow(clippy::needless_borrow)]
+        struct_ser.serialize_field("versionNonce", ToString::to_string(&self.version_nonce).as_str())?;
+        #[allow(clippy::needless_borrow)]
+        struct_ser.serialize_field("expectedPreviousVersionNonce", ToString::to_string(&self.expected_previous_version_nonce).as_str())?;
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for EncryptedBlobUpdate {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "encrypted_blob",
+            "encryptedBlob",
+            "version_nonce",
+            "versionNonce",
+            "expected_previous_version_nonce",
+            "expectedPreviousVersionNonce",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            EncryptedBlob,
+            VersionNonce,
+            ExpectedPreviousVersionNonce,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "encryptedBlob" | "encrypted_blob" => Ok(GeneratedField::EncryptedBlob),
+                            "versionNonce" | "version_nonce" => Ok(GeneratedField::VersionNonce),
+                            "expectedPreviousVersionNonce" | "expected_previous_version_nonce" => Ok(GeneratedField::ExpectedPreviousVersionNonce),
+                            _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = EncryptedBlobUpdate;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct entries.serverschema.EncryptedBlobUpdate")
+            }
+
+            fn visit_map<V>(self, mut map_: V) -> std::result::Result<EncryptedBlobUpdate, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut encrypted_blob__ = None;
+                let mut version_nonce__ = None;
+                let mut expected_previous_version_nonce__ = None;
+                while let Some(k) = map_.next_key()? {
+                    match k {
+                        GeneratedField::EncryptedBlob => {
+                            if encrypted_blob__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("encryptedBlob"));
+                            }
+                            encrypted_blob__ = 
+                                Some(map_.next_value::<::pbjson::private::BytesDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::VersionNonce => {
+                            if version_nonce__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("versionNonce"));
+                            }
+                            version_nonce__ = 
+                                Some(map_.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::ExpectedPreviousVersionNonce => {
+                            if expected_previous_version_nonce__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("expectedPreviousVersionNonce"));
+                            }
+                            expected_previous_version_nonce__ = 
+                                Some(map_.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                    }
+                }
+                Ok(EncryptedBlobUpdate {
+                    encrypted_blob: encrypted_blob__.ok_or_else(|| serde::de::Error::missing_field("encryptedBlob"))?,
+                    version_nonce: version_nonce__.ok_or_else(|| serde::de::Error::missing_field("versionNonce"))?,
+                    expected_previous_version_nonce: expected_previous_version_nonce__.ok_or_else(|| serde::de::Error::missing_field("expectedPreviousVersionNonce"))?,
+                })
+            }
+        }
+        deserializer.deserialize_struct("entries.serverschema.EncryptedBlobUpdate", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for Entry {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut len = 5;
+        if self.category_id.is_some() {
+            len += 1;
+        }
+        let mut struct_ser = serializer.serialize_struct("entries.serverschema.Entry", len)?;
+        struct_ser.serialize_field("id", &self.id)?;
+        struct_ser.serialize_field("budgetId", &self.budget_id)?;
+        if let Some(v) = self.category_id.as_ref() {
+            struct_ser.serialize_field("categoryId", v)?;
+        }
+        #[allow(clippy::needless_borrow)]
+        struct_ser.serialize_field("encryptedBlob", pbjson::private::base64::encode(&self.encrypted_blob).as_str())?;
+        #[allow(clippy::needless_borrow)]
+        struct_ser.serialize_field("versionNonce", ToString::to_string(&self.version_nonce).as_str())?;
+        struct_ser.serialize_field("modifiedTimestamp", &self.modified_timestamp)?;
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for Entry {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "id",
+            "budget_id",
+            "budgetId",
+            "category_id",
+            "categoryId",
+            "encrypted_blob",
+            "encryptedBlob",
+            "version_nonce",
+            "versionNonce",
+            "modified_timestamp",
+            "modifiedTimestamp",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            Id,
+            BudgetId,
+            CategoryId,
+            EncryptedBlob,
+            VersionNonce,
+            ModifiedTimestamp,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "id" => Ok(GeneratedField::Id),
+                            "budgetId" | "budget_id" => Ok(GeneratedField::BudgetId),
+                            "categoryId" | "category_id" => Ok(GeneratedField::CategoryId),
+                            "encryptedBlob" | "encrypted_blob" => Ok(GeneratedField::EncryptedBlob),
+                            "versionNonce" | "version_nonce" => Ok(GeneratedField::VersionNonce),
+                            "modifiedTimestamp" | "modified_timestamp" => Ok(GeneratedField::ModifiedTimestamp),
+                            _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = Entry;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct entries.serverschema.Entry")
+            }
+
+            fn visit_map<V>(self, mut map_: V) -> std::result::Result<Entry, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut id__ = None;
+                let mut budget_id__ = None;
+                let mut category_id__ = None;
+                let mut encrypted_blob__ = None;
+                let mut version_nonce__ = None;
+                let mut modified_timestamp__ = None;
+                while let Some(k) = map_.next_key()? {
+                    match k {
+                        GeneratedField::Id => {
+                            if id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("id"));
+                            }
+                            id__ = map_.next_value()?;
+                        }
+                        GeneratedField::BudgetId => {
+                            if budget_id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("budgetId"));
+                            }
+                            budget_id__ = map_.next_value()?;
+                        }
+                        GeneratedField::CategoryId => {
+                            if category_id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("categoryId"));
+                            }
+                            category_id__ = map_.next_value()?;
+                        }
+                        GeneratedField::EncryptedBlob => {
+                            if encrypted_blob__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("encryptedBlob"));
+                            }
+                            encrypted_blob__ = 
+                                Some(map_.next_value::<::pbjson::private::BytesDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::VersionNonce => {
+                            if version_nonce__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("versionNonce"));
+                            }
+                            version_nonce__ = 
+                                Some(map_.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::ModifiedTimestamp => {
+                            if modified_timestamp__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("modifiedTimestamp"));
+                            }
+                            modified_timestamp__ = map_.next_value()?;
+                        }
+                    }
+                }
+                Ok(Entry {
+                    id: id__.ok_or_else(|| serde::de::Error::missing_field("id"))?,
+                    budget_id: budget_id__.ok_or_else(|| serde::de::Error::missing_field("budgetId"))?,
+                    category_id: category_id__,
+                    encrypted_blob: encrypted_blob__.ok_or_else(|| serde::de::Error::missing_field("encryptedBlob"))?,
+                    version_nonce: version_nonce__.ok_or_else(|| serde::de::Error::missing_field("versionNonce"))?,
+                    modified_timestamp: modified_timestamp__.ok_or_else(|| serde::de::Error::missing_field("modifiedTimestamp"))?,
+                })
+            }
+        }
+        deserializer.deserialize_struct("entries.serverschema.Entry", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for EntryAndCategory {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut len = 4;
+        if self.entry_id.is_some() {
+            len += 1;
+        }
+        if self.category_id.is_some() {
+            len += 1;
+        }
+        let mut struct_ser = serializer.serialize_struct("entries.serverschema.EntryAndCategory", len)?;
+        #[allow(clippy::needless_borrow)]
+        struct_ser.serialize_field("entryEncryptedBlob", pbjson::private::base64::encode(&self.entry_encrypted_blob).as_str())?;
+        #[allow(clippy::needless_borrow)]
+        struct_ser.serialize_field("entryVersionNonce", ToString::to_string(&self.entry_version_nonce).as_str())?;
+        #[allow(clippy::needless_borrow)]
+        struct_ser.serialize_field("categoryEncryptedBlob", pbjson::private::base64::encode(&self.category_encrypted_blob).as_str())?;
+        #[allow(clippy::needless_borrow)]
+        struct_ser.serialize_field("categoryVersionNonce", ToString::to_string(&self.category_version_nonce).as_str())?;
+        if let Some(v) = self.entry_id.as_ref() {
+            struct_ser.serialize_field("entryId", v)?;
+        }
+        if let Some(v) = self.category_id.as_ref() {
+            struct_ser.serialize_field("categoryId", v)?;
+        }
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for EntryAndCategory {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "entry_encrypted_blob",
+            "entryEncryptedBlob",
+            "entry_version_nonce",
+            "entryVersionNonce",
+            "category_encrypted_blob",
+            "categoryEncryptedBlob",
+            "category_version_nonce",
+            "categoryVersionNonce",
+            "entry_id",
+            "entryId",
+            "category_id",
+            "categoryId",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            EntryEncryptedBlob,
+            EntryVersionNonce,
+            CategoryEncryptedBlob,
+            CategoryVersionNonce,
+            EntryId,
+            CategoryId,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "entryEncryptedBlob" | "entry_encrypted_blob" => Ok(GeneratedField::EntryEncryptedBlob),
+                            "entryVersionNonce" | "entry_version_nonce" => Ok(GeneratedField::EntryVersionNonce),
+                            "categoryEncryptedBlob" | "category_encrypted_blob" => Ok(GeneratedField::CategoryEncryptedBlob),
+                            "categoryVersionNonce" | "category_version_nonce" => Ok(GeneratedField::CategoryVersionNonce),
+                            "entryId" | "entry_id" => Ok(GeneratedField::EntryId),
+                            "categoryId" | "category_id" => Ok(GeneratedField::CategoryId),
+                            _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = EntryAndCategory;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct entries.serverschema.EntryAndCategory")
+            }
+
+            fn visit_map<V>(self, mut map_: V) -> std::result::Result<EntryAndCategory, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut entry_encrypted_blob__ = None;
+                let mut entry_version_nonce__ = None;
+                let mut category_encrypted_blob__ = None;
+                let mut category_version_nonce__ = None;
+                let mut entry_id__ = None;
+                let mut category_id__ = None;
+                while let Some(k) = map_.next_key()? {
+                    match k {
+                        GeneratedField::EntryEncryptedBlob => {
+                            if entry_encrypted_blob__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("entryEncryptedBlob"));
+                            }
+                            entry_encrypted_blob__ = 
+                                Some(map_.next_value::<::pbjson::private::BytesDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::EntryVersionNonce => {
+                            if entry_version_nonce__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("entryVersionNonce"));
+                            }
+                            entry_version_nonce__ = 
+                                Some(map_.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::CategoryEncryptedBlob => {
+                            if category_encrypted_blob__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("categoryEncryptedBlob"));
+                            }
+                            category_encrypted_blob__ = 
+                                Some(map_.next_value::<::pbjson::private::BytesDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::CategoryVersionNonce => {
+                            if category_version_nonce__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("categoryVersionNonce"));
+                            }
+                            category_version_nonce__ = 
+                                Some(map_.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::EntryId => {
+                            if entry_id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("entryId"));
+                            }
+                            entry_id__ = map_.next_value()?;
+                        }
+                        GeneratedField::CategoryId => {
+                            if category_id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("categoryId"));
+                            }
+                            category_id__ = map_.next_value()?;
+                        }
+                    }
+                }
+                Ok(EntryAndCategory {
+                    entry_encrypted_blob: entry_encrypted_blob__.ok_or_else(|| serde::de::Error::missing_field("entryEncryptedBlob"))?,
+                    entry_version_nonce: entry_version_nonce__.ok_or_else(|| serde::de::Error::missing_field("entryVersionNonce"))?,
+                    category_encrypted_blob: category_encrypted_blob__.ok_or_else(|| serde::de::Error::missing_field("categoryEncryptedBlob"))?,
+                    category_version_nonce: category_version_nonce__.ok_or_else(|| serde::de::Error::missing_field("categoryVersionNonce"))?,
+                    entry_id: entry_id__,
+                    category_id: category_id__,
+                })
+            }
+        }
+        deserializer.deserialize_struct("entries.serverschema.EntryAndCategory", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for EntryId {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let len = 1;
+        let mut struct_ser = serializer.serialize_struct("entries.serverschema.EntryId", len)?;
+        struct_ser.serialize_field("value", &self.value)?;
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for EntryId {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "value",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            Value,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "value" => Ok(GeneratedField::Value),
+                            _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = EntryId;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct entries.serverschema.EntryId")
+            }
+
+            fn visit_map<V>(self, mut map_: V) -> std::result::Result<EntryId, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut value__ = None;
+                while let Some(k) = map_.next_key()? {
+                    match k {
+                        GeneratedField::Value => {
+                            if value__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("value"));
+                            }
+                            value__ = map_.next_value()?;
+                        }
+                    }
+                }
+                Ok(EntryId {
+                    value: value__.ok_or_else(|| serde::de::Error::missing_field("value"))?,
+                })
+            }
+        }
+        deserializer.deserialize_struct("entries.serverschema.EntryId", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for EntryIdAndCategoryId {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let len = 2;
+        let mut struct_ser = serializer.serialize_struct("entries.serverschema.EntryIdAndCategoryId", len)?;
+        struct_ser.serialize_field("entryId", &self.entry_id)?;
+        struct_ser.serialize_field("categoryId", &self.category_id)?;
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for EntryIdAndCategoryId {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "entry_id",
+            "entryId",
+            "category_id",
+            "categoryId",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            EntryId,
+            CategoryId,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "entryId" | "entry_id" => Ok(GeneratedField::EntryId),
+                            "categoryId" | "category_id" => Ok(GeneratedField::CategoryId),
+                            _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = EntryIdAndCategoryId;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct entries.serverschema.EntryIdAndCategoryId")
+            }
+
+            fn visit_map<V>(self, mut map_: V) -> std::result::Result<EntryIdAndCategoryId, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut entry_id__ = None;
+                let mut category_id__ = None;
+                while let Some(k) = map_.next_key()? {
+                    match k {
+                        GeneratedField::EntryId => {
+                            if entry_id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("entryId"));
+                            }
+                            entry_id__ = map_.next_value()?;
+                        }
+                        GeneratedField::CategoryId => {
+                            if category_id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("categoryId"));
+                            }
+                            category_id__ = map_.next_value()?;
+                        }
+                    }
+                }
+                Ok(EntryIdAndCategoryId {
+                    entry_id: entry_id__.ok_or_else(|| serde::de::Error::missing_field("entryId"))?,
+                    category_id: category_id__.ok_or_else(|| serde::de::Error::missing_field("categoryId"))?,
+                })
+            }
+        }
+        deserializer.deserialize_struct("entries.serverschema.EntryIdAndCategoryId", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for EntryUpdate {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut len = 4;
+        if self.category_id.is_some() {
+            len += 1;
+        }
+        let mut struct_ser = serializer.serialize_struct("entries.serverschema.EntryUpdate", len)?;
+        struct_ser.serialize_field("entryId", &self.entry_id)?;
+        #[allow(clippy::needless_borrow)]
+        struct_ser.serialize_field("encryptedBlob", pbjson::private::base64::encode(&self.encrypted_blob).as_str())?;
+        #[allow(clippy::needless_borrow)]
+        struct_ser.serialize_field("versionNonce", ToString::to_string(&self.version_nonce).as_str())?;
+        #[allow(clippy::needless_borrow)]
+        struct_ser.serialize_field("expectedPreviousVersionNonce", ToString::to_string(&self.expected_previous_version_nonce).as_str())?;
+        if let Some(v) = self.category_id.as_ref() {
+            struct_ser.serialize_field("categoryId", v)?;
+        }
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for EntryUpdate {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "entry_id",
+            "entryId",
+            "encrypted_blob",
+            "encryptedBlob",
+            "version_nonce",
+            "versionNonce",
+            "expected_previous_version_nonce",
+            "expectedPreviousVersionNonce",
+            "category_id",
+            "categoryId",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            EntryId,
+            EncryptedBlob,
+            VersionNonce,
+            ExpectedPreviousVersionNonce,
+            CategoryId,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "entryId" | "entry_id" => Ok(GeneratedField::EntryId),
+                            "encryptedBlob" | "encrypted_blob" => Ok(GeneratedField::EncryptedBlob),
+                            "versionNonce" | "version_nonce" => Ok(GeneratedField::VersionNonce),
+                            "expectedPreviousVersionNonce" | "expected_previous_version_nonce" => Ok(GeneratedField::ExpectedPreviousVersionNonce),
+                            "categoryId" | "category_id" => Ok(GeneratedField::CategoryId),
+                            _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = EntryUpdate;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct entries.serverschema.EntryUpdate")
+            }
+
+            fn visit_map<V>(self, mut map_: V) -> std::result::Result<EntryUpdate, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut entry_id__ = None;
+                let mut encrypted_blob__ = None;
+                let mut version_nonce__ = None;
+                let mut expected_previous_version_nonce__ = None;
+                let mut category_id__ = None;
+                while let Some(k) = map_.next_key()? {
+                    match k {
+                        GeneratedField::EntryId => {
+                            if entry_id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("entryId"));
+                            }
+                            entry_id__ = map_.next_value()?;
+                        }
+                        GeneratedField::EncryptedBlob => {
+                            if encrypted_blob__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("encryptedBlob"));
+                            }
+                            encrypted_blob__ = 
+                                Some(map_.next_value::<::pbjson::private::BytesDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::VersionNonce => {
+                            if version_nonce__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("versionNonce"));
+                            }
+                            version_nonce__ = 
+                                Some(map_.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::ExpectedPreviousVersionNonce => {
+                            if expected_previous_version_nonce__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("expectedPreviousVersionNonce"));
+                            }
+                            expected_previous_version_nonce__ = 
+                                Some(map_.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::CategoryId => {
+                            if category_id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("categoryId"));
+                            }
+                            category_id__ = map_.next_value()?;
+                        }
+                    }
+                }
+                Ok(EntryUpdate {
+                    entry_id: entry_id__.ok_or_else(|| serde::de::Error::missing_field("entryId"))?,
+                    encrypted_blob: encrypted_blob__.ok_or_else(|| serde::de::Error::missing_field("encryptedBlob"))?,
+                    version_nonce: version_nonce__.ok_or_else(|| serde::de::Error::missing_field("versionNonce"))?,
+                    expected_previous_version_nonce: expected_previous_version_nonce__.ok_or_else(|| serde::de::Error::missing_field("expectedPreviousVersionNonce"))?,
+                    category_id: category_id__,
+                })
+            }
+        }
+        deserializer.deserialize_struct("entries.serverschema.EntryUpdate", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for ErrorType {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let variant = match self {
+            Self::IncorrectlyFormed => "INCORRECTLY_FORMED",
+            Self::InvalidMessage => "INVALID_MESSAGE",
+            Self::OutOfDate => "OUT_OF_DATE",
+            Self::InvalidState => "INVALID_STATE",
+            Self::MissingHeader => "MISSING_HEADER",
+            Self::ConflictWithExisting => "CONFLICT_WITH_EXISTING",
+            Self::IncorrectCredential => "INCORRECT_CREDENTIAL",
+            Self::TokenExpired => "TOKEN_EXPIRED",
+            Self::TokenMissing => "TOKEN_MISSING",
+            Self::WrongTokenType => "WRONG_TOKEN_TYPE",
+            Self::UserDisallowed => "USER_DISALLOWED",
+            Self::PendingAction => "PENDING_ACTION",
+            Self::IncorrectNonce => "INCORRECT_NONCE",
+            Self::TooManyAttempts => "TOO_MANY_ATTEMPTS",
+            Self::ReadOnlyAccess => "READ_ONLY_ACCESS",
+            Self::UserDoesNotExist => "USER_DOES_NOT_EXIST",
+            Self::KeyDoesNotExist => "KEY_DOES_NOT_EXIST",
+            Self::BudgetDoesNotExist => "BUDGET_DOES_NOT_EXIST",
+            Self::EntryDoesNotExist => "ENTRY_DOES_NOT_EXIST",
+            Self::CategoryDoesNotExist => "CATEGORY_DOES_NOT_EXIST",
+            Self::InvitationDoesNotExist => "INVITATION_DOES_NOT_EXIST",
+            Self::ForeignKeyDoesNotExist => "FOREIGN_KEY_DOES_NOT_EXIST",
+            Self::InputTooLarge => "INPUT_TOO_LARGE",
+            Self::TooManyRequested => "TOO_MANY_REQUESTED",
+            Self::ClientUpdateRequired => "CLIENT_UPDATE_REQUIRED",
+            Self::InternalError => "INTERNAL_ERROR",
+            Self::ActixWebPrehandler => "ACTIX_WEB_PREHANDLER",
+        };
+        serializer.serialize_str(variant)
+    }
+}
+impl<'de> serde::Deserialize<'de> for ErrorType {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "INCORRECTLY_FORMED",
+            "INVALID_MESSAGE",
+            "OUT_OF_DATE",
+            "INVALID_STATE",
+            "MISSING_HEADER",
+            "CONFLICT_WITH_EXISTING",
+            "INCORRECT_CREDENTIAL",
+            "TOKEN_EXPIRED",
+            "TOKEN_MISSING",
+            "WRONG_TOKEN_TYPE",
+            "USER_DISALLOWED",
+            "PENDING_ACTION",
+            "INCORRECT_NONCE",
+            "TOO_MANY_ATTEMPTS",
+            "READ_ONLY_ACCESS",
+            "USER_DOES_NOT_EXIST",
+            "KEY_DOES_NOT_EXIST",
+            "BUDGET_DOES_NOT_EXIST",
+            "ENTRY_DOES_NOT_EXIST",
+            "CATEGORY_DOES_NOT_EXIST",
+            "INVITATION_DOES_NOT_EXIST",
+            "FOREIGN_KEY_DOES_NOT_EXIST",
+            "INPUT_TOO_LARGE",
+            "TOO_MANY_REQUESTED",
+            "CLIENT_UPDATE_REQUIRED",
+            "INTERNAL_ERROR",
+            "ACTIX_WEB_PREHANDLER",
+        ];
+
+        struct GeneratedVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = ErrorType;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(formatter, "expected one of: {:?}", &FIELDS)
+            }
+
+            fn visit_i64<E>(self, v: i64) -> std::result::Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                i32::try_from(v)
+                    .ok()
+                    .and_then(|x| x.try_into().ok())
+                    .ok_or_else(|| {
+                        serde::de::Error::invalid_value(serde::de::Unexpected::Signed(v), &self)
+                    })
+            }
+
+            fn visit_u64<E>(self, v: u64) -> std::result::Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                i32::try_from(v)
+                    .ok()
+                    .and_then(|x| x.try_into().ok())
+                    .ok_or_else(|| {
+                        serde::de::Error::invalid_value(serde::de::Unexpected::Unsigned(v), &self)
+                    })
+            }
+
+            fn visit_str<E>(self, value: &str) -> std::result::Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                match value {
+                    "INCORRECTLY_FORMED" => Ok(ErrorType::IncorrectlyFormed),
+                    "INVALID_MESSAGE" => Ok(ErrorType::InvalidMessage),
+                    "OUT_OF_DATE" => Ok(ErrorType::OutOfDate),
+                    "INVALID_STATE" => Ok(ErrorType::InvalidState),
+                    "MISSING_HEADER" => Ok(ErrorType::MissingHeader),
+                    "CONFLICT_WITH_EXISTING" => Ok(ErrorType::ConflictWithExisting),
+                    "INCORRECT_CREDENTIAL" => Ok(ErrorType::IncorrectCredential),
+                    "TOKEN_EXPIRED" => Ok(ErrorType::TokenExpired),
+                    "TOKEN_MISSING" => Ok(ErrorType::TokenMissing),
+                    "WRONG_TOKEN_TYPE" => Ok(ErrorType::WrongTokenType),
+                    "USER_DISALLOWED" => Ok(ErrorType::UserDisallowed),
+                    "PENDING_ACTION" => Ok(ErrorType::PendingAction),
+                    "INCORRECT_NONCE" => Ok(ErrorType::IncorrectNonce),
+                    "TOO_MANY_ATTEMPTS" => Ok(ErrorType::TooManyAttempts),
+                    "READ_ONLY_ACCESS" => Ok(ErrorType::ReadOnlyAccess),
+                    "USER_DOES_NOT_EXIST" => Ok(ErrorType::UserDoesNotExist),
+                    "KEY_DOES_NOT_EXIST" => Ok(ErrorType::KeyDoesNotExist),
+                    "BUDGET_DOES_NOT_EXIST" => Ok(ErrorType::BudgetDoesNotExist),
+                    "ENTRY_DOES_NOT_EXIST" => Ok(ErrorType::EntryDoesNotExist),
+                    "CATEGORY_DOES_NOT_EXIST" => Ok(ErrorType::CategoryDoesNotExist),
+                    "INVITATION_DOES_NOT_EXIST" => Ok(ErrorType::InvitationDoesNotExist),
+                    "FOREIGN_KEY_DOES_NOT_EXIST" => Ok(ErrorType::ForeignKeyDoesNotExist),
+                    "INPUT_TOO_LARGE" => Ok(ErrorType::InputTooLarge),
+                    "TOO_MANY_REQUESTED" => Ok(ErrorType::TooManyRequested),
+                    "CLIENT_UPDATE_REQUIRED" => Ok(ErrorType::ClientUpdateRequired),
+                    "INTERNAL_ERROR" => Ok(ErrorType::InternalError),
+                    "ACTIX_WEB_PREHANDLER" => Ok(ErrorType::ActixWebPrehandler),
+                    _ => Err(serde::de::Error::unknown_variant(value, FIELDS)),
+                }
+            }
+        }
+        deserializer.deserialize_any(GeneratedVisitor)
+    }
+}
+impl serde::Serialize for InvitationId {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let len = 1;
+        let mut struct_ser = serializer.serialize_struct("entries.serverschema.InvitationId", len)?;
+        struct_ser.serialize_field("value", &self.value)?;
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for InvitationId {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "value",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            Value,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "value" => Ok(GeneratedField::Value),
+                            _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = InvitationId;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct entries.serverschema.InvitationId")
+            }
+
+            fn visit_map<V>(self, mut map_: V) -> std::result::Result<InvitationId, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut value__ = None;
+                while let Some(k) = map_.next_key()? {
+                    match k {
+                        GeneratedField::Value => {
+                            if value__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("value"));
+                            }
+                            value__ = map_.next_value()?;
+                        }
+                    }
+                }
+                Ok(InvitationId {
+                    value: value__.ok_or_else(|| serde::de::Error::missing_field("value"))?,
+                })
+            }
+        }
+        deserializer.deserialize_struct("entries.serverschema.InvitationId", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for IsUserListedForDeletion {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let len = 1;
+        let mut struct_ser = serializer.serialize_struct("entries.serverschema.IsUserListedForDeletion", len)?;
+        struct_ser.serialize_field("value", &self.value)?;
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for IsUserListedForDeletion {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "value",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            Value,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "value" => Ok(GeneratedField::Value),
+                            _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = IsUserListedForDeletion;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct entries.serverschema.IsUserListedForDeletion")
+            }
+
+            fn visit_map<V>(self, mut map_: V) -> std::result::Result<IsUserListedForDeletion, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut value__ = None;
+                while let Some(k) = map_.next_key()? {
+                    match k {
+                        GeneratedField::Value => {
+                            if value__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("value"));
+                            }
+                            value__ = Some(map_.next_value()?);
+                        }
+                    }
+                }
+                Ok(IsUserListedForDeletion {
+                    value: value__.ok_or_else(|| serde::de::Error::missing_field("value"))?,
+                })
+            }
+        }
+        deserializer.deserialize_struct("entries.serverschema.IsUserListedForDeletion", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for NewBudget {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut len = 3;
+        if !self.categories.is_empty() {
+            len += 1;
+        }
+        let mut struct_ser = serializer.serialize_struct("entries.serverschema.NewBudget", len)?;
+        #[allow(clippy::needless_borrow)]
+        struct_ser.serialize_field("encryptedBlob", pbjson::private::base64::encode(&self.encrypted_blob).as_str())?;
+        #[allow(clippy::needless_borrow)]
+        struct_ser.serialize_field("versionNonce", ToString::to_string(&self.version_nonce).as_str())?;
+        if !self.categories.is_empty() {
+            struct_ser.serialize_field("categories", &self.categories)?;
+        }
+        #[allow(clippy::needless_borrow)]
+        struct_ser.serialize_field("userPublicBudgetKey", pbjson::private::base64::encode(&self.user_public_budget_key).as_str())?;
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for NewBudget {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "encrypted_blob",
+            "encryptedBlob",
+            "version_nonce",
+            "versionNonce",
+            "categories",
+            "user_public_budget_key",
+            "userPublicBudgetKey",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            EncryptedBlob,
+            VersionNonce,
+            Categories,
+            UserPublicBudgetKey,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "encryptedBlob" | "encrypted_blob" => Ok(GeneratedField::EncryptedBlob),
+                            "versionNonce" | "version_nonce" => Ok(GeneratedField::VersionNonce),
+                            "categories" => Ok(GeneratedField::Categories),
+                            "userPublicBudgetKey" | "user_public_budget_key" => Ok(GeneratedField::UserPublicBudgetKey),
+                            _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = NewBudget;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct entries.serverschema.NewBudget")
+            }
+
+            fn visit_map<V>(self, mut map_: V) -> std::result::Result<NewBudget, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut encrypted_blob__ = None;
+                let mut version_nonce__ = None;
+                let mut categories__ = None;
+                let mut user_public_budget_key__ = None;
+                while let Some(k) = map_.next_key()? {
+                    match k {
+                        GeneratedField::EncryptedBlob => {
+                            if encrypted_blob__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("encryptedBlob"));
+                            }
+                            encrypted_blob__ = 
+                                Some(map_.next_value::<::pbjson::private::BytesDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::VersionNonce => {
+                            if version_nonce__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("versionNonce"));
+                            }
+                            version_nonce__ = 
+                                Some(map_.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::Categories => {
+                            if categories__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("categories"));
+                            }
+                            categories__ = Some(map_.next_value()?);
+                        }
+                        GeneratedField::UserPublicBudgetKey => {
+                            if user_public_budget_key__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("userPublicBudgetKey"));
+                            }
+                            user_public_budget_key__ = 
+                                Some(map_.next_value::<::pbjson::private::BytesDeserialize<_>>()?.0)
+                            ;
+                        }
+                    }
+                }
+                Ok(NewBudget {
+                    encrypted_blob: encrypted_blob__.ok_or_else(|| serde::de::Error::missing_field("encryptedBlob"))?,
+                    version_nonce: version_nonce__.ok_or_else(|| serde::de::Error::missing_field("versionNonce"))?,
+                    categories: categories__.unwrap_or_default(),
+                    user_public_budget_key: user_public_budget_key__.ok_or_else(|| serde::de::Error::missing_field("userPublicBudgetKey"))?,
+                })
+            }
+        }
+        deserializer.deserialize_struct("entries.serverschema.NewBudget", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for NewEncryptedBlob {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut len = 2;
+        if self.id.is_some() {
+            len += 1;
+        }
+        let mut struct_ser = serializer.serialize_struct("entries.serverschema.NewEncryptedBlob", len)?;
+        #[allow(clippy::needless_borrow)]
+        struct_ser.serialize_field("value", pbjson::private::base64::encode(&self.value).as_str())?;
+        #[allow(clippy::needless_borrow)]
+        struct_ser.serialize_field("versionNonce", ToString::to_string(&self.version_nonce).as_str())?;
+        if let Some(v) = self.id.as_ref() {
+            struct_ser.serialize_field("id", v)?;
+        }
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for NewEncryptedBlob {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "value",
+            "version_nonce",
+            "versionNonce",
+            "id",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            Value,
+            VersionNonce,
+            Id,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "value" => Ok(GeneratedField::Value),
+                            "versionNonce" | "version_nonce" => Ok(GeneratedField::VersionNonce),
+                            "id" => Ok(GeneratedField::Id),
+                            _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = NewEncryptedBlob;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct entries.serverschema.NewEncryptedBlob")
+            }
+
+            fn visit_map<V>(self, mut map_: V) -> std::result::Result<NewEncryptedBlob, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut value__ = None;
+                let mut version_nonce__ = None;
+                let mut id__ = None;
+                while let Some(k) = map_.next_key()? {
+                    match k {
+                        GeneratedField::Value => {
+                            if value__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("value"));
+                            }
+                            value__ = 
+                                Some(map_.next_value::<::pbjson::private::BytesDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::VersionNonce => {
+                            if version_nonce__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("versionNonce"));
+                            }
+                            version_nonce__ = 
+                                Some(map_.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::Id => {
+                            if id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("id"));
+                            }
+                            id__ = map_.next_value()?;
+                        }
+                    }
+                }
+                Ok(NewEncryptedBlob {
+                    value: value__.ok_or_else(|| serde::de::Error::missing_field("value"))?,
+                    version_nonce: version_nonce__.ok_or_else(|| serde::de::Error::missing_field("versionNonce"))?,
+                    id: id__,
+                })
+            }
+        }
+        deserializer.deserialize_struct("entries.serverschema.NewEncryptedBlob", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for NewUser {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let len = 22;
+        let mut struct_ser = serializer.serialize_struct("entries.serverschema.NewUser", len)?;
+        struct_ser.serialize_field("email", &self.email)?;
+        #[allow(clippy::needless_borrow)]
+        struct_ser.serialize_field("authString", pbjson::private::base64::encode(&self.auth_string).as_str())?;
+        #[allow(clippy::needless_borrow)]
+        struct_ser.serialize_field("authStringSalt", pbjson::private::base64::encode(&self.auth_string_salt).as_str())?;
+        struct_ser.serialize_field("authStringMemoryCostKib", &self.auth_string_memory_cost_kib)?;
+        struct_ser.serialize_field("authStringParallelismFactor", &self.auth_string_parallelism_factor)?;
+        struct_ser.serialize_field("authStringIters", &self.auth_string_iters)?;
+        #[allow(clippy::needless_borrow)]
+        struct_ser.serialize_field("passwordEncryptionSalt", pbjson::private::base64::encode(&self.password_encryption_salt).as_str())?;
+        struct_ser.serialize_field("passwordEncryptionMemoryCostKib", &self.password_encryption_memory_cost_kib)?;
+        struct_ser.serialize_field("passwordEncryptionParallelismFactor", &self.password_encryption_parallelism_factor)?;
+        struct_ser.serialize_field("passwordEncryptionIters", &self.password_encryption_iters)?;
+        #[allow(clippy::needless_borrow)]
+        struct_ser.serialize_field("recoveryKeySalt", pbjson::private::base64::encode(&self.recovery_key_salt).as_str())?;
+        struct_ser.serialize_field("recoveryKeyMemoryCostKib", &self.recovery_key_memory_cost_kib)?;
+        struct_ser.serialize_field("recoveryKeyParallelismFactor", &self.recovery_key_parallelism_factor)?;
+        struct_ser.serialize_field("recoveryKeyIters", &self.recovery_key_iters)?;
+        #[allow(clippy::needless_borrow)]
+        struct_ser.serialize_field("encryptionKeyEncryptedWithPassword", pbjson::private::base64::encode(&self.encryption_key_encrypted_with_password).as_str())?;
+        #[allow(clippy::needless_borrow)]
+        struct_ser.serialize_field("encryptionKeyEncryptedWithRecoveryKey", pbjson::private::base64::encode(&self.encryption_key_encrypted_with_recovery_key).as_str())?;
+        struct_ser.serialize_field("publicKeyId", &self.public_key_id)?;
+        #[allow(clippy::needless_borrow)]
+        struct_ser.serialize_field("publicKey", pbjson::private::base64::encode(&self.public_key).as_str())?;
+        #[allow(clippy::needless_borrow)]
+        struct_ser.serialize_field("preferencesEncrypted", pbjson::private::base64::encode(&self.preferences_encrypted).as_str())?;
+        #[allow(clippy::needless_borrow)]
+        struct_ser.serialize_field("preferencesVersionNonce", ToString::to_string(&self.preferences_version_nonce).as_str())?;
+        #[allow(clippy::needless_borrow)]
+        struct_ser.serialize_field("userKeystoreEncrypted", pbjson::private::base64::encode(&self.user_keystore_encrypted).as_str())?;
+        #[allow(clippy::needless_borrow)]
+        struct_ser.serialize_field("userKeystoreVersionNonce", ToString::to_string(&self.user_keystore_version_nonce).as_str())?;
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for NewUser {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "email",
+            "auth_string",
+            "authString",
+            "auth_string_salt",
+            "authStringSalt",
+            "auth_string_memory_cost_kib",
+            "authStringMemoryCostKib",
+            "auth_string_parallelism_factor",
+            "authStringParallelismFactor",
+            "auth_string_iters",
+            "authStringIters",
+            "password_encryption_salt",
+            "passwordEncryptionSalt",
+            "password_encryption_memory_cost_kib",
+            "passwordEncryptionMemoryCostKib",
+            "password_encryption_parallelism_factor",
+            "passwordEncryptionParallelismFactor",
+            "password_encryption_iters",
+            "passwordEncryptionIters",
+            "recovery_key_salt",
+            "recoveryKeySalt",
+            "recovery_key_memory_cost_kib",
+            "recoveryKeyMemoryCostKib",
+            "recovery_key_parallelism_factor",
+            "recoveryKeyParallelismFactor",
+            "recovery_key_iters",
+            "recoveryKeyIters",
+            "encryption_key_encrypted_with_password",
+            "encryptionKeyEncryptedWithPassword",
+            "encryption_key_encrypted_with_recovery_key",
+            "encryptionKeyEncryptedWithRecoveryKey",
+            "public_key_id",
+            "publicKeyId",
+            "public_key",
+            "publicKey",
+            "preferences_encrypted",
+            "preferencesEncrypted",
+            "preferences_version_nonce",
+            "preferencesVersionNonce",
+            "user_keystore_encrypted",
+            "userKeystoreEncrypted",
+            "user_keystore_version_nonce",
+            "userKeystoreVersionNonce",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            Email,
+            AuthString,
+            AuthStringSalt,
+            AuthStringMemoryCostKib,
+            AuthStringParallelismFactor,
+            AuthStringIters,
+            PasswordEncryptionSalt,
+            PasswordEncryptionMemoryCostKib,
+            PasswordEncryptionParallelismFactor,
+            PasswordEncryptionIters,
+            RecoveryKeySalt,
+            RecoveryKeyMemoryCostKib,
+            RecoveryKeyParallelismFactor,
+            RecoveryKeyIters,
+            EncryptionKeyEncryptedWithPassword,
+            EncryptionKeyEncryptedWithRecoveryKey,
+            PublicKeyId,
+            PublicKey,
+            PreferencesEncrypted,
+            PreferencesVersionNonce,
+            UserKeystoreEncrypted,
+            UserKeystoreVersionNonce,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "email" => Ok(GeneratedField::Email),
+                            "authString" | "auth_string" => Ok(GeneratedField::AuthString),
+                            "authStringSalt" | "auth_string_salt" => Ok(GeneratedField::AuthStringSalt),
+                            "authStringMemoryCostKib" | "auth_string_memory_cost_kib" => Ok(GeneratedField::AuthStringMemoryCostKib),
+                            "authStringParallelismFactor" | "auth_string_parallelism_factor" => Ok(GeneratedField::AuthStringParallelismFactor),
+                            "authStringIters" | "auth_string_iters" => Ok(GeneratedField::AuthStringIters),
+                            "passwordEncryptionSalt" | "password_encryption_salt" => Ok(GeneratedField::PasswordEncryptionSalt),
+                            "passwordEncryptionMemoryCostKib" | "password_encryption_memory_cost_kib" => Ok(GeneratedField::PasswordEncryptionMemoryCostKib),
+                            "passwordEncryptionParallelismFactor" | "password_encryption_parallelism_factor" => Ok(GeneratedField::PasswordEncryptionParallelismFactor),
+                            "passwordEncryptionIters" | "password_encryption_iters" => Ok(GeneratedField::PasswordEncryptionIters),
+                            "recoveryKeySalt" | "recovery_key_salt" => Ok(GeneratedField::RecoveryKeySalt),
+                            "recoveryKeyMemoryCostKib" | "recovery_key_memory_cost_kib" => Ok(GeneratedField::RecoveryKeyMemoryCostKib),
+                            "recoveryKeyParallelismFactor" | "recovery_key_parallelism_factor" => Ok(GeneratedField::RecoveryKeyParallelismFactor),
+                            "recoveryKeyIters" | "recovery_key_iters" => Ok(GeneratedField::RecoveryKeyIters),
+                            "encryptionKeyEncryptedWithPassword" | "encryption_key_encrypted_with_password" => Ok(GeneratedField::EncryptionKeyEncryptedWithPassword),
+                            "encryptionKeyEncryptedWithRecoveryKey" | "encryption_key_encrypted_with_recovery_key" => Ok(GeneratedField::EncryptionKeyEncryptedWithRecoveryKey),
+                            "publicKeyId" | "public_key_id" => Ok(GeneratedField::PublicKeyId),
+                            "publicKey" | "public_key" => Ok(GeneratedField::PublicKey),
+                            "preferencesEncrypted" | "preferences_encrypted" => Ok(GeneratedField::PreferencesEncrypted),
+                            "preferencesVersionNonce" | "preferences_version_nonce" => Ok(GeneratedField::PreferencesVersionNonce),
+                            "userKeystoreEncrypted" | "user_keystore_encrypted" => Ok(GeneratedField::UserKeystoreEncrypted),
+                            "userKeystoreVersionNonce" | "user_keystore_version_nonce" => Ok(GeneratedField::UserKeystoreVersionNonce),
+                            _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = NewUser;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct entries.serverschema.NewUser")
+            }
+
+            fn visit_map<V>(self, mut map_: V) -> std::result::Result<NewUser, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut email__ = None;
+                let mut auth_string__ = None;
+                let mut auth_string_salt__ = None;
+                let mut auth_string_memory_cost_kib__ = None;
+                let mut auth_string_parallelism_factor__ = None;
+                let mut auth_string_iters__ = None;
+                let mut password_encryption_salt__ = None;
+                let mut password_encryption_memory_cost_kib__ = None;
+                let mut password_encryption_parallelism_factor__ = None;
+                let mut password_encryption_iters__ = None;
+                let mut recovery_key_salt__ = None;
+                let mut recovery_key_memory_cost_kib__ = None;
+                let mut recovery_key_parallelism_factor__ = None;
+                let mut recovery_key_iters__ = None;
+                let mut encryption_key_encrypted_with_password__ = None;
+                let mut encryption_key_encrypted_with_recovery_key__ = None;
+                let mut public_key_id__ = None;
+                let mut public_key__ = None;
+                let mut preferences_encrypted__ = None;
+                let mut preferences_version_nonce__ = None;
+                let mut user_keystore_encrypted__ = None;
+                let mut user_keystore_version_nonce__ = None;
+                while let Some(k) = map_.next_key()? {
+                    match k {
+                        GeneratedField::Email => {
+                            if email__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("email"));
+                            }
+                            email__ = Some(map_.next_value()?);
+                        }
+                        GeneratedField::AuthString => {
+                            if auth_string__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("authString"));
+                            }
+                            auth_string__ = 
+                                Some(map_.next_value::<::pbjson::private::BytesDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::AuthStringSalt => {
+                            if auth_string_salt__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("authStringSalt"));
+                            }
+                            auth_string_salt__ = 
+                                Some(map_.next_value::<::pbjson::private::BytesDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::AuthStringMemoryCostKib => {
+                            if auth_string_memory_cost_kib__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("authStringMemoryCostKib"));
+                            }
+                            auth_string_memory_cost_kib__ = 
+                                Some(map_.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::AuthStringParallelismFactor => {
+                            if auth_string_parallelism_factor__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("authStringParallelismFactor"));
+                            }
+                            auth_string_parallelism_factor__ = 
+                                Some(map_.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::AuthStringIters => {
+                            if auth_string_iters__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("authStringIters"));
+                            }
+                            auth_string_iters__ = 
+                                Some(map_.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::PasswordEncryptionSalt => {
+                            if password_encryption_salt__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("passwordEncryptionSalt"));
+                            }
+                            password_encryption_salt__ = 
+                                Some(map_.next_value::<::pbjson::private::BytesDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::PasswordEncryptionMemoryCostKib => {
+                            if password_encryption_memory_cost_kib__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("passwordEncryptionMemoryCostKib"));
+                            }
+                            password_encryption_memory_cost_kib__ = 
+                                Some(map_.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::PasswordEncryptionParallelismFactor => {
+                            if password_encryption_parallelism_factor__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("passwordEncryptionParallelismFactor"));
+                            }
+                            password_encryption_parallelism_factor__ = 
+                                Some(map_.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::PasswordEncryptionIters => {
+                            if password_encryption_iters__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("passwordEncryptionIters"));
+                            }
+                            password_encryption_iters__ = 
+                                Some(map_.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::RecoveryKeySalt => {
+                            if recovery_key_salt__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("recoveryKeySalt"));
+                            }
+                            recovery_key_salt__ = 
+                                Some(map_.next_value::<::pbjson::private::BytesDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::RecoveryKeyMemoryCostKib => {
+                            if recovery_key_memory_cost_kib__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("recoveryKeyMemoryCostKib"));
+                            }
+                            recovery_key_memory_cost_kib__ = 
+                                Some(map_.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::RecoveryKeyParallelismFactor => {
+                            if recovery_key_parallelism_factor__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("recoveryKeyParallelismFactor"));
+                            }
+                            recovery_key_parallelism_factor__ = 
+                                Some(map_.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::RecoveryKeyIters => {
+                            if recovery_key_iters__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("recoveryKeyIters"));
+                            }
+                            recovery_key_iters__ = 
+                                Some(map_.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::EncryptionKeyEncryptedWithPassword => {
+                            if encryption_key_encrypted_with_password__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("encryptionKeyEncryptedWithPassword"));
+                            }
+                            encryption_key_encrypted_with_password__ = 
+                                Some(map_.next_value::<::pbjson::private::BytesDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::EncryptionKeyEncryptedWithRecoveryKey => {
+                            if encryption_key_encrypted_with_recovery_key__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("encryptionKeyEncryptedWithRecoveryKey"));
+                            }
+                            encryption_key_encrypted_with_recovery_key__ = 
+                                Some(map_.next_value::<::pbjson::private::BytesDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::PublicKeyId => {
+                            if public_key_id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("publicKeyId"));
+                            }
+                            public_key_id__ = map_.next_value()?;
+                        }
+                        GeneratedField::PublicKey => {
+                            if public_key__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("publicKey"));
+                            }
+                            public_key__ = 
+                                Some(map_.next_value::<::pbjson::private::BytesDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::PreferencesEncrypted => {
+                            if preferences_encrypted__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("preferencesEncrypted"));
+                            }
+                            preferences_encrypted__ = 
+                                Some(map_.next_value::<::pbjson::private::BytesDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::PreferencesVersionNonce => {
+                            if preferences_version_nonce__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("preferencesVersionNonce"));
+                            }
+                            preferences_version_nonce__ = 
+                                Some(map_.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::UserKeystoreEncrypted => {
+                            if user_keystore_encrypted__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("userKeystoreEncrypted"));
+                            }
+                            user_keystore_encrypted__ = 
+                                Some(map_.next_value::<::pbjson::private::BytesDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::UserKeystoreVersionNonce => {
+                            if user_keystore_version_nonce__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("userKeystoreVersionNonce"));
+                            }
+                            user_keystore_version_nonce__ = 
+                                Some(map_.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                    }
+                }
+                Ok(NewUser {
+                    email: email__.ok_or_else(|| serde::de::Error::missing_field("email"))?,
+                    auth_string: auth_string__.ok_or_else(|| serde::de::Error::missing_field("authString"))?,
+                    auth_string_salt: auth_string_salt__.ok_or_else(|| serde::de::Error::missing_field("authStringSalt"))?,
+                    auth_string_memory_cost_kib: auth_string_memory_cost_kib__.ok_or_else(|| serde::de::Error::missing_field("authStringMemoryCostKib"))?,
+                    auth_string_parallelism_factor: auth_string_parallelism_factor__.ok_or_else(|| serde::de::Error::missing_field("authStringParallelismFactor"))?,
+                    auth_string_iters: auth_string_iters__.ok_or_else(|| serde::de::Error::missing_field("authStringIters"))?,
+                    password_encryption_salt: password_encryption_salt__.ok_or_else(|| serde::de::Error::missing_field("passwordEncryptionSalt"))?,
+                    password_encryption_memory_cost_kib: password_encryption_memory_cost_kib__.ok_or_else(|| serde::de::Error::missing_field("passwordEncryptionMemoryCostKib"))?,
+                    password_encryption_parallelism_factor: password_encryption_parallelism_factor__.ok_or_else(|| serde::de::Error::missing_field("passwordEncryptionParallelismFactor"))?,
+                    password_encryption_iters: password_encryption_iters__.ok_or_else(|| serde::de::Error::missing_field("passwordEncryptionIters"))?,
+                    recovery_key_salt: recovery_key_salt__.ok_or_else(|| serde::de::Error::missing_field("recoveryKeySalt"))?,
+                    recovery_key_memory_cost_kib: recovery_key_memory_cost_kib__.ok_or_else(|| serde::de::Error::missing_field("recoveryKeyMemoryCostKib"))?,
+                    recovery_key_parallelism_factor: recovery_key_parallelism_factor__.ok_or_else(|| serde::de::Error::missing_field("recoveryKeyParallelismFactor"))?,
+                    recovery_key_iters: recovery_key_iters__.ok_or_else(|| serde::de::Error::missing_field("recoveryKeyIters"))?,
+                    encryption_key_encrypted_with_password: encryption_key_encrypted_with_password__.ok_or_else(|| serde::de::Error::missing_field("encryptionKeyEncryptedWithPassword"))?,
+                    encryption_key_encrypted_with_recovery_key: encryption_key_encrypted_with_recovery_key__.ok_or_else(|| serde::de::Error::missing_field("encryptionKeyEncryptedWithRecoveryKey"))?,
+                    public_key_id: public_key_id__.ok_or_else(|| serde::de::Error::missing_field("publicKeyId"))?,
+                    public_key: public_key__.ok_or_else(|| serde::de::Error::missing_field("publicKey"))?,
+                    preferences_encrypted: preferences_encrypted__.ok_or_else(|| serde::de::Error::missing_field("preferencesEncrypted"))?,
+                    preferences_version_nonce: preferences_version_nonce__.ok_or_else(|| serde::de::Error::missing_field("preferencesVersionNonce"))?,
+                    user_keystore_encrypted: user_keystore_encrypted__.ok_or_else(|| serde::de::Error::missing_field("userKeystoreEncrypted"))?,
+                    user_keystore_version_nonce: user_keystore_version_nonce__.ok_or_else(|| serde::de::Error::missing_field("userKeystoreVersionNonce"))?,
+                })
+            }
+        }
+        deserializer.deserialize_struct("entries.serverschema.NewUser", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for NewUserPublicKey {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let len = 3;
+        let mut struct_ser = serializer.serialize_struct("entries.serverschema.NewUserPublicKey", len)?;
+        struct_ser.serialize_field("id", &self.id)?;
+        #[allow(clippy::needless_borrow)]
+        struct_ser.serialize_field("value", pbjson::private::base64::encode(&self.value).as_str())?;
+        struct_ser.serialize_field("expectedPreviousPublicKeyId", &self.expected_previous_public_key_id)?;
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for NewUserPublicKey {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "id",
+            "value",
+            "expected_previous_public_key_id",
+            "expectedPreviousPublicKeyId",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            Id,
+            Value,
+            ExpectedPreviousPublicKeyId,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "id" => Ok(GeneratedField::Id),
+                            "value" => Ok(GeneratedField::Value),
+                            "expectedPreviousPublicKeyId" | "expected_previous_public_key_id" => Ok(GeneratedField::ExpectedPreviousPublicKeyId),
+                            _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = NewUserPublicKey;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct entries.serverschema.NewUserPublicKey")
+            }
+
+            fn visit_map<V>(self, mut map_: V) -> std::result::Result<NewUserPublicKey, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut id__ = None;
+                let mut value__ = None;
+                let mut expected_previous_public_key_id__ = None;
+                while let Some(k) = map_.next_key()? {
+                    match k {
+                        GeneratedField::Id => {
+                            if id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("id"));
+                            }
+                            id__ = map_.next_value()?;
+                        }
+                        GeneratedField::Value => {
+                            if value__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("value"));
+                            }
+                            value__ = 
+                                Some(map_.next_value::<::pbjson::private::BytesDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::ExpectedPreviousPublicKeyId => {
+                            if expected_previous_public_key_id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("expectedPreviousPublicKeyId"));
+                            }
+                            expected_previous_public_key_id__ = map_.next_value()?;
+                        }
+                    }
+                }
+                Ok(NewUserPublicKey {
+                    id: id__.ok_or_else(|| serde::de::Error::missing_field("id"))?,
+                    value: value__.ok_or_else(|| serde::de::Error::missing_field("value"))?,
+                    expected_previous_public_key_id: expected_previous_public_key_id__.ok_or_else(|| serde::de::Error::missing_field("expectedPreviousPublicKeyId"))?,
+                })
+            }
+        }
+        deserializer.deserialize_struct("entries.serverschema.NewUserPublicKey", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for Otp {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let len = 1;
+        let mut struct_ser = serializer.serialize_struct("entries.serverschema.Otp", len)?;
+        struct_ser.serialize_field("value", &self.value)?;
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for Otp {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "value",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            Value,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "value" => Ok(GeneratedField::Value),
+                            _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = Otp;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct entries.serverschema.Otp")
+            }
+
+            fn visit_map<V>(self, mut map_: V) -> std::result::Result<Otp, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut value__ = None;
+                while let Some(k) = map_.next_key()? {
+                    match k {
+                        GeneratedField::Value => {
+                            if value__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("value"));
+                            }
+                            value__ = Some(map_.next_value()?);
+                        }
+                    }
+                }
+                Ok(Otp {
+                    value: value__.ok_or_else(|| serde::de::Error::missing_field("value"))?,
+                })
+            }
+        }
+        deserializer.deserialize_struct("entries.serverschema.Otp", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for PublicKey {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let len = 1;
+        let mut struct_ser = serializer.serialize_struct("entries.serverschema.PublicKey", len)?;
+        #[allow(clippy::needless_borrow)]
+        struct_ser.serialize_field("value", pbjson::private::base64::encode(&self.value).as_str())?;
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for PublicKey {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "value",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            Value,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "value" => Ok(GeneratedField::Value),
+                            _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = PublicKey;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct entries.serverschema.PublicKey")
+            }
+
+            fn visit_map<V>(self, mut map_: V) -> std::result::Result<PublicKey, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut value__ = None;
+                while let Some(k) = map_.next_key()? {
+                    match k {
+                        GeneratedField::Value => {
+                            if value__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("value"));
+                            }
+                            value__ = 
+                                Some(map_.next_value::<::pbjson::private::BytesDeserialize<_>>()?.0)
+                            ;
+                        }
+                    }
+                }
+                Ok(PublicKey {
+                    value: value__.ok_or_else(|| serde::de::Error::missing_field("value"))?,
+                })
+            }
+        }
+        deserializer.deserialize_struct("entries.serverschema.PublicKey", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for RecoveryKeyUpdate {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let len = 6;
+        let mut struct_ser = serializer.serialize_struct("entries.serverschema.RecoveryKeyUpdate", len)?;
+        struct_ser.serialize_field("otp", &self.otp)?;
+        #[allow(clippy::needless_borrow)]
+        struct_ser.serialize_field("recoveryKeySalt", pbjson::private::base64::encode(&self.recovery_key_salt).as_str())?;
+        struct_ser.serialize_field("recoveryKeyMemoryCostKib", &self.recovery_key_memory_cost_kib)?;
+        struct_ser.serialize_field("recoveryKeyParallelismFactor", &self.recovery_key_parallelism_factor)?;
+        struct_ser.serialize_field("recoveryKeyIters", &self.recovery_key_iters)?;
+        #[allow(clippy::needless_borrow)]
+        struct_ser.serialize_field("encryptedEncryptionKey", pbjson::private::base64::encode(&self.encrypted_encryption_key).as_str())?;
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for RecoveryKeyUpdate {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "otp",
+            "recovery_key_salt",
+            "recoveryKeySalt",
+            "recovery_key_memory_cost_kib",
+            "recoveryKeyMemoryCostKib",
+            "recovery_key_parallelism_factor",
+            "recoveryKeyParallelismFactor",
+            "recovery_key_iters",
+            "recoveryKeyIters",
+            "encrypted_encryption_key",
+            "encryptedEncryptionKey",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            Otp,
+            RecoveryKeySalt,
+            RecoveryKeyMemoryCostKib,
+            RecoveryKeyParallelismFactor,
+            RecoveryKeyIters,
+            EncryptedEncryptionKey,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "otp" => Ok(GeneratedField::Otp),
+                            "recoveryKeySalt" | "recovery_key_salt" => Ok(GeneratedField::RecoveryKeySalt),
+                            "recoveryKeyMemoryCostKib" | "recovery_key_memory_cost_kib" => Ok(GeneratedField::RecoveryKeyMemoryCostKib),
+                            "recoveryKeyParallelismFactor" | "recovery_key_parallelism_factor" => Ok(GeneratedField::RecoveryKeyParallelismFactor),
+                            "recoveryKeyIters" | "recovery_key_iters" => Ok(GeneratedField::RecoveryKeyIters),
+                            "encryptedEncryptionKey" | "encrypted_encryption_key" => Ok(GeneratedField::EncryptedEncryptionKey),
+                            _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = RecoveryKeyUpdate;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct entries.serverschema.RecoveryKeyUpdate")
+            }
+
+            fn visit_map<V>(self, mut map_: V) -> std::result::Result<RecoveryKeyUpdate, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut otp__ = None;
+                let mut recovery_key_salt__ = None;
+                let mut recovery_key_memory_cost_kib__ = None;
+                let mut recovery_key_parallelism_factor__ = None;
+                let mut recovery_key_iters__ = None;
+                let mut encrypted_encryption_key__ = None;
+                while let Some(k) = map_.next_key()? {
+                    match k {
+                        GeneratedField::Otp => {
+                            if otp__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("otp"));
+                            }
+                            otp__ = Some(map_.next_value()?);
+                        }
+                        GeneratedField::RecoveryKeySalt => {
+                            if recovery_key_salt__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("recoveryKeySalt"));
+                            }
+                            recovery_key_salt__ = 
+                                Some(map_.next_value::<::pbjson::private::BytesDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::RecoveryKeyMemoryCostKib => {
+                            if recovery_key_memory_cost_kib__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("recoveryKeyMemoryCostKib"));
+                            }
+                            recovery_key_memory_cost_kib__ = 
+                                Some(map_.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::RecoveryKeyParallelismFactor => {
+                            if recovery_key_parallelism_factor__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("recoveryKeyParallelismFactor"));
+                            }
+                            recovery_key_parallelism_factor__ = 
+                                Some(map_.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::RecoveryKeyIters => {
+                            if recovery_key_iters__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("recoveryKeyIters"));
+                            }
+                            recovery_key_iters__ = 
+                                Some(map_.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::EncryptedEncryptionKey => {
+                            if encrypted_encryption_key__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("encryptedEncryptionKey"));
+                            }
+                            encrypted_encryption_key__ = 
+                                Some(map_.next_value::<::pbjson::private::BytesDeserialize<_>>()?.0)
+                            ;
+                        }
+                    }
+                }
+                Ok(RecoveryKeyUpdate {
+                    otp: otp__.ok_or_else(|| serde::de::Error::missing_field("otp"))?,
+                    recovery_key_salt: recovery_key_salt__.ok_or_else(|| serde::de::Error::missing_field("recoveryKeySalt"))?,
+                    recovery_key_memory_cost_kib: recovery_key_memory_cost_kib__.ok_or_else(|| serde::de::Error::missing_field("recoveryKeyMemoryCostKib"))?,
+                    recovery_key_parallelism_factor: recovery_key_parallelism_factor__.ok_or_else(|| serde::de::Error::missing_field("recoveryKeyParallelismFactor"))?,
+                    recovery_key_iters: recovery_key_iters__.ok_or_else(|| serde::de::Error::missing_field("recoveryKeyIters"))?,
+                    encrypted_encryption_key: encrypted_encryption_key__.ok_or_else(|| serde::de::Error::missing_field("encryptedEncryptionKey"))?,
+                })
+            }
+        }
+        deserializer.deserialize_struct("entries.serverschema.RecoveryKeyUpdate", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for ServerErrorResponse {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut len = 2;
+        if self.request_id.is_some() {
+            len += 1;
+        }
+        if self.field.is_some() {
+            len += 1;
+        }
+        if self.max_size.is_some() {
+            len += 1;
+        }
+        if self.retry_after_secs.is_some() {
+            len += 1;
+        }
+        if self.conflicting_id.is_some() {
+            len += 1;
+        }
+        if self.current_version_nonce.is_some() {
+            len += 1;
+        }
+        let mut struct_ser = serializer.serialize_struct("entries.serverschema.ServerErrorResponse", len)?;
+        let v = ErrorType::try_from(self.err_type)
+            .map_err(|_| serde::ser::Error::custom(format!("Invalid variant {}", self.err_type)))?;
+        struct_ser.serialize_field("errType", &v)?;
+        struct_ser.serialize_field("errMessage", &self.err_message)?;
+        if let Some(v) = self.request_id.as_ref() {
+            struct_ser.serialize_field("requestId", v)?;
+        }
+        if let Some(v) = self.field.as_ref() {
+            struct_ser.serialize_field("field", v)?;
+        }
+        if let Some(v) = self.max_size.as_ref() {
+            #[allow(clippy::needless_borrow)]
+            struct_ser.serialize_field("maxSize", ToString::to_string(&v).as_str())?;
+        }
+        if let Some(v) = self.retry_after_secs.as_ref() {
+            #[allow(clippy::needless_borrow)]
+            struct_ser.serialize_field("retryAfterSecs", ToString::to_string(&v).as_str())?;
+        }
+        if let Some(v) = self.conflicting_id.as_ref() {
+            struct_ser.serialize_field("conflictingId", v)?;
+        }
+        if let Some(v) = self.current_version_nonce.as_ref() {
+            #[allow(clippy::needless_borrow)]
+            struct_ser.serialize_field("currentVersionNonce", ToString::to_string(&v).as_str())?;
+        }
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for ServerErrorResponse {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "err_type",
+            "errType",
+            "err_message",
+            "errMessage",
+            "request_id",
+            "requestId",
+            "field",
+            "max_size",
+            "maxSize",
+            "retry_after_secs",
+            "retryAfterSecs",
+            "conflicting_id",
+            "conflictingId",
+            "current_version_nonce",
+            "currentVersionNonce",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            ErrType,
+            ErrMessage,
+            RequestId,
+            Field,
+            MaxSize,
+            RetryAfterSecs,
+            ConflictingId,
+            CurrentVersionNonce,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "errType" | "err_type" => Ok(GeneratedField::ErrType),
+                            "errMessage" | "err_message" => Ok(GeneratedField::ErrMessage),
+                            "requestId" | "request_id" => Ok(GeneratedField::RequestId),
+                            "field" => Ok(GeneratedField::Field),
+                            "maxSize" | "max_size" => Ok(GeneratedField::MaxSize),
+                            "retryAfterSecs" | "retry_after_secs" => Ok(GeneratedField::RetryAfterSecs),
+                            "conflictingId" | "conflicting_id" => Ok(GeneratedField::ConflictingId),
+                            "currentVersionNonce" | "current_version_nonce" => Ok(GeneratedField::CurrentVersionNonce),
+                            _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = ServerErrorResponse;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct entries.serverschema.ServerErrorResponse")
+            }
+
+            fn visit_map<V>(self, mut map_: V) -> std::result::Result<ServerErrorResponse, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut err_type__ = None;
+                let mut err_message__ = None;
+                let mut request_id__ = None;
+                let mut field__ = None;
+                let mut max_size__ = None;
+                let mut retry_after_secs__ = None;
+                let mut conflicting_id__ = None;
+                let mut current_version_nonce__ = None;
+                while let Some(k) = map_.next_key()? {
+                    match k {
+                        GeneratedField::ErrType => {
+                            if err_type__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("errType"));
+                            }
+                            err_type__ = Some(map_.next_value::<ErrorType>()? as i32);
+                        }
+                        GeneratedField::ErrMessage => {
+                            if err_message__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("errMessage"));
+                            }
+                            err_message__ = Some(map_.next_value()?);
+                        }
+                        GeneratedField::RequestId => {
+                            if request_id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("requestId"));
+                            }
+                            request_id__ = map_.next_value()?;
+                        }
+                        GeneratedField::Field => {
+                            if field__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("field"));
+                            }
+                            field__ = map_.next_value()?;
+                        }
+                        GeneratedField::MaxSize => {
+                            if max_size__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("maxSize"));
+                            }
+                            max_size__ = 
+                                map_.next_value::<::std::option::Option<::pbjson::private::NumberDeserialize<_>>>()?.map(|x| x.0)
+                            ;
+                        }
+                        GeneratedField::RetryAfterSecs => {
+                            if retry_after_secs__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("retryAfterSecs"));
+                            }
+                            retry_after_secs__ = 
+                                map_.next_value::<::std::option::Option<::pbjson::private::NumberDeserialize<_>>>()?.map(|x| x.0)
+                            ;
+                        }
+                        GeneratedField::ConflictingId => {
+                            if conflicting_id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("conflictingId"));
+                            }
+                            conflicting_id__ = map_.next_value()?;
+                        }
+                        GeneratedField::CurrentVersionNonce => {
+                            if current_version_nonce__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("currentVersionNonce"));
+                            }
+                            current_version_nonce__ = 
+                                map_.next_value::<::std::option::Option<::pbjson::private::NumberDeserialize<_>>>()?.map(|x| x.0)
+                            ;
+                        }
+                    }
+                }
+                Ok(ServerErrorResponse {
+                    err_type: err_type__.ok_or_else(|| serde::de::Error::missing_field("errType"))?,
+                    err_message: err_message__.ok_or_else(|| serde::de::Error::missing_field("errMessage"))?,
+                    request_id: request_id__,
+                    field: field__,
+                    max_size: max_size__,
+                    retry_after_secs: retry_after_secs__,
+                    conflicting_id: conflicting_id__,
+                    current_version_nonce: current_version_nonce__,
+                })
+            }
+        }
+        deserializer.deserialize_struct("entries.serverschema.ServerErrorResponse", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for SigninNonceAndHashParams {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let len = 5;
+        let mut struct_ser = serializer.serialize_struct("entries.serverschema.SigninNonceAndHashParams", len)?;
+        #[allow(clippy::needless_borrow)]
+        struct_ser.serialize_field("authStringSalt", pbjson::private::base64::encode(&self.auth_string_salt).as_str())?;
+        struct_ser.serialize_field("authStringMemoryCostKib", &self.auth_string_memory_cost_kib)?;
+        struct_ser.serialize_field("authStringParallelismFactor", &self.auth_string_parallelism_factor)?;
+        struct_ser.serialize_field("authStringIters", &self.auth_string_iters)?;
+        struct_ser.serialize_field("nonce", &self.nonce)?;
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for SigninNonceAndHashParams {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "auth_string_salt",
+            "authStringSalt",
+            "auth_string_memory_cost_kib",
+            "authStringMemoryCostKib",
+            "auth_string_parallelism_factor",
+            "authStringParallelismFactor",
+            "auth_string_iters",
+            "authStringIters",
+            "nonce",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            AuthStringSalt,
+            AuthStringMemoryCostKib,
+            AuthStringParallelismFactor,
+            AuthStringIters,
+            Nonce,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "authStringSalt" | "auth_string_salt" => Ok(GeneratedField::AuthStringSalt),
+                            "authStringMemoryCostKib" | "auth_string_memory_cost_kib" => Ok(GeneratedField::AuthStringMemoryCostKib),
+                            "authStringParallelismFactor" | "auth_string_parallelism_factor" => Ok(GeneratedField::AuthStringParallelismFactor),
+                            "authStringIters" | "auth_string_iters" => Ok(GeneratedField::AuthStringIters),
+                            "nonce" => Ok(GeneratedField::Nonce),
+                            _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = SigninNonceAndHashParams;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct entries.serverschema.SigninNonceAndHashParams")
+            }
+
+            fn visit_map<V>(self, mut map_: V) -> std::result::Result<SigninNonceAndHashParams, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut auth_string_salt__ = None;
+                let mut auth_string_memory_cost_kib__ = None;
+                let mut auth_string_parallelism_factor__ = None;
+                let mut auth_string_iters__ = None;
+                let mut nonce__ = None;
+                while let Some(k) = map_.next_key()? {
+                    match k {
+                        GeneratedField::AuthStringSalt => {
+                            if auth_string_salt__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("authStringSalt"));
+                            }
+                            auth_string_salt__ = 
+                                Some(map_.next_value::<::pbjson::private::BytesDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::AuthStringMemoryCostKib => {
+                            if auth_string_memory_cost_kib__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("authStringMemoryCostKib"));
+                            }
+                            auth_string_memory_cost_kib__ = 
+                                Some(map_.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::AuthStringParallelismFactor => {
+                            if auth_string_parallelism_factor__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("authStringParallelismFactor"));
+                            }
+                            auth_string_parallelism_factor__ = 
+                                Some(map_.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::AuthStringIters => {
+                            if auth_string_iters__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("authStringIters"));
+                            }
+                            auth_string_iters__ = 
+                                Some(map_.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::Nonce => {
+                            if nonce__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("nonce"));
+                            }
+                            nonce__ = 
+                                Some(map_.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                    }
+                }
+                Ok(SigninNonceAndHashParams {
+                    auth_string_salt: auth_string_salt__.ok_or_else(|| serde::de::Error::missing_field("authStringSalt"))?,
+                    auth_string_memory_cost_kib: auth_string_memory_cost_kib__.ok_or_else(|| serde::de::Error::missing_field("authStringMemoryCostKib"))?,
+                    auth_string_parallelism_factor: auth_string_parallelism_factor__.ok_or_else(|| serde::de::Error::missing_field("authStringParallelismFactor"))?,
+                    auth_string_iters: auth_string_iters__.ok_or_else(|| serde::de::Error::missing_field("authStringIters"))?,
+                    nonce: nonce__.ok_or_else(|| serde::de::Error::missing_field("nonce"))?,
+                })
+            }
+        }
+        deserializer.deserialize_struct("entries.serverschema.SigninNonceAndHashParams", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for SigninToken {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let len = 1;
+        let mut struct_ser = serializer.serialize_struct("entries.serverschema.SigninToken", len)?;
+        struct_ser.serialize_field("value", &self.value)?;
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for SigninToken {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "value",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            Value,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "value" => Ok(GeneratedField::Value),
+                            _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = SigninToken;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct entries.serverschema.SigninToken")
+            }
+
+            fn visit_map<V>(self, mut map_: V) -> std::result::Result<SigninToken, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut value__ = None;
+                while let Some(k) = map_.next_key()? {
+                    match k {
+                        GeneratedField::Value => {
+                            if value__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("value"));
+                            }
+                            value__ = Some(map_.next_value()?);
+                        }
+                    }
+                }
+                Ok(SigninToken {
+                    value: value__.ok_or_else(|| serde::de::Error::missing_field("value"))?,
+                })
+            }
+        }
+        deserializer.deserialize_struct("entries.serverschema.SigninToken", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for Timestamp {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let len = 2;
+        let mut struct_ser = serializer.serialize_struct("entries.serverschema.Timestamp", len)?;
+        #[allow(clippy::needless_borrow)]
+        struct_ser.serialize_field("secs", ToString::to_string(&self.secs).as_str())?;
+        struct_ser.serialize_field("nanos", &self.nanos)?;
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for Timestamp {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "secs",
+            "nanos",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            Secs,
+            Nanos,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "secs" => Ok(GeneratedField::Secs),
+                            "nanos" => Ok(GeneratedField::Nanos),
+                            _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = Timestamp;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct entries.serverschema.Timestamp")
+            }
+
+            fn visit_map<V>(self, mut map_: V) -> std::result::Result<Timestamp, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut secs__ = None;
+                let mut nanos__ = None;
+                while let Some(k) = map_.next_key()? {
+                    match k {
+                        GeneratedField::Secs => {
+                            if secs__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("secs"));
+                            }
+                            secs__ = 
+                                Some(map_.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::Nanos => {
+                            if nanos__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("nanos"));
+                            }
+                            nanos__ = 
+                                Some(map_.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                    }
+                }
+                Ok(Timestamp {
+                    secs: secs__.ok_or_else(|| serde::de::Error::missing_field("secs"))?,
+                    nanos: nanos__.ok_or_else(|| serde::de::Error::missing_field("nanos"))?,
+                })
+            }
+        }
+        deserializer.deserialize_struct("entries.serverschema.Timestamp", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for TokenPair {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let len = 3;
+        let mut struct_ser = serializer.serialize_struct("entries.serverschema.TokenPair", len)?;
+        struct_ser.serialize_field("accessToken", &self.access_token)?;
+        struct_ser.serialize_field("refreshToken", &self.refresh_token)?;
+        struct_ser.serialize_field("serverTime", &self.server_time)?;
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for TokenPair {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "access_token",
+            "accessToken",
+            "refresh_token",
+            "refreshToken",
+            "server_time",
+            "serverTime",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            AccessToken,
+            RefreshToken,
+            ServerTime,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "accessToken" | "access_token" => Ok(GeneratedField::AccessToken),
+                            "refreshToken" | "refresh_token" => Ok(GeneratedField::RefreshToken),
+                            "serverTime" | "server_time" => Ok(GeneratedField::ServerTime),
+                            _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = TokenPair;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct entries.serverschema.TokenPair")
+            }
+
+            fn visit_map<V>(self, mut map_: V) -> std::result::Result<TokenPair, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut access_token__ = None;
+                let mut refresh_token__ = None;
+                let mut server_time__ = None;
+                while let Some(k) = map_.next_key()? {
+                    match k {
+                        GeneratedField::AccessToken => {
+                            if access_token__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("accessToken"));
+                            }
+                            access_token__ = Some(map_.next_value()?);
+                        }
+                        GeneratedField::RefreshToken => {
+                            if refresh_token__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("refreshToken"));
+                            }
+                            refresh_token__ = Some(map_.next_value()?);
+                        }
+                        GeneratedField::ServerTime => {
+                            if server_time__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("serverTime"));
+                            }
+                            server_time__ = map_.next_value()?;
+                        }
+                    }
+                }
+                Ok(TokenPair {
+                    access_token: access_token__.ok_or_else(|| serde::de::Error::missing_field("accessToken"))?,
+                    refresh_token: refresh_token__.ok_or_else(|| serde::de::Error::missing_field("refreshToken"))?,
+                    server_time: server_time__.ok_or_else(|| serde::de::Error::missing_field("serverTime"))?,
+                })
+            }
+        }
+        deserializer.deserialize_struct("entries.serverschema.TokenPair", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for UserBootstrap {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let len = 4;
+        let mut struct_ser = serializer.serialize_struct("entries.serverschema.UserBootstrap", len)?;
+        struct_ser.serialize_field("preferences", &self.preferences)?;
+        struct_ser.serialize_field("keystore", &self.keystore)?;
+        struct_ser.serialize_field("publicKey", &self.public_key)?;
+        struct_ser.serialize_field("isListedForDeletion", &self.is_listed_for_deletion)?;
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for UserBootstrap {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "preferences",
+            "keystore",
+            "public_key",
+            "publicKey",
+            "is_listed_for_deletion",
+            "isListedForDeletion",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            Preferences,
+            Keystore,
+            PublicKey,
+            IsListedForDeletion,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "preferences" => Ok(GeneratedField::Preferences),
+                            "keystore" => Ok(GeneratedField::Keystore),
+                            "publicKey" | "public_key" => Ok(GeneratedField::PublicKey),
+                            "isListedForDeletion" | "is_listed_for_deletion" => Ok(GeneratedField::IsListedForDeletion),
+                            _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = UserBootstrap;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct entries.serverschema.UserBootstrap")
+            }
+
+            fn visit_map<V>(self, mut map_: V) -> std::result::Result<UserBootstrap, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut preferences__ = None;
+                let mut keystore__ = None;
+                let mut public_key__ = None;
+                let mut is_listed_for_deletion__ = None;
+                while let Some(k) = map_.next_key()? {
+                    match k {
+                        GeneratedField::Preferences => {
+                            if preferences__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("preferences"));
+                            }
+                            preferences__ = map_.next_value()?;
+                        }
+                        GeneratedField::Keystore => {
+                            if keystore__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("keystore"));
+                            }
+                            keystore__ = map_.next_value()?;
+                        }
+                        GeneratedField::PublicKey => {
+                            if public_key__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("publicKey"));
+                            }
+                            public_key__ = map_.next_value()?;
+                        }
+                        GeneratedField::IsListedForDeletion => {
+                            if is_listed_for_deletion__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("isListedForDeletion"));
+                            }
+                            is_listed_for_deletion__ = Some(map_.next_value()?);
+                        }
+                    }
+                }
+                Ok(UserBootstrap {
+                    preferences: preferences__.ok_or_else(|| serde::de::Error::missing_field("preferences"))?,
+                    keystore: keystore__.ok_or_else(|| serde::de::Error::missing_field("keystore"))?,
+                    public_key: public_key__.ok_or_else(|| serde::de::Error::missing_field("publicKey"))?,
+                    is_listed_for_deletion: is_listed_for_deletion__.ok_or_else(|| serde::de::Error::missing_field("isListedForDeletion"))?,
+                })
+            }
+        }
+        deserializer.deserialize_struct("entries.serverschema.UserBootstrap", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for UserInvitationToBudget {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let len = 10;
+        let mut struct_ser = serializer.serialize_struct("entries.serverschema.UserInvitationToBudget", len)?;
+        struct_ser.serialize_field("recipientUserEmail", &self.recipient_user_email)?;
+        struct_ser.serialize_field("recipientPublicKeyIdUsedBySender", &self.recipient_public_key_id_used_by_sender)?;
+        struct_ser.serialize_field("recipientPublicKeyIdUsedByServer", &self.recipient_public_key_id_used_by_server)?;
+        #[allow(clippy::needless_borrow)]
+        struct_ser.serialize_field("senderPublicKey", pbjson::private::base64::encode(&self.sender_public_key).as_str())?;
+        #[allow(clippy::needless_borrow)]
+        struct_ser.serialize_field("encryptionKeyEncrypted", pbjson::private::base64::encode(&self.encryption_key_encrypted).as_str())?;
+        #[allow(clippy::needless_borrow)]
+        struct_ser.serialize_field("budgetInfoEncrypted", pbjson::private::base64::encode(&self.budget_info_encrypted).as_str())?;
+        #[allow(clippy::needless_borrow)]
+        struct_ser.serialize_field("senderInfoEncrypted", pbjson::private::base64::encode(&self.sender_info_encrypted).as_str())?;
+        #[allow(clippy::needless_borrow)]
+        struct_ser.serialize_field("shareInfoSymmetricKeyEncrypted", pbjson::private::base64::encode(&self.share_info_symmetric_key_encrypted).as_str())?;
+        struct_ser.serialize_field("expiration", &self.expiration)?;
+        struct_ser.serialize_field("readOnly", &self.read_only)?;
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for UserInvitationToBudget {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "recipient_user_email",
+            "recipientUserEmail",
+            "recipient_public_key_id_used_by_sender",
+            "recipientPublicKeyIdUsedBySender",
+            "recipient_public_key_id_used_by_server",
+            "recipientPublicKeyIdUsedByServer",
+            "sender_public_key",
+            "senderPublicKey",
+            "encryption_key_encrypted",
+            "encryptionKeyEncrypted",
+            "budget_info_encrypted",
+            "budgetInfoEncrypted",
+            "sender_info_encrypted",
+            "senderInfoEncrypted",
+            "share_info_symmetric_key_encrypted",
+            "shareInfoSymmetricKeyEncrypted",
+            "expiration",
+            "read_only",
+            "readOnly",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            RecipientUserEmail,
+            RecipientPublicKeyIdUsedBySender,
+            RecipientPublicKeyIdUsedByServer,
+            SenderPublicKey,
+            EncryptionKeyEncrypted,
+            BudgetInfoEncrypted,
+            SenderInfoEncrypted,
+            ShareInfoSymmetricKeyEncrypted,
+            Expiration,
+            ReadOnly,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "recipientUserEmail" | "recipient_user_email" => Ok(GeneratedField::RecipientUserEmail),
+                            "recipientPublicKeyIdUsedBySender" | "recipient_public_key_id_used_by_sender" => Ok(GeneratedField::RecipientPublicKeyIdUsedBySender),
+                            "recipientPublicKeyIdUsedByServer" | "recipient_public_key_id_used_by_server" => Ok(GeneratedField::RecipientPublicKeyIdUsedByServer),
+                            "senderPublicKey" | "sender_public_key" => Ok(GeneratedField::SenderPublicKey),
+                            "encryptionKeyEncrypted" | "encryption_key_encrypted" => Ok(GeneratedField::EncryptionKeyEncrypted),
+                            "budgetInfoEncrypted" | "budget_info_encrypted" => Ok(GeneratedField::BudgetInfoEncrypted),
+                            "senderInfoEncrypted" | "sender_info_encrypted" => Ok(GeneratedField::SenderInfoEncrypted),
+                            "shareInfoSymmetricKeyEncrypted" | "share_info_symmetric_key_encrypted" => Ok(GeneratedField::ShareInfoSymmetricKeyEncrypted),
+                            "expiration" => Ok(GeneratedField::Expiration),
+                            "readOnly" | "read_only" => Ok(GeneratedField::ReadOnly),
+                            _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = UserInvitationToBudget;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct entries.serverschema.UserInvitationToBudget")
+            }
+
+            fn visit_map<V>(self, mut map_: V) -> std::result::Result<UserInvitationToBudget, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut recipient_user_email__ = None;
+                let mut recipient_public_key_id_used_by_sender__ = None;
+                let mut recipient_public_key_id_used_by_server__ = None;
+                let mut sender_public_key__ = None;
+                let mut encryption_key_encrypted__ = None;
+                let mut budget_info_encrypted__ = None;
+                let mut sender_info_encrypted__ = None;
+                let mut share_info_symmetric_key_encrypted__ = None;
+                let mut expiration__ = None;
+                let mut read_only__ = None;
+                while let Some(k) = map_.next_key()? {
+                    match k {
+                        GeneratedField::RecipientUserEmail => {
+                            if recipient_user_email__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("recipientUserEmail"));
+                            }
+                            recipient_user_email__ = Some(map_.next_value()?);
+                        }
+                        GeneratedField::RecipientPublicKeyIdUsedBySender => {
+                            if recipient_public_key_id_used_by_sender__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("recipientPublicKeyIdUsedBySender"));
+                            }
+                            recipient_public_key_id_used_by_sender__ = map_.next_value()?;
+                        }
+                        GeneratedField::RecipientPublicKeyIdUsedByServer => {
+                            if recipient_public_key_id_used_by_server__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("recipientPublicKeyIdUsedByServer"));
+                            }
+                            recipient_public_key_id_used_by_server__ = map_.next_value()?;
+                        }
+                        GeneratedField::SenderPublicKey => {
+                            if sender_public_key__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("senderPublicKey"));
+                            }
+                            sender_public_key__ = 
+                                Some(map_.next_value::<::pbjson::private::BytesDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::EncryptionKeyEncrypted => {
+                            if encryption_key_encrypted__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("encryptionKeyEncrypted"));
+                            }
+                            encryption_key_encrypted__ = 
+                                Some(map_.next_value::<::pbjson::private::BytesDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::BudgetInfoEncrypted => {
+                            if budget_info_encrypted__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("budgetInfoEncrypted"));
+                            }
+                            budget_info_encrypted__ = 
+                                Some(map_.next_value::<::pbjson::private::BytesDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::SenderInfoEncrypted => {
+                            if sender_info_encrypted__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("senderInfoEncrypted"));
+                            }
+                            sender_info_encrypted__ = 
+                                Some(map_.next_value::<::pbjson::private::BytesDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::ShareInfoSymmetricKeyEncrypted => {
+                            if share_info_symmetric_key_encrypted__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("shareInfoSymmetricKeyEncrypted"));
+                            }
+                            share_info_symmetric_key_encrypted__ = 
+                                Some(map_.next_value::<::pbjson::private::BytesDeserialize<_>>()?.0)
+                            ;
+                        }
+                        GeneratedField::Expiration => {
+                            if expiration__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("expiration"));
+                            }
+                            expiration__ = map_.next_value()?;
+                        }
+                        GeneratedField::ReadOnly => {
+                            if read_only__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("readOnly"));
+                            }
+                            read_only__ = Some(map_.next_value()?);
+                        }
+                    }
+                }
+                Ok(UserInvitationToBudget {
+                    recipient_user_email: recipient_user_email__.ok_or_else(|| serde::de::Error::missing_field("recipientUserEmail"))?,
+                    recipient_public_key_id_used_by_sender: recipient_public_key_id_used_by_sender__.ok_or_else(|| serde::de::Error::missing_field("recipientPublicKeyIdUsedBySender"))?,
+                    recipient_public_key_id_used_by_server: recipient_public_key_id_used_by_server__.ok_or_else(|| serde::de::Error::missing_field("recipientPublicKeyIdUsedByServer"))?,
+                    sender_public_key: sender_public_key__.ok_or_else(|| serde::de::Error::missing_field("senderPublicKey"))?,
+                    encryption_key_encrypted: encryption_key_encrypted__.ok_or_else(|| serde::de::Error::missing_field("encryptionKeyEncrypted"))?,
+                    budget_info_encrypted: budget_info_encrypted__.ok_or_else(|| serde::de::Error::missing_field("budgetInfoEncrypted"))?,
+                    sender_info_encrypted: sender_info_encrypted__.ok_or_else(|| serde::de::Error::missing_field("senderInfoEncrypted"))?,
+                    share_info_symmetric_key_encrypted: share_info_symmetric_key_encrypted__.ok_or_else(|| serde::de::Error::missing_field("shareInfoSymmetricKeyEncrypted"))?,
+                    expiration: expiration__.ok_or_else(|| serde::de::Error::missing_field("expiration"))?,
+                    read_only: read_only__.ok_or_else(|| serde::de::Error::missing_field("readOnly"))?,
+                })
+            }
+        }
+        deserializer.deserialize_struct("entries.serverschema.UserInvitationToBudget", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for UserPublicKey {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let len = 2;
+        let mut struct_ser = serializer.serialize_struct("entries.serverschema.UserPublicKey", len)?;
+        struct_ser.serialize_field("id", &self.id)?;
+        #[allow(clippy::needless_borrow)]
+        struct_ser.serialize_field("value", pbjson::private::base64::encode(&self.value).as_str())?;
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for UserPublicKey {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "id",
+            "value",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            Id,
+            Value,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "id" => Ok(GeneratedField::Id),
+                            "value" => Ok(GeneratedField::Value),
+                            _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = UserPublicKey;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct entries.serverschema.UserPublicKey")
+            }
+
+            fn visit_map<V>(self, mut map_: V) -> std::result::Result<UserPublicKey, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut id__ = None;
+                let mut value__ = None;
+                while let Some(k) = map_.next_key()? {
+                    match k {
+                        GeneratedField::Id => {
+                            if id__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("id"));
+                            }
+                            id__ = map_.next_value()?;
+                        }
+                        GeneratedField::Value => {
+                            if value__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("value"));
+                            }
+                            value__ = 
+                                Some(map_.next_value::<::pbjson::private::BytesDeserialize<_>>()?.0)
+                            ;
+                        }
+                    }
+                }
+                Ok(UserPublicKey {
+                    id: id__.ok_or_else(|| serde::de::Error::missing_field("id"))?,
+                    value: value__.ok_or_else(|| serde::de::Error::missing_field("value"))?,
+                })
+            }
+        }
+        deserializer.deserialize_struct("entries.serverschema.UserPublicKey", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for Uuid {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let len = 1;
+        let mut struct_ser = serializer.serialize_struct("entries.serverschema.Uuid", len)?;
+        #[allow(clippy::needless_borrow)]
+        struct_ser.serialize_field("value", pbjson::private::base64::encode(&self.value).as_str())?;
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for Uuid {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "value",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            Value,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "value" => Ok(GeneratedField::Value),
+                            _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = Uuid;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct entries.serverschema.Uuid")
+            }
+
+            fn visit_map<V>(self, mut map_: V) -> std::result::Result<Uuid, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut value__ = None;
+                while let Some(k) = map_.next_key()? {
+                    match k {
+                        GeneratedField::Value => {
+                            if value__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("value"));
+                            }
+                            value__ = 
+                                Some(map_.next_value::<::pbjson::private::BytesDeserialize<_>>()?.0)
+                            ;
+                        }
+                    }
+                }
+                Ok(Uuid {
+                    value: value__.ok_or_else(|| serde::de::Error::missing_field("value"))?,
+                })
+            }
+        }
+        deserializer.deserialize_struct("entries.serverschema.Uuid", FIELDS, GeneratedVisitor)
+    }
+}
+impl serde::Serialize for VerificationEmailSent {
+    #[allow(deprecated)]
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let len = 2;
+        let mut struct_ser = serializer.serialize_struct("entries.serverschema.VerificationEmailSent", len)?;
+        struct_ser.serialize_field("emailSent", &self.email_sent)?;
+        #[allow(clippy::needless_borrow)]
+        struct_ser.serialize_field("emailTokenLifetimeHours", ToString::to_string(&self.email_token_lifetime_hours).as_str())?;
+        struct_ser.end()
+    }
+}
+impl<'de> serde::Deserialize<'de> for VerificationEmailSent {
+    #[allow(deprecated)]
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        const FIELDS: &[&str] = &[
+            "email_sent",
+            "emailSent",
+            "email_token_lifetime_hours",
+            "emailTokenLifetimeHours",
+        ];
+
+        #[allow(clippy::enum_variant_names)]
+        enum GeneratedField {
+            EmailSent,
+            EmailTokenLifetimeHours,
+        }
+        impl<'de> serde::Deserialize<'de> for GeneratedField {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<GeneratedField, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct GeneratedVisitor;
+
+                impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+                    type Value = GeneratedField;
+
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                        write!(formatter, "expected one of: {:?}", &FIELDS)
+                    }
+
+                    #[allow(unused_variables)]
+                    fn visit_str<E>(self, value: &str) -> std::result::Result<GeneratedField, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        match value {
+                            "emailSent" | "email_sent" => Ok(GeneratedField::EmailSent),
+                            "emailTokenLifetimeHours" | "email_token_lifetime_hours" => Ok(GeneratedField::EmailTokenLifetimeHours),
+                            _ => Err(serde::de::Error::unknown_field(value, FIELDS)),
+                        }
+                    }
+                }
+                deserializer.deserialize_identifier(GeneratedVisitor)
+            }
+        }
+        struct GeneratedVisitor;
+        impl<'de> serde::de::Visitor<'de> for GeneratedVisitor {
+            type Value = VerificationEmailSent;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                formatter.write_str("struct entries.serverschema.VerificationEmailSent")
+            }
+
+            fn visit_map<V>(self, mut map_: V) -> std::result::Result<VerificationEmailSent, V::Error>
+                where
+                    V: serde::de::MapAccess<'de>,
+            {
+                let mut email_sent__ = None;
+                let mut email_token_lifetime_hours__ = None;
+                while let Some(k) = map_.next_key()? {
+                    match k {
+                        GeneratedField::EmailSent => {
+                            if email_sent__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("emailSent"));
+                            }
+                            email_sent__ = Some(map_.next_value()?);
+                        }
+                        GeneratedField::EmailTokenLifetimeHours => {
+                            if email_token_lifetime_hours__.is_some() {
+                                return Err(serde::de::Error::duplicate_field("emailTokenLifetimeHours"));
+                            }
+                            email_token_lifetime_hours__ = 
+                                Some(map_.next_value::<::pbjson::private::NumberDeserialize<_>>()?.0)
+                            ;
+                        }
+                    }
+                }
+                Ok(VerificationEmailSent {
+                    email_sent: email_sent__.ok_or_else(|| serde::de::Error::missing_field("emailSent"))?,
+                    email_token_lifetime_hours: email_token_lifetime_hours__.ok_or_else(|| serde::de::Error::missing_field("emailTokenLifetimeHours"))?,
+                })
+            }
+        }
+        deserializer.deserialize_struct("entries.serverschema.VerificationEmailSent", FIELDS, GeneratedVisitor)
+    }
+}
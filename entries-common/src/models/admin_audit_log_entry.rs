@@ -0,0 +1,32 @@
+use diesel::{Insertable, Queryable};
+use serde::{Deserialize, Serialize};
+use std::time::SystemTime;
+use uuid::Uuid;
+
+use crate::schema::admin_audit_log;
+
+#[derive(Clone, Debug, Serialize, Deserialize, Identifiable, Queryable)]
+#[diesel(table_name = admin_audit_log)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct AdminAuditLogEntry {
+    pub id: Uuid,
+    pub timestamp: SystemTime,
+    pub operator: String,
+    pub action: String,
+    pub target_user_email: String,
+    pub details: String,
+    pub succeeded: bool,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = admin_audit_log)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct NewAdminAuditLogEntry<'a> {
+    pub id: Uuid,
+    pub timestamp: SystemTime,
+    pub operator: &'a str,
+    pub action: &'a str,
+    pub target_user_email: &'a str,
+    pub details: &'a str,
+    pub succeeded: bool,
+}
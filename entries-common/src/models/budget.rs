@@ -5,7 +5,7 @@ use uuid::Uuid;
 
 use crate::schema::budgets;
 
-#[derive(Debug, Serialize, Deserialize, Identifiable, Queryable, QueryableByName)]
+#[derive(Clone, Debug, Serialize, Deserialize, Identifiable, Queryable, QueryableByName)]
 #[diesel(table_name = budgets)]
 #[diesel(check_for_backend(diesel::pg::Pg))]
 pub struct Budget {
@@ -0,0 +1,30 @@
+use diesel::{Insertable, Queryable};
+use std::time::SystemTime;
+use uuid::Uuid;
+
+use crate::schema::idempotency_keys;
+
+#[derive(Clone, Debug, Identifiable, Queryable)]
+#[diesel(table_name = idempotency_keys, primary_key(user_id, key))]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct IdempotencyKey {
+    pub user_id: Uuid,
+    pub key: String,
+    pub request_hash: Vec<u8>,
+    pub reserved_at: SystemTime,
+    pub response_status: Option<i16>,
+    pub response_content_type: Option<String>,
+    pub response_body: Option<Vec<u8>>,
+    pub expiration: SystemTime,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = idempotency_keys, primary_key(user_id, key))]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct NewIdempotencyKey<'a> {
+    pub user_id: Uuid,
+    pub key: &'a str,
+    pub request_hash: &'a [u8],
+    pub reserved_at: SystemTime,
+    pub expiration: SystemTime,
+}
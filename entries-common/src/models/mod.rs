@@ -1,3 +1,4 @@
+pub mod admin_audit_log_entry;
 pub mod blacklisted_token;
 pub mod budget;
 pub mod budget_accept_key;
@@ -5,6 +6,7 @@ pub mod budget_access_key;
 pub mod budget_share_invite;
 pub mod category;
 pub mod entry;
+pub mod idempotency_key;
 pub mod job_registry_item;
 pub mod signin_nonce;
 pub mod user;
@@ -1,5 +1,17 @@
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    admin_audit_log (id) {
+        id -> Uuid,
+        timestamp -> Timestamp,
+        operator -> Text,
+        action -> Text,
+        target_user_email -> Text,
+        details -> Text,
+        succeeded -> Bool,
+    }
+}
+
 diesel::table! {
     blacklisted_tokens (token_signature) {
         token_signature -> Bytea,
@@ -74,6 +86,19 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    idempotency_keys (user_id, key) {
+        user_id -> Uuid,
+        key -> Text,
+        request_hash -> Bytea,
+        reserved_at -> Timestamp,
+        response_status -> Nullable<Int2>,
+        response_content_type -> Nullable<Text>,
+        response_body -> Nullable<Bytea>,
+        expiration -> Timestamp,
+    }
+}
+
 diesel::table! {
     job_registry (job_name) {
         job_name -> Text,
@@ -167,6 +192,7 @@ diesel::joinable!(budget_access_keys -> budgets (budget_id));
 diesel::joinable!(categories -> budgets (budget_id));
 diesel::joinable!(entries -> budgets (budget_id));
 diesel::joinable!(entries -> categories (category_id));
+diesel::joinable!(idempotency_keys -> users (user_id));
 diesel::joinable!(user_backup_codes -> users (user_id));
 diesel::joinable!(user_deletion_request_budget_keys -> users (user_id));
 diesel::joinable!(user_deletion_requests -> users (user_id));
@@ -174,6 +200,7 @@ diesel::joinable!(user_keystores -> users (user_id));
 diesel::joinable!(user_preferences -> users (user_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
+    admin_audit_log,
     blacklisted_tokens,
     budget_accept_keys,
     budget_access_keys,
@@ -181,6 +208,7 @@ diesel::allow_tables_to_appear_in_same_query!(
     budgets,
     categories,
     entries,
+    idempotency_keys,
     job_registry,
     signin_nonces,
     user_backup_codes,
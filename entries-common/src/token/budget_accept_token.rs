@@ -1,5 +1,7 @@
 use crate::token::{Ed25519Verifier, Expiring, Token};
 
+use ed25519_dalek as ed25519;
+
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -23,6 +25,12 @@ impl Expiring for BudgetAcceptTokenClaims {
 
 pub struct BudgetAcceptToken {}
 
+impl BudgetAcceptToken {
+    pub fn sign_new(claims: BudgetAcceptTokenClaims, signing_key: &ed25519::SigningKey) -> String {
+        super::sign_ed25519(&claims, signing_key)
+    }
+}
+
 impl Token for BudgetAcceptToken {
     type Claims = BudgetAcceptTokenClaims;
     type Verifier = Ed25519Verifier;
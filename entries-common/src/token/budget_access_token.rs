@@ -1,5 +1,7 @@
 use crate::token::{Ed25519Verifier, Expiring, Token};
 
+use ed25519_dalek as ed25519;
+
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -21,6 +23,12 @@ impl Expiring for BudgetAccessTokenClaims {
 
 pub struct BudgetAccessToken {}
 
+impl BudgetAccessToken {
+    pub fn sign_new(claims: BudgetAccessTokenClaims, signing_key: &ed25519::SigningKey) -> String {
+        super::sign_ed25519(&claims, signing_key)
+    }
+}
+
 impl Token for BudgetAccessToken {
     type Claims = BudgetAccessTokenClaims;
     type Verifier = Ed25519Verifier;
@@ -115,4 +123,36 @@ mod tests {
             .verify(&pub_key)
             .is_err());
     }
+
+    #[test]
+    fn test_sign_new() {
+        let kid = Uuid::now_v7();
+        let bid = Uuid::now_v7();
+        let exp = (SystemTime::now() + Duration::from_secs(10))
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+
+        let keypair = ed25519::SigningKey::generate(&mut rand::rngs::OsRng);
+        let pub_key = keypair.verifying_key().to_bytes();
+
+        let token = BudgetAccessToken::sign_new(
+            BudgetAccessTokenClaims {
+                key_id: kid,
+                budget_id: bid,
+                expiration: exp,
+            },
+            &keypair,
+        );
+
+        let t = BudgetAccessToken::decode(&token).unwrap();
+        let verified_claims = t.verify(&pub_key).unwrap();
+
+        assert_eq!(verified_claims.key_id, kid);
+        assert_eq!(verified_claims.budget_id, bid);
+        assert_eq!(verified_claims.expiration, exp);
+
+        let other_keypair = ed25519::SigningKey::generate(&mut rand::rngs::OsRng);
+        assert!(t.verify(&other_keypair.verifying_key().to_bytes()).is_err());
+    }
 }
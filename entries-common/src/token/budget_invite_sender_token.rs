@@ -1,5 +1,7 @@
 use crate::token::{Ed25519Verifier, Expiring, Token};
 
+use ed25519_dalek as ed25519;
+
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -19,6 +21,15 @@ impl Expiring for BudgetInviteSenderTokenClaims {
 
 pub struct BudgetInviteSenderToken {}
 
+impl BudgetInviteSenderToken {
+    pub fn sign_new(
+        claims: BudgetInviteSenderTokenClaims,
+        signing_key: &ed25519::SigningKey,
+    ) -> String {
+        super::sign_ed25519(&claims, signing_key)
+    }
+}
+
 impl Token for BudgetInviteSenderToken {
     type Claims = BudgetInviteSenderTokenClaims;
     type Verifier = Ed25519Verifier;
@@ -6,8 +6,10 @@ pub mod budget_invite_sender_token;
 use base64::engine::general_purpose::URL_SAFE as b64_urlsafe;
 use base64::Engine;
 use ed25519_dalek as ed25519;
+use ed25519_dalek::Signer;
 use hmac::{Hmac, Mac};
 use serde::de::DeserializeOwned;
+use serde::Serialize;
 use sha2::Sha256;
 use std::fmt::Debug;
 use std::marker::PhantomData;
@@ -147,6 +149,17 @@ impl TokenSignatureVerifier for Ed25519Verifier {
     }
 }
 
+/// Signs claims the way clients sign budget tokens, for tokens verified by `Ed25519Verifier`
+fn sign_ed25519<C: Serialize>(claims: &C, signing_key: &ed25519::SigningKey) -> String {
+    let mut token_unencoded =
+        serde_json::to_vec(claims).expect("Failed to transform claims into JSON");
+
+    let signature = signing_key.sign(&token_unencoded);
+    token_unencoded.extend_from_slice(&signature.to_bytes());
+
+    b64_urlsafe.encode(&token_unencoded)
+}
+
 #[derive(Debug)]
 pub struct HmacSha256Verifier {}
 
@@ -1,3 +1,4 @@
+use entries_common::config::{ConfigError, ConfigSource};
 use once_cell::sync::Lazy;
 use std::cell::UnsafeCell;
 use std::fmt;
@@ -6,6 +7,8 @@ use std::str::FromStr;
 use std::time::Duration;
 use zeroize::Zeroize;
 
+const DB_BACKEND_VAR: &str = "ENTRIES_DB_BACKEND";
+const DB_SQLITE_PATH_VAR: &str = "ENTRIES_DB_SQLITE_PATH";
 const DB_USERNAME_VAR: &str = "ENTRIES_DB_USERNAME";
 const DB_PASSWORD_VAR: &str = "ENTRIES_DB_PASSWORD";
 const DB_HOSTNAME_VAR: &str = "ENTRIES_DB_HOSTNAME";
@@ -17,9 +20,12 @@ const DB_IDLE_TIMEOUT_SECS_VAR: &str = "ENTRIES_DB_IDLE_TIMEOUT_SECS";
 const UPDATE_FREQUENCY_MS_VAR: &str = "ENTRIES_JOB_RUNNER_UPDATE_FREQUENCY_MS";
 const WORKER_THREADS_VAR: &str = "ENTRIES_JOB_RUNNER_WORKER_THREADS";
 const MAX_BLOCKING_THREADS_VAR: &str = "ENTRIES_JOB_RUNNER_MAX_BLOCKING_THREADS";
+const SHUTDOWN_TIMEOUT_SECS_VAR: &str = "ENTRIES_JOB_RUNNER_SHUTDOWN_TIMEOUT_SECS";
 
 const CLEAR_EXPIRED_BUDGET_INVITES_JOB_FREQUENCY_SECS_VAR: &str =
     "ENTRIES_CLEAR_EXPIRED_BUDGET_INVITES_JOB_FREQUENCY_SECS";
+const CLEAR_EXPIRED_IDEMPOTENCY_KEYS_JOB_FREQUENCY_SECS_VAR: &str =
+    "ENTRIES_CLEAR_EXPIRED_IDEMPOTENCY_KEYS_JOB_FREQUENCY_SECS";
 const CLEAR_EXPIRED_OTPS_JOB_FREQUENCY_SECS_VAR: &str =
     "ENTRIES_CLEAR_EXPIRED_OTPS_JOB_FREQUENCY_SECS";
 const CLEAR_OLD_USER_DELETION_REQUESTS_JOB_FREQUENCY_SECS_VAR: &str =
@@ -34,7 +40,7 @@ const UNBLACKLIST_EXPIRED_TOKENS_JOB_FREQUENCY_SECS_VAR: &str =
 
 const LOG_LEVEL_VAR: &str = "ENTRIES_LOG_LEVEL";
 
-pub static CONF: Lazy<Config> = Lazy::new(|| match Config::from_env() {
+pub static CONF: Lazy<Config> = Lazy::new(|| match Config::load() {
     Ok(c) => c,
     Err(e) => {
         eprintln!("ERROR: Failed to load configuration: {e}");
@@ -49,6 +55,9 @@ pub static CONF: Lazy<Config> = Lazy::new(|| match Config::from_env() {
 
 #[derive(Zeroize)]
 pub struct ConfigInner {
+    #[zeroize(skip)]
+    pub db_backend: DbBackend,
+    pub db_sqlite_path: String,
     pub db_username: String,
     pub db_password: String,
     pub db_hostname: String,
@@ -65,10 +74,14 @@ pub struct ConfigInner {
     pub worker_threads: usize,
     #[zeroize(skip)]
     pub max_blocking_threads: usize,
+    #[zeroize(skip)]
+    pub shutdown_timeout: Duration,
 
     #[zeroize(skip)]
     pub clear_expired_budget_invites_job_frequency: Duration,
     #[zeroize(skip)]
+    pub clear_expired_idempotency_keys_job_frequency: Duration,
+    #[zeroize(skip)]
     pub clear_expired_otps_job_frequency: Duration,
     #[zeroize(skip)]
     pub clear_old_user_deletion_requests_job_frequency: Duration,
@@ -102,44 +115,72 @@ impl Deref for Config {
 unsafe impl Sync for Config {}
 
 impl Config {
-    pub fn from_env() -> Result<Config, ConfigError> {
+    pub fn load() -> Result<Config, ConfigError> {
+        Self::from_source(&ConfigSource::load()?)
+    }
+
+    pub fn from_source(source: &ConfigSource) -> Result<Config, ConfigError> {
+        // Postgres connection settings aren't needed when the data lives in a SQLite file
+        let db_backend = source.get_or(DB_BACKEND_VAR, DbBackend::Postgres)?;
+        let (db_username, db_password, db_hostname, db_port, db_name) = match db_backend {
+            DbBackend::Postgres => (
+                source.get(DB_USERNAME_VAR)?,
+                source.get_secret(DB_PASSWORD_VAR)?,
+                source.get(DB_HOSTNAME_VAR)?,
+                source.get(DB_PORT_VAR)?,
+                source.get(DB_NAME_VAR)?,
+            ),
+            #[cfg(feature = "sqlite")]
+            DbBackend::Sqlite => (
+                String::new(),
+                String::new(),
+                String::new(),
+                0,
+                String::new(),
+            ),
+        };
+
         let inner = ConfigInner {
-            db_username: env_var(DB_USERNAME_VAR)?,
-            db_password: env_var(DB_PASSWORD_VAR)?,
-            db_hostname: env_var(DB_HOSTNAME_VAR)?,
-            db_port: env_var(DB_PORT_VAR)?,
-            db_name: env_var(DB_NAME_VAR)?,
-            db_max_connections: env_var_or(DB_MAX_CONNECTIONS_VAR, 48)?,
-            db_idle_timeout: Duration::from_secs(env_var_or(DB_IDLE_TIMEOUT_SECS_VAR, 30)?),
-
-            update_frequency: Duration::from_millis(env_var_or(UPDATE_FREQUENCY_MS_VAR, 5)?),
-            worker_threads: env_var_or(WORKER_THREADS_VAR, num_cpus::get())?,
-            max_blocking_threads: env_var_or(MAX_BLOCKING_THREADS_VAR, 40)?,
-
-            clear_expired_budget_invites_job_frequency: Duration::from_secs(env_var(
-                CLEAR_EXPIRED_BUDGET_INVITES_JOB_FREQUENCY_SECS_VAR,
-            )?),
-            clear_expired_otps_job_frequency: Duration::from_secs(env_var(
-                CLEAR_EXPIRED_OTPS_JOB_FREQUENCY_SECS_VAR,
-            )?),
-            clear_old_user_deletion_requests_job_frequency: Duration::from_secs(env_var(
-                CLEAR_OLD_USER_DELETION_REQUESTS_JOB_FREQUENCY_SECS_VAR,
-            )?),
-            clear_unverified_users_job_frequency: Duration::from_secs(env_var(
-                CLEAR_UNVERIFIED_USERS_JOB_FREQUENCY_SECS_VAR,
-            )?),
-            clear_unverified_users_max_user_age_days: env_var_or(
-                CLEAR_UNVERIFIED_USERS_MAX_USER_AGE_DAYS_VAR,
-                7,
-            )?,
-            delete_users_job_frequency: Duration::from_secs(env_var(
-                DELETE_USERS_JOB_FREQUENCY_SECS_VAR,
-            )?),
-            unblacklist_expired_tokens_job_frequency: Duration::from_secs(env_var(
-                UNBLACKLIST_EXPIRED_TOKENS_JOB_FREQUENCY_SECS_VAR,
-            )?),
-
-            log_level: env_var_or(LOG_LEVEL_VAR, String::from("info"))?,
+            db_backend,
+            db_sqlite_path: source.get_or(DB_SQLITE_PATH_VAR, String::from("./entries.db"))?,
+            db_username,
+            db_password,
+            db_hostname,
+            db_port,
+            db_name,
+            db_max_connections: source.get_or(DB_MAX_CONNECTIONS_VAR, 48)?,
+            db_idle_timeout: Duration::from_secs(source.get_or(DB_IDLE_TIMEOUT_SECS_VAR, 30)?),
+
+            update_frequency: Duration::from_millis(source.get_or(UPDATE_FREQUENCY_MS_VAR, 5)?),
+            worker_threads: source.get_or(WORKER_THREADS_VAR, num_cpus::get())?,
+            max_blocking_threads: source.get_or(MAX_BLOCKING_THREADS_VAR, 40)?,
+            shutdown_timeout: Duration::from_secs(source.get_or(SHUTDOWN_TIMEOUT_SECS_VAR, 30)?),
+
+            clear_expired_budget_invites_job_frequency: Duration::from_secs(
+                source.get(CLEAR_EXPIRED_BUDGET_INVITES_JOB_FREQUENCY_SECS_VAR)?,
+            ),
+            clear_expired_idempotency_keys_job_frequency: Duration::from_secs(
+                source.get_or(CLEAR_EXPIRED_IDEMPOTENCY_KEYS_JOB_FREQUENCY_SECS_VAR, 3600)?,
+            ),
+            clear_expired_otps_job_frequency: Duration::from_secs(
+                source.get(CLEAR_EXPIRED_OTPS_JOB_FREQUENCY_SECS_VAR)?,
+            ),
+            clear_old_user_deletion_requests_job_frequency: Duration::from_secs(
+                source.get(CLEAR_OLD_USER_DELETION_REQUESTS_JOB_FREQUENCY_SECS_VAR)?,
+            ),
+            clear_unverified_users_job_frequency: Duration::from_secs(
+                source.get(CLEAR_UNVERIFIED_USERS_JOB_FREQUENCY_SECS_VAR)?,
+            ),
+            clear_unverified_users_max_user_age_days: source
+                .get_or(CLEAR_UNVERIFIED_USERS_MAX_USER_AGE_DAYS_VAR, 7)?,
+            delete_users_job_frequency: Duration::from_secs(
+                source.get(DELETE_USERS_JOB_FREQUENCY_SECS_VAR)?,
+            ),
+            unblacklist_expired_tokens_job_frequency: Duration::from_secs(
+                source.get(UNBLACKLIST_EXPIRED_TOKENS_JOB_FREQUENCY_SECS_VAR)?,
+            ),
+
+            log_level: source.get_or(LOG_LEVEL_VAR, String::from("info"))?,
         };
 
         Ok(Config {
@@ -159,50 +200,97 @@ impl Config {
     }
 }
 
-fn env_var<T: FromStr>(key: &'static str) -> Result<T, ConfigError> {
-    let var = std::env::var(key).map_err(|_| ConfigError::missing(key))?;
-    let var: T = var.parse().map_err(|_| ConfigError::invalid(key))?;
-    Ok(var)
-}
-
-fn env_var_or<T: FromStr>(key: &'static str, default: T) -> Result<T, ConfigError> {
-    let Ok(var) = std::env::var(key) else {
-        return Ok(default);
+/// Loads and validates the configuration, then prints the effective configuration with
+/// secrets redacted. Returns whether the configuration is valid.
+pub fn check_config() -> bool {
+    let source = match ConfigSource::load() {
+        Ok(s) => s.collect_errors(),
+        Err(e) => {
+            eprintln!("ERROR: {e}");
+            return false;
+        }
     };
 
-    var.parse().map_err(|_| ConfigError::invalid(key))
-}
+    // Missing and invalid values are collected by the source instead of failing the load, so
+    // every problem can be reported at once
+    let config = Config::from_source(&source);
+    let errors: Vec<String> = source
+        .take_errors()
+        .iter()
+        .chain(config.as_ref().err())
+        .map(ToString::to_string)
+        .collect();
 
-#[derive(Clone, Copy, Debug)]
-pub enum ConfigError {
-    MissingVar(&'static str),
-    InvalidVar(&'static str),
-}
+    match source.file_path() {
+        Some(path) => println!("Config file: {}", path.display()),
+        None => println!("Config file: none"),
+    }
 
-impl ConfigError {
-    fn missing(var_name: &'static str) -> Self {
-        Self::MissingVar(var_name)
+    println!();
+    print!("{}", source.effective_config());
+
+    let unused_keys = source.unused_file_keys();
+    if !unused_keys.is_empty() {
+        println!();
+        println!("Keys in the config file not used by this binary:");
+
+        for key in unused_keys {
+            println!("  {key}");
+        }
     }
 
-    fn invalid(var_name: &'static str) -> Self {
-        Self::InvalidVar(var_name)
+    for e in &errors {
+        eprintln!("ERROR: {e}");
     }
+
+    if let Ok(config) = config {
+        // Safe because this config is never shared
+        unsafe {
+            config.zeroize();
+        }
+    }
+
+    errors.is_empty()
 }
 
-impl std::error::Error for ConfigError {}
+/// The database the jobs run against. SQLite is only available when the job scheduler is built
+/// with the `sqlite` feature.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DbBackend {
+    Postgres,
+    #[cfg(feature = "sqlite")]
+    Sqlite,
+}
+
+impl FromStr for DbBackend {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "postgres" => Ok(DbBackend::Postgres),
+            #[cfg(feature = "sqlite")]
+            "sqlite" => Ok(DbBackend::Sqlite),
+            _ => Err(()),
+        }
+    }
+}
 
-impl fmt::Display for ConfigError {
+impl fmt::Display for DbBackend {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Self::MissingVar(key) => write!(f, "Missing environment variable '{}'", key),
-            Self::InvalidVar(key) => write!(f, "Environment variable '{}' is invalid", key),
+            DbBackend::Postgres => write!(f, "postgres"),
+            #[cfg(feature = "sqlite")]
+            DbBackend::Sqlite => write!(f, "sqlite"),
         }
     }
 }
 
 #[cfg(test)]
 pub mod testing {
+    use entries_common::db::user::UserDao;
     use entries_common::db::{create_db_thread_pool, DbThreadPool};
+    use rand::Rng;
+    use uuid::Uuid;
 
     use super::*;
 
@@ -214,6 +302,43 @@ pub mod testing {
             ),
             CONF.db_max_connections,
             CONF.db_idle_timeout,
+            None,
         )
     });
+
+    /// Creates an unverified user with a random email address, returning the user's ID and email
+    pub fn create_user(dao: &dyn UserDao) -> (Uuid, String) {
+        let user_number = rand::thread_rng().gen_range::<u128, _>(u128::MIN..u128::MAX);
+        let email = format!("test_user{}@test.com", &user_number);
+
+        let user_id = dao
+            .create_user(
+                &email,
+                "",
+                &[],
+                1024,
+                1,
+                2,
+                &[],
+                1024,
+                1,
+                2,
+                &[],
+                1024,
+                1,
+                2,
+                &[],
+                &[],
+                Uuid::now_v7(),
+                &[],
+                &[],
+                rand::thread_rng().gen(),
+                &[],
+                rand::thread_rng().gen(),
+                &[],
+            )
+            .unwrap();
+
+        (user_id, email)
+    }
 }
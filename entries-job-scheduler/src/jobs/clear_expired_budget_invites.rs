@@ -1,19 +1,19 @@
-use entries_common::db::budget::Dao as BudgetDao;
-use entries_common::db::DbThreadPool;
+use entries_common::db::budget::BudgetDao;
 
 use async_trait::async_trait;
+use std::sync::Arc;
 
 use crate::jobs::{Job, JobError};
 
 pub struct ClearExpiredBudgetInvitesJob {
-    db_thread_pool: DbThreadPool,
+    dao: Arc<dyn BudgetDao>,
     is_running: bool,
 }
 
 impl ClearExpiredBudgetInvitesJob {
-    pub fn new(db_thread_pool: DbThreadPool) -> Self {
+    pub fn new(dao: Arc<dyn BudgetDao>) -> Self {
         Self {
-            db_thread_pool,
+            dao,
             is_running: false,
         }
     }
@@ -32,7 +32,7 @@ impl Job for ClearExpiredBudgetInvitesJob {
     async fn execute(&mut self) -> Result<(), JobError> {
         self.is_running = true;
 
-        let dao = BudgetDao::new(&self.db_thread_pool);
+        let dao = Arc::clone(&self.dao);
         tokio::task::spawn_blocking(move || dao.delete_all_expired_invitations()).await??;
 
         self.is_running = false;
@@ -44,7 +44,8 @@ impl Job for ClearExpiredBudgetInvitesJob {
 mod tests {
     use super::*;
 
-    use entries_common::db::user;
+    use entries_common::db::user::{self, UserDao};
+    use entries_common::db::{budget, memory};
     use entries_common::messages::NewUser;
     use entries_common::models::budget::NewBudget;
     use entries_common::models::budget_accept_key::NewBudgetAcceptKey;
@@ -208,7 +209,9 @@ mod tests {
             .execute(&mut env::testing::DB_THREAD_POOL.get().unwrap())
             .unwrap();
 
-        let mut job = ClearExpiredBudgetInvitesJob::new(env::testing::DB_THREAD_POOL.clone());
+        let mut job = ClearExpiredBudgetInvitesJob::new(Arc::new(budget::Dao::new(
+            &env::testing::DB_THREAD_POOL,
+        )));
 
         assert_eq!(
             budget_share_invites::table
@@ -276,4 +279,56 @@ mod tests {
             1
         );
     }
+
+    #[tokio::test]
+    async fn test_execute_in_memory() {
+        let dao = memory::Dao::new();
+        let (_, user_email) = env::testing::create_user(&dao);
+
+        let budget = dao.create_budget(&[0; 32], 1, &[], &[0; 32]).unwrap();
+        let budget_id = (&budget.id).try_into().unwrap();
+
+        let invite = |expiration| {
+            let key_id = Uuid::now_v7();
+            dao.invite_user(
+                &user_email,
+                &[0; 4],
+                &[0; 4],
+                &[0; 4],
+                &[0; 4],
+                &[0; 4],
+                Uuid::now_v7(),
+                Uuid::now_v7(),
+                budget_id,
+                expiration,
+                false,
+                key_id,
+                &[0; 4],
+                &[0; 4],
+                &[0; 4],
+                &[0; 4],
+            )
+            .unwrap();
+            key_id
+        };
+
+        let exp_key_id = invite(SystemTime::now() - Duration::from_nanos(1));
+        let not_exp_key_id = invite(SystemTime::now() + Duration::from_secs(100));
+
+        let mut job = ClearExpiredBudgetInvitesJob::new(Arc::new(dao.clone()));
+        job.execute().await.unwrap();
+
+        assert!(dao
+            .get_budget_accept_public_key(exp_key_id, budget_id)
+            .is_err());
+        assert!(dao
+            .get_budget_accept_public_key(not_exp_key_id, budget_id)
+            .is_ok());
+
+        // Neither invitation is old enough to be cleared
+        assert_eq!(
+            dao.get_pending_invitation_ids(&user_email).unwrap().len(),
+            2
+        );
+    }
 }
@@ -0,0 +1,213 @@
+use entries_common::db::idempotency::IdempotencyDao;
+
+use async_trait::async_trait;
+use std::sync::Arc;
+
+use crate::jobs::{Job, JobError};
+
+pub struct ClearExpiredIdempotencyKeysJob {
+    dao: Arc<dyn IdempotencyDao>,
+    is_running: bool,
+}
+
+impl ClearExpiredIdempotencyKeysJob {
+    pub fn new(dao: Arc<dyn IdempotencyDao>) -> Self {
+        Self {
+            dao,
+            is_running: false,
+        }
+    }
+}
+
+#[async_trait]
+impl Job for ClearExpiredIdempotencyKeysJob {
+    fn name(&self) -> &'static str {
+        "Clear Expired Idempotency Keys"
+    }
+
+    fn is_ready(&self) -> bool {
+        !self.is_running
+    }
+
+    async fn execute(&mut self) -> Result<(), JobError> {
+        self.is_running = true;
+
+        let dao = Arc::clone(&self.dao);
+        tokio::task::spawn_blocking(move || dao.delete_all_expired_keys()).await??;
+
+        self.is_running = false;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use entries_common::db::idempotency::{self, KeyReservation};
+    use entries_common::db::memory;
+    use entries_common::db::user::{self, UserDao};
+    use entries_common::messages::NewUser;
+    use entries_common::schema::idempotency_keys;
+
+    use diesel::{QueryDsl, RunQueryDsl};
+    use rand::Rng;
+    use std::time::{Duration, SystemTime};
+    use uuid::Uuid;
+
+    use crate::env;
+
+    #[tokio::test]
+    async fn test_execute() {
+        let user_number = rand::thread_rng().gen_range::<u128, _>(u128::MIN..u128::MAX);
+
+        let public_key_id = Uuid::now_v7();
+        let new_user = NewUser {
+            email: format!("test_user{}@test.com", &user_number),
+            auth_string: Vec::new(),
+
+            auth_string_salt: Vec::new(),
+            auth_string_memory_cost_kib: 1024,
+            auth_string_parallelism_factor: 1,
+            auth_string_iters: 2,
+
+            password_encryption_salt: Vec::new(),
+            password_encryption_memory_cost_kib: 1024,
+            password_encryption_parallelism_factor: 1,
+            password_encryption_iters: 2,
+
+            recovery_key_salt: Vec::new(),
+            recovery_key_memory_cost_kib: 1024,
+            recovery_key_parallelism_factor: 1,
+            recovery_key_iters: 2,
+
+            encryption_key_encrypted_with_password: Vec::new(),
+            encryption_key_encrypted_with_recovery_key: Vec::new(),
+
+            public_key_id: public_key_id.into(),
+            public_key: Vec::new(),
+
+            preferences_encrypted: Vec::new(),
+            preferences_version_nonce: rand::thread_rng().gen(),
+            user_keystore_encrypted: Vec::new(),
+            user_keystore_version_nonce: rand::thread_rng().gen(),
+        };
+
+        let user_dao = user::Dao::new(&env::testing::DB_THREAD_POOL);
+
+        let user_id = user_dao
+            .create_user(
+                &new_user.email,
+                "",
+                &new_user.auth_string_salt,
+                new_user.auth_string_memory_cost_kib,
+                new_user.auth_string_parallelism_factor,
+                new_user.auth_string_iters,
+                &new_user.password_encryption_salt,
+                new_user.password_encryption_memory_cost_kib,
+                new_user.password_encryption_parallelism_factor,
+                new_user.password_encryption_iters,
+                &new_user.recovery_key_salt,
+                new_user.recovery_key_memory_cost_kib,
+                new_user.recovery_key_parallelism_factor,
+                new_user.recovery_key_iters,
+                &new_user.encryption_key_encrypted_with_password,
+                &new_user.encryption_key_encrypted_with_recovery_key,
+                public_key_id,
+                &new_user.public_key,
+                &new_user.preferences_encrypted,
+                new_user.preferences_version_nonce,
+                &new_user.user_keystore_encrypted,
+                new_user.user_keystore_version_nonce,
+                &Vec::new(),
+            )
+            .unwrap();
+        user_dao.verify_user_creation(user_id).unwrap();
+
+        let dao = idempotency::Dao::new(&env::testing::DB_THREAD_POOL);
+
+        let expired_key = Uuid::now_v7().to_string();
+        let unexpired_key = Uuid::now_v7().to_string();
+
+        dao.reserve_key(
+            user_id,
+            &expired_key,
+            &[1; 32],
+            SystemTime::now() - Duration::from_nanos(1),
+        )
+        .unwrap();
+        dao.reserve_key(
+            user_id,
+            &unexpired_key,
+            &[2; 32],
+            SystemTime::now() + Duration::from_secs(100),
+        )
+        .unwrap();
+
+        assert_eq!(
+            idempotency_keys::table
+                .find((user_id, &expired_key))
+                .execute(&mut env::testing::DB_THREAD_POOL.get().unwrap())
+                .unwrap(),
+            1
+        );
+
+        let mut job = ClearExpiredIdempotencyKeysJob::new(Arc::new(dao));
+        job.execute().await.unwrap();
+
+        assert_eq!(
+            idempotency_keys::table
+                .find((user_id, &expired_key))
+                .execute(&mut env::testing::DB_THREAD_POOL.get().unwrap())
+                .unwrap(),
+            0
+        );
+
+        assert_eq!(
+            idempotency_keys::table
+                .find((user_id, &unexpired_key))
+                .execute(&mut env::testing::DB_THREAD_POOL.get().unwrap())
+                .unwrap(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_execute_in_memory() {
+        let dao = memory::Dao::new();
+        let (user_id, _) = env::testing::create_user(&dao);
+
+        let expired_key = Uuid::now_v7().to_string();
+        let unexpired_key = Uuid::now_v7().to_string();
+
+        dao.reserve_key(
+            user_id,
+            &expired_key,
+            &[1; 32],
+            SystemTime::now() - Duration::from_nanos(1),
+        )
+        .unwrap();
+        dao.reserve_key(
+            user_id,
+            &unexpired_key,
+            &[2; 32],
+            SystemTime::now() + Duration::from_secs(100),
+        )
+        .unwrap();
+
+        let mut job = ClearExpiredIdempotencyKeysJob::new(Arc::new(dao.clone()));
+        job.execute().await.unwrap();
+
+        assert_eq!(dao.delete_all_expired_keys().unwrap(), 0);
+        assert!(matches!(
+            dao.reserve_key(
+                user_id,
+                &unexpired_key,
+                &[2; 32],
+                SystemTime::now() + Duration::from_secs(100),
+            )
+            .unwrap(),
+            KeyReservation::Existing(_)
+        ));
+    }
+}
@@ -1,19 +1,19 @@
-use entries_common::db::auth::Dao as AuthDao;
-use entries_common::db::DbThreadPool;
+use entries_common::db::auth::AuthDao;
 
 use async_trait::async_trait;
+use std::sync::Arc;
 
 use crate::jobs::{Job, JobError};
 
 pub struct ClearExpiredOtpsJob {
-    db_thread_pool: DbThreadPool,
+    dao: Arc<dyn AuthDao>,
     is_running: bool,
 }
 
 impl ClearExpiredOtpsJob {
-    pub fn new(db_thread_pool: DbThreadPool) -> Self {
+    pub fn new(dao: Arc<dyn AuthDao>) -> Self {
         Self {
-            db_thread_pool,
+            dao,
             is_running: false,
         }
     }
@@ -32,7 +32,7 @@ impl Job for ClearExpiredOtpsJob {
     async fn execute(&mut self) -> Result<(), JobError> {
         self.is_running = true;
 
-        let dao = AuthDao::new(&self.db_thread_pool);
+        let dao = Arc::clone(&self.dao);
         tokio::task::spawn_blocking(move || dao.delete_all_expired_otps()).await??;
 
         self.is_running = false;
@@ -44,7 +44,8 @@ impl Job for ClearExpiredOtpsJob {
 mod tests {
     use super::*;
 
-    use entries_common::db::user;
+    use entries_common::db::user::{self, UserDao};
+    use entries_common::db::{auth, memory};
     use entries_common::messages::NewUser;
     use entries_common::models::user_otp::NewUserOtp;
     use entries_common::schema::user_otps;
@@ -212,7 +213,8 @@ mod tests {
             .execute(&mut env::testing::DB_THREAD_POOL.get().unwrap())
             .unwrap();
 
-        let mut job = ClearExpiredOtpsJob::new(env::testing::DB_THREAD_POOL.clone());
+        let mut job =
+            ClearExpiredOtpsJob::new(Arc::new(auth::Dao::new(&env::testing::DB_THREAD_POOL)));
 
         assert_eq!(
             user_otps::table
@@ -252,4 +254,30 @@ mod tests {
             1
         );
     }
+
+    #[tokio::test]
+    async fn test_execute_in_memory() {
+        let dao = memory::Dao::new();
+        let (_, user1_email) = env::testing::create_user(&dao);
+        let (_, user2_email) = env::testing::create_user(&dao);
+
+        dao.save_otp(
+            "ABC123",
+            &user1_email,
+            SystemTime::now() - Duration::from_nanos(1),
+        )
+        .unwrap();
+        dao.save_otp(
+            "ABC456",
+            &user2_email,
+            SystemTime::now() + Duration::from_secs(100),
+        )
+        .unwrap();
+
+        let mut job = ClearExpiredOtpsJob::new(Arc::new(dao.clone()));
+        job.execute().await.unwrap();
+
+        assert_eq!(dao.delete_all_otps_for_user(&user1_email).unwrap(), 0);
+        assert_eq!(dao.delete_all_otps_for_user(&user2_email).unwrap(), 1);
+    }
 }
@@ -1,19 +1,19 @@
-use entries_common::db::user::Dao as UserDao;
-use entries_common::db::DbThreadPool;
+use entries_common::db::user::UserDao;
 
 use async_trait::async_trait;
+use std::sync::Arc;
 
 use crate::jobs::{Job, JobError};
 
 pub struct ClearOldUserDeletionRequestsJob {
-    db_thread_pool: DbThreadPool,
+    dao: Arc<dyn UserDao>,
     is_running: bool,
 }
 
 impl ClearOldUserDeletionRequestsJob {
-    pub fn new(db_thread_pool: DbThreadPool) -> Self {
+    pub fn new(dao: Arc<dyn UserDao>) -> Self {
         Self {
-            db_thread_pool,
+            dao,
             is_running: false,
         }
     }
@@ -32,7 +32,7 @@ impl Job for ClearOldUserDeletionRequestsJob {
     async fn execute(&mut self) -> Result<(), JobError> {
         self.is_running = true;
 
-        let dao = UserDao::new(&self.db_thread_pool);
+        let dao = Arc::clone(&self.dao);
         tokio::task::spawn_blocking(move || dao.delete_old_user_deletion_requests()).await??;
 
         self.is_running = false;
@@ -44,6 +44,8 @@ impl Job for ClearOldUserDeletionRequestsJob {
 mod tests {
     use super::*;
 
+    use entries_common::db::budget::BudgetDao;
+    use entries_common::db::memory;
     use entries_common::messages::NewUser;
     use entries_common::models::budget::NewBudget;
     use entries_common::models::budget_access_key::NewBudgetAccessKey;
@@ -269,7 +271,9 @@ mod tests {
             .execute(&mut env::testing::DB_THREAD_POOL.get().unwrap())
             .unwrap();
 
-        let mut job = ClearOldUserDeletionRequestsJob::new(env::testing::DB_THREAD_POOL.clone());
+        let mut job = ClearOldUserDeletionRequestsJob::new(Arc::new(user::Dao::new(
+            &env::testing::DB_THREAD_POOL,
+        )));
 
         assert_eq!(
             user_deletion_requests::table
@@ -337,4 +341,32 @@ mod tests {
             1
         );
     }
+
+    #[tokio::test]
+    async fn test_execute_in_memory() {
+        let dao = memory::Dao::new();
+        let (user1_id, _) = env::testing::create_user(&dao);
+        let (user2_id, _) = env::testing::create_user(&dao);
+
+        for (user_id, delete_me_time) in [
+            (user1_id, SystemTime::now() - Duration::from_secs(10)),
+            (user2_id, SystemTime::now() + Duration::from_secs(3600)),
+        ] {
+            dao.verify_user_creation(user_id).unwrap();
+
+            let budget = dao.create_budget(&[0; 32], 1, &[], &[0; 32]).unwrap();
+            let key_id = (&budget.access_key_id).try_into().unwrap();
+
+            dao.initiate_user_deletion(user_id, Duration::from_secs(3600))
+                .unwrap();
+            dao.save_user_deletion_budget_keys(&[key_id], user_id, delete_me_time)
+                .unwrap();
+        }
+
+        let mut job = ClearOldUserDeletionRequestsJob::new(Arc::new(dao.clone()));
+        job.execute().await.unwrap();
+
+        assert!(!dao.check_is_user_listed_for_deletion(user1_id).unwrap());
+        assert!(dao.check_is_user_listed_for_deletion(user2_id).unwrap());
+    }
 }
@@ -1,7 +1,7 @@
-use entries_common::db::user::Dao as UserDao;
-use entries_common::db::DbThreadPool;
+use entries_common::db::user::UserDao;
 
 use async_trait::async_trait;
+use std::sync::Arc;
 use std::time::Duration;
 
 use crate::jobs::{Job, JobError};
@@ -9,15 +9,15 @@ use crate::jobs::{Job, JobError};
 pub struct ClearUnverifiedUsersJob {
     pub max_unverified_user_age: Duration,
 
-    db_thread_pool: DbThreadPool,
+    dao: Arc<dyn UserDao>,
     is_running: bool,
 }
 
 impl ClearUnverifiedUsersJob {
-    pub fn new(max_unverified_user_age: Duration, db_thread_pool: DbThreadPool) -> Self {
+    pub fn new(max_unverified_user_age: Duration, dao: Arc<dyn UserDao>) -> Self {
         Self {
             max_unverified_user_age,
-            db_thread_pool,
+            dao,
             is_running: false,
         }
     }
@@ -37,7 +37,7 @@ impl Job for ClearUnverifiedUsersJob {
         self.is_running = true;
 
         let max_unverified_user_age = self.max_unverified_user_age;
-        let dao = UserDao::new(&self.db_thread_pool);
+        let dao = Arc::clone(&self.dao);
 
         tokio::task::spawn_blocking(move || dao.clear_unverified_users(max_unverified_user_age))
             .await??;
@@ -51,7 +51,7 @@ impl Job for ClearUnverifiedUsersJob {
 mod tests {
     use super::*;
 
-    use entries_common::db::user;
+    use entries_common::db::{memory, user};
     use entries_common::messages::NewUser;
     use entries_common::schema::users;
 
@@ -268,7 +268,7 @@ mod tests {
 
         let mut job = ClearUnverifiedUsersJob::new(
             Duration::from_secs(86400),
-            env::testing::DB_THREAD_POOL.clone(),
+            Arc::new(user::Dao::new(&env::testing::DB_THREAD_POOL)),
         );
 
         assert_eq!(
@@ -321,4 +321,18 @@ mod tests {
             0
         );
     }
+
+    #[tokio::test]
+    async fn test_execute_in_memory() {
+        let dao = memory::Dao::new();
+        let (_, unverified_email) = env::testing::create_user(&dao);
+        let (verified_id, verified_email) = env::testing::create_user(&dao);
+        dao.verify_user_creation(verified_id).unwrap();
+
+        let mut job = ClearUnverifiedUsersJob::new(Duration::ZERO, Arc::new(dao.clone()));
+        job.execute().await.unwrap();
+
+        assert!(dao.get_user_status(&unverified_email).is_err());
+        assert!(dao.get_user_status(&verified_email).is_ok());
+    }
 }
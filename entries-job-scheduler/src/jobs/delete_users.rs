@@ -1,20 +1,20 @@
-use entries_common::db::user::Dao as UserDao;
-use entries_common::db::DbThreadPool;
+use entries_common::db::user::UserDao;
 
 use async_trait::async_trait;
 use futures::future;
+use std::sync::Arc;
 
 use crate::jobs::{Job, JobError};
 
 pub struct DeleteUsersJob {
-    db_thread_pool: DbThreadPool,
+    dao: Arc<dyn UserDao>,
     is_running: bool,
 }
 
 impl DeleteUsersJob {
-    pub fn new(db_thread_pool: DbThreadPool) -> Self {
+    pub fn new(dao: Arc<dyn UserDao>) -> Self {
         Self {
-            db_thread_pool,
+            dao,
             is_running: false,
         }
     }
@@ -33,7 +33,7 @@ impl Job for DeleteUsersJob {
     async fn execute(&mut self) -> Result<(), JobError> {
         self.is_running = true;
 
-        let dao = UserDao::new(&self.db_thread_pool);
+        let dao = Arc::clone(&self.dao);
 
         let users_ready_for_deletion =
             tokio::task::spawn_blocking(move || dao.get_all_users_ready_for_deletion()).await??;
@@ -41,7 +41,7 @@ impl Job for DeleteUsersJob {
         let mut delete_user_futures = Vec::new();
 
         for user in users_ready_for_deletion {
-            let dao = UserDao::new(&self.db_thread_pool);
+            let dao = Arc::clone(&self.dao);
 
             delete_user_futures.push(tokio::task::spawn_blocking(move || {
                 let result = dao.delete_user(&user);
@@ -72,7 +72,8 @@ impl Job for DeleteUsersJob {
 mod tests {
     use super::*;
 
-    use entries_common::db::budget;
+    use entries_common::db::budget::{self, BudgetDao};
+    use entries_common::db::memory;
     use entries_common::messages::NewUser;
     use entries_common::models::budget::NewBudget;
     use entries_common::models::budget_access_key::NewBudgetAccessKey;
@@ -251,8 +252,10 @@ mod tests {
 
         let out = budget_dao
             .create_entry_and_category(
+                None,
                 &[0],
                 rand::thread_rng().gen(),
+                None,
                 &[0],
                 rand::thread_rng().gen(),
                 new_budget1.id,
@@ -264,8 +267,10 @@ mod tests {
 
         let out = budget_dao
             .create_entry_and_category(
+                None,
                 &[0],
                 rand::thread_rng().gen(),
+                None,
                 &[0],
                 rand::thread_rng().gen(),
                 new_budget2.id,
@@ -364,7 +369,7 @@ mod tests {
             .execute(&mut env::testing::DB_THREAD_POOL.get().unwrap())
             .unwrap();
 
-        let mut job = DeleteUsersJob::new(env::testing::DB_THREAD_POOL.clone());
+        let mut job = DeleteUsersJob::new(Arc::new(user::Dao::new(&env::testing::DB_THREAD_POOL)));
 
         assert_eq!(
             user_deletion_requests::table
@@ -869,4 +874,44 @@ mod tests {
             0
         );
     }
+
+    #[tokio::test]
+    async fn test_execute_in_memory() {
+        let dao = memory::Dao::new();
+        let (user1_id, user1_email) = env::testing::create_user(&dao);
+        let (user2_id, user2_email) = env::testing::create_user(&dao);
+
+        let mut budget_ids = Vec::new();
+
+        for (user_id, time_until_deletion) in [
+            (user1_id, Duration::ZERO),
+            (user2_id, Duration::from_secs(3600)),
+        ] {
+            dao.verify_user_creation(user_id).unwrap();
+
+            let budget = dao.create_budget(&[0; 32], 1, &[], &[0; 32]).unwrap();
+            let budget_id: Uuid = (&budget.id).try_into().unwrap();
+            let key_id = (&budget.access_key_id).try_into().unwrap();
+            budget_ids.push(budget_id);
+
+            dao.initiate_user_deletion(user_id, time_until_deletion)
+                .unwrap();
+            dao.save_user_deletion_budget_keys(
+                &[key_id],
+                user_id,
+                SystemTime::now() + Duration::from_secs(3600),
+            )
+            .unwrap();
+        }
+
+        let mut job = DeleteUsersJob::new(Arc::new(dao.clone()));
+        job.execute().await.unwrap();
+
+        assert!(dao.get_user_status(&user1_email).is_err());
+        assert!(dao.get_budget(budget_ids[0]).is_err());
+
+        assert!(dao.get_user_status(&user2_email).is_ok());
+        assert!(dao.get_budget(budget_ids[1]).is_ok());
+        assert!(dao.check_is_user_listed_for_deletion(user2_id).unwrap());
+    }
 }
@@ -1,19 +1,19 @@
-use entries_common::db::auth::Dao as AuthDao;
-use entries_common::db::DbThreadPool;
+use entries_common::db::auth::AuthDao;
 
 use async_trait::async_trait;
+use std::sync::Arc;
 
 use crate::jobs::{Job, JobError};
 
 pub struct UnblacklistExpiredTokensJob {
-    db_thread_pool: DbThreadPool,
+    dao: Arc<dyn AuthDao>,
     is_running: bool,
 }
 
 impl UnblacklistExpiredTokensJob {
-    pub fn new(db_thread_pool: DbThreadPool) -> Self {
+    pub fn new(dao: Arc<dyn AuthDao>) -> Self {
         Self {
-            db_thread_pool,
+            dao,
             is_running: false,
         }
     }
@@ -32,7 +32,7 @@ impl Job for UnblacklistExpiredTokensJob {
     async fn execute(&mut self) -> Result<(), JobError> {
         self.is_running = true;
 
-        let dao = AuthDao::new(&self.db_thread_pool);
+        let dao = Arc::clone(&self.dao);
         tokio::task::spawn_blocking(move || dao.clear_all_expired_tokens()).await??;
 
         self.is_running = false;
@@ -44,7 +44,8 @@ impl Job for UnblacklistExpiredTokensJob {
 mod tests {
     use super::*;
 
-    use entries_common::db::user;
+    use entries_common::db::user::{self, UserDao};
+    use entries_common::db::{auth, memory};
     use entries_common::messages::NewUser;
     use entries_common::models::blacklisted_token::NewBlacklistedToken;
     use entries_common::schema::blacklisted_tokens::dsl::blacklisted_tokens;
@@ -53,7 +54,7 @@ mod tests {
 
     use diesel::{dsl, RunQueryDsl};
     use rand::Rng;
-    use std::time::{Duration, SystemTime};
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
     use uuid::Uuid;
 
     use crate::env;
@@ -175,10 +176,12 @@ mod tests {
             .execute(&mut db_connection)
             .unwrap();
 
-        let mut job = UnblacklistExpiredTokensJob::new(env::testing::DB_THREAD_POOL.clone());
+        let mut job = UnblacklistExpiredTokensJob::new(Arc::new(auth::Dao::new(
+            &env::testing::DB_THREAD_POOL,
+        )));
         job.execute().await.unwrap();
 
-        let dao = AuthDao::new(&env::testing::DB_THREAD_POOL);
+        let dao = auth::Dao::new(&env::testing::DB_THREAD_POOL);
 
         assert!(!dao
             .check_is_token_on_blacklist_and_blacklist(&pretend_expired_token.signature, 0)
@@ -187,4 +190,26 @@ mod tests {
             .check_is_token_on_blacklist_and_blacklist(&unexpired_token.signature, 0)
             .unwrap());
     }
+
+    #[tokio::test]
+    async fn test_execute_in_memory() {
+        let dao = memory::Dao::new();
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+
+        dao.blacklist_token(b"expired", now - 1).unwrap();
+        dao.blacklist_token(b"unexpired", now + 3600).unwrap();
+
+        let mut job = UnblacklistExpiredTokensJob::new(Arc::new(dao.clone()));
+        job.execute().await.unwrap();
+
+        assert!(!dao
+            .check_is_token_on_blacklist_and_blacklist(b"expired", 0)
+            .unwrap());
+        assert!(dao
+            .check_is_token_on_blacklist_and_blacklist(b"unexpired", 0)
+            .unwrap());
+    }
 }
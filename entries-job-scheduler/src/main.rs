@@ -1,6 +1,17 @@
-use entries_common::db::create_db_thread_pool;
+use clap::Parser;
+use entries_common::config;
+use entries_common::db::auth::AuthDao;
+use entries_common::db::budget::BudgetDao;
+use entries_common::db::idempotency::IdempotencyDao;
+use entries_common::db::job_registry::JobRegistryDao;
+use entries_common::db::migrations::MigrationsDao;
+use entries_common::db::user::UserDao;
+use entries_common::db::{self, create_db_thread_pool};
+use entries_common::shutdown;
+use env::DbBackend;
 use flexi_logger::{Age, Cleanup, Criterion, Duplicate, FileSpec, Logger, Naming, WriteMode};
 use runner::JobRunner;
+use std::sync::Arc;
 use std::time::Duration;
 use zeroize::Zeroizing;
 
@@ -9,115 +20,239 @@ mod jobs;
 mod runner;
 
 use jobs::{
-    ClearExpiredBudgetInvitesJob, ClearExpiredOtpsJob, ClearOldUserDeletionRequestsJob,
-    ClearUnverifiedUsersJob, DeleteUsersJob, UnblacklistExpiredTokensJob,
+    ClearExpiredBudgetInvitesJob, ClearExpiredIdempotencyKeysJob, ClearExpiredOtpsJob,
+    ClearOldUserDeletionRequestsJob, ClearUnverifiedUsersJob, DeleteUsersJob,
+    UnblacklistExpiredTokensJob,
 };
 
+#[derive(Debug, Parser)]
+#[command(version, about = "Runs scheduled maintenance jobs for the Entries App")]
+struct Args {
+    /// Read configuration from this TOML file. Overrides ENTRIES_CONFIG_FILE
+    #[arg(long, value_name = "PATH")]
+    config: Option<String>,
+
+    /// Validate the configuration, print it with secrets redacted, and exit
+    #[arg(long)]
+    check_config: bool,
+}
+
 fn main() {
-    let db_uri = Zeroizing::new(format!(
-        "postgres://{}:{}@{}:{}/{}",
-        env::CONF.db_username,
-        env::CONF.db_password,
-        env::CONF.db_hostname,
-        env::CONF.db_port,
-        env::CONF.db_name,
-    ));
-
-    let db_thread_pool = create_db_thread_pool(
-        &db_uri,
-        env::CONF.db_max_connections,
-        env::CONF.db_idle_timeout,
-    );
-
-    tokio::runtime::Builder::new_multi_thread()
+    let args = Args::parse();
+
+    if let Some(config_path) = &args.config {
+        std::env::set_var(config::CONFIG_FILE_VAR, config_path);
+    }
+
+    if args.check_config {
+        std::process::exit(if env::check_config() { 0 } else { 1 });
+    }
+
+    let daos = match env::CONF.db_backend {
+        DbBackend::Postgres => Daos::postgres(),
+        #[cfg(feature = "sqlite")]
+        DbBackend::Sqlite => Daos::sqlite(),
+    };
+
+    let runtime = tokio::runtime::Builder::new_multi_thread()
         .worker_threads(env::CONF.worker_threads)
         .max_blocking_threads(env::CONF.max_blocking_threads)
         .enable_all()
         .build()
-        .expect("Failed to launch asynchronous runtime")
-        .block_on(async move {
-            Logger::try_with_str(&env::CONF.log_level)
-                .expect(
-                    "Invalid log level. Options: ERROR, WARN, INFO, DEBUG, TRACE. \
-                     Example: `info, my::critical::module=trace`",
-                )
-                .log_to_file(FileSpec::default().directory("./logs"))
-                .rotate(
-                    Criterion::Age(Age::Day),
-                    Naming::Timestamps,
-                    Cleanup::KeepLogAndCompressedFiles(60, 365),
-                )
-                .cleanup_in_background_thread(true)
-                .duplicate_to_stdout(Duplicate::All)
-                .write_mode(WriteMode::BufferAndFlush)
-                .format(|writer, now, record| {
-                    write!(
-                        writer,
-                        "{:5} | {} | {}:{} | {}",
-                        record.level(),
-                        now.format("%Y-%m-%dT%H:%M:%S%.6fZ"),
-                        record.module_path().unwrap_or("<unknown>"),
-                        record.line().unwrap_or(0),
-                        record.args()
-                    )
-                })
-                .use_utc()
-                .start()
-                .expect("Failed to start logger");
-
-            let mut job_runner = JobRunner::new(env::CONF.update_frequency, db_thread_pool.clone());
-
-            job_runner
-                .register(
-                    Box::new(ClearExpiredBudgetInvitesJob::new(db_thread_pool.clone())),
-                    env::CONF.clear_expired_budget_invites_job_frequency,
-                )
-                .await;
+        .expect("Failed to launch asynchronous runtime");
 
-            job_runner
-                .register(
-                    Box::new(ClearExpiredOtpsJob::new(db_thread_pool.clone())),
-                    env::CONF.clear_expired_otps_job_frequency,
+    let logger = runtime.block_on(async move {
+        let logger = Logger::try_with_str(&env::CONF.log_level)
+            .expect(
+                "Invalid log level. Options: ERROR, WARN, INFO, DEBUG, TRACE. \
+                 Example: `info, my::critical::module=trace`",
+            )
+            .log_to_file(FileSpec::default().directory("./logs"))
+            .rotate(
+                Criterion::Age(Age::Day),
+                Naming::Timestamps,
+                Cleanup::KeepLogAndCompressedFiles(60, 365),
+            )
+            .cleanup_in_background_thread(true)
+            .duplicate_to_stdout(Duplicate::All)
+            .write_mode(WriteMode::BufferAndFlush)
+            .format(|writer, now, record| {
+                write!(
+                    writer,
+                    "{:5} | {} | {}:{} | {}",
+                    record.level(),
+                    now.format("%Y-%m-%dT%H:%M:%S%.6fZ"),
+                    record.module_path().unwrap_or("<unknown>"),
+                    record.line().unwrap_or(0),
+                    record.args()
                 )
-                .await;
+            })
+            .use_utc()
+            .start()
+            .expect("Failed to start logger");
 
-            job_runner
-                .register(
-                    Box::new(ClearOldUserDeletionRequestsJob::new(db_thread_pool.clone())),
-                    env::CONF.clear_old_user_deletion_requests_job_frequency,
-                )
-                .await;
-
-            job_runner
-                .register(
-                    Box::new(ClearUnverifiedUsersJob::new(
-                        Duration::from_secs(
-                            env::CONF.clear_unverified_users_max_user_age_days * 86400,
-                        ),
-                        db_thread_pool.clone(),
-                    )),
-                    env::CONF.clear_unverified_users_job_frequency,
-                )
-                .await;
+        let pending_migrations = daos
+            .migrations
+            .get_pending_migrations()
+            .expect("Failed to check for pending database migrations");
 
-            job_runner
-                .register(
-                    Box::new(DeleteUsersJob::new(db_thread_pool.clone())),
-                    env::CONF.delete_users_job_frequency,
-                )
-                .await;
+        if !pending_migrations.is_empty() {
+            for name in &pending_migrations {
+                log::error!("Pending migration: {name}");
+            }
 
-            job_runner
-                .register(
-                    Box::new(UnblacklistExpiredTokensJob::new(db_thread_pool.clone())),
-                    env::CONF.unblacklist_expired_tokens_job_frequency,
-                )
-                .await;
+            log::error!(
+                "The database schema is behind this job scheduler. Run `entries_server migrate` \
+                 before starting it."
+            );
+
+            logger.flush();
+            std::process::exit(1);
+        }
+
+        let mut job_runner = JobRunner::new(
+            env::CONF.update_frequency,
+            env::CONF.shutdown_timeout,
+            Arc::clone(&daos.job_registry),
+        );
+
+        let Daos {
+            auth: auth_dao,
+            budget: budget_dao,
+            user: user_dao,
+            idempotency: idempotency_dao,
+            ..
+        } = daos;
+
+        job_runner
+            .register(
+                Box::new(ClearExpiredBudgetInvitesJob::new(Arc::clone(&budget_dao))),
+                env::CONF.clear_expired_budget_invites_job_frequency,
+            )
+            .await;
+
+        job_runner
+            .register(
+                Box::new(ClearExpiredIdempotencyKeysJob::new(idempotency_dao)),
+                env::CONF.clear_expired_idempotency_keys_job_frequency,
+            )
+            .await;
 
-            job_runner.start().await;
-        });
+        job_runner
+            .register(
+                Box::new(ClearExpiredOtpsJob::new(Arc::clone(&auth_dao))),
+                env::CONF.clear_expired_otps_job_frequency,
+            )
+            .await;
+
+        job_runner
+            .register(
+                Box::new(ClearOldUserDeletionRequestsJob::new(Arc::clone(&user_dao))),
+                env::CONF.clear_old_user_deletion_requests_job_frequency,
+            )
+            .await;
+
+        job_runner
+            .register(
+                Box::new(ClearUnverifiedUsersJob::new(
+                    Duration::from_secs(env::CONF.clear_unverified_users_max_user_age_days * 86400),
+                    Arc::clone(&user_dao),
+                )),
+                env::CONF.clear_unverified_users_job_frequency,
+            )
+            .await;
+
+        job_runner
+            .register(
+                Box::new(DeleteUsersJob::new(Arc::clone(&user_dao))),
+                env::CONF.delete_users_job_frequency,
+            )
+            .await;
+
+        job_runner
+            .register(
+                Box::new(UnblacklistExpiredTokensJob::new(Arc::clone(&auth_dao))),
+                env::CONF.unblacklist_expired_tokens_job_frequency,
+            )
+            .await;
+
+        job_runner.start(wait_for_shutdown_signal()).await;
+        log::info!("Job runner stopped");
+
+        logger
+    });
+
+    // Jobs that were abandoned may have left DB calls running on the blocking thread pool.
+    // Give them a chance to finish rather than cutting them off mid-query.
+    runtime.shutdown_timeout(env::CONF.shutdown_timeout);
+
+    logger.flush();
+    logger.shutdown();
 
     unsafe {
         env::CONF.zeroize();
     }
 }
+
+struct Daos {
+    auth: Arc<dyn AuthDao>,
+    budget: Arc<dyn BudgetDao>,
+    user: Arc<dyn UserDao>,
+    idempotency: Arc<dyn IdempotencyDao>,
+    job_registry: Arc<dyn JobRegistryDao>,
+    migrations: Box<dyn MigrationsDao>,
+}
+
+impl Daos {
+    fn postgres() -> Self {
+        let db_uri = Zeroizing::new(format!(
+            "postgres://{}:{}@{}:{}/{}",
+            env::CONF.db_username,
+            env::CONF.db_password,
+            env::CONF.db_hostname,
+            env::CONF.db_port,
+            env::CONF.db_name,
+        ));
+
+        let db_thread_pool = create_db_thread_pool(
+            &db_uri,
+            env::CONF.db_max_connections,
+            env::CONF.db_idle_timeout,
+            None,
+        );
+
+        Self {
+            auth: Arc::new(db::auth::Dao::new(&db_thread_pool)),
+            budget: Arc::new(db::budget::Dao::new(&db_thread_pool)),
+            user: Arc::new(db::user::Dao::new(&db_thread_pool)),
+            idempotency: Arc::new(db::idempotency::Dao::new(&db_thread_pool)),
+            job_registry: Arc::new(db::job_registry::Dao::new(&db_thread_pool)),
+            migrations: Box::new(db::migrations::Dao::new(&db_thread_pool)),
+        }
+    }
+
+    #[cfg(feature = "sqlite")]
+    fn sqlite() -> Self {
+        // Jobs can wait on the server's writes for a while without holding anything up
+        let db_pool = db::sqlite::create_db_pool(
+            &env::CONF.db_sqlite_path,
+            env::CONF.db_max_connections,
+            Duration::from_secs(30),
+        );
+        let dao = db::sqlite::Dao::new(&db_pool);
+
+        Self {
+            auth: Arc::new(dao.clone()),
+            budget: Arc::new(dao.clone()),
+            user: Arc::new(dao.clone()),
+            idempotency: Arc::new(dao.clone()),
+            job_registry: Arc::new(dao.clone()),
+            migrations: Box::new(dao),
+        }
+    }
+}
+
+async fn wait_for_shutdown_signal() {
+    shutdown::wait_for_signal().await;
+    log::info!("Shutdown signal received. Stopping job runner...");
+}
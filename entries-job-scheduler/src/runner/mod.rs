@@ -1,11 +1,15 @@
-use entries_common::db::job_registry::Dao as JobRegistryDao;
+use entries_common::db::job_registry::JobRegistryDao;
 
-use entries_common::db::DbThreadPool;
+use entries_common::db::DaoError;
 use futures::future;
+use std::future::Future;
+use std::pin::pin;
+use std::sync::Arc;
 use std::time::{Duration, Instant, SystemTime};
+use tokio::task::JoinError;
 use tokio::time;
 
-use crate::jobs::Job;
+use crate::jobs::{Job, JobError};
 
 struct JobContainer {
     job: Box<dyn Job>,
@@ -16,15 +20,21 @@ struct JobContainer {
 pub struct JobRunner {
     jobs: Vec<JobContainer>,
     update_frequency: Duration,
-    db_thread_pool: DbThreadPool,
+    shutdown_timeout: Duration,
+    job_registry_dao: Arc<dyn JobRegistryDao>,
 }
 
 impl JobRunner {
-    pub fn new(update_frequency: Duration, db_thread_pool: DbThreadPool) -> Self {
+    pub fn new(
+        update_frequency: Duration,
+        shutdown_timeout: Duration,
+        job_registry_dao: Arc<dyn JobRegistryDao>,
+    ) -> Self {
         Self {
             jobs: Vec::new(),
             update_frequency,
-            db_thread_pool,
+            shutdown_timeout,
+            job_registry_dao,
         }
     }
 
@@ -37,7 +47,7 @@ impl JobRunner {
             run_frequency.as_secs()
         );
 
-        let dao = JobRegistryDao::new(&self.db_thread_pool);
+        let dao = Arc::clone(&self.job_registry_dao);
         let last_run_time = tokio::task::spawn_blocking(move || {
             dao.get_job_last_run_timestamp(job_name_ref)
                 .unwrap_or_else(|e| {
@@ -64,7 +74,12 @@ impl JobRunner {
         self.jobs.push(job_container);
     }
 
-    pub async fn start(&mut self) -> ! {
+    /// Runs jobs as they come due until `shutdown` resolves. Jobs that are running when
+    /// `shutdown` resolves are given up to the runner's shutdown timeout to finish before they
+    /// are abandoned.
+    pub async fn start(&mut self, shutdown: impl Future<Output = ()>) {
+        let mut shutdown = pin!(shutdown);
+
         loop {
             let before = Instant::now();
 
@@ -91,7 +106,7 @@ impl JobRunner {
                     let current_time = SystemTime::now();
                     job_container.last_run_time = current_time;
 
-                    let dao = JobRegistryDao::new(&self.db_thread_pool);
+                    let dao = Arc::clone(&self.job_registry_dao);
                     let record_run_task = tokio::task::spawn_blocking(move || {
                         dao.set_job_last_run_timestamp(name_ref, current_time)
                     });
@@ -100,23 +115,34 @@ impl JobRunner {
                 }
             }
 
-            let (job_results, recording_results) = future::join(
+            let mut jobs_future = pin!(future::join(
                 future::join_all(job_futures),
                 future::join_all(record_job_run_futures),
-            )
-            .await;
+            ));
 
-            for (i, result) in job_results.into_iter().enumerate() {
-                if let Err(e) = result {
-                    log::error!("{}", e);
-                } else {
-                    log::info!("Job \"{}\" finished successfully", job_names[i]);
+            tokio::select! {
+                (job_results, recording_results) = &mut jobs_future => {
+                    log_results(&job_names, job_results, recording_results);
                 }
-            }
-
-            for result in recording_results.into_iter() {
-                if let Err(e) = result {
-                    log::error!("Error recording job run: {}", e);
+                _ = &mut shutdown => {
+                    if !job_names.is_empty() {
+                        log::info!(
+                            "Waiting up to {} seconds for running jobs to finish...",
+                            self.shutdown_timeout.as_secs()
+                        );
+                    }
+
+                    match time::timeout(self.shutdown_timeout, jobs_future).await {
+                        Ok((job_results, recording_results)) => {
+                            log_results(&job_names, job_results, recording_results)
+                        }
+                        Err(_) => log::warn!(
+                            "Abandoning jobs that didn't finish before shutdown: {}",
+                            job_names.join(", ")
+                        ),
+                    }
+
+                    return;
                 }
             }
 
@@ -124,28 +150,130 @@ impl JobRunner {
             let delta = after - before;
 
             if delta < self.update_frequency {
-                time::sleep(self.update_frequency - delta).await;
+                tokio::select! {
+                    _ = time::sleep(self.update_frequency - delta) => (),
+                    _ = &mut shutdown => return,
+                }
             }
         }
     }
 }
 
+fn log_results(
+    job_names: &[&'static str],
+    job_results: Vec<Result<(), JobError>>,
+    recording_results: Vec<Result<Result<(), DaoError>, JoinError>>,
+) {
+    for (i, result) in job_results.into_iter().enumerate() {
+        if let Err(e) = result {
+            log::error!("{}", e);
+        } else {
+            log::info!("Job \"{}\" finished successfully", job_names[i]);
+        }
+    }
+
+    for result in recording_results.into_iter() {
+        if let Err(e) = result {
+            log::error!("Error recording job run: {}", e);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    use std::sync::Arc;
+    use async_trait::async_trait;
+    use entries_common::db::job_registry;
+    use std::sync::atomic::{AtomicBool, Ordering};
     use std::time::Duration;
+    use tokio::sync::oneshot;
 
     use crate::env;
     use crate::jobs::tests::MockJob;
 
+    struct SlowJob {
+        duration: Duration,
+        finished: Arc<AtomicBool>,
+    }
+
+    #[async_trait]
+    impl Job for SlowJob {
+        fn name(&self) -> &'static str {
+            "Slow Mock"
+        }
+
+        fn is_ready(&self) -> bool {
+            true
+        }
+
+        async fn execute(&mut self) -> Result<(), JobError> {
+            time::sleep(self.duration).await;
+            self.finished.store(true, Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
+    async fn run_slow_job_until_shutdown(
+        job_duration: Duration,
+        shutdown_timeout: Duration,
+    ) -> bool {
+        let mut job_runner = JobRunner::new(
+            Duration::from_millis(1),
+            shutdown_timeout,
+            Arc::new(job_registry::Dao::new(&env::testing::DB_THREAD_POOL)),
+        );
+
+        let finished = Arc::new(AtomicBool::new(false));
+        let job = SlowJob {
+            duration: job_duration,
+            finished: Arc::clone(&finished),
+        };
+
+        job_runner.register(Box::new(job), Duration::ZERO).await;
+
+        let (shutdown_sender, shutdown_receiver) = oneshot::channel::<()>();
+        let runner = tokio::task::spawn(async move {
+            job_runner
+                .start(async move {
+                    let _ = shutdown_receiver.await;
+                })
+                .await
+        });
+
+        // Let the job start before signaling shutdown
+        time::sleep(Duration::from_millis(20)).await;
+        shutdown_sender.send(()).unwrap();
+
+        time::timeout(Duration::from_secs(5), runner)
+            .await
+            .expect("Runner should stop after shutdown")
+            .unwrap();
+
+        finished.load(Ordering::SeqCst)
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_waits_for_running_jobs() {
+        let finished =
+            run_slow_job_until_shutdown(Duration::from_millis(100), Duration::from_secs(2)).await;
+        assert!(finished);
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_abandons_jobs_after_timeout() {
+        let finished =
+            run_slow_job_until_shutdown(Duration::from_secs(10), Duration::from_millis(50)).await;
+        assert!(!finished);
+    }
+
     #[tokio::test]
     #[ignore]
     async fn test_register() {
         let mut job_runner = JobRunner::new(
             Duration::from_micros(200),
-            env::testing::DB_THREAD_POOL.clone(),
+            Duration::from_secs(1),
+            Arc::new(job_registry::Dao::new(&env::testing::DB_THREAD_POOL)),
         );
         assert_eq!(job_runner.update_frequency, Duration::from_micros(200));
         assert!(job_runner.jobs.is_empty());
@@ -155,7 +283,7 @@ mod tests {
 
         let set_time = SystemTime::now();
 
-        let dao = JobRegistryDao::new(&env::testing::DB_THREAD_POOL);
+        let dao = job_registry::Dao::new(&env::testing::DB_THREAD_POOL);
         dao.set_job_last_run_timestamp(mock_job1.name(), set_time)
             .unwrap();
 
@@ -177,7 +305,8 @@ mod tests {
     async fn test_start() {
         let mut job_runner = JobRunner::new(
             Duration::from_millis(1),
-            env::testing::DB_THREAD_POOL.clone(),
+            Duration::from_secs(1),
+            Arc::new(job_registry::Dao::new(&env::testing::DB_THREAD_POOL)),
         );
         let job1 = MockJob::new();
         let job2 = MockJob::new();
@@ -200,7 +329,7 @@ mod tests {
                 job.last_run_time = SystemTime::now();
             }
 
-            job_runner.start().await
+            job_runner.start(future::pending()).await
         });
 
         time::sleep(Duration::from_millis(20)).await;
@@ -220,7 +349,7 @@ mod tests {
         assert_eq!(*job1_run_count.lock().await, 2);
         assert_eq!(*job2_run_count.lock().await, 1);
 
-        let dao = JobRegistryDao::new(&env::testing::DB_THREAD_POOL);
+        let dao = job_registry::Dao::new(&env::testing::DB_THREAD_POOL);
         let mock_job_last_run = dao
             .get_job_last_run_timestamp(job_name)
             .unwrap()
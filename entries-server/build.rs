@@ -0,0 +1,24 @@
+use std::path::PathBuf;
+
+fn main() -> std::io::Result<()> {
+    const PROTO_DIR: &[&str] = &["protobuf"];
+    const SERVICE_FILE: &str = "service.proto";
+    const SCHEMA_PACKAGE: &str = ".entries.serverschema";
+    const SCHEMA_RS_PATH: &str = "::entries_common::messages";
+
+    let cwd = std::env::current_dir()?;
+
+    let import_dir = PathBuf::from_iter([&cwd, &"..".into(), &PROTO_DIR.iter().collect()]);
+    let service_schema = PathBuf::from_iter([&import_dir, &SERVICE_FILE.into()]);
+
+    println!("cargo:rerun-if-changed={}", service_schema.display());
+
+    // The messages from schema.proto are generated in entries_common, so only the gRPC
+    // services and their own messages are generated here
+    tonic_build::configure()
+        .build_client(false)
+        .extern_path(SCHEMA_PACKAGE, SCHEMA_RS_PATH)
+        .compile_protos(&[service_schema], &[import_dir])?;
+
+    Ok(())
+}
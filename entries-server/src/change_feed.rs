@@ -0,0 +1,155 @@
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::SystemTime;
+use tokio::sync::broadcast::{self, error::RecvError};
+use uuid::Uuid;
+
+/// Changes beyond this many that a subscriber hasn't received yet are dropped, and the
+/// subscriber is told to resync instead
+const CHANNEL_CAPACITY: usize = 64;
+
+static CHANNELS: Lazy<Mutex<HashMap<Uuid, broadcast::Sender<BudgetChange>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChangeType {
+    ResyncRequired,
+    BudgetEdited,
+    EntryCreated,
+    EntryEdited,
+    EntryDeleted,
+    CategoryCreated,
+    CategoryEdited,
+    CategoryDeleted,
+    MemberJoined,
+    MemberLeft,
+}
+
+#[derive(Clone, Debug)]
+pub struct BudgetChange {
+    pub budget_id: Uuid,
+    pub change_type: ChangeType,
+    pub object_id: Option<Uuid>,
+    pub timestamp: SystemTime,
+}
+
+/// Notifies subscribers of a budget that it has changed. Changes are only delivered to
+/// subscribers in this process, and nothing is kept for budgets without subscribers.
+pub fn publish(budget_id: Uuid, change_type: ChangeType, object_id: Option<Uuid>) {
+    let channels = CHANNELS.lock().expect("Change feed lock was poisoned");
+
+    if let Some(sender) = channels.get(&budget_id) {
+        // Fails only if there are no receivers, in which case nobody is listening anyway
+        let _ = sender.send(BudgetChange {
+            budget_id,
+            change_type,
+            object_id,
+            timestamp: SystemTime::now(),
+        });
+    }
+}
+
+pub fn subscribe(budget_id: Uuid) -> Subscription {
+    let mut channels = CHANNELS.lock().expect("Change feed lock was poisoned");
+
+    let receiver = channels
+        .entry(budget_id)
+        .or_insert_with(|| broadcast::channel(CHANNEL_CAPACITY).0)
+        .subscribe();
+
+    Subscription {
+        budget_id,
+        receiver,
+    }
+}
+
+pub struct Subscription {
+    budget_id: Uuid,
+    receiver: broadcast::Receiver<BudgetChange>,
+}
+
+impl Subscription {
+    /// Waits for the next change to the budget. If the subscriber fell behind and changes
+    /// were dropped, a `ResyncRequired` change is returned in their place.
+    pub async fn next(&mut self) -> Option<BudgetChange> {
+        match self.receiver.recv().await {
+            Ok(change) => Some(change),
+            Err(RecvError::Lagged(_)) => Some(BudgetChange {
+                budget_id: self.budget_id,
+                change_type: ChangeType::ResyncRequired,
+                object_id: None,
+                timestamp: SystemTime::now(),
+            }),
+            // The sender is only removed once its last receiver is dropped, so this shouldn't
+            // happen while the subscription is alive
+            Err(RecvError::Closed) => None,
+        }
+    }
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        let mut channels = CHANNELS.lock().expect("Change feed lock was poisoned");
+
+        // This subscription's receiver is dropped after this function returns, so it is still
+        // counted here
+        if channels
+            .get(&self.budget_id)
+            .is_some_and(|sender| sender.receiver_count() <= 1)
+        {
+            channels.remove(&self.budget_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[actix_web::test]
+    async fn test_publish_and_subscribe() {
+        let budget_id = Uuid::now_v7();
+        let entry_id = Uuid::now_v7();
+
+        // Nothing is kept for budgets without subscribers
+        publish(budget_id, ChangeType::BudgetEdited, None);
+        assert!(!CHANNELS.lock().unwrap().contains_key(&budget_id));
+
+        let mut first = subscribe(budget_id);
+        let mut second = subscribe(budget_id);
+
+        publish(budget_id, ChangeType::EntryCreated, Some(entry_id));
+        publish(Uuid::now_v7(), ChangeType::BudgetEdited, None);
+
+        for subscription in [&mut first, &mut second] {
+            let change = subscription.next().await.unwrap();
+            assert_eq!(change.budget_id, budget_id);
+            assert_eq!(change.change_type, ChangeType::EntryCreated);
+            assert_eq!(change.object_id, Some(entry_id));
+        }
+
+        drop(first);
+        assert!(CHANNELS.lock().unwrap().contains_key(&budget_id));
+
+        drop(second);
+        assert!(!CHANNELS.lock().unwrap().contains_key(&budget_id));
+    }
+
+    #[actix_web::test]
+    async fn test_lagging_subscriber_is_told_to_resync() {
+        let budget_id = Uuid::now_v7();
+        let mut subscription = subscribe(budget_id);
+
+        for _ in 0..(CHANNEL_CAPACITY + 1) {
+            publish(budget_id, ChangeType::EntryEdited, None);
+        }
+
+        let change = subscription.next().await.unwrap();
+        assert_eq!(change.change_type, ChangeType::ResyncRequired);
+        assert_eq!(change.budget_id, budget_id);
+
+        let change = subscription.next().await.unwrap();
+        assert_eq!(change.change_type, ChangeType::EntryEdited);
+    }
+}
@@ -0,0 +1,181 @@
+use clap::{Parser, Subcommand};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+const DEFAULT_PORT: u16 = 9000;
+
+#[derive(Debug, Parser)]
+#[command(version, about = "Server for the Entries App")]
+pub struct Args {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Listen on 127.0.0.1 at this port. Shorthand for `--bind 127.0.0.1:<PORT>`
+    #[arg(long, conflicts_with = "bind")]
+    pub port: Option<u16>,
+
+    /// Listen on this address, e.g. `0.0.0.0:9000` or `[::]:9000`. May be given more than
+    /// once. Overrides ENTRIES_BIND_ADDRESSES
+    #[arg(long, value_name = "ADDR")]
+    pub bind: Vec<SocketAddr>,
+
+    /// Also listen on a Unix domain socket at this path. Overrides ENTRIES_UNIX_SOCKET_PATH
+    #[arg(long, value_name = "PATH")]
+    pub unix_socket: Option<String>,
+
+    /// Read configuration from this TOML file. Overrides ENTRIES_CONFIG_FILE
+    #[arg(long, value_name = "PATH")]
+    pub config: Option<String>,
+
+    /// Validate the configuration, print it with secrets redacted, and exit
+    #[arg(long)]
+    pub check_config: bool,
+
+    /// Apply pending database migrations before starting. Same as setting
+    /// ENTRIES_RUN_MIGRATIONS_ON_STARTUP
+    #[arg(long)]
+    pub run_migrations: bool,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Apply pending database migrations and exit
+    Migrate {
+        /// List the pending migrations without applying them
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+impl Args {
+    /// Picks the TCP addresses to listen on. Addresses given on the command line take
+    /// precedence over the configured addresses. If neither are given, the server listens on
+    /// 127.0.0.1:9000.
+    pub fn bind_addresses(&self, configured: &[SocketAddr]) -> Vec<SocketAddr> {
+        if !self.bind.is_empty() {
+            self.bind.clone()
+        } else if let Some(port) = self.port {
+            vec![SocketAddr::from((Ipv4Addr::LOCALHOST, port))]
+        } else if !configured.is_empty() {
+            configured.to_vec()
+        } else {
+            vec![SocketAddr::from((Ipv4Addr::LOCALHOST, DEFAULT_PORT))]
+        }
+    }
+
+    pub fn unix_socket_path<'a>(&'a self, configured: Option<&'a str>) -> Option<&'a str> {
+        self.unix_socket.as_deref().or(configured)
+    }
+}
+
+/// Addresses for the plain HTTP listener that redirects to HTTPS: one per distinct IP that the
+/// server listens on. The listener on `[::]` also accepts IPv4 connections (actix doesn't set
+/// IPV6_V6ONLY), so `0.0.0.0` is left out when both are present.
+pub fn redirect_addresses(bind_addresses: &[SocketAddr], redirect_port: u16) -> Vec<SocketAddr> {
+    let mut ips: Vec<IpAddr> = Vec::new();
+
+    for addr in bind_addresses {
+        if !ips.contains(&addr.ip()) {
+            ips.push(addr.ip());
+        }
+    }
+
+    if ips.contains(&IpAddr::V6(Ipv6Addr::UNSPECIFIED)) {
+        ips.retain(|ip| *ip != IpAddr::V4(Ipv4Addr::UNSPECIFIED));
+    }
+
+    ips.into_iter()
+        .map(|ip| SocketAddr::new(ip, redirect_port))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bind_addresses() {
+        let configured: Vec<SocketAddr> = vec!["0.0.0.0:8000".parse().unwrap()];
+
+        let args = Args::parse_from(["entries_server"]);
+        assert_eq!(
+            args.bind_addresses(&[]),
+            vec!["127.0.0.1:9000".parse::<SocketAddr>().unwrap()]
+        );
+        assert_eq!(args.bind_addresses(&configured), configured);
+
+        let args = Args::parse_from(["entries_server", "--port", "9123"]);
+        assert_eq!(
+            args.bind_addresses(&configured),
+            vec!["127.0.0.1:9123".parse::<SocketAddr>().unwrap()]
+        );
+
+        let args = Args::parse_from([
+            "entries_server",
+            "--bind",
+            "0.0.0.0:9000",
+            "--bind",
+            "[::]:9000",
+        ]);
+        assert_eq!(
+            args.bind_addresses(&configured),
+            vec![
+                "0.0.0.0:9000".parse::<SocketAddr>().unwrap(),
+                "[::]:9000".parse::<SocketAddr>().unwrap(),
+            ]
+        );
+
+        assert!(
+            Args::try_parse_from(["entries_server", "--port", "1", "--bind", "[::]:1"]).is_err()
+        );
+        assert!(Args::try_parse_from(["entries_server", "--bind", "localhost"]).is_err());
+    }
+
+    #[test]
+    fn test_redirect_addresses() {
+        let parse = |addrs: &[&str]| -> Vec<SocketAddr> {
+            addrs.iter().map(|a| a.parse().unwrap()).collect()
+        };
+
+        assert_eq!(
+            redirect_addresses(
+                &parse(&["127.0.0.1:443", "127.0.0.1:8443", "10.0.0.1:443"]),
+                80
+            ),
+            parse(&["127.0.0.1:80", "10.0.0.1:80"])
+        );
+        assert_eq!(
+            redirect_addresses(&parse(&["0.0.0.0:443", "[::]:443"]), 80),
+            parse(&["[::]:80"])
+        );
+        assert_eq!(
+            redirect_addresses(&parse(&["0.0.0.0:443", "[::1]:443"]), 80),
+            parse(&["0.0.0.0:80", "[::1]:80"])
+        );
+    }
+
+    #[test]
+    fn test_unix_socket_path() {
+        let args = Args::parse_from(["entries_server"]);
+        assert_eq!(args.unix_socket_path(None), None);
+        assert_eq!(args.unix_socket_path(Some("/a.sock")), Some("/a.sock"));
+
+        let args = Args::parse_from(["entries_server", "--unix-socket", "/b.sock"]);
+        assert_eq!(args.unix_socket_path(Some("/a.sock")), Some("/b.sock"));
+    }
+
+    #[test]
+    fn test_migrate_command() {
+        let args = Args::parse_from(["entries_server"]);
+        assert!(args.command.is_none());
+        assert!(!args.run_migrations);
+
+        let args = Args::parse_from(["entries_server", "migrate", "--dry-run"]);
+        assert!(matches!(
+            args.command,
+            Some(Command::Migrate { dry_run: true })
+        ));
+
+        let args = Args::parse_from(["entries_server", "--run-migrations"]);
+        assert!(args.run_migrations);
+    }
+}
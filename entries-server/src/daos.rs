@@ -0,0 +1,142 @@
+use entries_common::db::auth::AuthDao;
+use entries_common::db::budget::BudgetDao;
+use entries_common::db::health::HealthDao;
+use entries_common::db::idempotency::IdempotencyDao;
+use entries_common::db::job_registry::JobRegistryDao;
+use entries_common::db::nonblocking::budget::AsyncBudgetDao;
+#[cfg(feature = "sqlite")]
+use entries_common::db::sqlite::SqliteDbPool;
+use entries_common::db::user::UserDao;
+use entries_common::db::{self, AsyncDbPools, DbThreadPool};
+
+use actix_web::web::{Data, ServiceConfig};
+use std::sync::Arc;
+
+/// The DAOs handlers extract from app data. Each is registered as a trait object so the server
+/// can run on Postgres or SQLite and tests can swap in `db::memory::Dao`.
+#[derive(Clone)]
+pub struct Daos {
+    auth: Data<dyn AuthDao>,
+    user: Data<dyn UserDao>,
+    budget: Data<dyn BudgetDao>,
+    async_budget: Data<dyn AsyncBudgetDao>,
+    idempotency: Data<dyn IdempotencyDao>,
+    health: Data<dyn HealthDao>,
+    job_registry: Data<dyn JobRegistryDao>,
+    pools: Pools,
+}
+
+/// The connection pools behind the DAOs. They are registered too so the health endpoint can
+/// report on them.
+#[derive(Clone)]
+enum Pools {
+    Postgres {
+        db_thread_pool: Data<DbThreadPool>,
+        db_pools: Data<AsyncDbPools>,
+    },
+    #[cfg(feature = "sqlite")]
+    Sqlite(Data<SqliteDbPool>),
+    #[cfg(test)]
+    None,
+}
+
+impl Daos {
+    pub fn postgres(db_thread_pool: &DbThreadPool, db_pools: &AsyncDbPools) -> Self {
+        let auth: Arc<dyn AuthDao> = Arc::new(db::auth::Dao::new(db_thread_pool));
+        let user: Arc<dyn UserDao> = Arc::new(db::user::Dao::new(db_thread_pool));
+        let budget: Arc<dyn BudgetDao> = Arc::new(db::budget::Dao::new(db_thread_pool));
+        let async_budget: Arc<dyn AsyncBudgetDao> =
+            Arc::new(db::nonblocking::budget::Dao::new(db_pools));
+        let idempotency: Arc<dyn IdempotencyDao> =
+            Arc::new(db::idempotency::Dao::new(db_thread_pool));
+        let health: Arc<dyn HealthDao> = Arc::new(db::health::Dao::new(db_thread_pool));
+        let job_registry: Arc<dyn JobRegistryDao> =
+            Arc::new(db::job_registry::Dao::new(db_thread_pool));
+
+        Daos {
+            auth: Data::from(auth),
+            user: Data::from(user),
+            budget: Data::from(budget),
+            async_budget: Data::from(async_budget),
+            idempotency: Data::from(idempotency),
+            health: Data::from(health),
+            job_registry: Data::from(job_registry),
+            pools: Pools::Postgres {
+                db_thread_pool: Data::new(db_thread_pool.clone()),
+                db_pools: Data::new(db_pools.clone()),
+            },
+        }
+    }
+
+    #[cfg(feature = "sqlite")]
+    pub fn sqlite(db_pool: &SqliteDbPool) -> Self {
+        let dao = db::sqlite::Dao::new(db_pool);
+
+        let auth: Arc<dyn AuthDao> = Arc::new(dao.clone());
+        let user: Arc<dyn UserDao> = Arc::new(dao.clone());
+        let budget: Arc<dyn BudgetDao> = Arc::new(dao.clone());
+        let async_budget: Arc<dyn AsyncBudgetDao> = Arc::new(dao.clone());
+        let idempotency: Arc<dyn IdempotencyDao> = Arc::new(dao.clone());
+        let health: Arc<dyn HealthDao> = Arc::new(dao.clone());
+        let job_registry: Arc<dyn JobRegistryDao> = Arc::new(dao);
+
+        Daos {
+            auth: Data::from(auth),
+            user: Data::from(user),
+            budget: Data::from(budget),
+            async_budget: Data::from(async_budget),
+            idempotency: Data::from(idempotency),
+            health: Data::from(health),
+            job_registry: Data::from(job_registry),
+            pools: Pools::Sqlite(Data::new(db_pool.clone())),
+        }
+    }
+
+    #[cfg(test)]
+    pub fn memory(dao: &db::memory::Dao) -> Self {
+        let auth: Arc<dyn AuthDao> = Arc::new(dao.clone());
+        let user: Arc<dyn UserDao> = Arc::new(dao.clone());
+        let budget: Arc<dyn BudgetDao> = Arc::new(dao.clone());
+        let async_budget: Arc<dyn AsyncBudgetDao> = Arc::new(dao.clone());
+        let idempotency: Arc<dyn IdempotencyDao> = Arc::new(dao.clone());
+        let health: Arc<dyn HealthDao> = Arc::new(dao.clone());
+        let job_registry: Arc<dyn JobRegistryDao> = Arc::new(dao.clone());
+
+        Daos {
+            auth: Data::from(auth),
+            user: Data::from(user),
+            budget: Data::from(budget),
+            async_budget: Data::from(async_budget),
+            idempotency: Data::from(idempotency),
+            health: Data::from(health),
+            job_registry: Data::from(job_registry),
+            pools: Pools::None,
+        }
+    }
+
+    pub fn configure(&self, cfg: &mut ServiceConfig) {
+        cfg.app_data(self.auth.clone())
+            .app_data(self.user.clone())
+            .app_data(self.budget.clone())
+            .app_data(self.async_budget.clone())
+            .app_data(self.idempotency.clone())
+            .app_data(self.health.clone())
+            .app_data(self.job_registry.clone());
+
+        match &self.pools {
+            Pools::Postgres {
+                db_thread_pool,
+                db_pools,
+            } => {
+                cfg.app_data(db_thread_pool.clone())
+                    .app_data(db_pools.clone());
+            }
+            #[cfg(feature = "sqlite")]
+            Pools::Sqlite(db_pool) => {
+                cfg.app_data(db_pool.clone());
+            }
+            #[cfg(test)]
+            Pools::None => (),
+        }
+    }
+}
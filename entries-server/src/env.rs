@@ -1,15 +1,16 @@
-use base64::engine::general_purpose::STANDARD as b64;
-use base64::Engine;
+use entries_common::config::{ConfigError, ConfigSource};
 use lettre::message::Mailbox;
 use once_cell::sync::Lazy;
+use semver::Version;
 use std::cell::UnsafeCell;
 use std::fmt;
+use std::net::SocketAddr;
 use std::ops::Deref;
 use std::str::FromStr;
 use std::time::Duration;
-use zeroize::{Zeroize, Zeroizing};
+use zeroize::Zeroize;
 
-pub static CONF: Lazy<Config> = Lazy::new(|| match Config::from_env() {
+pub static CONF: Lazy<Config> = Lazy::new(|| match Config::load() {
     Ok(c) => c,
     Err(e) => {
         eprintln!("ERROR: Failed to load configuration: {e}");
@@ -22,13 +23,21 @@ pub static CONF: Lazy<Config> = Lazy::new(|| match Config::from_env() {
     }
 });
 
+const DB_BACKEND_VAR: &str = "ENTRIES_DB_BACKEND";
+const DB_SQLITE_PATH_VAR: &str = "ENTRIES_DB_SQLITE_PATH";
 const DB_USERNAME_VAR: &str = "ENTRIES_DB_USERNAME";
 const DB_PASSWORD_VAR: &str = "ENTRIES_DB_PASSWORD";
 const DB_HOSTNAME_VAR: &str = "ENTRIES_DB_HOSTNAME";
 const DB_PORT_VAR: &str = "ENTRIES_DB_PORT";
 const DB_NAME_VAR: &str = "ENTRIES_DB_NAME";
 const DB_MAX_CONNECTIONS_VAR: &str = "ENTRIES_DB_MAX_CONNECTIONS";
+const DB_ASYNC_MAX_CONNECTIONS_VAR: &str = "ENTRIES_DB_ASYNC_MAX_CONNECTIONS";
 const DB_IDLE_TIMEOUT_SECS_VAR: &str = "ENTRIES_DB_IDLE_TIMEOUT_SECS";
+const DB_STATEMENT_TIMEOUT_MS_VAR: &str = "ENTRIES_DB_STATEMENT_TIMEOUT_MS";
+const DB_REPLICA_HOSTNAME_VAR: &str = "ENTRIES_DB_REPLICA_HOSTNAME";
+const DB_REPLICA_PORT_VAR: &str = "ENTRIES_DB_REPLICA_PORT";
+const DB_READ_YOUR_WRITES_WINDOW_SECS_VAR: &str = "ENTRIES_DB_READ_YOUR_WRITES_WINDOW_SECS";
+const RUN_MIGRATIONS_ON_STARTUP_VAR: &str = "ENTRIES_RUN_MIGRATIONS_ON_STARTUP";
 
 const HASHING_KEY_VAR: &str = "ENTRIES_HASHING_KEY_B64";
 const TOKEN_SIGNING_KEY_VAR: &str = "ENTRIES_TOKEN_SIGNING_KEY_B64";
@@ -58,10 +67,32 @@ const USER_CREATION_TOKEN_LIFETIME_DAYS_VAR: &str = "ENTRIES_USER_CREATION_TOKEN
 const USER_DELETION_TOKEN_LIFETIME_DAYS_VAR: &str = "ENTRIES_USER_DELETION_TOKEN_LIFETIME_DAYS";
 const OTP_LIFETIME_MINS_VAR: &str = "ENTRIES_OTP_LIFETIME_MINS";
 const USER_DELETION_DELAY_DAYS_VAR: &str = "ENTRIES_USER_DELETION_DELAY_DAYS";
+const IDEMPOTENCY_KEY_LIFETIME_HOURS_VAR: &str = "ENTRIES_IDEMPOTENCY_KEY_LIFETIME_HOURS";
 
 const ACTIX_WORKER_COUNT_VAR: &str = "ENTRIES_ACTIX_WORKER_COUNT";
 const LOG_LEVEL_VAR: &str = "ENTRIES_LOG_LEVEL";
+const LOG_FORMAT_VAR: &str = "ENTRIES_LOG_FORMAT";
 const PROTOBUF_MAX_SIZE_MB_VAR: &str = "ENTRIES_PROTOBUF_MAX_SIZE_MB";
+const BIND_ADDRESSES_VAR: &str = "ENTRIES_BIND_ADDRESSES";
+const UNIX_SOCKET_PATH_VAR: &str = "ENTRIES_UNIX_SOCKET_PATH";
+const GRPC_BIND_ADDRESS_VAR: &str = "ENTRIES_GRPC_BIND_ADDRESS";
+const TLS_ENABLED_VAR: &str = "ENTRIES_TLS_ENABLED";
+const TLS_CERT_PATH_VAR: &str = "ENTRIES_TLS_CERT_PATH";
+const TLS_KEY_PATH_VAR: &str = "ENTRIES_TLS_KEY_PATH";
+const TLS_RELOAD_INTERVAL_SECS_VAR: &str = "ENTRIES_TLS_RELOAD_INTERVAL_SECS";
+const HTTP_REDIRECT_PORT_VAR: &str = "ENTRIES_HTTP_REDIRECT_PORT";
+const HSTS_MAX_AGE_SECS_VAR: &str = "ENTRIES_HSTS_MAX_AGE_SECS";
+const WEB_DIR_VAR: &str = "ENTRIES_WEB_DIR";
+const WEB_MOUNT_PATH_VAR: &str = "ENTRIES_WEB_MOUNT_PATH";
+const WEB_CACHE_MAX_AGE_SECS_VAR: &str = "ENTRIES_WEB_CACHE_MAX_AGE_SECS";
+const MIN_CLIENT_VERSIONS_VAR: &str = "ENTRIES_MIN_CLIENT_VERSIONS";
+const RECOMMENDED_CLIENT_VERSIONS_VAR: &str = "ENTRIES_RECOMMENDED_CLIENT_VERSIONS";
+const SHUTDOWN_TIMEOUT_SECS_VAR: &str = "ENTRIES_SHUTDOWN_TIMEOUT_SECS";
+const SHUTDOWN_READINESS_DELAY_SECS_VAR: &str = "ENTRIES_SHUTDOWN_READINESS_DELAY_SECS";
+
+const TRACE_EXPORTER_VAR: &str = "ENTRIES_TRACE_EXPORTER";
+const OTLP_ENDPOINT_VAR: &str = "ENTRIES_OTLP_ENDPOINT";
+const TRACE_FILE_PATH_VAR: &str = "ENTRIES_TRACE_FILE_PATH";
 
 const MAX_SMALL_OBJECT_SIZE_KB_VAR: &str = "ENTRIES_MAX_SMALL_OBJECT_SIZE_KB";
 const MAX_KEYSTORE_SIZE_KB_VAR: &str = "ENTRIES_MAX_KEYSTORE_SIZE_KB";
@@ -71,12 +102,17 @@ const MAX_BUDGETS_VAR: &str = "ENTRIES_MAX_BUDGETS";
 const MAX_BUDGET_FETCH_COUNT_VAR: &str = "ENTRIES_MAX_BUDGET_FETCH_COUNT";
 
 const HEALTH_ENDPOINT_KEY_VAR: &str = "ENTRIES_HEALTH_ENDPOINT_KEY";
+const READINESS_CHECK_TIMEOUT_MS_VAR: &str = "ENTRIES_READINESS_CHECK_TIMEOUT_MS";
+const JOB_STALENESS_THRESHOLD_HOURS_VAR: &str = "ENTRIES_JOB_STALENESS_THRESHOLD_HOURS";
 
 const HASHING_KEY_SIZE: usize = 32;
 const TOKEN_SIGNING_KEY_SIZE: usize = 64;
 
 #[derive(Zeroize)]
 pub struct ConfigInner {
+    #[zeroize(skip)]
+    pub db_backend: DbBackend,
+    pub db_sqlite_path: String,
     pub db_username: String,
     pub db_password: String,
     pub db_hostname: String,
@@ -85,7 +121,18 @@ pub struct ConfigInner {
     #[zeroize(skip)]
     pub db_max_connections: u32,
     #[zeroize(skip)]
+    pub db_async_max_connections: u32,
+    #[zeroize(skip)]
     pub db_idle_timeout: Duration,
+    #[zeroize(skip)]
+    pub db_statement_timeout: Duration,
+    pub db_replica_hostname: Option<String>,
+    #[zeroize(skip)]
+    pub db_replica_port: Option<u16>,
+    #[zeroize(skip)]
+    pub db_read_your_writes_window: Duration,
+    #[zeroize(skip)]
+    pub run_migrations_on_startup: bool,
 
     pub hashing_key: [u8; HASHING_KEY_SIZE],
     pub token_signing_key: [u8; TOKEN_SIGNING_KEY_SIZE],
@@ -128,13 +175,56 @@ pub struct ConfigInner {
     pub otp_lifetime: Duration,
     #[zeroize(skip)]
     pub user_deletion_delay_days: u64,
+    #[zeroize(skip)]
+    pub idempotency_key_lifetime: Duration,
 
     #[zeroize(skip)]
     pub actix_worker_count: usize,
     #[zeroize(skip)]
     pub log_level: String,
     #[zeroize(skip)]
+    pub log_format: LogFormat,
+    #[zeroize(skip)]
     pub protobuf_max_size: usize,
+    #[zeroize(skip)]
+    pub bind_addresses: Vec<SocketAddr>,
+    #[zeroize(skip)]
+    pub unix_socket_path: Option<String>,
+    #[zeroize(skip)]
+    pub grpc_bind_address: Option<SocketAddr>,
+    #[zeroize(skip)]
+    pub tls_enabled: bool,
+    #[zeroize(skip)]
+    pub tls_cert_path: Option<String>,
+    #[zeroize(skip)]
+    pub tls_key_path: Option<String>,
+    #[zeroize(skip)]
+    pub tls_reload_interval: Duration,
+    #[zeroize(skip)]
+    pub http_redirect_port: Option<u16>,
+    #[zeroize(skip)]
+    pub hsts_max_age: Option<Duration>,
+    #[zeroize(skip)]
+    pub web_dir: Option<String>,
+    #[zeroize(skip)]
+    pub web_mount_path: WebMountPath,
+    #[zeroize(skip)]
+    pub web_cache_max_age: Duration,
+    #[zeroize(skip)]
+    pub min_client_versions: Vec<PlatformVersion>,
+    #[zeroize(skip)]
+    pub recommended_client_versions: Vec<PlatformVersion>,
+    #[zeroize(skip)]
+    pub shutdown_timeout: Duration,
+    #[zeroize(skip)]
+    pub shutdown_readiness_delay: Duration,
+
+    #[zeroize(skip)]
+    pub trace_exporter: TraceExporter,
+    #[zeroize(skip)]
+    pub otlp_endpoint: String,
+    #[zeroize(skip)]
+    pub trace_file_path: String,
 
     #[zeroize(skip)]
     pub max_small_object_size: usize,
@@ -150,6 +240,10 @@ pub struct ConfigInner {
     pub max_budget_fetch_count: usize,
 
     pub health_endpoint_key: String,
+    #[zeroize(skip)]
+    pub readiness_check_timeout: Duration,
+    #[zeroize(skip)]
+    pub job_staleness_threshold: Duration,
 }
 
 pub struct Config {
@@ -169,94 +263,165 @@ impl Deref for Config {
 unsafe impl Sync for Config {}
 
 impl Config {
-    pub fn from_env() -> Result<Config, ConfigError> {
-        let hashing_key = Zeroizing::new(
-            b64.decode(env_var::<String>(HASHING_KEY_VAR)?.as_bytes())
-                .map_err(|_| ConfigError::InvalidVar(HASHING_KEY_VAR))?,
-        );
-        let hashing_key = hashing_key[..HASHING_KEY_SIZE]
-            .try_into()
-            .map_err(|_| ConfigError::InvalidVar(HASHING_KEY_VAR))?;
-
-        let token_signing_key = Zeroizing::new(
-            b64.decode(env_var::<String>(TOKEN_SIGNING_KEY_VAR)?.as_bytes())
-                .map_err(|_| ConfigError::InvalidVar(TOKEN_SIGNING_KEY_VAR))?,
-        );
-        let token_signing_key = token_signing_key[..TOKEN_SIGNING_KEY_SIZE]
-            .try_into()
-            .map_err(|_| ConfigError::InvalidVar(TOKEN_SIGNING_KEY_VAR))?;
-
-        let email_from_address: Mailbox = env_var::<String>(EMAIL_FROM_ADDR)?
-            .parse()
-            .map_err(|_| ConfigError::InvalidVar(EMAIL_FROM_ADDR))?;
-        let email_reply_to_address: Mailbox = env_var::<String>(EMAIL_REPLY_TO_ADDR)?
-            .parse()
-            .map_err(|_| ConfigError::InvalidVar(EMAIL_REPLY_TO_ADDR))?;
+    pub fn load() -> Result<Config, ConfigError> {
+        Self::from_source(&ConfigSource::load()?)
+    }
+
+    pub fn from_source(source: &ConfigSource) -> Result<Config, ConfigError> {
+        let hashing_key = source.get_secret_key(HASHING_KEY_VAR)?;
+        let token_signing_key = source.get_secret_key(TOKEN_SIGNING_KEY_VAR)?;
+
+        let email_from_address = source.get_mailbox(EMAIL_FROM_ADDR)?;
+        let email_reply_to_address = source.get_mailbox(EMAIL_REPLY_TO_ADDR)?;
+
+        let tls_enabled = source.get_or(TLS_ENABLED_VAR, false)?;
+        let (tls_cert_path, tls_key_path) = if tls_enabled {
+            (
+                Some(source.get(TLS_CERT_PATH_VAR)?),
+                Some(source.get(TLS_KEY_PATH_VAR)?),
+            )
+        } else {
+            (None, None)
+        };
+
+        // Postgres connection settings aren't needed when the data lives in a SQLite file
+        let db_backend = source.get_or(DB_BACKEND_VAR, DbBackend::Postgres)?;
+        let (db_username, db_password, db_hostname, db_port, db_name) = match db_backend {
+            DbBackend::Postgres => (
+                source.get(DB_USERNAME_VAR)?,
+                source.get_secret(DB_PASSWORD_VAR)?,
+                source.get(DB_HOSTNAME_VAR)?,
+                source.get(DB_PORT_VAR)?,
+                source.get(DB_NAME_VAR)?,
+            ),
+            #[cfg(feature = "sqlite")]
+            DbBackend::Sqlite => (
+                String::new(),
+                String::new(),
+                String::new(),
+                0,
+                String::new(),
+            ),
+        };
 
         let inner = ConfigInner {
-            db_username: env_var(DB_USERNAME_VAR)?,
-            db_password: env_var(DB_PASSWORD_VAR)?,
-            db_hostname: env_var(DB_HOSTNAME_VAR)?,
-            db_port: env_var(DB_PORT_VAR)?,
-            db_name: env_var(DB_NAME_VAR)?,
-            db_max_connections: env_var_or(DB_MAX_CONNECTIONS_VAR, 48)?,
-            db_idle_timeout: Duration::from_secs(env_var_or(DB_IDLE_TIMEOUT_SECS_VAR, 30)?),
+            db_backend,
+            db_sqlite_path: source.get_or(DB_SQLITE_PATH_VAR, String::from("./entries.db"))?,
+            db_username,
+            db_password,
+            db_hostname,
+            db_port,
+            db_name,
+            db_max_connections: source.get_or(DB_MAX_CONNECTIONS_VAR, 48)?,
+            db_async_max_connections: source.get_or(DB_ASYNC_MAX_CONNECTIONS_VAR, 48)?,
+            db_idle_timeout: Duration::from_secs(source.get_or(DB_IDLE_TIMEOUT_SECS_VAR, 30)?),
+            db_statement_timeout: Duration::from_millis(
+                source.get_or(DB_STATEMENT_TIMEOUT_MS_VAR, 15000)?,
+            ),
+            db_replica_hostname: source.get_opt(DB_REPLICA_HOSTNAME_VAR)?,
+            db_replica_port: source.get_opt(DB_REPLICA_PORT_VAR)?,
+            db_read_your_writes_window: Duration::from_secs(
+                source.get_or(DB_READ_YOUR_WRITES_WINDOW_SECS_VAR, 10)?,
+            ),
+            run_migrations_on_startup: source.get_or(RUN_MIGRATIONS_ON_STARTUP_VAR, false)?,
 
             hashing_key,
             token_signing_key,
-            amazon_ses_username: env_var(AMAZON_SES_USERNAME_VAR)?,
-            amazon_ses_key: env_var(AMAZON_SES_KEY_VAR)?,
+            amazon_ses_username: source.get_secret(AMAZON_SES_USERNAME_VAR)?,
+            amazon_ses_key: source.get_secret(AMAZON_SES_KEY_VAR)?,
 
-            hash_length: env_var(HASH_LENGTH_VAR)?,
-            hash_iterations: env_var(HASH_ITERATIONS_VAR)?,
-            hash_mem_cost_kib: env_var(HASH_MEM_COST_KIB_VAR)?,
-            hash_threads: env_var(HASH_THREADS_VAR)?,
-            hash_salt_length: env_var(HASH_SALT_LENGTH_VAR)?,
+            hash_length: source.get(HASH_LENGTH_VAR)?,
+            hash_iterations: source.get(HASH_ITERATIONS_VAR)?,
+            hash_mem_cost_kib: source.get(HASH_MEM_COST_KIB_VAR)?,
+            hash_threads: source.get(HASH_THREADS_VAR)?,
+            hash_salt_length: source.get(HASH_SALT_LENGTH_VAR)?,
 
             email_enabled: if cfg!(test) {
                 false
             } else {
-                env_var(EMAIL_ENABLED_VAR)?
+                source.get(EMAIL_ENABLED_VAR)?
             },
             email_from_address,
             email_reply_to_address,
-            smtp_address: env_var(SMTP_ADDRESS_VAR)?,
-            max_smtp_connections: env_var_or(MAX_SMTP_CONNECTIONS_VAR, 24)?,
-            smtp_idle_timeout: Duration::from_secs(env_var_or(SMTP_IDLE_TIMEOUT_SECS_VAR, 60)?),
+            smtp_address: source.get(SMTP_ADDRESS_VAR)?,
+            max_smtp_connections: source.get_or(MAX_SMTP_CONNECTIONS_VAR, 24)?,
+            smtp_idle_timeout: Duration::from_secs(source.get_or(SMTP_IDLE_TIMEOUT_SECS_VAR, 60)?),
 
-            user_verification_url: env_var(USER_VERIFICATION_URL_VAR)?,
-            user_deletion_url: env_var(USER_DELETION_URL_VAR)?,
+            user_verification_url: source.get(USER_VERIFICATION_URL_VAR)?,
+            user_deletion_url: source.get(USER_DELETION_URL_VAR)?,
 
             access_token_lifetime: Duration::from_secs(
-                env_var_or(ACCESS_TOKEN_LIFETIME_MINS_VAR, 15)? * 60,
+                source.get_or(ACCESS_TOKEN_LIFETIME_MINS_VAR, 15)? * 60,
             ),
             refresh_token_lifetime: Duration::from_secs(
-                env_var_or(REFRESH_TOKEN_LIFETIME_DAYS_VAR, 30)? * 86400,
+                source.get_or(REFRESH_TOKEN_LIFETIME_DAYS_VAR, 30)? * 86400,
             ),
             signin_token_lifetime: Duration::from_secs(
-                env_var_or(SIGNIN_TOKEN_LIFETIME_MINS_VAR, 30)? * 60,
+                source.get_or(SIGNIN_TOKEN_LIFETIME_MINS_VAR, 30)? * 60,
             ),
             user_creation_token_lifetime: Duration::from_secs(
-                env_var_or(USER_CREATION_TOKEN_LIFETIME_DAYS_VAR, 7)? * 86400,
+                source.get_or(USER_CREATION_TOKEN_LIFETIME_DAYS_VAR, 7)? * 86400,
             ),
             user_deletion_token_lifetime: Duration::from_secs(
-                env_var_or(USER_DELETION_TOKEN_LIFETIME_DAYS_VAR, 7)? * 86400,
+                source.get_or(USER_DELETION_TOKEN_LIFETIME_DAYS_VAR, 7)? * 86400,
+            ),
+            otp_lifetime: Duration::from_secs(source.get_or(OTP_LIFETIME_MINS_VAR, 15)? * 60),
+            user_deletion_delay_days: source.get_or(USER_DELETION_DELAY_DAYS_VAR, 7)?,
+            idempotency_key_lifetime: Duration::from_secs(
+                source.get_or(IDEMPOTENCY_KEY_LIFETIME_HOURS_VAR, 24)? * 3600,
             ),
-            otp_lifetime: Duration::from_secs(env_var_or(OTP_LIFETIME_MINS_VAR, 15)? * 60),
-            user_deletion_delay_days: env_var_or(USER_DELETION_DELAY_DAYS_VAR, 7)?,
-
-            actix_worker_count: env_var_or(ACTIX_WORKER_COUNT_VAR, num_cpus::get())?,
-            log_level: env_var_or(LOG_LEVEL_VAR, String::from("info"))?,
-            protobuf_max_size: env_var_or(PROTOBUF_MAX_SIZE_MB_VAR, 100)? * 1024 * 1024,
 
-            max_small_object_size: env_var_or(MAX_SMALL_OBJECT_SIZE_KB_VAR, 4)? * 1024,
-            max_keystore_size: env_var_or(MAX_KEYSTORE_SIZE_KB_VAR, 80_000)? * 1024,
-            max_user_preferences_size: env_var_or(MAX_USER_PREFERENCES_SIZE_KB_VAR, 32)? * 1024,
-            max_encryption_key_size: env_var_or(MAX_ENCRYPTION_KEY_SIZE_KB_VAR, 4)? * 1024,
-            max_budgets: env_var_or(MAX_BUDGETS_VAR, 5_000)?,
-            max_budget_fetch_count: env_var_or(MAX_BUDGET_FETCH_COUNT_VAR, 50)?,
+            actix_worker_count: source.get_or(ACTIX_WORKER_COUNT_VAR, num_cpus::get())?,
+            log_level: source.get_or(LOG_LEVEL_VAR, String::from("info"))?,
+            log_format: source.get_or(LOG_FORMAT_VAR, LogFormat::Text)?,
+            protobuf_max_size: source.get_or(PROTOBUF_MAX_SIZE_MB_VAR, 100)? * 1024 * 1024,
+            bind_addresses: source.get_list(BIND_ADDRESSES_VAR)?,
+            unix_socket_path: source.get_opt(UNIX_SOCKET_PATH_VAR)?,
+            grpc_bind_address: source.get_opt(GRPC_BIND_ADDRESS_VAR)?,
+            tls_enabled,
+            tls_cert_path,
+            tls_key_path,
+            tls_reload_interval: Duration::from_secs(
+                source.get_or(TLS_RELOAD_INTERVAL_SECS_VAR, 60)?,
+            ),
+            http_redirect_port: source.get_opt(HTTP_REDIRECT_PORT_VAR)?,
+            hsts_max_age: source
+                .get_opt(HSTS_MAX_AGE_SECS_VAR)?
+                .map(Duration::from_secs),
+            web_dir: source.get_opt(WEB_DIR_VAR)?,
+            web_mount_path: source.get_or(WEB_MOUNT_PATH_VAR, WebMountPath::root())?,
+            web_cache_max_age: Duration::from_secs(
+                source.get_or(WEB_CACHE_MAX_AGE_SECS_VAR, 3600)?,
+            ),
+            min_client_versions: source.get_list(MIN_CLIENT_VERSIONS_VAR)?,
+            recommended_client_versions: source.get_list(RECOMMENDED_CLIENT_VERSIONS_VAR)?,
+            shutdown_timeout: Duration::from_secs(source.get_or(SHUTDOWN_TIMEOUT_SECS_VAR, 30)?),
+            shutdown_readiness_delay: Duration::from_secs(
+                source.get_or(SHUTDOWN_READINESS_DELAY_SECS_VAR, 5)?,
+            ),
 
-            health_endpoint_key: env_var(HEALTH_ENDPOINT_KEY_VAR)?,
+            trace_exporter: source.get_or(TRACE_EXPORTER_VAR, TraceExporter::None)?,
+            otlp_endpoint: source.get_or(
+                OTLP_ENDPOINT_VAR,
+                String::from("http://localhost:4318/v1/traces"),
+            )?,
+            trace_file_path: source
+                .get_or(TRACE_FILE_PATH_VAR, String::from("./logs/traces.jsonl"))?,
+
+            max_small_object_size: source.get_or(MAX_SMALL_OBJECT_SIZE_KB_VAR, 4)? * 1024,
+            max_keystore_size: source.get_or(MAX_KEYSTORE_SIZE_KB_VAR, 80_000)? * 1024,
+            max_user_preferences_size: source.get_or(MAX_USER_PREFERENCES_SIZE_KB_VAR, 32)? * 1024,
+            max_encryption_key_size: source.get_or(MAX_ENCRYPTION_KEY_SIZE_KB_VAR, 4)? * 1024,
+            max_budgets: source.get_or(MAX_BUDGETS_VAR, 5_000)?,
+            max_budget_fetch_count: source.get_or(MAX_BUDGET_FETCH_COUNT_VAR, 50)?,
+
+            health_endpoint_key: source.get_secret(HEALTH_ENDPOINT_KEY_VAR)?,
+            readiness_check_timeout: Duration::from_millis(
+                source.get_or(READINESS_CHECK_TIMEOUT_MS_VAR, 2000)?,
+            ),
+            job_staleness_threshold: Duration::from_secs(
+                source.get_or(JOB_STALENESS_THRESHOLD_HOURS_VAR, 72)? * 3600,
+            ),
         };
 
         Ok(Config {
@@ -277,68 +442,301 @@ impl Config {
     }
 }
 
-fn env_var<T: FromStr>(key: &'static str) -> Result<T, ConfigError> {
-    let var = std::env::var(key).map_err(|_| ConfigError::missing(key))?;
-    let var: T = var.parse().map_err(|_| ConfigError::invalid(key))?;
-    Ok(var)
+/// The database the server stores its data in. SQLite is only available when the server is
+/// built with the `sqlite` feature.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DbBackend {
+    Postgres,
+    #[cfg(feature = "sqlite")]
+    Sqlite,
 }
 
-fn env_var_or<T: FromStr>(key: &'static str, default: T) -> Result<T, ConfigError> {
-    let Ok(var) = std::env::var(key) else {
-        return Ok(default);
-    };
+impl FromStr for DbBackend {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "postgres" => Ok(DbBackend::Postgres),
+            #[cfg(feature = "sqlite")]
+            "sqlite" => Ok(DbBackend::Sqlite),
+            _ => Err(()),
+        }
+    }
+}
+
+impl fmt::Display for DbBackend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DbBackend::Postgres => write!(f, "postgres"),
+            #[cfg(feature = "sqlite")]
+            DbBackend::Sqlite => write!(f, "sqlite"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LogFormat {
+    Text,
+    Json,
+}
+
+impl FromStr for LogFormat {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "text" => Ok(LogFormat::Text),
+            "json" => Ok(LogFormat::Json),
+            _ => Err(()),
+        }
+    }
+}
 
-    var.parse().map_err(|_| ConfigError::invalid(key))
+impl fmt::Display for LogFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LogFormat::Text => write!(f, "text"),
+            LogFormat::Json => write!(f, "json"),
+        }
+    }
 }
 
-#[derive(Clone, Copy, Debug)]
-pub enum ConfigError {
-    MissingVar(&'static str),
-    InvalidVar(&'static str),
+/// Where the web directory is served from. Stored without a trailing slash, so the root is an
+/// empty string. Paths used by other services are rejected.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WebMountPath(String);
+
+impl WebMountPath {
+    const RESERVED_PREFIXES: [&'static str; 2] = ["api", "metrics"];
+
+    pub fn root() -> Self {
+        Self(String::new())
+    }
+
+    pub fn is_root(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl FromStr for WebMountPath {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let path = s.trim().strip_prefix('/').ok_or(())?.trim_end_matches('/');
+
+        if path.is_empty() {
+            return Ok(Self::root());
+        }
+
+        for (i, segment) in path.split('/').enumerate() {
+            let is_valid_segment = !segment.is_empty()
+                && segment != "."
+                && segment != ".."
+                && segment
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '~'));
+
+            if !is_valid_segment || (i == 0 && Self::RESERVED_PREFIXES.contains(&segment)) {
+                return Err(());
+            }
+        }
+
+        Ok(Self(format!("/{path}")))
+    }
 }
 
-impl ConfigError {
-    fn missing(var_name: &'static str) -> Self {
-        Self::MissingVar(var_name)
+impl fmt::Display for WebMountPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_root() {
+            write!(f, "/")
+        } else {
+            write!(f, "{}", self.0)
+        }
     }
+}
+
+/// A client version for a platform, written as `platform=version` (e.g. `ios=1.4.0`). The
+/// platform `*` applies to clients whose platform isn't otherwise listed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PlatformVersion {
+    pub platform: String,
+    pub version: Version,
+}
 
-    fn invalid(var_name: &'static str) -> Self {
-        Self::InvalidVar(var_name)
+impl FromStr for PlatformVersion {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (platform, version) = s.split_once('=').ok_or(())?;
+        let platform = platform.trim().to_lowercase();
+
+        let is_valid_platform = platform == "*"
+            || (!platform.is_empty()
+                && platform
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_')));
+
+        if !is_valid_platform {
+            return Err(());
+        }
+
+        Ok(Self {
+            platform,
+            version: version.trim().parse().map_err(|_| ())?,
+        })
     }
 }
 
-impl std::error::Error for ConfigError {}
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TraceExporter {
+    None,
+    Otlp,
+    Stdout,
+    File,
+}
+
+impl FromStr for TraceExporter {
+    type Err = ();
 
-impl fmt::Display for ConfigError {
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "none" => Ok(TraceExporter::None),
+            "otlp" => Ok(TraceExporter::Otlp),
+            "stdout" => Ok(TraceExporter::Stdout),
+            "file" => Ok(TraceExporter::File),
+            _ => Err(()),
+        }
+    }
+}
+
+impl fmt::Display for TraceExporter {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Self::MissingVar(key) => write!(f, "Missing environment variable '{}'", key),
-            Self::InvalidVar(key) => write!(f, "Environment variable '{}' is invalid", key),
+            TraceExporter::None => write!(f, "none"),
+            TraceExporter::Otlp => write!(f, "otlp"),
+            TraceExporter::Stdout => write!(f, "stdout"),
+            TraceExporter::File => write!(f, "file"),
         }
     }
 }
 
+/// Loads and validates the configuration, then prints the effective configuration with
+/// secrets redacted. Returns whether the configuration is valid.
+pub fn check_config() -> bool {
+    let source = match ConfigSource::load() {
+        Ok(s) => s.collect_errors(),
+        Err(e) => {
+            eprintln!("ERROR: {e}");
+            return false;
+        }
+    };
+
+    // Missing and invalid values are collected by the source instead of failing the load, so
+    // every problem can be reported at once
+    let config = Config::from_source(&source);
+    let config_errors = source.take_errors();
+    let mut errors: Vec<String> = config_errors
+        .iter()
+        .chain(config.as_ref().err())
+        .map(ToString::to_string)
+        .collect();
+
+    let are_tls_paths_valid = !config_errors
+        .iter()
+        .any(|e| matches!(e.var(), Some(TLS_CERT_PATH_VAR | TLS_KEY_PATH_VAR)));
+
+    if let Ok(config) = &config {
+        if config.tls_enabled && are_tls_paths_valid {
+            if let Err(e) = crate::tls::CertResolver::load(
+                config.tls_cert_path.as_deref().unwrap_or_default(),
+                config.tls_key_path.as_deref().unwrap_or_default(),
+            ) {
+                errors.push(e.to_string());
+            }
+        }
+    }
+
+    match source.file_path() {
+        Some(path) => println!("Config file: {}", path.display()),
+        None => println!("Config file: none"),
+    }
+
+    println!();
+    print!("{}", source.effective_config());
+
+    let unused_keys = source.unused_file_keys();
+    if !unused_keys.is_empty() {
+        println!();
+        println!("Keys in the config file not used by this binary:");
+
+        for key in unused_keys {
+            println!("  {key}");
+        }
+    }
+
+    for e in &errors {
+        eprintln!("ERROR: {e}");
+    }
+
+    if let Ok(config) = config {
+        // Safe because this config is never shared
+        unsafe {
+            config.zeroize();
+        }
+    }
+
+    errors.is_empty()
+}
+
 #[cfg(test)]
 pub mod testing {
-    use entries_common::db::{create_db_thread_pool, DbThreadPool};
+    use entries_common::db::DbThreadPool;
+    use entries_common::db::{create_async_db_pool, create_db_thread_pool, AsyncDbPools};
     use entries_common::email::senders::MockSender;
     use entries_common::email::SendEmail;
 
+    use actix_web::web::ServiceConfig;
     use std::sync::Arc;
 
     use super::*;
+    use crate::daos::Daos;
 
     pub static DB_THREAD_POOL: Lazy<DbThreadPool> = Lazy::new(|| {
         create_db_thread_pool(
-            &format!(
-                "postgres://{}:{}@{}:{}/{}",
-                CONF.db_username, CONF.db_password, CONF.db_hostname, CONF.db_port, CONF.db_name,
-            ),
+            &db_uri(),
             CONF.db_max_connections,
             CONF.db_idle_timeout,
+            Some(CONF.db_statement_timeout),
         )
     });
 
+    /// Connections in an async pool are driven by the runtime that opened them, so each test
+    /// (which gets its own runtime) needs its own pool rather than a shared static one
+    pub fn async_db_pools() -> AsyncDbPools {
+        AsyncDbPools::from(create_async_db_pool(
+            &db_uri(),
+            4,
+            Some(CONF.db_statement_timeout),
+        ))
+    }
+
+    /// Registers Postgres-backed DAOs. Tests that don't exercise SQL can register
+    /// `Daos::memory` instead and run without a database.
+    pub fn configure_daos(cfg: &mut ServiceConfig) {
+        Daos::postgres(&DB_THREAD_POOL, &async_db_pools()).configure(cfg);
+    }
+
+    fn db_uri() -> String {
+        format!(
+            "postgres://{}:{}@{}:{}/{}",
+            CONF.db_username, CONF.db_password, CONF.db_hostname, CONF.db_port, CONF.db_name,
+        )
+    }
+
     pub static SMTP_THREAD_POOL: Lazy<Arc<Box<dyn SendEmail>>> =
         Lazy::new(|| Arc::new(Box::new(MockSender::new())));
 }
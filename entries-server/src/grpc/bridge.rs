@@ -0,0 +1,178 @@
+use actix_http::h1;
+use actix_service::IntoServiceFactory;
+use actix_web::body::{self, MessageBody};
+use actix_web::dev::{AppConfig, Service, ServiceFactory, ServiceRequest, ServiceResponse};
+use actix_web::http::header::{HeaderMap, HeaderName, HeaderValue};
+use actix_web::http::{Method, StatusCode};
+use actix_web::web::Bytes;
+use actix_web::App;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use tokio::sync::{mpsc, oneshot};
+
+/// An HTTP request to run through the actix app on one of the bridge's workers
+#[derive(Debug)]
+pub struct BridgeRequest {
+    pub method: Method,
+    pub uri: String,
+    pub headers: Vec<(HeaderName, HeaderValue)>,
+    pub peer_addr: Option<SocketAddr>,
+    pub body: Bytes,
+}
+
+#[derive(Debug)]
+pub struct BridgeResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: Bytes,
+}
+
+struct Call {
+    request: BridgeRequest,
+    reply: oneshot::Sender<BridgeResponse>,
+}
+
+/// Runs requests from outside of actix through the same app, routes, and middleware that serve
+/// the HTTP API. Like `HttpServer`, each worker thread runs its own actix system with its own
+/// instance of the app.
+#[derive(Clone)]
+pub struct Bridge {
+    workers: Arc<[mpsc::UnboundedSender<Call>]>,
+    next_worker: Arc<AtomicUsize>,
+}
+
+impl Bridge {
+    pub fn start<F, T, B>(worker_count: usize, app_factory: F) -> Self
+    where
+        F: Fn() -> App<T> + Send + Clone + 'static,
+        T: ServiceFactory<
+                ServiceRequest,
+                Config = (),
+                Response = ServiceResponse<B>,
+                Error = actix_web::Error,
+                InitError = (),
+            > + 'static,
+        B: MessageBody + 'static,
+    {
+        let workers = (0..worker_count.max(1))
+            .map(|i| {
+                let (sender, receiver) = mpsc::unbounded_channel();
+                let app_factory = app_factory.clone();
+
+                thread::Builder::new()
+                    .name(format!("grpc-bridge-{i}"))
+                    .spawn(move || {
+                        actix_rt::System::new().block_on(run_worker(app_factory(), receiver))
+                    })
+                    .expect("Failed to spawn gRPC bridge worker");
+
+                sender
+            })
+            .collect();
+
+        Self {
+            workers,
+            next_worker: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// Returns `None` if the worker stopped before responding.
+    pub async fn send(&self, request: BridgeRequest) -> Option<BridgeResponse> {
+        let worker = self.next_worker.fetch_add(1, Ordering::Relaxed) % self.workers.len();
+        let (reply_sender, reply_receiver) = oneshot::channel();
+
+        self.workers[worker]
+            .send(Call {
+                request,
+                reply: reply_sender,
+            })
+            .ok()?;
+
+        reply_receiver.await.ok()
+    }
+}
+
+/// Handles calls until every `Bridge` sending to this worker has been dropped
+async fn run_worker<T, B>(app: App<T>, mut receiver: mpsc::UnboundedReceiver<Call>)
+where
+    T: ServiceFactory<
+            ServiceRequest,
+            Config = (),
+            Response = ServiceResponse<B>,
+            Error = actix_web::Error,
+            InitError = (),
+        > + 'static,
+    B: MessageBody + 'static,
+{
+    let service = app
+        .into_factory()
+        .new_service(AppConfig::default())
+        .await
+        .expect("Failed to start gRPC bridge worker");
+    let service = Rc::new(service);
+
+    while let Some(call) = receiver.recv().await {
+        let service = Rc::clone(&service);
+
+        actix_rt::spawn(async move {
+            let response = dispatch(service.as_ref(), call.request).await;
+
+            // The caller is gone if this fails, so there is nobody to give the response to
+            let _ = call.reply.send(response);
+        });
+    }
+}
+
+async fn dispatch<S, B>(service: &S, request: BridgeRequest) -> BridgeResponse
+where
+    S: Service<actix_http::Request, Response = ServiceResponse<B>, Error = actix_web::Error>,
+    B: MessageBody + 'static,
+{
+    let uri = match request.uri.parse() {
+        Ok(uri) => uri,
+        Err(_) => {
+            return BridgeResponse {
+                status: StatusCode::BAD_REQUEST,
+                headers: HeaderMap::new(),
+                body: Bytes::new(),
+            };
+        }
+    };
+
+    let (mut payload_sender, payload) = h1::Payload::create(false);
+    payload_sender.feed_data(request.body);
+    payload_sender.feed_eof();
+
+    let mut req = actix_http::Request::with_payload(payload.into());
+    let head = req.head_mut();
+    head.method = request.method;
+    head.uri = uri;
+    // The rate limiters key on the peer address, so there must always be one
+    head.peer_addr = Some(
+        request
+            .peer_addr
+            .unwrap_or_else(|| SocketAddr::from((Ipv4Addr::UNSPECIFIED, 0))),
+    );
+
+    for (name, value) in request.headers {
+        head.headers.append(name, value);
+    }
+
+    let resp = match service.call(req).await {
+        Ok(resp) => resp.into_parts().1.map_into_boxed_body(),
+        Err(e) => e.error_response(),
+    };
+
+    let status = resp.status();
+    let headers = resp.headers().clone();
+    let body = body::to_bytes(resp.into_body()).await.unwrap_or_default();
+
+    BridgeResponse {
+        status,
+        headers,
+        body,
+    }
+}
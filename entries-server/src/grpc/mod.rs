@@ -0,0 +1,447 @@
+use entries_common::messages::ServerErrorResponse;
+
+use actix_web::body::MessageBody;
+use actix_web::dev::{ServiceFactory, ServiceRequest, ServiceResponse};
+use actix_web::http::header::{self, HeaderMap, HeaderName, HeaderValue};
+use actix_web::http::{Method, StatusCode};
+use actix_web::web::{self, ServiceConfig};
+use actix_web::App;
+use prost::Message;
+use std::net::SocketAddr;
+use std::thread;
+use tokio::sync::{oneshot, watch};
+use tonic::metadata::{MetadataMap, MetadataValue};
+use tonic::transport::server::TcpIncoming;
+use tonic::transport::Server;
+use tonic::{Code, Request, Response, Status};
+
+use crate::env;
+use crate::handlers;
+
+mod bridge;
+mod services;
+
+use bridge::{Bridge, BridgeRequest, BridgeResponse};
+
+pub mod rpc {
+    tonic::include_proto!("entries.rpc");
+}
+
+use rpc::auth_service_server::AuthServiceServer;
+use rpc::budget_service_server::BudgetServiceServer;
+use rpc::user_service_server::UserServiceServer;
+
+/// Metadata that is passed to the handlers as request headers. gRPC metadata keys are
+/// lowercase, and header names are case-insensitive.
+const FORWARDED_METADATA: &[&str] = &[
+    "accesstoken",
+    "refreshtoken",
+    "signintoken",
+    "budgetaccesstoken",
+    "budgetinvitesendertoken",
+    "budgetaccepttoken",
+    "appversion",
+    "idempotency-key",
+    "x-request-id",
+];
+
+/// Response headers that are passed back to the client as metadata. `If-None-Match` isn't
+/// forwarded because RPCs have no equivalent of a 304 response, but the ETag is still returned so
+/// clients can use it for HTTP requests.
+const RETURNED_HEADERS: &[&str] = &[
+    "x-request-id",
+    "warning",
+    "idempotent-replayed",
+    "etag",
+    "retry-after",
+];
+
+const PROTOBUF_CONTENT_TYPE: &str = "application/protobuf";
+
+/// Routes used only by the gRPC services. Not registered on the HTTP server.
+pub fn configure(cfg: &mut ServiceConfig) {
+    cfg.service(web::scope("/grpc").route(
+        "/budget/changes",
+        web::get().to(handlers::budget::authorize_change_feed),
+    ));
+}
+
+/// Serves the gRPC services on `addr` from a thread of its own until `shutdown` resolves.
+/// Calls are handled by apps built with `app_factory` on `worker_count` bridge workers.
+pub fn start<F, T, B>(
+    addr: SocketAddr,
+    worker_count: usize,
+    app_factory: F,
+    shutdown: oneshot::Receiver<()>,
+) -> std::io::Result<thread::JoinHandle<()>>
+where
+    F: Fn() -> App<T> + Send + Clone + 'static,
+    T: ServiceFactory<
+            ServiceRequest,
+            Config = (),
+            Response = ServiceResponse<B>,
+            Error = actix_web::Error,
+            InitError = (),
+        > + 'static,
+    B: MessageBody + 'static,
+{
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .thread_name("grpc")
+        .enable_all()
+        .build()?;
+
+    // Bind right away so a bad address fails startup rather than failing in the background
+    let incoming = {
+        let _guard = runtime.enter();
+        TcpIncoming::new(addr, true, None).map_err(std::io::Error::other)?
+    };
+
+    let bridge = Bridge::start(worker_count, app_factory);
+    let (stop_streams_sender, stop_streams) = watch::channel(false);
+
+    let auth_service = AuthServiceServer::new(services::Auth::new(bridge.clone()))
+        .max_decoding_message_size(env::CONF.protobuf_max_size);
+    let user_service = UserServiceServer::new(services::User::new(bridge.clone()))
+        .max_decoding_message_size(env::CONF.protobuf_max_size);
+    let budget_service = BudgetServiceServer::new(services::Budget::new(bridge, stop_streams))
+        .max_decoding_message_size(env::CONF.protobuf_max_size);
+
+    thread::Builder::new()
+        .name(String::from("grpc-server"))
+        .spawn(move || {
+            runtime.block_on(async move {
+                let result = Server::builder()
+                    .add_service(auth_service)
+                    .add_service(user_service)
+                    .add_service(budget_service)
+                    .serve_with_incoming_shutdown(incoming, async move {
+                        let _ = shutdown.await;
+
+                        // Change feeds never end on their own, so the server would wait on
+                        // them forever
+                        let _ = stop_streams_sender.send(true);
+                    })
+                    .await;
+
+                if let Err(e) = result {
+                    log::error!("gRPC server failed: {e}");
+                }
+            })
+        })
+}
+
+/// Runs a call through the route at `path`, sending the request message as the body
+async fn forward<T, R>(
+    bridge: &Bridge,
+    method: Method,
+    path: &str,
+    request: Request<T>,
+) -> Result<Response<R>, Status>
+where
+    T: Message,
+    R: Message + Default,
+{
+    let body = request.get_ref().encode_to_vec();
+    send(bridge, method, path.to_owned(), &request, body).await
+}
+
+/// Runs a call through a GET route that takes the user's email address as a query parameter
+async fn forward_email_query<R>(
+    bridge: &Bridge,
+    path: &str,
+    request: Request<rpc::UserEmail>,
+) -> Result<Response<R>, Status>
+where
+    R: Message + Default,
+{
+    let query = serde_urlencoded::to_string([("email", &request.get_ref().email)])
+        .map_err(|_| Status::invalid_argument("Invalid email"))?;
+
+    send(
+        bridge,
+        Method::GET,
+        format!("{path}?{query}"),
+        &request,
+        Vec::new(),
+    )
+    .await
+}
+
+async fn send<T, R>(
+    bridge: &Bridge,
+    method: Method,
+    uri: String,
+    request: &Request<T>,
+    body: Vec<u8>,
+) -> Result<Response<R>, Status>
+where
+    R: Message + Default,
+{
+    let mut headers = forwarded_headers(request.metadata());
+    headers.push((
+        header::CONTENT_TYPE,
+        HeaderValue::from_static(PROTOBUF_CONTENT_TYPE),
+    ));
+    headers.push((
+        header::ACCEPT,
+        HeaderValue::from_static(PROTOBUF_CONTENT_TYPE),
+    ));
+
+    let resp = bridge
+        .send(BridgeRequest {
+            method,
+            uri,
+            headers,
+            peer_addr: request.remote_addr(),
+            body: body.into(),
+        })
+        .await
+        .ok_or_else(|| Status::unavailable("Server is shutting down"))?;
+
+    if !resp.status.is_success() {
+        return Err(into_status(resp));
+    }
+
+    // Handlers that don't return a message respond with an empty body, which decodes to the
+    // default message
+    let message =
+        R::decode(resp.body).map_err(|_| Status::internal("Failed to decode response"))?;
+
+    let mut response = Response::new(message);
+    return_headers(&resp.headers, response.metadata_mut());
+
+    Ok(response)
+}
+
+fn forwarded_headers(metadata: &MetadataMap) -> Vec<(HeaderName, HeaderValue)> {
+    FORWARDED_METADATA
+        .iter()
+        .filter_map(|key| {
+            let value = HeaderValue::from_bytes(metadata.get(*key)?.as_bytes()).ok()?;
+            Some((HeaderName::from_static(key), value))
+        })
+        .collect()
+}
+
+fn return_headers(headers: &HeaderMap, metadata: &mut MetadataMap) {
+    for name in RETURNED_HEADERS {
+        let Some(value) = headers.get(*name) else {
+            continue;
+        };
+
+        if let Ok(value) = MetadataValue::try_from(value.as_bytes()) {
+            metadata.insert(*name, value);
+        }
+    }
+}
+
+/// The status carries the encoded `ServerErrorResponse` as its details so clients get the same
+/// error types as over HTTP
+fn into_status(resp: BridgeResponse) -> Status {
+    let code = status_code_to_grpc_code(resp.status);
+
+    let mut status = match ServerErrorResponse::decode(resp.body.clone()) {
+        Ok(error) if !resp.body.is_empty() => {
+            Status::with_details(code, error.err_message, resp.body)
+        }
+        _ => Status::new(
+            code,
+            resp.status.canonical_reason().unwrap_or("Request failed"),
+        ),
+    };
+
+    return_headers(&resp.headers, status.metadata_mut());
+
+    status
+}
+
+fn status_code_to_grpc_code(status: StatusCode) -> Code {
+    match status {
+        StatusCode::BAD_REQUEST => Code::InvalidArgument,
+        StatusCode::UNAUTHORIZED => Code::Unauthenticated,
+        StatusCode::FORBIDDEN => Code::PermissionDenied,
+        StatusCode::NOT_FOUND => Code::NotFound,
+        StatusCode::METHOD_NOT_ALLOWED => Code::Unimplemented,
+        StatusCode::PAYLOAD_TOO_LARGE | StatusCode::IM_A_TEAPOT | StatusCode::TOO_MANY_REQUESTS => {
+            Code::ResourceExhausted
+        }
+        StatusCode::UPGRADE_REQUIRED => Code::FailedPrecondition,
+        StatusCode::SERVICE_UNAVAILABLE => Code::Unavailable,
+        _ => Code::Internal,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use entries_common::messages::{
+        BudgetFrame, CategoryId, ErrorType, NewBudget, NewEncryptedBlob, SigninNonceAndHashParams,
+    };
+
+    use actix_web::web::Data;
+    use futures::StreamExt;
+    use uuid::Uuid;
+
+    use rpc::budget_service_server::BudgetService;
+
+    use crate::handlers::test_utils;
+    use crate::middleware::proto_or_json::ProtoOrJsonConfig;
+    use crate::middleware::request_id::RequestId;
+    use crate::services::api::RouteLimiters;
+
+    fn start_bridge() -> Bridge {
+        Bridge::start(1, || {
+            App::new()
+                .app_data(Data::new(env::testing::DB_THREAD_POOL.clone()))
+                .app_data(Data::new(env::testing::async_db_pools()))
+                .configure(env::testing::configure_daos)
+                .app_data(Data::new(env::testing::SMTP_THREAD_POOL.clone()))
+                .app_data(ProtoOrJsonConfig::default())
+                .configure(|cfg| crate::services::api::configure(cfg, RouteLimiters::default()))
+                .configure(configure)
+                .wrap(RequestId)
+        })
+    }
+
+    fn with_tokens<T>(message: T, access_token: &str, budget_access_token: &str) -> Request<T> {
+        let mut request = Request::new(message);
+        request
+            .metadata_mut()
+            .insert("accesstoken", access_token.parse().unwrap());
+        request
+            .metadata_mut()
+            .insert("budgetaccesstoken", budget_access_token.parse().unwrap());
+        request
+    }
+
+    #[test]
+    fn test_status_code_to_grpc_code() {
+        assert_eq!(
+            status_code_to_grpc_code(StatusCode::BAD_REQUEST),
+            Code::InvalidArgument
+        );
+        assert_eq!(
+            status_code_to_grpc_code(StatusCode::UNAUTHORIZED),
+            Code::Unauthenticated
+        );
+        assert_eq!(
+            status_code_to_grpc_code(StatusCode::IM_A_TEAPOT),
+            Code::ResourceExhausted
+        );
+        assert_eq!(
+            status_code_to_grpc_code(StatusCode::UPGRADE_REQUIRED),
+            Code::FailedPrecondition
+        );
+        assert_eq!(
+            status_code_to_grpc_code(StatusCode::BAD_GATEWAY),
+            Code::Internal
+        );
+    }
+
+    #[actix_web::test]
+    async fn test_forward() {
+        let bridge = start_bridge();
+
+        let mut request = Request::new(rpc::UserEmail {
+            email: String::from("nobody@test.com"),
+        });
+        request
+            .metadata_mut()
+            .insert("x-request-id", "grpc-test".parse().unwrap());
+
+        let response: Response<SigninNonceAndHashParams> =
+            forward_email_query(&bridge, "/api/auth/nonce_and_auth_string_params", request)
+                .await
+                .unwrap();
+        assert_eq!(
+            response.metadata().get("x-request-id").unwrap(),
+            "grpc-test"
+        );
+        assert!(!response.get_ref().auth_string_salt.is_empty());
+
+        let (_, access_token, _, _) = test_utils::create_user().await;
+
+        let mut request = Request::new(NewBudget {
+            encrypted_blob: vec![1; 8],
+            version_nonce: 1,
+            categories: Vec::new(),
+            user_public_budget_key: vec![2; 32],
+        });
+        request
+            .metadata_mut()
+            .insert("accesstoken", access_token.parse().unwrap());
+
+        let response: Response<BudgetFrame> =
+            forward(&bridge, Method::POST, "/api/budget", request)
+                .await
+                .unwrap();
+        assert_eq!(response.get_ref().access_key_id.value.len(), 16);
+
+        // Tokens only count when sent in the metadata
+        let status = forward::<_, BudgetFrame>(
+            &bridge,
+            Method::POST,
+            "/api/budget",
+            Request::new(NewBudget::default()),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(status.code(), Code::Unauthenticated);
+
+        let error = ServerErrorResponse::decode(status.details()).unwrap();
+        assert_eq!(error.err_type, ErrorType::TokenMissing as i32);
+        assert_eq!(status.message(), error.err_message);
+    }
+
+    #[actix_web::test]
+    async fn test_watch_changes() {
+        let (_, access_token, _, _) = test_utils::create_user().await;
+        let (budget, budget_access_token) = test_utils::create_budget(&access_token).await;
+
+        let (stop_streams_sender, stop_streams) = watch::channel(false);
+        let service = services::Budget::new(start_bridge(), stop_streams);
+
+        let mut changes = service
+            .watch_changes(with_tokens(
+                rpc::Empty {},
+                &access_token,
+                &budget_access_token,
+            ))
+            .await
+            .unwrap()
+            .into_inner();
+
+        let category_id: CategoryId = service
+            .create_category(with_tokens(
+                NewEncryptedBlob {
+                    value: vec![1; 8],
+                    version_nonce: 1,
+                    id: None,
+                },
+                &access_token,
+                &budget_access_token,
+            ))
+            .await
+            .unwrap()
+            .into_inner();
+
+        let change = changes.next().await.unwrap().unwrap();
+        assert_eq!(change.change_type(), rpc::BudgetChangeType::CategoryCreated);
+        assert_eq!(Uuid::try_from(&change.budget_id).unwrap(), budget.id);
+        assert_eq!(change.object_id, Some(category_id.value));
+
+        stop_streams_sender.send(true).unwrap();
+        assert!(changes.next().await.is_none());
+
+        let mut request = Request::new(rpc::Empty {});
+        request
+            .metadata_mut()
+            .insert("accesstoken", access_token.parse().unwrap());
+
+        let Err(status) = service.watch_changes(request).await else {
+            panic!("Watching changes without a budget access token should fail");
+        };
+        assert_eq!(status.code(), Code::Unauthenticated);
+    }
+}
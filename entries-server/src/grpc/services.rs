@@ -0,0 +1,414 @@
+use entries_common::messages::{
+    AuthStringAndEncryptedPasswordUpdate, BackupCode, BackupCodeList,
+    BackupCodesAndVerificationEmailSent, BudgetAccessTokenList, BudgetFrame,
+    BudgetIdAndEncryptionKey, BudgetList, BudgetShareInviteList, CategoryId, CategoryUpdate,
+    CredentialPair, EncryptedBlob, EncryptedBlobAndCategoryId, EncryptedBlobUpdate,
+    EntryAndCategory, EntryId, EntryIdAndCategoryId, EntryUpdate, InvitationId,
+    IsUserListedForDeletion, NewBudget, NewEncryptedBlob, NewUser, NewUserPublicKey, Otp,
+    PublicKey, RecoveryKeyUpdate, SigninNonceAndHashParams, SigninToken, TokenPair, UserBootstrap,
+    UserInvitationToBudget, UserPublicKey, VerificationEmailSent,
+};
+
+use actix_web::http::Method;
+use futures::stream::{self, Stream};
+use std::pin::Pin;
+use tokio::sync::watch;
+use tonic::{Request, Response, Status};
+use uuid::Uuid;
+
+use crate::change_feed::{self, ChangeType};
+
+use super::rpc::{self, auth_service_server, budget_service_server, user_service_server, Empty};
+use super::{forward, forward_email_query, Bridge};
+
+pub struct Auth {
+    bridge: Bridge,
+}
+
+impl Auth {
+    pub fn new(bridge: Bridge) -> Self {
+        Self { bridge }
+    }
+}
+
+#[tonic::async_trait]
+impl auth_service_server::AuthService for Auth {
+    async fn get_nonce_and_auth_string_params(
+        &self,
+        request: Request<rpc::UserEmail>,
+    ) -> Result<Response<SigninNonceAndHashParams>, Status> {
+        forward_email_query(
+            &self.bridge,
+            "/api/auth/nonce_and_auth_string_params",
+            request,
+        )
+        .await
+    }
+
+    async fn sign_in(
+        &self,
+        request: Request<CredentialPair>,
+    ) -> Result<Response<SigninToken>, Status> {
+        forward(&self.bridge, Method::POST, "/api/auth/sign_in", request).await
+    }
+
+    async fn verify_otp(&self, request: Request<Otp>) -> Result<Response<TokenPair>, Status> {
+        forward(&self.bridge, Method::POST, "/api/auth/otp/verify", request).await
+    }
+
+    async fn use_backup_code(
+        &self,
+        request: Request<BackupCode>,
+    ) -> Result<Response<TokenPair>, Status> {
+        forward(
+            &self.bridge,
+            Method::POST,
+            "/api/auth/backup_code/use",
+            request,
+        )
+        .await
+    }
+
+    async fn regenerate_backup_codes(
+        &self,
+        request: Request<Otp>,
+    ) -> Result<Response<BackupCodeList>, Status> {
+        forward(
+            &self.bridge,
+            Method::PUT,
+            "/api/auth/backup_code/regenerate",
+            request,
+        )
+        .await
+    }
+
+    async fn obtain_otp(&self, request: Request<Empty>) -> Result<Response<Empty>, Status> {
+        forward(&self.bridge, Method::GET, "/api/auth/otp", request).await
+    }
+
+    async fn refresh_tokens(&self, request: Request<Empty>) -> Result<Response<TokenPair>, Status> {
+        forward(
+            &self.bridge,
+            Method::POST,
+            "/api/auth/token/refresh",
+            request,
+        )
+        .await
+    }
+
+    async fn logout(&self, request: Request<Empty>) -> Result<Response<Empty>, Status> {
+        forward(&self.bridge, Method::POST, "/api/auth/logout", request).await
+    }
+}
+
+pub struct User {
+    bridge: Bridge,
+}
+
+impl User {
+    pub fn new(bridge: Bridge) -> Self {
+        Self { bridge }
+    }
+}
+
+#[tonic::async_trait]
+impl user_service_server::UserService for User {
+    async fn create(
+        &self,
+        request: Request<NewUser>,
+    ) -> Result<Response<BackupCodesAndVerificationEmailSent>, Status> {
+        forward(&self.bridge, Method::POST, "/api/user", request).await
+    }
+
+    async fn init_delete(
+        &self,
+        request: Request<BudgetAccessTokenList>,
+    ) -> Result<Response<VerificationEmailSent>, Status> {
+        forward(&self.bridge, Method::DELETE, "/api/user", request).await
+    }
+
+    async fn get_public_key(
+        &self,
+        request: Request<rpc::UserEmail>,
+    ) -> Result<Response<UserPublicKey>, Status> {
+        forward_email_query(&self.bridge, "/api/user/public_key", request).await
+    }
+
+    async fn rotate_public_key(
+        &self,
+        request: Request<NewUserPublicKey>,
+    ) -> Result<Response<Empty>, Status> {
+        forward(&self.bridge, Method::PUT, "/api/user/public_key", request).await
+    }
+
+    async fn get_bootstrap(
+        &self,
+        request: Request<Empty>,
+    ) -> Result<Response<UserBootstrap>, Status> {
+        forward(&self.bridge, Method::GET, "/api/user/bootstrap", request).await
+    }
+
+    async fn get_preferences(
+        &self,
+        request: Request<Empty>,
+    ) -> Result<Response<EncryptedBlob>, Status> {
+        forward(&self.bridge, Method::GET, "/api/user/preferences", request).await
+    }
+
+    async fn get_keystore(
+        &self,
+        request: Request<Empty>,
+    ) -> Result<Response<EncryptedBlob>, Status> {
+        forward(&self.bridge, Method::GET, "/api/user/keystore", request).await
+    }
+
+    async fn edit_preferences(
+        &self,
+        request: Request<EncryptedBlobUpdate>,
+    ) -> Result<Response<Empty>, Status> {
+        forward(&self.bridge, Method::PUT, "/api/user/preferences", request).await
+    }
+
+    async fn edit_keystore(
+        &self,
+        request: Request<EncryptedBlobUpdate>,
+    ) -> Result<Response<Empty>, Status> {
+        forward(&self.bridge, Method::PUT, "/api/user/keystore", request).await
+    }
+
+    async fn change_password(
+        &self,
+        request: Request<AuthStringAndEncryptedPasswordUpdate>,
+    ) -> Result<Response<Empty>, Status> {
+        forward(&self.bridge, Method::PUT, "/api/user/password", request).await
+    }
+
+    async fn change_recovery_key(
+        &self,
+        request: Request<RecoveryKeyUpdate>,
+    ) -> Result<Response<Empty>, Status> {
+        forward(&self.bridge, Method::PUT, "/api/user/recovery_key", request).await
+    }
+
+    async fn is_listed_for_deletion(
+        &self,
+        request: Request<Empty>,
+    ) -> Result<Response<IsUserListedForDeletion>, Status> {
+        forward(&self.bridge, Method::GET, "/api/user/deletion", request).await
+    }
+
+    async fn cancel_delete(&self, request: Request<Empty>) -> Result<Response<Empty>, Status> {
+        forward(&self.bridge, Method::DELETE, "/api/user/deletion", request).await
+    }
+}
+
+pub struct Budget {
+    bridge: Bridge,
+    stop_streams: watch::Receiver<bool>,
+}
+
+impl Budget {
+    pub fn new(bridge: Bridge, stop_streams: watch::Receiver<bool>) -> Self {
+        Self {
+            bridge,
+            stop_streams,
+        }
+    }
+}
+
+type ChangeStream = Pin<Box<dyn Stream<Item = Result<rpc::BudgetChange, Status>> + Send>>;
+
+#[tonic::async_trait]
+impl budget_service_server::BudgetService for Budget {
+    async fn get(
+        &self,
+        request: Request<BudgetAccessTokenList>,
+    ) -> Result<Response<BudgetList>, Status> {
+        forward(&self.bridge, Method::GET, "/api/budget", request).await
+    }
+
+    async fn create(&self, request: Request<NewBudget>) -> Result<Response<BudgetFrame>, Status> {
+        forward(&self.bridge, Method::POST, "/api/budget", request).await
+    }
+
+    async fn edit(&self, request: Request<EncryptedBlobUpdate>) -> Result<Response<Empty>, Status> {
+        forward(&self.bridge, Method::PUT, "/api/budget", request).await
+    }
+
+    async fn invite_user(
+        &self,
+        request: Request<UserInvitationToBudget>,
+    ) -> Result<Response<InvitationId>, Status> {
+        forward(
+            &self.bridge,
+            Method::POST,
+            "/api/budget/invitation",
+            request,
+        )
+        .await
+    }
+
+    async fn retract_invitation(&self, request: Request<Empty>) -> Result<Response<Empty>, Status> {
+        forward(
+            &self.bridge,
+            Method::DELETE,
+            "/api/budget/invitation",
+            request,
+        )
+        .await
+    }
+
+    async fn accept_invitation(
+        &self,
+        request: Request<PublicKey>,
+    ) -> Result<Response<BudgetIdAndEncryptionKey>, Status> {
+        forward(
+            &self.bridge,
+            Method::PUT,
+            "/api/budget/invitation/accept",
+            request,
+        )
+        .await
+    }
+
+    async fn decline_invitation(&self, request: Request<Empty>) -> Result<Response<Empty>, Status> {
+        forward(
+            &self.bridge,
+            Method::PUT,
+            "/api/budget/invitation/decline",
+            request,
+        )
+        .await
+    }
+
+    async fn get_all_pending_invitations(
+        &self,
+        request: Request<Empty>,
+    ) -> Result<Response<BudgetShareInviteList>, Status> {
+        forward(
+            &self.bridge,
+            Method::GET,
+            "/api/budget/invitation/all_pending",
+            request,
+        )
+        .await
+    }
+
+    async fn leave(&self, request: Request<Empty>) -> Result<Response<Empty>, Status> {
+        forward(&self.bridge, Method::DELETE, "/api/budget/leave", request).await
+    }
+
+    async fn create_entry(
+        &self,
+        request: Request<EncryptedBlobAndCategoryId>,
+    ) -> Result<Response<EntryId>, Status> {
+        forward(&self.bridge, Method::POST, "/api/budget/entry", request).await
+    }
+
+    async fn create_entry_and_category(
+        &self,
+        request: Request<EntryAndCategory>,
+    ) -> Result<Response<EntryIdAndCategoryId>, Status> {
+        forward(
+            &self.bridge,
+            Method::POST,
+            "/api/budget/entry_and_category",
+            request,
+        )
+        .await
+    }
+
+    async fn edit_entry(&self, request: Request<EntryUpdate>) -> Result<Response<Empty>, Status> {
+        forward(&self.bridge, Method::PUT, "/api/budget/entry", request).await
+    }
+
+    async fn delete_entry(&self, request: Request<EntryId>) -> Result<Response<Empty>, Status> {
+        forward(&self.bridge, Method::DELETE, "/api/budget/entry", request).await
+    }
+
+    async fn create_category(
+        &self,
+        request: Request<NewEncryptedBlob>,
+    ) -> Result<Response<CategoryId>, Status> {
+        forward(&self.bridge, Method::POST, "/api/budget/category", request).await
+    }
+
+    async fn edit_category(
+        &self,
+        request: Request<CategoryUpdate>,
+    ) -> Result<Response<Empty>, Status> {
+        forward(&self.bridge, Method::PUT, "/api/budget/category", request).await
+    }
+
+    async fn delete_category(
+        &self,
+        request: Request<CategoryId>,
+    ) -> Result<Response<Empty>, Status> {
+        forward(
+            &self.bridge,
+            Method::DELETE,
+            "/api/budget/category",
+            request,
+        )
+        .await
+    }
+
+    type WatchChangesStream = ChangeStream;
+
+    async fn watch_changes(
+        &self,
+        request: Request<Empty>,
+    ) -> Result<Response<ChangeStream>, Status> {
+        // Run through the same token checks as the HTTP routes for the budget
+        let budget_id: Response<entries_common::messages::Uuid> =
+            forward(&self.bridge, Method::GET, "/grpc/budget/changes", request).await?;
+        let budget_id = Uuid::try_from(budget_id.get_ref())
+            .map_err(|_| Status::internal("Failed to decode budget ID"))?;
+
+        let subscription = change_feed::subscribe(budget_id);
+        let stop = self.stop_streams.clone();
+
+        let changes = stream::unfold(
+            (subscription, stop),
+            |(mut subscription, mut stop)| async move {
+                if *stop.borrow_and_update() {
+                    return None;
+                }
+
+                tokio::select! {
+                    change = subscription.next() => {
+                        let change = rpc::BudgetChange::from(change?);
+                        Some((Ok(change), (subscription, stop)))
+                    }
+                    _ = stop.changed() => None,
+                }
+            },
+        );
+
+        Ok(Response::new(Box::pin(changes)))
+    }
+}
+
+impl From<change_feed::BudgetChange> for rpc::BudgetChange {
+    fn from(change: change_feed::BudgetChange) -> Self {
+        let change_type = match change.change_type {
+            ChangeType::ResyncRequired => rpc::BudgetChangeType::ResyncRequired,
+            ChangeType::BudgetEdited => rpc::BudgetChangeType::BudgetEdited,
+            ChangeType::EntryCreated => rpc::BudgetChangeType::EntryCreated,
+            ChangeType::EntryEdited => rpc::BudgetChangeType::EntryEdited,
+            ChangeType::EntryDeleted => rpc::BudgetChangeType::EntryDeleted,
+            ChangeType::CategoryCreated => rpc::BudgetChangeType::CategoryCreated,
+            ChangeType::CategoryEdited => rpc::BudgetChangeType::CategoryEdited,
+            ChangeType::CategoryDeleted => rpc::BudgetChangeType::CategoryDeleted,
+            ChangeType::MemberJoined => rpc::BudgetChangeType::MemberJoined,
+            ChangeType::MemberLeft => rpc::BudgetChangeType::MemberLeft,
+        };
+
+        rpc::BudgetChange {
+            budget_id: change.budget_id.into(),
+            change_type: change_type.into(),
+            object_id: change.object_id.map(Into::into),
+            timestamp: change.timestamp.try_into().unwrap_or_default(),
+        }
+    }
+}
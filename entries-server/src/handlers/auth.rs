@@ -1,4 +1,5 @@
-use entries_common::db::{self, DaoError, DbThreadPool};
+use entries_common::db::auth::AuthDao;
+use entries_common::db::DaoError;
 use entries_common::email::EmailSender;
 use entries_common::messages::{
     BackupCode, BackupCodeList, CredentialPair, EmailQuery, SigninNonceAndHashParams, SigninToken,
@@ -8,7 +9,6 @@ use entries_common::otp::Otp;
 use entries_common::token::auth_token::{AuthToken, AuthTokenType, NewAuthTokenClaims};
 use entries_common::validators::{self, Validity};
 
-use actix_protobuf::{ProtoBuf, ProtoBufResponseBuilder};
 use actix_web::{web, HttpResponse};
 use rand::{Rng, SeedableRng};
 use rand_chacha::ChaCha20Rng;
@@ -18,12 +18,14 @@ use std::time::{SystemTime, UNIX_EPOCH};
 use zeroize::Zeroizing;
 
 use crate::env;
-use crate::handlers::{self, error::DoesNotExistType, error::HttpErrorResponse};
+use crate::handlers::{self, block_task, error::DoesNotExistType, error::HttpErrorResponse};
 use crate::middleware::auth::{Access, Refresh, SignIn, UnverifiedToken, VerifiedToken};
+use crate::middleware::proto_or_json::{ProtoOrJson, ProtoOrJsonResponseBuilder};
 use crate::middleware::FromHeader;
 
+#[tracing::instrument(level = "debug", skip_all)]
 pub async fn obtain_nonce_and_auth_string_params(
-    db_thread_pool: web::Data<DbThreadPool>,
+    auth_dao: web::Data<dyn AuthDao>,
     email: web::Query<EmailQuery>,
 ) -> Result<HttpResponse, HttpErrorResponse> {
     // Disguise that the user doesn't exist by returning random data that only changes
@@ -56,31 +58,29 @@ pub async fn obtain_nonce_and_auth_string_params(
         nonce: phony_nonce,
     };
 
-    let real_params = match web::block(move || {
-        let auth_dao = db::auth::Dao::new(&db_thread_pool);
-        auth_dao.get_auth_string_data_signin_nonce(&email.0.email)
-    })
-    .await?
-    {
-        Ok(a) => a,
-        Err(DaoError::QueryFailure(diesel::result::Error::NotFound)) => {
-            return Ok(HttpResponse::Ok().protobuf(phony_params)?);
-        }
-        Err(e) => {
-            log::error!("{e}");
-            return Err(HttpErrorResponse::InternalError(String::from(
-                "Failed to obtain nonce or authentication string data",
-            )));
-        }
-    };
+    let real_params =
+        match block_task(move || auth_dao.get_auth_string_data_signin_nonce(&email.0.email)).await?
+        {
+            Ok(a) => a,
+            Err(DaoError::QueryFailure(diesel::result::Error::NotFound)) => {
+                return Ok(HttpResponse::Ok().proto_or_json(phony_params)?);
+            }
+            Err(e) => {
+                log::error!("{e}");
+                return Err(HttpErrorResponse::InternalError(String::from(
+                    "Failed to obtain nonce or authentication string data",
+                )));
+            }
+        };
 
-    Ok(HttpResponse::Ok().protobuf(real_params)?)
+    Ok(HttpResponse::Ok().proto_or_json(real_params)?)
 }
 
+#[tracing::instrument(level = "debug", skip_all)]
 pub async fn sign_in(
-    db_thread_pool: web::Data<DbThreadPool>,
+    auth_dao: web::Data<dyn AuthDao>,
     smtp_thread_pool: web::Data<EmailSender>,
-    credentials: ProtoBuf<CredentialPair>,
+    credentials: ProtoOrJson<CredentialPair>,
 ) -> Result<HttpResponse, HttpErrorResponse> {
     let credentials = Zeroizing::new(credentials.0);
 
@@ -91,10 +91,10 @@ pub async fn sign_in(
     let credentials = Arc::new(credentials);
     let credentials_ref = Arc::clone(&credentials);
 
-    let auth_dao = db::auth::Dao::new(&db_thread_pool);
+    let auth_dao_ref = web::Data::clone(&auth_dao);
 
     let nonce =
-        match web::block(move || auth_dao.get_and_refresh_signin_nonce(&credentials_ref.email))
+        match block_task(move || auth_dao_ref.get_and_refresh_signin_nonce(&credentials_ref.email))
             .await?
         {
             Ok(a) => a,
@@ -119,10 +119,10 @@ pub async fn sign_in(
     }
 
     let credentials_ref = Arc::clone(&credentials);
-    let auth_dao = db::auth::Dao::new(&db_thread_pool);
+    let auth_dao_ref = web::Data::clone(&auth_dao);
 
-    let hash_and_status = match web::block(move || {
-        auth_dao.get_user_auth_string_hash_and_status(&credentials_ref.email)
+    let hash_and_status = match block_task(move || {
+        auth_dao_ref.get_user_auth_string_hash_and_status(&credentials_ref.email)
     })
     .await?
     {
@@ -152,7 +152,7 @@ pub async fn sign_in(
     handlers::verification::verify_auth_string(
         &credentials.auth_string,
         &credentials.email,
-        &db_thread_pool,
+        &auth_dao,
     )
     .await?;
 
@@ -174,23 +174,24 @@ pub async fn sign_in(
 
     handlers::verification::generate_and_email_otp(
         &credentials.email,
-        db_thread_pool.as_ref(),
+        &auth_dao,
         smtp_thread_pool.as_ref(),
     )
     .await?;
 
-    Ok(HttpResponse::Ok().protobuf(signin_token)?)
+    Ok(HttpResponse::Ok().proto_or_json(signin_token)?)
 }
 
+#[tracing::instrument(level = "debug", skip_all)]
 pub async fn verify_otp_for_signin(
-    db_thread_pool: web::Data<DbThreadPool>,
+    auth_dao: web::Data<dyn AuthDao>,
     signin_token: UnverifiedToken<SignIn, FromHeader>,
-    otp: ProtoBuf<OtpMessage>,
+    otp: ProtoOrJson<OtpMessage>,
 ) -> Result<HttpResponse, HttpErrorResponse> {
     let claims = signin_token.verify()?;
     let user_id = claims.user_id;
 
-    handlers::verification::verify_otp(&otp.value, &claims.user_email, &db_thread_pool).await?;
+    handlers::verification::verify_otp(&otp.value, &claims.user_email, &auth_dao).await?;
 
     let now = SystemTime::now();
 
@@ -224,17 +225,18 @@ pub async fn verify_otp_for_signin(
         server_time: SystemTime::now().try_into()?,
     };
 
-    Ok(HttpResponse::Ok().protobuf(token_pair)?)
+    Ok(HttpResponse::Ok().proto_or_json(token_pair)?)
 }
 
+#[tracing::instrument(level = "debug", skip_all)]
 pub async fn obtain_otp(
-    db_thread_pool: web::Data<DbThreadPool>,
+    auth_dao: web::Data<dyn AuthDao>,
     smtp_thread_pool: web::Data<EmailSender>,
     user_access_token: VerifiedToken<Access, FromHeader>,
 ) -> Result<HttpResponse, HttpErrorResponse> {
     handlers::verification::generate_and_email_otp(
         &user_access_token.0.user_email,
-        db_thread_pool.as_ref(),
+        &auth_dao,
         smtp_thread_pool.as_ref(),
     )
     .await?;
@@ -242,22 +244,18 @@ pub async fn obtain_otp(
     Ok(HttpResponse::Ok().finish())
 }
 
+#[tracing::instrument(level = "debug", skip_all)]
 pub async fn use_backup_code_for_signin(
-    db_thread_pool: web::Data<DbThreadPool>,
+    auth_dao: web::Data<dyn AuthDao>,
     signin_token: UnverifiedToken<SignIn, FromHeader>,
-    code: ProtoBuf<BackupCode>,
+    code: ProtoOrJson<BackupCode>,
 ) -> Result<HttpResponse, HttpErrorResponse> {
     let code = Zeroizing::new(code.0);
 
     let claims = signin_token.verify()?;
     let user_id = claims.user_id;
 
-    match web::block(move || {
-        let auth_dao = db::auth::Dao::new(&db_thread_pool);
-        auth_dao.delete_backup_code(&code.value, user_id)
-    })
-    .await?
-    {
+    match block_task(move || auth_dao.delete_backup_code(&code.value, user_id)).await? {
         Ok(_) => (),
         Err(DaoError::QueryFailure(diesel::result::Error::NotFound)) => {
             return Err(HttpErrorResponse::IncorrectCredential(String::from(
@@ -299,32 +297,24 @@ pub async fn use_backup_code_for_signin(
         server_time: SystemTime::now().try_into()?,
     };
 
-    Ok(HttpResponse::Ok().protobuf(token_pair)?)
+    Ok(HttpResponse::Ok().proto_or_json(token_pair)?)
 }
 
+#[tracing::instrument(level = "debug", skip_all)]
 pub async fn regenerate_backup_codes(
-    db_thread_pool: web::Data<DbThreadPool>,
+    auth_dao: web::Data<dyn AuthDao>,
     user_access_token: VerifiedToken<Access, FromHeader>,
-    otp: ProtoBuf<OtpMessage>,
+    otp: ProtoOrJson<OtpMessage>,
 ) -> Result<HttpResponse, HttpErrorResponse> {
     let user_id = user_access_token.0.user_id;
 
-    handlers::verification::verify_otp(
-        &otp.value,
-        &user_access_token.0.user_email,
-        &db_thread_pool,
-    )
-    .await?;
+    handlers::verification::verify_otp(&otp.value, &user_access_token.0.user_email, &auth_dao)
+        .await?;
 
     let backup_codes = Arc::new(Otp::generate_multiple(12, 8));
     let backup_codes_ref = Arc::clone(&backup_codes);
 
-    match web::block(move || {
-        let auth_dao = db::auth::Dao::new(&db_thread_pool);
-        auth_dao.replace_backup_codes(user_id, &backup_codes_ref)
-    })
-    .await?
-    {
+    match block_task(move || auth_dao.replace_backup_codes(user_id, &backup_codes_ref)).await? {
         Ok(id) => id,
         Err(e) => {
             log::error!("{e}");
@@ -339,18 +329,18 @@ pub async fn regenerate_backup_codes(
 
     let resp_body = BackupCodeList { backup_codes };
 
-    Ok(HttpResponse::Ok().protobuf(resp_body)?)
+    Ok(HttpResponse::Ok().proto_or_json(resp_body)?)
 }
 
+#[tracing::instrument(level = "debug", skip_all)]
 pub async fn refresh_tokens(
-    db_thread_pool: web::Data<DbThreadPool>,
+    auth_dao: web::Data<dyn AuthDao>,
     token: UnverifiedToken<Refresh, FromHeader>,
 ) -> Result<HttpResponse, HttpErrorResponse> {
     let token_claims = token.verify()?;
     let token_expiration = token_claims.expiration;
 
-    match web::block(move || {
-        let auth_dao = db::auth::Dao::new(&db_thread_pool);
+    match block_task(move || {
         auth_dao.check_is_token_on_blacklist_and_blacklist(&token.0.signature, token_expiration)
     })
     .await?
@@ -401,11 +391,12 @@ pub async fn refresh_tokens(
         server_time: SystemTime::now().try_into()?,
     };
 
-    Ok(HttpResponse::Ok().protobuf(token_pair)?)
+    Ok(HttpResponse::Ok().proto_or_json(token_pair)?)
 }
 
+#[tracing::instrument(level = "debug", skip_all)]
 pub async fn logout(
-    db_thread_pool: web::Data<DbThreadPool>,
+    auth_dao: web::Data<dyn AuthDao>,
     user_access_token: VerifiedToken<Access, FromHeader>,
     refresh_token: UnverifiedToken<Refresh, FromHeader>,
 ) -> Result<HttpResponse, HttpErrorResponse> {
@@ -417,8 +408,7 @@ pub async fn logout(
         )));
     }
 
-    match web::block(move || {
-        let auth_dao = db::auth::Dao::new(&db_thread_pool);
+    match block_task(move || {
         auth_dao.blacklist_token(&refresh_token.0.signature, refresh_token_claims.expiration)
     })
     .await?
@@ -428,9 +418,10 @@ pub async fn logout(
             diesel::result::DatabaseErrorKind::UniqueViolation,
             _,
         ))) => {
-            return Err(HttpErrorResponse::ConflictWithExisting(String::from(
-                "Token already on blacklist",
-            )));
+            return Err(HttpErrorResponse::ConflictWithExisting(
+                String::from("Token already on blacklist"),
+                None,
+            ));
         }
         Err(e) => {
             log::error!("{e}");
@@ -453,7 +444,7 @@ mod tests {
     use entries_common::models::user_otp::UserOtp;
     use entries_common::schema::{signin_nonces, user_otps, users};
 
-    use actix_protobuf::ProtoBufConfig;
+    use crate::middleware::proto_or_json::ProtoOrJsonConfig;
     use actix_web::body::to_bytes;
     use actix_web::http::StatusCode;
     use actix_web::test::{self, TestRequest};
@@ -472,8 +463,10 @@ mod tests {
         let app = test::init_service(
             App::new()
                 .app_data(Data::new(env::testing::DB_THREAD_POOL.clone()))
+                .app_data(Data::new(env::testing::async_db_pools()))
+                .configure(env::testing::configure_daos)
                 .app_data(Data::new(env::testing::SMTP_THREAD_POOL.clone()))
-                .app_data(ProtoBufConfig::default())
+                .app_data(ProtoOrJsonConfig::default())
                 .configure(|cfg| crate::services::api::configure(cfg, RouteLimiters::default())),
         )
         .await;
@@ -576,8 +569,10 @@ mod tests {
         let app = test::init_service(
             App::new()
                 .app_data(Data::new(env::testing::DB_THREAD_POOL.clone()))
+                .app_data(Data::new(env::testing::async_db_pools()))
+                .configure(env::testing::configure_daos)
                 .app_data(Data::new(env::testing::SMTP_THREAD_POOL.clone()))
-                .app_data(ProtoBufConfig::default())
+                .app_data(ProtoOrJsonConfig::default())
                 .configure(|cfg| crate::services::api::configure(cfg, RouteLimiters::default())),
         )
         .await;
@@ -761,8 +756,10 @@ mod tests {
         let app = test::init_service(
             App::new()
                 .app_data(Data::new(env::testing::DB_THREAD_POOL.clone()))
+                .app_data(Data::new(env::testing::async_db_pools()))
+                .configure(env::testing::configure_daos)
                 .app_data(Data::new(env::testing::SMTP_THREAD_POOL.clone()))
-                .app_data(ProtoBufConfig::default())
+                .app_data(ProtoOrJsonConfig::default())
                 .configure(|cfg| crate::services::api::configure(cfg, RouteLimiters::default())),
         )
         .await;
@@ -943,8 +940,10 @@ mod tests {
         let app = test::init_service(
             App::new()
                 .app_data(Data::new(env::testing::DB_THREAD_POOL.clone()))
+                .app_data(Data::new(env::testing::async_db_pools()))
+                .configure(env::testing::configure_daos)
                 .app_data(Data::new(env::testing::SMTP_THREAD_POOL.clone()))
-                .app_data(ProtoBufConfig::default())
+                .app_data(ProtoOrJsonConfig::default())
                 .configure(|cfg| crate::services::api::configure(cfg, RouteLimiters::default())),
         )
         .await;
@@ -1,7 +1,11 @@
+use entries_common::db::budget::BudgetDao;
+use entries_common::db::nonblocking::budget::AsyncBudgetDao;
+use entries_common::db::user::UserDao;
+use entries_common::db::DaoError;
 use entries_common::messages::{
     AcceptKeyInfo, BudgetAccessTokenList, BudgetList, CategoryId, CategoryUpdate,
     EncryptedBlobAndCategoryId, EncryptedBlobUpdate, EntryAndCategory, EntryId, EntryUpdate,
-    NewBudget, NewEncryptedBlob, PublicKey, UserInvitationToBudget,
+    NewBudget, NewEncryptedBlob, PublicKey, UserInvitationToBudget, Uuid as UuidMessage,
 };
 use entries_common::models::budget_access_key::BudgetAccessKey;
 use entries_common::token::budget_accept_token::BudgetAcceptToken;
@@ -9,10 +13,9 @@ use entries_common::token::budget_access_token::BudgetAccessToken;
 use entries_common::token::budget_invite_sender_token::BudgetInviteSenderToken;
 use entries_common::token::Token;
 use entries_common::validators::{self, Validity};
-use entries_common::{db, db::DaoError, db::DbThreadPool};
 
-use actix_protobuf::{ProtoBuf, ProtoBufResponseBuilder};
-use actix_web::{web, HttpResponse};
+use actix_web::http::header::ETag;
+use actix_web::{web, HttpRequest, HttpResponse};
 use ed25519_dalek as ed25519;
 use openssl::rsa::{Padding, Rsa};
 use prost::Message;
@@ -23,16 +26,22 @@ use std::time::{SystemTime, UNIX_EPOCH};
 use tokio::sync::oneshot;
 use uuid::Uuid;
 
+use crate::change_feed::{self, ChangeType};
 use crate::env;
-use crate::handlers::error::{DoesNotExistType, HttpErrorResponse};
+use crate::handlers::error::SizeLimit;
+use crate::handlers::{self, block_task, error::DoesNotExistType, error::HttpErrorResponse, etag};
 use crate::middleware::auth::{Access, VerifiedToken};
+use crate::middleware::proto_or_json::{ProtoOrJson, ProtoOrJsonResponseBuilder};
+use crate::middleware::read_your_writes::RoutedBudgetDao;
 use crate::middleware::special_access_token::SpecialAccessToken;
 use crate::middleware::{FromHeader, TokenLocation};
 
+#[tracing::instrument(level = "debug", skip_all)]
 pub async fn get(
-    db_thread_pool: web::Data<DbThreadPool>,
+    req: HttpRequest,
+    budget_dao: RoutedBudgetDao,
     _user_access_token: VerifiedToken<Access, FromHeader>,
-    budget_access_tokens: ProtoBuf<BudgetAccessTokenList>,
+    budget_access_tokens: ProtoOrJson<BudgetAccessTokenList>,
 ) -> Result<HttpResponse, HttpErrorResponse> {
     const INVALID_ID_MSG: &str = "One of the provided budget access tokens had an invalid ID";
 
@@ -56,21 +65,18 @@ pub async fn get(
         tokens.insert(token.claims.key_id, token);
     }
 
-    let budget_ids = Arc::new(budget_ids);
-    let budget_ids_ref = Arc::clone(&budget_ids);
+    let public_keys = if budget_ids.len() == 1 && key_ids.len() == 1 {
+        budget_dao
+            .get_public_budget_key(key_ids[0], budget_ids[0])
+            .await
+            .map(|key| vec![key])
+    } else {
+        budget_dao
+            .get_multiple_public_budget_keys(&key_ids, &budget_ids)
+            .await
+    };
 
-    let budget_dao = db::budget::Dao::new(&db_thread_pool);
-    let public_keys = match web::block(move || {
-        if budget_ids_ref.len() == 1 && key_ids.len() == 1 {
-            vec![budget_dao.get_public_budget_key(key_ids[0], budget_ids_ref[0])]
-                .into_iter()
-                .collect()
-        } else {
-            budget_dao.get_multiple_public_budget_keys(&key_ids, &budget_ids_ref)
-        }
-    })
-    .await?
-    {
+    let public_keys = match public_keys {
         Ok(b) => b,
         Err(e) => match e {
             DaoError::QueryFailure(diesel::result::Error::NotFound) => {
@@ -109,19 +115,18 @@ pub async fn get(
         token.verify(&key.public_key)?;
     }
 
-    let budgets = match web::block(move || {
-        let budget_dao = db::budget::Dao::new(&db_thread_pool);
-        if budget_ids.len() == 1 {
-            let budget = budget_dao.get_budget(budget_ids[0])?;
-            Ok(BudgetList {
+    let budgets = if budget_ids.len() == 1 {
+        budget_dao
+            .get_budget(budget_ids[0])
+            .await
+            .map(|budget| BudgetList {
                 budgets: vec![budget],
             })
-        } else {
-            budget_dao.get_multiple_budgets_by_id(&budget_ids)
-        }
-    })
-    .await?
-    {
+    } else {
+        budget_dao.get_multiple_budgets_by_id(&budget_ids).await
+    };
+
+    let budgets = match budgets {
         Ok(b) => b,
         Err(e) => match e {
             DaoError::QueryFailure(diesel::result::Error::NotFound) => {
@@ -139,32 +144,45 @@ pub async fn get(
         },
     };
 
-    Ok(HttpResponse::Ok().protobuf(budgets)?)
+    let etag = etag::for_budgets(&budgets);
+    if etag::matches(&req, &etag) {
+        return Ok(etag::not_modified(etag));
+    }
+
+    Ok(HttpResponse::Ok()
+        .insert_header(ETag(etag))
+        .proto_or_json(budgets)?)
 }
 
+#[tracing::instrument(level = "debug", skip_all)]
 pub async fn create(
-    db_thread_pool: web::Data<DbThreadPool>,
-    budget_data: ProtoBuf<NewBudget>,
+    budget_dao: web::Data<dyn BudgetDao>,
+    budget_data: ProtoOrJson<NewBudget>,
     _user_access_token: VerifiedToken<Access, FromHeader>,
 ) -> Result<HttpResponse, HttpErrorResponse> {
     if budget_data.encrypted_blob.len() > env::CONF.max_small_object_size {
-        return Err(HttpErrorResponse::InputTooLarge(String::from(
-            "Budget encrypted blob too large",
-        )));
+        return Err(HttpErrorResponse::InputTooLarge(
+            String::from("Budget encrypted blob too large"),
+            SizeLimit::field("encrypted_blob", env::CONF.max_small_object_size),
+        ));
     }
 
     if budget_data.user_public_budget_key.len() > env::CONF.max_encryption_key_size {
-        return Err(HttpErrorResponse::InputTooLarge(String::from(
-            "User public key too large",
-        )));
+        return Err(HttpErrorResponse::InputTooLarge(
+            String::from("User public key too large"),
+            SizeLimit::field("user_public_budget_key", env::CONF.max_encryption_key_size),
+        ));
     }
 
     for category in budget_data.categories.iter() {
         if category.encrypted_blob.len() > env::CONF.max_small_object_size {
-            return Err(HttpErrorResponse::InputTooLarge(format!(
-                "Category encrypted blob too large for category with temp ID {}",
-                category.temp_id,
-            )));
+            return Err(HttpErrorResponse::InputTooLarge(
+                format!(
+                    "Category encrypted blob too large for category with temp ID {}",
+                    category.temp_id,
+                ),
+                SizeLimit::field("categories.encrypted_blob", env::CONF.max_small_object_size),
+            ));
         }
     }
 
@@ -179,8 +197,19 @@ pub async fn create(
         )));
     }
 
-    let new_budget = match web::block(move || {
-        let budget_dao = db::budget::Dao::new(&db_thread_pool);
+    let mut category_id_set = HashSet::new();
+    for category in budget_data.categories.iter() {
+        if let Some(id) = client_generated_id(category.id.as_ref())? {
+            if !category_id_set.insert(id) {
+                return Err(HttpErrorResponse::ConflictWithExisting(
+                    String::from("Multiple categories with the same client-generated ID"),
+                    Some(id),
+                ));
+            }
+        }
+    }
+
+    let new_budget = match block_task(move || {
         budget_dao.create_budget(
             &budget_data.encrypted_blob,
             budget_data.version_nonce,
@@ -191,48 +220,59 @@ pub async fn create(
     .await?
     {
         Ok(b) => b,
-        Err(e) => {
-            log::error!("{e}");
-            return Err(HttpErrorResponse::InternalError(String::from(
-                "Failed to create budget",
-            )));
-        }
+        Err(e) => match e {
+            e if is_duplicate_id(&e) => {
+                return Err(HttpErrorResponse::ConflictWithExisting(
+                    String::from("A category with the given ID already exists"),
+                    None,
+                ));
+            }
+            _ => {
+                log::error!("{e}");
+                return Err(HttpErrorResponse::InternalError(String::from(
+                    "Failed to create budget",
+                )));
+            }
+        },
     };
 
-    Ok(HttpResponse::Created().protobuf(new_budget)?)
+    Ok(HttpResponse::Created().proto_or_json(new_budget)?)
 }
 
+#[tracing::instrument(level = "debug", skip_all)]
 pub async fn edit(
-    db_thread_pool: web::Data<DbThreadPool>,
+    budget_dao: RoutedBudgetDao,
     _user_access_token: VerifiedToken<Access, FromHeader>,
     budget_access_token: SpecialAccessToken<BudgetAccessToken, FromHeader>,
-    budget_data: ProtoBuf<EncryptedBlobUpdate>,
+    budget_data: ProtoOrJson<EncryptedBlobUpdate>,
 ) -> Result<HttpResponse, HttpErrorResponse> {
-    verify_read_write_access(&budget_access_token, &db_thread_pool).await?;
+    verify_read_write_access(&budget_access_token, &*budget_dao).await?;
+
+    let budget_id = budget_access_token.0.claims.budget_id;
 
     if budget_data.encrypted_blob.len() > env::CONF.max_small_object_size {
-        return Err(HttpErrorResponse::InputTooLarge(String::from(
-            "Budget encrypted blob too large",
-        )));
+        return Err(HttpErrorResponse::InputTooLarge(
+            String::from("Budget encrypted blob too large"),
+            SizeLimit::field("encrypted_blob", env::CONF.max_small_object_size),
+        ));
     }
 
-    match web::block(move || {
-        let budget_dao = db::budget::Dao::new(&db_thread_pool);
-        budget_dao.update_budget(
-            budget_access_token.0.claims.budget_id,
+    match budget_dao
+        .update_budget(
+            budget_id,
             &budget_data.encrypted_blob,
             budget_data.version_nonce,
             budget_data.expected_previous_version_nonce,
         )
-    })
-    .await?
+        .await
     {
         Ok(_) => (),
         Err(e) => match e {
-            DaoError::OutOfDate => {
-                return Err(HttpErrorResponse::OutOfDate(String::from(
-                    "Out of date version nonce",
-                )));
+            DaoError::OutOfDate(current_version_nonce) => {
+                return Err(HttpErrorResponse::OutOfDate(
+                    String::from("Out of date version nonce"),
+                    current_version_nonce,
+                ));
             }
             DaoError::QueryFailure(diesel::result::Error::NotFound) => {
                 return Err(HttpErrorResponse::DoesNotExist(
@@ -249,6 +289,8 @@ pub async fn edit(
         },
     };
 
+    change_feed::publish(budget_id, ChangeType::BudgetEdited, None);
+
     Ok(HttpResponse::Ok().finish())
 }
 
@@ -261,43 +303,57 @@ pub struct AcceptKey {
     key_info_encrypted: Vec<u8>,
 }
 
+#[tracing::instrument(level = "debug", skip_all)]
 pub async fn invite_user(
-    db_thread_pool: web::Data<DbThreadPool>,
+    user_dao: web::Data<dyn UserDao>,
+    budget_dao: web::Data<dyn BudgetDao>,
+    async_budget_dao: RoutedBudgetDao,
     user_access_token: VerifiedToken<Access, FromHeader>,
     budget_access_token: SpecialAccessToken<BudgetAccessToken, FromHeader>,
-    invitation_info: ProtoBuf<UserInvitationToBudget>,
+    invitation_info: ProtoOrJson<UserInvitationToBudget>,
 ) -> Result<HttpResponse, HttpErrorResponse> {
-    verify_read_write_access(&budget_access_token, &db_thread_pool).await?;
+    verify_read_write_access(&budget_access_token, &*async_budget_dao).await?;
 
     if invitation_info.sender_public_key.len() > env::CONF.max_encryption_key_size {
-        return Err(HttpErrorResponse::InputTooLarge(String::from(
-            "Sender public key too large",
-        )));
+        return Err(HttpErrorResponse::InputTooLarge(
+            String::from("Sender public key too large"),
+            SizeLimit::field("sender_public_key", env::CONF.max_encryption_key_size),
+        ));
     }
 
     if invitation_info.encryption_key_encrypted.len() > env::CONF.max_encryption_key_size {
-        return Err(HttpErrorResponse::InputTooLarge(String::from(
-            "Encrypted encryption key too large",
-        )));
+        return Err(HttpErrorResponse::InputTooLarge(
+            String::from("Encrypted encryption key too large"),
+            SizeLimit::field(
+                "encryption_key_encrypted",
+                env::CONF.max_encryption_key_size,
+            ),
+        ));
     }
 
     if invitation_info.budget_info_encrypted.len() > env::CONF.max_small_object_size {
-        return Err(HttpErrorResponse::InputTooLarge(String::from(
-            "Budget info encrypted too large",
-        )));
+        return Err(HttpErrorResponse::InputTooLarge(
+            String::from("Budget info encrypted too large"),
+            SizeLimit::field("budget_info_encrypted", env::CONF.max_small_object_size),
+        ));
     }
 
     if invitation_info.sender_info_encrypted.len() > env::CONF.max_small_object_size {
-        return Err(HttpErrorResponse::InputTooLarge(String::from(
-            "Sender info encrypted too large",
-        )));
+        return Err(HttpErrorResponse::InputTooLarge(
+            String::from("Sender info encrypted too large"),
+            SizeLimit::field("sender_info_encrypted", env::CONF.max_small_object_size),
+        ));
     }
 
     if invitation_info.share_info_symmetric_key_encrypted.len() > env::CONF.max_encryption_key_size
     {
-        return Err(HttpErrorResponse::InputTooLarge(String::from(
-            "Encrypted symmetric key too large",
-        )));
+        return Err(HttpErrorResponse::InputTooLarge(
+            String::from("Encrypted symmetric key too large"),
+            SizeLimit::field(
+                "share_info_symmetric_key_encrypted",
+                env::CONF.max_encryption_key_size,
+            ),
+        ));
     }
 
     if let Validity::Invalid(msg) =
@@ -318,9 +374,9 @@ pub async fn invite_user(
     let invitation_info = Arc::new(invitation_info.0);
     let invitation_info_ref = Arc::clone(&invitation_info);
 
-    let user_dao = db::user::Dao::new(&db_thread_pool);
-    let (recipient_pub_key_id, recipient_public_key) = match web::block(move || {
-        user_dao.get_user_public_key(&invitation_info_ref.recipient_user_email)
+    let user_dao_ref = web::Data::clone(&user_dao);
+    let (recipient_pub_key_id, recipient_public_key) = match block_task(move || {
+        user_dao_ref.get_user_public_key(&invitation_info_ref.recipient_user_email)
     })
     .await?
     {
@@ -343,7 +399,7 @@ pub async fn invite_user(
 
     let (sender, receiver) = oneshot::channel();
 
-    rayon::spawn(move || {
+    handlers::rayon_spawn(move || {
         let accept_key_pair = ed25519::SigningKey::generate(&mut OsRng);
         let accept_public_key = accept_key_pair.verifying_key().to_bytes();
         let accept_private_key = accept_key_pair.as_bytes();
@@ -419,8 +475,7 @@ pub async fn invite_user(
     let recipient_pub_key_id_used_by_sender =
         (&invitation_info.recipient_public_key_id_used_by_sender).try_into()?;
 
-    let invite_id = match web::block(move || {
-        let budget_dao = db::budget::Dao::new(&db_thread_pool);
+    let invite_id = match block_task(move || {
         budget_dao.invite_user(
             &invitation_info.recipient_user_email,
             &invitation_info.sender_public_key,
@@ -459,19 +514,20 @@ pub async fn invite_user(
         },
     };
 
-    Ok(HttpResponse::Ok().protobuf(invite_id)?)
+    Ok(HttpResponse::Ok().proto_or_json(invite_id)?)
 }
 
+#[tracing::instrument(level = "debug", skip_all)]
 pub async fn retract_invitation(
-    db_thread_pool: web::Data<DbThreadPool>,
+    budget_dao: web::Data<dyn BudgetDao>,
     _user_access_token: VerifiedToken<Access, FromHeader>,
     invite_sender_token: SpecialAccessToken<BudgetInviteSenderToken, FromHeader>,
 ) -> Result<HttpResponse, HttpErrorResponse> {
     let invitation_id = invite_sender_token.0.claims.invite_id;
 
-    let budget_dao = db::budget::Dao::new(&db_thread_pool);
+    let budget_dao_ref = web::Data::clone(&budget_dao);
     let invite_sender_public_key =
-        match web::block(move || budget_dao.get_budget_invite_sender_public_key(invitation_id))
+        match block_task(move || budget_dao_ref.get_budget_invite_sender_public_key(invitation_id))
             .await?
         {
             Ok(k) => k,
@@ -493,11 +549,8 @@ pub async fn retract_invitation(
 
     invite_sender_token.0.verify(&invite_sender_public_key)?;
 
-    match web::block(move || {
-        let budget_dao = db::budget::Dao::new(&db_thread_pool);
-        budget_dao.delete_invitation(invite_sender_token.0.claims.invite_id)
-    })
-    .await?
+    match block_task(move || budget_dao.delete_invitation(invite_sender_token.0.claims.invite_id))
+        .await?
     {
         Ok(_) => (),
         Err(e) => match e {
@@ -519,24 +572,27 @@ pub async fn retract_invitation(
     Ok(HttpResponse::Ok().finish())
 }
 
+#[tracing::instrument(level = "debug", skip_all)]
 pub async fn accept_invitation(
-    db_thread_pool: web::Data<DbThreadPool>,
+    budget_dao: web::Data<dyn BudgetDao>,
     user_access_token: VerifiedToken<Access, FromHeader>,
     accept_token: SpecialAccessToken<BudgetAcceptToken, FromHeader>,
-    budget_user_public_key: ProtoBuf<PublicKey>,
+    budget_user_public_key: ProtoOrJson<PublicKey>,
 ) -> Result<HttpResponse, HttpErrorResponse> {
     if budget_user_public_key.value.len() > env::CONF.max_encryption_key_size {
-        return Err(HttpErrorResponse::InputTooLarge(String::from(
-            "Public key too large",
-        )));
+        return Err(HttpErrorResponse::InputTooLarge(
+            String::from("Public key too large"),
+            SizeLimit::field("value", env::CONF.max_encryption_key_size),
+        ));
     }
 
     let key_id = accept_token.0.claims.key_id;
     let budget_id = accept_token.0.claims.budget_id;
 
-    let budget_dao = db::budget::Dao::new(&db_thread_pool);
+    let budget_dao_ref = web::Data::clone(&budget_dao);
     let budget_accept_key =
-        match web::block(move || budget_dao.get_budget_accept_public_key(key_id, budget_id)).await?
+        match block_task(move || budget_dao_ref.get_budget_accept_public_key(key_id, budget_id))
+            .await?
         {
             Ok(key) => key,
             Err(e) => match e {
@@ -556,15 +612,15 @@ pub async fn accept_invitation(
         };
 
     if budget_accept_key.expiration < SystemTime::now() {
-        return Err(HttpErrorResponse::OutOfDate(String::from(
-            "Invitation has expired",
-        )));
+        return Err(HttpErrorResponse::OutOfDate(
+            String::from("Invitation has expired"),
+            None,
+        ));
     }
 
     accept_token.0.verify(&budget_accept_key.public_key)?;
 
-    let budget_keys = match web::block(move || {
-        let budget_dao = db::budget::Dao::new(&db_thread_pool);
+    let budget_keys = match block_task(move || {
         budget_dao.accept_invitation(
             budget_accept_key.key_id,
             budget_accept_key.budget_id,
@@ -593,20 +649,24 @@ pub async fn accept_invitation(
         },
     };
 
-    Ok(HttpResponse::Ok().protobuf(budget_keys)?)
+    change_feed::publish(budget_id, ChangeType::MemberJoined, None);
+
+    Ok(HttpResponse::Ok().proto_or_json(budget_keys)?)
 }
 
+#[tracing::instrument(level = "debug", skip_all)]
 pub async fn decline_invitation(
-    db_thread_pool: web::Data<DbThreadPool>,
+    budget_dao: web::Data<dyn BudgetDao>,
     user_access_token: VerifiedToken<Access, FromHeader>,
     accept_token: SpecialAccessToken<BudgetAcceptToken, FromHeader>,
 ) -> Result<HttpResponse, HttpErrorResponse> {
     let key_id = accept_token.0.claims.key_id;
     let budget_id = accept_token.0.claims.budget_id;
 
-    let budget_dao = db::budget::Dao::new(&db_thread_pool);
+    let budget_dao_ref = web::Data::clone(&budget_dao);
     let budget_accept_key =
-        match web::block(move || budget_dao.get_budget_accept_public_key(key_id, budget_id)).await?
+        match block_task(move || budget_dao_ref.get_budget_accept_public_key(key_id, budget_id))
+            .await?
         {
             Ok(key) => key,
             Err(e) => match e {
@@ -627,8 +687,7 @@ pub async fn decline_invitation(
 
     accept_token.0.verify(&budget_accept_key.public_key)?;
 
-    match web::block(move || {
-        let budget_dao = db::budget::Dao::new(&db_thread_pool);
+    match block_task(move || {
         budget_dao.reject_invitation(
             accept_token.0.claims.invite_id,
             accept_token.0.claims.key_id,
@@ -657,20 +716,19 @@ pub async fn decline_invitation(
     Ok(HttpResponse::Ok().finish())
 }
 
+#[tracing::instrument(level = "debug", skip_all)]
 pub async fn get_all_pending_invitations(
-    db_thread_pool: web::Data<DbThreadPool>,
+    budget_dao: RoutedBudgetDao,
     user_access_token: VerifiedToken<Access, FromHeader>,
 ) -> Result<HttpResponse, HttpErrorResponse> {
-    let invites = match web::block(move || {
-        let budget_dao = db::budget::Dao::new(&db_thread_pool);
-        budget_dao.get_all_pending_invitations(&user_access_token.0.user_email)
-    })
-    .await?
+    let invites = match budget_dao
+        .get_all_pending_invitations(&user_access_token.0.user_email)
+        .await
     {
         Ok(invites) => invites,
         Err(e) => match e {
             DaoError::QueryFailure(diesel::result::Error::NotFound) => {
-                return Ok(HttpResponse::Ok().protobuf(BudgetAccessTokenList::default())?);
+                return Ok(HttpResponse::Ok().proto_or_json(BudgetAccessTokenList::default())?);
             }
             _ => {
                 log::error!("{e}");
@@ -681,22 +739,22 @@ pub async fn get_all_pending_invitations(
         },
     };
 
-    Ok(HttpResponse::Ok().protobuf(invites)?)
+    Ok(HttpResponse::Ok().proto_or_json(invites)?)
 }
 
+#[tracing::instrument(level = "debug", skip_all)]
 pub async fn leave_budget(
-    db_thread_pool: web::Data<DbThreadPool>,
+    budget_dao: web::Data<dyn BudgetDao>,
+    async_budget_dao: RoutedBudgetDao,
     _user_access_token: VerifiedToken<Access, FromHeader>,
     budget_access_token: SpecialAccessToken<BudgetAccessToken, FromHeader>,
 ) -> Result<HttpResponse, HttpErrorResponse> {
-    verify_read_access(&budget_access_token, &db_thread_pool).await?;
+    verify_read_access(&budget_access_token, &*async_budget_dao).await?;
 
-    match web::block(move || {
-        let budget_dao = db::budget::Dao::new(&db_thread_pool);
-        budget_dao.leave_budget(
-            budget_access_token.0.claims.budget_id,
-            budget_access_token.0.claims.key_id,
-        )
+    let budget_id = budget_access_token.0.claims.budget_id;
+
+    match block_task(move || {
+        budget_dao.leave_budget(budget_id, budget_access_token.0.claims.key_id)
     })
     .await?
     {
@@ -717,21 +775,27 @@ pub async fn leave_budget(
         },
     };
 
+    change_feed::publish(budget_id, ChangeType::MemberLeft, None);
+
     Ok(HttpResponse::Ok().finish())
 }
 
+#[tracing::instrument(level = "debug", skip_all)]
 pub async fn create_entry(
-    db_thread_pool: web::Data<DbThreadPool>,
+    budget_dao: RoutedBudgetDao,
     _user_access_token: VerifiedToken<Access, FromHeader>,
     budget_access_token: SpecialAccessToken<BudgetAccessToken, FromHeader>,
-    entry_data: ProtoBuf<EncryptedBlobAndCategoryId>,
+    entry_data: ProtoOrJson<EncryptedBlobAndCategoryId>,
 ) -> Result<HttpResponse, HttpErrorResponse> {
-    verify_read_write_access(&budget_access_token, &db_thread_pool).await?;
+    verify_read_write_access(&budget_access_token, &*budget_dao).await?;
+
+    let budget_id = budget_access_token.0.claims.budget_id;
 
     if entry_data.encrypted_blob.len() > env::CONF.max_small_object_size {
-        return Err(HttpErrorResponse::InputTooLarge(String::from(
-            "Encrypted blob too large",
-        )));
+        return Err(HttpErrorResponse::InputTooLarge(
+            String::from("Encrypted blob too large"),
+            SizeLimit::field("encrypted_blob", env::CONF.max_small_object_size),
+        ));
     }
 
     // Actually optional
@@ -741,23 +805,25 @@ pub async fn create_entry(
         .map(Uuid::try_from)
         .transpose()?;
 
-    let entry_id = match web::block(move || {
-        let budget_dao = db::budget::Dao::new(&db_thread_pool);
-        budget_dao.create_entry(
+    let client_entry_id = client_generated_id(entry_data.id.as_ref())?;
+
+    let entry_id = match budget_dao
+        .create_entry(
+            client_entry_id,
             &entry_data.0.encrypted_blob,
             entry_data.0.version_nonce,
             category_id,
-            budget_access_token.0.claims.budget_id,
+            budget_id,
         )
-    })
-    .await?
+        .await
     {
         Ok(id) => id,
         Err(e) => match e {
+            // The category is in a different budget
             DaoError::QueryFailure(diesel::result::Error::NotFound) => {
                 return Err(HttpErrorResponse::DoesNotExist(
-                    String::from("There was an ID mismatch for the budget, entry, or category"),
-                    DoesNotExistType::Entry,
+                    String::from("No category with ID matching budget"),
+                    DoesNotExistType::Category,
                 ));
             }
             DaoError::QueryFailure(diesel::result::Error::DatabaseError(
@@ -768,6 +834,12 @@ pub async fn create_entry(
                     "No category matching ID",
                 )))
             }
+            e if is_duplicate_id(&e) => {
+                return Err(HttpErrorResponse::ConflictWithExisting(
+                    String::from("An entry with the given ID already exists"),
+                    client_entry_id,
+                ));
+            }
             _ => {
                 log::error!("{e}");
                 return Err(HttpErrorResponse::InternalError(String::from(
@@ -777,42 +849,52 @@ pub async fn create_entry(
         },
     };
 
-    Ok(HttpResponse::Created().protobuf(EntryId {
+    change_feed::publish(budget_id, ChangeType::EntryCreated, Some(entry_id));
+
+    Ok(HttpResponse::Created().proto_or_json(EntryId {
         value: entry_id.into(),
     })?)
 }
 
+#[tracing::instrument(level = "debug", skip_all)]
 pub async fn create_entry_and_category(
-    db_thread_pool: web::Data<DbThreadPool>,
+    budget_dao: RoutedBudgetDao,
     _user_access_token: VerifiedToken<Access, FromHeader>,
     budget_access_token: SpecialAccessToken<BudgetAccessToken, FromHeader>,
-    entry_and_category_data: ProtoBuf<EntryAndCategory>,
+    entry_and_category_data: ProtoOrJson<EntryAndCategory>,
 ) -> Result<HttpResponse, HttpErrorResponse> {
-    verify_read_write_access(&budget_access_token, &db_thread_pool).await?;
+    verify_read_write_access(&budget_access_token, &*budget_dao).await?;
+
+    let budget_id = budget_access_token.0.claims.budget_id;
 
     if entry_and_category_data.entry_encrypted_blob.len() > env::CONF.max_small_object_size {
-        return Err(HttpErrorResponse::InputTooLarge(String::from(
-            "Entry encrypted blob too large",
-        )));
+        return Err(HttpErrorResponse::InputTooLarge(
+            String::from("Entry encrypted blob too large"),
+            SizeLimit::field("entry_encrypted_blob", env::CONF.max_small_object_size),
+        ));
     }
 
     if entry_and_category_data.category_encrypted_blob.len() > env::CONF.max_small_object_size {
-        return Err(HttpErrorResponse::InputTooLarge(String::from(
-            "Category encrypted blob too large",
-        )));
+        return Err(HttpErrorResponse::InputTooLarge(
+            String::from("Category encrypted blob too large"),
+            SizeLimit::field("category_encrypted_blob", env::CONF.max_small_object_size),
+        ));
     }
 
-    let entry_and_category_ids = match web::block(move || {
-        let budget_dao = db::budget::Dao::new(&db_thread_pool);
-        budget_dao.create_entry_and_category(
+    let entry_id = client_generated_id(entry_and_category_data.entry_id.as_ref())?;
+    let category_id = client_generated_id(entry_and_category_data.category_id.as_ref())?;
+
+    let entry_and_category_ids = match budget_dao
+        .create_entry_and_category(
+            entry_id,
             &entry_and_category_data.entry_encrypted_blob,
             entry_and_category_data.entry_version_nonce,
+            category_id,
             &entry_and_category_data.category_encrypted_blob,
             entry_and_category_data.category_version_nonce,
-            budget_access_token.0.claims.budget_id,
+            budget_id,
         )
-    })
-    .await?
+        .await
     {
         Ok(ids) => ids,
         Err(e) => match e {
@@ -822,6 +904,12 @@ pub async fn create_entry_and_category(
                     DoesNotExistType::Budget,
                 ));
             }
+            e if is_duplicate_id(&e) => {
+                return Err(HttpErrorResponse::ConflictWithExisting(
+                    String::from("An entry or category with the given ID already exists"),
+                    None,
+                ));
+            }
             _ => {
                 log::error!("{e}");
                 return Err(HttpErrorResponse::InternalError(String::from(
@@ -831,21 +919,36 @@ pub async fn create_entry_and_category(
         },
     };
 
-    Ok(HttpResponse::Created().protobuf(entry_and_category_ids)?)
+    change_feed::publish(
+        budget_id,
+        ChangeType::CategoryCreated,
+        Uuid::try_from(&entry_and_category_ids.category_id).ok(),
+    );
+    change_feed::publish(
+        budget_id,
+        ChangeType::EntryCreated,
+        Uuid::try_from(&entry_and_category_ids.entry_id).ok(),
+    );
+
+    Ok(HttpResponse::Created().proto_or_json(entry_and_category_ids)?)
 }
 
+#[tracing::instrument(level = "debug", skip_all)]
 pub async fn edit_entry(
-    db_thread_pool: web::Data<DbThreadPool>,
+    budget_dao: RoutedBudgetDao,
     _user_access_token: VerifiedToken<Access, FromHeader>,
     budget_access_token: SpecialAccessToken<BudgetAccessToken, FromHeader>,
-    entry_data: ProtoBuf<EntryUpdate>,
+    entry_data: ProtoOrJson<EntryUpdate>,
 ) -> Result<HttpResponse, HttpErrorResponse> {
-    verify_read_write_access(&budget_access_token, &db_thread_pool).await?;
+    verify_read_write_access(&budget_access_token, &*budget_dao).await?;
+
+    let budget_id = budget_access_token.0.claims.budget_id;
 
     if entry_data.encrypted_blob.len() > env::CONF.max_small_object_size {
-        return Err(HttpErrorResponse::InputTooLarge(String::from(
-            "Encrypted blob too large",
-        )));
+        return Err(HttpErrorResponse::InputTooLarge(
+            String::from("Encrypted blob too large"),
+            SizeLimit::field("encrypted_blob", env::CONF.max_small_object_size),
+        ));
     }
 
     let category_id = entry_data
@@ -856,25 +959,24 @@ pub async fn edit_entry(
 
     let entry_id = (&entry_data.entry_id).try_into()?;
 
-    match web::block(move || {
-        let budget_dao = db::budget::Dao::new(&db_thread_pool);
-        budget_dao.update_entry(
+    match budget_dao
+        .update_entry(
             entry_id,
             &entry_data.encrypted_blob,
             entry_data.version_nonce,
             entry_data.expected_previous_version_nonce,
             category_id,
-            budget_access_token.0.claims.budget_id,
+            budget_id,
         )
-    })
-    .await?
+        .await
     {
         Ok(_) => (),
         Err(e) => match e {
-            DaoError::OutOfDate => {
-                return Err(HttpErrorResponse::OutOfDate(String::from(
-                    "Out of date version nonce",
-                )));
+            DaoError::OutOfDate(current_version_nonce) => {
+                return Err(HttpErrorResponse::OutOfDate(
+                    String::from("Out of date version nonce"),
+                    current_version_nonce,
+                ));
             }
             DaoError::QueryFailure(diesel::result::Error::NotFound) => {
                 return Err(HttpErrorResponse::DoesNotExist(
@@ -899,25 +1001,25 @@ pub async fn edit_entry(
         },
     };
 
+    change_feed::publish(budget_id, ChangeType::EntryEdited, Some(entry_id));
+
     Ok(HttpResponse::Ok().finish())
 }
 
+#[tracing::instrument(level = "debug", skip_all)]
 pub async fn delete_entry(
-    db_thread_pool: web::Data<DbThreadPool>,
+    budget_dao: RoutedBudgetDao,
     _user_access_token: VerifiedToken<Access, FromHeader>,
     budget_access_token: SpecialAccessToken<BudgetAccessToken, FromHeader>,
-    entry_id: ProtoBuf<EntryId>,
+    entry_id: ProtoOrJson<EntryId>,
 ) -> Result<HttpResponse, HttpErrorResponse> {
-    verify_read_write_access(&budget_access_token, &db_thread_pool).await?;
+    verify_read_write_access(&budget_access_token, &*budget_dao).await?;
+
+    let budget_id = budget_access_token.0.claims.budget_id;
 
     let entry_id = (&entry_id.value).try_into()?;
 
-    match web::block(move || {
-        let budget_dao = db::budget::Dao::new(&db_thread_pool);
-        budget_dao.delete_entry(entry_id, budget_access_token.0.claims.budget_id)
-    })
-    .await?
-    {
+    match budget_dao.delete_entry(entry_id, budget_id).await {
         Ok(id) => id,
         Err(e) => match e {
             DaoError::QueryFailure(diesel::result::Error::NotFound) => {
@@ -935,32 +1037,39 @@ pub async fn delete_entry(
         },
     };
 
+    change_feed::publish(budget_id, ChangeType::EntryDeleted, Some(entry_id));
+
     Ok(HttpResponse::Ok().finish())
 }
 
+#[tracing::instrument(level = "debug", skip_all)]
 pub async fn create_category(
-    db_thread_pool: web::Data<DbThreadPool>,
+    budget_dao: RoutedBudgetDao,
     _user_access_token: VerifiedToken<Access, FromHeader>,
     budget_access_token: SpecialAccessToken<BudgetAccessToken, FromHeader>,
-    category_data: ProtoBuf<NewEncryptedBlob>,
+    category_data: ProtoOrJson<NewEncryptedBlob>,
 ) -> Result<HttpResponse, HttpErrorResponse> {
-    verify_read_write_access(&budget_access_token, &db_thread_pool).await?;
+    verify_read_write_access(&budget_access_token, &*budget_dao).await?;
+
+    let budget_id = budget_access_token.0.claims.budget_id;
 
     if category_data.value.len() > env::CONF.max_small_object_size {
-        return Err(HttpErrorResponse::InputTooLarge(String::from(
-            "Encrypted blob too large",
-        )));
+        return Err(HttpErrorResponse::InputTooLarge(
+            String::from("Encrypted blob too large"),
+            SizeLimit::field("value", env::CONF.max_small_object_size),
+        ));
     }
 
-    let category_id = match web::block(move || {
-        let budget_dao = db::budget::Dao::new(&db_thread_pool);
-        budget_dao.create_category(
+    let client_category_id = client_generated_id(category_data.id.as_ref())?;
+
+    let category_id = match budget_dao
+        .create_category(
+            client_category_id,
             &category_data.value,
             category_data.version_nonce,
-            budget_access_token.0.claims.budget_id,
+            budget_id,
         )
-    })
-    .await?
+        .await
     {
         Ok(id) => id,
         Err(e) => match e {
@@ -970,6 +1079,12 @@ pub async fn create_category(
                     DoesNotExistType::Budget,
                 ));
             }
+            e if is_duplicate_id(&e) => {
+                return Err(HttpErrorResponse::ConflictWithExisting(
+                    String::from("A category with the given ID already exists"),
+                    client_category_id,
+                ));
+            }
             _ => {
                 log::error!("{e}");
                 return Err(HttpErrorResponse::InternalError(String::from(
@@ -979,45 +1094,50 @@ pub async fn create_category(
         },
     };
 
-    Ok(HttpResponse::Created().protobuf(CategoryId {
+    change_feed::publish(budget_id, ChangeType::CategoryCreated, Some(category_id));
+
+    Ok(HttpResponse::Created().proto_or_json(CategoryId {
         value: category_id.into(),
     })?)
 }
 
+#[tracing::instrument(level = "debug", skip_all)]
 pub async fn edit_category(
-    db_thread_pool: web::Data<DbThreadPool>,
+    budget_dao: RoutedBudgetDao,
     _user_access_token: VerifiedToken<Access, FromHeader>,
     budget_access_token: SpecialAccessToken<BudgetAccessToken, FromHeader>,
-    category_data: ProtoBuf<CategoryUpdate>,
+    category_data: ProtoOrJson<CategoryUpdate>,
 ) -> Result<HttpResponse, HttpErrorResponse> {
-    verify_read_write_access(&budget_access_token, &db_thread_pool).await?;
+    verify_read_write_access(&budget_access_token, &*budget_dao).await?;
+
+    let budget_id = budget_access_token.0.claims.budget_id;
 
     if category_data.encrypted_blob.len() > env::CONF.max_small_object_size {
-        return Err(HttpErrorResponse::InputTooLarge(String::from(
-            "Encrypted blob too large",
-        )));
+        return Err(HttpErrorResponse::InputTooLarge(
+            String::from("Encrypted blob too large"),
+            SizeLimit::field("encrypted_blob", env::CONF.max_small_object_size),
+        ));
     }
 
     let category_id = (&category_data.category_id).try_into()?;
 
-    match web::block(move || {
-        let budget_dao = db::budget::Dao::new(&db_thread_pool);
-        budget_dao.update_category(
+    match budget_dao
+        .update_category(
             category_id,
             &category_data.encrypted_blob,
             category_data.version_nonce,
             category_data.expected_previous_version_nonce,
-            budget_access_token.0.claims.budget_id,
+            budget_id,
         )
-    })
-    .await?
+        .await
     {
         Ok(_) => (),
         Err(e) => match e {
-            DaoError::OutOfDate => {
-                return Err(HttpErrorResponse::OutOfDate(String::from(
-                    "Out of date version nonce",
-                )));
+            DaoError::OutOfDate(current_version_nonce) => {
+                return Err(HttpErrorResponse::OutOfDate(
+                    String::from("Out of date version nonce"),
+                    current_version_nonce,
+                ));
             }
             DaoError::QueryFailure(diesel::result::Error::NotFound) => {
                 return Err(HttpErrorResponse::DoesNotExist(
@@ -1034,25 +1154,25 @@ pub async fn edit_category(
         },
     };
 
+    change_feed::publish(budget_id, ChangeType::CategoryEdited, Some(category_id));
+
     Ok(HttpResponse::Ok().finish())
 }
 
+#[tracing::instrument(level = "debug", skip_all)]
 pub async fn delete_category(
-    db_thread_pool: web::Data<DbThreadPool>,
+    budget_dao: RoutedBudgetDao,
     _user_access_token: VerifiedToken<Access, FromHeader>,
     budget_access_token: SpecialAccessToken<BudgetAccessToken, FromHeader>,
-    category_id: ProtoBuf<CategoryId>,
+    category_id: ProtoOrJson<CategoryId>,
 ) -> Result<HttpResponse, HttpErrorResponse> {
-    verify_read_write_access(&budget_access_token, &db_thread_pool).await?;
+    verify_read_write_access(&budget_access_token, &*budget_dao).await?;
+
+    let budget_id = budget_access_token.0.claims.budget_id;
 
     let category_id = (&category_id.value).try_into()?;
 
-    match web::block(move || {
-        let budget_dao = db::budget::Dao::new(&db_thread_pool);
-        budget_dao.delete_category(category_id, budget_access_token.0.claims.budget_id)
-    })
-    .await?
-    {
+    match budget_dao.delete_category(category_id, budget_id).await {
         Ok(id) => id,
         Err(e) => match e {
             DaoError::QueryFailure(diesel::result::Error::NotFound) => {
@@ -1070,16 +1190,60 @@ pub async fn delete_category(
         },
     };
 
+    change_feed::publish(budget_id, ChangeType::CategoryDeleted, Some(category_id));
+
     Ok(HttpResponse::Ok().finish())
 }
 
+/// Checks that the caller may read the budget before the gRPC server subscribes them to its
+/// change feed. Responds with the ID of the budget.
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn authorize_change_feed(
+    budget_dao: RoutedBudgetDao,
+    _user_access_token: VerifiedToken<Access, FromHeader>,
+    budget_access_token: SpecialAccessToken<BudgetAccessToken, FromHeader>,
+) -> Result<HttpResponse, HttpErrorResponse> {
+    verify_read_access(&budget_access_token, &*budget_dao).await?;
+
+    Ok(HttpResponse::Ok()
+        .proto_or_json(UuidMessage::from(budget_access_token.0.claims.budget_id))?)
+}
+
+/// Parses an ID the client generated for a new entry or category. These must be UUIDv7s so they
+/// sort by creation time like the IDs the server generates.
+fn client_generated_id(id: Option<&UuidMessage>) -> Result<Option<Uuid>, HttpErrorResponse> {
+    let Some(id) = id else {
+        return Ok(None);
+    };
+
+    let id = Uuid::try_from(id)?;
+
+    if id.get_version() != Some(uuid::Version::SortRand) {
+        return Err(HttpErrorResponse::IncorrectlyFormed(String::from(
+            "Client-generated IDs must be UUIDv7s",
+        )));
+    }
+
+    Ok(Some(id))
+}
+
+/// Whether an insert failed because a client-generated ID is already in use
+fn is_duplicate_id(e: &DaoError) -> bool {
+    matches!(
+        e,
+        DaoError::QueryFailure(diesel::result::Error::DatabaseError(
+            diesel::result::DatabaseErrorKind::UniqueViolation,
+            _,
+        ))
+    )
+}
+
 async fn obtain_public_key(
     key_id: Uuid,
     budget_id: Uuid,
-    db_thread_pool: &DbThreadPool,
+    budget_dao: &dyn AsyncBudgetDao,
 ) -> Result<BudgetAccessKey, HttpErrorResponse> {
-    let budget_dao = db::budget::Dao::new(db_thread_pool);
-    let key = match web::block(move || budget_dao.get_public_budget_key(key_id, budget_id)).await? {
+    let key = match budget_dao.get_public_budget_key(key_id, budget_id).await {
         Ok(b) => b,
         Err(e) => match e {
             DaoError::QueryFailure(diesel::result::Error::NotFound) => {
@@ -1102,10 +1266,10 @@ async fn obtain_public_key(
 
 async fn verify_read_write_access<F: TokenLocation>(
     budget_access_token: &SpecialAccessToken<BudgetAccessToken, F>,
-    db_thread_pool: &DbThreadPool,
+    budget_dao: &dyn AsyncBudgetDao,
 ) -> Result<(), HttpErrorResponse> {
     let claims = &budget_access_token.0.claims;
-    let public_key = obtain_public_key(claims.key_id, claims.budget_id, db_thread_pool).await?;
+    let public_key = obtain_public_key(claims.key_id, claims.budget_id, budget_dao).await?;
     budget_access_token.0.verify(&public_key.public_key)?;
 
     if public_key.read_only {
@@ -1119,10 +1283,10 @@ async fn verify_read_write_access<F: TokenLocation>(
 
 async fn verify_read_access<F: TokenLocation>(
     budget_access_token: &SpecialAccessToken<BudgetAccessToken, F>,
-    db_thread_pool: &DbThreadPool,
+    budget_dao: &dyn AsyncBudgetDao,
 ) -> Result<(), HttpErrorResponse> {
     let claims = &budget_access_token.0.claims;
-    let public_key = obtain_public_key(claims.key_id, claims.budget_id, db_thread_pool).await?;
+    let public_key = obtain_public_key(claims.key_id, claims.budget_id, budget_dao).await?;
     budget_access_token.0.verify(&public_key.public_key)?;
 
     Ok(())
@@ -1134,6 +1298,7 @@ pub mod tests {
 
     use super::*;
 
+    use entries_common::db::{self, memory, AsyncDbPools};
     use entries_common::messages::{BudgetFrame, CategoryWithTempId};
     use entries_common::messages::{
         BudgetIdAndEncryptionKey, BudgetList, BudgetShareInviteList, EntryIdAndCategoryId,
@@ -1146,7 +1311,7 @@ pub mod tests {
     use entries_common::schema::users as user_fields;
     use entries_common::schema::users::dsl::users;
 
-    use actix_protobuf::ProtoBufConfig;
+    use crate::middleware::proto_or_json::ProtoOrJsonConfig;
     use actix_web::body::to_bytes;
     use actix_web::http::StatusCode;
     use actix_web::test::{self, TestRequest};
@@ -1162,6 +1327,7 @@ pub mod tests {
     use prost::Message;
     use rand::Rng;
 
+    use crate::daos::Daos;
     use crate::env;
     use crate::handlers::test_utils::{self, gen_budget_token, gen_bytes};
     use crate::services::api::RouteLimiters;
@@ -1171,8 +1337,10 @@ pub mod tests {
         let app = test::init_service(
             App::new()
                 .app_data(Data::new(env::testing::DB_THREAD_POOL.clone()))
+                .app_data(Data::new(env::testing::async_db_pools()))
+                .configure(env::testing::configure_daos)
                 .app_data(Data::new(env::testing::SMTP_THREAD_POOL.clone()))
-                .app_data(ProtoBufConfig::default())
+                .app_data(ProtoOrJsonConfig::default())
                 .configure(|cfg| crate::services::api::configure(cfg, RouteLimiters::default())),
         )
         .await;
@@ -1190,11 +1358,13 @@ pub mod tests {
                     temp_id: 0,
                     encrypted_blob: gen_bytes(40),
                     version_nonce: rand::thread_rng().gen(),
+                    id: None,
                 },
                 CategoryWithTempId {
                     temp_id: 1,
                     encrypted_blob: gen_bytes(60),
                     version_nonce: rand::thread_rng().gen(),
+                    id: None,
                 },
             ],
             user_public_budget_key: public_key,
@@ -1281,6 +1451,7 @@ pub mod tests {
         let new_category = NewEncryptedBlob {
             value: gen_bytes(40),
             version_nonce: rand::thread_rng().gen(),
+            id: None,
         };
 
         let req = TestRequest::post()
@@ -1346,6 +1517,7 @@ pub mod tests {
             encrypted_blob: gen_bytes(20),
             version_nonce: rand::thread_rng().gen(),
             category_id: Some(new_category_id.into()),
+            id: None,
         };
 
         let req = TestRequest::post()
@@ -1428,6 +1600,7 @@ pub mod tests {
             encrypted_blob: gen_bytes(20),
             version_nonce: rand::thread_rng().gen(),
             category_id: None,
+            id: None,
         };
 
         let req = TestRequest::post()
@@ -1495,6 +1668,8 @@ pub mod tests {
             entry_version_nonce: rand::thread_rng().gen(),
             category_encrypted_blob: gen_bytes(12),
             category_version_nonce: rand::thread_rng().gen(),
+            entry_id: None,
+            category_id: None,
         };
 
         let req = TestRequest::post()
@@ -1575,16 +1750,17 @@ pub mod tests {
     #[actix_rt::test]
     #[ignore]
     async fn test_create_budget_fails_with_large_input() {
+        let daos = Daos::memory(&memory::Dao::new());
         let app = test::init_service(
             App::new()
-                .app_data(Data::new(env::testing::DB_THREAD_POOL.clone()))
+                .configure(|cfg| daos.configure(cfg))
                 .app_data(Data::new(env::testing::SMTP_THREAD_POOL.clone()))
-                .app_data(ProtoBufConfig::default())
+                .app_data(ProtoOrJsonConfig::default())
                 .configure(|cfg| crate::services::api::configure(cfg, RouteLimiters::default())),
         )
         .await;
 
-        let (_, access_token, _, _) = test_utils::create_user().await;
+        let access_token = test_utils::gen_access_token();
 
         let new_budget = NewBudget {
             encrypted_blob: vec![0; env::CONF.max_small_object_size + 1],
@@ -1594,11 +1770,13 @@ pub mod tests {
                     temp_id: 0,
                     encrypted_blob: gen_bytes(40),
                     version_nonce: rand::thread_rng().gen(),
+                    id: None,
                 },
                 CategoryWithTempId {
                     temp_id: 1,
                     encrypted_blob: gen_bytes(60),
                     version_nonce: rand::thread_rng().gen(),
+                    id: None,
                 },
             ],
             user_public_budget_key: gen_bytes(40),
@@ -1627,11 +1805,13 @@ pub mod tests {
                     temp_id: 0,
                     encrypted_blob: gen_bytes(40),
                     version_nonce: rand::thread_rng().gen(),
+                    id: None,
                 },
                 CategoryWithTempId {
                     temp_id: 1,
                     encrypted_blob: gen_bytes(60),
                     version_nonce: rand::thread_rng().gen(),
+                    id: None,
                 },
             ],
             user_public_budget_key: vec![0; env::CONF.max_encryption_key_size + 1],
@@ -1659,8 +1839,10 @@ pub mod tests {
         let app = test::init_service(
             App::new()
                 .app_data(Data::new(env::testing::DB_THREAD_POOL.clone()))
+                .app_data(Data::new(env::testing::async_db_pools()))
+                .configure(env::testing::configure_daos)
                 .app_data(Data::new(env::testing::SMTP_THREAD_POOL.clone()))
-                .app_data(ProtoBufConfig::default())
+                .app_data(ProtoOrJsonConfig::default())
                 .configure(|cfg| crate::services::api::configure(cfg, RouteLimiters::default())),
         )
         .await;
@@ -1677,6 +1859,7 @@ pub mod tests {
                 temp_id: 0,
                 encrypted_blob: gen_bytes(40),
                 version_nonce: rand::thread_rng().gen(),
+                id: None,
             }],
             user_public_budget_key: public_key,
         };
@@ -1711,6 +1894,7 @@ pub mod tests {
             encrypted_blob: vec![0; env::CONF.max_small_object_size + 1],
             version_nonce: rand::thread_rng().gen(),
             category_id: Some(category_id.into()),
+            id: None,
         };
 
         let req = TestRequest::post()
@@ -1733,23 +1917,26 @@ pub mod tests {
     #[actix_rt::test]
     #[ignore]
     async fn test_create_entry_and_category_fails_with_large_input() {
+        let daos = Daos::memory(&memory::Dao::new());
         let app = test::init_service(
             App::new()
-                .app_data(Data::new(env::testing::DB_THREAD_POOL.clone()))
+                .configure(|cfg| daos.configure(cfg))
                 .app_data(Data::new(env::testing::SMTP_THREAD_POOL.clone()))
-                .app_data(ProtoBufConfig::default())
+                .app_data(ProtoOrJsonConfig::default())
                 .configure(|cfg| crate::services::api::configure(cfg, RouteLimiters::default())),
         )
         .await;
 
-        let (_, access_token, _, _) = test_utils::create_user().await;
-        let (_, budget_token) = test_utils::create_budget(&access_token).await;
+        let access_token = test_utils::gen_access_token();
+        let (_, budget_token) = test_utils::create_budget_in(&daos, &access_token).await;
 
         let new_entry_and_category = EntryAndCategory {
             entry_encrypted_blob: vec![0; env::CONF.max_small_object_size + 1],
             entry_version_nonce: rand::thread_rng().gen(),
             category_encrypted_blob: gen_bytes(12),
             category_version_nonce: rand::thread_rng().gen(),
+            entry_id: None,
+            category_id: None,
         };
 
         let req = TestRequest::post()
@@ -1773,6 +1960,8 @@ pub mod tests {
             entry_version_nonce: rand::thread_rng().gen(),
             category_encrypted_blob: vec![0; env::CONF.max_small_object_size + 1],
             category_version_nonce: rand::thread_rng().gen(),
+            entry_id: None,
+            category_id: None,
         };
 
         let req = TestRequest::post()
@@ -1798,26 +1987,275 @@ pub mod tests {
         let app = test::init_service(
             App::new()
                 .app_data(Data::new(env::testing::DB_THREAD_POOL.clone()))
+                .app_data(Data::new(env::testing::async_db_pools()))
+                .configure(env::testing::configure_daos)
                 .app_data(Data::new(env::testing::SMTP_THREAD_POOL.clone()))
-                .app_data(ProtoBufConfig::default())
+                .app_data(ProtoOrJsonConfig::default())
                 .configure(|cfg| crate::services::api::configure(cfg, RouteLimiters::default())),
         )
         .await;
 
-        let (_, access_token, _, _) = test_utils::create_user().await;
+        let (_, access_token, _, _) = test_utils::create_user().await;
+
+        let key_pair = ed25519::SigningKey::generate(&mut rand::rngs::OsRng);
+        let public_key = Vec::from(key_pair.verifying_key().to_bytes());
+
+        let new_budget = NewBudget {
+            encrypted_blob: gen_bytes(32),
+            version_nonce: rand::thread_rng().gen(),
+            categories: vec![CategoryWithTempId {
+                temp_id: 0,
+                encrypted_blob: gen_bytes(40),
+                version_nonce: rand::thread_rng().gen(),
+                id: None,
+            }],
+            user_public_budget_key: public_key,
+        };
+
+        let req = TestRequest::post()
+            .uri("/api/budget")
+            .insert_header(("AccessToken", access_token.as_str()))
+            .insert_header(("Content-Type", "application/protobuf"))
+            .set_payload(new_budget.encode_to_vec())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::CREATED);
+
+        let resp_body = to_bytes(resp.into_body()).await.unwrap();
+        let budget_data = BudgetFrame::decode(resp_body).unwrap();
+
+        let budget = budgets
+            .find(Uuid::try_from(budget_data.id).unwrap())
+            .get_result::<Budget>(&mut env::testing::DB_THREAD_POOL.get().unwrap())
+            .unwrap();
+
+        let budget_access_token = gen_budget_token(
+            budget.id,
+            budget_data.access_key_id.try_into().unwrap(),
+            &key_pair,
+        );
+
+        let new_category = NewEncryptedBlob {
+            value: vec![0; env::CONF.max_small_object_size + 1],
+            version_nonce: rand::thread_rng().gen(),
+            id: None,
+        };
+
+        let req = TestRequest::post()
+            .uri("/api/budget/category")
+            .insert_header(("AccessToken", access_token.as_str()))
+            .insert_header(("BudgetAccessToken", budget_access_token.as_str()))
+            .insert_header(("Content-Type", "application/protobuf"))
+            .set_payload(new_category.encode_to_vec())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+        let resp_body = to_bytes(resp.into_body()).await.unwrap();
+        let resp_body = ServerErrorResponse::decode(resp_body).unwrap();
+
+        assert_eq!(resp_body.err_type, ErrorType::InputTooLarge as i32);
+    }
+
+    #[actix_rt::test]
+    async fn test_create_with_client_generated_ids() {
+        let daos = Daos::memory(&memory::Dao::new());
+        let app = test::init_service(
+            App::new()
+                .configure(|cfg| daos.configure(cfg))
+                .app_data(Data::new(env::testing::SMTP_THREAD_POOL.clone()))
+                .app_data(ProtoOrJsonConfig::default())
+                .configure(|cfg| crate::services::api::configure(cfg, RouteLimiters::default())),
+        )
+        .await;
+
+        let access_token = test_utils::gen_access_token();
+        let (_, budget_token) = test_utils::create_budget_in(&daos, &access_token).await;
+        let (_, other_budget_token) = test_utils::create_budget_in(&daos, &access_token).await;
+
+        let category_id = Uuid::now_v7();
+        let new_category = NewEncryptedBlob {
+            value: gen_bytes(40),
+            version_nonce: rand::thread_rng().gen(),
+            id: Some(category_id.into()),
+        };
+
+        let req = TestRequest::post()
+            .uri("/api/budget/category")
+            .insert_header(("AccessToken", access_token.as_str()))
+            .insert_header(("BudgetAccessToken", budget_token.as_str()))
+            .insert_header(("Content-Type", "application/protobuf"))
+            .set_payload(new_category.encode_to_vec())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::CREATED);
+
+        let resp_body = to_bytes(resp.into_body()).await.unwrap();
+        let resp_category_id = CategoryId::decode(resp_body).unwrap();
+        assert_eq!(Uuid::try_from(resp_category_id.value).unwrap(), category_id);
+
+        // The same ID can't be used twice, even in another budget
+        let req = TestRequest::post()
+            .uri("/api/budget/category")
+            .insert_header(("AccessToken", access_token.as_str()))
+            .insert_header(("BudgetAccessToken", other_budget_token.as_str()))
+            .insert_header(("Content-Type", "application/protobuf"))
+            .set_payload(new_category.encode_to_vec())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+        let resp_body = to_bytes(resp.into_body()).await.unwrap();
+        let resp_err = ServerErrorResponse::decode(resp_body).unwrap();
+        assert_eq!(resp_err.err_type, ErrorType::ConflictWithExisting as i32);
+        assert_eq!(
+            Uuid::try_from(resp_err.conflicting_id.unwrap()).unwrap(),
+            category_id
+        );
+
+        let entry_id = Uuid::now_v7();
+        let new_entry = EncryptedBlobAndCategoryId {
+            encrypted_blob: gen_bytes(20),
+            version_nonce: rand::thread_rng().gen(),
+            category_id: Some(category_id.into()),
+            id: Some(entry_id.into()),
+        };
+
+        let req = TestRequest::post()
+            .uri("/api/budget/entry")
+            .insert_header(("AccessToken", access_token.as_str()))
+            .insert_header(("BudgetAccessToken", budget_token.as_str()))
+            .insert_header(("Content-Type", "application/protobuf"))
+            .set_payload(new_entry.encode_to_vec())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::CREATED);
+
+        let resp_body = to_bytes(resp.into_body()).await.unwrap();
+        let resp_entry_id = EntryId::decode(resp_body).unwrap();
+        assert_eq!(Uuid::try_from(resp_entry_id.value).unwrap(), entry_id);
+
+        let req = TestRequest::post()
+            .uri("/api/budget/entry")
+            .insert_header(("AccessToken", access_token.as_str()))
+            .insert_header(("BudgetAccessToken", budget_token.as_str()))
+            .insert_header(("Content-Type", "application/protobuf"))
+            .set_payload(new_entry.encode_to_vec())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+        let resp_body = to_bytes(resp.into_body()).await.unwrap();
+        let resp_err = ServerErrorResponse::decode(resp_body).unwrap();
+        assert_eq!(resp_err.err_type, ErrorType::ConflictWithExisting as i32);
+        assert_eq!(
+            Uuid::try_from(resp_err.conflicting_id.unwrap()).unwrap(),
+            entry_id
+        );
+
+        // An entry can't reference a category from another budget
+        let new_entry = EncryptedBlobAndCategoryId {
+            encrypted_blob: gen_bytes(20),
+            version_nonce: rand::thread_rng().gen(),
+            category_id: Some(category_id.into()),
+            id: Some(Uuid::now_v7().into()),
+        };
+
+        let req = TestRequest::post()
+            .uri("/api/budget/entry")
+            .insert_header(("AccessToken", access_token.as_str()))
+            .insert_header(("BudgetAccessToken", other_budget_token.as_str()))
+            .insert_header(("Content-Type", "application/protobuf"))
+            .set_payload(new_entry.encode_to_vec())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+        let resp_body = to_bytes(resp.into_body()).await.unwrap();
+        let resp_err = ServerErrorResponse::decode(resp_body).unwrap();
+        assert_eq!(resp_err.err_type, ErrorType::CategoryDoesNotExist as i32);
+
+        // IDs must be UUIDv7s
+        let new_entry = EncryptedBlobAndCategoryId {
+            encrypted_blob: gen_bytes(20),
+            version_nonce: rand::thread_rng().gen(),
+            category_id: None,
+            id: Some(
+                uuid::Builder::from_random_bytes(rand::random())
+                    .into_uuid()
+                    .into(),
+            ),
+        };
+
+        let req = TestRequest::post()
+            .uri("/api/budget/entry")
+            .insert_header(("AccessToken", access_token.as_str()))
+            .insert_header(("BudgetAccessToken", budget_token.as_str()))
+            .insert_header(("Content-Type", "application/protobuf"))
+            .set_payload(new_entry.encode_to_vec())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+        let resp_body = to_bytes(resp.into_body()).await.unwrap();
+        let resp_err = ServerErrorResponse::decode(resp_body).unwrap();
+        assert_eq!(resp_err.err_type, ErrorType::IncorrectlyFormed as i32);
+
+        let entry_id = Uuid::now_v7();
+        let category_id = Uuid::now_v7();
+        let new_entry_and_category = EntryAndCategory {
+            entry_encrypted_blob: gen_bytes(30),
+            entry_version_nonce: rand::thread_rng().gen(),
+            category_encrypted_blob: gen_bytes(12),
+            category_version_nonce: rand::thread_rng().gen(),
+            entry_id: Some(entry_id.into()),
+            category_id: Some(category_id.into()),
+        };
+
+        let req = TestRequest::post()
+            .uri("/api/budget/entry_and_category")
+            .insert_header(("AccessToken", access_token.as_str()))
+            .insert_header(("BudgetAccessToken", budget_token.as_str()))
+            .insert_header(("Content-Type", "application/protobuf"))
+            .set_payload(new_entry_and_category.encode_to_vec())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::CREATED);
 
-        let key_pair = ed25519::SigningKey::generate(&mut rand::rngs::OsRng);
-        let public_key = Vec::from(key_pair.verifying_key().to_bytes());
+        let resp_body = to_bytes(resp.into_body()).await.unwrap();
+        let ids = EntryIdAndCategoryId::decode(resp_body).unwrap();
+        assert_eq!(Uuid::try_from(ids.entry_id).unwrap(), entry_id);
+        assert_eq!(Uuid::try_from(ids.category_id).unwrap(), category_id);
 
-        let new_budget = NewBudget {
+        let key_pair = ed25519::SigningKey::generate(&mut rand::rngs::OsRng);
+        let category_id = Uuid::now_v7();
+        let mut new_budget = NewBudget {
             encrypted_blob: gen_bytes(32),
             version_nonce: rand::thread_rng().gen(),
-            categories: vec![CategoryWithTempId {
-                temp_id: 0,
-                encrypted_blob: gen_bytes(40),
-                version_nonce: rand::thread_rng().gen(),
-            }],
-            user_public_budget_key: public_key,
+            categories: vec![
+                CategoryWithTempId {
+                    temp_id: 0,
+                    encrypted_blob: gen_bytes(40),
+                    version_nonce: rand::thread_rng().gen(),
+                    id: Some(category_id.into()),
+                },
+                CategoryWithTempId {
+                    temp_id: 1,
+                    encrypted_blob: gen_bytes(60),
+                    version_nonce: rand::thread_rng().gen(),
+                    id: Some(category_id.into()),
+                },
+            ],
+            user_public_budget_key: Vec::from(key_pair.verifying_key().to_bytes()),
         };
 
         let req = TestRequest::post()
@@ -1828,65 +2266,67 @@ pub mod tests {
             .to_request();
         let resp = test::call_service(&app, req).await;
 
-        assert_eq!(resp.status(), StatusCode::CREATED);
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
 
         let resp_body = to_bytes(resp.into_body()).await.unwrap();
-        let budget_data = BudgetFrame::decode(resp_body).unwrap();
-
-        let budget = budgets
-            .find(Uuid::try_from(budget_data.id).unwrap())
-            .get_result::<Budget>(&mut env::testing::DB_THREAD_POOL.get().unwrap())
-            .unwrap();
-
-        let budget_access_token = gen_budget_token(
-            budget.id,
-            budget_data.access_key_id.try_into().unwrap(),
-            &key_pair,
-        );
+        let resp_err = ServerErrorResponse::decode(resp_body).unwrap();
+        assert_eq!(resp_err.err_type, ErrorType::ConflictWithExisting as i32);
 
-        let new_category = NewEncryptedBlob {
-            value: vec![0; env::CONF.max_small_object_size + 1],
-            version_nonce: rand::thread_rng().gen(),
-        };
+        // Categories without a client-generated ID get one from the server
+        new_budget.categories[1].id = None;
 
         let req = TestRequest::post()
-            .uri("/api/budget/category")
+            .uri("/api/budget")
             .insert_header(("AccessToken", access_token.as_str()))
-            .insert_header(("BudgetAccessToken", budget_access_token.as_str()))
             .insert_header(("Content-Type", "application/protobuf"))
-            .set_payload(new_category.encode_to_vec())
+            .set_payload(new_budget.encode_to_vec())
             .to_request();
         let resp = test::call_service(&app, req).await;
 
-        assert_eq!(resp.status(), StatusCode::PAYLOAD_TOO_LARGE);
+        assert_eq!(resp.status(), StatusCode::CREATED);
 
         let resp_body = to_bytes(resp.into_body()).await.unwrap();
-        let resp_body = ServerErrorResponse::decode(resp_body).unwrap();
+        let budget_data = BudgetFrame::decode(resp_body).unwrap();
 
-        assert_eq!(resp_body.err_type, ErrorType::InputTooLarge as i32);
+        let category_0 = budget_data
+            .category_ids
+            .iter()
+            .find(|c| c.temp_id == 0)
+            .unwrap();
+        let category_1 = budget_data
+            .category_ids
+            .iter()
+            .find(|c| c.temp_id == 1)
+            .unwrap();
+
+        assert_eq!(Uuid::try_from(&category_0.real_id).unwrap(), category_id);
+        assert_ne!(Uuid::try_from(&category_1.real_id).unwrap(), category_id);
     }
 
     #[actix_rt::test]
     async fn test_get_multiple_budgets() {
+        let daos = Daos::memory(&memory::Dao::new());
         let app = test::init_service(
             App::new()
-                .app_data(Data::new(env::testing::DB_THREAD_POOL.clone()))
+                .configure(|cfg| daos.configure(cfg))
                 .app_data(Data::new(env::testing::SMTP_THREAD_POOL.clone()))
-                .app_data(ProtoBufConfig::default())
+                .app_data(ProtoOrJsonConfig::default())
                 .configure(|cfg| crate::services::api::configure(cfg, RouteLimiters::default())),
         )
         .await;
 
-        let (_, access_token, _, _) = test_utils::create_user().await;
-        let (budget1, budget1_token) = test_utils::create_budget(&access_token).await;
-        let (budget2, budget2_token) = test_utils::create_budget(&access_token).await;
-        let (budget3, budget3_token) = test_utils::create_budget(&access_token).await;
+        let access_token = test_utils::gen_access_token();
+        let (budget1, budget1_token) = test_utils::create_budget_in(&daos, &access_token).await;
+        let (budget2, budget2_token) = test_utils::create_budget_in(&daos, &access_token).await;
+        let (budget3, budget3_token) = test_utils::create_budget_in(&daos, &access_token).await;
 
         let new_entry_and_category = EntryAndCategory {
             entry_encrypted_blob: gen_bytes(30),
             entry_version_nonce: rand::thread_rng().gen(),
             category_encrypted_blob: gen_bytes(12),
             category_version_nonce: rand::thread_rng().gen(),
+            entry_id: None,
+            category_id: None,
         };
 
         let req = TestRequest::post()
@@ -2019,19 +2459,127 @@ pub mod tests {
         assert_eq!(resp_err.err_type, ErrorType::TooManyRequested as i32);
     }
 
+    #[actix_rt::test]
+    async fn test_get_budgets_with_etag() {
+        let daos = Daos::memory(&memory::Dao::new());
+        let app = test::init_service(
+            App::new()
+                .configure(|cfg| daos.configure(cfg))
+                .app_data(Data::new(env::testing::SMTP_THREAD_POOL.clone()))
+                .app_data(ProtoOrJsonConfig::default())
+                .configure(|cfg| crate::services::api::configure(cfg, RouteLimiters::default())),
+        )
+        .await;
+
+        let access_token = test_utils::gen_access_token();
+        let (_, budget1_token) = test_utils::create_budget_in(&daos, &access_token).await;
+        let (_, budget2_token) = test_utils::create_budget_in(&daos, &access_token).await;
+
+        let budget_access_tokens = BudgetAccessTokenList {
+            tokens: vec![budget1_token.clone(), budget2_token.clone()],
+        };
+
+        let req = TestRequest::get()
+            .uri("/api/budget")
+            .insert_header(("AccessToken", access_token.as_str()))
+            .insert_header(("Content-Type", "application/protobuf"))
+            .set_payload(budget_access_tokens.encode_to_vec())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let etag = resp.headers().get("ETag").unwrap().clone();
+        assert!(!etag.to_str().unwrap().starts_with("W/"));
+
+        // The order of the tokens doesn't change the response
+        let reordered_budget_access_tokens = BudgetAccessTokenList {
+            tokens: vec![budget2_token, budget1_token.clone()],
+        };
+
+        let req = TestRequest::get()
+            .uri("/api/budget")
+            .insert_header(("AccessToken", access_token.as_str()))
+            .insert_header(("Content-Type", "application/protobuf"))
+            .insert_header(("If-None-Match", etag.clone()))
+            .set_payload(reordered_budget_access_tokens.encode_to_vec())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(resp.headers().get("ETag").unwrap(), &etag);
+
+        let resp_body = to_bytes(resp.into_body()).await.unwrap();
+        assert!(resp_body.is_empty());
+
+        let new_category = NewEncryptedBlob {
+            value: gen_bytes(12),
+            version_nonce: rand::thread_rng().gen(),
+            id: None,
+        };
+
+        let req = TestRequest::post()
+            .uri("/api/budget/category")
+            .insert_header(("AccessToken", access_token.as_str()))
+            .insert_header(("BudgetAccessToken", budget1_token.as_str()))
+            .insert_header(("Content-Type", "application/protobuf"))
+            .set_payload(new_category.encode_to_vec())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::CREATED);
+
+        let req = TestRequest::get()
+            .uri("/api/budget")
+            .insert_header(("AccessToken", access_token.as_str()))
+            .insert_header(("Content-Type", "application/protobuf"))
+            .insert_header(("If-None-Match", etag.clone()))
+            .set_payload(budget_access_tokens.encode_to_vec())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let new_etag = resp.headers().get("ETag").unwrap().clone();
+        assert_ne!(new_etag, etag);
+
+        let resp_body = to_bytes(resp.into_body()).await.unwrap();
+        let budget_list = BudgetList::decode(resp_body).unwrap();
+        assert_eq!(
+            budget_list
+                .budgets
+                .iter()
+                .map(|b| b.categories.len())
+                .sum::<usize>(),
+            1
+        );
+
+        let req = TestRequest::get()
+            .uri("/api/budget")
+            .insert_header(("AccessToken", access_token.as_str()))
+            .insert_header(("Content-Type", "application/protobuf"))
+            .insert_header(("If-None-Match", "*"))
+            .set_payload(budget_access_tokens.encode_to_vec())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::NOT_MODIFIED);
+    }
+
     #[actix_rt::test]
     #[ignore]
     async fn test_get_multiple_budgets_fails_with_too_many_tokens() {
+        let daos = Daos::memory(&memory::Dao::new());
         let app = test::init_service(
             App::new()
-                .app_data(Data::new(env::testing::DB_THREAD_POOL.clone()))
+                .configure(|cfg| daos.configure(cfg))
                 .app_data(Data::new(env::testing::SMTP_THREAD_POOL.clone()))
-                .app_data(ProtoBufConfig::default())
+                .app_data(ProtoOrJsonConfig::default())
                 .configure(|cfg| crate::services::api::configure(cfg, RouteLimiters::default())),
         )
         .await;
 
-        let (_, access_token, _, _) = test_utils::create_user().await;
+        let access_token = test_utils::gen_access_token();
 
         let budget_access_tokens = BudgetAccessTokenList {
             tokens: vec![String::from("test"); env::CONF.max_budget_fetch_count + 1],
@@ -2058,8 +2606,10 @@ pub mod tests {
         let app = test::init_service(
             App::new()
                 .app_data(Data::new(env::testing::DB_THREAD_POOL.clone()))
+                .app_data(Data::new(env::testing::async_db_pools()))
+                .configure(env::testing::configure_daos)
                 .app_data(Data::new(env::testing::SMTP_THREAD_POOL.clone()))
-                .app_data(ProtoBufConfig::default())
+                .app_data(ProtoOrJsonConfig::default())
                 .configure(|cfg| crate::services::api::configure(cfg, RouteLimiters::default())),
         )
         .await;
@@ -2076,6 +2626,7 @@ pub mod tests {
                 temp_id: 0,
                 encrypted_blob: gen_bytes(40),
                 version_nonce: rand::thread_rng().gen(),
+                id: None,
             }],
             user_public_budget_key: public_key,
         };
@@ -2110,6 +2661,7 @@ pub mod tests {
             encrypted_blob: gen_bytes(20),
             version_nonce: rand::thread_rng().gen(),
             category_id: Some(category_id.into()),
+            id: None,
         };
 
         let req = TestRequest::post()
@@ -2212,8 +2764,10 @@ pub mod tests {
         let app = test::init_service(
             App::new()
                 .app_data(Data::new(env::testing::DB_THREAD_POOL.clone()))
+                .app_data(Data::new(env::testing::async_db_pools()))
+                .configure(env::testing::configure_daos)
                 .app_data(Data::new(env::testing::SMTP_THREAD_POOL.clone()))
-                .app_data(ProtoBufConfig::default())
+                .app_data(ProtoOrJsonConfig::default())
                 .configure(|cfg| crate::services::api::configure(cfg, RouteLimiters::default())),
         )
         .await;
@@ -2230,6 +2784,7 @@ pub mod tests {
                 temp_id: 0,
                 encrypted_blob: gen_bytes(40),
                 version_nonce: rand::thread_rng().gen(),
+                id: None,
             }],
             user_public_budget_key: public_key,
         };
@@ -2264,6 +2819,7 @@ pub mod tests {
             encrypted_blob: gen_bytes(20),
             version_nonce: rand::thread_rng().gen(),
             category_id: Some(category_id.into()),
+            id: None,
         };
 
         let req = TestRequest::post()
@@ -2351,17 +2907,18 @@ pub mod tests {
 
     #[actix_rt::test]
     async fn test_edit_budget() {
+        let daos = Daos::memory(&memory::Dao::new());
         let app = test::init_service(
             App::new()
-                .app_data(Data::new(env::testing::DB_THREAD_POOL.clone()))
+                .configure(|cfg| daos.configure(cfg))
                 .app_data(Data::new(env::testing::SMTP_THREAD_POOL.clone()))
-                .app_data(ProtoBufConfig::default())
+                .app_data(ProtoOrJsonConfig::default())
                 .configure(|cfg| crate::services::api::configure(cfg, RouteLimiters::default())),
         )
         .await;
 
-        let (_, access_token, _, _) = test_utils::create_user().await;
-        let (budget, budget_token) = test_utils::create_budget(&access_token).await;
+        let access_token = test_utils::gen_access_token();
+        let (budget, budget_token) = test_utils::create_budget_in(&daos, &access_token).await;
 
         let budget_token_list = BudgetAccessTokenList {
             tokens: vec![budget_token.clone()],
@@ -2452,17 +3009,18 @@ pub mod tests {
     #[actix_rt::test]
     #[ignore]
     async fn test_edit_budget_fails_with_large_input() {
+        let daos = Daos::memory(&memory::Dao::new());
         let app = test::init_service(
             App::new()
-                .app_data(Data::new(env::testing::DB_THREAD_POOL.clone()))
+                .configure(|cfg| daos.configure(cfg))
                 .app_data(Data::new(env::testing::SMTP_THREAD_POOL.clone()))
-                .app_data(ProtoBufConfig::default())
+                .app_data(ProtoOrJsonConfig::default())
                 .configure(|cfg| crate::services::api::configure(cfg, RouteLimiters::default())),
         )
         .await;
 
-        let (_, access_token, _, _) = test_utils::create_user().await;
-        let (budget, budget_token) = test_utils::create_budget(&access_token).await;
+        let access_token = test_utils::gen_access_token();
+        let (budget, budget_token) = test_utils::create_budget_in(&daos, &access_token).await;
 
         let blob_update = EncryptedBlobUpdate {
             encrypted_blob: vec![0; env::CONF.max_small_object_size + 1],
@@ -2489,17 +3047,18 @@ pub mod tests {
 
     #[actix_rt::test]
     async fn test_edit_entry() {
+        let daos = Daos::memory(&memory::Dao::new());
         let app = test::init_service(
             App::new()
-                .app_data(Data::new(env::testing::DB_THREAD_POOL.clone()))
+                .configure(|cfg| daos.configure(cfg))
                 .app_data(Data::new(env::testing::SMTP_THREAD_POOL.clone()))
-                .app_data(ProtoBufConfig::default())
+                .app_data(ProtoOrJsonConfig::default())
                 .configure(|cfg| crate::services::api::configure(cfg, RouteLimiters::default())),
         )
         .await;
 
-        let (_, access_token, _, _) = test_utils::create_user().await;
-        let (budget, budget_token) = test_utils::create_budget(&access_token).await;
+        let access_token = test_utils::gen_access_token();
+        let (budget, budget_token) = test_utils::create_budget_in(&daos, &access_token).await;
 
         let budget_token_list = BudgetAccessTokenList {
             tokens: vec![budget_token.clone()],
@@ -2527,6 +3086,7 @@ pub mod tests {
         let new_category1 = NewEncryptedBlob {
             value: gen_bytes(40),
             version_nonce: rand::thread_rng().gen(),
+            id: None,
         };
 
         let req = TestRequest::post()
@@ -2550,6 +3110,7 @@ pub mod tests {
         let new_category2 = NewEncryptedBlob {
             value: gen_bytes(40),
             version_nonce: rand::thread_rng().gen(),
+            id: None,
         };
 
         let req = TestRequest::post()
@@ -2574,6 +3135,7 @@ pub mod tests {
             encrypted_blob: gen_bytes(20),
             version_nonce: rand::thread_rng().gen(),
             category_id: Some(category2_id.into()),
+            id: None,
         };
 
         let req = TestRequest::post()
@@ -2821,17 +3383,18 @@ pub mod tests {
     #[actix_rt::test]
     #[ignore]
     async fn test_edit_entry_fails_with_large_input() {
+        let daos = Daos::memory(&memory::Dao::new());
         let app = test::init_service(
             App::new()
-                .app_data(Data::new(env::testing::DB_THREAD_POOL.clone()))
+                .configure(|cfg| daos.configure(cfg))
                 .app_data(Data::new(env::testing::SMTP_THREAD_POOL.clone()))
-                .app_data(ProtoBufConfig::default())
+                .app_data(ProtoOrJsonConfig::default())
                 .configure(|cfg| crate::services::api::configure(cfg, RouteLimiters::default())),
         )
         .await;
 
-        let (_, access_token, _, _) = test_utils::create_user().await;
-        let (_, budget_token) = test_utils::create_budget(&access_token).await;
+        let access_token = test_utils::gen_access_token();
+        let (_, budget_token) = test_utils::create_budget_in(&daos, &access_token).await;
 
         let budget_token_list = BudgetAccessTokenList {
             tokens: vec![budget_token.clone()],
@@ -2850,6 +3413,7 @@ pub mod tests {
         let new_category = NewEncryptedBlob {
             value: gen_bytes(40),
             version_nonce: rand::thread_rng().gen(),
+            id: None,
         };
 
         let req = TestRequest::post()
@@ -2874,6 +3438,7 @@ pub mod tests {
             encrypted_blob: gen_bytes(20),
             version_nonce: rand::thread_rng().gen(),
             category_id: Some(category_id.into()),
+            id: None,
         };
 
         let req = TestRequest::post()
@@ -2938,17 +3503,18 @@ pub mod tests {
 
     #[actix_rt::test]
     async fn test_edit_category() {
+        let daos = Daos::memory(&memory::Dao::new());
         let app = test::init_service(
             App::new()
-                .app_data(Data::new(env::testing::DB_THREAD_POOL.clone()))
+                .configure(|cfg| daos.configure(cfg))
                 .app_data(Data::new(env::testing::SMTP_THREAD_POOL.clone()))
-                .app_data(ProtoBufConfig::default())
+                .app_data(ProtoOrJsonConfig::default())
                 .configure(|cfg| crate::services::api::configure(cfg, RouteLimiters::default())),
         )
         .await;
 
-        let (_, access_token, _, _) = test_utils::create_user().await;
-        let (budget, budget_token) = test_utils::create_budget(&access_token).await;
+        let access_token = test_utils::gen_access_token();
+        let (budget, budget_token) = test_utils::create_budget_in(&daos, &access_token).await;
 
         let budget_token_list = BudgetAccessTokenList {
             tokens: vec![budget_token.clone()],
@@ -2976,6 +3542,7 @@ pub mod tests {
         let new_category1 = NewEncryptedBlob {
             value: gen_bytes(40),
             version_nonce: rand::thread_rng().gen(),
+            id: None,
         };
 
         let req = TestRequest::post()
@@ -2999,6 +3566,7 @@ pub mod tests {
         let new_category2 = NewEncryptedBlob {
             value: gen_bytes(40),
             version_nonce: rand::thread_rng().gen(),
+            id: None,
         };
 
         let req = TestRequest::post()
@@ -3023,6 +3591,7 @@ pub mod tests {
             encrypted_blob: gen_bytes(20),
             version_nonce: rand::thread_rng().gen(),
             category_id: Some(category2_id.into()),
+            id: None,
         };
 
         let req = TestRequest::post()
@@ -3357,17 +3926,18 @@ pub mod tests {
     #[actix_rt::test]
     #[ignore]
     async fn test_edit_category_fails_with_large_input() {
+        let daos = Daos::memory(&memory::Dao::new());
         let app = test::init_service(
             App::new()
-                .app_data(Data::new(env::testing::DB_THREAD_POOL.clone()))
+                .configure(|cfg| daos.configure(cfg))
                 .app_data(Data::new(env::testing::SMTP_THREAD_POOL.clone()))
-                .app_data(ProtoBufConfig::default())
+                .app_data(ProtoOrJsonConfig::default())
                 .configure(|cfg| crate::services::api::configure(cfg, RouteLimiters::default())),
         )
         .await;
 
-        let (_, access_token, _, _) = test_utils::create_user().await;
-        let (_, budget_token) = test_utils::create_budget(&access_token).await;
+        let access_token = test_utils::gen_access_token();
+        let (_, budget_token) = test_utils::create_budget_in(&daos, &access_token).await;
 
         let budget_token_list = BudgetAccessTokenList {
             tokens: vec![budget_token.clone()],
@@ -3386,6 +3956,7 @@ pub mod tests {
         let new_category = NewEncryptedBlob {
             value: gen_bytes(40),
             version_nonce: rand::thread_rng().gen(),
+            id: None,
         };
 
         let req = TestRequest::post()
@@ -3449,8 +4020,10 @@ pub mod tests {
         let app = test::init_service(
             App::new()
                 .app_data(Data::new(env::testing::DB_THREAD_POOL.clone()))
+                .app_data(Data::new(env::testing::async_db_pools()))
+                .configure(env::testing::configure_daos)
                 .app_data(Data::new(env::testing::SMTP_THREAD_POOL.clone()))
-                .app_data(ProtoBufConfig::default())
+                .app_data(ProtoOrJsonConfig::default())
                 .configure(|cfg| crate::services::api::configure(cfg, RouteLimiters::default())),
         )
         .await;
@@ -3707,8 +4280,10 @@ pub mod tests {
         let app = test::init_service(
             App::new()
                 .app_data(Data::new(env::testing::DB_THREAD_POOL.clone()))
+                .app_data(Data::new(env::testing::async_db_pools()))
+                .configure(env::testing::configure_daos)
                 .app_data(Data::new(env::testing::SMTP_THREAD_POOL.clone()))
-                .app_data(ProtoBufConfig::default())
+                .app_data(ProtoOrJsonConfig::default())
                 .configure(|cfg| crate::services::api::configure(cfg, RouteLimiters::default())),
         )
         .await;
@@ -3909,8 +4484,10 @@ pub mod tests {
         let app = test::init_service(
             App::new()
                 .app_data(Data::new(env::testing::DB_THREAD_POOL.clone()))
+                .app_data(Data::new(env::testing::async_db_pools()))
+                .configure(env::testing::configure_daos)
                 .app_data(Data::new(env::testing::SMTP_THREAD_POOL.clone()))
-                .app_data(ProtoBufConfig::default())
+                .app_data(ProtoOrJsonConfig::default())
                 .configure(|cfg| crate::services::api::configure(cfg, RouteLimiters::default())),
         )
         .await;
@@ -3957,8 +4534,10 @@ pub mod tests {
         let app = test::init_service(
             App::new()
                 .app_data(Data::new(env::testing::DB_THREAD_POOL.clone()))
+                .app_data(Data::new(env::testing::async_db_pools()))
+                .configure(env::testing::configure_daos)
                 .app_data(Data::new(env::testing::SMTP_THREAD_POOL.clone()))
-                .app_data(ProtoBufConfig::default())
+                .app_data(ProtoOrJsonConfig::default())
                 .configure(|cfg| crate::services::api::configure(cfg, RouteLimiters::default())),
         )
         .await;
@@ -4072,8 +4651,10 @@ pub mod tests {
         let app = test::init_service(
             App::new()
                 .app_data(Data::new(env::testing::DB_THREAD_POOL.clone()))
+                .app_data(Data::new(env::testing::async_db_pools()))
+                .configure(env::testing::configure_daos)
                 .app_data(Data::new(env::testing::SMTP_THREAD_POOL.clone()))
-                .app_data(ProtoBufConfig::default())
+                .app_data(ProtoOrJsonConfig::default())
                 .configure(|cfg| crate::services::api::configure(cfg, RouteLimiters::default())),
         )
         .await;
@@ -4210,8 +4791,10 @@ pub mod tests {
         let app = test::init_service(
             App::new()
                 .app_data(Data::new(env::testing::DB_THREAD_POOL.clone()))
+                .app_data(Data::new(env::testing::async_db_pools()))
+                .configure(env::testing::configure_daos)
                 .app_data(Data::new(env::testing::SMTP_THREAD_POOL.clone()))
-                .app_data(ProtoBufConfig::default())
+                .app_data(ProtoOrJsonConfig::default())
                 .configure(|cfg| crate::services::api::configure(cfg, RouteLimiters::default())),
         )
         .await;
@@ -4356,8 +4939,10 @@ pub mod tests {
         let app = test::init_service(
             App::new()
                 .app_data(Data::new(env::testing::DB_THREAD_POOL.clone()))
+                .app_data(Data::new(env::testing::async_db_pools()))
+                .configure(env::testing::configure_daos)
                 .app_data(Data::new(env::testing::SMTP_THREAD_POOL.clone()))
-                .app_data(ProtoBufConfig::default())
+                .app_data(ProtoOrJsonConfig::default())
                 .configure(|cfg| crate::services::api::configure(cfg, RouteLimiters::default())),
         )
         .await;
@@ -4616,4 +5201,136 @@ pub mod tests {
             .first::<Budget>(&mut env::testing::DB_THREAD_POOL.get().unwrap())
             .is_err());
     }
+
+    #[actix_rt::test]
+    async fn test_budget_access_keys_are_read_from_primary() {
+        let (_, access_token, _, _) = test_utils::create_user().await;
+        let (budget, budget_token) = test_utils::create_budget(&access_token).await;
+        let key_id = BudgetAccessToken::decode(&budget_token)
+            .unwrap()
+            .claims
+            .key_id;
+
+        // Nothing listens on this port, so any query sent to the replica fails
+        let unreachable_replica =
+            db::create_async_db_pool("postgres://entries@127.0.0.1:1/entries", 1, None);
+        let primary = env::testing::async_db_pools().primary().clone();
+        let db_pools = AsyncDbPools::new(primary, Some(unreachable_replica));
+        let budget_dao = db::nonblocking::budget::Dao::new(&db_pools);
+
+        let key = budget_dao
+            .get_public_budget_key(key_id, budget.id)
+            .await
+            .unwrap();
+        assert_eq!(key.key_id, key_id);
+
+        let keys = budget_dao
+            .get_multiple_public_budget_keys(&[key_id], &[budget.id])
+            .await
+            .unwrap();
+        assert_eq!(keys.len(), 1);
+
+        assert!(budget_dao.get_budget(budget.id).await.is_err());
+    }
+
+    #[actix_rt::test]
+    async fn test_create_edit_and_get_budget_in_memory() {
+        let daos = Daos::memory(&memory::Dao::new());
+        let app = test::init_service(
+            App::new()
+                .configure(|cfg| daos.configure(cfg))
+                .app_data(Data::new(env::testing::SMTP_THREAD_POOL.clone()))
+                .app_data(ProtoOrJsonConfig::default())
+                .configure(|cfg| crate::services::api::configure(cfg, RouteLimiters::default())),
+        )
+        .await;
+
+        let access_token = test_utils::gen_access_token();
+
+        let key_pair = ed25519::SigningKey::generate(&mut rand::rngs::OsRng);
+        let new_budget = NewBudget {
+            encrypted_blob: gen_bytes(32),
+            version_nonce: rand::thread_rng().gen(),
+            categories: Vec::new(),
+            user_public_budget_key: Vec::from(key_pair.verifying_key().to_bytes()),
+        };
+
+        let req = TestRequest::post()
+            .uri("/api/budget")
+            .insert_header(("AccessToken", access_token.as_str()))
+            .insert_header(("Content-Type", "application/protobuf"))
+            .set_payload(new_budget.encode_to_vec())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::CREATED);
+
+        let resp_body = to_bytes(resp.into_body()).await.unwrap();
+        let budget_data = BudgetFrame::decode(resp_body).unwrap();
+        let budget_id = Uuid::try_from(budget_data.id).unwrap();
+        let budget_token = gen_budget_token(
+            budget_id,
+            budget_data.access_key_id.try_into().unwrap(),
+            &key_pair,
+        );
+
+        let blob_update = EncryptedBlobUpdate {
+            encrypted_blob: gen_bytes(20),
+            version_nonce: new_budget.version_nonce.wrapping_add(1),
+            expected_previous_version_nonce: new_budget.version_nonce.wrapping_sub(1),
+        };
+
+        let req = TestRequest::put()
+            .uri("/api/budget")
+            .insert_header(("AccessToken", access_token.as_str()))
+            .insert_header(("BudgetAccessToken", budget_token.as_str()))
+            .insert_header(("Content-Type", "application/protobuf"))
+            .set_payload(blob_update.encode_to_vec())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+        let resp_body = to_bytes(resp.into_body()).await.unwrap();
+        let error_message = ServerErrorResponse::decode(resp_body).unwrap();
+
+        assert_eq!(error_message.err_type, ErrorType::OutOfDate as i32);
+
+        let blob_update = EncryptedBlobUpdate {
+            expected_previous_version_nonce: new_budget.version_nonce,
+            ..blob_update
+        };
+
+        let req = TestRequest::put()
+            .uri("/api/budget")
+            .insert_header(("AccessToken", access_token.as_str()))
+            .insert_header(("BudgetAccessToken", budget_token.as_str()))
+            .insert_header(("Content-Type", "application/protobuf"))
+            .set_payload(blob_update.encode_to_vec())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let budget_token_list = BudgetAccessTokenList {
+            tokens: vec![budget_token],
+        };
+
+        let req = TestRequest::get()
+            .uri("/api/budget")
+            .insert_header(("AccessToken", access_token.as_str()))
+            .insert_header(("Content-Type", "application/protobuf"))
+            .set_payload(budget_token_list.encode_to_vec())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let resp_body = to_bytes(resp.into_body()).await.unwrap();
+        let budget_message = BudgetList::decode(resp_body).unwrap().budgets[0].clone();
+
+        assert_eq!(Uuid::try_from(budget_message.id).unwrap(), budget_id);
+        assert_eq!(budget_message.encrypted_blob, blob_update.encrypted_blob);
+        assert_eq!(budget_message.version_nonce, blob_update.version_nonce);
+    }
 }
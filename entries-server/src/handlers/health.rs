@@ -1,36 +1,309 @@
 use actix_web::{web, HttpRequest, HttpResponse, Responder};
-use entries_common::db::DbThreadPool;
-use serde_json::json;
+use diesel::r2d2::{ManageConnection, Pool};
+use entries_common::db::health::HealthDao;
+use entries_common::db::job_registry::JobRegistryDao;
+#[cfg(feature = "sqlite")]
+use entries_common::db::sqlite::SqliteDbPool;
+use entries_common::db::{self, AsyncDbPools, DbThreadPool};
+use entries_common::email::EmailSender;
+use serde_json::{json, Map, Value};
+use std::future::Future;
+use std::time::{Instant, SystemTime};
 
 use crate::env;
+use crate::handlers::block_task;
+use crate::metrics;
+use crate::shutdown;
 
 pub async fn heartbeat() -> impl Responder {
     HttpResponse::Ok()
 }
 
-pub async fn health(db_thread_pool: web::Data<DbThreadPool>, req: HttpRequest) -> impl Responder {
-    let Some(key) = req.headers().get("Key") else {
+/// Reports whether the process is up. Unlike readiness, this doesn't depend on anything
+/// outside the process, so a failing dependency won't cause the server to be restarted.
+pub async fn liveness() -> impl Responder {
+    HttpResponse::Ok().json(json!({ "status": "ok" }))
+}
+
+/// Reports whether the server can handle requests, along with the status and latency of each
+/// dependency check. Responds with 503 if the database, the migration version, or the SMTP
+/// relay checks fail. Stale jobs are reported but don't make the server unready because the
+/// job scheduler runs separately. Once shutdown has begun, responds with 503 without running
+/// any checks.
+pub async fn readiness(
+    health_dao: web::Data<dyn HealthDao>,
+    job_registry_dao: web::Data<dyn JobRegistryDao>,
+    smtp_thread_pool: web::Data<EmailSender>,
+    req: HttpRequest,
+) -> impl Responder {
+    if !has_valid_key(&req) {
         return HttpResponse::Unauthorized().finish();
-    };
+    }
+
+    if shutdown::is_shutting_down() {
+        return HttpResponse::ServiceUnavailable().json(json!({ "status": "shutting_down" }));
+    }
 
-    let correct_key = &env::CONF.health_endpoint_key;
+    let (database, migrations, smtp, jobs) = futures::join!(
+        run_check(check_database(&health_dao)),
+        run_check(check_migrations(&health_dao)),
+        run_check(check_smtp(&smtp_thread_pool)),
+        run_check(check_jobs(&job_registry_dao)),
+    );
+
+    let is_ready = [&database, &migrations, &smtp]
+        .iter()
+        .all(|c| matches!(c.status, CheckStatus::Ok | CheckStatus::Skipped));
+
+    let resp_body = json!({
+        "status": if is_ready { "ok" } else { "fail" },
+        "checks": {
+            "database": database.to_json(),
+            "migrations": migrations.to_json(),
+            "smtp": smtp.to_json(),
+            "jobs": jobs.to_json(),
+        }
+    });
+
+    if is_ready {
+        HttpResponse::Ok().json(resp_body)
+    } else {
+        HttpResponse::ServiceUnavailable().json(resp_body)
+    }
+}
 
-    if !keys_equal(key.as_bytes(), correct_key.as_bytes()) {
+/// Reports the state of the database connection pools and, if read-only queries are sent to a
+/// replica, how far the replica lags behind the primary.
+pub async fn health(
+    db_thread_pool: Option<web::Data<DbThreadPool>>,
+    db_pools: Option<web::Data<AsyncDbPools>>,
+    #[cfg(feature = "sqlite")] sqlite_db_pool: Option<web::Data<SqliteDbPool>>,
+    req: HttpRequest,
+) -> impl Responder {
+    if !has_valid_key(&req) {
         return HttpResponse::Unauthorized().finish();
     }
 
-    let thread_pool_state = db_thread_pool.state();
-    let resp_body = json!({
-        "db_thread_pool_state": {
-            "connections": thread_pool_state.connections,
-            "idle_connections": thread_pool_state.idle_connections,
-            "max_connections": db_thread_pool.max_size()
+    let db_replica = match db_pools.as_ref().and_then(|p| p.replica()) {
+        Some(replica_pool) => {
+            let health_dao = db::nonblocking::health::Dao::new(replica_pool);
+
+            match health_dao.get_replication_lag().await {
+                Ok(lag) => json!({ "lag_secs": lag.map(|l| l.as_secs_f64()) }),
+                Err(e) => {
+                    log::error!("Failed to get replica lag: {e}");
+                    json!({ "error": e.to_string() })
+                }
+            }
         }
+        None => Value::Null,
+    };
+
+    // A SQLite server has a single pool, which stands in for the Postgres thread pool
+    let thread_pool_state = db_thread_pool.map(|p| pool_state(&p));
+    #[cfg(feature = "sqlite")]
+    let thread_pool_state = thread_pool_state.or_else(|| sqlite_db_pool.map(|p| pool_state(&p)));
+
+    let resp_body = json!({
+        "db_thread_pool_state": thread_pool_state,
+        "db_replica": db_replica,
     });
 
     HttpResponse::Ok().json(resp_body)
 }
 
+pub async fn metrics(req: HttpRequest) -> impl Responder {
+    if !has_valid_key(&req) {
+        return HttpResponse::Unauthorized().finish();
+    }
+
+    match metrics::encode() {
+        Ok(body) => HttpResponse::Ok()
+            .content_type("text/plain; version=0.0.4")
+            .body(body),
+        Err(e) => {
+            log::error!("Failed to encode metrics: {e}");
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+fn pool_state<M: ManageConnection>(pool: &Pool<M>) -> Value {
+    let state = pool.state();
+
+    json!({
+        "connections": state.connections,
+        "idle_connections": state.idle_connections,
+        "max_connections": pool.max_size()
+    })
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum CheckStatus {
+    Ok,
+    Fail,
+    Timeout,
+    Skipped,
+    Stale,
+}
+
+impl CheckStatus {
+    fn as_str(&self) -> &'static str {
+        match self {
+            CheckStatus::Ok => "ok",
+            CheckStatus::Fail => "fail",
+            CheckStatus::Timeout => "timeout",
+            CheckStatus::Skipped => "skipped",
+            CheckStatus::Stale => "stale",
+        }
+    }
+}
+
+struct CheckResult {
+    status: CheckStatus,
+    latency_ms: f64,
+    details: Map<String, Value>,
+}
+
+impl CheckResult {
+    fn to_json(&self) -> Value {
+        let mut check = Map::new();
+        check.insert(String::from("status"), json!(self.status.as_str()));
+        check.insert(String::from("latency_ms"), json!(self.latency_ms));
+        check.extend(self.details.clone());
+
+        Value::Object(check)
+    }
+}
+
+async fn run_check<F>(check: F) -> CheckResult
+where
+    F: Future<Output = (CheckStatus, Map<String, Value>)>,
+{
+    let start = Instant::now();
+
+    let (status, details) =
+        match tokio::time::timeout(env::CONF.readiness_check_timeout, check).await {
+            Ok(r) => r,
+            Err(_) => (CheckStatus::Timeout, Map::new()),
+        };
+
+    CheckResult {
+        status,
+        latency_ms: start.elapsed().as_secs_f64() * 1000.0,
+        details,
+    }
+}
+
+fn failed_check(error: impl ToString) -> (CheckStatus, Map<String, Value>) {
+    let mut details = Map::new();
+    details.insert(String::from("error"), json!(error.to_string()));
+
+    (CheckStatus::Fail, details)
+}
+
+async fn check_database(
+    health_dao: &web::Data<dyn HealthDao>,
+) -> (CheckStatus, Map<String, Value>) {
+    let health_dao = web::Data::clone(health_dao);
+
+    match block_task(move || health_dao.check_connection()).await {
+        Ok(Ok(())) => (CheckStatus::Ok, Map::new()),
+        Ok(Err(e)) => failed_check(e),
+        Err(e) => failed_check(e),
+    }
+}
+
+async fn check_migrations(
+    health_dao: &web::Data<dyn HealthDao>,
+) -> (CheckStatus, Map<String, Value>) {
+    let health_dao = web::Data::clone(health_dao);
+
+    let applied_version =
+        match block_task(move || health_dao.get_latest_applied_migration_version()).await {
+            Ok(Ok(v)) => v,
+            Ok(Err(e)) => return failed_check(e),
+            Err(e) => return failed_check(e),
+        };
+
+    let expected_version = db::migrations::expected_version();
+
+    let status = if applied_version == expected_version {
+        CheckStatus::Ok
+    } else {
+        CheckStatus::Fail
+    };
+
+    let mut details = Map::new();
+    details.insert(String::from("applied_version"), json!(applied_version));
+    details.insert(String::from("expected_version"), json!(expected_version));
+
+    (status, details)
+}
+
+async fn check_smtp(smtp_thread_pool: &EmailSender) -> (CheckStatus, Map<String, Value>) {
+    if !env::CONF.email_enabled {
+        return (CheckStatus::Skipped, Map::new());
+    }
+
+    match smtp_thread_pool.test_connection().await {
+        Ok(true) => (CheckStatus::Ok, Map::new()),
+        Ok(false) => failed_check("SMTP relay rejected the connection"),
+        Err(e) => failed_check(e),
+    }
+}
+
+async fn check_jobs(
+    job_registry_dao: &web::Data<dyn JobRegistryDao>,
+) -> (CheckStatus, Map<String, Value>) {
+    let job_registry_dao = web::Data::clone(job_registry_dao);
+
+    let last_runs =
+        match block_task(move || job_registry_dao.get_all_job_last_run_timestamps()).await {
+            Ok(Ok(r)) => r,
+            Ok(Err(e)) => return failed_check(e),
+            Err(e) => return failed_check(e),
+        };
+
+    let now = SystemTime::now();
+    let mut status = CheckStatus::Ok;
+    let mut jobs = Map::new();
+
+    for (job_name, last_run) in last_runs {
+        let age = now.duration_since(last_run).unwrap_or_default();
+        let is_stale = age > env::CONF.job_staleness_threshold;
+
+        if is_stale {
+            status = CheckStatus::Stale;
+        }
+
+        jobs.insert(
+            job_name,
+            json!({
+                "last_run_timestamp": last_run
+                    .duration_since(SystemTime::UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_secs(),
+                "age_secs": age.as_secs(),
+                "stale": is_stale,
+            }),
+        );
+    }
+
+    let mut details = Map::new();
+    details.insert(String::from("jobs"), Value::Object(jobs));
+
+    (status, details)
+}
+
+fn has_valid_key(req: &HttpRequest) -> bool {
+    let Some(key) = req.headers().get("Key") else {
+        return false;
+    };
+
+    keys_equal(key.as_bytes(), env::CONF.health_endpoint_key.as_bytes())
+}
+
 fn keys_equal(key1: &[u8], key2: &[u8]) -> bool {
     if key1.len() != key2.len() {
         return false;
@@ -44,3 +317,144 @@ fn keys_equal(key1: &[u8], key2: &[u8]) -> bool {
 
     keys_not_equal == 0
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::middleware::proto_or_json::ProtoOrJsonConfig;
+    use actix_web::body::to_bytes;
+    use actix_web::http::StatusCode;
+    use actix_web::test::{self, TestRequest};
+    use actix_web::web::Data;
+    use actix_web::App;
+    use diesel::{QueryDsl, RunQueryDsl};
+    use entries_common::db::job_registry::JobRegistryDao;
+    use std::time::Duration;
+    use uuid::Uuid;
+
+    use crate::services::api::RouteLimiters;
+
+    #[actix_web::test]
+    async fn test_health() {
+        let app = test::init_service(
+            App::new()
+                .app_data(Data::new(env::testing::DB_THREAD_POOL.clone()))
+                .app_data(Data::new(env::testing::async_db_pools()))
+                .app_data(Data::new(env::testing::SMTP_THREAD_POOL.clone()))
+                .app_data(ProtoOrJsonConfig::default())
+                .configure(|cfg| crate::services::api::configure(cfg, RouteLimiters::default())),
+        )
+        .await;
+
+        let req = TestRequest::get().uri("/api/health").to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+        let req = TestRequest::get()
+            .uri("/api/health")
+            .insert_header(("Key", env::CONF.health_endpoint_key.as_str()))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let body: Value =
+            serde_json::from_slice(&to_bytes(resp.into_body()).await.unwrap()).unwrap();
+        assert!(body["db_thread_pool_state"]["max_connections"].is_u64());
+        assert!(body["db_replica"].is_null());
+
+        // The test database isn't a replica, so it has no lag to report
+        let db_pool = env::testing::async_db_pools().primary().clone();
+        let app = test::init_service(
+            App::new()
+                .app_data(Data::new(env::testing::DB_THREAD_POOL.clone()))
+                .app_data(Data::new(AsyncDbPools::new(db_pool.clone(), Some(db_pool))))
+                .app_data(Data::new(env::testing::SMTP_THREAD_POOL.clone()))
+                .app_data(ProtoOrJsonConfig::default())
+                .configure(|cfg| crate::services::api::configure(cfg, RouteLimiters::default())),
+        )
+        .await;
+
+        let req = TestRequest::get()
+            .uri("/api/health")
+            .insert_header(("Key", env::CONF.health_endpoint_key.as_str()))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let body: Value =
+            serde_json::from_slice(&to_bytes(resp.into_body()).await.unwrap()).unwrap();
+        assert!(body["db_replica"].is_object());
+        assert!(body["db_replica"]["lag_secs"].is_null());
+        assert!(body["db_replica"].get("error").is_none());
+    }
+
+    #[actix_web::test]
+    async fn test_readiness() {
+        let app = test::init_service(
+            App::new()
+                .app_data(Data::new(env::testing::DB_THREAD_POOL.clone()))
+                .app_data(Data::new(env::testing::async_db_pools()))
+                .app_data(Data::new(env::testing::SMTP_THREAD_POOL.clone()))
+                .app_data(ProtoOrJsonConfig::default())
+                .configure(env::testing::configure_daos)
+                .configure(|cfg| crate::services::api::configure(cfg, RouteLimiters::default())),
+        )
+        .await;
+
+        let req = TestRequest::get().uri("/api/health/live").to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let req = TestRequest::get().uri("/api/health/ready").to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+        let req = TestRequest::get()
+            .uri("/api/health/ready")
+            .insert_header(("Key", "incorrect"))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+        let stale_job_name = format!("test_stale_job_{}", Uuid::now_v7());
+        let job_registry_dao = db::job_registry::Dao::new(&env::testing::DB_THREAD_POOL);
+        job_registry_dao
+            .set_job_last_run_timestamp(
+                &stale_job_name,
+                SystemTime::now() - env::CONF.job_staleness_threshold - Duration::from_secs(60),
+            )
+            .unwrap();
+
+        let req = TestRequest::get()
+            .uri("/api/health/ready")
+            .insert_header(("Key", env::CONF.health_endpoint_key.as_str()))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let body: Value =
+            serde_json::from_slice(&to_bytes(resp.into_body()).await.unwrap()).unwrap();
+        let checks = &body["checks"];
+
+        assert_eq!(body["status"], "ok");
+        assert_eq!(checks["database"]["status"], "ok");
+        assert!(checks["database"]["latency_ms"].is_f64());
+        assert_eq!(checks["migrations"]["status"], "ok");
+        assert_eq!(
+            checks["migrations"]["applied_version"],
+            checks["migrations"]["expected_version"]
+        );
+
+        // Emails are always disabled in tests
+        assert_eq!(checks["smtp"]["status"], "skipped");
+
+        // A stale job shouldn't make the server unready
+        assert_eq!(checks["jobs"]["status"], "stale");
+        assert_eq!(checks["jobs"]["jobs"][&stale_job_name]["stale"], true);
+
+        diesel::delete(entries_common::schema::job_registry::table.find(&stale_job_name))
+            .execute(&mut env::testing::DB_THREAD_POOL.get().unwrap())
+            .unwrap();
+    }
+}
@@ -3,9 +3,72 @@ pub mod budget;
 pub mod health;
 pub mod user;
 
+use actix_web::error::BlockingError;
+use actix_web::web;
+
+use crate::metrics;
+use crate::middleware::request_id;
+use crate::shutdown;
+
+/// Equivalent to `web::block`, but tracks how many closures are waiting for a thread in the
+/// blocking thread pool, carries the current request ID and tracing span over to the closure,
+/// and keeps shutdown from completing until the closure has finished.
+pub async fn block_task<F, R>(f: F) -> Result<R, BlockingError>
+where
+    F: FnOnce() -> R + Send + 'static,
+    R: Send + 'static,
+{
+    let request_id = request_id::current();
+    let span = tracing::Span::current();
+    let task_guard = shutdown::track_task();
+    let queued_guard = QueuedGuard::new();
+
+    web::block(move || {
+        let _task_guard = task_guard;
+        drop(queued_guard);
+        span.in_scope(|| request_id::scope(request_id, f))
+    })
+    .await
+}
+
+/// Counts a closure in the blocking queue depth until the guard is dropped. The guard moves into
+/// the closure, so the count also comes back down if the thread pool rejects the closure or the
+/// request is cancelled before the closure runs.
+struct QueuedGuard(());
+
+impl QueuedGuard {
+    fn new() -> Self {
+        metrics::BLOCKING_QUEUE_DEPTH.inc();
+        QueuedGuard(())
+    }
+}
+
+impl Drop for QueuedGuard {
+    fn drop(&mut self) {
+        metrics::BLOCKING_QUEUE_DEPTH.dec();
+    }
+}
+
+/// Equivalent to `rayon::spawn`, but carries the current request ID and tracing span over to
+/// the task and keeps shutdown from completing until the task has finished.
+pub fn rayon_spawn<F>(f: F)
+where
+    F: FnOnce() + Send + 'static,
+{
+    let request_id = request_id::current();
+    let span = tracing::Span::current();
+    let task_guard = shutdown::track_task();
+
+    rayon::spawn(move || {
+        let _task_guard = task_guard;
+        span.in_scope(|| request_id::scope(request_id, f))
+    });
+}
+
 pub mod verification {
     use actix_web::web;
-    use entries_common::db::{self, DaoError, DbThreadPool};
+    use entries_common::db::auth::AuthDao;
+    use entries_common::db::DaoError;
     use entries_common::email::{templates::OtpMessage, EmailMessage, EmailSender};
     use entries_common::otp::Otp;
     use std::str::FromStr;
@@ -14,12 +77,15 @@ pub mod verification {
     use tokio::sync::oneshot;
     use zeroize::Zeroizing;
 
+    use super::block_task;
     use super::error::{DoesNotExistType, HttpErrorResponse};
     use crate::env;
+    use crate::metrics;
 
+    #[tracing::instrument(level = "debug", skip_all)]
     pub async fn generate_and_email_otp(
         user_email: &str,
-        db_thread_pool: &DbThreadPool,
+        auth_dao: &web::Data<dyn AuthDao>,
         smtp_thread_pool: &EmailSender,
     ) -> Result<(), HttpErrorResponse> {
         let otp_expiration = SystemTime::now() + env::CONF.otp_lifetime;
@@ -29,8 +95,8 @@ pub mod verification {
         let otp = Arc::new(Otp::generate(8));
         let otp_ref = Arc::clone(&otp);
 
-        let auth_dao = db::auth::Dao::new(db_thread_pool);
-        match web::block(move || auth_dao.save_otp(&otp_ref, &user_email_copy, otp_expiration))
+        let auth_dao = web::Data::clone(auth_dao);
+        match block_task(move || auth_dao.save_otp(&otp_ref, &user_email_copy, otp_expiration))
             .await?
         {
             Ok(a) => a,
@@ -70,10 +136,11 @@ pub mod verification {
         Ok(())
     }
 
+    #[tracing::instrument(level = "debug", skip_all)]
     pub async fn verify_otp(
         otp: &str,
         user_email: &str,
-        db_thread_pool: &DbThreadPool,
+        auth_dao: &web::Data<dyn AuthDao>,
     ) -> Result<(), HttpErrorResponse> {
         const WRONG_OR_EXPIRED_OTP_MSG: &str = "OTP was incorrect or has expired";
 
@@ -88,9 +155,9 @@ pub mod verification {
         let user_email_copy = Arc::new(String::from(user_email));
         let user_email_ref = Arc::clone(&user_email_copy);
 
-        let auth_dao = db::auth::Dao::new(db_thread_pool);
+        let auth_dao_ref = web::Data::clone(auth_dao);
         let exists_unexpired_otp =
-            match web::block(move || auth_dao.check_unexpired_otp(&otp_copy, &user_email_copy))
+            match block_task(move || auth_dao_ref.check_unexpired_otp(&otp_copy, &user_email_copy))
                 .await?
             {
                 Ok(e) => e,
@@ -103,8 +170,8 @@ pub mod verification {
             };
 
         if exists_unexpired_otp {
-            let auth_dao = db::auth::Dao::new(db_thread_pool);
-            match web::block(move || auth_dao.delete_otp(&otp_ref, &user_email_ref)).await? {
+            let auth_dao = web::Data::clone(auth_dao);
+            match block_task(move || auth_dao.delete_otp(&otp_ref, &user_email_ref)).await? {
                 Ok(_) => (),
                 Err(e) => {
                     log::error!("{e}");
@@ -119,10 +186,11 @@ pub mod verification {
         Ok(())
     }
 
+    #[tracing::instrument(level = "debug", skip_all)]
     pub async fn verify_auth_string(
         auth_string: &[u8],
         user_email: &str,
-        db_thread_pool: &DbThreadPool,
+        auth_dao: &web::Data<dyn AuthDao>,
     ) -> Result<(), HttpErrorResponse> {
         if user_email.len() > 255 || auth_string.len() > 1024 {
             return Err(HttpErrorResponse::IncorrectCredential(String::from(
@@ -133,8 +201,8 @@ pub mod verification {
         let user_email_copy = String::from(user_email);
         let auth_string = Zeroizing::new(Vec::from(auth_string));
 
-        let auth_dao = db::auth::Dao::new(db_thread_pool);
-        let hash = match web::block(move || {
+        let auth_dao = web::Data::clone(auth_dao);
+        let hash = match block_task(move || {
             auth_dao.get_user_auth_string_hash_and_status(&user_email_copy)
         })
         .await?
@@ -150,7 +218,12 @@ pub mod verification {
 
         let (sender, receiver) = oneshot::channel();
 
-        rayon::spawn(move || {
+        super::rayon_spawn(move || {
+            let _span = tracing::debug_span!("argon2_verify").entered();
+            let _timer = metrics::ARGON2_DURATION
+                .with_label_values(&["verify"])
+                .start_timer();
+
             let hash = match argon2_kdf::Hash::from_str(&hash.auth_string_hash) {
                 Ok(h) => h,
                 Err(e) => {
@@ -186,15 +259,128 @@ pub mod verification {
     }
 }
 
+pub mod etag {
+    use entries_common::messages::{BudgetList, Timestamp};
+
+    use actix_web::http::header::{ETag, EntityTag, IfNoneMatch};
+    use actix_web::{HttpMessage, HttpRequest, HttpResponse};
+    use base64::engine::general_purpose::URL_SAFE_NO_PAD as b64_urlsafe_nopad;
+    use base64::Engine;
+    use sha2::{Digest, Sha256};
+
+    use crate::middleware::proto_or_json::current_format;
+
+    /// Builds a strong ETag from the ID and version of each object in a response. Every update
+    /// to an object replaces its `version_nonce`, so the tag changes whenever the response would.
+    pub struct ETagBuilder {
+        hasher: Sha256,
+    }
+
+    impl ETagBuilder {
+        pub fn new() -> Self {
+            let mut hasher = Sha256::new();
+
+            // The same data encoded differently is a different representation
+            hasher.update(current_format().content_type());
+
+            Self { hasher }
+        }
+
+        pub fn add(
+            &mut self,
+            id: &[u8],
+            version_nonce: i64,
+            modified_timestamp: Option<&Timestamp>,
+        ) -> &mut Self {
+            self.hasher.update((id.len() as u64).to_be_bytes());
+            self.hasher.update(id);
+            self.hasher.update(version_nonce.to_be_bytes());
+
+            if let Some(timestamp) = modified_timestamp {
+                self.hasher.update(timestamp.secs.to_be_bytes());
+                self.hasher.update(timestamp.nanos.to_be_bytes());
+            }
+
+            self
+        }
+
+        pub fn finish(self) -> EntityTag {
+            let hash = self.hasher.finalize();
+            EntityTag::new_strong(b64_urlsafe_nopad.encode(&hash[..16]))
+        }
+    }
+
+    impl Default for ETagBuilder {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    pub fn for_budgets(budget_list: &BudgetList) -> EntityTag {
+        let mut etag = ETagBuilder::new();
+
+        for budget in budget_list.budgets.iter() {
+            etag.add(
+                &budget.id.value,
+                budget.version_nonce,
+                Some(&budget.modified_timestamp),
+            );
+
+            // Entries and categories are hashed along with their budget so a budget's tag
+            // changes when one of them is added, edited, or removed
+            for category in budget.categories.iter() {
+                etag.add(
+                    &category.id.value,
+                    category.version_nonce,
+                    Some(&category.modified_timestamp),
+                );
+            }
+
+            for entry in budget.entries.iter() {
+                etag.add(
+                    &entry.id.value,
+                    entry.version_nonce,
+                    Some(&entry.modified_timestamp),
+                );
+            }
+        }
+
+        etag.finish()
+    }
+
+    /// Whether the client's `If-None-Match` header matches `etag`, meaning the client already
+    /// has the current version of the response
+    pub fn matches(req: &HttpRequest, etag: &EntityTag) -> bool {
+        match req.get_header::<IfNoneMatch>() {
+            Some(IfNoneMatch::Any) => true,
+            Some(IfNoneMatch::Items(items)) => items.iter().any(|item| item.weak_eq(etag)),
+            None => false,
+        }
+    }
+
+    pub fn not_modified(etag: EntityTag) -> HttpResponse {
+        HttpResponse::NotModified()
+            .insert_header(ETag(etag))
+            .finish()
+    }
+}
+
 pub mod error {
-    use actix_protobuf::ProtoBufResponseBuilder;
-    use entries_common::messages::{ErrorType, MessageError, ServerErrorResponse};
+    use entries_common::messages::{
+        ErrorType, MessageError, ServerErrorResponse, Uuid as UuidMessage,
+    };
     use entries_common::token::TokenError;
 
-    use actix_web::http::{header, StatusCode};
+    use actix_web::http::header::RETRY_AFTER;
+    use actix_web::http::StatusCode;
     use actix_web::{HttpResponse, HttpResponseBuilder};
     use std::fmt;
+    use std::time::Duration;
     use tokio::sync::oneshot;
+    use uuid::Uuid;
+
+    use crate::middleware::proto_or_json::ProtoOrJsonResponseBuilder;
+    use crate::middleware::request_id;
 
     #[derive(Debug)]
     pub enum DoesNotExistType {
@@ -206,15 +392,39 @@ pub mod error {
         Invitation,
     }
 
+    /// The limit that an input which was too large exceeded
+    #[derive(Debug)]
+    pub struct SizeLimit {
+        /// The name of the message field, or `None` if the whole request body was too large
+        pub field: Option<&'static str>,
+        pub max_size: usize,
+    }
+
+    impl SizeLimit {
+        pub fn field(name: &'static str, max_size: usize) -> Self {
+            Self {
+                field: Some(name),
+                max_size,
+            }
+        }
+
+        pub fn body(max_size: usize) -> Self {
+            Self {
+                field: None,
+                max_size,
+            }
+        }
+    }
+
     #[derive(Debug)]
     pub enum HttpErrorResponse {
         // 400
         IncorrectlyFormed(String),
         InvalidMessage(MessageError),
-        OutOfDate(String),
+        OutOfDate(String, Option<i64>), // Current version nonce, if known
         InvalidState(String),
         MissingHeader(String),
-        ConflictWithExisting(String),
+        ConflictWithExisting(String, Option<Uuid>), // ID that is already taken, if known
 
         // 401
         IncorrectCredential(String),
@@ -235,11 +445,17 @@ pub mod error {
         ForeignKeyDoesNotExist(String),
 
         // 413
-        InputTooLarge(String),
+        InputTooLarge(String, SizeLimit),
 
         // 418
         TooManyRequested(String),
 
+        // 429
+        TooManyRequests(String, Duration), // How long until the client may try again
+
+        // 426
+        ClientUpdateRequired(String),
+
         // 500
         InternalError(String),
     }
@@ -261,132 +477,164 @@ pub mod error {
 
     impl From<&HttpErrorResponse> for ServerErrorResponse {
         fn from(resp: &HttpErrorResponse) -> Self {
-            match resp {
+            let mut server_error = ServerErrorResponse::default();
+
+            let (err_type, err_message) = match resp {
                 // 400
-                HttpErrorResponse::IncorrectlyFormed(msg) => ServerErrorResponse {
-                    err_type: ErrorType::IncorrectlyFormed.into(),
-                    err_message: format!("Incorrectly formed request: {msg}"),
-                },
-                HttpErrorResponse::InvalidMessage(e) => ServerErrorResponse {
-                    err_type: ErrorType::InvalidMessage.into(),
-                    err_message: format!("Invalid message: {e}"),
-                },
-                HttpErrorResponse::OutOfDate(msg) => ServerErrorResponse {
-                    err_type: ErrorType::OutOfDate.into(),
-                    err_message: format!("Out of date: {msg}"),
-                },
-                HttpErrorResponse::InvalidState(msg) => ServerErrorResponse {
-                    err_type: ErrorType::InvalidState.into(),
-                    err_message: format!("Invalid state: {msg}"),
-                },
-                HttpErrorResponse::MissingHeader(msg) => ServerErrorResponse {
-                    err_type: ErrorType::MissingHeader.into(),
-                    err_message: format!("Missing header: {msg}"),
-                },
-                HttpErrorResponse::ConflictWithExisting(msg) => ServerErrorResponse {
-                    err_type: ErrorType::ConflictWithExisting.into(),
-                    err_message: format!("Conflict with existing data: {msg}"),
-                },
+                HttpErrorResponse::IncorrectlyFormed(msg) => (
+                    ErrorType::IncorrectlyFormed,
+                    format!("Incorrectly formed request: {msg}"),
+                ),
+                HttpErrorResponse::InvalidMessage(e) => {
+                    (ErrorType::InvalidMessage, format!("Invalid message: {e}"))
+                }
+                HttpErrorResponse::OutOfDate(msg, current_version_nonce) => {
+                    server_error.current_version_nonce = *current_version_nonce;
+                    (ErrorType::OutOfDate, format!("Out of date: {msg}"))
+                }
+                HttpErrorResponse::InvalidState(msg) => {
+                    (ErrorType::InvalidState, format!("Invalid state: {msg}"))
+                }
+                HttpErrorResponse::MissingHeader(msg) => {
+                    (ErrorType::MissingHeader, format!("Missing header: {msg}"))
+                }
+                HttpErrorResponse::ConflictWithExisting(msg, conflicting_id) => {
+                    server_error.conflicting_id = conflicting_id.as_ref().map(UuidMessage::from);
+                    (
+                        ErrorType::ConflictWithExisting,
+                        format!("Conflict with existing data: {msg}"),
+                    )
+                }
 
                 // 401
-                HttpErrorResponse::IncorrectCredential(msg) => ServerErrorResponse {
-                    err_type: ErrorType::IncorrectCredential.into(),
-                    err_message: format!("Incorrect credential: {msg}"),
-                },
-                HttpErrorResponse::BadToken(msg) => ServerErrorResponse {
-                    err_type: ErrorType::IncorrectCredential.into(),
-                    err_message: format!("Bad token: {msg}"),
-                },
-                HttpErrorResponse::TokenExpired(msg) => ServerErrorResponse {
-                    err_type: ErrorType::TokenExpired.into(),
-                    err_message: format!("Token expired: {msg}"),
-                },
-                HttpErrorResponse::TokenMissing(msg) => ServerErrorResponse {
-                    err_type: ErrorType::TokenMissing.into(),
-                    err_message: format!("Token missing: {msg}"),
-                },
-                HttpErrorResponse::WrongTokenType(msg) => ServerErrorResponse {
-                    err_type: ErrorType::WrongTokenType.into(),
-                    err_message: format!("Wrong token type: {msg}"),
-                },
+                HttpErrorResponse::IncorrectCredential(msg) => (
+                    ErrorType::IncorrectCredential,
+                    format!("Incorrect credential: {msg}"),
+                ),
+                HttpErrorResponse::BadToken(msg) => {
+                    (ErrorType::IncorrectCredential, format!("Bad token: {msg}"))
+                }
+                HttpErrorResponse::TokenExpired(msg) => {
+                    (ErrorType::TokenExpired, format!("Token expired: {msg}"))
+                }
+                HttpErrorResponse::TokenMissing(msg) => {
+                    (ErrorType::TokenMissing, format!("Token missing: {msg}"))
+                }
+                HttpErrorResponse::WrongTokenType(msg) => (
+                    ErrorType::WrongTokenType,
+                    format!("Wrong token type: {msg}"),
+                ),
 
                 // 403
-                HttpErrorResponse::UserDisallowed(msg) => ServerErrorResponse {
-                    err_type: ErrorType::UserDisallowed.into(),
-                    err_message: format!("User disallowed: {msg}"),
-                },
-                HttpErrorResponse::PendingAction(msg) => ServerErrorResponse {
-                    err_type: ErrorType::PendingAction.into(),
-                    err_message: format!("Pending user action: {msg}"),
-                },
-                HttpErrorResponse::IncorrectNonce(msg) => ServerErrorResponse {
-                    err_type: ErrorType::IncorrectNonce.into(),
-                    err_message: format!("Incorrect nonce: {msg}"),
-                },
-                HttpErrorResponse::TooManyAttempts(msg) => ServerErrorResponse {
-                    err_type: ErrorType::TooManyAttempts.into(),
-                    err_message: format!("Too many attempts: {msg}"),
-                },
-                HttpErrorResponse::ReadOnlyAccess(msg) => ServerErrorResponse {
-                    err_type: ErrorType::ReadOnlyAccess.into(),
-                    err_message: format!("Read-only access: {msg}"),
-                },
+                HttpErrorResponse::UserDisallowed(msg) => {
+                    (ErrorType::UserDisallowed, format!("User disallowed: {msg}"))
+                }
+                HttpErrorResponse::PendingAction(msg) => (
+                    ErrorType::PendingAction,
+                    format!("Pending user action: {msg}"),
+                ),
+                HttpErrorResponse::IncorrectNonce(msg) => {
+                    (ErrorType::IncorrectNonce, format!("Incorrect nonce: {msg}"))
+                }
+                HttpErrorResponse::TooManyAttempts(msg) => (
+                    ErrorType::TooManyAttempts,
+                    format!("Too many attempts: {msg}"),
+                ),
+                HttpErrorResponse::ReadOnlyAccess(msg) => (
+                    ErrorType::ReadOnlyAccess,
+                    format!("Read-only access: {msg}"),
+                ),
 
                 // 404
-                HttpErrorResponse::DoesNotExist(msg, dne_type) => ServerErrorResponse {
-                    err_type: match dne_type {
+                HttpErrorResponse::DoesNotExist(msg, dne_type) => (
+                    match dne_type {
                         DoesNotExistType::User => ErrorType::UserDoesNotExist,
                         DoesNotExistType::Key => ErrorType::KeyDoesNotExist,
                         DoesNotExistType::Budget => ErrorType::BudgetDoesNotExist,
                         DoesNotExistType::Entry => ErrorType::EntryDoesNotExist,
                         DoesNotExistType::Category => ErrorType::CategoryDoesNotExist,
                         DoesNotExistType::Invitation => ErrorType::InvitationDoesNotExist,
-                    }
-                    .into(),
-                    err_message: format!("Does not exist: {msg}"),
-                },
-                HttpErrorResponse::ForeignKeyDoesNotExist(msg) => ServerErrorResponse {
-                    err_type: ErrorType::ForeignKeyDoesNotExist.into(),
-                    err_message: format!("Foreign key does not exist: {msg}"),
-                },
+                    },
+                    format!("Does not exist: {msg}"),
+                ),
+                HttpErrorResponse::ForeignKeyDoesNotExist(msg) => (
+                    ErrorType::ForeignKeyDoesNotExist,
+                    format!("Foreign key does not exist: {msg}"),
+                ),
 
                 // 413
-                HttpErrorResponse::InputTooLarge(msg) => ServerErrorResponse {
-                    err_type: ErrorType::InputTooLarge.into(),
-                    err_message: format!("Input is too long: {msg}"),
-                },
+                HttpErrorResponse::InputTooLarge(msg, limit) => {
+                    server_error.field = limit.field.map(String::from);
+                    server_error.max_size = Some(limit.max_size as u64);
+                    (
+                        ErrorType::InputTooLarge,
+                        format!("Input is too long: {msg}"),
+                    )
+                }
 
                 // 418
-                HttpErrorResponse::TooManyRequested(msg) => ServerErrorResponse {
-                    err_type: ErrorType::TooManyRequested.into(),
-                    err_message: format!("Too many requested: {msg}"),
-                },
+                HttpErrorResponse::TooManyRequested(msg) => (
+                    ErrorType::TooManyRequested,
+                    format!("Too many requested: {msg}"),
+                ),
+
+                // 429
+                HttpErrorResponse::TooManyRequests(msg, retry_after) => {
+                    server_error.retry_after_secs = Some(retry_after_secs(*retry_after));
+                    (
+                        ErrorType::TooManyAttempts,
+                        format!("Too many requests: {msg}"),
+                    )
+                }
+
+                // 426
+                HttpErrorResponse::ClientUpdateRequired(msg) => (
+                    ErrorType::ClientUpdateRequired,
+                    format!("Client update required: {msg}"),
+                ),
 
                 // 500
-                HttpErrorResponse::InternalError(msg) => ServerErrorResponse {
-                    err_type: ErrorType::InternalError.into(),
-                    err_message: format!("Internal error: {msg}"),
-                },
-            }
+                HttpErrorResponse::InternalError(msg) => {
+                    (ErrorType::InternalError, format!("Internal error: {msg}"))
+                }
+            };
+
+            server_error.err_type = err_type.into();
+            server_error.err_message = err_message;
+
+            server_error
         }
     }
 
+    /// Rounded up so a client that waits this long won't be rejected again
+    fn retry_after_secs(retry_after: Duration) -> u64 {
+        retry_after.as_secs() + u64::from(retry_after.subsec_nanos() > 0)
+    }
+
     impl actix_web::error::ResponseError for HttpErrorResponse {
         fn error_response(&self) -> HttpResponse {
-            HttpResponseBuilder::new(self.status_code())
-                .insert_header((header::CONTENT_TYPE, "application/protobuf"))
-                .protobuf::<ServerErrorResponse>(self.into())
-                .expect("HttpErrorResponse failed to serialize to ProtoBuf")
+            let mut server_error: ServerErrorResponse = self.into();
+            server_error.request_id = request_id::current().map(|id| String::from(&*id));
+
+            let mut builder = HttpResponseBuilder::new(self.status_code());
+
+            if let HttpErrorResponse::TooManyRequests(_, retry_after) = self {
+                builder.insert_header((RETRY_AFTER, retry_after_secs(*retry_after)));
+            }
+
+            builder
+                .proto_or_json(server_error)
+                .expect("HttpErrorResponse failed to serialize")
         }
 
         fn status_code(&self) -> StatusCode {
             match *self {
                 HttpErrorResponse::IncorrectlyFormed(_)
                 | HttpErrorResponse::InvalidMessage(_)
-                | HttpErrorResponse::OutOfDate(_)
+                | HttpErrorResponse::OutOfDate(_, _)
                 | HttpErrorResponse::InvalidState(_)
                 | HttpErrorResponse::MissingHeader(_)
-                | HttpErrorResponse::ConflictWithExisting(_) => StatusCode::BAD_REQUEST,
+                | HttpErrorResponse::ConflictWithExisting(_, _) => StatusCode::BAD_REQUEST,
                 HttpErrorResponse::IncorrectCredential(_)
                 | HttpErrorResponse::IncorrectNonce(_)
                 | HttpErrorResponse::BadToken(_)
@@ -399,8 +647,10 @@ pub mod error {
                 | HttpErrorResponse::ReadOnlyAccess(_) => StatusCode::FORBIDDEN,
                 HttpErrorResponse::DoesNotExist(_, _)
                 | HttpErrorResponse::ForeignKeyDoesNotExist(_) => StatusCode::NOT_FOUND,
-                HttpErrorResponse::InputTooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
+                HttpErrorResponse::InputTooLarge(_, _) => StatusCode::PAYLOAD_TOO_LARGE,
                 HttpErrorResponse::TooManyRequested(_) => StatusCode::IM_A_TEAPOT,
+                HttpErrorResponse::TooManyRequests(_, _) => StatusCode::TOO_MANY_REQUESTS,
+                HttpErrorResponse::ClientUpdateRequired(_) => StatusCode::UPGRADE_REQUIRED,
                 HttpErrorResponse::InternalError(_) => StatusCode::INTERNAL_SERVER_ERROR,
             }
         }
@@ -408,7 +658,7 @@ pub mod error {
 
     impl From<actix_web::Error> for HttpErrorResponse {
         fn from(_err: actix_web::Error) -> Self {
-            HttpErrorResponse::InternalError(String::from("Failed to serialize ProtoBuf response"))
+            HttpErrorResponse::InternalError(String::from("Failed to serialize response"))
         }
     }
 
@@ -452,19 +702,18 @@ pub mod error {
 
 #[cfg(test)]
 pub mod test_utils {
-    use entries_common::db;
+    use entries_common::db::{self, auth::AuthDao, user::UserDao};
     use entries_common::messages::{
         BudgetFrame, BudgetIdAndEncryptionKey, BudgetShareInviteList, NewBudget, NewUser,
         PublicKey, UserInvitationToBudget,
     };
     use entries_common::models::budget::Budget;
     use entries_common::models::user::User;
-    use entries_common::schema::budgets::dsl::budgets;
     use entries_common::schema::users as user_fields;
     use entries_common::schema::users::dsl::users;
     use entries_common::token::auth_token::{AuthToken, AuthTokenType, NewAuthTokenClaims};
 
-    use actix_protobuf::ProtoBufConfig;
+    use crate::middleware::proto_or_json::ProtoOrJsonConfig;
     use actix_web::body::to_bytes;
     use actix_web::http::StatusCode;
     use actix_web::test::{self, TestRequest};
@@ -481,9 +730,11 @@ pub mod test_utils {
     use openssl::rsa::{Padding, Rsa};
     use prost::Message;
     use rand::Rng;
+    use std::sync::Arc;
     use std::time::{Duration, SystemTime, UNIX_EPOCH};
     use uuid::Uuid;
 
+    use crate::daos::Daos;
     use crate::env;
     use crate::services::api::RouteLimiters;
 
@@ -520,8 +771,10 @@ pub mod test_utils {
         let app = test::init_service(
             App::new()
                 .app_data(Data::new(env::testing::DB_THREAD_POOL.clone()))
+                .app_data(Data::new(env::testing::async_db_pools()))
+                .configure(env::testing::configure_daos)
                 .app_data(Data::new(env::testing::SMTP_THREAD_POOL.clone()))
-                .app_data(ProtoBufConfig::default())
+                .app_data(ProtoOrJsonConfig::default())
                 .configure(|cfg| crate::services::api::configure(cfg, RouteLimiters::default())),
         )
         .await;
@@ -578,9 +831,11 @@ pub mod test_utils {
         let user_dao = db::user::Dao::new(&env::testing::DB_THREAD_POOL);
         user_dao.verify_user_creation(user.id).unwrap();
 
+        let auth_dao: Arc<dyn AuthDao> =
+            Arc::new(db::auth::Dao::new(&env::testing::DB_THREAD_POOL));
         super::verification::generate_and_email_otp(
             &user.email,
-            &env::testing::DB_THREAD_POOL,
+            &Data::from(auth_dao),
             &env::testing::SMTP_THREAD_POOL,
         )
         .await
@@ -606,6 +861,25 @@ pub mod test_utils {
         )
     }
 
+    /// Signs an access token for a user that doesn't exist, for tests that run against DAOs
+    /// without any users in them
+    pub fn gen_access_token() -> String {
+        let user_number = rand::thread_rng().gen_range::<u128, _>(u128::MIN..u128::MAX);
+        let user_email = format!("test_user{}@test.com", &user_number);
+
+        let access_token_claims = NewAuthTokenClaims {
+            user_id: Uuid::now_v7(),
+            user_email: &user_email,
+            expiration: (SystemTime::now() + env::CONF.access_token_lifetime)
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_secs(),
+            token_type: AuthTokenType::Access,
+        };
+
+        AuthToken::sign_new(access_token_claims, &env::CONF.token_signing_key)
+    }
+
     pub fn gen_new_user_rsa_key(user_id: Uuid) -> Rsa<Private> {
         let keypair = Rsa::generate(512).unwrap();
         let public_key = keypair.public_key_to_der().unwrap();
@@ -619,11 +893,20 @@ pub mod test_utils {
     }
 
     pub async fn create_budget(access_token: &str) -> (Budget, String) {
+        let daos = Daos::postgres(
+            &env::testing::DB_THREAD_POOL,
+            &env::testing::async_db_pools(),
+        );
+
+        create_budget_in(&daos, access_token).await
+    }
+
+    pub async fn create_budget_in(daos: &Daos, access_token: &str) -> (Budget, String) {
         let app = test::init_service(
             App::new()
-                .app_data(Data::new(env::testing::DB_THREAD_POOL.clone()))
+                .configure(|cfg| daos.configure(cfg))
                 .app_data(Data::new(env::testing::SMTP_THREAD_POOL.clone()))
-                .app_data(ProtoBufConfig::default())
+                .app_data(ProtoOrJsonConfig::default())
                 .configure(|cfg| crate::services::api::configure(cfg, RouteLimiters::default())),
         )
         .await;
@@ -650,10 +933,12 @@ pub mod test_utils {
 
         let resp_body = to_bytes(resp.into_body()).await.unwrap();
         let budget_data = BudgetFrame::decode(resp_body).unwrap();
-        let budget = budgets
-            .find(Uuid::try_from(budget_data.id).unwrap())
-            .get_result::<Budget>(&mut env::testing::DB_THREAD_POOL.get().unwrap())
-            .unwrap();
+        let budget = Budget {
+            id: budget_data.id.try_into().unwrap(),
+            encrypted_blob: new_budget.encrypted_blob,
+            version_nonce: new_budget.version_nonce,
+            modified_timestamp: SystemTime::from(&budget_data.modified_timestamp),
+        };
 
         let budget_access_token = gen_budget_token(
             budget.id,
@@ -678,8 +963,10 @@ pub mod test_utils {
         let app = test::init_service(
             App::new()
                 .app_data(Data::new(env::testing::DB_THREAD_POOL.clone()))
+                .app_data(Data::new(env::testing::async_db_pools()))
+                .configure(env::testing::configure_daos)
                 .app_data(Data::new(env::testing::SMTP_THREAD_POOL.clone()))
-                .app_data(ProtoBufConfig::default())
+                .app_data(ProtoOrJsonConfig::default())
                 .configure(|cfg| crate::services::api::configure(cfg, RouteLimiters::default())),
         )
         .await;
@@ -1,4 +1,7 @@
-use entries_common::db::{self, DaoError, DbThreadPool};
+use entries_common::db::auth::AuthDao;
+use entries_common::db::budget::BudgetDao;
+use entries_common::db::user::UserDao;
+use entries_common::db::DaoError;
 use entries_common::email::templates::UserVerificationMessage;
 use entries_common::email::{EmailMessage, EmailSender};
 use entries_common::html::templates::{
@@ -9,8 +12,8 @@ use entries_common::html::templates::{
 };
 use entries_common::messages::{
     AuthStringAndEncryptedPasswordUpdate, BackupCodesAndVerificationEmailSent,
-    BudgetAccessTokenList, EmailQuery, EncryptedBlobUpdate, IsUserListedForDeletion, NewUser,
-    NewUserPublicKey, RecoveryKeyUpdate, UserPublicKey, VerificationEmailSent,
+    BudgetAccessTokenList, EmailQuery, EncryptedBlob, EncryptedBlobUpdate, IsUserListedForDeletion,
+    NewUser, NewUserPublicKey, RecoveryKeyUpdate, UserPublicKey, VerificationEmailSent,
 };
 use entries_common::otp::Otp;
 use entries_common::token::auth_token::{AuthToken, AuthTokenType, NewAuthTokenClaims};
@@ -18,57 +21,60 @@ use entries_common::token::budget_access_token::BudgetAccessToken;
 use entries_common::token::{Token, TokenError};
 use entries_common::validators::{self, Validity};
 
-use actix_protobuf::{ProtoBuf, ProtoBufResponseBuilder};
-use actix_web::{web, HttpResponse};
+use actix_web::http::header::ETag;
+use actix_web::{web, HttpRequest, HttpResponse};
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tokio::sync::oneshot;
+use uuid::Uuid;
 use zeroize::Zeroizing;
 
 use crate::env;
-use crate::handlers::{self, error::DoesNotExistType, error::HttpErrorResponse};
+use crate::handlers::error::SizeLimit;
+use crate::handlers::etag::{self, ETagBuilder};
+use crate::handlers::{self, block_task, error::DoesNotExistType, error::HttpErrorResponse};
+use crate::metrics;
 use crate::middleware::auth::{Access, UnverifiedToken, UserCreation, UserDeletion, VerifiedToken};
+use crate::middleware::proto_or_json::{ProtoOrJson, ProtoOrJsonResponseBuilder};
 use crate::middleware::{FromHeader, FromQuery};
 
+#[tracing::instrument(level = "debug", skip_all)]
 pub async fn lookup_user_public_key(
-    db_thread_pool: web::Data<DbThreadPool>,
+    user_dao: web::Data<dyn UserDao>,
     _user_access_token: VerifiedToken<Access, FromHeader>,
     user_email: web::Query<EmailQuery>,
 ) -> Result<HttpResponse, HttpErrorResponse> {
-    let (key_id, key) = match web::block(move || {
-        let user_dao = db::user::Dao::new(&db_thread_pool);
-        user_dao.get_user_public_key(&user_email.email)
-    })
-    .await?
-    {
-        Ok(k) => (k.id, k.value),
-        Err(e) => match e {
-            DaoError::QueryFailure(diesel::result::Error::NotFound) => {
-                return Err(HttpErrorResponse::DoesNotExist(
-                    String::from("No user with given email address"),
-                    DoesNotExistType::Key,
-                ));
-            }
-            _ => {
-                log::error!("{e}");
-                return Err(HttpErrorResponse::InternalError(String::from(
-                    "Failed to get user's public key",
-                )));
-            }
-        },
-    };
+    let (key_id, key) =
+        match block_task(move || user_dao.get_user_public_key(&user_email.email)).await? {
+            Ok(k) => (k.id, k.value),
+            Err(e) => match e {
+                DaoError::QueryFailure(diesel::result::Error::NotFound) => {
+                    return Err(HttpErrorResponse::DoesNotExist(
+                        String::from("No user with given email address"),
+                        DoesNotExistType::Key,
+                    ));
+                }
+                _ => {
+                    log::error!("{e}");
+                    return Err(HttpErrorResponse::InternalError(String::from(
+                        "Failed to get user's public key",
+                    )));
+                }
+            },
+        };
 
-    Ok(HttpResponse::Ok().protobuf(UserPublicKey {
+    Ok(HttpResponse::Ok().proto_or_json(UserPublicKey {
         id: key_id,
         value: key,
     })?)
 }
 
+#[tracing::instrument(level = "debug", skip_all)]
 pub async fn create(
-    db_thread_pool: web::Data<DbThreadPool>,
+    user_dao: web::Data<dyn UserDao>,
     smtp_thread_pool: web::Data<EmailSender>,
-    user_data: ProtoBuf<NewUser>,
+    user_data: ProtoOrJson<NewUser>,
 ) -> Result<HttpResponse, HttpErrorResponse> {
     let user_data = Zeroizing::new(user_data.0);
 
@@ -77,59 +83,77 @@ pub async fn create(
     }
 
     if user_data.auth_string.len() > env::CONF.max_encryption_key_size {
-        return Err(HttpErrorResponse::InputTooLarge(String::from(
-            "Auth string is too long",
-        )));
+        return Err(HttpErrorResponse::InputTooLarge(
+            String::from("Auth string is too long"),
+            SizeLimit::field("auth_string", env::CONF.max_encryption_key_size),
+        ));
     }
 
     if user_data.auth_string_salt.len() > env::CONF.max_encryption_key_size {
-        return Err(HttpErrorResponse::InputTooLarge(String::from(
-            "Auth string salt is too big",
-        )));
+        return Err(HttpErrorResponse::InputTooLarge(
+            String::from("Auth string salt is too big"),
+            SizeLimit::field("auth_string_salt", env::CONF.max_encryption_key_size),
+        ));
     }
 
     if user_data.password_encryption_salt.len() > env::CONF.max_encryption_key_size {
-        return Err(HttpErrorResponse::InputTooLarge(String::from(
-            "Password encryption salt is too big",
-        )));
+        return Err(HttpErrorResponse::InputTooLarge(
+            String::from("Password encryption salt is too big"),
+            SizeLimit::field(
+                "password_encryption_salt",
+                env::CONF.max_encryption_key_size,
+            ),
+        ));
     }
 
     if user_data.recovery_key_salt.len() > env::CONF.max_encryption_key_size {
-        return Err(HttpErrorResponse::InputTooLarge(String::from(
-            "Recovery key salt is too big",
-        )));
+        return Err(HttpErrorResponse::InputTooLarge(
+            String::from("Recovery key salt is too big"),
+            SizeLimit::field("recovery_key_salt", env::CONF.max_encryption_key_size),
+        ));
     }
 
     if user_data.encryption_key_encrypted_with_password.len() > env::CONF.max_encryption_key_size {
-        return Err(HttpErrorResponse::InputTooLarge(String::from(
-            "Encryption key encrypted with password is too big",
-        )));
+        return Err(HttpErrorResponse::InputTooLarge(
+            String::from("Encryption key encrypted with password is too big"),
+            SizeLimit::field(
+                "encryption_key_encrypted_with_password",
+                env::CONF.max_encryption_key_size,
+            ),
+        ));
     }
 
     if user_data.encryption_key_encrypted_with_recovery_key.len()
         > env::CONF.max_encryption_key_size
     {
-        return Err(HttpErrorResponse::InputTooLarge(String::from(
-            "Encryption key encrypted with recovery key is too big",
-        )));
+        return Err(HttpErrorResponse::InputTooLarge(
+            String::from("Encryption key encrypted with recovery key is too big"),
+            SizeLimit::field(
+                "encryption_key_encrypted_with_recovery_key",
+                env::CONF.max_encryption_key_size,
+            ),
+        ));
     }
 
     if user_data.public_key.len() > env::CONF.max_encryption_key_size {
-        return Err(HttpErrorResponse::InputTooLarge(String::from(
-            "Public key is too big",
-        )));
+        return Err(HttpErrorResponse::InputTooLarge(
+            String::from("Public key is too big"),
+            SizeLimit::field("public_key", env::CONF.max_encryption_key_size),
+        ));
     }
 
     if user_data.preferences_encrypted.len() > env::CONF.max_user_preferences_size {
-        return Err(HttpErrorResponse::InputTooLarge(String::from(
-            "Preferences encrypted is too big",
-        )));
+        return Err(HttpErrorResponse::InputTooLarge(
+            String::from("Preferences encrypted is too big"),
+            SizeLimit::field("preferences_encrypted", env::CONF.max_user_preferences_size),
+        ));
     }
 
     if user_data.user_keystore_encrypted.len() > env::CONF.max_keystore_size {
-        return Err(HttpErrorResponse::InputTooLarge(String::from(
-            "User keystore encrypted is too big",
-        )));
+        return Err(HttpErrorResponse::InputTooLarge(
+            String::from("User keystore encrypted is too big"),
+            SizeLimit::field("user_keystore_encrypted", env::CONF.max_keystore_size),
+        ));
     }
 
     let user_data = Arc::new(user_data);
@@ -137,7 +161,12 @@ pub async fn create(
 
     let (sender, receiver) = oneshot::channel();
 
-    rayon::spawn(move || {
+    handlers::rayon_spawn(move || {
+        let _span = tracing::debug_span!("argon2_hash").entered();
+        let _timer = metrics::ARGON2_DURATION
+            .with_label_values(&["hash"])
+            .start_timer();
+
         let hash_result = argon2_kdf::Hasher::default()
             .algorithm(argon2_kdf::Algorithm::Argon2id)
             .salt_length(env::CONF.hash_salt_length)
@@ -176,8 +205,7 @@ pub async fn create(
 
     let user_data_ref = Arc::clone(&user_data);
 
-    let user_id = match web::block(move || {
-        let user_dao = db::user::Dao::new(&db_thread_pool);
+    let user_id = match block_task(move || {
         user_dao.create_user(
             &user_data_ref.email,
             &auth_string_hash.to_string(),
@@ -212,9 +240,10 @@ pub async fn create(
                 diesel::result::DatabaseErrorKind::UniqueViolation,
                 _,
             )) => {
-                return Err(HttpErrorResponse::ConflictWithExisting(String::from(
-                    "A user with the given email address already exists",
-                )));
+                return Err(HttpErrorResponse::ConflictWithExisting(
+                    String::from("A user with the given email address already exists"),
+                    None,
+                ));
             }
             _ => {
                 log::error!("{e}");
@@ -264,7 +293,7 @@ pub async fn create(
     let backup_codes = Arc::into_inner(backup_codes)
         .expect("Multiple references exist to data that should only have one reference");
 
-    let resp_body = HttpResponse::Created().protobuf(BackupCodesAndVerificationEmailSent {
+    let resp_body = HttpResponse::Created().proto_or_json(BackupCodesAndVerificationEmailSent {
         email_sent: true,
         email_token_lifetime_hours: env::CONF.user_creation_token_lifetime.as_secs() / 3600,
         backup_codes,
@@ -273,8 +302,9 @@ pub async fn create(
     Ok(resp_body)
 }
 
+#[tracing::instrument(level = "debug", skip_all)]
 pub async fn verify_creation(
-    db_thread_pool: web::Data<DbThreadPool>,
+    user_dao: web::Data<dyn UserDao>,
     user_creation_token: UnverifiedToken<UserCreation, FromQuery>,
 ) -> Result<HttpResponse, HttpErrorResponse> {
     let claims = match user_creation_token.verify() {
@@ -296,12 +326,7 @@ pub async fn verify_creation(
         }
     };
 
-    match web::block(move || {
-        let user_dao = db::user::Dao::new(&db_thread_pool);
-        user_dao.verify_user_creation(claims.user_id)
-    })
-    .await?
-    {
+    match block_task(move || user_dao.verify_user_creation(claims.user_id)).await? {
         Ok(_) => (),
         Err(e) => {
             log::error!("{e}");
@@ -316,22 +341,23 @@ pub async fn verify_creation(
         .body(VerifyUserSuccessPage::generate(&claims.user_email)))
 }
 
+#[tracing::instrument(level = "debug", skip_all)]
 pub async fn rotate_user_public_key(
-    db_thread_pool: web::Data<DbThreadPool>,
+    user_dao: web::Data<dyn UserDao>,
     user_access_token: VerifiedToken<Access, FromHeader>,
-    new_key: ProtoBuf<NewUserPublicKey>,
+    new_key: ProtoOrJson<NewUserPublicKey>,
 ) -> Result<HttpResponse, HttpErrorResponse> {
     if new_key.value.len() > env::CONF.max_encryption_key_size {
-        return Err(HttpErrorResponse::InputTooLarge(String::from(
-            "User public key is too long",
-        )));
+        return Err(HttpErrorResponse::InputTooLarge(
+            String::from("User public key is too long"),
+            SizeLimit::field("value", env::CONF.max_encryption_key_size),
+        ));
     }
 
     let new_key_id = (&new_key.0.id).try_into()?;
     let expected_previous_public_key_id =
         (&new_key.0.expected_previous_public_key_id).try_into()?;
-    match web::block(move || {
-        let user_dao = db::user::Dao::new(&db_thread_pool);
+    match block_task(move || {
         user_dao.rotate_user_public_key(
             user_access_token.0.user_id,
             new_key_id,
@@ -342,10 +368,11 @@ pub async fn rotate_user_public_key(
     .await?
     {
         Ok(_) => (),
-        Err(DaoError::OutOfDate) => {
-            return Err(HttpErrorResponse::OutOfDate(String::from(
-                "Expected key was out of date",
-            )));
+        Err(DaoError::OutOfDate(current_version_nonce)) => {
+            return Err(HttpErrorResponse::OutOfDate(
+                String::from("Expected key was out of date"),
+                current_version_nonce,
+            ));
         }
         Err(e) => {
             log::error!("{e}");
@@ -358,19 +385,129 @@ pub async fn rotate_user_public_key(
     Ok(HttpResponse::Ok().finish())
 }
 
+/// Fetches everything a new device needs from the user's account in one request
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn get_bootstrap(
+    user_dao: web::Data<dyn UserDao>,
+    user_access_token: VerifiedToken<Access, FromHeader>,
+) -> Result<HttpResponse, HttpErrorResponse> {
+    let bootstrap =
+        match block_task(move || user_dao.get_user_bootstrap(user_access_token.0.user_id)).await? {
+            Ok(b) => b,
+            Err(DaoError::QueryFailure(diesel::result::Error::NotFound)) => {
+                return Err(HttpErrorResponse::DoesNotExist(
+                    String::from("User not found"),
+                    DoesNotExistType::User,
+                ));
+            }
+            Err(e) => {
+                log::error!("{e}");
+                return Err(HttpErrorResponse::InternalError(String::from(
+                    "Failed to get user data",
+                )));
+            }
+        };
+
+    Ok(HttpResponse::Ok().proto_or_json(bootstrap)?)
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn get_preferences(
+    req: HttpRequest,
+    user_dao: web::Data<dyn UserDao>,
+    user_access_token: VerifiedToken<Access, FromHeader>,
+) -> Result<HttpResponse, HttpErrorResponse> {
+    let user_id = user_access_token.0.user_id;
+
+    let prefs = match block_task(move || user_dao.get_user_prefs(user_id)).await? {
+        Ok(p) => p,
+        Err(DaoError::QueryFailure(diesel::result::Error::NotFound)) => {
+            return Err(HttpErrorResponse::DoesNotExist(
+                String::from("User not found"),
+                DoesNotExistType::User,
+            ));
+        }
+        Err(e) => {
+            log::error!("{e}");
+            return Err(HttpErrorResponse::InternalError(String::from(
+                "Failed to get user preferences",
+            )));
+        }
+    };
+
+    encrypted_blob_response(&req, user_id, prefs.encrypted_blob, prefs.version_nonce)
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
+pub async fn get_keystore(
+    req: HttpRequest,
+    user_dao: web::Data<dyn UserDao>,
+    user_access_token: VerifiedToken<Access, FromHeader>,
+) -> Result<HttpResponse, HttpErrorResponse> {
+    let user_id = user_access_token.0.user_id;
+
+    let keystore = match block_task(move || user_dao.get_user_keystore(user_id)).await? {
+        Ok(k) => k,
+        Err(DaoError::QueryFailure(diesel::result::Error::NotFound)) => {
+            return Err(HttpErrorResponse::DoesNotExist(
+                String::from("User not found"),
+                DoesNotExistType::User,
+            ));
+        }
+        Err(e) => {
+            log::error!("{e}");
+            return Err(HttpErrorResponse::InternalError(String::from(
+                "Failed to get user keystore",
+            )));
+        }
+    };
+
+    encrypted_blob_response(
+        &req,
+        user_id,
+        keystore.encrypted_blob,
+        keystore.version_nonce,
+    )
+}
+
+fn encrypted_blob_response(
+    req: &HttpRequest,
+    user_id: Uuid,
+    encrypted_blob: Vec<u8>,
+    version_nonce: i64,
+) -> Result<HttpResponse, HttpErrorResponse> {
+    let mut etag = ETagBuilder::new();
+    etag.add(user_id.as_bytes(), version_nonce, None);
+    let etag = etag.finish();
+
+    if etag::matches(req, &etag) {
+        return Ok(etag::not_modified(etag));
+    }
+
+    let blob = EncryptedBlob {
+        encrypted_blob,
+        version_nonce,
+    };
+
+    Ok(HttpResponse::Ok()
+        .insert_header(ETag(etag))
+        .proto_or_json(blob)?)
+}
+
+#[tracing::instrument(level = "debug", skip_all)]
 pub async fn edit_preferences(
-    db_thread_pool: web::Data<DbThreadPool>,
+    user_dao: web::Data<dyn UserDao>,
     user_access_token: VerifiedToken<Access, FromHeader>,
-    new_prefs: ProtoBuf<EncryptedBlobUpdate>,
+    new_prefs: ProtoOrJson<EncryptedBlobUpdate>,
 ) -> Result<HttpResponse, HttpErrorResponse> {
     if new_prefs.encrypted_blob.len() > env::CONF.max_user_preferences_size {
-        return Err(HttpErrorResponse::InputTooLarge(String::from(
-            "User preferences are too large",
-        )));
+        return Err(HttpErrorResponse::InputTooLarge(
+            String::from("User preferences are too large"),
+            SizeLimit::field("encrypted_blob", env::CONF.max_user_preferences_size),
+        ));
     }
 
-    match web::block(move || {
-        let user_dao = db::user::Dao::new(&db_thread_pool);
+    match block_task(move || {
         user_dao.update_user_prefs(
             user_access_token.0.user_id,
             &new_prefs.encrypted_blob,
@@ -382,10 +519,11 @@ pub async fn edit_preferences(
     {
         Ok(_) => (),
         Err(e) => match e {
-            DaoError::OutOfDate => {
-                return Err(HttpErrorResponse::OutOfDate(String::from(
-                    "Out of date version nonce",
-                )));
+            DaoError::OutOfDate(current_version_nonce) => {
+                return Err(HttpErrorResponse::OutOfDate(
+                    String::from("Out of date version nonce"),
+                    current_version_nonce,
+                ));
             }
             _ => {
                 log::error!("{e}");
@@ -399,19 +537,20 @@ pub async fn edit_preferences(
     Ok(HttpResponse::Ok().finish())
 }
 
+#[tracing::instrument(level = "debug", skip_all)]
 pub async fn edit_keystore(
-    db_thread_pool: web::Data<DbThreadPool>,
+    user_dao: web::Data<dyn UserDao>,
     user_access_token: VerifiedToken<Access, FromHeader>,
-    new_keystore: ProtoBuf<EncryptedBlobUpdate>,
+    new_keystore: ProtoOrJson<EncryptedBlobUpdate>,
 ) -> Result<HttpResponse, HttpErrorResponse> {
     if new_keystore.encrypted_blob.len() > env::CONF.max_keystore_size {
-        return Err(HttpErrorResponse::InputTooLarge(String::from(
-            "User keystore is too large",
-        )));
+        return Err(HttpErrorResponse::InputTooLarge(
+            String::from("User keystore is too large"),
+            SizeLimit::field("encrypted_blob", env::CONF.max_keystore_size),
+        ));
     }
 
-    match web::block(move || {
-        let user_dao = db::user::Dao::new(&db_thread_pool);
+    match block_task(move || {
         user_dao.update_user_keystore(
             user_access_token.0.user_id,
             &new_keystore.encrypted_blob,
@@ -423,10 +562,11 @@ pub async fn edit_keystore(
     {
         Ok(_) => (),
         Err(e) => match e {
-            DaoError::OutOfDate => {
-                return Err(HttpErrorResponse::OutOfDate(String::from(
-                    "Out of date version nonce",
-                )));
+            DaoError::OutOfDate(current_version_nonce) => {
+                return Err(HttpErrorResponse::OutOfDate(
+                    String::from("Out of date version nonce"),
+                    current_version_nonce,
+                ));
             }
             _ => {
                 log::error!("{e}");
@@ -440,40 +580,52 @@ pub async fn edit_keystore(
     Ok(HttpResponse::Ok().finish())
 }
 
+#[tracing::instrument(level = "debug", skip_all)]
 pub async fn change_password(
-    db_thread_pool: web::Data<DbThreadPool>,
-    new_password_data: ProtoBuf<AuthStringAndEncryptedPasswordUpdate>,
+    auth_dao: web::Data<dyn AuthDao>,
+    user_dao: web::Data<dyn UserDao>,
+    new_password_data: ProtoOrJson<AuthStringAndEncryptedPasswordUpdate>,
 ) -> Result<HttpResponse, HttpErrorResponse> {
     let new_password_data = Zeroizing::new(new_password_data.0);
 
     if new_password_data.new_auth_string.len() > env::CONF.max_encryption_key_size {
-        return Err(HttpErrorResponse::InputTooLarge(String::from(
-            "Auth string is too long",
-        )));
+        return Err(HttpErrorResponse::InputTooLarge(
+            String::from("Auth string is too long"),
+            SizeLimit::field("new_auth_string", env::CONF.max_encryption_key_size),
+        ));
     }
 
     if new_password_data.auth_string_salt.len() > env::CONF.max_encryption_key_size {
-        return Err(HttpErrorResponse::InputTooLarge(String::from(
-            "Auth string salt is too long",
-        )));
+        return Err(HttpErrorResponse::InputTooLarge(
+            String::from("Auth string salt is too long"),
+            SizeLimit::field("auth_string_salt", env::CONF.max_encryption_key_size),
+        ));
     }
 
     if new_password_data.password_encryption_salt.len() > env::CONF.max_encryption_key_size {
-        return Err(HttpErrorResponse::InputTooLarge(String::from(
-            "Password encryption salt is too long",
-        )));
+        return Err(HttpErrorResponse::InputTooLarge(
+            String::from("Password encryption salt is too long"),
+            SizeLimit::field(
+                "password_encryption_salt",
+                env::CONF.max_encryption_key_size,
+            ),
+        ));
     }
 
     if new_password_data.encrypted_encryption_key.len() > env::CONF.max_encryption_key_size {
-        return Err(HttpErrorResponse::InputTooLarge(String::from(
-            "Encrypted encryption key is too long",
-        )));
+        return Err(HttpErrorResponse::InputTooLarge(
+            String::from("Encrypted encryption key is too long"),
+            SizeLimit::field(
+                "encrypted_encryption_key",
+                env::CONF.max_encryption_key_size,
+            ),
+        ));
     }
 
     handlers::verification::verify_otp(
         &new_password_data.otp,
         &new_password_data.user_email,
-        &db_thread_pool,
+        &auth_dao,
     )
     .await?;
 
@@ -482,7 +634,12 @@ pub async fn change_password(
 
     let (sender, receiver) = oneshot::channel();
 
-    rayon::spawn(move || {
+    handlers::rayon_spawn(move || {
+        let _span = tracing::debug_span!("argon2_hash").entered();
+        let _timer = metrics::ARGON2_DURATION
+            .with_label_values(&["hash"])
+            .start_timer();
+
         let hash_result = argon2_kdf::Hasher::default()
             .algorithm(argon2_kdf::Algorithm::Argon2id)
             .salt_length(env::CONF.hash_salt_length)
@@ -514,8 +671,7 @@ pub async fn change_password(
         }
     };
 
-    web::block(move || {
-        let user_dao = db::user::Dao::new(&db_thread_pool);
+    block_task(move || {
         user_dao.update_password(
             &new_password_data.user_email,
             &auth_string_hash.to_string(),
@@ -538,21 +694,28 @@ pub async fn change_password(
     })
 }
 
+#[tracing::instrument(level = "debug", skip_all)]
 pub async fn change_recovery_key(
-    db_thread_pool: web::Data<DbThreadPool>,
+    auth_dao: web::Data<dyn AuthDao>,
+    user_dao: web::Data<dyn UserDao>,
     user_access_token: VerifiedToken<Access, FromHeader>,
-    new_recovery_key_data: ProtoBuf<RecoveryKeyUpdate>,
+    new_recovery_key_data: ProtoOrJson<RecoveryKeyUpdate>,
 ) -> Result<HttpResponse, HttpErrorResponse> {
     if new_recovery_key_data.recovery_key_salt.len() > env::CONF.max_encryption_key_size {
-        return Err(HttpErrorResponse::InputTooLarge(String::from(
-            "Recovery key salt is too long",
-        )));
+        return Err(HttpErrorResponse::InputTooLarge(
+            String::from("Recovery key salt is too long"),
+            SizeLimit::field("recovery_key_salt", env::CONF.max_encryption_key_size),
+        ));
     }
 
     if new_recovery_key_data.encrypted_encryption_key.len() > env::CONF.max_encryption_key_size {
-        return Err(HttpErrorResponse::InputTooLarge(String::from(
-            "Encrypted encryption key is too long",
-        )));
+        return Err(HttpErrorResponse::InputTooLarge(
+            String::from("Encrypted encryption key is too long"),
+            SizeLimit::field(
+                "encrypted_encryption_key",
+                env::CONF.max_encryption_key_size,
+            ),
+        ));
     }
 
     let user_id = user_access_token.0.user_id;
@@ -560,12 +723,11 @@ pub async fn change_recovery_key(
     handlers::verification::verify_otp(
         &new_recovery_key_data.otp,
         &user_access_token.0.user_email,
-        &db_thread_pool,
+        &auth_dao,
     )
     .await?;
 
-    match web::block(move || {
-        let user_dao = db::user::Dao::new(&db_thread_pool);
+    match block_task(move || {
         user_dao.update_recovery_key(
             user_id,
             &new_recovery_key_data.recovery_key_salt,
@@ -589,19 +751,22 @@ pub async fn change_recovery_key(
     Ok(HttpResponse::Ok().finish())
 }
 
+#[tracing::instrument(level = "debug", skip_all)]
 pub async fn init_delete(
-    db_thread_pool: web::Data<DbThreadPool>,
+    user_dao: web::Data<dyn UserDao>,
+    budget_dao: web::Data<dyn BudgetDao>,
     smtp_thread_pool: web::Data<EmailSender>,
     user_access_token: VerifiedToken<Access, FromHeader>,
-    budget_access_tokens: ProtoBuf<BudgetAccessTokenList>,
+    budget_access_tokens: ProtoOrJson<BudgetAccessTokenList>,
 ) -> Result<HttpResponse, HttpErrorResponse> {
     const INVALID_ID_MSG: &str =
         "One of the provided budget access tokens is invalid or has an incorrect ID";
 
     if budget_access_tokens.tokens.len() > env::CONF.max_budgets {
-        return Err(HttpErrorResponse::InputTooLarge(String::from(
-            "Too many budget access tokens",
-        )));
+        return Err(HttpErrorResponse::InputTooLarge(
+            String::from("Too many budget access tokens"),
+            SizeLimit::field("tokens", env::CONF.max_budgets),
+        ));
     }
 
     let mut tokens = HashMap::new();
@@ -620,9 +785,9 @@ pub async fn init_delete(
     let key_ids = Arc::new(key_ids);
     let key_ids_ref = Arc::clone(&key_ids);
 
-    let budget_dao = db::budget::Dao::new(&db_thread_pool);
-    let public_keys = match web::block(move || {
-        budget_dao.get_multiple_public_budget_keys(&key_ids_ref, &budget_ids)
+    let budget_dao_ref = web::Data::clone(&budget_dao);
+    let public_keys = match block_task(move || {
+        budget_dao_ref.get_multiple_public_budget_keys(&key_ids_ref, &budget_ids)
     })
     .await?
     {
@@ -670,8 +835,7 @@ pub async fn init_delete(
 
     let user_id = user_access_token.0.user_id;
 
-    match web::block(move || {
-        let user_dao = db::user::Dao::new(&db_thread_pool);
+    match block_task(move || {
         user_dao.save_user_deletion_budget_keys(&key_ids, user_id, delete_me_time)
     })
     .await?
@@ -729,14 +893,15 @@ pub async fn init_delete(
         }
     };
 
-    Ok(HttpResponse::Ok().protobuf(VerificationEmailSent {
+    Ok(HttpResponse::Ok().proto_or_json(VerificationEmailSent {
         email_sent: true,
         email_token_lifetime_hours: env::CONF.user_deletion_token_lifetime.as_secs() / 3600,
     })?)
 }
 
+#[tracing::instrument(level = "debug", skip_all)]
 pub async fn delete(
-    db_thread_pool: web::Data<DbThreadPool>,
+    user_dao: web::Data<dyn UserDao>,
     user_deletion_token: UnverifiedToken<UserDeletion, FromQuery>,
 ) -> Result<HttpResponse, HttpErrorResponse> {
     let claims = match user_deletion_token.verify() {
@@ -761,8 +926,7 @@ pub async fn delete(
     let user_id = claims.user_id;
     let days_until_deletion = env::CONF.user_deletion_delay_days;
 
-    match web::block(move || {
-        let user_dao = db::user::Dao::new(&db_thread_pool);
+    match block_task(move || {
         user_dao.initiate_user_deletion(
             user_id,
             Duration::from_secs(days_until_deletion * 24 * 60 * 60),
@@ -806,12 +970,12 @@ pub async fn delete(
         )))
 }
 
+#[tracing::instrument(level = "debug", skip_all)]
 pub async fn is_listed_for_deletion(
-    db_thread_pool: web::Data<DbThreadPool>,
+    user_dao: web::Data<dyn UserDao>,
     user_access_token: VerifiedToken<Access, FromHeader>,
 ) -> Result<HttpResponse, HttpErrorResponse> {
-    let is_listed_for_deletion = match web::block(move || {
-        let user_dao = db::user::Dao::new(&db_thread_pool);
+    let is_listed_for_deletion = match block_task(move || {
         user_dao.check_is_user_listed_for_deletion(user_access_token.0.user_id)
     })
     .await?
@@ -825,21 +989,17 @@ pub async fn is_listed_for_deletion(
         }
     };
 
-    Ok(HttpResponse::Ok().protobuf(IsUserListedForDeletion {
+    Ok(HttpResponse::Ok().proto_or_json(IsUserListedForDeletion {
         value: is_listed_for_deletion,
     })?)
 }
 
+#[tracing::instrument(level = "debug", skip_all)]
 pub async fn cancel_delete(
-    db_thread_pool: web::Data<DbThreadPool>,
+    user_dao: web::Data<dyn UserDao>,
     user_access_token: VerifiedToken<Access, FromHeader>,
 ) -> Result<HttpResponse, HttpErrorResponse> {
-    match web::block(move || {
-        let user_dao = db::user::Dao::new(&db_thread_pool);
-        user_dao.cancel_user_deletion(user_access_token.0.user_id)
-    })
-    .await?
-    {
+    match block_task(move || user_dao.cancel_user_deletion(user_access_token.0.user_id)).await? {
         Ok(_) => (),
         Err(e) => {
             log::error!("{e}");
@@ -856,9 +1016,10 @@ pub async fn cancel_delete(
 pub mod tests {
     use super::*;
 
+    use entries_common::db;
     use entries_common::messages::{
         EntryAndCategory, EntryIdAndCategoryId, ErrorType, NewUser, ServerErrorResponse,
-        Uuid as UuidMessage,
+        UserBootstrap, Uuid as UuidMessage,
     };
     use entries_common::models::user::User;
     use entries_common::models::user_deletion_request::UserDeletionRequest;
@@ -887,7 +1048,7 @@ pub mod tests {
     use entries_common::schema::users::dsl::users;
     use entries_common::token::budget_access_token::BudgetAccessTokenClaims;
 
-    use actix_protobuf::ProtoBufConfig;
+    use crate::middleware::proto_or_json::{ContentNegotiation, ProtoOrJsonConfig};
     use actix_web::body::to_bytes;
     use actix_web::http::StatusCode;
     use actix_web::test::{self, TestRequest};
@@ -913,8 +1074,10 @@ pub mod tests {
         let app = test::init_service(
             App::new()
                 .app_data(Data::new(env::testing::DB_THREAD_POOL.clone()))
+                .app_data(Data::new(env::testing::async_db_pools()))
+                .configure(env::testing::configure_daos)
                 .app_data(Data::new(env::testing::SMTP_THREAD_POOL.clone()))
-                .app_data(ProtoBufConfig::default())
+                .app_data(ProtoOrJsonConfig::default())
                 .configure(|cfg| crate::services::api::configure(cfg, RouteLimiters::default())),
         )
         .await;
@@ -943,8 +1106,10 @@ pub mod tests {
         let app = test::init_service(
             App::new()
                 .app_data(Data::new(env::testing::DB_THREAD_POOL.clone()))
+                .app_data(Data::new(env::testing::async_db_pools()))
+                .configure(env::testing::configure_daos)
                 .app_data(Data::new(env::testing::SMTP_THREAD_POOL.clone()))
-                .app_data(ProtoBufConfig::default())
+                .app_data(ProtoOrJsonConfig::default())
                 .configure(|cfg| crate::services::api::configure(cfg, RouteLimiters::default())),
         )
         .await;
@@ -1143,7 +1308,7 @@ pub mod tests {
     #[actix_web::test]
     #[ignore]
     async fn test_create_user_fails_with_large_input() {
-        let mut protobuf_config = ProtoBufConfig::default();
+        let mut protobuf_config = ProtoOrJsonConfig::default();
         protobuf_config.limit(env::CONF.protobuf_max_size);
         let route_limiters = RouteLimiters {
             create_user: Limiter::new(15, Duration::from_secs(1200), Duration::from_secs(3600)),
@@ -1152,6 +1317,8 @@ pub mod tests {
         let app = test::init_service(
             App::new()
                 .app_data(Data::new(env::testing::DB_THREAD_POOL.clone()))
+                .app_data(Data::new(env::testing::async_db_pools()))
+                .configure(env::testing::configure_daos)
                 .app_data(Data::new(env::testing::SMTP_THREAD_POOL.clone()))
                 .app_data(protobuf_config)
                 .configure(|cfg| crate::services::api::configure(cfg, route_limiters)),
@@ -1209,6 +1376,11 @@ pub mod tests {
         let resp_body = ServerErrorResponse::decode(resp_body).unwrap();
 
         assert_eq!(resp_body.err_type, ErrorType::InputTooLarge as i32);
+        assert_eq!(resp_body.field.as_deref(), Some("auth_string"));
+        assert_eq!(
+            resp_body.max_size,
+            Some(env::CONF.max_encryption_key_size as u64)
+        );
 
         let mut temp = new_user.clone();
         temp.auth_string_salt = vec![0; env::CONF.max_encryption_key_size + 1];
@@ -1225,6 +1397,11 @@ pub mod tests {
         let resp_body = ServerErrorResponse::decode(resp_body).unwrap();
 
         assert_eq!(resp_body.err_type, ErrorType::InputTooLarge as i32);
+        assert_eq!(resp_body.field.as_deref(), Some("auth_string_salt"));
+        assert_eq!(
+            resp_body.max_size,
+            Some(env::CONF.max_encryption_key_size as u64)
+        );
 
         let mut temp = new_user.clone();
         temp.password_encryption_salt = vec![0; env::CONF.max_encryption_key_size + 1];
@@ -1346,8 +1523,10 @@ pub mod tests {
         let app = test::init_service(
             App::new()
                 .app_data(Data::new(env::testing::DB_THREAD_POOL.clone()))
+                .app_data(Data::new(env::testing::async_db_pools()))
+                .configure(env::testing::configure_daos)
                 .app_data(Data::new(env::testing::SMTP_THREAD_POOL.clone()))
-                .app_data(ProtoBufConfig::default())
+                .app_data(ProtoOrJsonConfig::default())
                 .configure(|cfg| crate::services::api::configure(cfg, RouteLimiters::default())),
         )
         .await;
@@ -1458,8 +1637,10 @@ pub mod tests {
         let app = test::init_service(
             App::new()
                 .app_data(Data::new(env::testing::DB_THREAD_POOL.clone()))
+                .app_data(Data::new(env::testing::async_db_pools()))
+                .configure(env::testing::configure_daos)
                 .app_data(Data::new(env::testing::SMTP_THREAD_POOL.clone()))
-                .app_data(ProtoBufConfig::default())
+                .app_data(ProtoOrJsonConfig::default())
                 .configure(|cfg| crate::services::api::configure(cfg, RouteLimiters::default())),
         )
         .await;
@@ -1527,11 +1708,13 @@ pub mod tests {
     #[actix_web::test]
     #[ignore]
     async fn test_rotate_user_public_key_fails_with_large_input() {
-        let mut protobuf_config = ProtoBufConfig::default();
+        let mut protobuf_config = ProtoOrJsonConfig::default();
         protobuf_config.limit(env::CONF.protobuf_max_size);
         let app = test::init_service(
             App::new()
                 .app_data(Data::new(env::testing::DB_THREAD_POOL.clone()))
+                .app_data(Data::new(env::testing::async_db_pools()))
+                .configure(env::testing::configure_daos)
                 .app_data(Data::new(env::testing::SMTP_THREAD_POOL.clone()))
                 .app_data(protobuf_config)
                 .configure(|cfg| crate::services::api::configure(cfg, RouteLimiters::default())),
@@ -1562,13 +1745,182 @@ pub mod tests {
         assert_eq!(resp_body.err_type, ErrorType::InputTooLarge as i32);
     }
 
+    #[actix_web::test]
+    async fn test_get_bootstrap() {
+        let app = test::init_service(
+            App::new()
+                .app_data(Data::new(env::testing::DB_THREAD_POOL.clone()))
+                .app_data(Data::new(env::testing::async_db_pools()))
+                .configure(env::testing::configure_daos)
+                .app_data(Data::new(env::testing::SMTP_THREAD_POOL.clone()))
+                .app_data(ProtoOrJsonConfig::default())
+                .configure(|cfg| crate::services::api::configure(cfg, RouteLimiters::default())),
+        )
+        .await;
+
+        let (user, access_token, preferences_version_nonce, keystore_version_nonce) =
+            test_utils::create_user().await;
+
+        let req = TestRequest::get()
+            .uri("/api/user/bootstrap")
+            .insert_header(("AccessToken", access_token.as_str()))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let resp_body = to_bytes(resp.into_body()).await.unwrap();
+        let bootstrap = UserBootstrap::decode(resp_body).unwrap();
+
+        let stored_prefs_blob = user_preferences
+            .select(user_preferences_fields::encrypted_blob)
+            .find(user.id)
+            .get_result::<Vec<u8>>(&mut env::testing::DB_THREAD_POOL.get().unwrap())
+            .unwrap();
+        let stored_keystore_blob = user_keystores
+            .select(user_keystore_fields::encrypted_blob)
+            .find(user.id)
+            .get_result::<Vec<u8>>(&mut env::testing::DB_THREAD_POOL.get().unwrap())
+            .unwrap();
+
+        assert_eq!(bootstrap.preferences.encrypted_blob, stored_prefs_blob);
+        assert_eq!(
+            bootstrap.preferences.version_nonce,
+            preferences_version_nonce
+        );
+        assert_eq!(bootstrap.keystore.encrypted_blob, stored_keystore_blob);
+        assert_eq!(bootstrap.keystore.version_nonce, keystore_version_nonce);
+        assert_eq!(
+            Uuid::try_from(&bootstrap.public_key.id).unwrap(),
+            user.public_key_id
+        );
+        assert_eq!(bootstrap.public_key.value, user.public_key);
+        assert!(!bootstrap.is_listed_for_deletion);
+    }
+
+    #[actix_web::test]
+    async fn test_get_preferences_and_keystore() {
+        let app = test::init_service(
+            App::new()
+                .app_data(Data::new(env::testing::DB_THREAD_POOL.clone()))
+                .app_data(Data::new(env::testing::async_db_pools()))
+                .configure(env::testing::configure_daos)
+                .app_data(Data::new(env::testing::SMTP_THREAD_POOL.clone()))
+                .app_data(ProtoOrJsonConfig::default())
+                .wrap(ContentNegotiation)
+                .configure(|cfg| crate::services::api::configure(cfg, RouteLimiters::default())),
+        )
+        .await;
+
+        let (_, access_token, preferences_version_nonce, keystore_version_nonce) =
+            test_utils::create_user().await;
+
+        for (path, version_nonce) in [
+            ("/api/user/preferences", preferences_version_nonce),
+            ("/api/user/keystore", keystore_version_nonce),
+        ] {
+            let req = TestRequest::get()
+                .uri(path)
+                .insert_header(("AccessToken", access_token.as_str()))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+
+            assert_eq!(resp.status(), StatusCode::OK);
+
+            let etag = resp.headers().get("ETag").unwrap().clone();
+
+            let resp_body = to_bytes(resp.into_body()).await.unwrap();
+            let blob = EncryptedBlob::decode(resp_body).unwrap();
+
+            assert_eq!(blob.version_nonce, version_nonce);
+
+            let req = TestRequest::get()
+                .uri(path)
+                .insert_header(("AccessToken", access_token.as_str()))
+                .insert_header(("If-None-Match", etag.clone()))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+
+            assert_eq!(resp.status(), StatusCode::NOT_MODIFIED);
+            assert_eq!(resp.headers().get("ETag").unwrap(), &etag);
+
+            let resp_body = to_bytes(resp.into_body()).await.unwrap();
+            assert!(resp_body.is_empty());
+
+            let update = EncryptedBlobUpdate {
+                encrypted_blob: gen_bytes(32),
+                version_nonce: rand::thread_rng().gen(),
+                expected_previous_version_nonce: version_nonce,
+            };
+
+            let req = TestRequest::put()
+                .uri(path)
+                .insert_header(("AccessToken", access_token.as_str()))
+                .insert_header(("Content-Type", "application/protobuf"))
+                .set_payload(update.encode_to_vec())
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+
+            assert_eq!(resp.status(), StatusCode::OK);
+
+            let req = TestRequest::get()
+                .uri(path)
+                .insert_header(("AccessToken", access_token.as_str()))
+                .insert_header(("If-None-Match", etag.clone()))
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+
+            assert_eq!(resp.status(), StatusCode::OK);
+            assert_ne!(resp.headers().get("ETag").unwrap(), &etag);
+
+            let resp_body = to_bytes(resp.into_body()).await.unwrap();
+            let blob = EncryptedBlob::decode(resp_body).unwrap();
+
+            assert_eq!(blob.encrypted_blob, update.encrypted_blob);
+            assert_eq!(blob.version_nonce, update.version_nonce);
+        }
+
+        // A JSON representation has a different tag than the protobuf one
+        let req = TestRequest::get()
+            .uri("/api/user/keystore")
+            .insert_header(("AccessToken", access_token.as_str()))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        let protobuf_etag = resp.headers().get("ETag").unwrap().clone();
+
+        let req = TestRequest::get()
+            .uri("/api/user/keystore")
+            .insert_header(("AccessToken", access_token.as_str()))
+            .insert_header(("Accept", "application/json"))
+            .insert_header(("If-None-Match", protobuf_etag.clone()))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_ne!(resp.headers().get("ETag").unwrap(), &protobuf_etag);
+
+        // Tags are scoped to the user
+        let (_, other_access_token, _, _) = test_utils::create_user().await;
+
+        let req = TestRequest::get()
+            .uri("/api/user/keystore")
+            .insert_header(("AccessToken", other_access_token.as_str()))
+            .insert_header(("If-None-Match", protobuf_etag))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+
     #[actix_web::test]
     async fn test_edit_preferences() {
         let app = test::init_service(
             App::new()
                 .app_data(Data::new(env::testing::DB_THREAD_POOL.clone()))
+                .app_data(Data::new(env::testing::async_db_pools()))
+                .configure(env::testing::configure_daos)
                 .app_data(Data::new(env::testing::SMTP_THREAD_POOL.clone()))
-                .app_data(ProtoBufConfig::default())
+                .app_data(ProtoOrJsonConfig::default())
                 .configure(|cfg| crate::services::api::configure(cfg, RouteLimiters::default())),
         )
         .await;
@@ -1599,6 +1951,10 @@ pub mod tests {
         let resp_err = ServerErrorResponse::decode(resp_body).unwrap();
 
         assert_eq!(resp_err.err_type, ErrorType::OutOfDate as i32);
+        assert_eq!(
+            resp_err.current_version_nonce,
+            Some(preferences_version_nonce)
+        );
 
         let (stored_prefs_blob, stored_prefs_version_nonce) = user_preferences
             .select((
@@ -1644,11 +2000,13 @@ pub mod tests {
     #[actix_web::test]
     #[ignore]
     async fn test_edit_preferences_fails_with_large_input() {
-        let mut protobuf_config = ProtoBufConfig::default();
+        let mut protobuf_config = ProtoOrJsonConfig::default();
         protobuf_config.limit(env::CONF.protobuf_max_size);
         let app = test::init_service(
             App::new()
                 .app_data(Data::new(env::testing::DB_THREAD_POOL.clone()))
+                .app_data(Data::new(env::testing::async_db_pools()))
+                .configure(env::testing::configure_daos)
                 .app_data(Data::new(env::testing::SMTP_THREAD_POOL.clone()))
                 .app_data(protobuf_config)
                 .configure(|cfg| crate::services::api::configure(cfg, RouteLimiters::default())),
@@ -1684,8 +2042,10 @@ pub mod tests {
         let app = test::init_service(
             App::new()
                 .app_data(Data::new(env::testing::DB_THREAD_POOL.clone()))
+                .app_data(Data::new(env::testing::async_db_pools()))
+                .configure(env::testing::configure_daos)
                 .app_data(Data::new(env::testing::SMTP_THREAD_POOL.clone()))
-                .app_data(ProtoBufConfig::default())
+                .app_data(ProtoOrJsonConfig::default())
                 .configure(|cfg| crate::services::api::configure(cfg, RouteLimiters::default())),
         )
         .await;
@@ -1767,11 +2127,13 @@ pub mod tests {
     #[actix_web::test]
     #[ignore]
     async fn test_edit_keystore_fails_with_large_input() {
-        let mut protobuf_config = ProtoBufConfig::default();
+        let mut protobuf_config = ProtoOrJsonConfig::default();
         protobuf_config.limit(env::CONF.protobuf_max_size);
         let app = test::init_service(
             App::new()
                 .app_data(Data::new(env::testing::DB_THREAD_POOL.clone()))
+                .app_data(Data::new(env::testing::async_db_pools()))
+                .configure(env::testing::configure_daos)
                 .app_data(Data::new(env::testing::SMTP_THREAD_POOL.clone()))
                 .app_data(protobuf_config)
                 .configure(|cfg| crate::services::api::configure(cfg, RouteLimiters::default())),
@@ -1807,8 +2169,10 @@ pub mod tests {
         let app = test::init_service(
             App::new()
                 .app_data(Data::new(env::testing::DB_THREAD_POOL.clone()))
+                .app_data(Data::new(env::testing::async_db_pools()))
+                .configure(env::testing::configure_daos)
                 .app_data(Data::new(env::testing::SMTP_THREAD_POOL.clone()))
-                .app_data(ProtoBufConfig::default())
+                .app_data(ProtoOrJsonConfig::default())
                 .configure(|cfg| crate::services::api::configure(cfg, RouteLimiters::default())),
         )
         .await;
@@ -2014,11 +2378,13 @@ pub mod tests {
     #[actix_web::test]
     #[ignore]
     async fn test_change_password_fails_with_large_input() {
-        let mut protobuf_config = ProtoBufConfig::default();
+        let mut protobuf_config = ProtoOrJsonConfig::default();
         protobuf_config.limit(env::CONF.protobuf_max_size);
         let app = test::init_service(
             App::new()
                 .app_data(Data::new(env::testing::DB_THREAD_POOL.clone()))
+                .app_data(Data::new(env::testing::async_db_pools()))
+                .configure(env::testing::configure_daos)
                 .app_data(Data::new(env::testing::SMTP_THREAD_POOL.clone()))
                 .app_data(protobuf_config)
                 .configure(|cfg| crate::services::api::configure(cfg, RouteLimiters::default())),
@@ -2186,8 +2552,10 @@ pub mod tests {
         let app = test::init_service(
             App::new()
                 .app_data(Data::new(env::testing::DB_THREAD_POOL.clone()))
+                .app_data(Data::new(env::testing::async_db_pools()))
+                .configure(env::testing::configure_daos)
                 .app_data(Data::new(env::testing::SMTP_THREAD_POOL.clone()))
-                .app_data(ProtoBufConfig::default())
+                .app_data(ProtoOrJsonConfig::default())
                 .configure(|cfg| crate::services::api::configure(cfg, RouteLimiters::default())),
         )
         .await;
@@ -2322,8 +2690,10 @@ pub mod tests {
         let app = test::init_service(
             App::new()
                 .app_data(Data::new(env::testing::DB_THREAD_POOL.clone()))
+                .app_data(Data::new(env::testing::async_db_pools()))
+                .configure(env::testing::configure_daos)
                 .app_data(Data::new(env::testing::SMTP_THREAD_POOL.clone()))
-                .app_data(ProtoBufConfig::default())
+                .app_data(ProtoOrJsonConfig::default())
                 .configure(|cfg| crate::services::api::configure(cfg, RouteLimiters::default())),
         )
         .await;
@@ -2404,8 +2774,10 @@ pub mod tests {
         let app = test::init_service(
             App::new()
                 .app_data(Data::new(env::testing::DB_THREAD_POOL.clone()))
+                .app_data(Data::new(env::testing::async_db_pools()))
+                .configure(env::testing::configure_daos)
                 .app_data(Data::new(env::testing::SMTP_THREAD_POOL.clone()))
-                .app_data(ProtoBufConfig::default())
+                .app_data(ProtoOrJsonConfig::default())
                 .configure(|cfg| crate::services::api::configure(cfg, RouteLimiters::default())),
         )
         .await;
@@ -2437,8 +2809,10 @@ pub mod tests {
         let app = test::init_service(
             App::new()
                 .app_data(Data::new(env::testing::DB_THREAD_POOL.clone()))
+                .app_data(Data::new(env::testing::async_db_pools()))
+                .configure(env::testing::configure_daos)
                 .app_data(Data::new(env::testing::SMTP_THREAD_POOL.clone()))
-                .app_data(ProtoBufConfig::default())
+                .app_data(ProtoOrJsonConfig::default())
                 .configure(|cfg| crate::services::api::configure(cfg, RouteLimiters::default())),
         )
         .await;
@@ -2566,8 +2940,10 @@ pub mod tests {
         let app = test::init_service(
             App::new()
                 .app_data(Data::new(env::testing::DB_THREAD_POOL.clone()))
+                .app_data(Data::new(env::testing::async_db_pools()))
+                .configure(env::testing::configure_daos)
                 .app_data(Data::new(env::testing::SMTP_THREAD_POOL.clone()))
-                .app_data(ProtoBufConfig::default())
+                .app_data(ProtoOrJsonConfig::default())
                 .configure(|cfg| crate::services::api::configure(cfg, RouteLimiters::default())),
         )
         .await;
@@ -2582,6 +2958,8 @@ pub mod tests {
             entry_version_nonce: rand::thread_rng().gen(),
             category_encrypted_blob: gen_bytes(14),
             category_version_nonce: rand::thread_rng().gen(),
+            entry_id: None,
+            category_id: None,
         };
 
         let req = TestRequest::post()
@@ -2899,8 +3277,10 @@ pub mod tests {
         let app = test::init_service(
             App::new()
                 .app_data(Data::new(env::testing::DB_THREAD_POOL.clone()))
+                .app_data(Data::new(env::testing::async_db_pools()))
+                .configure(env::testing::configure_daos)
                 .app_data(Data::new(env::testing::SMTP_THREAD_POOL.clone()))
-                .app_data(ProtoBufConfig::default())
+                .app_data(ProtoOrJsonConfig::default())
                 .configure(|cfg| crate::services::api::configure(cfg, RouteLimiters::default())),
         )
         .await;
@@ -3120,8 +3500,10 @@ pub mod tests {
         let app = test::init_service(
             App::new()
                 .app_data(Data::new(env::testing::DB_THREAD_POOL.clone()))
+                .app_data(Data::new(env::testing::async_db_pools()))
+                .configure(env::testing::configure_daos)
                 .app_data(Data::new(env::testing::SMTP_THREAD_POOL.clone()))
-                .app_data(ProtoBufConfig::default())
+                .app_data(ProtoOrJsonConfig::default())
                 .configure(|cfg| crate::services::api::configure(cfg, RouteLimiters::default())),
         )
         .await;
@@ -3548,8 +3930,10 @@ pub mod tests {
         let app = test::init_service(
             App::new()
                 .app_data(Data::new(env::testing::DB_THREAD_POOL.clone()))
+                .app_data(Data::new(env::testing::async_db_pools()))
+                .configure(env::testing::configure_daos)
                 .app_data(Data::new(env::testing::SMTP_THREAD_POOL.clone()))
-                .app_data(ProtoBufConfig::default())
+                .app_data(ProtoOrJsonConfig::default())
                 .configure(|cfg| crate::services::api::configure(cfg, RouteLimiters::default())),
         )
         .await;
@@ -3672,8 +4056,10 @@ pub mod tests {
         let app = test::init_service(
             App::new()
                 .app_data(Data::new(env::testing::DB_THREAD_POOL.clone()))
+                .app_data(Data::new(env::testing::async_db_pools()))
+                .configure(env::testing::configure_daos)
                 .app_data(Data::new(env::testing::SMTP_THREAD_POOL.clone()))
-                .app_data(ProtoBufConfig::default())
+                .app_data(ProtoOrJsonConfig::default())
                 .configure(|cfg| crate::services::api::configure(cfg, RouteLimiters::default())),
         )
         .await;
@@ -0,0 +1,42 @@
+use flexi_logger::DeferredNow;
+use log::Record;
+use serde_json::json;
+use std::io::Write;
+
+use crate::middleware::request_id;
+
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.6fZ";
+
+pub fn text_format(
+    writer: &mut dyn Write,
+    now: &mut DeferredNow,
+    record: &Record,
+) -> std::io::Result<()> {
+    write!(
+        writer,
+        "{:5} | {} | {}:{} | {} | {}",
+        record.level(),
+        now.format(TIMESTAMP_FORMAT),
+        record.module_path().unwrap_or("<unknown>"),
+        record.line().unwrap_or(0),
+        request_id::current().as_deref().unwrap_or("-"),
+        record.args()
+    )
+}
+
+pub fn json_format(
+    writer: &mut dyn Write,
+    now: &mut DeferredNow,
+    record: &Record,
+) -> std::io::Result<()> {
+    let line = json!({
+        "level": record.level().as_str(),
+        "timestamp": now.format(TIMESTAMP_FORMAT).to_string(),
+        "module": record.module_path().unwrap_or("<unknown>"),
+        "line": record.line().unwrap_or(0),
+        "request_id": request_id::current().as_deref(),
+        "message": record.args().to_string(),
+    });
+
+    write!(writer, "{line}")
+}
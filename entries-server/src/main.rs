@@ -1,69 +1,61 @@
-use entries_common::db::create_db_thread_pool;
+use entries_common::config;
+use entries_common::db::migrations::MigrationsDao;
+use entries_common::db::AsyncDbPools;
+use entries_common::db::{
+    self, create_async_db_pool, create_db_thread_pool, create_db_thread_pool_with_event_handler,
+};
 use entries_common::email::senders::{AmazonSes, MockSender};
 use entries_common::email::SendEmail;
 
-use actix_protobuf::ProtoBufConfig;
 use actix_web::web::Data;
 use actix_web::{App, HttpServer};
+use clap::Parser;
 use flexi_logger::{Age, Cleanup, Criterion, Duplicate, FileSpec, Logger, Naming, WriteMode};
+use std::net::SocketAddr;
+use std::path::Path;
 use std::sync::Arc;
+use tokio::sync::oneshot;
 use zeroize::Zeroizing;
 
+mod change_feed;
+mod cli;
+mod daos;
 mod env;
+mod grpc;
 mod handlers;
+mod logging;
+mod metrics;
 mod middleware;
 mod services;
+mod shutdown;
+mod systemd;
+mod telemetry;
+mod tls;
 
+use daos::Daos;
+use env::{DbBackend, LogFormat};
+use middleware::app_version::ClientVersionCheck;
+use middleware::https::Https;
+use middleware::proto_or_json::{ContentNegotiation, ProtoOrJsonConfig};
+use middleware::read_your_writes::ReadYourWrites;
+use middleware::request_id::RequestId;
 use services::api::RouteLimiters;
+use services::web::WebFiles;
+use systemd::ActivatedSocket;
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
-    let mut port = 9000u16;
+    let args = cli::Args::parse();
 
-    let mut args = std::env::args();
-
-    // Eat the first argument, which is the relative path to the executable
-    args.next();
-
-    while let Some(arg) = args.next() {
-        match arg.to_lowercase().as_str() {
-            "--port" => {
-                let port_str = {
-                    let next_arg = args.next();
-
-                    match next_arg {
-                        Some(s) => s,
-                        None => {
-                            eprintln!("ERROR: --port option specified but no port was given");
-                            std::process::exit(1);
-                        }
-                    }
-                };
-
-                port = {
-                    let port_result = port_str.parse::<u16>();
-
-                    match port_result {
-                        Ok(p) => p,
-                        Err(_) => {
-                            eprintln!("ERROR: Incorrect format for port. Integer expected");
-                            std::process::exit(1);
-                        }
-                    }
-                };
-
-                continue;
-            }
-            a => {
-                eprintln!("ERROR: Invalid argument: {}", &a);
-                std::process::exit(1);
-            }
-        }
+    if let Some(config_path) = &args.config {
+        std::env::set_var(config::CONFIG_FILE_VAR, config_path);
     }
 
-    let base_addr = format!("127.0.0.1:{}", &port);
+    if args.check_config {
+        std::process::exit(if env::check_config() { 0 } else { 1 });
+    }
 
-    let _logger = Logger::try_with_str(&env::CONF.log_level)
+    let logger = Logger::try_with_str(&env::CONF.log_level)
         .expect(
             "Invalid log level. Options: ERROR, WARN, INFO, DEBUG, TRACE. \
              Example: `info, my::critical::module=trace`",
@@ -77,40 +69,92 @@ async fn main() -> std::io::Result<()> {
         .cleanup_in_background_thread(true)
         .duplicate_to_stdout(Duplicate::All)
         .write_mode(WriteMode::Async)
-        .format(|writer, now, record| {
-            write!(
-                writer,
-                "{:5} | {} | {}:{} | {}",
-                record.level(),
-                now.format("%Y-%m-%dT%H:%M:%S%.6fZ"),
-                record.module_path().unwrap_or("<unknown>"),
-                record.line().unwrap_or(0),
-                record.args()
-            )
+        .format(match env::CONF.log_format {
+            LogFormat::Text => logging::text_format,
+            LogFormat::Json => logging::json_format,
         })
         .use_utc()
         .start()
         .expect("Failed to start logger");
 
-    log::info!("Connecting to database...");
+    metrics::init();
+    let tracer_provider = telemetry::init().expect("Failed to initialize tracing");
 
-    let db_uri = Zeroizing::new(format!(
-        "postgres://{}:{}@{}:{}/{}",
-        env::CONF.db_username,
-        env::CONF.db_password,
-        env::CONF.db_hostname,
-        env::CONF.db_port,
-        env::CONF.db_name,
-    ));
+    log::info!("Connecting to database...");
 
-    let db_thread_pool = create_db_thread_pool(
-        &db_uri,
-        env::CONF.db_max_connections,
-        env::CONF.db_idle_timeout,
-    );
+    let (daos, migrations_dao) = match env::CONF.db_backend {
+        DbBackend::Postgres => connect_to_postgres(),
+        #[cfg(feature = "sqlite")]
+        DbBackend::Sqlite => open_sqlite_database(),
+    };
 
     log::info!("Successfully connected to database");
 
+    if let Some(cli::Command::Migrate { dry_run }) = args.command {
+        let result = if dry_run {
+            migrations_dao.get_pending_migrations()
+        } else {
+            migrations_dao.run_pending_migrations()
+        };
+
+        let exit_code = match result {
+            Ok(migrations) if migrations.is_empty() => {
+                log::info!("The database schema is up to date");
+                0
+            }
+            Ok(migrations) => {
+                for name in migrations {
+                    if dry_run {
+                        log::info!("Pending migration: {name}");
+                    } else {
+                        log::info!("Applied migration: {name}");
+                    }
+                }
+
+                0
+            }
+            Err(e) => {
+                log::error!("Failed to migrate database: {e}");
+                1
+            }
+        };
+
+        logger.flush();
+        std::process::exit(exit_code);
+    }
+
+    let pending_migrations = migrations_dao
+        .get_pending_migrations()
+        .expect("Failed to check for pending database migrations");
+
+    if !pending_migrations.is_empty() {
+        for name in &pending_migrations {
+            log::warn!("Pending migration: {name}");
+        }
+
+        if args.run_migrations || env::CONF.run_migrations_on_startup {
+            log::info!("Applying database migrations...");
+
+            for name in migrations_dao
+                .run_pending_migrations()
+                .expect("Failed to migrate database")
+            {
+                log::info!("Applied migration: {name}");
+            }
+        } else {
+            log::error!(
+                "The database schema is behind this server. Run `entries_server migrate` or \
+                 start the server with --run-migrations."
+            );
+
+            logger.flush();
+            std::process::exit(1);
+        }
+    }
+
+    // Closes the migrations connection, which isn't needed once the schema is up to date
+    drop(migrations_dao);
+
     let smtp_thread_pool: Arc<Box<dyn SendEmail>> = if env::CONF.email_enabled {
         log::info!("Connecting to SMTP relay...");
 
@@ -131,37 +175,353 @@ async fn main() -> std::io::Result<()> {
 
         log::info!("Successfully connected to SMTP relay");
 
-        Arc::new(Box::new(smtp_thread_pool))
+        Arc::new(Box::new(metrics::MeteredSender::new(Box::new(
+            smtp_thread_pool,
+        ))))
     } else {
         log::info!("Emails are disabled. Using mock SMTP thread pool.");
-        Arc::new(Box::new(MockSender::new()))
+        Arc::new(Box::new(metrics::MeteredSender::new(Box::new(
+            MockSender::new(),
+        ))))
     };
 
-    let db_thread_pool = Data::new(db_thread_pool);
     let smtp_thread_pool = Data::new(smtp_thread_pool);
 
+    let activated_sockets = systemd::activated_sockets()?;
+    let bind_addresses = args.bind_addresses(&env::CONF.bind_addresses);
+    let unix_socket_path = args.unix_socket_path(env::CONF.unix_socket_path.as_deref());
+
+    // Requests over plain HTTP are redirected to the port of the first TCP listener
+    let https_port = if activated_sockets.is_empty() {
+        bind_addresses.first().map(SocketAddr::port)
+    } else {
+        activated_sockets.iter().find_map(|s| match s {
+            ActivatedSocket::Tcp(l) => l.local_addr().ok().map(|a| a.port()),
+            #[cfg(unix)]
+            ActivatedSocket::Unix(_) => None,
+        })
+    }
+    .unwrap_or(443);
+
+    let tls_config = if env::CONF.tls_enabled {
+        let cert_resolver = Arc::new(
+            tls::CertResolver::load(
+                env::CONF.tls_cert_path.as_deref().unwrap_or_default(),
+                env::CONF.tls_key_path.as_deref().unwrap_or_default(),
+            )
+            .expect("Failed to load TLS certificate"),
+        );
+        tls::spawn_reloader(Arc::clone(&cert_resolver), env::CONF.tls_reload_interval);
+
+        Some(tls::server_config(cert_resolver).expect("Failed to create TLS configuration"))
+    } else {
+        None
+    };
+
+    let web_files = env::CONF.web_dir.as_ref().map(|web_dir| {
+        if !Path::new(web_dir).is_dir() {
+            panic!("Web directory '{web_dir}' does not exist");
+        }
+
+        log::info!(
+            "Serving files from '{web_dir}' at {}",
+            env::CONF.web_mount_path
+        );
+
+        WebFiles::new(web_dir, env::CONF.web_cache_max_age)
+    });
+
     let limiters = RouteLimiters::default();
+    let read_your_writes = ReadYourWrites::new(env::CONF.db_read_your_writes_window);
+    let client_version_check = ClientVersionCheck::new(
+        &env::CONF.min_client_versions,
+        &env::CONF.recommended_client_versions,
+    );
+
+    let (grpc_shutdown, grpc_thread) = match env::CONF.grpc_bind_address {
+        Some(addr) => {
+            let daos = daos.clone();
+            let read_your_writes = read_your_writes.clone();
+            let smtp_thread_pool = smtp_thread_pool.clone();
+            let limiters = limiters.clone();
+            let client_version_check = client_version_check.clone();
 
-    HttpServer::new(move || {
-        let mut protobuf_config = ProtoBufConfig::default();
-        protobuf_config.limit(env::CONF.protobuf_max_size);
+            let (shutdown_sender, shutdown_receiver) = oneshot::channel();
+            let grpc_thread = grpc::start(
+                addr,
+                env::CONF.actix_worker_count,
+                move || {
+                    let mut body_config = ProtoOrJsonConfig::default();
+                    body_config.limit(env::CONF.protobuf_max_size);
+
+                    App::new()
+                        .app_data(body_config)
+                        .app_data(smtp_thread_pool.clone())
+                        .configure(|cfg| daos.configure(cfg))
+                        .configure(|cfg| services::api::configure(cfg, limiters.clone()))
+                        .configure(grpc::configure)
+                        .wrap(read_your_writes.clone())
+                        .wrap(client_version_check.clone())
+                        .wrap(actix_web::middleware::Logger::new(
+                            r#"%a "gRPC %r" %s %b %T %{X-Request-Id}o"#,
+                        ))
+                        .wrap(RequestId)
+                },
+                shutdown_receiver,
+            )?;
+
+            log::info!("Serving gRPC on {addr}");
+
+            (Some(shutdown_sender), Some(grpc_thread))
+        }
+        None => (None, None),
+    };
+
+    let server = HttpServer::new(move || {
+        let mut body_config = ProtoOrJsonConfig::default();
+        body_config.limit(env::CONF.protobuf_max_size);
 
         App::new()
-            .app_data(protobuf_config)
-            .app_data(db_thread_pool.clone())
+            .app_data(body_config)
             .app_data(smtp_thread_pool.clone())
+            .configure(|cfg| daos.configure(cfg))
             .configure(|cfg| services::api::configure(cfg, limiters.clone()))
+            .configure(services::metrics::configure)
+            .configure(|cfg| {
+                if let Some(web_files) = &web_files {
+                    services::web::configure(cfg, &env::CONF.web_mount_path, web_files.clone());
+                }
+            })
+            .wrap(read_your_writes.clone())
+            .wrap(client_version_check.clone())
+            .wrap(ContentNegotiation)
             .wrap(actix_web::middleware::Compress::default())
-            .wrap(actix_web::middleware::Logger::default())
+            .wrap(middleware::RequestMetrics)
+            .wrap(actix_web::middleware::Logger::new(
+                r#"%a "%r" %s %b "%{Referer}i" "%{User-Agent}i" %T %{X-Request-Id}o"#,
+            ))
+            .wrap(actix_web::middleware::Condition::new(
+                env::CONF.tls_enabled,
+                Https::new(https_port, env::CONF.hsts_max_age),
+            ))
+            .wrap(RequestId)
     })
     .workers(env::CONF.actix_worker_count)
-    .bind(base_addr)?
-    .run()
-    .await?;
+    .shutdown_timeout(env::CONF.shutdown_timeout.as_secs())
+    .disable_signals();
 
-    unsafe {
-        env::CONF.zeroize();
+    let mut server = server;
+    let mut created_socket_file = None;
+
+    if activated_sockets.is_empty() {
+        for addr in &bind_addresses {
+            server = match &tls_config {
+                Some(tls_config) => server.bind_rustls_0_23(addr, tls_config.clone())?,
+                None => server.bind(addr)?,
+            };
+        }
+
+        if let (Some(_), Some(redirect_port)) = (&tls_config, env::CONF.http_redirect_port) {
+            for addr in cli::redirect_addresses(&bind_addresses, redirect_port) {
+                server = server.bind(addr)?;
+            }
+        }
+
+        if let Some(path) = unix_socket_path {
+            // TLS is left to the proxy on the other end of the socket
+            #[cfg(unix)]
+            {
+                remove_stale_socket_file(path)?;
+                server = server.bind_uds(path)?;
+                created_socket_file = Some(path);
+            }
+
+            #[cfg(not(unix))]
+            panic!("Unix domain sockets are not supported on this platform: {path}");
+        }
+    } else {
+        log::info!(
+            "Listening on {} socket(s) passed by systemd. Configured bind addresses are ignored.",
+            activated_sockets.len(),
+        );
+
+        for socket in activated_sockets {
+            server = match socket {
+                ActivatedSocket::Tcp(listener) => match &tls_config {
+                    Some(tls_config) => server.listen_rustls_0_23(listener, tls_config.clone())?,
+                    None => server.listen(listener)?,
+                },
+                #[cfg(unix)]
+                ActivatedSocket::Unix(listener) => server.listen_uds(listener)?,
+            };
+        }
+    }
+
+    let server = server.run();
+
+    let server_handle = server.handle();
+    actix_web::rt::spawn(async move {
+        entries_common::shutdown::wait_for_signal().await;
+
+        log::info!("Shutdown signal received. Marking server as not ready...");
+        shutdown::begin();
+        actix_web::rt::time::sleep(env::CONF.shutdown_readiness_delay).await;
+
+        log::info!("Waiting for in-flight requests to finish...");
+        if let Some(grpc_shutdown) = grpc_shutdown {
+            let _ = grpc_shutdown.send(());
+        }
+
+        server_handle.stop(true).await;
+    });
+
+    server.await?;
+
+    if let Some(grpc_thread) = grpc_thread {
+        if grpc_thread.join().is_err() {
+            log::error!("gRPC server thread panicked");
+        }
+    }
+
+    let unfinished_tasks = shutdown::wait_for_tasks(env::CONF.shutdown_timeout).await;
+    if unfinished_tasks > 0 {
+        log::warn!(
+            "Shutting down with {unfinished_tasks} background task(s) still running. \
+             The config will not be zeroized because the tasks may still be reading it."
+        );
+    }
+
+    if let Some(path) = created_socket_file {
+        // actix usually removes the file itself when the listener is closed
+        match std::fs::remove_file(path) {
+            Ok(()) => (),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => (),
+            Err(e) => log::error!("Failed to remove Unix socket file '{path}': {e}"),
+        }
+    }
+
+    log::info!("Server stopped");
+
+    if let Some(tracer_provider) = tracer_provider {
+        if let Err(e) = tracer_provider.shutdown() {
+            log::error!("Failed to flush traces: {e}");
+        }
+    }
+
+    logger.flush();
+    logger.shutdown();
+
+    // The workers have stopped, every background task has finished, and the logger has shut
+    // down, so nothing else can be reading the config
+    if unfinished_tasks == 0 {
+        unsafe {
+            env::CONF.zeroize();
+        }
     }
 
     Ok(())
 }
+
+/// Removes a Unix socket file left behind by a server that didn't shut down cleanly. Binding
+/// replaces whatever is at the path, so this fails instead if the file isn't a socket or another
+/// process is still listening on it.
+#[cfg(unix)]
+fn remove_stale_socket_file(path: &str) -> std::io::Result<()> {
+    use std::io::{Error, ErrorKind};
+    use std::os::unix::fs::FileTypeExt;
+    use std::os::unix::net::UnixStream;
+
+    let metadata = match std::fs::symlink_metadata(path) {
+        Ok(m) => m,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e),
+    };
+
+    if !metadata.file_type().is_socket() {
+        return Err(Error::new(
+            ErrorKind::AlreadyExists,
+            format!("'{path}' already exists and is not a Unix socket"),
+        ));
+    }
+
+    if UnixStream::connect(path).is_ok() {
+        return Err(Error::new(
+            ErrorKind::AddrInUse,
+            format!("Another process is listening on '{path}'"),
+        ));
+    }
+
+    log::info!("Removing stale Unix socket file '{path}'");
+    std::fs::remove_file(path)
+}
+
+fn connect_to_postgres() -> (Daos, Box<dyn MigrationsDao>) {
+    let db_uri = Zeroizing::new(format!(
+        "postgres://{}:{}@{}:{}/{}",
+        env::CONF.db_username,
+        env::CONF.db_password,
+        env::CONF.db_hostname,
+        env::CONF.db_port,
+        env::CONF.db_name,
+    ));
+
+    let db_thread_pool = create_db_thread_pool_with_event_handler(
+        &db_uri,
+        env::CONF.db_max_connections,
+        env::CONF.db_idle_timeout,
+        Some(env::CONF.db_statement_timeout),
+        metrics::DbPoolEventHandler,
+    );
+    let async_db_pool = create_async_db_pool(
+        &db_uri,
+        env::CONF.db_async_max_connections,
+        Some(env::CONF.db_statement_timeout),
+    );
+
+    let async_db_replica_pool = env::CONF.db_replica_hostname.as_ref().map(|hostname| {
+        let replica_uri = Zeroizing::new(format!(
+            "postgres://{}:{}@{}:{}/{}",
+            env::CONF.db_username,
+            env::CONF.db_password,
+            hostname,
+            env::CONF.db_replica_port.unwrap_or(env::CONF.db_port),
+            env::CONF.db_name,
+        ));
+
+        log::info!("Routing read-only queries to replica at {hostname}");
+        create_async_db_pool(
+            &replica_uri,
+            env::CONF.db_async_max_connections,
+            Some(env::CONF.db_statement_timeout),
+        )
+    });
+
+    // Migrations can rewrite whole tables and may wait on another instance's migration lock, so
+    // they get their own connection without the statement timeout
+    let migrations_db_thread_pool =
+        create_db_thread_pool(&db_uri, 1, env::CONF.db_idle_timeout, None);
+    let migrations_dao = db::migrations::Dao::new(&migrations_db_thread_pool);
+    let async_db_pools = AsyncDbPools::new(async_db_pool, async_db_replica_pool);
+
+    (
+        Daos::postgres(&db_thread_pool, &async_db_pools),
+        Box::new(migrations_dao),
+    )
+}
+
+#[cfg(feature = "sqlite")]
+fn open_sqlite_database() -> (Daos, Box<dyn MigrationsDao>) {
+    log::info!("Using SQLite database at '{}'", env::CONF.db_sqlite_path);
+
+    // Waiting on another writer is bounded by the same timeout as a Postgres statement
+    let db_pool = db::sqlite::create_db_pool(
+        &env::CONF.db_sqlite_path,
+        env::CONF.db_max_connections,
+        env::CONF.db_statement_timeout,
+    );
+
+    (
+        Daos::sqlite(&db_pool),
+        Box::new(db::sqlite::Dao::new(&db_pool)),
+    )
+}
@@ -0,0 +1,192 @@
+use entries_common::email::{EmailError, EmailMessage, SendEmail};
+
+use async_trait::async_trait;
+use diesel::r2d2::event::{CheckoutEvent, TimeoutEvent};
+use diesel::r2d2::HandleEvent;
+use once_cell::sync::Lazy;
+use prometheus::{
+    Encoder, Histogram, HistogramOpts, HistogramVec, IntCounter, IntCounterVec, IntGauge, Opts,
+    Registry, TextEncoder,
+};
+
+const HTTP_DURATION_BUCKETS: &[f64] = &[
+    0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+const DB_POOL_WAIT_BUCKETS: &[f64] = &[
+    0.0001, 0.0005, 0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.5, 1.0, 5.0, 30.0,
+];
+const ARGON2_DURATION_BUCKETS: &[f64] = &[
+    0.01, 0.05, 0.1, 0.25, 0.5, 0.75, 1.0, 1.5, 2.0, 3.0, 5.0, 10.0,
+];
+
+pub static REGISTRY: Lazy<Registry> = Lazy::new(Registry::new);
+
+pub static HTTP_REQUESTS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register(
+        IntCounterVec::new(
+            Opts::new(
+                "entries_http_requests_total",
+                "Number of HTTP requests handled",
+            ),
+            &["route", "method", "status"],
+        )
+        .expect("Invalid metric"),
+    )
+});
+
+pub static HTTP_REQUEST_DURATION: Lazy<HistogramVec> = Lazy::new(|| {
+    register(
+        HistogramVec::new(
+            HistogramOpts::new(
+                "entries_http_request_duration_seconds",
+                "Time taken to handle HTTP requests",
+            )
+            .buckets(Vec::from(HTTP_DURATION_BUCKETS)),
+            &["route", "method", "status"],
+        )
+        .expect("Invalid metric"),
+    )
+});
+
+pub static LIMITER_REJECTIONS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register(
+        IntCounterVec::new(
+            Opts::new(
+                "entries_limiter_rejections_total",
+                "Number of requests rejected by a rate limiter",
+            ),
+            &["route"],
+        )
+        .expect("Invalid metric"),
+    )
+});
+
+pub static DB_POOL_WAIT: Lazy<Histogram> = Lazy::new(|| {
+    register(
+        Histogram::with_opts(
+            HistogramOpts::new(
+                "entries_db_pool_wait_seconds",
+                "Time spent waiting to check out a connection from the DB thread pool",
+            )
+            .buckets(Vec::from(DB_POOL_WAIT_BUCKETS)),
+        )
+        .expect("Invalid metric"),
+    )
+});
+
+pub static DB_POOL_TIMEOUTS: Lazy<IntCounter> = Lazy::new(|| {
+    register(
+        IntCounter::new(
+            "entries_db_pool_timeouts_total",
+            "Number of DB connection checkouts that timed out",
+        )
+        .expect("Invalid metric"),
+    )
+});
+
+pub static BLOCKING_QUEUE_DEPTH: Lazy<IntGauge> = Lazy::new(|| {
+    register(
+        IntGauge::new(
+            "entries_blocking_queue_depth",
+            "Number of closures waiting for a thread in the blocking thread pool",
+        )
+        .expect("Invalid metric"),
+    )
+});
+
+pub static EMAILS_SENT: Lazy<IntCounterVec> = Lazy::new(|| {
+    register(
+        IntCounterVec::new(
+            Opts::new(
+                "entries_emails_sent_total",
+                "Number of attempted email sends",
+            ),
+            &["result"],
+        )
+        .expect("Invalid metric"),
+    )
+});
+
+pub static ARGON2_DURATION: Lazy<HistogramVec> = Lazy::new(|| {
+    register(
+        HistogramVec::new(
+            HistogramOpts::new(
+                "entries_argon2_duration_seconds",
+                "Time taken to compute Argon2 hashes",
+            )
+            .buckets(Vec::from(ARGON2_DURATION_BUCKETS)),
+            &["operation"],
+        )
+        .expect("Invalid metric"),
+    )
+});
+
+fn register<T>(metric: T) -> T
+where
+    T: prometheus::core::Collector + Clone + 'static,
+{
+    REGISTRY
+        .register(Box::new(metric.clone()))
+        .expect("Metric registered more than once");
+    metric
+}
+
+/// Registers all metrics so they are exported (with zero values) before they are first
+/// recorded.
+pub fn init() {
+    Lazy::force(&HTTP_REQUESTS);
+    Lazy::force(&HTTP_REQUEST_DURATION);
+    Lazy::force(&LIMITER_REJECTIONS);
+    Lazy::force(&DB_POOL_WAIT);
+    Lazy::force(&DB_POOL_TIMEOUTS);
+    Lazy::force(&BLOCKING_QUEUE_DEPTH);
+    Lazy::force(&EMAILS_SENT);
+    Lazy::force(&ARGON2_DURATION);
+}
+
+pub fn encode() -> Result<String, prometheus::Error> {
+    let mut buffer = Vec::new();
+    TextEncoder::new().encode(&REGISTRY.gather(), &mut buffer)?;
+
+    // The text encoder only ever outputs UTF-8
+    Ok(String::from_utf8(buffer).expect("Prometheus output should be valid UTF-8"))
+}
+
+#[derive(Debug)]
+pub struct DbPoolEventHandler;
+
+impl HandleEvent for DbPoolEventHandler {
+    fn handle_checkout(&self, event: CheckoutEvent) {
+        DB_POOL_WAIT.observe(event.duration().as_secs_f64());
+    }
+
+    fn handle_timeout(&self, _event: TimeoutEvent) {
+        DB_POOL_TIMEOUTS.inc();
+    }
+}
+
+pub struct MeteredSender {
+    sender: Box<dyn SendEmail>,
+}
+
+impl MeteredSender {
+    pub fn new(sender: Box<dyn SendEmail>) -> Self {
+        Self { sender }
+    }
+}
+
+#[async_trait]
+impl SendEmail for MeteredSender {
+    async fn send<'a>(&self, message: EmailMessage<'a>) -> Result<(), EmailError> {
+        let result = self.sender.send(message).await;
+
+        let label = if result.is_ok() { "success" } else { "failure" };
+        EMAILS_SENT.with_label_values(&[label]).inc();
+
+        result
+    }
+
+    async fn test_connection(&self) -> Result<bool, EmailError> {
+        self.sender.test_connection().await
+    }
+}
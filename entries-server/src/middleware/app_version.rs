@@ -1,9 +1,18 @@
-use actix_web::dev::Payload;
+use actix_web::dev::{forward_ready, Payload, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::http::header::{self, HeaderValue};
 use actix_web::{FromRequest, HttpRequest};
-use futures::future;
+use futures::future::{self, LocalBoxFuture};
+use semver::Version;
+use std::collections::HashMap;
+use std::future::{ready, Ready};
+use std::sync::Arc;
 
+use crate::env::PlatformVersion;
 use crate::handlers::error::HttpErrorResponse;
 
+const APP_VERSION_HEADER: &str = "AppVersion";
+const ANY_PLATFORM: &str = "*";
+
 #[derive(Debug)]
 #[allow(dead_code)]
 pub struct AppVersion(pub String);
@@ -15,7 +24,7 @@ impl FromRequest for AppVersion {
     fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
         const NO_VERSION_HEADER_MESSAGE: &str = "AppVersion header is missing or invalid";
 
-        let app_version = match req.headers().get("AppVersion") {
+        let app_version = match req.headers().get(APP_VERSION_HEADER) {
             Some(header) => header,
             None => {
                 return future::err(HttpErrorResponse::MissingHeader(String::from(
@@ -43,12 +52,179 @@ impl FromRequest for AppVersion {
     }
 }
 
+#[derive(Clone, Debug, Default)]
+struct VersionRequirement {
+    minimum: Option<Version>,
+    recommended: Option<Version>,
+}
+
+/// Rejects requests from clients older than the minimum version for their platform and adds a
+/// `Warning` header to responses for clients older than the recommended version, giving users a
+/// grace period to update before the minimum is raised.
+///
+/// The `AppVersion` header is either a semantic version (`1.4.2`) or a platform and a semantic
+/// version (`ios/1.4.2`). Requests without the header aren't checked, so browsers and health
+/// checks are unaffected.
+#[derive(Clone)]
+pub struct ClientVersionCheck {
+    requirements: Arc<HashMap<String, VersionRequirement>>,
+}
+
+impl ClientVersionCheck {
+    pub fn new(minimum: &[PlatformVersion], recommended: &[PlatformVersion]) -> Self {
+        let mut requirements = HashMap::<String, VersionRequirement>::new();
+
+        for min in minimum {
+            requirements
+                .entry(min.platform.clone())
+                .or_default()
+                .minimum = Some(min.version.clone());
+        }
+
+        for rec in recommended {
+            requirements
+                .entry(rec.platform.clone())
+                .or_default()
+                .recommended = Some(rec.version.clone());
+        }
+
+        Self {
+            requirements: Arc::new(requirements),
+        }
+    }
+
+    /// Returns the warning to attach to the response, if any
+    fn check(
+        &self,
+        app_version: Option<&HeaderValue>,
+    ) -> Result<Option<String>, HttpErrorResponse> {
+        if self.requirements.is_empty() {
+            return Ok(None);
+        }
+
+        let Some(app_version) = app_version else {
+            return Ok(None);
+        };
+
+        let Some((platform, version)) = app_version.to_str().ok().and_then(parse_app_version)
+        else {
+            return Err(HttpErrorResponse::IncorrectlyFormed(String::from(
+                "AppVersion header must be a semantic version, optionally prefixed by a platform \
+                 (e.g. ios/1.4.2)",
+            )));
+        };
+
+        let requirement = platform
+            .and_then(|p| self.requirements.get(&p.to_lowercase()))
+            .or_else(|| self.requirements.get(ANY_PLATFORM));
+
+        let Some(requirement) = requirement else {
+            return Ok(None);
+        };
+
+        if let Some(minimum) = &requirement.minimum {
+            if version < *minimum {
+                return Err(HttpErrorResponse::ClientUpdateRequired(format!(
+                    "Version {version} is no longer supported. Update to {minimum} or later."
+                )));
+            }
+        }
+
+        if let Some(recommended) = &requirement.recommended {
+            if version < *recommended {
+                return Ok(Some(format!(
+                    "Version {version} is deprecated and will stop working in a future \
+                     release. Update to {recommended} or later."
+                )));
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+fn parse_app_version(app_version: &str) -> Option<(Option<&str>, Version)> {
+    let (platform, version) = match app_version.split_once('/') {
+        Some((platform, version)) if !platform.is_empty() => (Some(platform), version),
+        Some(_) => return None,
+        None => (None, app_version),
+    };
+
+    Some((platform, Version::parse(version).ok()?))
+}
+
+impl<S, B> Transform<S, ServiceRequest> for ClientVersionCheck
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = actix_web::Error;
+    type InitError = ();
+    type Transform = ClientVersionCheckMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(ClientVersionCheckMiddleware {
+            service,
+            check: self.clone(),
+        }))
+    }
+}
+
+pub struct ClientVersionCheckMiddleware<S> {
+    service: S,
+    check: ClientVersionCheck,
+}
+
+impl<S, B> Service<ServiceRequest> for ClientVersionCheckMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let warning = match self.check.check(req.headers().get(APP_VERSION_HEADER)) {
+            Ok(w) => w,
+            Err(e) => return Box::pin(ready(Err(e.into()))),
+        };
+
+        let req_fut = self.service.call(req);
+
+        Box::pin(async move {
+            let mut res = req_fut.await?;
+
+            // 299 is the "miscellaneous persistent warning" code. The agent is unknown (-).
+            if let Some(warning) = warning {
+                let warning = format!("299 - \"{warning}\"");
+                res.headers_mut().insert(
+                    header::WARNING,
+                    HeaderValue::from_str(&warning).expect("Warning header should be valid"),
+                );
+            }
+
+            Ok(res)
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     use actix_web::dev::Payload;
-    use actix_web::test::TestRequest;
+    use actix_web::http::StatusCode;
+    use actix_web::test::{self, TestRequest};
+    use actix_web::{web, App, HttpResponse};
+    use entries_common::messages::{ErrorType, ServerErrorResponse};
+    use prost::Message;
     use rand::{thread_rng, Rng};
 
     #[actix_web::test]
@@ -74,4 +250,103 @@ mod tests {
             .await
             .is_err());
     }
+
+    #[test]
+    fn test_parse_app_version() {
+        assert_eq!(
+            parse_app_version("ios/1.4.2"),
+            Some((Some("ios"), Version::new(1, 4, 2)))
+        );
+        assert_eq!(
+            parse_app_version("2.0.0-beta.1"),
+            Some((None, Version::parse("2.0.0-beta.1").unwrap()))
+        );
+
+        assert_eq!(parse_app_version("ios/1.4"), None);
+        assert_eq!(parse_app_version("/1.4.2"), None);
+        assert_eq!(parse_app_version("ios/"), None);
+        assert_eq!(parse_app_version("latest"), None);
+    }
+
+    #[actix_web::test]
+    async fn test_client_version_check() {
+        let minimum = ["ios=1.4.0".parse().unwrap(), "*=1.0.0".parse().unwrap()];
+        let recommended = ["ios=1.6.0".parse().unwrap()];
+
+        let app = test::init_service(
+            App::new()
+                .route("/", web::get().to(HttpResponse::Ok))
+                .wrap(ClientVersionCheck::new(&minimum, &recommended)),
+        )
+        .await;
+
+        for app_version in [None, Some("ios/1.6.0"), Some("IOS/2.0.0"), Some("1.0.0")] {
+            let mut req = TestRequest::get().uri("/");
+            if let Some(app_version) = app_version {
+                req = req.insert_header((APP_VERSION_HEADER, app_version));
+            }
+
+            let resp = test::call_service(&app, req.to_request()).await;
+            assert_eq!(resp.status(), StatusCode::OK, "{app_version:?}");
+            assert!(resp.headers().get(header::WARNING).is_none());
+        }
+
+        let req = TestRequest::get()
+            .uri("/")
+            .insert_header((APP_VERSION_HEADER, "ios/1.5.9"))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let warning = resp
+            .headers()
+            .get(header::WARNING)
+            .unwrap()
+            .to_str()
+            .unwrap();
+        assert!(warning.starts_with("299 - \"Version 1.5.9 is deprecated"));
+
+        // Platforms without their own minimum fall back to `*`
+        for app_version in ["ios/1.3.9", "ios/1.4.0-beta.1", "android/0.9.0", "0.9.0"] {
+            let req = TestRequest::get()
+                .uri("/")
+                .insert_header((APP_VERSION_HEADER, app_version))
+                .to_request();
+            let resp = test::try_call_service(&app, req)
+                .await
+                .unwrap_err()
+                .error_response();
+            assert_eq!(resp.status(), StatusCode::UPGRADE_REQUIRED, "{app_version}");
+
+            let body = actix_web::body::to_bytes(resp.into_body()).await.unwrap();
+            let resp_err = ServerErrorResponse::decode(body).unwrap();
+            assert_eq!(resp_err.err_type, ErrorType::ClientUpdateRequired as i32);
+        }
+
+        let req = TestRequest::get()
+            .uri("/")
+            .insert_header((APP_VERSION_HEADER, "ios/one"))
+            .to_request();
+        let resp = test::try_call_service(&app, req)
+            .await
+            .unwrap_err()
+            .error_response();
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[actix_web::test]
+    async fn test_client_version_check_without_requirements() {
+        let app = test::init_service(
+            App::new()
+                .route("/", web::get().to(HttpResponse::Ok))
+                .wrap(ClientVersionCheck::new(&[], &[])),
+        )
+        .await;
+
+        let req = TestRequest::get()
+            .uri("/")
+            .insert_header((APP_VERSION_HEADER, "not a version"))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
 }
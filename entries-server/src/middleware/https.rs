@@ -0,0 +1,182 @@
+use std::future::{ready, Ready};
+use std::time::Duration;
+
+use actix_web::dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::error::InternalError;
+use actix_web::http::header::{self, HeaderValue};
+use actix_web::HttpResponse;
+use futures::future::LocalBoxFuture;
+
+/// Redirects requests that arrive over plain HTTP to HTTPS and, if an HSTS max age is given,
+/// adds a `Strict-Transport-Security` header to responses sent over HTTPS. Only needed when
+/// the server terminates TLS itself.
+#[derive(Clone)]
+pub struct Https {
+    https_port: u16,
+    hsts_header: Option<HeaderValue>,
+}
+
+impl Https {
+    pub fn new(https_port: u16, hsts_max_age: Option<Duration>) -> Self {
+        let hsts_header = hsts_max_age.map(|max_age| {
+            HeaderValue::from_str(&format!("max-age={}", max_age.as_secs()))
+                .expect("HSTS header should be valid")
+        });
+
+        Self {
+            https_port,
+            hsts_header,
+        }
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for Https
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = actix_web::Error;
+    type InitError = ();
+    type Transform = HttpsMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(HttpsMiddleware {
+            service,
+            https_port: self.https_port,
+            hsts_header: self.hsts_header.clone(),
+        }))
+    }
+}
+
+pub struct HttpsMiddleware<S> {
+    service: S,
+    https_port: u16,
+    hsts_header: Option<HeaderValue>,
+}
+
+impl<S, B> Service<ServiceRequest> for HttpsMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        // Requests over a Unix domain socket have no peer address. They come from a local proxy
+        // that is responsible for TLS, so they aren't redirected.
+        if !req.app_config().secure() && req.peer_addr().is_some() {
+            let location = https_url(
+                req.connection_info().host(),
+                self.https_port,
+                req.uri()
+                    .path_and_query()
+                    .map(|p| p.as_str())
+                    .unwrap_or("/"),
+            );
+
+            // A permanent redirect preserves the method and body, unlike 301
+            let redirect = HttpResponse::PermanentRedirect()
+                .insert_header((header::LOCATION, location))
+                .finish();
+
+            return Box::pin(ready(Err(InternalError::from_response(
+                "HTTPS required",
+                redirect,
+            )
+            .into())));
+        }
+
+        let hsts_header = self.hsts_header.clone();
+        let req_fut = self.service.call(req);
+
+        Box::pin(async move {
+            let mut res = req_fut.await?;
+
+            if let Some(hsts_header) = hsts_header {
+                res.headers_mut()
+                    .insert(header::STRICT_TRANSPORT_SECURITY, hsts_header);
+            }
+
+            Ok(res)
+        })
+    }
+}
+
+fn https_url(host: &str, https_port: u16, path_and_query: &str) -> String {
+    // Strip the port from the host, taking care not to split an IPv6 address
+    let hostname = match host.rfind(':') {
+        Some(i) if !host[i..].contains(']') => &host[..i],
+        _ => host,
+    };
+
+    if https_port == 443 {
+        format!("https://{hostname}{path_and_query}")
+    } else {
+        format!("https://{hostname}:{https_port}{path_and_query}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use actix_web::http::StatusCode;
+    use actix_web::test::{self, TestRequest};
+    use actix_web::{web, App};
+
+    #[test]
+    fn test_https_url() {
+        assert_eq!(
+            https_url("example.com", 443, "/api/a?b=c"),
+            "https://example.com/api/a?b=c"
+        );
+        assert_eq!(
+            https_url("example.com:8080", 8443, "/"),
+            "https://example.com:8443/"
+        );
+        assert_eq!(https_url("[::1]:8080", 443, "/x"), "https://[::1]/x");
+        assert_eq!(https_url("[::1]", 8443, "/x"), "https://[::1]:8443/x");
+    }
+
+    #[actix_web::test]
+    async fn test_https_redirect() {
+        // Test requests are never secure, so they should always be redirected
+        let app = test::init_service(
+            App::new()
+                .wrap(Https::new(8443, Some(Duration::from_secs(60))))
+                .route("/test", web::post().to(HttpResponse::Ok)),
+        )
+        .await;
+
+        let req = TestRequest::post()
+            .uri("/test?a=b")
+            .insert_header((header::HOST, "example.com:8080"))
+            .peer_addr("127.0.0.1:12345".parse().unwrap())
+            .to_request();
+        let resp = test::try_call_service(&app, req).await;
+
+        let resp = resp.unwrap_err().error_response();
+        assert_eq!(resp.status(), StatusCode::PERMANENT_REDIRECT);
+        assert_eq!(
+            resp.headers().get(header::LOCATION).unwrap(),
+            "https://example.com:8443/test?a=b"
+        );
+        assert!(resp
+            .headers()
+            .get(header::STRICT_TRANSPORT_SECURITY)
+            .is_none());
+
+        // Requests without a peer address came through a Unix domain socket
+        let req = TestRequest::post().uri("/test").to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+}
@@ -0,0 +1,668 @@
+use entries_common::db::idempotency::{IdempotencyDao, KeyReservation};
+use entries_common::token::budget_access_token::BudgetAccessToken;
+
+use actix_web::body::{self, BoxBody, MessageBody};
+use actix_web::dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::http::header::{self, HeaderName, HeaderValue};
+use actix_web::http::StatusCode;
+use actix_web::web::{self, BytesMut};
+use actix_web::{HttpMessage, HttpResponse};
+use futures::future::LocalBoxFuture;
+use futures::StreamExt;
+use sha2::{Digest, Sha256};
+use std::future::{ready, Ready};
+use std::rc::Rc;
+use std::time::SystemTime;
+use uuid::Uuid;
+
+use crate::env;
+use crate::handlers::block_task;
+use crate::handlers::error::{HttpErrorResponse, SizeLimit};
+use crate::middleware::auth::{Access, VerifiedToken};
+use crate::middleware::special_access_token::SpecialAccessToken;
+use crate::middleware::FromHeader;
+
+const IDEMPOTENCY_KEY_HEADER: &str = "Idempotency-Key";
+const REPLAYED_HEADER: &str = "idempotent-replayed";
+const MAX_KEY_LEN: usize = 255;
+
+/// Lets clients safely retry requests that create something. When a request carries an
+/// `Idempotency-Key` header, the first successful response is saved for the key and sent again
+/// (with an `Idempotent-Replayed: true` header) in response to retries instead of running the
+/// handler a second time.
+///
+/// Keys are scoped to the user in the `AccessToken` header and expire after
+/// `ENTRIES_IDEMPOTENCY_KEY_LIFETIME_HOURS`. Reusing a key for a request with a different
+/// method, path, body, or budget (the one in the `BudgetAccessToken` header) is rejected, as is
+/// retrying while the original request is still being handled. A reservation that goes
+/// unanswered for longer than `RESERVATION_TIMEOUT` is assumed to have been abandoned and can be
+/// taken over. Error responses aren't saved, so a request that failed can be retried with the
+/// same key. Requests without the header (or without a valid access token) are passed through
+/// unchanged.
+#[derive(Clone, Copy, Default)]
+pub struct Idempotency;
+
+impl<S, B> Transform<S, ServiceRequest> for Idempotency
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error> + 'static,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<BoxBody>;
+    type Error = actix_web::Error;
+    type InitError = ();
+    type Transform = IdempotencyMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(IdempotencyMiddleware {
+            service: Rc::new(service),
+        }))
+    }
+}
+
+pub struct IdempotencyMiddleware<S> {
+    service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for IdempotencyMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error> + 'static,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<BoxBody>;
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = Rc::clone(&self.service);
+
+        Box::pin(async move {
+            let Some(key) = req.headers().get(IDEMPOTENCY_KEY_HEADER) else {
+                return Ok(service.call(req).await?.map_into_boxed_body());
+            };
+
+            let key = match parse_key(key) {
+                Ok(k) => k,
+                Err(e) => return Ok(req.error_response(e)),
+            };
+
+            handle_with_key(service, req, key).await
+        })
+    }
+}
+
+enum Reservation {
+    /// The request doesn't have a valid access token, so the handler will reject it anyway
+    NoUser,
+    Reserved {
+        user_id: Uuid,
+        idempotency_dao: web::Data<dyn IdempotencyDao>,
+    },
+    Replay(HttpResponse),
+}
+
+async fn handle_with_key<S, B>(
+    service: Rc<S>,
+    mut req: ServiceRequest,
+    key: String,
+) -> Result<ServiceResponse<BoxBody>, actix_web::Error>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error>,
+    B: MessageBody + 'static,
+{
+    let (user_id, idempotency_dao) = match reserve(&mut req, &key).await {
+        Ok(Reservation::NoUser) => return Ok(service.call(req).await?.map_into_boxed_body()),
+        Ok(Reservation::Reserved {
+            user_id,
+            idempotency_dao,
+        }) => (user_id, idempotency_dao),
+        Ok(Reservation::Replay(resp)) => return Ok(req.into_response(resp)),
+        Err(e) => return Ok(req.error_response(e)),
+    };
+
+    // Frees the key if this future is dropped before a response is saved, e.g. because the
+    // client disconnected
+    let mut reservation = ReservedKey {
+        idempotency_dao: Some(web::Data::clone(&idempotency_dao)),
+        user_id,
+        key: key.clone(),
+    };
+
+    let resp = match service.call(req).await {
+        Ok(resp) => resp,
+        Err(e) => {
+            reservation.release().await;
+            return Err(e);
+        }
+    };
+
+    if !resp.status().is_success() {
+        reservation.release().await;
+        return Ok(resp.map_into_boxed_body());
+    }
+
+    let (req, resp) = resp.into_parts();
+    let (resp, resp_body) = resp.into_parts();
+
+    let resp_body = match body::to_bytes(resp_body).await {
+        Ok(b) => b,
+        Err(e) => {
+            let e: Box<dyn std::error::Error> = e.into();
+            log::error!("Failed to read response body: {e}");
+            reservation.release().await;
+            return Ok(ServiceResponse::from_err(internal_error(), req));
+        }
+    };
+
+    let status = resp.status().as_u16() as i16;
+    let content_type = resp
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|c| c.to_str().ok())
+        .map(String::from);
+
+    let saved_body = resp_body.clone();
+    let save_result = block_task(move || {
+        idempotency_dao.save_response(user_id, &key, status, content_type.as_deref(), &saved_body)
+    })
+    .await;
+
+    // The request succeeded, so the client gets the response even if it couldn't be saved. The
+    // key is released in that case so a retry isn't stuck waiting on a response that will never
+    // be saved.
+    match save_result {
+        Ok(Ok(())) => reservation.idempotency_dao = None,
+        Ok(Err(e)) => {
+            log::error!("Failed to save idempotent response: {e}");
+            reservation.release().await;
+        }
+        Err(e) => {
+            log::error!("Failed to save idempotent response: {e}");
+            reservation.release().await;
+        }
+    }
+
+    Ok(ServiceResponse::new(
+        req,
+        resp.set_body(resp_body).map_into_boxed_body(),
+    ))
+}
+
+async fn reserve(req: &mut ServiceRequest, key: &str) -> Result<Reservation, HttpErrorResponse> {
+    let user_id = match req.extract::<VerifiedToken<Access, FromHeader>>().await {
+        Ok(token) => token.0.user_id,
+        Err(_) => return Ok(Reservation::NoUser),
+    };
+
+    let idempotency_dao = req
+        .app_data::<web::Data<dyn IdempotencyDao>>()
+        .expect("IdempotencyDao should be in app data")
+        .clone();
+
+    // The token itself may be signed again for a retry, so only the budget it is for counts
+    let budget_id = req
+        .extract::<SpecialAccessToken<BudgetAccessToken, FromHeader>>()
+        .await
+        .ok()
+        .map(|token| token.0.claims.budget_id);
+
+    let body = read_body(req).await?;
+    let request_hash = hash_request(req, budget_id, &body);
+
+    // The handler reads the body after it has been hashed
+    let (_, mut payload) = actix_http::h1::Payload::create(true);
+    payload.unread_data(body.freeze());
+    req.set_payload(payload.into());
+
+    let reservation = {
+        let idempotency_dao = web::Data::clone(&idempotency_dao);
+        let key = String::from(key);
+        let request_hash = request_hash.clone();
+        let expiration = SystemTime::now() + env::CONF.idempotency_key_lifetime;
+
+        block_task(move || idempotency_dao.reserve_key(user_id, &key, &request_hash, expiration))
+            .await
+            .map_err(|_| internal_error())?
+            .map_err(|e| {
+                log::error!("{e}");
+                internal_error()
+            })?
+    };
+
+    let existing = match reservation {
+        KeyReservation::Reserved => {
+            return Ok(Reservation::Reserved {
+                user_id,
+                idempotency_dao,
+            })
+        }
+        KeyReservation::Existing(existing) => existing,
+    };
+
+    if existing.request_hash != request_hash {
+        return Err(HttpErrorResponse::ConflictWithExisting(
+            String::from("Idempotency key was already used for a different request"),
+            None,
+        ));
+    }
+
+    let (Some(status), Some(body)) = (existing.response_status, existing.response_body) else {
+        return Err(HttpErrorResponse::ConflictWithExisting(
+            String::from("A request with this idempotency key is still being processed"),
+            None,
+        ));
+    };
+
+    let status = StatusCode::from_u16(status as u16).map_err(|_| internal_error())?;
+    let mut resp = HttpResponse::build(status);
+    resp.insert_header((
+        HeaderName::from_static(REPLAYED_HEADER),
+        HeaderValue::from_static("true"),
+    ));
+
+    if let Some(content_type) = existing.response_content_type {
+        resp.content_type(content_type);
+    }
+
+    Ok(Reservation::Replay(resp.body(body)))
+}
+
+struct ReservedKey {
+    idempotency_dao: Option<web::Data<dyn IdempotencyDao>>,
+    user_id: Uuid,
+    key: String,
+}
+
+impl ReservedKey {
+    /// Frees the key before returning so an immediate retry doesn't find it still reserved
+    async fn release(mut self) {
+        let Some(idempotency_dao) = self.idempotency_dao.take() else {
+            return;
+        };
+
+        let user_id = self.user_id;
+        let key = std::mem::take(&mut self.key);
+
+        let result = block_task(move || idempotency_dao.release_key(user_id, &key)).await;
+
+        match result {
+            Ok(Ok(())) => (),
+            Ok(Err(e)) => log::error!("Failed to release idempotency key: {e}"),
+            Err(e) => log::error!("Failed to release idempotency key: {e}"),
+        }
+    }
+}
+
+impl Drop for ReservedKey {
+    fn drop(&mut self) {
+        let Some(idempotency_dao) = self.idempotency_dao.take() else {
+            return;
+        };
+
+        let reservation = ReservedKey {
+            idempotency_dao: Some(idempotency_dao),
+            user_id: self.user_id,
+            key: std::mem::take(&mut self.key),
+        };
+
+        actix_web::rt::spawn(reservation.release());
+    }
+}
+
+fn parse_key(key: &HeaderValue) -> Result<String, HttpErrorResponse> {
+    let key = key.to_str().ok().filter(|k| {
+        !k.is_empty() && k.len() <= MAX_KEY_LEN && k.bytes().all(|b| b.is_ascii_graphic())
+    });
+
+    match key {
+        Some(k) => Ok(String::from(k)),
+        None => Err(HttpErrorResponse::IncorrectlyFormed(format!(
+            "Idempotency-Key header must be 1 to {MAX_KEY_LEN} visible ASCII characters"
+        ))),
+    }
+}
+
+async fn read_body(req: &mut ServiceRequest) -> Result<BytesMut, HttpErrorResponse> {
+    let limit = env::CONF.protobuf_max_size;
+    let mut payload = req.take_payload();
+    let mut body = BytesMut::new();
+
+    while let Some(chunk) = payload.next().await {
+        let chunk = chunk.map_err(|e| {
+            HttpErrorResponse::IncorrectlyFormed(format!("Failed to read body: {e}"))
+        })?;
+
+        if body.len() + chunk.len() > limit {
+            return Err(HttpErrorResponse::InputTooLarge(
+                String::from("Request body is too large"),
+                SizeLimit::body(limit),
+            ));
+        }
+
+        body.extend_from_slice(&chunk);
+    }
+
+    Ok(body)
+}
+
+fn hash_request(req: &ServiceRequest, budget_id: Option<Uuid>, body: &[u8]) -> Vec<u8> {
+    let mut hasher = Sha256::new();
+
+    hasher.update(req.method().as_str());
+    hasher.update([0]);
+    hasher.update(req.path());
+    hasher.update([0]);
+    hasher.update(req.content_type());
+    hasher.update([0]);
+    if let Some(budget_id) = budget_id {
+        hasher.update(budget_id.as_bytes());
+    }
+    hasher.update([0]);
+    hasher.update(body);
+
+    Vec::from(hasher.finalize().as_slice())
+}
+
+fn internal_error() -> HttpErrorResponse {
+    HttpErrorResponse::InternalError(String::from("Failed to check idempotency key"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use entries_common::db::idempotency::{self, RESERVATION_TIMEOUT};
+    use entries_common::messages::{
+        BudgetFrame, EncryptedBlobAndCategoryId, EntryId, ErrorType, NewBudget, ServerErrorResponse,
+    };
+    use entries_common::schema::idempotency_keys as idempotency_key_fields;
+    use entries_common::schema::idempotency_keys::dsl::idempotency_keys;
+
+    use actix_web::test::{self, TestRequest};
+    use actix_web::web::Data;
+    use actix_web::App;
+    use diesel::{dsl, ExpressionMethods, QueryDsl, RunQueryDsl};
+    use ed25519_dalek as ed25519;
+    use prost::Message;
+    use rand::Rng;
+    use std::time::Duration;
+
+    use crate::handlers::test_utils::{self, gen_bytes};
+    use crate::middleware::proto_or_json::ProtoOrJsonConfig;
+    use crate::services::api::RouteLimiters;
+
+    fn new_budget() -> NewBudget {
+        let key_pair = ed25519::SigningKey::generate(&mut rand::rngs::OsRng);
+
+        NewBudget {
+            encrypted_blob: gen_bytes(32),
+            version_nonce: rand::thread_rng().gen(),
+            categories: Vec::new(),
+            user_public_budget_key: Vec::from(key_pair.verifying_key().to_bytes()),
+        }
+    }
+
+    fn create_budget_req(
+        access_token: &str,
+        key: Option<&str>,
+        budget: &NewBudget,
+    ) -> actix_http::Request {
+        let mut req = TestRequest::post()
+            .uri("/api/budget")
+            .insert_header(("AccessToken", access_token))
+            .insert_header(("Content-Type", "application/protobuf"))
+            .set_payload(budget.encode_to_vec());
+
+        if let Some(key) = key {
+            req = req.insert_header((IDEMPOTENCY_KEY_HEADER, key));
+        }
+
+        req.to_request()
+    }
+
+    #[actix_web::test]
+    async fn test_idempotency_key() {
+        let app = test::init_service(
+            App::new()
+                .app_data(Data::new(env::testing::DB_THREAD_POOL.clone()))
+                .app_data(Data::new(env::testing::async_db_pools()))
+                .configure(env::testing::configure_daos)
+                .app_data(ProtoOrJsonConfig::default())
+                .configure(|cfg| crate::services::api::configure(cfg, RouteLimiters::default())),
+        )
+        .await;
+
+        let (_, access_token, _, _) = test_utils::create_user().await;
+        let key = Uuid::now_v7().to_string();
+        let budget = new_budget();
+
+        let resp =
+            test::call_service(&app, create_budget_req(&access_token, Some(&key), &budget)).await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+        assert!(resp.headers().get(REPLAYED_HEADER).is_none());
+
+        let content_type = resp.headers().get(header::CONTENT_TYPE).unwrap().clone();
+        let first_body = body::to_bytes(resp.into_body()).await.unwrap();
+        let first_budget = BudgetFrame::decode(first_body.clone()).unwrap();
+
+        // A retry gets the original response rather than creating a second budget
+        let resp =
+            test::call_service(&app, create_budget_req(&access_token, Some(&key), &budget)).await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+        assert_eq!(resp.headers().get(REPLAYED_HEADER).unwrap(), "true");
+        assert_eq!(
+            resp.headers().get(header::CONTENT_TYPE).unwrap(),
+            content_type
+        );
+
+        let replayed_body = body::to_bytes(resp.into_body()).await.unwrap();
+        assert_eq!(replayed_body, first_body);
+
+        // Keys are scoped to the user
+        let (_, other_access_token, _, _) = test_utils::create_user().await;
+        let resp = test::call_service(
+            &app,
+            create_budget_req(&other_access_token, Some(&key), &budget),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+        assert!(resp.headers().get(REPLAYED_HEADER).is_none());
+
+        let other_budget =
+            BudgetFrame::decode(body::to_bytes(resp.into_body()).await.unwrap()).unwrap();
+        assert_ne!(other_budget.id, first_budget.id);
+
+        // Reusing the key for a different request is rejected
+        let resp = test::call_service(
+            &app,
+            create_budget_req(&access_token, Some(&key), &new_budget()),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+        let resp_body = body::to_bytes(resp.into_body()).await.unwrap();
+        let resp_err = ServerErrorResponse::decode(resp_body).unwrap();
+        assert_eq!(resp_err.err_type, ErrorType::ConflictWithExisting as i32);
+
+        // Error responses aren't saved, so the key is free to be retried right away
+        let key = Uuid::now_v7().to_string();
+        let mut budget = new_budget();
+        budget.encrypted_blob = gen_bytes(env::CONF.max_small_object_size + 1);
+
+        for _ in 0..2 {
+            let resp =
+                test::call_service(&app, create_budget_req(&access_token, Some(&key), &budget))
+                    .await;
+            assert_eq!(resp.status(), StatusCode::PAYLOAD_TOO_LARGE);
+            assert!(resp.headers().get(REPLAYED_HEADER).is_none());
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_idempotency_key_is_scoped_to_budget() {
+        let app = test::init_service(
+            App::new()
+                .app_data(Data::new(env::testing::DB_THREAD_POOL.clone()))
+                .app_data(Data::new(env::testing::async_db_pools()))
+                .configure(env::testing::configure_daos)
+                .app_data(ProtoOrJsonConfig::default())
+                .configure(|cfg| crate::services::api::configure(cfg, RouteLimiters::default())),
+        )
+        .await;
+
+        let (_, access_token, _, _) = test_utils::create_user().await;
+        let (_, budget_token) = test_utils::create_budget(&access_token).await;
+        let (_, other_budget_token) = test_utils::create_budget(&access_token).await;
+
+        let key = Uuid::now_v7().to_string();
+        let entry = EncryptedBlobAndCategoryId {
+            encrypted_blob: gen_bytes(32),
+            version_nonce: rand::thread_rng().gen(),
+            category_id: None,
+            id: None,
+        };
+
+        let create_entry_req = |budget_token: &str| {
+            TestRequest::post()
+                .uri("/api/budget/entry")
+                .insert_header(("AccessToken", access_token.as_str()))
+                .insert_header(("BudgetAccessToken", budget_token))
+                .insert_header(("Content-Type", "application/protobuf"))
+                .insert_header((IDEMPOTENCY_KEY_HEADER, key.as_str()))
+                .set_payload(entry.encode_to_vec())
+                .to_request()
+        };
+
+        let resp = test::call_service(&app, create_entry_req(&budget_token)).await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+        let first_body = body::to_bytes(resp.into_body()).await.unwrap();
+        EntryId::decode(first_body.clone()).unwrap();
+
+        // The same request to another budget must not be answered with the first budget's entry
+        let resp = test::call_service(&app, create_entry_req(&other_budget_token)).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        assert!(resp.headers().get(REPLAYED_HEADER).is_none());
+
+        let resp_body = body::to_bytes(resp.into_body()).await.unwrap();
+        let resp_err = ServerErrorResponse::decode(resp_body).unwrap();
+        assert_eq!(resp_err.err_type, ErrorType::ConflictWithExisting as i32);
+
+        let resp = test::call_service(&app, create_entry_req(&budget_token)).await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+        assert_eq!(resp.headers().get(REPLAYED_HEADER).unwrap(), "true");
+        assert_eq!(body::to_bytes(resp.into_body()).await.unwrap(), first_body);
+    }
+
+    #[actix_web::test]
+    async fn test_abandoned_reservation_is_taken_over() {
+        let app = test::init_service(
+            App::new()
+                .app_data(Data::new(env::testing::DB_THREAD_POOL.clone()))
+                .app_data(Data::new(env::testing::async_db_pools()))
+                .configure(env::testing::configure_daos)
+                .app_data(ProtoOrJsonConfig::default())
+                .configure(|cfg| crate::services::api::configure(cfg, RouteLimiters::default())),
+        )
+        .await;
+
+        let (user, access_token, _, _) = test_utils::create_user().await;
+        let key = Uuid::now_v7().to_string();
+        let budget = new_budget();
+
+        // Reserve the key the way a request that never finished would have
+        let dao = idempotency::Dao::new(&env::testing::DB_THREAD_POOL);
+        dao.reserve_key(
+            user.id,
+            &key,
+            &[0; 32],
+            SystemTime::now() + env::CONF.idempotency_key_lifetime,
+        )
+        .unwrap();
+
+        let resp =
+            test::call_service(&app, create_budget_req(&access_token, Some(&key), &budget)).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+        dsl::update(idempotency_keys.find((user.id, &key)))
+            .set(
+                idempotency_key_fields::reserved_at
+                    .eq(SystemTime::now() - RESERVATION_TIMEOUT - Duration::from_secs(1)),
+            )
+            .execute(&mut env::testing::DB_THREAD_POOL.get().unwrap())
+            .unwrap();
+
+        let resp =
+            test::call_service(&app, create_budget_req(&access_token, Some(&key), &budget)).await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+        assert!(resp.headers().get(REPLAYED_HEADER).is_none());
+        let first_body = body::to_bytes(resp.into_body()).await.unwrap();
+
+        // The new reservation holds the response like any other
+        let resp =
+            test::call_service(&app, create_budget_req(&access_token, Some(&key), &budget)).await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+        assert_eq!(resp.headers().get(REPLAYED_HEADER).unwrap(), "true");
+        assert_eq!(body::to_bytes(resp.into_body()).await.unwrap(), first_body);
+    }
+
+    #[actix_web::test]
+    async fn test_without_idempotency_key() {
+        let app = test::init_service(
+            App::new()
+                .app_data(Data::new(env::testing::DB_THREAD_POOL.clone()))
+                .app_data(Data::new(env::testing::async_db_pools()))
+                .configure(env::testing::configure_daos)
+                .app_data(ProtoOrJsonConfig::default())
+                .configure(|cfg| crate::services::api::configure(cfg, RouteLimiters::default())),
+        )
+        .await;
+
+        let (_, access_token, _, _) = test_utils::create_user().await;
+        let budget = new_budget();
+
+        let mut budget_ids = Vec::new();
+
+        for _ in 0..2 {
+            let resp =
+                test::call_service(&app, create_budget_req(&access_token, None, &budget)).await;
+            assert_eq!(resp.status(), StatusCode::CREATED);
+            assert!(resp.headers().get(REPLAYED_HEADER).is_none());
+
+            let resp_body = body::to_bytes(resp.into_body()).await.unwrap();
+            budget_ids.push(BudgetFrame::decode(resp_body).unwrap().id);
+        }
+
+        assert_ne!(budget_ids[0], budget_ids[1]);
+
+        let resp = test::call_service(
+            &app,
+            create_budget_req(&access_token, Some("has a space"), &budget),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+        let resp_body = body::to_bytes(resp.into_body()).await.unwrap();
+        let resp_err = ServerErrorResponse::decode(resp_body).unwrap();
+        assert_eq!(resp_err.err_type, ErrorType::IncorrectlyFormed as i32);
+    }
+
+    #[test]
+    fn test_parse_key() {
+        assert_eq!(
+            parse_key(&HeaderValue::from_static(
+                "0192a4c5-7d3e-7f00-8000-000000000000"
+            ))
+            .unwrap(),
+            "0192a4c5-7d3e-7f00-8000-000000000000"
+        );
+
+        assert!(parse_key(&HeaderValue::from_static("")).is_err());
+        assert!(parse_key(&HeaderValue::from_static("a b")).is_err());
+        assert!(parse_key(&HeaderValue::from_str(&"a".repeat(MAX_KEY_LEN)).unwrap()).is_ok());
+        assert!(parse_key(&HeaderValue::from_str(&"a".repeat(MAX_KEY_LEN + 1)).unwrap()).is_err());
+    }
+}
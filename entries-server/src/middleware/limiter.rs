@@ -6,13 +6,13 @@ use std::{
     time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
-use actix_web::{
-    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
-    error::ErrorTooManyRequests,
-};
+use actix_web::dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform};
 use futures::future::LocalBoxFuture;
 use tokio::sync::RwLock;
 
+use crate::handlers::error::HttpErrorResponse;
+use crate::metrics;
+
 #[derive(Debug, Default)]
 struct LimiterEntry {
     count: u64,
@@ -160,6 +160,7 @@ where
         let table_index = (final_octet & 0x0F) as usize;
         let table = unsafe { self.limiter_tables.get_unchecked(table_index) };
 
+        let route = req.match_pattern();
         let req_fut = self.service.call(req);
 
         let max_per_period = self.max_per_period;
@@ -192,9 +193,19 @@ where
                         entry.count = 1;
                     } else {
                         if entry.count >= max_per_period {
-                            return Err(ErrorTooManyRequests(
-                                "Too many requests. Please try again later.",
-                            ));
+                            metrics::LIMITER_REJECTIONS
+                                .with_label_values(&[route.as_deref().unwrap_or("unmatched")])
+                                .inc();
+
+                            let retry_after = (first_access + period)
+                                .duration_since(now)
+                                .unwrap_or_default();
+
+                            return Err(HttpErrorResponse::TooManyRequests(
+                                String::from("Please try again later"),
+                                retry_after,
+                            )
+                            .into());
                         }
 
                         entry.count += 1;
@@ -240,6 +251,8 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
+    use actix_web::http::header::RETRY_AFTER;
+    use actix_web::http::StatusCode;
     use actix_web::{test, web, App, HttpResponse};
     use tokio::time::sleep;
 
@@ -263,7 +276,9 @@ mod tests {
 
         let req = test::TestRequest::default().to_request();
         let res = app.call(req).await;
-        assert!(res.is_err());
+        let resp = res.unwrap_err().error_response();
+        assert_eq!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(resp.headers().get(RETRY_AFTER).unwrap(), "1");
 
         // Other IPs should still be able to make requests
         let req = test::TestRequest::default()
@@ -1,10 +1,17 @@
 pub mod app_version;
 pub mod auth;
+pub mod https;
+pub mod idempotency;
+pub mod proto_or_json;
+pub mod read_your_writes;
+pub mod request_id;
 pub mod special_access_token;
 
 mod limiter;
+mod request_metrics;
 
 pub use limiter::Limiter;
+pub use request_metrics::RequestMetrics;
 
 use entries_common::token::TokenError;
 
@@ -0,0 +1,492 @@
+use std::cell::Cell;
+use std::future::{ready, Future, Ready};
+use std::ops::{Deref, DerefMut};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use actix_web::dev::{forward_ready, Payload, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::error::{ErrorInternalServerError, InternalError};
+use actix_web::http::header::{self, Accept, Quality};
+use actix_web::web::BytesMut;
+use actix_web::{FromRequest, HttpMessage, HttpRequest, HttpResponse, HttpResponseBuilder};
+use futures::future::{FutureExt, LocalBoxFuture};
+use futures::StreamExt;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::handlers::error::{HttpErrorResponse, SizeLimit};
+
+const DEFAULT_LIMIT: usize = 262_144;
+
+/// The encoding of a request or response body. JSON follows the protobuf JSON mapping, so
+/// field names are camelCase, bytes are base64 strings, 64-bit integers are strings, and enums
+/// are their names.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Format {
+    #[default]
+    Protobuf,
+    Json,
+}
+
+impl Format {
+    fn from_mime_type(mime_type: &str) -> Option<Self> {
+        match mime_type {
+            "application/protobuf" | "application/x-protobuf" => Some(Format::Protobuf),
+            "application/json" => Some(Format::Json),
+            _ => None,
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Format::Protobuf => "application/protobuf",
+            Format::Json => "application/json",
+        }
+    }
+}
+
+thread_local! {
+    static CURRENT_FORMAT: Cell<Format> = const { Cell::new(Format::Protobuf) };
+}
+
+/// Returns the format responses to the request currently being handled on this thread should
+/// be encoded in. Protobuf unless the client asked for something else.
+pub fn current_format() -> Format {
+    CURRENT_FORMAT.with(Cell::get)
+}
+
+fn scope<R>(format: Format, f: impl FnOnce() -> R) -> R {
+    struct RestoreGuard(Format);
+
+    impl Drop for RestoreGuard {
+        fn drop(&mut self) {
+            CURRENT_FORMAT.with(|current| current.set(self.0));
+        }
+    }
+
+    let previous = CURRENT_FORMAT.with(|current| current.replace(format));
+    let _guard = RestoreGuard(previous);
+
+    f()
+}
+
+/// The format a client wants responses in. An explicit `Accept` header wins. Otherwise,
+/// responses are sent in the format of the request body.
+fn negotiate(req: &HttpRequest) -> Format {
+    let request_format = Format::from_mime_type(req.content_type()).unwrap_or_default();
+
+    let Some(accept) = req.get_header::<Accept>() else {
+        return request_format;
+    };
+
+    let mut best: Option<(Format, Quality)> = None;
+
+    for item in accept.iter() {
+        let Some(format) = Format::from_mime_type(item.item.essence_str()) else {
+            continue;
+        };
+
+        if item.quality > Quality::ZERO && best.is_none_or(|(_, q)| item.quality > q) {
+            best = Some((format, item.quality));
+        }
+    }
+
+    best.map(|(format, _)| format).unwrap_or(request_format)
+}
+
+/// A future that sets the current format each time it is polled. See `request_id::Scoped`.
+struct Scoped<F> {
+    format: Format,
+    inner: F,
+}
+
+impl<F: Future + Unpin> Future for Scoped<F> {
+    type Output = F::Output;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+        scope(this.format, || Pin::new(&mut this.inner).poll(cx))
+    }
+}
+
+/// Picks the format responses are encoded in (see `negotiate()`) and makes it available to
+/// handlers and `HttpErrorResponse` through `current_format()`. Errors returned by inner
+/// middleware and services are rendered here so they are encoded in the negotiated format too.
+#[derive(Clone, Default)]
+pub struct ContentNegotiation;
+
+impl<S, B> Transform<S, ServiceRequest> for ContentNegotiation
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = actix_web::Error;
+    type InitError = ();
+    type Transform = ContentNegotiationMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(ContentNegotiationMiddleware { service }))
+    }
+}
+
+pub struct ContentNegotiationMiddleware<S> {
+    service: S,
+}
+
+impl<S, B> Service<ServiceRequest> for ContentNegotiationMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let format = negotiate(req.request());
+        let req_fut = scope(format, || self.service.call(req));
+
+        let inner = async move {
+            req_fut.await.map_err(|e| {
+                // Render the response now, while the format is set, rather than when the error
+                // reaches the outermost service
+                let resp = e.error_response();
+                InternalError::from_response(e, resp).into()
+            })
+        }
+        .boxed_local();
+
+        Box::pin(Scoped { format, inner })
+    }
+}
+
+/// Limits the size of bodies read by the `ProtoOrJson` extractor. Defaults to 256 KiB.
+#[derive(Clone, Copy, Debug)]
+pub struct ProtoOrJsonConfig {
+    limit: usize,
+}
+
+impl ProtoOrJsonConfig {
+    pub fn limit(&mut self, limit: usize) -> &mut Self {
+        self.limit = limit;
+        self
+    }
+}
+
+impl Default for ProtoOrJsonConfig {
+    fn default() -> Self {
+        Self {
+            limit: DEFAULT_LIMIT,
+        }
+    }
+}
+
+/// Extracts a message from a request body sent as either protobuf or JSON, depending on the
+/// request's `Content-Type`.
+#[derive(Debug)]
+pub struct ProtoOrJson<T>(pub T);
+
+impl<T> Deref for ProtoOrJson<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for ProtoOrJson<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T> FromRequest for ProtoOrJson<T>
+where
+    T: prost::Message + DeserializeOwned + Default + 'static,
+{
+    type Error = HttpErrorResponse;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let Some(format) = Format::from_mime_type(req.content_type()) else {
+            return Box::pin(ready(Err(HttpErrorResponse::IncorrectlyFormed(
+                String::from("Content-Type must be application/protobuf or application/json"),
+            ))));
+        };
+
+        let limit = req
+            .app_data::<ProtoOrJsonConfig>()
+            .map(|c| c.limit)
+            .unwrap_or(DEFAULT_LIMIT);
+
+        let content_length = req
+            .headers()
+            .get(header::CONTENT_LENGTH)
+            .and_then(|l| l.to_str().ok())
+            .and_then(|l| l.parse::<usize>().ok());
+
+        if content_length.is_some_and(|len| len > limit) {
+            return Box::pin(ready(Err(body_too_large(limit))));
+        }
+
+        let mut payload = payload.take();
+
+        Box::pin(async move {
+            let mut body = BytesMut::with_capacity(8192);
+
+            while let Some(chunk) = payload.next().await {
+                let chunk = chunk.map_err(|e| {
+                    HttpErrorResponse::IncorrectlyFormed(format!("Failed to read body: {e}"))
+                })?;
+
+                if body.len() + chunk.len() > limit {
+                    return Err(body_too_large(limit));
+                }
+
+                body.extend_from_slice(&chunk);
+            }
+
+            let message = match format {
+                Format::Protobuf => T::decode(body).map_err(|e| {
+                    HttpErrorResponse::IncorrectlyFormed(format!("Failed to decode protobuf: {e}"))
+                })?,
+                Format::Json => serde_json::from_slice(&body).map_err(|e| {
+                    HttpErrorResponse::IncorrectlyFormed(format!("Failed to decode JSON: {e}"))
+                })?,
+            };
+
+            Ok(ProtoOrJson(message))
+        })
+    }
+}
+
+fn body_too_large(limit: usize) -> HttpErrorResponse {
+    HttpErrorResponse::InputTooLarge(
+        String::from("Request body is too large"),
+        SizeLimit::body(limit),
+    )
+}
+
+pub trait ProtoOrJsonResponseBuilder {
+    /// Sets the body to `value` encoded in the format negotiated for the current request
+    fn proto_or_json<T>(&mut self, value: T) -> Result<HttpResponse, actix_web::Error>
+    where
+        T: prost::Message + Serialize;
+}
+
+impl ProtoOrJsonResponseBuilder for HttpResponseBuilder {
+    fn proto_or_json<T>(&mut self, value: T) -> Result<HttpResponse, actix_web::Error>
+    where
+        T: prost::Message + Serialize,
+    {
+        let format = current_format();
+
+        let body = match format {
+            Format::Protobuf => value.encode_to_vec(),
+            Format::Json => serde_json::to_vec(&value).map_err(ErrorInternalServerError)?,
+        };
+
+        Ok(self
+            .insert_header((header::CONTENT_TYPE, format.content_type()))
+            .body(body))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use actix_web::http::StatusCode;
+    use actix_web::test::{self, TestRequest};
+    use actix_web::{web, App};
+    use entries_common::messages::{CredentialPair, ErrorType, ServerErrorResponse};
+    use prost::Message;
+
+    const CREDENTIALS_JSON: &str = r#"{"email":"test@example.com","authString":"AQID","nonce":7}"#;
+
+    async fn echo_credentials(
+        credentials: ProtoOrJson<CredentialPair>,
+    ) -> Result<HttpResponse, HttpErrorResponse> {
+        if credentials.email.is_empty() {
+            return Err(HttpErrorResponse::IncorrectlyFormed(String::from(
+                "Email is empty",
+            )));
+        }
+
+        Ok(HttpResponse::Ok().proto_or_json(credentials.0)?)
+    }
+
+    #[test]
+    fn test_negotiate() {
+        let cases = [
+            (None, None, Format::Protobuf),
+            (Some("application/json"), None, Format::Json),
+            (Some("application/protobuf"), None, Format::Protobuf),
+            (None, Some("application/json"), Format::Json),
+            (Some("application/json"), Some("*/*"), Format::Json),
+            (
+                Some("application/protobuf"),
+                Some("application/json;q=0.5, application/x-protobuf"),
+                Format::Protobuf,
+            ),
+            (
+                Some("application/protobuf"),
+                Some("text/html, application/json;q=0.9"),
+                Format::Json,
+            ),
+            (
+                Some("application/json"),
+                Some("application/protobuf;q=0"),
+                Format::Json,
+            ),
+        ];
+
+        for (content_type, accept, expected) in cases {
+            let mut req = TestRequest::default();
+
+            if let Some(content_type) = content_type {
+                req = req.insert_header((header::CONTENT_TYPE, content_type));
+            }
+
+            if let Some(accept) = accept {
+                req = req.insert_header((header::ACCEPT, accept));
+            }
+
+            assert_eq!(
+                negotiate(&req.to_http_request()),
+                expected,
+                "{content_type:?} {accept:?}"
+            );
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_proto_or_json() {
+        let app = test::init_service(
+            App::new()
+                .app_data(ProtoOrJsonConfig::default())
+                .route("/", web::post().to(echo_credentials))
+                .wrap(ContentNegotiation),
+        )
+        .await;
+
+        let credentials = CredentialPair {
+            email: String::from("test@example.com"),
+            auth_string: vec![1, 2, 3],
+            nonce: 7,
+        };
+
+        let req = TestRequest::post()
+            .uri("/")
+            .insert_header((header::CONTENT_TYPE, "application/protobuf"))
+            .set_payload(credentials.encode_to_vec())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(
+            resp.headers().get(header::CONTENT_TYPE).unwrap(),
+            "application/protobuf"
+        );
+        let body = test::read_body(resp).await;
+        assert_eq!(CredentialPair::decode(body).unwrap(), credentials);
+
+        let req = TestRequest::post()
+            .uri("/")
+            .insert_header((header::CONTENT_TYPE, "application/json"))
+            .set_payload(CREDENTIALS_JSON)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(
+            resp.headers().get(header::CONTENT_TYPE).unwrap(),
+            "application/json"
+        );
+        let body = test::read_body(resp).await;
+        assert_eq!(
+            serde_json::from_slice::<CredentialPair>(&body).unwrap(),
+            credentials
+        );
+
+        let req = TestRequest::post()
+            .uri("/")
+            .insert_header((header::CONTENT_TYPE, "application/json"))
+            .insert_header((header::ACCEPT, "application/protobuf"))
+            .set_payload(CREDENTIALS_JSON)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        let body = test::read_body(resp).await;
+        assert_eq!(CredentialPair::decode(body).unwrap(), credentials);
+
+        // Errors are encoded in the negotiated format
+        let req = TestRequest::post()
+            .uri("/")
+            .insert_header((header::CONTENT_TYPE, "application/json"))
+            .set_payload(r#"{"email":"","authString":"","nonce":0}"#)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            resp.headers().get(header::CONTENT_TYPE).unwrap(),
+            "application/json"
+        );
+        let body = test::read_body(resp).await;
+        let resp_err = serde_json::from_slice::<ServerErrorResponse>(&body).unwrap();
+        assert_eq!(resp_err.err_type, ErrorType::IncorrectlyFormed as i32);
+
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["errType"], "INCORRECTLY_FORMED");
+
+        let req = TestRequest::post()
+            .uri("/")
+            .insert_header((header::CONTENT_TYPE, "application/json"))
+            .set_payload(r#"{"email":"test@example.com""#)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        let body = test::read_body(resp).await;
+        let resp_err = serde_json::from_slice::<ServerErrorResponse>(&body).unwrap();
+        assert_eq!(resp_err.err_type, ErrorType::IncorrectlyFormed as i32);
+
+        let req = TestRequest::post()
+            .uri("/")
+            .insert_header((header::CONTENT_TYPE, "text/plain"))
+            .set_payload(CREDENTIALS_JSON)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        let body = test::read_body(resp).await;
+        let resp_err = ServerErrorResponse::decode(body).unwrap();
+        assert_eq!(resp_err.err_type, ErrorType::IncorrectlyFormed as i32);
+    }
+
+    #[actix_web::test]
+    async fn test_body_limit() {
+        let mut config = ProtoOrJsonConfig::default();
+        config.limit(16);
+
+        let app = test::init_service(
+            App::new()
+                .app_data(config)
+                .route("/", web::post().to(echo_credentials))
+                .wrap(ContentNegotiation),
+        )
+        .await;
+
+        let req = TestRequest::post()
+            .uri("/")
+            .insert_header((header::CONTENT_TYPE, "application/json"))
+            .set_payload(CREDENTIALS_JSON)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::PAYLOAD_TOO_LARGE);
+        let body = test::read_body(resp).await;
+        let resp_err = serde_json::from_slice::<ServerErrorResponse>(&body).unwrap();
+        assert_eq!(resp_err.err_type, ErrorType::InputTooLarge as i32);
+    }
+}
@@ -0,0 +1,289 @@
+use entries_common::db::nonblocking::budget::AsyncBudgetDao;
+
+use actix_web::dev::{forward_ready, Payload, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::http::Method;
+use actix_web::{web, FromRequest, HttpMessage, HttpRequest};
+use futures::future::{self, LocalBoxFuture};
+use std::collections::HashMap;
+use std::future::{ready, Ready};
+use std::ops::Deref;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+use crate::handlers::error::HttpErrorResponse;
+use crate::middleware::auth::{Access, VerifiedToken};
+use crate::middleware::FromHeader;
+
+/// Lets users see their own changes when read-only queries are sent to a replica, which may lag
+/// behind the primary. Once a request from a user that changes something succeeds, that user's
+/// requests read from the primary for `ENTRIES_DB_READ_YOUR_WRITES_WINDOW_SECS`.
+///
+/// Handlers pick up the decision through the [`RoutedBudgetDao`] extractor. Recent writes are
+/// tracked in memory, so they only cover requests handled by the same server process.
+#[derive(Clone)]
+pub struct ReadYourWrites {
+    window: Duration,
+    recent_writes: Arc<Mutex<RecentWrites>>,
+}
+
+impl ReadYourWrites {
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            recent_writes: Arc::new(Mutex::new(RecentWrites {
+                last_write_by_user: HashMap::new(),
+                last_pruned: Instant::now(),
+            })),
+        }
+    }
+}
+
+struct RecentWrites {
+    last_write_by_user: HashMap<Uuid, Instant>,
+    last_pruned: Instant,
+}
+
+impl RecentWrites {
+    fn record(&mut self, user_id: Uuid, window: Duration) {
+        let now = Instant::now();
+
+        if now.duration_since(self.last_pruned) >= window {
+            self.last_write_by_user
+                .retain(|_, written| now.duration_since(*written) < window);
+            self.last_pruned = now;
+        }
+
+        self.last_write_by_user.insert(user_id, now);
+    }
+
+    fn wrote_within(&self, user_id: Uuid, window: Duration) -> bool {
+        self.last_write_by_user
+            .get(&user_id)
+            .is_some_and(|written| written.elapsed() < window)
+    }
+}
+
+/// Marks a request that should read from the primary because its user wrote recently
+#[derive(Clone, Copy)]
+struct ReadFromPrimary;
+
+impl<S, B> Transform<S, ServiceRequest> for ReadYourWrites
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = actix_web::Error;
+    type InitError = ();
+    type Transform = ReadYourWritesMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(ReadYourWritesMiddleware {
+            service: Rc::new(service),
+            window: self.window,
+            recent_writes: Arc::clone(&self.recent_writes),
+        }))
+    }
+}
+
+pub struct ReadYourWritesMiddleware<S> {
+    service: Rc<S>,
+    window: Duration,
+    recent_writes: Arc<Mutex<RecentWrites>>,
+}
+
+impl<S, B> Service<ServiceRequest> for ReadYourWritesMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, mut req: ServiceRequest) -> Self::Future {
+        let service = Rc::clone(&self.service);
+        let window = self.window;
+        let recent_writes = Arc::clone(&self.recent_writes);
+
+        Box::pin(async move {
+            let user_id = match req.extract::<VerifiedToken<Access, FromHeader>>().await {
+                Ok(token) => token.0.user_id,
+                Err(_) => return service.call(req).await,
+            };
+
+            let wrote_recently = recent_writes
+                .lock()
+                .expect("Lock was poisoned")
+                .wrote_within(user_id, window);
+
+            if wrote_recently {
+                req.extensions_mut().insert(ReadFromPrimary);
+            }
+
+            let is_write = !is_read_only(req.method());
+            let resp = service.call(req).await?;
+
+            if is_write && resp.status().is_success() {
+                recent_writes
+                    .lock()
+                    .expect("Lock was poisoned")
+                    .record(user_id, window);
+            }
+
+            Ok(resp)
+        })
+    }
+}
+
+/// The budget DAO a handler should query. Read-only queries go to the replica, if there is one,
+/// unless the request changes something or comes from a user who recently did (see
+/// [`ReadYourWrites`]), in which case every query goes to the primary.
+pub struct RoutedBudgetDao(Arc<dyn AsyncBudgetDao>);
+
+impl Deref for RoutedBudgetDao {
+    type Target = dyn AsyncBudgetDao;
+
+    fn deref(&self) -> &Self::Target {
+        self.0.as_ref()
+    }
+}
+
+impl FromRequest for RoutedBudgetDao {
+    type Error = HttpErrorResponse;
+    type Future = future::Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        let Some(budget_dao) = req.app_data::<web::Data<dyn AsyncBudgetDao>>() else {
+            log::error!("AsyncBudgetDao should be in app data");
+            return future::err(HttpErrorResponse::InternalError(String::from(
+                "Failed to connect to database",
+            )));
+        };
+
+        let read_from_primary =
+            !is_read_only(req.method()) || req.extensions().contains::<ReadFromPrimary>();
+
+        if read_from_primary {
+            future::ok(RoutedBudgetDao(budget_dao.primary_only()))
+        } else {
+            future::ok(RoutedBudgetDao(budget_dao.clone().into_inner()))
+        }
+    }
+}
+
+fn is_read_only(method: &Method) -> bool {
+    matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use entries_common::db::nonblocking::budget;
+    use entries_common::db::AsyncDbPools;
+    use entries_common::token::auth_token::{AuthToken, AuthTokenType, NewAuthTokenClaims};
+
+    use actix_web::body::to_bytes;
+    use actix_web::http::StatusCode;
+    use actix_web::test::{self, TestRequest};
+    use actix_web::web::Data;
+    use actix_web::{App, HttpResponse};
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use crate::env;
+
+    async fn pool_used(budget_dao: RoutedBudgetDao) -> HttpResponse {
+        if budget_dao.reads_from_replica() {
+            HttpResponse::Ok().body("replica")
+        } else {
+            HttpResponse::Ok().body("primary")
+        }
+    }
+
+    fn access_token(user_id: Uuid) -> String {
+        let expiration = (SystemTime::now() + Duration::from_secs(60))
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+
+        let claims = NewAuthTokenClaims {
+            user_id,
+            user_email: "test@example.com",
+            expiration,
+            token_type: AuthTokenType::Access,
+        };
+
+        AuthToken::sign_new(claims, &env::CONF.token_signing_key)
+    }
+
+    #[actix_web::test]
+    async fn test_reads_from_primary_after_write() {
+        // The handlers never query the database, so the "replica" can be the primary's pool
+        let db_pool = env::testing::async_db_pools().primary().clone();
+        let db_pools = AsyncDbPools::new(db_pool.clone(), Some(db_pool));
+        let budget_dao: Arc<dyn AsyncBudgetDao> = Arc::new(budget::Dao::new(&db_pools));
+
+        let app = test::init_service(
+            App::new()
+                .app_data(Data::from(budget_dao))
+                .route("/read", web::get().to(pool_used))
+                .route("/write", web::put().to(pool_used))
+                .route(
+                    "/fail",
+                    web::put().to(|| async { HttpResponse::BadRequest().finish() }),
+                )
+                .wrap(ReadYourWrites::new(Duration::from_millis(300))),
+        )
+        .await;
+
+        let writer_token = access_token(Uuid::now_v7());
+        let other_token = access_token(Uuid::now_v7());
+
+        let pool_for = |method: Method, path: &'static str, token: &str| {
+            let req = TestRequest::default()
+                .method(method)
+                .uri(path)
+                .insert_header(("AccessToken", token))
+                .to_request();
+
+            test::call_service(&app, req)
+        };
+
+        let resp = pool_for(Method::GET, "/read", &writer_token).await;
+        assert_eq!(to_bytes(resp.into_body()).await.unwrap(), "replica");
+
+        // Requests that change something always use the primary
+        let resp = pool_for(Method::PUT, "/write", &writer_token).await;
+        assert_eq!(to_bytes(resp.into_body()).await.unwrap(), "primary");
+
+        let resp = pool_for(Method::GET, "/read", &writer_token).await;
+        assert_eq!(to_bytes(resp.into_body()).await.unwrap(), "primary");
+
+        let resp = pool_for(Method::GET, "/read", &other_token).await;
+        assert_eq!(to_bytes(resp.into_body()).await.unwrap(), "replica");
+
+        // Failed writes don't change anything, so they don't count
+        let resp = pool_for(Method::PUT, "/fail", &other_token).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+        let resp = pool_for(Method::GET, "/read", &other_token).await;
+        assert_eq!(to_bytes(resp.into_body()).await.unwrap(), "replica");
+
+        tokio::time::sleep(Duration::from_millis(400)).await;
+
+        let resp = pool_for(Method::GET, "/read", &writer_token).await;
+        assert_eq!(to_bytes(resp.into_body()).await.unwrap(), "replica");
+
+        let req = TestRequest::get().uri("/read").to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(to_bytes(resp.into_body()).await.unwrap(), "replica");
+    }
+}
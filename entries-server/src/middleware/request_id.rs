@@ -0,0 +1,252 @@
+use std::cell::RefCell;
+use std::future::{ready, Future, Ready};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use actix_web::dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::http::header::{HeaderName, HeaderValue};
+use futures::future::LocalBoxFuture;
+use uuid::Uuid;
+
+pub const REQUEST_ID_HEADER: &str = "X-Request-Id";
+
+const MAX_REQUEST_ID_LEN: usize = 64;
+
+thread_local! {
+    static CURRENT_REQUEST_ID: RefCell<Option<Arc<str>>> = const { RefCell::new(None) };
+}
+
+/// Returns the ID of the request currently being handled on this thread, if any.
+pub fn current() -> Option<Arc<str>> {
+    CURRENT_REQUEST_ID.with(|id| id.borrow().clone())
+}
+
+/// Runs `f` with `request_id` set as the current request ID, restoring the previous ID
+/// afterward. Used to carry the ID over to work that runs on other threads, such as
+/// closures passed to `web::block` or `rayon::spawn`.
+pub fn scope<R>(request_id: Option<Arc<str>>, f: impl FnOnce() -> R) -> R {
+    struct RestoreGuard(Option<Arc<str>>);
+
+    impl Drop for RestoreGuard {
+        fn drop(&mut self) {
+            CURRENT_REQUEST_ID.with(|id| *id.borrow_mut() = self.0.take());
+        }
+    }
+
+    let previous = CURRENT_REQUEST_ID.with(|id| id.replace(request_id));
+    let _guard = RestoreGuard(previous);
+
+    f()
+}
+
+/// A future that sets the current request ID and enters the request's tracing span each time
+/// it is polled. Actix workers interleave many requests on a single thread, so these can't
+/// simply be set once when the request arrives.
+struct Scoped<F> {
+    request_id: Arc<str>,
+    span: tracing::Span,
+    inner: F,
+}
+
+impl<F: Future + Unpin> Future for Scoped<F> {
+    type Output = F::Output;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+        let _entered = this.span.enter();
+        scope(Some(Arc::clone(&this.request_id)), || {
+            Pin::new(&mut this.inner).poll(cx)
+        })
+    }
+}
+
+/// Assigns each request an ID (or accepts the one the client sent in the `X-Request-Id`
+/// header), makes it available to log calls made while handling the request, and echoes it
+/// back in the response. Also opens the root tracing span for the request so spans created by
+/// handlers and DAOs are grouped into a single trace.
+#[derive(Clone, Default)]
+pub struct RequestId;
+
+impl<S, B> Transform<S, ServiceRequest> for RequestId
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = actix_web::Error;
+    type InitError = ();
+    type Transform = RequestIdMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RequestIdMiddleware { service }))
+    }
+}
+
+pub struct RequestIdMiddleware<S> {
+    service: S,
+}
+
+impl<S, B> Service<ServiceRequest> for RequestIdMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let request_id: Arc<str> = match req
+            .headers()
+            .get(REQUEST_ID_HEADER)
+            .and_then(|h| h.to_str().ok())
+        {
+            Some(id) if is_valid_request_id(id) => Arc::from(id),
+            _ => Arc::from(Uuid::now_v7().to_string()),
+        };
+
+        let header_value =
+            HeaderValue::from_str(&request_id).expect("Request ID should be a valid header");
+
+        let route = req.match_pattern();
+        let span = tracing::debug_span!(
+            "request",
+            otel.name = format!("{} {}", req.method(), route.as_deref().unwrap_or("unmatched")),
+            otel.kind = "server",
+            http.request.method = %req.method(),
+            http.route = route.as_deref(),
+            http.response.status_code = tracing::field::Empty,
+            request_id = &*request_id,
+        );
+
+        let req_fut =
+            span.in_scope(|| scope(Some(Arc::clone(&request_id)), || self.service.call(req)));
+
+        let response_span = span.clone();
+        Box::pin(Scoped {
+            request_id,
+            span,
+            inner: Box::pin(async move {
+                // Errors returned by handlers have already been turned into responses by this
+                // point. Errors from other middleware (e.g. the limiter) are passed through
+                // untouched and won't carry the header.
+                let mut res = req_fut.await?;
+
+                response_span.record("http.response.status_code", res.status().as_u16());
+                res.headers_mut()
+                    .insert(HeaderName::from_static("x-request-id"), header_value);
+
+                Ok(res)
+            }),
+        })
+    }
+}
+
+fn is_valid_request_id(id: &str) -> bool {
+    !id.is_empty()
+        && id.len() <= MAX_REQUEST_ID_LEN
+        && id
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_' || b == b'.')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use actix_web::body::to_bytes;
+    use actix_web::http::StatusCode;
+    use actix_web::{test, web, App, HttpResponse};
+    use entries_common::messages::ServerErrorResponse;
+    use prost::Message;
+
+    use crate::handlers::error::HttpErrorResponse;
+
+    #[actix_web::test]
+    async fn test_request_id() {
+        let app = test::init_service(
+            App::new()
+                .wrap(RequestId)
+                .service(web::resource("/ok").route(web::get().to(|| async {
+                    let id = current().expect("Request ID should be set");
+
+                    // The ID should carry over to the blocking thread pool
+                    let blocking_id = crate::handlers::block_task(current).await.unwrap();
+                    assert_eq!(blocking_id.as_deref(), Some(&*id));
+
+                    HttpResponse::Ok().body(String::from(&*id))
+                })))
+                .service(web::resource("/err").route(web::get().to(|| async {
+                    Err::<HttpResponse, _>(HttpErrorResponse::InternalError(String::from("Test")))
+                }))),
+        )
+        .await;
+
+        // Generated when absent
+        let req = test::TestRequest::get().uri("/ok").to_request();
+        let res = test::call_service(&app, req).await;
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let header_id = res
+            .headers()
+            .get(REQUEST_ID_HEADER)
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_owned();
+        assert!(Uuid::parse_str(&header_id).is_ok());
+
+        let body = to_bytes(res.into_body()).await.unwrap();
+        assert_eq!(body, header_id.as_bytes());
+
+        // Accepted from the client
+        let req = test::TestRequest::get()
+            .uri("/ok")
+            .insert_header((REQUEST_ID_HEADER, "client-id_1.2"))
+            .to_request();
+        let res = test::call_service(&app, req).await;
+        assert_eq!(
+            res.headers().get(REQUEST_ID_HEADER).unwrap(),
+            "client-id_1.2"
+        );
+
+        // Replaced when invalid
+        let req = test::TestRequest::get()
+            .uri("/ok")
+            .insert_header((REQUEST_ID_HEADER, "bad id\twith spaces"))
+            .to_request();
+        let res = test::call_service(&app, req).await;
+        let header_id = res.headers().get(REQUEST_ID_HEADER).unwrap();
+        assert!(Uuid::parse_str(header_id.to_str().unwrap()).is_ok());
+
+        let req = test::TestRequest::get()
+            .uri("/ok")
+            .insert_header((REQUEST_ID_HEADER, "a".repeat(MAX_REQUEST_ID_LEN + 1)))
+            .to_request();
+        let res = test::call_service(&app, req).await;
+        let header_id = res.headers().get(REQUEST_ID_HEADER).unwrap();
+        assert!(Uuid::parse_str(header_id.to_str().unwrap()).is_ok());
+
+        // Included in error responses
+        let req = test::TestRequest::get()
+            .uri("/err")
+            .insert_header((REQUEST_ID_HEADER, "err-id"))
+            .to_request();
+        let res = test::call_service(&app, req).await;
+        assert_eq!(res.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(res.headers().get(REQUEST_ID_HEADER).unwrap(), "err-id");
+
+        let body = to_bytes(res.into_body()).await.unwrap();
+        let server_error = ServerErrorResponse::decode(body).unwrap();
+        assert_eq!(server_error.request_id.as_deref(), Some("err-id"));
+
+        // Not leaked outside of the request
+        assert!(current().is_none());
+    }
+}
@@ -0,0 +1,136 @@
+use std::future::{ready, Ready};
+use std::time::Instant;
+
+use actix_web::dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform};
+use futures::future::LocalBoxFuture;
+
+use crate::metrics;
+
+const UNMATCHED_ROUTE: &str = "unmatched";
+
+/// Records the count and latency of requests, labeled by the matched route pattern (rather
+/// than the raw path, which would give every query string its own label), method, and
+/// response status.
+#[derive(Clone, Default)]
+pub struct RequestMetrics;
+
+impl<S, B> Transform<S, ServiceRequest> for RequestMetrics
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = actix_web::Error;
+    type InitError = ();
+    type Transform = RequestMetricsMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RequestMetricsMiddleware { service }))
+    }
+}
+
+pub struct RequestMetricsMiddleware<S> {
+    service: S,
+}
+
+impl<S, B> Service<ServiceRequest> for RequestMetricsMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let start = Instant::now();
+
+        let route = req
+            .match_pattern()
+            .unwrap_or_else(|| String::from(UNMATCHED_ROUTE));
+        let method = req.method().to_string();
+
+        let req_fut = self.service.call(req);
+
+        Box::pin(async move {
+            let res = req_fut.await;
+
+            let status = match &res {
+                Ok(res) => res.status(),
+                Err(e) => e.as_response_error().status_code(),
+            };
+            let labels = [route.as_str(), method.as_str(), status.as_str()];
+
+            metrics::HTTP_REQUESTS.with_label_values(&labels).inc();
+            metrics::HTTP_REQUEST_DURATION
+                .with_label_values(&labels)
+                .observe(start.elapsed().as_secs_f64());
+
+            res
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use actix_web::{test, web, App, HttpResponse};
+
+    #[actix_web::test]
+    async fn test_request_metrics() {
+        let app = test::init_service(
+            App::new()
+                .wrap(RequestMetrics)
+                .service(
+                    web::resource("/metrics_test/{id}")
+                        .route(web::get().to(|| async { HttpResponse::Ok().finish() })),
+                )
+                .service(
+                    web::resource("/metrics_test_fail")
+                        .route(web::get().to(|| async { HttpResponse::NotFound().finish() })),
+                ),
+        )
+        .await;
+
+        let ok_labels = ["/metrics_test/{id}", "GET", "200"];
+        let fail_labels = ["/metrics_test_fail", "GET", "404"];
+
+        let ok_count_before = metrics::HTTP_REQUESTS.with_label_values(&ok_labels).get();
+        let fail_count_before = metrics::HTTP_REQUESTS.with_label_values(&fail_labels).get();
+        let ok_observations_before = metrics::HTTP_REQUEST_DURATION
+            .with_label_values(&ok_labels)
+            .get_sample_count();
+
+        let req = test::TestRequest::get().uri("/metrics_test/1").to_request();
+        test::call_service(&app, req).await;
+
+        let req = test::TestRequest::get().uri("/metrics_test/2").to_request();
+        test::call_service(&app, req).await;
+
+        let req = test::TestRequest::get()
+            .uri("/metrics_test_fail")
+            .to_request();
+        test::call_service(&app, req).await;
+
+        assert_eq!(
+            metrics::HTTP_REQUESTS.with_label_values(&ok_labels).get(),
+            ok_count_before + 2,
+        );
+        assert_eq!(
+            metrics::HTTP_REQUESTS.with_label_values(&fail_labels).get(),
+            fail_count_before + 1,
+        );
+        assert_eq!(
+            metrics::HTTP_REQUEST_DURATION
+                .with_label_values(&ok_labels)
+                .get_sample_count(),
+            ok_observations_before + 2,
+        );
+    }
+}
@@ -1,6 +1,7 @@
 use actix_web::web::*;
 
 use crate::handlers::budget;
+use crate::middleware::idempotency::Idempotency;
 
 use super::RouteLimiters;
 
@@ -12,11 +13,21 @@ pub fn configure(cfg: &mut ServiceConfig, limiters: RouteLimiters) {
                     .route(get().to(budget::get))
                     .wrap(limiters.get_budgets)
                     .route(put().to(budget::edit))
-                    .route(post().to(budget::create).wrap(limiters.create_budget)),
+                    .route(
+                        post()
+                            .to(budget::create)
+                            .wrap(Idempotency)
+                            .wrap(limiters.create_budget),
+                    ),
             )
             .service(
                 resource("invitation")
-                    .route(post().to(budget::invite_user).wrap(limiters.budget_invite))
+                    .route(
+                        post()
+                            .to(budget::invite_user)
+                            .wrap(Idempotency)
+                            .wrap(limiters.budget_invite),
+                    )
                     .route(delete().to(budget::retract_invitation)),
             )
             .service(resource("/invitation/accept").route(put().to(budget::accept_invitation)))
@@ -28,14 +39,18 @@ pub fn configure(cfg: &mut ServiceConfig, limiters: RouteLimiters) {
             .service(resource("/leave").route(delete().to(budget::leave_budget)))
             .service(
                 resource("/entry")
-                    .route(post().to(budget::create_entry))
+                    .route(post().to(budget::create_entry).wrap(Idempotency))
                     .wrap(limiters.create_object.clone())
                     .route(put().to(budget::edit_entry))
                     .route(delete().to(budget::delete_entry)),
             )
             .service(
                 resource("/entry_and_category")
-                    .route(post().to(budget::create_entry_and_category))
+                    .route(
+                        post()
+                            .to(budget::create_entry_and_category)
+                            .wrap(Idempotency),
+                    )
                     .wrap(limiters.create_object.clone()),
             )
             .service(
@@ -6,6 +6,8 @@ pub fn configure(cfg: &mut ServiceConfig) {
     cfg.service(
         scope("")
             .route("/heartbeat", get().to(health::heartbeat))
-            .route("/health", get().to(health::health)),
+            .route("/health", get().to(health::health))
+            .route("/health/live", get().to(health::liveness))
+            .route("/health/ready", get().to(health::readiness)),
     );
 }
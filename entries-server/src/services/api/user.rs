@@ -22,8 +22,17 @@ pub fn configure(cfg: &mut ServiceConfig, limiters: RouteLimiters) {
                     .route(put().to(user::rotate_user_public_key)),
             )
             .service(resource("/verify").route(get().to(user::verify_creation)))
-            .service(resource("/preferences").route(put().to(user::edit_preferences)))
-            .service(resource("/keystore").route(put().to(user::edit_keystore)))
+            .service(resource("/bootstrap").route(get().to(user::get_bootstrap)))
+            .service(
+                resource("/preferences")
+                    .route(get().to(user::get_preferences))
+                    .route(put().to(user::edit_preferences)),
+            )
+            .service(
+                resource("/keystore")
+                    .route(get().to(user::get_keystore))
+                    .route(put().to(user::edit_keystore)),
+            )
             .service(
                 resource("/password").route(
                     put()
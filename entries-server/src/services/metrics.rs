@@ -0,0 +1,7 @@
+use actix_web::web::*;
+
+use crate::handlers::health;
+
+pub fn configure(cfg: &mut ServiceConfig) {
+    cfg.route("/metrics", get().to(health::metrics));
+}
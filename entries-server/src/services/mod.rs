@@ -1 +1,3 @@
 pub mod api;
+pub mod metrics;
+pub mod web;
@@ -0,0 +1,315 @@
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use actix_files::{file_extension_to_mime, NamedFile};
+use actix_web::http::header::{
+    self, AcceptEncoding, CacheControl, CacheDirective, ContentEncoding, Encoding, HeaderValue,
+    TryIntoHeaderValue,
+};
+use actix_web::web::*;
+use actix_web::{guard, HttpMessage, HttpRequest, HttpResponse};
+
+use crate::env::WebMountPath;
+
+/// Files are served from `root` with precompressed `.br` and `.gz` variants used when they
+/// exist next to the original and the client accepts them.
+#[derive(Clone, Debug)]
+pub struct WebFiles {
+    root: PathBuf,
+    cache_max_age: Duration,
+}
+
+impl WebFiles {
+    pub fn new(root: impl Into<PathBuf>, cache_max_age: Duration) -> Self {
+        Self {
+            root: root.into(),
+            cache_max_age,
+        }
+    }
+}
+
+/// Must be registered after the other services so the API routes take priority when the web
+/// directory is mounted at the root.
+pub fn configure(cfg: &mut ServiceConfig, mount_path: &WebMountPath, web_files: WebFiles) {
+    let get_or_head = || route().guard(guard::Any(guard::Get()).or(guard::Head()));
+
+    let mut scope = scope(mount_path.as_str()).app_data(Data::new(web_files));
+
+    // Without the trailing slash, relative links in index.html would resolve against the
+    // parent of the mount path
+    if !mount_path.is_root() {
+        scope = scope.route("", get_or_head().to(redirect_to_directory));
+    }
+
+    cfg.service(scope.route("/{tail:.*}", get_or_head().to(serve)));
+}
+
+async fn redirect_to_directory(req: HttpRequest) -> HttpResponse {
+    let location = match req.uri().query() {
+        Some(query) => format!("{}/?{query}", req.path()),
+        None => format!("{}/", req.path()),
+    };
+
+    HttpResponse::PermanentRedirect()
+        .insert_header((header::LOCATION, location))
+        .finish()
+}
+
+async fn serve(req: HttpRequest, web_files: Data<WebFiles>) -> HttpResponse {
+    let Some(relative_path) = sanitize_path(req.match_info().query("tail")) else {
+        return HttpResponse::NotFound().finish();
+    };
+
+    let mut path = web_files.root.join(relative_path);
+    if path.is_dir() {
+        path.push("index.html");
+    }
+
+    if !path.is_file() {
+        return HttpResponse::NotFound().finish();
+    }
+
+    let variants = [
+        (Encoding::brotli(), ContentEncoding::Brotli, "br"),
+        (Encoding::gzip(), ContentEncoding::Gzip, "gz"),
+    ]
+    .into_iter()
+    .filter_map(|(encoding, content_encoding, extension)| {
+        let variant_path = with_added_extension(&path, extension);
+        variant_path
+            .is_file()
+            .then_some((encoding, content_encoding, variant_path))
+    })
+    .collect::<Vec<_>>();
+
+    let chosen_variant = match req.get_header::<AcceptEncoding>() {
+        Some(accept_encoding) if !variants.is_empty() => {
+            let supported = variants
+                .iter()
+                .map(|(encoding, _, _)| encoding)
+                .chain(std::iter::once(&Encoding::identity()))
+                .cloned()
+                .collect::<Vec<_>>();
+
+            accept_encoding
+                .negotiate(supported.iter())
+                .and_then(|chosen| variants.iter().find(|(encoding, _, _)| *encoding == chosen))
+        }
+        _ => None,
+    };
+
+    let file = match chosen_variant {
+        Some((_, content_encoding, variant_path)) => {
+            NamedFile::open_async(variant_path).await.map(|f| {
+                let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+
+                f.set_content_type(file_extension_to_mime(extension))
+                    .set_content_encoding(*content_encoding)
+            })
+        }
+        None => NamedFile::open_async(&path).await,
+    };
+
+    let file = match file {
+        Ok(f) => f.disable_content_disposition(),
+        Err(e) => {
+            log::error!("Failed to open {}: {e}", path.display());
+            return HttpResponse::NotFound().finish();
+        }
+    };
+
+    let mut resp = file.into_response(&req);
+
+    // HTML is revalidated on every load (cheap with the ETag) so new deployments are picked up
+    // right away. Everything else can be cached for the configured time.
+    let cache_control = if path.extension().is_some_and(|ext| ext == "html") {
+        CacheControl(vec![CacheDirective::NoCache])
+    } else {
+        CacheControl(vec![
+            CacheDirective::Public,
+            CacheDirective::MaxAge(web_files.cache_max_age.as_secs() as u32),
+        ])
+    };
+
+    resp.headers_mut().insert(
+        header::CACHE_CONTROL,
+        cache_control
+            .try_into_value()
+            .expect("Cache-Control header should be valid"),
+    );
+
+    if !variants.is_empty() {
+        resp.headers_mut()
+            .insert(header::VARY, HeaderValue::from_static("accept-encoding"));
+    }
+
+    resp
+}
+
+/// Turns the request path into a path relative to the web root. Returns `None` for paths that
+/// could escape the root or that name hidden files.
+fn sanitize_path(tail: &str) -> Option<PathBuf> {
+    let mut path = PathBuf::new();
+
+    for segment in tail.split('/') {
+        if segment.is_empty() {
+            continue;
+        }
+
+        if segment.starts_with('.') || segment.contains('\\') || segment.contains('\0') {
+            return None;
+        }
+
+        path.push(segment);
+    }
+
+    Some(path)
+}
+
+fn with_added_extension(path: &Path, extension: &str) -> PathBuf {
+    let mut path = path.as_os_str().to_owned();
+    path.push(".");
+    path.push(extension);
+    path.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use actix_web::http::StatusCode;
+    use actix_web::test::{self, TestRequest};
+    use actix_web::App;
+    use std::fs;
+
+    fn test_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("entries-web-test-{}", rand::random::<u64>()));
+        fs::create_dir_all(dir.join("static")).unwrap();
+
+        fs::write(dir.join("index.html"), "<html></html>").unwrap();
+        fs::write(dir.join("static/styles.css"), "body {}").unwrap();
+        fs::write(dir.join("static/styles.css.br"), "brotli").unwrap();
+        fs::write(dir.join("static/styles.css.gz"), "gzip").unwrap();
+        fs::write(dir.join(".secret"), "secret").unwrap();
+
+        dir
+    }
+
+    #[test]
+    fn test_sanitize_path() {
+        assert_eq!(sanitize_path(""), Some(PathBuf::new()));
+        assert_eq!(
+            sanitize_path("static//images/a.png"),
+            Some(PathBuf::from("static/images/a.png"))
+        );
+        assert_eq!(sanitize_path("static/../../etc/passwd"), None);
+        assert_eq!(sanitize_path(".git/config"), None);
+        assert_eq!(sanitize_path("a\\..\\b"), None);
+    }
+
+    #[actix_web::test]
+    async fn test_serve() {
+        let dir = test_dir();
+        let mount_path = "/app".parse::<WebMountPath>().unwrap();
+        let web_files = WebFiles::new(&dir, Duration::from_secs(600));
+
+        let app = test::init_service(
+            App::new().configure(|cfg| configure(cfg, &mount_path, web_files.clone())),
+        )
+        .await;
+
+        let req = TestRequest::get().uri("/app?a=b").to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::PERMANENT_REDIRECT);
+        assert_eq!(resp.headers().get(header::LOCATION).unwrap(), "/app/?a=b");
+
+        let req = TestRequest::get().uri("/app/").to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(
+            resp.headers().get(header::CACHE_CONTROL).unwrap(),
+            "no-cache"
+        );
+        assert!(resp.headers().get(header::VARY).is_none());
+        let etag = resp.headers().get(header::ETAG).unwrap().clone();
+        assert_eq!(test::read_body(resp).await, "<html></html>");
+
+        let req = TestRequest::get()
+            .uri("/app/")
+            .insert_header((header::IF_NONE_MATCH, etag))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::NOT_MODIFIED);
+
+        let req = TestRequest::get()
+            .uri("/app/static/styles.css")
+            .insert_header((header::ACCEPT_ENCODING, "gzip, br"))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.headers().get(header::CONTENT_ENCODING).unwrap(), "br");
+        assert_eq!(
+            resp.headers().get(header::CONTENT_TYPE).unwrap(),
+            "text/css; charset=utf-8"
+        );
+        assert_eq!(resp.headers().get(header::VARY).unwrap(), "accept-encoding");
+        assert_eq!(
+            resp.headers().get(header::CACHE_CONTROL).unwrap(),
+            "public, max-age=600"
+        );
+        assert_eq!(test::read_body(resp).await, "brotli");
+
+        let req = TestRequest::get()
+            .uri("/app/static/styles.css")
+            .insert_header((header::ACCEPT_ENCODING, "gzip"))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(
+            resp.headers().get(header::CONTENT_ENCODING).unwrap(),
+            "gzip"
+        );
+        assert_eq!(test::read_body(resp).await, "gzip");
+
+        let req = TestRequest::get()
+            .uri("/app/static/styles.css")
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert!(resp.headers().get(header::CONTENT_ENCODING).is_none());
+        assert_eq!(resp.headers().get(header::VARY).unwrap(), "accept-encoding");
+        assert_eq!(test::read_body(resp).await, "body {}");
+
+        for uri in [
+            "/app/.secret",
+            "/app/missing.js",
+            "/app/static/%2e%2e/.secret",
+        ] {
+            let req = TestRequest::get().uri(uri).to_request();
+            let resp = test::call_service(&app, req).await;
+            assert_eq!(resp.status(), StatusCode::NOT_FOUND, "{uri}");
+        }
+
+        let req = TestRequest::post().uri("/app/").to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_parse_mount_path() {
+        assert!("/".parse::<WebMountPath>().unwrap().is_root());
+        assert_eq!("/app/".parse::<WebMountPath>().unwrap().as_str(), "/app");
+        assert_eq!(
+            "/web/app".parse::<WebMountPath>().unwrap().to_string(),
+            "/web/app"
+        );
+
+        assert!("app".parse::<WebMountPath>().is_err());
+        assert!("/api".parse::<WebMountPath>().is_err());
+        assert!("/api/web".parse::<WebMountPath>().is_err());
+        assert!("/metrics".parse::<WebMountPath>().is_err());
+        assert!("/a/../b".parse::<WebMountPath>().is_err());
+        assert!("/{tail}".parse::<WebMountPath>().is_err());
+        assert!("/apiary".parse::<WebMountPath>().is_ok());
+    }
+}
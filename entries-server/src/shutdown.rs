@@ -0,0 +1,93 @@
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+static IS_SHUTTING_DOWN: AtomicBool = AtomicBool::new(false);
+static IN_FLIGHT_TASKS: TaskCounter = TaskCounter::new();
+
+const TASK_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Marks the server as shutting down so the readiness endpoint starts reporting that the
+/// server can't take new traffic.
+pub fn begin() {
+    IS_SHUTTING_DOWN.store(true, Ordering::SeqCst);
+}
+
+pub fn is_shutting_down() -> bool {
+    IS_SHUTTING_DOWN.load(Ordering::SeqCst)
+}
+
+/// Counts tasks that are in flight.
+struct TaskCounter {
+    in_flight: AtomicUsize,
+}
+
+impl TaskCounter {
+    const fn new() -> Self {
+        TaskCounter {
+            in_flight: AtomicUsize::new(0),
+        }
+    }
+
+    fn track(&'static self) -> TaskGuard {
+        self.in_flight.fetch_add(1, Ordering::SeqCst);
+        TaskGuard(self)
+    }
+
+    async fn wait(&self, timeout: Duration) -> usize {
+        let deadline = Instant::now() + timeout;
+
+        loop {
+            let remaining = self.in_flight.load(Ordering::SeqCst);
+
+            if remaining == 0 || Instant::now() >= deadline {
+                return remaining;
+            }
+
+            tokio::time::sleep(TASK_POLL_INTERVAL).await;
+        }
+    }
+}
+
+/// Marks a task as in flight until the guard is dropped.
+pub struct TaskGuard(&'static TaskCounter);
+
+impl Drop for TaskGuard {
+    fn drop(&mut self) {
+        self.0.in_flight.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Tracks work that runs outside of the request futures (on the blocking thread pool or in
+/// rayon) so shutdown can wait for it.
+pub fn track_task() -> TaskGuard {
+    IN_FLIGHT_TASKS.track()
+}
+
+/// Waits for tracked tasks to finish. Returns the number of tasks that were still running when
+/// the timeout elapsed.
+pub async fn wait_for_tasks(timeout: Duration) -> usize {
+    IN_FLIGHT_TASKS.wait(timeout).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[actix_web::test]
+    async fn test_wait_for_tasks() {
+        // A counter of its own keeps tasks tracked by other tests from affecting this one
+        static TASKS: TaskCounter = TaskCounter::new();
+
+        let guard = TASKS.track();
+        let wait = actix_web::rt::spawn(TASKS.wait(Duration::from_secs(10)));
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(!wait.is_finished());
+
+        drop(guard);
+        assert_eq!(wait.await.unwrap(), 0);
+
+        let _guard = TASKS.track();
+        assert_eq!(TASKS.wait(Duration::from_millis(100)).await, 1);
+    }
+}
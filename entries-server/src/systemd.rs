@@ -0,0 +1,38 @@
+use listenfd::ListenFd;
+use std::io;
+use std::net::TcpListener;
+#[cfg(unix)]
+use std::os::unix::net::UnixListener;
+
+pub enum ActivatedSocket {
+    Tcp(TcpListener),
+    #[cfg(unix)]
+    Unix(UnixListener),
+}
+
+/// Takes the sockets passed to the process through systemd socket activation (the
+/// `LISTEN_FDS` protocol). Returns an empty list if the process wasn't socket-activated.
+pub fn activated_sockets() -> io::Result<Vec<ActivatedSocket>> {
+    let mut listen_fd = ListenFd::from_env();
+    let mut sockets = Vec::with_capacity(listen_fd.len());
+
+    for i in 0..listen_fd.len() {
+        if let Ok(Some(listener)) = listen_fd.take_tcp_listener(i) {
+            sockets.push(ActivatedSocket::Tcp(listener));
+            continue;
+        }
+
+        #[cfg(unix)]
+        if let Some(listener) = listen_fd.take_unix_listener(i)? {
+            sockets.push(ActivatedSocket::Unix(listener));
+            continue;
+        }
+
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("Socket {i} passed by systemd is not a stream socket"),
+        ));
+    }
+
+    Ok(sockets)
+}
@@ -0,0 +1,206 @@
+use opentelemetry::trace::{Status, TracerProvider};
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::error::{OTelSdkError, OTelSdkResult};
+use opentelemetry_sdk::trace::{SdkTracerProvider, SpanData, SpanExporter};
+use opentelemetry_sdk::Resource;
+use serde_json::{json, Map, Value};
+use std::fmt;
+use std::fs::OpenOptions;
+use std::future::{ready, Future};
+use std::io::{self, Write};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing_subscriber::layer::SubscriberExt;
+
+use crate::env::{self, TraceExporter};
+
+const SERVICE_NAME: &str = "entries-server";
+
+/// Installs a global `tracing` subscriber that exports spans using the exporter selected by
+/// `ENTRIES_TRACE_EXPORTER`. Returns `None` if tracing is disabled, in which case spans are
+/// never recorded.
+///
+/// The returned provider should be shut down before the process exits so buffered spans are
+/// flushed.
+///
+/// Spans throughout the server are created at the debug level. Without a subscriber,
+/// `tracing` reports spans as `log` records instead (actix enables its `log` feature), and
+/// the debug level keeps them out of the log at the default log level. The subscriber
+/// installed here has no level filter, so debug spans are still exported.
+pub fn init() -> Result<Option<SdkTracerProvider>, Box<dyn std::error::Error>> {
+    let builder = SdkTracerProvider::builder()
+        .with_resource(Resource::builder().with_service_name(SERVICE_NAME).build());
+
+    let provider = match env::CONF.trace_exporter {
+        TraceExporter::None => return Ok(None),
+        TraceExporter::Otlp => {
+            let exporter = opentelemetry_otlp::SpanExporter::builder()
+                .with_http()
+                .with_endpoint(&env::CONF.otlp_endpoint)
+                .build()?;
+
+            builder.with_batch_exporter(exporter).build()
+        }
+        TraceExporter::Stdout => builder
+            .with_batch_exporter(JsonLinesExporter::new(io::stdout()))
+            .build(),
+        TraceExporter::File => {
+            let file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&env::CONF.trace_file_path)?;
+
+            builder
+                .with_batch_exporter(JsonLinesExporter::new(file))
+                .build()
+        }
+    };
+
+    let subscriber = tracing_subscriber::registry()
+        .with(tracing_opentelemetry::layer().with_tracer(provider.tracer(SERVICE_NAME)));
+    tracing::subscriber::set_global_default(subscriber)?;
+
+    Ok(Some(provider))
+}
+
+/// Writes each span as a single line of JSON. Intended for local development, where running
+/// an OTLP collector is more trouble than it's worth.
+pub struct JsonLinesExporter {
+    writer: Mutex<Box<dyn Write + Send>>,
+}
+
+impl JsonLinesExporter {
+    pub fn new(writer: impl Write + Send + 'static) -> Self {
+        Self {
+            writer: Mutex::new(Box::new(writer)),
+        }
+    }
+}
+
+impl fmt::Debug for JsonLinesExporter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JsonLinesExporter").finish_non_exhaustive()
+    }
+}
+
+impl SpanExporter for JsonLinesExporter {
+    fn export(&self, batch: Vec<SpanData>) -> impl Future<Output = OTelSdkResult> + Send {
+        let mut writer = match self.writer.lock() {
+            Ok(w) => w,
+            Err(_) => {
+                return ready(Err(OTelSdkError::InternalFailure(String::from(
+                    "Span writer mutex was poisoned",
+                ))))
+            }
+        };
+
+        let result = batch
+            .iter()
+            .try_for_each(|span| writeln!(writer, "{}", span_to_json(span)))
+            .and_then(|_| writer.flush())
+            .map_err(|e| OTelSdkError::InternalFailure(e.to_string()));
+
+        ready(result)
+    }
+}
+
+fn span_to_json(span: &SpanData) -> Value {
+    let attributes: Map<String, Value> = span
+        .attributes
+        .iter()
+        .map(|kv| (kv.key.to_string(), Value::String(kv.value.to_string())))
+        .collect();
+
+    let status = match &span.status {
+        Status::Unset => None,
+        Status::Ok => Some(String::from("ok")),
+        Status::Error { description } => Some(format!("error: {description}")),
+    };
+
+    json!({
+        "name": span.name,
+        "trace_id": span.span_context.trace_id().to_string(),
+        "span_id": span.span_context.span_id().to_string(),
+        "parent_span_id": span.parent_span_id.to_string(),
+        "start_unix_nanos": unix_nanos(span.start_time),
+        "duration_micros": span
+            .end_time
+            .duration_since(span.start_time)
+            .unwrap_or_default()
+            .as_micros() as u64,
+        "attributes": attributes,
+        "status": status,
+    })
+}
+
+fn unix_nanos(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::Arc;
+
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_json_lines_exporter() {
+        let buffer = SharedBuffer::default();
+        let provider = SdkTracerProvider::builder()
+            .with_simple_exporter(JsonLinesExporter::new(buffer.clone()))
+            .build();
+
+        let subscriber = tracing_subscriber::registry()
+            .with(tracing_opentelemetry::layer().with_tracer(provider.tracer("test")));
+
+        tracing::subscriber::with_default(subscriber, || {
+            let parent = tracing::info_span!("parent", request_id = "abc");
+            let _entered = parent.enter();
+
+            // Spans should follow the closure onto other threads (the server installs its
+            // subscriber globally, so only the test needs to pass the dispatcher along)
+            let child_parent = tracing::Span::current();
+            let dispatch = tracing::dispatcher::get_default(|d| d.clone());
+            std::thread::spawn(move || {
+                tracing::dispatcher::with_default(&dispatch, || {
+                    child_parent.in_scope(|| {
+                        let _child = tracing::info_span!("child").entered();
+                    });
+                });
+            })
+            .join()
+            .unwrap();
+        });
+
+        provider.shutdown().unwrap();
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let spans: Vec<Value> = output
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(spans.len(), 2);
+
+        let child = spans.iter().find(|s| s["name"] == "child").unwrap();
+        let parent = spans.iter().find(|s| s["name"] == "parent").unwrap();
+
+        assert_eq!(child["trace_id"], parent["trace_id"]);
+        assert_eq!(child["parent_span_id"], parent["span_id"]);
+        assert_eq!(parent["attributes"]["request_id"], "abc");
+    }
+}
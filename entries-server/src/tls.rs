@@ -0,0 +1,265 @@
+use rustls::crypto::ring;
+use rustls::server::{ClientHello, ResolvesServerCert};
+use rustls::sign::CertifiedKey;
+use rustls::ServerConfig;
+use std::fmt;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime};
+
+/// Serves the certificate most recently loaded from disk. The files are checked periodically
+/// by the thread started with `spawn_reloader()` so renewed certificates are picked up without
+/// a restart.
+pub struct CertResolver {
+    cert_path: PathBuf,
+    key_path: PathBuf,
+    current: RwLock<LoadedCert>,
+}
+
+struct LoadedCert {
+    certified_key: Arc<CertifiedKey>,
+    modified: Option<SystemTime>,
+}
+
+impl CertResolver {
+    pub fn load(
+        cert_path: impl Into<PathBuf>,
+        key_path: impl Into<PathBuf>,
+    ) -> Result<Self, TlsError> {
+        let cert_path = cert_path.into();
+        let key_path = key_path.into();
+
+        let modified = last_modified(&cert_path, &key_path);
+        let certified_key = load_certified_key(&cert_path, &key_path)?;
+
+        Ok(Self {
+            cert_path,
+            key_path,
+            current: RwLock::new(LoadedCert {
+                certified_key: Arc::new(certified_key),
+                modified,
+            }),
+        })
+    }
+
+    /// Reloads the certificate and key if either file has changed since they were last loaded.
+    /// Returns whether a new certificate was loaded. If the new files can't be loaded, the
+    /// previous certificate stays in use.
+    pub fn reload_if_changed(&self) -> Result<bool, TlsError> {
+        let modified = last_modified(&self.cert_path, &self.key_path);
+
+        if modified
+            == self
+                .current
+                .read()
+                .expect("Lock should not be poisoned")
+                .modified
+        {
+            return Ok(false);
+        }
+
+        let certified_key = load_certified_key(&self.cert_path, &self.key_path)?;
+
+        *self.current.write().expect("Lock should not be poisoned") = LoadedCert {
+            certified_key: Arc::new(certified_key),
+            modified,
+        };
+
+        Ok(true)
+    }
+}
+
+impl ResolvesServerCert for CertResolver {
+    fn resolve(&self, _client_hello: ClientHello<'_>) -> Option<Arc<CertifiedKey>> {
+        let current = self.current.read().expect("Lock should not be poisoned");
+        Some(Arc::clone(&current.certified_key))
+    }
+}
+
+impl fmt::Debug for CertResolver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CertResolver")
+            .field("cert_path", &self.cert_path)
+            .field("key_path", &self.key_path)
+            .finish_non_exhaustive()
+    }
+}
+
+pub fn server_config(resolver: Arc<CertResolver>) -> Result<ServerConfig, TlsError> {
+    Ok(
+        ServerConfig::builder_with_provider(Arc::new(ring::default_provider()))
+            .with_safe_default_protocol_versions()
+            .map_err(TlsError::InvalidConfig)?
+            .with_no_client_auth()
+            .with_cert_resolver(resolver),
+    )
+}
+
+/// Starts a thread that checks the certificate and key files for changes every `interval`.
+pub fn spawn_reloader(resolver: Arc<CertResolver>, interval: Duration) {
+    std::thread::Builder::new()
+        .name(String::from("tls-cert-reloader"))
+        .spawn(move || loop {
+            std::thread::sleep(interval);
+
+            match resolver.reload_if_changed() {
+                Ok(true) => log::info!("Reloaded TLS certificate"),
+                Ok(false) => (),
+                Err(e) => log::error!("Failed to reload TLS certificate: {e}"),
+            }
+        })
+        .expect("Failed to start TLS certificate reloader thread");
+}
+
+fn last_modified(cert_path: &Path, key_path: &Path) -> Option<SystemTime> {
+    let cert_modified = std::fs::metadata(cert_path).and_then(|m| m.modified()).ok();
+    let key_modified = std::fs::metadata(key_path).and_then(|m| m.modified()).ok();
+
+    cert_modified.max(key_modified)
+}
+
+fn load_certified_key(cert_path: &Path, key_path: &Path) -> Result<CertifiedKey, TlsError> {
+    let cert_file = File::open(cert_path).map_err(|e| TlsError::Io(cert_path.to_owned(), e))?;
+    let certs = rustls_pemfile::certs(&mut BufReader::new(cert_file))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| TlsError::Io(cert_path.to_owned(), e))?;
+
+    if certs.is_empty() {
+        return Err(TlsError::NoCertificates(cert_path.to_owned()));
+    }
+
+    let key_file = File::open(key_path).map_err(|e| TlsError::Io(key_path.to_owned(), e))?;
+    let key = rustls_pemfile::private_key(&mut BufReader::new(key_file))
+        .map_err(|e| TlsError::Io(key_path.to_owned(), e))?
+        .ok_or_else(|| TlsError::NoPrivateKey(key_path.to_owned()))?;
+
+    let signing_key = ring::sign::any_supported_type(&key).map_err(TlsError::InvalidKey)?;
+    let certified_key = CertifiedKey::new(certs, signing_key);
+    certified_key.keys_match().map_err(TlsError::InvalidKey)?;
+
+    Ok(certified_key)
+}
+
+#[derive(Debug)]
+pub enum TlsError {
+    Io(PathBuf, std::io::Error),
+    NoCertificates(PathBuf),
+    NoPrivateKey(PathBuf),
+    InvalidKey(rustls::Error),
+    InvalidConfig(rustls::Error),
+}
+
+impl std::error::Error for TlsError {}
+
+impl fmt::Display for TlsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TlsError::Io(path, e) => {
+                write!(f, "TlsError: Failed to read '{}': {e}", path.display())
+            }
+            TlsError::NoCertificates(path) => {
+                write!(f, "TlsError: No certificates found in '{}'", path.display())
+            }
+            TlsError::NoPrivateKey(path) => {
+                write!(f, "TlsError: No private key found in '{}'", path.display())
+            }
+            TlsError::InvalidKey(e) => write!(f, "TlsError: Invalid private key: {e}"),
+            TlsError::InvalidConfig(e) => write!(f, "TlsError: Invalid TLS config: {e}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::fs;
+
+    fn write_self_signed_cert(cert_path: &Path, key_path: &Path) {
+        let cert = rcgen::generate_simple_self_signed(vec![String::from("localhost")]).unwrap();
+        fs::write(cert_path, cert.cert.pem()).unwrap();
+        fs::write(key_path, cert.signing_key.serialize_pem()).unwrap();
+    }
+
+    fn test_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("entries-tls-test-{name}-{}", rand::random::<u64>()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_reload_if_changed() {
+        let dir = test_dir("reload");
+        let cert_path = dir.join("cert.pem");
+        let key_path = dir.join("key.pem");
+
+        write_self_signed_cert(&cert_path, &key_path);
+        let resolver = CertResolver::load(&cert_path, &key_path).unwrap();
+        let first_cert = resolver.current.read().unwrap().certified_key.cert[0].clone();
+
+        assert!(!resolver.reload_if_changed().unwrap());
+
+        write_self_signed_cert(&cert_path, &key_path);
+
+        // Make sure the modification time changes even on filesystems with coarse timestamps
+        let later = SystemTime::now() + Duration::from_secs(10);
+        File::options()
+            .write(true)
+            .open(&cert_path)
+            .unwrap()
+            .set_modified(later)
+            .unwrap();
+
+        assert!(resolver.reload_if_changed().unwrap());
+        assert!(!resolver.reload_if_changed().unwrap());
+
+        let second_cert = resolver.current.read().unwrap().certified_key.cert[0].clone();
+        assert_ne!(first_cert, second_cert);
+
+        // A broken certificate should leave the previous one in place
+        fs::write(&cert_path, "not a certificate").unwrap();
+        File::options()
+            .write(true)
+            .open(&cert_path)
+            .unwrap()
+            .set_modified(later + Duration::from_secs(10))
+            .unwrap();
+
+        assert!(matches!(
+            resolver.reload_if_changed(),
+            Err(TlsError::NoCertificates(_))
+        ));
+        assert_eq!(
+            resolver.current.read().unwrap().certified_key.cert[0],
+            second_cert
+        );
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_load_mismatched_key() {
+        let dir = test_dir("mismatch");
+        let cert_path = dir.join("cert.pem");
+        let key_path = dir.join("key.pem");
+        let other_key_path = dir.join("other_key.pem");
+
+        write_self_signed_cert(&cert_path, &key_path);
+        write_self_signed_cert(&dir.join("other_cert.pem"), &other_key_path);
+
+        assert!(CertResolver::load(&cert_path, &key_path).is_ok());
+        assert!(matches!(
+            CertResolver::load(&cert_path, &other_key_path),
+            Err(TlsError::InvalidKey(_))
+        ));
+        assert!(matches!(
+            CertResolver::load(dir.join("missing.pem"), &key_path),
+            Err(TlsError::Io(_, _))
+        ));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}